@@ -0,0 +1,21 @@
+use object::read::archive::ArchiveFile;
+use object::FileKind;
+
+/// Whether `data` is a static archive, like `libfoo.a`.
+pub fn is_archive(data: &[u8]) -> bool {
+    matches!(FileKind::parse(data), Ok(FileKind::Archive))
+}
+
+/// Members of a static archive by name, in the order they're stored. The archive's symbol
+/// tables aren't counted as members.
+pub fn members(data: &[u8]) -> Result<Vec<(String, &[u8])>, object::Error> {
+    let archive = ArchiveFile::parse(data)?;
+    let mut members = Vec::new();
+    for member in archive.members() {
+        let member = member?;
+        let name = String::from_utf8_lossy(member.name()).into_owned();
+        members.push((name, member.data(data)?));
+    }
+
+    Ok(members)
+}
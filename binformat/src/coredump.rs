@@ -0,0 +1,246 @@
+//! State of a process at the time it dumped core, recovered from the notes and segments of an
+//! ELF core file.
+
+use crate::notes::Layout;
+use object::elf;
+use object::read::elf::{ElfFile, FileHeader, ProgramHeader};
+use processor_shared::PhysAddr;
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+pub struct CoreDump {
+    /// Threads of the process, the one that caused the dump coming first.
+    pub threads: Vec<Thread>,
+    /// Files mapped into the process, from its `NT_FILE` note.
+    pub files: Vec<MappedFile>,
+    /// Memory of the process that was written to the core file.
+    memory: Vec<Memory>,
+    /// Size and byte order of the process's words.
+    layout: Layout,
+}
+
+/// Thread of a dumped process, from its `NT_PRSTATUS` note.
+#[derive(Debug, Clone)]
+pub struct Thread {
+    pub pid: u32,
+    /// Signal the thread was stopped by.
+    pub signal: u16,
+    /// General-purpose registers by name, empty if the machine's register layout isn't known.
+    pub registers: Vec<(&'static str, u64)>,
+}
+
+impl Thread {
+    pub fn register(&self, name: &str) -> Option<u64> {
+        self.registers.iter().find(|(reg, _)| *reg == name).map(|&(_, value)| value)
+    }
+
+    /// Address of the instruction the thread was stopped at.
+    pub fn pc(&self) -> Option<u64> {
+        ["rip", "pc", "eip"].into_iter().find_map(|name| self.register(name))
+    }
+
+    pub fn sp(&self) -> Option<u64> {
+        ["rsp", "sp", "esp"].into_iter().find_map(|name| self.register(name))
+    }
+
+    /// Register that points to the saved frame pointer and return address of the current frame,
+    /// in code built with frame pointers.
+    pub fn fp(&self) -> Option<u64> {
+        ["rbp", "x29", "ebp"].into_iter().find_map(|name| self.register(name))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MappedFile {
+    pub start: PhysAddr,
+    pub end: PhysAddr,
+    /// Offset in the file the mapping starts at.
+    pub offset: u64,
+    pub path: String,
+}
+
+/// Loadable segment of a core file.
+#[derive(Debug, Clone)]
+struct Memory {
+    start: PhysAddr,
+    end: PhysAddr,
+    /// Contents of the segment that were dumped, which may stop short of its end.
+    bytes: &'static [u8],
+}
+
+impl CoreDump {
+    /// Fill `buf` with the memory at `addr`. Memory that isn't in the core file, like code that
+    /// was mapped from an unmodified file, can't be read and the first address of it is
+    /// returned instead.
+    pub fn read_memory(&self, addr: PhysAddr, buf: &mut [u8]) -> Result<(), PhysAddr> {
+        let mut done = 0;
+        while done < buf.len() {
+            let addr = addr + done;
+            let memory = self
+                .memory
+                .iter()
+                .find(|memory| (memory.start..memory.end).contains(&addr))
+                .ok_or(addr)?;
+
+            let bytes = memory.bytes.get(addr - memory.start..).unwrap_or(&[]);
+            if bytes.is_empty() {
+                return Err(addr);
+            }
+
+            let len = bytes.len().min(buf.len() - done);
+            buf[done..done + len].copy_from_slice(&bytes[..len]);
+            done += len;
+        }
+
+        Ok(())
+    }
+
+    /// Word of the process's pointer size at `addr`.
+    pub fn read_word(&self, addr: PhysAddr) -> Result<u64, PhysAddr> {
+        let mut bytes = [0; 8];
+        let bytes = &mut bytes[..self.layout.word_size()];
+        self.read_memory(addr, bytes)?;
+        Ok(self.layout.word(bytes, 0).unwrap_or(0))
+    }
+
+    /// Size of the process's pointers in bytes.
+    pub fn word_size(&self) -> usize {
+        self.layout.word_size()
+    }
+
+    /// Addresses of the mapping `addr` is in, like the bounds of a thread's stack.
+    pub fn region(&self, addr: PhysAddr) -> Option<Range<PhysAddr>> {
+        self.memory
+            .iter()
+            .map(|memory| memory.start..memory.end)
+            .find(|range| range.contains(&addr))
+    }
+}
+
+pub fn parse<'data, Elf: FileHeader>(obj: &ElfFile<'data, Elf>) -> CoreDump {
+    let endian = obj.endian();
+    let data = obj.data();
+    let layout = Layout::of(obj);
+
+    let mut dump = CoreDump {
+        threads: Vec::new(),
+        files: Vec::new(),
+        memory: Vec::new(),
+        layout,
+    };
+    for segment in obj.elf_program_headers() {
+        if segment.p_type(endian) == elf::PT_LOAD {
+            let start: u64 = segment.p_vaddr(endian).into();
+            let size: u64 = segment.p_memsz(endian).into();
+
+            // The file is memory mapped so only the bytes are of lifetime &'static [u8].
+            let bytes: &'static [u8] = match segment.data(endian, data) {
+                Ok(bytes) => unsafe { std::mem::transmute(bytes) },
+                Err(..) => &[],
+            };
+
+            dump.memory.push(Memory {
+                start: start as PhysAddr,
+                end: (start + size) as PhysAddr,
+                bytes,
+            });
+        }
+
+        let mut iter = match segment.notes(endian, data) {
+            Ok(Some(iter)) => iter,
+            _ => continue,
+        };
+
+        while let Ok(Some(note)) = iter.next() {
+            if note.name() != b"CORE" {
+                continue;
+            }
+
+            match note.n_type(endian) {
+                elf::NT_PRSTATUS => dump.threads.extend(thread(layout, note.desc())),
+                elf::NT_FILE => dump.files.extend(mapped_files(layout, note.desc())),
+                _ => {}
+            }
+        }
+    }
+
+    dump
+}
+
+/// Names of the registers of `struct user_regs_struct`, as they're stored in `NT_PRSTATUS`.
+fn register_names(machine: u16) -> &'static [&'static str] {
+    match machine {
+        elf::EM_X86_64 => &[
+            "r15", "r14", "r13", "r12", "rbp", "rbx", "r11", "r10", "r9", "r8", "rax", "rcx",
+            "rdx", "rsi", "rdi", "orig_rax", "rip", "cs", "eflags", "rsp", "ss", "fs_base",
+            "gs_base", "ds", "es", "fs", "gs",
+        ],
+        elf::EM_386 => &[
+            "ebx", "ecx", "edx", "esi", "edi", "ebp", "eax", "ds", "es", "fs", "gs", "orig_eax",
+            "eip", "cs", "eflags", "esp", "ss",
+        ],
+        elf::EM_AARCH64 => &[
+            "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12",
+            "x13", "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24",
+            "x25", "x26", "x27", "x28", "x29", "x30", "sp", "pc", "pstate",
+        ],
+        _ => &[],
+    }
+}
+
+/// Thread of a `struct elf_prstatus`.
+fn thread(layout: Layout, desc: &[u8]) -> Option<Thread> {
+    let word = layout.word_size();
+
+    // siginfo and the signal come first, followed by the sets of pending and held signals
+    let signal = layout.u16(desc, 12)?;
+    let pid = layout.u32(desc, 16 + word * 2)?;
+
+    // the pid, parent pid, group and session are followed by 4 timevals of 2 words each
+    let regs = 16 + word * 2 + 4 * 4 + word * 8;
+    let registers = register_names(layout.machine)
+        .iter()
+        .enumerate()
+        .map_while(|(idx, &name)| Some((name, layout.word(desc, regs + idx * word)?)))
+        .collect();
+
+    Some(Thread { pid, signal, registers })
+}
+
+/// Mappings of a `NT_FILE` note, a count and the page size followed by the start, end and
+/// offset in pages of each mapping, and then their paths.
+fn mapped_files(layout: Layout, desc: &[u8]) -> Vec<MappedFile> {
+    let word = layout.word_size();
+    let (count, page_size) = match (layout.word(desc, 0), layout.word(desc, word)) {
+        (Some(count), Some(page_size)) => (count as usize, page_size),
+        _ => return Vec::new(),
+    };
+
+    let entries = count.checked_mul(word * 3).and_then(|len| len.checked_add(word * 2));
+    let paths = match entries.and_then(|entries| desc.get(entries..)) {
+        Some(paths) => paths.split(|&byte| byte == 0),
+        None => return Vec::new(),
+    };
+
+    let mut files = Vec::new();
+    for (idx, path) in (0..count).zip(paths) {
+        let entry = word * 2 + idx * word * 3;
+        let (start, end, offset) = match (
+            layout.word(desc, entry),
+            layout.word(desc, entry + word),
+            layout.word(desc, entry + word * 2),
+        ) {
+            (Some(start), Some(end), Some(offset)) => (start, end, offset),
+            _ => break,
+        };
+
+        files.push(MappedFile {
+            start: start as PhysAddr,
+            end: end as PhysAddr,
+            offset: offset * page_size,
+            path: String::from_utf8_lossy(path).into_owned(),
+        });
+    }
+
+    files
+}
@@ -1,11 +1,13 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use crate::{datastructure, RawSymbol};
+use crate::{datastructure, Binding, Dependencies, Import, RawSymbol, SegmentHeader};
+use crate::{RelocationEntry, RelocationTable, SymbolTable, TableSymbol};
 use processor_shared::{AddressMap, Addressed, Section, SectionKind};
 use object::elf;
-use object::read::elf::{ElfFile, FileHeader, SectionHeader};
+use object::read::elf::{Dyn, ElfFile, FileHeader, ProgramHeader, Rel, Rela, SectionHeader, Sym};
 use object::{
-    Endian, Object, ObjectSection, ObjectSymbol, ObjectSymbolTable, RelocationKind,
-    RelocationTarget,
+    Architecture, Endian, Object, ObjectSection, ObjectSymbol, ObjectSymbolTable, RelocationKind,
+    RelocationTarget, SectionIndex, SymbolKind,
 };
 
 pub struct ElfDebugInfo<'data, Elf: FileHeader> {
@@ -15,6 +17,8 @@ pub struct ElfDebugInfo<'data, Elf: FileHeader> {
     pub sections: Vec<Section>,
     /// Any parsed but not yet relocated symbols.
     pub syms: AddressMap<RawSymbol<'data>>,
+    /// Symbols of `syms` that aren't in any symbol table, like PLT entries and thunks.
+    pub synthesized: Vec<TableSymbol>,
 }
 
 impl<'data, Elf: FileHeader> ElfDebugInfo<'data, Elf> {
@@ -23,10 +27,12 @@ impl<'data, Elf: FileHeader> ElfDebugInfo<'data, Elf> {
             obj,
             syms: AddressMap::default(),
             sections: Vec::new(),
+            synthesized: Vec::new(),
         };
         this.sections = parse_sections(obj);
         this.parse_symbols();
         this.parse_imports();
+        this.parse_thunks();
         Ok(this)
     }
 
@@ -41,16 +47,17 @@ impl<'data, Elf: FileHeader> ElfDebugInfo<'data, Elf> {
             None => return,
         };
 
+        // symbols the slots of the GOT are resolved to, which PLT entries jump through
+        let mut slots = HashMap::new();
         for (r_offset, reloc) in relocations {
             if let RelocationTarget::Symbol(idx) = reloc.target() {
-                let opt_section = self.obj.sections().find(|section| {
+                let in_section = self.obj.sections().any(|section| {
                     (section.address()..section.address() + section.size()).contains(&r_offset)
                 });
 
-                let section = match opt_section {
-                    Some(section) => section,
-                    None => continue,
-                };
+                if !in_section {
+                    continue;
+                }
 
                 if let Ok(sym) = dyn_syms.symbol_by_index(idx) {
                     let name = match sym.name() {
@@ -58,52 +65,488 @@ impl<'data, Elf: FileHeader> ElfDebugInfo<'data, Elf> {
                         Err(..) => continue,
                     };
 
+                    slots.insert(r_offset as usize, name);
                     let addr = match reloc.kind() {
                         // hard-coded address to function which doesn't require a relocation
                         RelocationKind::Absolute => r_offset as usize,
                         RelocationKind::Elf(elf::R_X86_64_GLOB_DAT) => r_offset as usize,
                         RelocationKind::Elf(elf::R_X86_64_COPY) => r_offset as usize,
-                        // address in .got.plt section which contains an address to the function
-                        RelocationKind::Elf(elf::R_X86_64_JUMP_SLOT) => {
-                            let width = if self.obj.is_64() { 8 } else { 4 };
-
-                            let bytes = match section.data_range(r_offset, width) {
-                                Ok(Some(bytes)) => bytes,
-                                _ => continue,
-                            };
-
-                            let phys_addr = if self.obj.is_64() {
-                                self.obj.endian().read_u64_bytes(bytes.try_into().unwrap()) as usize
-                            } else {
-                                self.obj.endian().read_u32_bytes(bytes.try_into().unwrap()) as usize
-                            };
-
-                            // idk why we need this
-                            phys_addr.saturating_sub(6)
-                        }
                         _ => continue,
                     };
 
                     // TODO: find modules
                     self.syms.push(Addressed {
                         addr,
-                        item: RawSymbol { name, module: None },
+                        item: RawSymbol {
+                            name,
+                            module: None,
+                            size: 0,
+                            plt: false,
+                            kind: SymbolKind::Unknown,
+                            global: false,
+                        },
                     });
                 }
             }
         }
+
+        self.parse_plt(&slots);
+    }
+
+    /// Addresses that already have a symbol, which aren't given a synthesized one.
+    fn known_addrs(&self) -> HashSet<usize> {
+        self.syms.iter().map(|sym| sym.addr).collect()
+    }
+
+    /// Add a symbol that isn't in any symbol table.
+    fn synthesize(&mut self, addr: usize, size: usize, name: &'data str, section: &str, plt: bool) {
+        self.syms.push(Addressed {
+            addr,
+            item: RawSymbol {
+                name,
+                module: None,
+                size,
+                plt,
+                kind: SymbolKind::Text,
+                global: false,
+            },
+        });
+
+        self.synthesized.push(TableSymbol {
+            name: if plt { format!("{name}@plt") } else { name.to_string() },
+            addr,
+            size: size as u64,
+            kind: SymbolKind::Text,
+            binding: Binding::Local,
+            section: Some(section.to_string()),
+            table: SymbolTable::Synthetic,
+        });
+    }
+
+    /// Name the entries of the `.plt`, `.plt.sec` and `.plt.got` sections after the symbol of
+    /// the GOT slot they jump through, given by `slots`. Linkers don't add symbols for them, so
+    /// calls to imported functions are otherwise left unnamed.
+    fn parse_plt(&mut self, slots: &HashMap<usize, &'data str>) {
+        let is_64 = match self.obj.architecture() {
+            Architecture::X86_64 => true,
+            Architecture::I386 => false,
+            _ => return,
+        };
+
+        // `ebx` points at the GOT in position independent code
+        let got = self.sections.iter().find(|section| section.name == ".got.plt");
+        let got = got.map(|section| section.start);
+        let known = self.known_addrs();
+
+        let mut entries = Vec::new();
+        for section in &self.sections {
+            if !matches!(section.name.as_str(), ".plt" | ".plt.sec" | ".plt.got") {
+                continue;
+            }
+
+            let bytes = section.bytes();
+            let mut section_entries = Vec::new();
+            let mut offset = 0;
+            while offset + 6 <= bytes.len() {
+                let disp = i32::from_le_bytes(bytes[offset + 2..offset + 6].try_into().unwrap());
+                let slot = match (bytes[offset], bytes[offset + 1], got) {
+                    // jmp [rip + disp32]
+                    (0xff, 0x25, _) if is_64 => {
+                        (section.start + offset + 6).wrapping_add(disp as usize)
+                    }
+                    // jmp [disp32]
+                    (0xff, 0x25, _) => disp as u32 as usize,
+                    // jmp [ebx + disp32]
+                    (0xff, 0xa3, Some(got)) if !is_64 => got.wrapping_add(disp as usize),
+                    _ => {
+                        offset += 1;
+                        continue;
+                    }
+                };
+
+                // entries may start with `endbr64` or `endbr32` and use `bnd jmp`
+                let mut start = offset;
+                if start >= 1 && bytes[start - 1] == 0xf2 {
+                    start -= 1;
+                }
+                let endbr = &bytes[start.saturating_sub(4)..start];
+                if matches!(endbr, [0xf3, 0x0f, 0x1e, 0xfa | 0xfb]) {
+                    start -= 4;
+                }
+
+                // the jump of the first `.plt` entry goes to the dynamic linker
+                if let Some(name) = slots.get(&slot) {
+                    section_entries.push((section.start + start, *name));
+                }
+
+                offset += 6;
+            }
+
+            // entries span up to the next one
+            for (idx, &(addr, name)) in section_entries.iter().enumerate() {
+                let end = section_entries.get(idx + 1).map_or(section.end, |(next, _)| *next);
+                if !known.contains(&addr) {
+                    entries.push((addr, end - addr, name, section.name.clone()));
+                }
+            }
+        }
+
+        for (addr, size, name, section) in entries {
+            self.synthesize(addr, size, name, &section, true);
+        }
+    }
+
+    /// Name the `__x86.get_pc_thunk` functions that 32-bit x86 position independent code calls
+    /// to load the address it's running at, by looking for calls to `mov reg, [esp]; ret`.
+    pub fn parse_thunks(&mut self) {
+        const THUNKS: [&str; 8] = [
+            "__x86.get_pc_thunk.ax",
+            "__x86.get_pc_thunk.cx",
+            "__x86.get_pc_thunk.dx",
+            "__x86.get_pc_thunk.bx",
+            "__x86.get_pc_thunk.sp",
+            "__x86.get_pc_thunk.bp",
+            "__x86.get_pc_thunk.si",
+            "__x86.get_pc_thunk.di",
+        ];
+
+        if self.obj.architecture() != Architecture::I386 {
+            return;
+        }
+
+        let known = self.known_addrs();
+        let code: Vec<&Section> =
+            self.sections.iter().filter(|section| section.kind == SectionKind::Code).collect();
+
+        let mut thunks = HashMap::new();
+        for section in &code {
+            let bytes = section.bytes();
+            for offset in 0..bytes.len().saturating_sub(4) {
+                // call rel32
+                if bytes[offset] != 0xe8 {
+                    continue;
+                }
+
+                let disp = i32::from_le_bytes(bytes[offset + 1..offset + 5].try_into().unwrap());
+                let target = (section.start + offset + 5).wrapping_add(disp as usize);
+                if known.contains(&target) || thunks.contains_key(&target) {
+                    continue;
+                }
+
+                let target_section = code.iter().find(|s| (s.start..s.end).contains(&target));
+                let target_section = match target_section {
+                    Some(section) => section,
+                    None => continue,
+                };
+
+                // `mov esp, [esp]` isn't a thunk
+                match target_section.bytes_by_addr(target, 4) {
+                    &[0x8b, modrm, 0x24, 0xc3] if modrm & 0xc7 == 0x04 && modrm != 0x24 => {
+                        let thunk = THUNKS[(modrm >> 3) as usize & 7];
+                        thunks.insert(target, (thunk, target_section.name.clone()));
+                    }
+                    _ => continue,
+                }
+            }
+        }
+
+        let mut thunks: Vec<_> = thunks.into_iter().collect();
+        thunks.sort_unstable_by_key(|(addr, _)| *addr);
+        for (addr, (name, section)) in thunks {
+            self.synthesize(addr, 4, name, &section, false);
+        }
     }
 
     pub fn parse_symbols(&mut self) {
-        self.syms.extend(crate::parse_symbol_table(self.obj));
+        let symtab = crate::parse_symbol_table(self.obj);
+        let known: HashSet<(usize, &str)> =
+            symtab.iter().map(|sym| (sym.addr, sym.item.name)).collect();
+
+        // stripped objects still have the symbols they export in `.dynsym`
+        let dynsym = crate::parse_dynamic_symbol_table(self.obj);
+        self.syms.extend(symtab);
+        self.syms.extend(AddressMap {
+            mapping: dynsym
+                .mapping
+                .into_iter()
+                .filter(|sym| !known.contains(&(sym.addr, sym.item.name)))
+                .collect(),
+        });
         self.syms.push(Addressed {
             addr: self.obj.entry() as usize,
             item: RawSymbol {
                 name: "entry",
                 module: None,
+                size: 0,
+                plt: false,
+                kind: SymbolKind::Text,
+                global: false,
+            },
+        });
+    }
+}
+
+/// Name of an ELF program header's type, as printed by `readelf`.
+fn segment_kind(p_type: u32) -> String {
+    let name = match p_type {
+        elf::PT_NULL => "NULL",
+        elf::PT_LOAD => "LOAD",
+        elf::PT_DYNAMIC => "DYNAMIC",
+        elf::PT_INTERP => "INTERP",
+        elf::PT_NOTE => "NOTE",
+        elf::PT_SHLIB => "SHLIB",
+        elf::PT_PHDR => "PHDR",
+        elf::PT_TLS => "TLS",
+        elf::PT_GNU_EH_FRAME => "GNU_EH_FRAME",
+        elf::PT_GNU_STACK => "GNU_STACK",
+        elf::PT_GNU_RELRO => "GNU_RELRO",
+        0x6474e553 => "GNU_PROPERTY",
+        p_type => return format!("{p_type:#x}"),
+    };
+
+    name.to_string()
+}
+
+/// Program headers of an ELF object, along with the path of its interpreter if it has one.
+pub fn program_headers<'data, Elf: FileHeader>(
+    obj: &ElfFile<'data, Elf>,
+) -> (Vec<SegmentHeader>, Option<String>) {
+    let endian = obj.endian();
+    let mut interpreter = None;
+    let mut headers = Vec::new();
+
+    for header in obj.elf_program_headers() {
+        let p_type = header.p_type(endian);
+        let p_flags = header.p_flags(endian);
+        let addr: u64 = header.p_vaddr(endian).into();
+
+        if p_type == elf::PT_INTERP {
+            if let Ok(path) = header.data(endian, obj.data()) {
+                let path = path.strip_suffix(&[0]).unwrap_or(path);
+                interpreter = Some(String::from_utf8_lossy(path).into_owned());
+            }
+        }
+
+        headers.push(SegmentHeader {
+            kind: segment_kind(p_type),
+            offset: header.p_offset(endian).into(),
+            addr: addr as usize,
+            file_size: header.p_filesz(endian).into(),
+            mem_size: header.p_memsz(endian).into(),
+            align: header.p_align(endian).into(),
+            load: p_type == elf::PT_LOAD,
+            read: p_flags & elf::PF_R != 0,
+            write: p_flags & elf::PF_W != 0,
+            exec: p_flags & elf::PF_X != 0,
+        });
+    }
+
+    (headers, interpreter)
+}
+
+/// Libraries of an ELF object's `DT_NEEDED` entries, the versions it requires from them and its
+/// search paths.
+pub fn dependencies<'data, Elf: FileHeader>(obj: &ElfFile<'data, Elf>) -> Dependencies {
+    let endian = obj.endian();
+    let data = obj.data();
+    let sections = obj.elf_section_table();
+    let mut deps = Dependencies::default();
+
+    if let Ok(Some((entries, link))) = sections.dynamic(endian, data) {
+        let strings = match sections.strings(endian, data, link) {
+            Ok(strings) => strings,
+            Err(..) => return deps,
+        };
+
+        for entry in entries {
+            let tag = match entry.tag32(endian) {
+                Some(tag @ (elf::DT_NEEDED | elf::DT_RPATH | elf::DT_RUNPATH)) => tag,
+                _ => continue,
+            };
+
+            let val: u64 = entry.d_val(endian).into();
+            let string = match strings.get(val as u32) {
+                Ok(string) => String::from_utf8_lossy(string),
+                Err(()) => continue,
+            };
+
+            // search paths are separated by colons
+            let paths = string.split(':').filter(|path| !path.is_empty()).map(str::to_string);
+            match tag {
+                elf::DT_NEEDED => deps.libraries.push(string.to_string()),
+                elf::DT_RPATH => deps.rpath.extend(paths),
+                _ => deps.runpath.extend(paths),
+            }
+        }
+    }
+
+    if let Ok(Some((mut verneeds, link))) = sections.gnu_verneed(endian, data) {
+        let strings = match sections.strings(endian, data, link) {
+            Ok(strings) => strings,
+            Err(..) => return deps,
+        };
+
+        while let Ok(Some((verneed, mut vernauxs))) = verneeds.next() {
+            let file = match strings.get(verneed.vn_file.get(endian)) {
+                Ok(file) => String::from_utf8_lossy(file).into_owned(),
+                Err(()) => continue,
+            };
+
+            let mut versions = Vec::new();
+            while let Ok(Some(vernaux)) = vernauxs.next() {
+                if let Ok(name) = strings.get(vernaux.vna_name.get(endian)) {
+                    versions.push(String::from_utf8_lossy(name).into_owned());
+                }
+            }
+
+            deps.versions.push((file, versions));
+        }
+    }
+
+    deps
+}
+
+/// Relocation sections of an ELF object, both `REL` and `RELA` ones, with their types named after
+/// the object's machine.
+pub fn relocations<'data, Elf: FileHeader>(obj: &ElfFile<'data, Elf>) -> Vec<RelocationTable> {
+    let endian = obj.endian();
+    let data = obj.data();
+    let header = obj.elf_header();
+    let machine = header.e_machine(endian);
+    let is_mips64el = header.is_mips64el(endian);
+    let sections = obj.elf_section_table();
+    let mut tables = Vec::new();
+
+    for section in sections.iter() {
+        // offset, type, symbol index and addend of each relocation
+        let mut relocs: Vec<(u64, u32, u32, Option<i64>)> = Vec::new();
+        let link = match section.sh_type(endian) {
+            elf::SHT_RELA => match section.rela(endian, data) {
+                Ok(Some((relas, link))) => {
+                    relocs.extend(relas.iter().map(|rela| {
+                        (
+                            rela.r_offset(endian).into(),
+                            rela.r_type(endian, is_mips64el),
+                            rela.r_sym(endian, is_mips64el),
+                            Some(rela.r_addend(endian).into()),
+                        )
+                    }));
+                    link
+                }
+                _ => continue,
             },
+            elf::SHT_REL => match section.rel(endian, data) {
+                Ok(Some((rels, link))) => {
+                    relocs.extend(rels.iter().map(|rel| {
+                        (rel.r_offset(endian).into(), rel.r_type(endian), rel.r_sym(endian), None)
+                    }));
+                    link
+                }
+                _ => continue,
+            },
+            _ => continue,
+        };
+
+        // relocations that don't refer to a symbol, like `R_X86_64_RELATIVE`, may not link to
+        // a symbol table
+        let symbols = sections.symbol_table_by_index(endian, data, link).ok();
+        let symbol_name = |idx: u32| {
+            let symbols = symbols.as_ref()?;
+            let symbol = symbols.symbols().get(idx as usize).filter(|_| idx != 0)?;
+
+            // section symbols are nameless, they're named after their section
+            let name = match symbol.st_type() {
+                elf::STT_SECTION => {
+                    let idx = SectionIndex(symbol.st_shndx(endian) as usize);
+                    sections.section_name(endian, sections.section(idx).ok()?).ok()?
+                }
+                _ => symbols.symbol_name(endian, symbol).ok()?,
+            };
+
+            Some(String::from_utf8_lossy(name).into_owned())
+        };
+
+        let entries = relocs
+            .into_iter()
+            .map(|(offset, r_type, sym, addend)| RelocationEntry {
+                offset,
+                kind: match crate::reloc_names::name(machine, r_type) {
+                    Some(name) => name.to_string(),
+                    None => format!("{r_type:#x}"),
+                },
+                symbol: symbol_name(sym),
+                addend,
+            })
+            .collect();
+
+        let name = sections.section_name(endian, section).unwrap_or(b"unknown");
+        tables.push(RelocationTable {
+            section: String::from_utf8_lossy(name).into_owned(),
+            entries,
         });
     }
+
+    tables
+}
+
+/// Undefined dynamic symbols, along with the library and version they're bound to by the GNU
+/// symbol versioning sections.
+///
+/// ELF doesn't otherwise record which library provides a symbol, so symbols without a version
+/// have an empty module.
+pub fn imports<'data, Elf: FileHeader>(obj: &ElfFile<'data, Elf>) -> Vec<Import> {
+    let endian = obj.endian();
+    let data = obj.data();
+    let sections = obj.elf_section_table();
+
+    // versions are referred to by the index `vna_other` assigns them
+    let mut needed: HashMap<u16, (String, String)> = HashMap::new();
+    if let Ok(Some((mut verneeds, link))) = sections.gnu_verneed(endian, data) {
+        if let Ok(strings) = sections.strings(endian, data, link) {
+            while let Ok(Some((verneed, mut vernauxs))) = verneeds.next() {
+                let file = match strings.get(verneed.vn_file.get(endian)) {
+                    Ok(file) => String::from_utf8_lossy(file).into_owned(),
+                    Err(()) => continue,
+                };
+
+                while let Ok(Some(vernaux)) = vernauxs.next() {
+                    if let Ok(name) = strings.get(vernaux.vna_name.get(endian)) {
+                        let version = String::from_utf8_lossy(name).into_owned();
+                        needed.insert(vernaux.vna_other.get(endian), (file.clone(), version));
+                    }
+                }
+            }
+        }
+    }
+
+    let versyms = match sections.gnu_versym(endian, data) {
+        Ok(Some((versyms, _))) => versyms,
+        _ => &[],
+    };
+
+    let mut imports = Vec::new();
+    for symbol in obj.dynamic_symbols() {
+        if !symbol.is_undefined() {
+            continue;
+        }
+
+        let name = match symbol.name() {
+            Ok(name) if !name.is_empty() => name,
+            _ => continue,
+        };
+
+        let version = versyms
+            .get(symbol.index().0)
+            .and_then(|versym| needed.get(&(versym.0.get(endian) & elf::VERSYM_VERSION)));
+
+        imports.push(Import {
+            module: version.map(|(file, _)| file.clone()).unwrap_or_default(),
+            name: name.to_string(),
+            version: version.map(|(_, version)| version.clone()),
+        });
+    }
+
+    imports
 }
 
 /// Common ELF dwarf section names I've found so far.
@@ -137,7 +580,7 @@ fn parse_sections<'data, Elf: FileHeader>(obj: &'data ElfFile<'data, Elf>) -> Ve
 
     for (header, section) in section_headers.iter().zip(obj.sections()) {
         let sh_flags = header.sh_flags(endian).into();
-        let (name, bytes, start, end) = crate::parse_section_generics(&section);
+        let (name, bytes, compressed, start, end) = crate::parse_section_generics(&section);
 
         let (mut kind, ident) = match header.sh_type(endian) {
             // Section header table entry is unused.
@@ -254,7 +697,7 @@ fn parse_sections<'data, Elf: FileHeader>(obj: &'data ElfFile<'data, Elf>) -> Ve
             bytes,
             start,
             end
-        ));
+        ).with_compression(compressed));
     }
 
     sections
@@ -1,24 +1,496 @@
-use object::{Object, ObjectSection, ObjectSymbol};
-use processor_shared::{AddressMap, Addressed};
+use object::{Object, ObjectSection, ObjectSegment, ObjectSymbol};
+use object::{CompressedData, CompressionFormat, SectionFlags, SectionKind, SegmentFlags};
+use object::SymbolKind;
+use processor_shared::{AddressMap, Addressed, PhysAddr};
 
+pub mod archive;
+pub mod coredump;
 pub mod elf;
 pub mod macho;
+pub mod notes;
 pub mod pe;
+mod reloc_names;
+pub mod wasm;
 
 pub struct RawSymbol<'data> {
     pub name: &'data str,
     pub module: Option<&'data str>,
+    /// Number of bytes the symbol spans, zero if unknown.
+    pub size: usize,
+    /// Whether the symbol is a PLT stub of an imported function.
+    pub plt: bool,
+    /// What the symbol refers to, `Section` for the name given to the start of a section.
+    pub kind: SymbolKind,
+    /// Whether the symbol is visible outside of the object that defines it.
+    pub global: bool,
+}
+
+/// Symbol that has to be provided by another module or the environment.
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// Library or module the symbol is taken from, empty if it isn't known.
+    pub module: String,
+    pub name: String,
+    /// Version the symbol is required to have, like `GLIBC_2.34` for ELF's symbol versioning.
+    pub version: Option<String>,
+}
+
+/// Symbol that's made available to other modules.
+#[derive(Debug, Clone)]
+pub struct Export {
+    pub name: String,
+    /// Where the symbol is located, if it's located anywhere.
+    pub addr: Option<PhysAddr>,
+    /// Number the symbol can be imported by instead of its name, like in a PE export table.
+    pub ordinal: Option<u32>,
+}
+
+/// Libraries an object is linked against and where the dynamic linker looks for them.
+#[derive(Debug, Default, Clone)]
+pub struct Dependencies {
+    /// Libraries in the order they're loaded.
+    pub libraries: Vec<String>,
+    /// Symbol versions required from a library, like `GLIBC_2.34` from `libc.so.6`.
+    pub versions: Vec<(String, Vec<String>)>,
+    /// Directories of ELF's `DT_RPATH`.
+    pub rpath: Vec<String>,
+    /// Directories of ELF's `DT_RUNPATH`.
+    pub runpath: Vec<String>,
+}
+
+impl Dependencies {
+    /// Symbol versions required from `library`.
+    pub fn versions_of(&self, library: &str) -> &[String] {
+        match self.versions.iter().find(|(name, _)| name == library) {
+            Some((_, versions)) => versions,
+            None => &[],
+        }
+    }
+}
+
+/// Whether a symbol is visible outside of the object that defines it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    Local,
+    Global,
+    /// Global, but may be overridden by another definition.
+    Weak,
+}
+
+/// Symbol of the object's own symbol table, like the COFF symbols of a PE executable.
+#[derive(Debug, Clone)]
+pub struct TableSymbol {
+    pub name: String,
+    /// Where the symbol is located, zero if it's undefined.
+    pub addr: PhysAddr,
+    pub size: u64,
+    pub kind: SymbolKind,
+    pub binding: Binding,
+    /// Name of the section the symbol is defined in, or `ABS` and `COM` for absolute and common
+    /// symbols. `None` if the symbol is undefined.
+    pub section: Option<String>,
+    pub table: SymbolTable,
+}
+
+/// Symbol table a symbol is listed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolTable {
+    /// The object's complete symbol table, like ELF's `.symtab`, which is removed by stripping.
+    Static,
+    /// Symbols used for dynamic linking, like ELF's `.dynsym`.
+    Dynamic,
+    Both,
+    /// Symbols made up for code that doesn't have one, like the PLT entries of stripped objects.
+    Synthetic,
+}
+
+/// Section as described by the object's section table.
+#[derive(Debug, Clone)]
+pub struct SectionHeader {
+    pub index: usize,
+    pub name: String,
+    pub kind: SectionKind,
+    pub addr: PhysAddr,
+    /// Where the section's bytes are in the file, unless they aren't stored, like `.bss`.
+    pub offset: Option<u64>,
+    pub size: u64,
+    pub align: u64,
+    /// Whether the section is loaded into memory.
+    pub alloc: bool,
+    pub write: bool,
+    pub exec: bool,
+    /// Whether the section is the template of thread-local storage, like `.tdata` and `.tbss`.
+    pub tls: bool,
+    /// Whether the section's bytes are stored compressed, like debug info often is.
+    pub compressed: bool,
+}
+
+impl SectionHeader {
+    pub fn parse<'data, S: ObjectSection<'data>>(section: &S) -> Self {
+        let kind = section.kind();
+        let (alloc, write, exec) = match section.flags() {
+            SectionFlags::Elf { sh_flags } => (
+                sh_flags & object::elf::SHF_ALLOC as u64 != 0,
+                sh_flags & object::elf::SHF_WRITE as u64 != 0,
+                sh_flags & object::elf::SHF_EXECINSTR as u64 != 0,
+            ),
+            SectionFlags::Coff { characteristics } => (
+                characteristics & object::pe::IMAGE_SCN_MEM_DISCARDABLE == 0
+                    && characteristics & object::pe::IMAGE_SCN_LNK_REMOVE == 0,
+                characteristics & object::pe::IMAGE_SCN_MEM_WRITE != 0,
+                characteristics & object::pe::IMAGE_SCN_MEM_EXECUTE != 0,
+            ),
+            // Mach-O keeps permissions with segments, so they're derived from what the section
+            // contains
+            _ => (
+                !matches!(
+                    kind,
+                    SectionKind::Debug | SectionKind::Metadata | SectionKind::Linker
+                ),
+                matches!(
+                    kind,
+                    SectionKind::Data
+                        | SectionKind::UninitializedData
+                        | SectionKind::Common
+                        | SectionKind::Tls
+                        | SectionKind::UninitializedTls
+                        | SectionKind::TlsVariables
+                ),
+                kind == SectionKind::Text,
+            ),
+        };
+
+        Self {
+            index: section.index().0,
+            name: section.name().unwrap_or("unknown").to_string(),
+            kind,
+            addr: section.address() as PhysAddr,
+            offset: section.file_range().map(|(offset, _)| offset),
+            size: section.size(),
+            align: section.align(),
+            alloc,
+            write,
+            exec,
+            tls: matches!(kind, SectionKind::Tls | SectionKind::UninitializedTls),
+            compressed: section
+                .compressed_data()
+                .is_ok_and(|data| data.format != CompressionFormat::None),
+        }
+    }
+}
+
+/// Segment as described by the object's program headers or load commands.
+#[derive(Debug, Clone)]
+pub struct SegmentHeader {
+    /// Type of an ELF program header like `LOAD`, otherwise the name of the segment.
+    pub kind: String,
+    pub offset: u64,
+    pub addr: PhysAddr,
+    /// Number of bytes stored in the file.
+    pub file_size: u64,
+    /// Number of bytes the segment spans once loaded.
+    pub mem_size: u64,
+    pub align: u64,
+    /// Whether the segment's mapped into memory.
+    pub load: bool,
+    pub read: bool,
+    pub write: bool,
+    pub exec: bool,
+}
+
+impl SegmentHeader {
+    /// Header of a segment of an object without program headers, like a Mach-O segment or the
+    /// section of a PE executable.
+    pub fn parse<'data, S: ObjectSegment<'data>>(segment: &S) -> Self {
+        let (read, write, exec) = match segment.flags() {
+            SegmentFlags::Elf { p_flags } => (
+                p_flags & object::elf::PF_R != 0,
+                p_flags & object::elf::PF_W != 0,
+                p_flags & object::elf::PF_X != 0,
+            ),
+            SegmentFlags::MachO { initprot, .. } => (
+                initprot & object::macho::VM_PROT_READ != 0,
+                initprot & object::macho::VM_PROT_WRITE != 0,
+                initprot & object::macho::VM_PROT_EXECUTE != 0,
+            ),
+            SegmentFlags::Coff { characteristics } => (
+                characteristics & object::pe::IMAGE_SCN_MEM_READ != 0,
+                characteristics & object::pe::IMAGE_SCN_MEM_WRITE != 0,
+                characteristics & object::pe::IMAGE_SCN_MEM_EXECUTE != 0,
+            ),
+            _ => (false, false, false),
+        };
+
+        let (offset, file_size) = segment.file_range();
+        Self {
+            kind: segment.name().ok().flatten().unwrap_or("unknown").to_string(),
+            offset,
+            addr: segment.address() as PhysAddr,
+            file_size,
+            mem_size: segment.size(),
+            align: segment.align(),
+            load: true,
+            read,
+            write,
+            exec,
+        }
+    }
+}
+
+/// Relocations stored in one section, like ELF's `.rela.text`.
+#[derive(Debug, Clone)]
+pub struct RelocationTable {
+    /// Name of the section the relocations are stored in, or of the section they patch for
+    /// formats that don't store them in sections of their own.
+    pub section: String,
+    pub entries: Vec<RelocationEntry>,
+}
+
+/// Relocation as it's stored in the object.
+#[derive(Debug, Clone)]
+pub struct RelocationEntry {
+    /// Where the relocation patches, relative to its section in relocatable objects and an
+    /// address otherwise.
+    pub offset: u64,
+    /// Type of the relocation, like `R_X86_64_PLT32`.
+    pub kind: String,
+    pub symbol: Option<String>,
+    /// Value added to the symbol, `None` if it's stored in the bytes that are patched, like with
+    /// ELF's `REL` relocations.
+    pub addend: Option<i64>,
+}
+
+/// Relocation tables of an object, in the order they're stored.
+pub fn relocations(obj: &object::File) -> Vec<RelocationTable> {
+    match obj {
+        object::File::Elf32(elf) => return elf::relocations(elf),
+        object::File::Elf64(elf) => return elf::relocations(elf),
+        _ => {}
+    }
+
+    let mut tables = Vec::new();
+    for section in obj.sections() {
+        let mut entries = Vec::new();
+        for (offset, reloc) in section.relocations() {
+            let symbol = match reloc.target() {
+                object::RelocationTarget::Symbol(idx) => obj
+                    .symbol_by_index(idx)
+                    .and_then(|symbol| symbol.name().map(str::to_string))
+                    .ok(),
+                object::RelocationTarget::Section(idx) => obj
+                    .section_by_index(idx)
+                    .and_then(|section| section.name().map(str::to_string))
+                    .ok(),
+                _ => None,
+            };
+
+            entries.push(RelocationEntry {
+                offset,
+                kind: format!("{:?}{}", reloc.kind(), reloc.size()),
+                symbol,
+                addend: Some(reloc.addend()),
+            });
+        }
+
+        if !entries.is_empty() {
+            let section = section.name().unwrap_or("unknown").to_string();
+            tables.push(RelocationTable { section, entries });
+        }
+    }
+
+    tables
+}
+
+/// Note sections of an object. Only ELF objects store notes.
+pub fn notes(obj: &object::File) -> Vec<notes::NoteSection> {
+    match obj {
+        object::File::Elf32(elf) => notes::parse(elf),
+        object::File::Elf64(elf) => notes::parse(elf),
+        _ => Vec::new(),
+    }
+}
+
+/// Threads, mapped files and memory of a core dump, if the object is an ELF core file.
+pub fn core_dump(obj: &object::File) -> Option<coredump::CoreDump> {
+    if obj.kind() != object::ObjectKind::Core {
+        return None;
+    }
+
+    match obj {
+        object::File::Elf32(elf) => Some(coredump::parse(elf)),
+        object::File::Elf64(elf) => Some(coredump::parse(elf)),
+        _ => None,
+    }
+}
+
+/// Program database a PE executable's debug info is stored in, from its CodeView record.
+#[derive(Debug, Clone)]
+pub struct PdbInfo {
+    pub path: String,
+    pub guid: [u8; 16],
+    /// Number of times the database was written to, which has to match along with the GUID.
+    pub age: u32,
+}
+
+/// Version of the Go toolchain a binary was built with, from its `.go.buildinfo` section.
+#[derive(Debug, Clone)]
+pub struct GoBuildInfo {
+    pub version: String,
+    /// Import path of the main package, like `example.com/hello`.
+    pub path: Option<String>,
+}
+
+/// Facts that identify an object, like the build-id the linker gave it.
+#[derive(Debug, Default, Clone)]
+pub struct Identity {
+    /// ELF's `NT_GNU_BUILD_ID` note, used to find the object's separate debug info.
+    pub build_id: Option<Vec<u8>>,
+    /// Mach-O's `LC_UUID`.
+    pub uuid: Option<[u8; 16]>,
+    /// Creation time of a PE executable.
+    pub timestamp: Option<u32>,
+    pub pdb: Option<PdbInfo>,
+    pub go: Option<GoBuildInfo>,
+    /// Whether the object doesn't have a static symbol table, like ELF's `.symtab`.
+    pub stripped: bool,
+}
+
+impl Identity {
+    pub fn parse(obj: &object::File) -> Self {
+        let timestamp = match obj {
+            object::File::Pe32(pe) => Some(pe::timestamp(pe)),
+            object::File::Pe64(pe) => Some(pe::timestamp(pe)),
+            _ => None,
+        };
+
+        let pdb = obj.pdb_info().ok().flatten().map(|pdb| PdbInfo {
+            path: String::from_utf8_lossy(pdb.path()).into_owned(),
+            guid: pdb.guid(),
+            age: pdb.age(),
+        });
+
+        Self {
+            build_id: obj.build_id().ok().flatten().filter(|id| !id.is_empty()).map(Vec::from),
+            uuid: obj.mach_uuid().ok().flatten(),
+            timestamp,
+            pdb,
+            go: go_build_info(obj),
+            stripped: obj.symbol_table().is_none(),
+        }
+    }
+}
+
+/// Build info of a Go binary, which is either in a section of its own or at the start of a data
+/// section, like with PE executables.
+fn go_build_info(obj: &object::File) -> Option<GoBuildInfo> {
+    const MAGIC: &[u8] = b"\xff Go buildinf:";
+
+    let section = obj
+        .section_by_name(".go.buildinfo")
+        .or_else(|| obj.section_by_name("__go_buildinfo"));
+    let data = match section {
+        Some(section) => section.data().ok()?,
+        None => obj
+            .sections()
+            .filter(|section| section.kind() == SectionKind::Data)
+            .filter_map(|section| section.data().ok())
+            .find(|data| data.starts_with(MAGIC))?,
+    };
+
+    let header = data.strip_prefix(MAGIC)?;
+
+    // before Go 1.18 the strings are referred to by pointers instead of following the header
+    let flags = *header.get(1)?;
+    if flags & 2 == 0 {
+        return None;
+    }
+
+    let mut strings = data.get(32..)?;
+    let version = std::str::from_utf8(read_go_string(&mut strings)?).ok()?;
+    let modinfo = read_go_string(&mut strings)?;
+
+    // module info is wrapped in 16 bytes of markers on either side
+    let modinfo: &[u8] = match modinfo.len() {
+        len if len >= 33 && modinfo[len - 17] == b'\n' => &modinfo[16..len - 16],
+        _ => &[],
+    };
+
+    let path = String::from_utf8_lossy(modinfo)
+        .lines()
+        .find_map(|line| line.strip_prefix("path\t"))
+        .map(str::to_string);
+
+    Some(GoBuildInfo { version: version.to_string(), path })
+}
+
+/// Bytes prefixed by their length as an unsigned LEB128 varint.
+fn read_go_string<'data>(data: &mut &'data [u8]) -> Option<&'data [u8]> {
+    let mut len = 0usize;
+    let mut shift = 0;
+    loop {
+        let (&byte, rest) = data.split_first()?;
+        *data = rest;
+        len |= ((byte & 0x7f) as usize).checked_shl(shift)?;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+
+    let string = data.get(..len)?;
+    *data = &data[len..];
+    Some(string)
+}
+
+/// Whether `name` is one of the mapping symbols ARM, AArch64 and RISC-V objects mark switches
+/// between instruction sets and data with, like `$t`, `$d.12` or `$xrv64i2p1_m2p0`.
+pub fn is_mapping_symbol(name: &str) -> bool {
+    let name = name.split('.').next().unwrap_or(name);
+    matches!(name, "$a" | "$t" | "$d" | "$x") || name.starts_with("$xrv")
 }
 
 fn parse_symbol_table<'data, Obj: Object<'data, 'data>>(
     obj: &'data Obj,
+) -> AddressMap<RawSymbol<'data>> {
+    parse_symbols(obj.symbols())
+}
+
+/// Symbols of the dynamic symbol table, like ELF's `.dynsym`.
+fn parse_dynamic_symbol_table<'data, Obj: Object<'data, 'data>>(
+    obj: &'data Obj,
+) -> AddressMap<RawSymbol<'data>> {
+    parse_symbols(obj.dynamic_symbols())
+}
+
+fn parse_symbols<'data, Sym: ObjectSymbol<'data>>(
+    symbols: impl Iterator<Item = Sym>,
 ) -> AddressMap<RawSymbol<'data>> {
     let mut syms = AddressMap::default();
-    for sym in obj.symbols() {
+    for sym in symbols {
+        // symbols defined elsewhere, which all claim to be at address zero
+        if sym.is_undefined() {
+            continue;
+        }
+
+        // thread-local symbols are at offsets into each thread's copy of the TLS template,
+        // which would collide with whatever is at those addresses
+        if sym.kind() == SymbolKind::Tls {
+            continue;
+        }
+
         match sym.name() {
+            // mapping symbols mark where code turns into data, they don't name anything
+            Ok(name) if is_mapping_symbol(name) => continue,
             Ok(name) => syms.push(Addressed {
                 addr: sym.address() as usize,
-                item: RawSymbol { name, module: None },
+                item: RawSymbol {
+                    name,
+                    module: None,
+                    size: sym.size() as usize,
+                    plt: false,
+                    kind: sym.kind(),
+                    global: sym.is_global(),
+                },
             }),
             Err(err) => {
                 log::complex!(
@@ -35,12 +507,12 @@ fn parse_symbol_table<'data, Obj: Object<'data, 'data>>(
 
 fn parse_section_generics<'data, Obj: ObjectSection<'data>>(
     section: &'data Obj,
-) -> (String, &'static [u8], usize, usize) {
+) -> (String, &'static [u8], Option<CompressedData<'static>>, usize, usize) {
     let name = match section.name() {
         Ok(name) => name,
         Err(_) => {
             log::complex!(
-                w "[macho::parse_sections] ",
+                w "[binformat::parse_sections] ",
                 y "Failed to read name.",
             );
             "unknown"
@@ -52,7 +524,7 @@ fn parse_section_generics<'data, Obj: ObjectSection<'data>>(
         Ok(data) => unsafe { std::mem::transmute(data) },
         Err(..) => {
             log::complex!(
-                w "[macho::parse_sections] ",
+                w "[binformat::parse_sections] ",
                 y "Failed to read section ",
                 b name,
                 y "."
@@ -61,10 +533,39 @@ fn parse_section_generics<'data, Obj: ObjectSection<'data>>(
         }
     };
 
+    // Compressed sections are only decompressed once they're read, as debug info can inflate
+    // to many times its size.
+    let compressed: Option<CompressedData<'static>> = match section.compressed_data() {
+        Ok(data) if data.format == CompressionFormat::None => None,
+        Ok(data) => {
+            // The file is memory mapped so the compressed bytes are of lifetime &'static [u8].
+            let bytes: &'static [u8] = unsafe { std::mem::transmute(data.data) };
+            Some(CompressedData {
+                format: data.format,
+                data: bytes,
+                uncompressed_size: data.uncompressed_size,
+            })
+        }
+        Err(..) => {
+            log::complex!(
+                w "[binformat::parse_sections] ",
+                y "Failed to read compression header of section ",
+                b name,
+                y "."
+            );
+            None
+        }
+    };
+
+    let size = match compressed {
+        Some(compressed) => compressed.uncompressed_size,
+        None => section.size(),
+    };
+
     let start = section.address() as usize;
-    let end = start + section.size() as usize;
+    let end = start + size as usize;
 
-    (name.to_string(), bytes, start, end)
+    (name.to_string(), bytes, compressed, start, end)
 }
 
 pub struct Datastructure {
@@ -1,8 +1,9 @@
 use crate::RawSymbol;
 use processor_shared::{AddressMap, Addressed, Section, SectionKind};
 use object::macho::{self, DyldInfoCommand, DysymtabCommand, LinkeditDataCommand};
-use object::read::macho::{MachHeader, MachOFile, SymbolTable};
-use object::{Endianness, Object, ObjectSection, ObjectSegment, ReadRef, SectionFlags};
+use object::read::macho::{FatArch, MachHeader, MachOFile, SymbolTable};
+use object::{Architecture, Endianness, FileKind, Object, ObjectSection, ObjectSegment};
+use object::{ReadRef, SectionFlags, SymbolKind};
 use std::mem::size_of;
 
 #[derive(Debug, Clone, Copy)]
@@ -107,6 +108,32 @@ impl ChainedFixupPointerGeneric {
     }
 }
 
+/// Object of the architecture `arch` in a universal (fat) binary, or its first object if `arch`
+/// isn't given. Anything that isn't a universal binary is returned as is.
+pub fn fat_slice(
+    data: &[u8],
+    arch: Option<Architecture>,
+) -> Result<Option<&[u8]>, object::Error> {
+    fn select<'data, Fat: FatArch>(
+        arches: &[Fat],
+        data: &'data [u8],
+        arch: Option<Architecture>,
+    ) -> Result<Option<&'data [u8]>, object::Error> {
+        let slice = match arch {
+            Some(arch) => arches.iter().find(|slice| slice.architecture() == arch),
+            None => arches.first(),
+        };
+
+        slice.map(|slice| slice.data(data)).transpose()
+    }
+
+    match FileKind::parse(data) {
+        Ok(FileKind::MachOFat32) => select(macho::FatHeader::parse_arch32(data)?, data, arch),
+        Ok(FileKind::MachOFat64) => select(macho::FatHeader::parse_arch64(data)?, data, arch),
+        _ => Ok(Some(data)),
+    }
+}
+
 pub struct MachoDebugInfo<'data, Mach: MachHeader> {
     /// Parsed Mach-O header.
     obj: &'data MachOFile<'data, Mach>,
@@ -177,7 +204,8 @@ impl<'data, Mach: MachHeader<Endian = Endianness>> MachoDebugInfo<'data, Mach> {
             if let Some(dylib_info) = lcmd.dyld_info()? {
                 self.dylid_info = Some(dylib_info);
             }
-            if let Some(dylib) = lcmd.dylib()? {
+            // the library's own name isn't one it's linked against
+            if let Some(dylib) = lcmd.dylib()?.filter(|_| lcmd.cmd() != macho::LC_ID_DYLIB) {
                 let dylib = lcmd.string(endian, dylib.dylib.name)?;
                 let dylib = std::str::from_utf8(dylib).unwrap_or("");
                 self.dylibs.push(dylib);
@@ -190,6 +218,11 @@ impl<'data, Mach: MachHeader<Endian = Endianness>> MachoDebugInfo<'data, Mach> {
         Ok(())
     }
 
+    /// Dynamic libraries the object is linked against, in the order of their load commands.
+    pub fn libraries(&self) -> impl Iterator<Item = &'data str> + '_ {
+        self.dylibs.iter().copied().filter(|dylib| !dylib.is_empty())
+    }
+
     fn parse_dylid_info(&mut self) -> Result<(), object::Error> {
         let endian = self.obj.endian();
         let dylib_info = match self.dylid_info {
@@ -256,6 +289,10 @@ impl<'data, Mach: MachHeader<Endian = Endianness>> MachoDebugInfo<'data, Mach> {
             item: RawSymbol {
                 name: "entry",
                 module: None,
+                size: 0,
+                plt: false,
+                kind: SymbolKind::Text,
+                global: false,
             },
         });
     }
@@ -288,7 +325,7 @@ const DWARF_SECTIONS: [&str; 20] = [
 fn parse_sections<'data, Mach: MachHeader>(obj: &'data MachOFile<'data, Mach>) -> Vec<Section> {
     let mut sections = Vec::new();
     for section in obj.sections() {
-        let (name, bytes, start, end) = crate::parse_section_generics(&section);
+        let (name, bytes, compressed, start, end) = crate::parse_section_generics(&section);
         let section_flags = match section.flags() {
             SectionFlags::MachO { flags } => flags,
             _ => unreachable!()
@@ -394,7 +431,7 @@ fn parse_sections<'data, Mach: MachHeader>(obj: &'data MachOFile<'data, Mach>) -
             bytes,
             start,
             end
-        ));
+        ).with_compression(compressed));
     }
 
     sections
@@ -778,6 +815,10 @@ fn parse_chained_fixups<'data, Mach: MachHeader<Endian = Endianness>>(
                                     item: RawSymbol {
                                         name: entry.name,
                                         module,
+                                        size: 0,
+                                        plt: false,
+                                        kind: SymbolKind::Unknown,
+                                        global: false,
                                     }
                                 });
                             } else {
@@ -856,3 +897,42 @@ fn parse_chained_fixups<'data, Mach: MachHeader<Endian = Endianness>>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::fat_slice;
+    use object::Architecture;
+
+    /// Universal binary with an x86_64 and an arm64 slice, that only contain their name.
+    fn universal() -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&0xcafebabe_u32.to_be_bytes());
+        data.extend_from_slice(&2_u32.to_be_bytes());
+
+        // cputype, cpusubtype, offset, size and alignment of each slice
+        for (cputype, offset) in [(0x01000007_u32, 0x1000_u32), (0x0100000c, 0x2000)] {
+            for field in [cputype, 3, offset, 6, 12] {
+                data.extend_from_slice(&field.to_be_bytes());
+            }
+        }
+
+        data.resize(0x1000, 0);
+        data.extend_from_slice(b"x86_64");
+        data.resize(0x2000, 0);
+        data.extend_from_slice(b"arm64\0");
+        data
+    }
+
+    #[test]
+    fn slices() {
+        let data = universal();
+        assert_eq!(fat_slice(&data, None).unwrap(), Some(&b"x86_64"[..]));
+        assert_eq!(fat_slice(&data, Some(Architecture::X86_64)).unwrap(), Some(&b"x86_64"[..]));
+        assert_eq!(fat_slice(&data, Some(Architecture::Aarch64)).unwrap(), Some(&b"arm64\0"[..]));
+        assert_eq!(fat_slice(&data, Some(Architecture::Riscv64)).unwrap(), None);
+
+        // thin objects are left as is
+        let thin = [0xcf, 0xfa, 0xed, 0xfe, 0x07, 0x00, 0x00, 0x01].repeat(4);
+        assert_eq!(fat_slice(&thin, Some(Architecture::Aarch64)).unwrap(), Some(&thin[..]));
+    }
+}
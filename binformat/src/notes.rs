@@ -0,0 +1,283 @@
+//! Notes of ELF objects, the metadata that's stored in `.note.*` sections and `PT_NOTE`
+//! segments, like the build-id or the registers of a core dump's threads.
+
+use object::elf;
+use object::read::elf::{ElfFile, FileHeader, ProgramHeader, SectionHeader};
+
+/// Notes stored in one section, or in a segment for objects without sections like core dumps.
+#[derive(Debug, Clone)]
+pub struct NoteSection {
+    /// Name of the section, or the type and address of the segment.
+    pub name: String,
+    pub notes: Vec<Note>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Note {
+    /// Who defined the note's type, like `GNU` or `CORE`.
+    pub owner: String,
+    pub kind: u32,
+    pub desc: Vec<u8>,
+    /// What the note describes, one line per fact. Empty if the note's type isn't known.
+    pub description: Vec<String>,
+}
+
+impl Note {
+    /// Name of the note's type, like `NT_GNU_BUILD_ID`, if it's known.
+    pub fn kind_name(&self) -> Option<&'static str> {
+        let name = match (self.owner.as_str(), self.kind) {
+            ("GNU", elf::NT_GNU_ABI_TAG) => "NT_GNU_ABI_TAG",
+            ("GNU", elf::NT_GNU_HWCAP) => "NT_GNU_HWCAP",
+            ("GNU", elf::NT_GNU_BUILD_ID) => "NT_GNU_BUILD_ID",
+            ("GNU", elf::NT_GNU_GOLD_VERSION) => "NT_GNU_GOLD_VERSION",
+            ("GNU", elf::NT_GNU_PROPERTY_TYPE_0) => "NT_GNU_PROPERTY_TYPE_0",
+            ("Go", 4) => "NT_GO_BUILDID",
+            ("stapsdt", 3) => "NT_STAPSDT",
+            ("CORE", elf::NT_PRSTATUS) => "NT_PRSTATUS",
+            ("CORE", elf::NT_PRFPREG) => "NT_PRFPREG",
+            ("CORE", elf::NT_PRPSINFO) => "NT_PRPSINFO",
+            ("CORE", elf::NT_TASKSTRUCT) => "NT_TASKSTRUCT",
+            ("CORE", elf::NT_AUXV) => "NT_AUXV",
+            ("CORE", elf::NT_SIGINFO) => "NT_SIGINFO",
+            ("CORE", elf::NT_FILE) => "NT_FILE",
+            ("LINUX", elf::NT_PRXFPREG) => "NT_PRXFPREG",
+            ("LINUX", elf::NT_X86_XSTATE) => "NT_X86_XSTATE",
+            ("LINUX", elf::NT_ARM_VFP) => "NT_ARM_VFP",
+            ("LINUX", elf::NT_ARM_TLS) => "NT_ARM_TLS",
+            ("LINUX", elf::NT_ARM_HW_BREAK) => "NT_ARM_HW_BREAK",
+            ("LINUX", elf::NT_ARM_HW_WATCH) => "NT_ARM_HW_WATCH",
+            ("LINUX", elf::NT_ARM_SYSTEM_CALL) => "NT_ARM_SYSTEM_CALL",
+            _ => return None,
+        };
+
+        Some(name)
+    }
+}
+
+/// How the fields of a note's descriptor are laid out.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Layout {
+    pub machine: u16,
+    big_endian: bool,
+    is_64: bool,
+}
+
+impl Layout {
+    pub fn of<'data, Elf: FileHeader>(obj: &ElfFile<'data, Elf>) -> Self {
+        let header = obj.elf_header();
+        Self {
+            machine: header.e_machine(obj.endian()),
+            big_endian: header.is_big_endian(),
+            is_64: header.is_type_64(),
+        }
+    }
+
+    pub fn u16(&self, bytes: &[u8], offset: usize) -> Option<u16> {
+        let bytes: [u8; 2] = bytes.get(offset..offset + 2)?.try_into().ok()?;
+        Some(match self.big_endian {
+            true => u16::from_be_bytes(bytes),
+            false => u16::from_le_bytes(bytes),
+        })
+    }
+
+    pub fn u32(&self, bytes: &[u8], offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = bytes.get(offset..offset + 4)?.try_into().ok()?;
+        Some(match self.big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        })
+    }
+
+    /// Word of the object's pointer size.
+    pub fn word(&self, bytes: &[u8], offset: usize) -> Option<u64> {
+        if !self.is_64 {
+            return self.u32(bytes, offset).map(u64::from);
+        }
+
+        let bytes: [u8; 8] = bytes.get(offset..offset + 8)?.try_into().ok()?;
+        Some(match self.big_endian {
+            true => u64::from_be_bytes(bytes),
+            false => u64::from_le_bytes(bytes),
+        })
+    }
+
+    pub fn word_size(&self) -> usize {
+        if self.is_64 {
+            8
+        } else {
+            4
+        }
+    }
+}
+
+/// Notes of each note section, or of each `PT_NOTE` segment if the object doesn't have any
+/// sections.
+pub fn parse<'data, Elf: FileHeader>(obj: &ElfFile<'data, Elf>) -> Vec<NoteSection> {
+    let endian = obj.endian();
+    let data = obj.data();
+    let layout = Layout::of(obj);
+
+    let sections = obj.elf_section_table();
+    let mut tables = Vec::new();
+    for section in sections.iter() {
+        let mut iter = match section.notes(endian, data) {
+            Ok(Some(iter)) => iter,
+            _ => continue,
+        };
+
+        let mut notes = Vec::new();
+        while let Ok(Some(note)) = iter.next() {
+            notes.push(parse_note(layout, note.name(), note.n_type(endian), note.desc()));
+        }
+
+        let name = sections.section_name(endian, section).unwrap_or(b"unknown");
+        let name = String::from_utf8_lossy(name).into_owned();
+        tables.push(NoteSection { name, notes });
+    }
+
+    if !tables.is_empty() {
+        return tables;
+    }
+
+    for segment in obj.elf_program_headers() {
+        let mut iter = match segment.notes(endian, data) {
+            Ok(Some(iter)) => iter,
+            _ => continue,
+        };
+
+        let mut notes = Vec::new();
+        while let Ok(Some(note)) = iter.next() {
+            notes.push(parse_note(layout, note.name(), note.n_type(endian), note.desc()));
+        }
+
+        let offset: u64 = segment.p_offset(endian).into();
+        let name = format!("NOTE segment at offset {offset:#x}");
+        tables.push(NoteSection { name, notes });
+    }
+
+    tables
+}
+
+fn parse_note(layout: Layout, owner: &[u8], kind: u32, desc: &[u8]) -> Note {
+    let owner = String::from_utf8_lossy(owner).into_owned();
+    let description = match (owner.as_str(), kind) {
+        ("GNU", elf::NT_GNU_ABI_TAG) => abi_tag(layout, desc).into_iter().collect(),
+        ("GNU", elf::NT_GNU_BUILD_ID) => vec![format!("Build ID: {}", hex(desc))],
+        ("GNU", elf::NT_GNU_GOLD_VERSION) => vec![format!("Version: {}", string(desc))],
+        ("GNU", elf::NT_GNU_PROPERTY_TYPE_0) => properties(layout, desc),
+        ("Go", 4) => vec![format!("Go build ID: {}", string(desc))],
+        ("stapsdt", 3) => probe(layout, desc).into_iter().collect(),
+        _ => Vec::new(),
+    };
+
+    Note { owner, kind, desc: desc.to_vec(), description }
+}
+
+/// OS and the oldest version of its ABI the object runs on, like `GNU ABI: Linux 3.2.0`.
+fn abi_tag(layout: Layout, desc: &[u8]) -> Option<String> {
+    let os = match layout.u32(desc, 0)? {
+        elf::ELF_NOTE_OS_LINUX => "Linux".to_string(),
+        elf::ELF_NOTE_OS_GNU => "Hurd".to_string(),
+        elf::ELF_NOTE_OS_SOLARIS2 => "Solaris".to_string(),
+        elf::ELF_NOTE_OS_FREEBSD => "FreeBSD".to_string(),
+        os => format!("OS {os}"),
+    };
+
+    let major = layout.u32(desc, 4)?;
+    let minor = layout.u32(desc, 8)?;
+    let patch = layout.u32(desc, 12)?;
+    Some(format!("GNU ABI: {os} {major}.{minor}.{patch}"))
+}
+
+/// Names of the bits set in `bits`, the lowest bit being named first by `names`.
+fn flags(bits: u32, names: &[&str]) -> String {
+    let mut set: Vec<String> = Vec::new();
+    for bit in 0..32 {
+        if bits & (1 << bit) == 0 {
+            continue;
+        }
+
+        match names.get(bit) {
+            Some(name) => set.push(name.to_string()),
+            None => set.push(format!("{:#x}", 1u32 << bit)),
+        }
+    }
+
+    if set.is_empty() {
+        return "none".to_string();
+    }
+
+    set.join(", ")
+}
+
+/// Properties of a GNU property note, like the control-flow protection the object supports.
+fn properties(layout: Layout, desc: &[u8]) -> Vec<String> {
+    const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+    const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+    const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc0010002;
+    const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc0008002;
+    const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc0000002;
+    const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc0000000;
+
+    let x86 = matches!(layout.machine, elf::EM_386 | elf::EM_X86_64);
+    let aarch64 = layout.machine == elf::EM_AARCH64;
+    let isa = ["x86-64-baseline", "x86-64-v2", "x86-64-v3", "x86-64-v4"];
+
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    while let (Some(kind), Some(size)) = (layout.u32(desc, offset), layout.u32(desc, offset + 4)) {
+        let data = match desc.get(offset + 8..offset + 8 + size as usize) {
+            Some(data) => data,
+            None => break,
+        };
+
+        let value = layout.u32(data, 0).unwrap_or(0);
+        let line = match kind {
+            GNU_PROPERTY_STACK_SIZE => match layout.word(data, 0) {
+                Some(size) => format!("Stack size: {size:#x}"),
+                None => format!("Stack size: {}", hex(data)),
+            },
+            GNU_PROPERTY_NO_COPY_ON_PROTECTED => "No copy on protected".to_string(),
+            GNU_PROPERTY_X86_FEATURE_1_AND if x86 => {
+                format!("x86 features: {}", flags(value, &["IBT", "SHSTK"]))
+            }
+            GNU_PROPERTY_X86_ISA_1_NEEDED if x86 => {
+                format!("x86 ISA needed: {}", flags(value, &isa))
+            }
+            GNU_PROPERTY_X86_ISA_1_USED if x86 => format!("x86 ISA used: {}", flags(value, &isa)),
+            GNU_PROPERTY_AARCH64_FEATURE_1_AND if aarch64 => {
+                format!("AArch64 features: {}", flags(value, &["BTI", "PAC"]))
+            }
+            kind => format!("Property {kind:#x}: {}", hex(data)),
+        };
+        lines.push(line);
+
+        // properties are aligned to the size of a word
+        let align = layout.word_size();
+        offset += (8 + size as usize + align - 1) & !(align - 1);
+    }
+
+    lines
+}
+
+/// Provider and name of a SystemTap probe, like `Probe: libc:setjmp`.
+fn probe(layout: Layout, desc: &[u8]) -> Option<String> {
+    let word = layout.word_size();
+    let pc = layout.word(desc, 0)?;
+
+    // the address of the probe, the `.stapsdt.base` section and its semaphore come first
+    let mut strings = desc.get(word * 3..)?.split(|&byte| byte == 0);
+    let provider = String::from_utf8_lossy(strings.next()?);
+    let name = String::from_utf8_lossy(strings.next()?);
+    Some(format!("Probe: {provider}:{name} at {pc:#x}"))
+}
+
+/// Text of a string that may be terminated by a nul byte.
+fn string(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -1,9 +1,9 @@
-use crate::{datastructure, RawSymbol};
-use processor_shared::{AddressMap, Addressed, Section, SectionKind};
+use crate::{datastructure, Export, Import, RawSymbol};
+use processor_shared::{AddressMap, Addressed, PhysAddr, Section, SectionKind};
 use object::pe;
-use object::read::pe::{ImageNtHeaders, ImageThunkData, PeFile};
+use object::read::pe::{ExportTarget, ImageNtHeaders, ImageThunkData, PeFile};
 use object::LittleEndian as LE;
-use object::Object;
+use object::{Object, SymbolKind};
 use std::mem::size_of;
 
 datastructure! {
@@ -45,56 +45,50 @@ impl<'data, Pe: ImageNtHeaders> PeDebugInfo<'data, Pe> {
         let mut import_descs = import_table.descriptors()?;
         while let Some(import_desc) = import_descs.next()? {
             let module = import_table.name(import_desc.name.get(LE))?;
+            let module = std::str::from_utf8(module).ok().map(module_name);
             let first_thunk = import_desc.first_thunk.get(LE);
             let original_first_thunk = import_desc.original_first_thunk.get(LE);
 
-            let thunk = if first_thunk == 0 {
-                original_first_thunk
-            } else {
+            // the lookup table still has the names once the address table is bound, but
+            // it's optional
+            let thunk = if original_first_thunk == 0 {
                 first_thunk
+            } else {
+                original_first_thunk
             };
 
-            let mut import_addr_table = import_table.thunks(thunk)?;
+            let mut import_lookup_table = import_table.thunks(thunk)?;
             let mut func_rva = first_thunk;
-            while let Some(func) = import_addr_table.next::<Pe>()? {
-                if !func.is_ordinal() {
-                    let (hint, name) = match import_table.hint_name(func.address()) {
-                        Ok(val) => val,
-                        Err(..) => {
-                            // skip over an entry
-                            func_rva += size_of::<Pe::ImageThunkData>() as u32;
-                            continue;
-                        }
-                    };
-
-                    let name = match std::str::from_utf8(name) {
-                        Ok(name) => name,
-                        Err(..) => {
-                            // skip over an entry
-                            func_rva += size_of::<Pe::ImageThunkData>() as u32;
-                            continue;
-                        }
-                    };
-
-                    // `original_first_thunk` uses a `hint` into the export
-                    // table whilst iterating thourhg regular `thunk`'s is
-                    // a simple offset into the symbol export table
-                    let addr = if thunk == original_first_thunk {
-                        hint as u64 + self.obj.relative_address_base()
-                    } else {
-                        func_rva as u64 + self.obj.relative_address_base()
-                    };
-
-                    let module =
-                        std::str::from_utf8(module).ok().and_then(|x| x.strip_suffix(".dll"));
-                    self.syms.push(Addressed {
-                        addr: addr as usize,
-                        item: RawSymbol { name, module },
-                    });
+            while let Some(func) = import_lookup_table.next::<Pe>()? {
+                // imports are located at their entry in the import address table
+                let addr = func_rva as u64 + self.obj.relative_address_base();
+                func_rva += size_of::<Pe::ImageThunkData>() as u32;
+
+                if func.is_ordinal() {
+                    continue;
                 }
 
-                // skip over an entry
-                func_rva += size_of::<Pe::ImageThunkData>() as u32;
+                let name = match import_table.hint_name(func.address()) {
+                    Ok((_, name)) => name,
+                    Err(..) => continue,
+                };
+
+                let name = match std::str::from_utf8(name) {
+                    Ok(name) => name,
+                    Err(..) => continue,
+                };
+
+                self.syms.push(Addressed {
+                    addr: addr as usize,
+                    item: RawSymbol {
+                        name,
+                        module,
+                        size: 0,
+                        plt: false,
+                        kind: SymbolKind::Unknown,
+                        global: false,
+                    },
+                });
             }
         }
 
@@ -108,11 +102,114 @@ impl<'data, Pe: ImageNtHeaders> PeDebugInfo<'data, Pe> {
             item: RawSymbol {
                 name: "entry",
                 module: None,
+                size: 0,
+                plt: false,
+                kind: SymbolKind::Text,
+                global: false,
             },
         });
     }
 }
 
+/// Entries of the import directory, in the order of the DLLs they're imported from.
+///
+/// Functions imported by ordinal are named after it, like `#12`.
+pub fn imports<'data, Pe: ImageNtHeaders>(obj: &PeFile<'data, Pe>) -> Vec<Import> {
+    let mut imports = Vec::new();
+    let import_table = match obj.import_table() {
+        Ok(Some(table)) => table,
+        _ => return imports,
+    };
+
+    let mut import_descs = match import_table.descriptors() {
+        Ok(descs) => descs,
+        Err(..) => return imports,
+    };
+
+    while let Ok(Some(import_desc)) = import_descs.next() {
+        let module = match import_table.name(import_desc.name.get(LE)) {
+            Ok(module) => String::from_utf8_lossy(module).into_owned(),
+            Err(..) => continue,
+        };
+
+        let thunk = match import_desc.original_first_thunk.get(LE) {
+            0 => import_desc.first_thunk.get(LE),
+            thunk => thunk,
+        };
+
+        let mut import_lookup_table = match import_table.thunks(thunk) {
+            Ok(thunks) => thunks,
+            Err(..) => continue,
+        };
+
+        while let Ok(Some(func)) = import_lookup_table.next::<Pe>() {
+            let name = if func.is_ordinal() {
+                format!("#{}", func.ordinal())
+            } else {
+                match import_table.hint_name(func.address()) {
+                    Ok((_, name)) => String::from_utf8_lossy(name).into_owned(),
+                    Err(..) => continue,
+                }
+            };
+
+            imports.push(Import { module: module.clone(), name, version: None });
+        }
+    }
+
+    imports
+}
+
+/// Entries of the export table along with their ordinals, sorted by ordinal.
+///
+/// Exports without a name are named after their ordinal, and exports forwarded to another
+/// DLL don't have an address.
+/// Seconds since the unix epoch the linker gave as the executable's creation time. Reproducible
+/// builds put a hash of the executable here instead.
+pub fn timestamp<'data, Pe: ImageNtHeaders>(obj: &PeFile<'data, Pe>) -> u32 {
+    obj.nt_headers().file_header().time_date_stamp.get(LE)
+}
+
+pub fn exports<'data, Pe: ImageNtHeaders>(obj: &PeFile<'data, Pe>) -> Vec<Export> {
+    let export_table = match obj.export_table() {
+        Ok(Some(table)) => table,
+        _ => return Vec::new(),
+    };
+
+    let mut exports: Vec<Export> = export_table
+        .exports()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|export| {
+            let name = match export.name {
+                Some(name) => String::from_utf8_lossy(name).into_owned(),
+                None => format!("#{}", export.ordinal),
+            };
+
+            let addr = match export.target {
+                ExportTarget::Address(rva) => {
+                    Some((rva as u64 + obj.relative_address_base()) as PhysAddr)
+                }
+                _ => None,
+            };
+
+            Export { name, addr, ordinal: Some(export.ordinal) }
+        })
+        .collect();
+
+    exports.sort_by_key(|export| export.ordinal);
+    exports
+}
+
+/// Name of a DLL without its extension, e.g. `KERNEL32` for `KERNEL32.dll`.
+fn module_name(dll: &str) -> &str {
+    match dll.len().checked_sub(".dll".len()) {
+        Some(split) if dll.is_char_boundary(split) && dll[split..].eq_ignore_ascii_case(".dll") => {
+            &dll[..split]
+        }
+        _ => dll,
+    }
+}
+
 /// Common ELF dwarf section names I've found so far.
 const DWARF_SECTIONS: [&str; 20] = [
     ".debug_abbrev",
@@ -148,7 +245,7 @@ fn parse_sections<'data, Pe: ImageNtHeaders>(obj: &'data PeFile<'data, Pe>) -> V
     let section_headers = nt_headers.sections(data, offset).unwrap();
 
     for (header, section) in section_headers.iter().zip(obj.sections()) {
-        let (name, bytes, start, end) = crate::parse_section_generics(&section);
+        let (name, bytes, compressed, start, end) = crate::parse_section_generics(&section);
 
         let characteristics = header.characteristics.get(LE);
         let (mut kind, ident) = (SectionKind::Raw, "UNKNOWN");
@@ -175,7 +272,7 @@ fn parse_sections<'data, Pe: ImageNtHeaders>(obj: &'data PeFile<'data, Pe>) -> V
             bytes,
             start,
             end
-        ));
+        ).with_compression(compressed));
     }
 
     sections
@@ -0,0 +1,351 @@
+//! WebAssembly modules, which aren't supported by `object`.
+
+use std::fmt;
+use crate::{Export, Import, RawSymbol};
+use processor_shared::{AddressMap, Addressed, PhysAddr, Section, SectionKind};
+use object::SymbolKind;
+
+/// Bytes every module starts with.
+pub const MAGIC: &[u8] = b"\0asm";
+
+/// Failed to parse a module, most likely because it's truncated or not a module at all.
+#[derive(Debug, Clone, Copy)]
+pub struct Error(&'static str);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+pub struct WasmDebugInfo<'data> {
+    /// Parsed sections, addressed by their offset in the file.
+    pub sections: Vec<Section>,
+    /// Functions named by the name section, exports and imports.
+    pub syms: AddressMap<RawSymbol<'data>>,
+    /// Address of every function by index. Imported functions are located at their entry in
+    /// the import section.
+    pub functions: Vec<PhysAddr>,
+    /// Where the bodies of functions defined by the module start, sorted.
+    pub bodies: Vec<PhysAddr>,
+    /// Functions, tables, memories and globals taken from the environment.
+    pub imports: Vec<Import>,
+    /// Functions, tables, memories and globals made available to the environment.
+    pub exports: Vec<Export>,
+    /// Function that runs once the module is instantiated.
+    pub entrypoint: Option<PhysAddr>,
+}
+
+/// Cursor over the bytes of a module, or of one of its sections.
+struct Reader<'data> {
+    data: &'data [u8],
+    pos: usize,
+    /// Offset of `data` in the file.
+    base: usize,
+}
+
+impl<'data> Reader<'data> {
+    /// Offset of the cursor in the file.
+    fn offset(&self) -> usize {
+        self.base + self.pos
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        let byte = *self.data.get(self.pos).ok_or(Error("Unexpected end of module"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn uleb(&mut self) -> Result<u64, Error> {
+        let mut value = 0;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.u8()?;
+            if shift >= 64 {
+                return Err(Error("Integer is too large"));
+            }
+
+            value |= ((byte & 0x7f) as u64) << shift;
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        u32::try_from(self.uleb()?).map_err(|_| Error("Integer is too large"))
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'data [u8], Error> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos.saturating_add(len))
+            .ok_or(Error("Unexpected end of module"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn name(&mut self) -> Result<&'data str, Error> {
+        let len = self.u32()? as usize;
+        std::str::from_utf8(self.bytes(len)?).map_err(|_| Error("Name isn't valid UTF-8"))
+    }
+
+    /// Skips over the limits of a table or memory.
+    fn limits(&mut self) -> Result<(), Error> {
+        let flags = self.u8()?;
+        self.uleb()?;
+        if flags & 1 != 0 {
+            self.uleb()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'data> WasmDebugInfo<'data> {
+    pub fn parse(binary: &'data [u8]) -> Result<Self, Error> {
+        if !binary.starts_with(MAGIC) {
+            return Err(Error("Missing magic number"));
+        }
+
+        if binary.get(4..8) != Some(&[1, 0, 0, 0]) {
+            return Err(Error("Unsupported version"));
+        }
+
+        let mut this = Self {
+            sections: Vec::new(),
+            syms: AddressMap::default(),
+            functions: Vec::new(),
+            bodies: Vec::new(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+            entrypoint: None,
+        };
+
+        // names of functions by index, in order of preference
+        let mut imported: Vec<(&'data str, &'data str)> = Vec::new();
+        let mut exported: Vec<(u32, &'data str)> = Vec::new();
+        let mut named: Vec<(u32, &'data str)> = Vec::new();
+        let mut sizes = Vec::new();
+        let mut exports = Vec::new();
+        let mut start = None;
+
+        let mut reader = Reader { data: binary, pos: 8, base: 0 };
+        while reader.pos < binary.len() {
+            let id = reader.u8()?;
+            let len = reader.u32()? as usize;
+            let payload = reader.bytes(len)?;
+            let offset = reader.pos - len;
+            let mut section = Reader { data: payload, pos: 0, base: offset };
+
+            let (name, ident, kind) = match id {
+                0 => {
+                    let name = section.name()?;
+                    let kind = if name.starts_with(".debug") {
+                        SectionKind::Debug
+                    } else {
+                        SectionKind::Unloaded
+                    };
+
+                    if name == "name" {
+                        parse_names(&mut section, &mut named)?;
+                    }
+
+                    (name, "CUSTOM", kind)
+                }
+                1 => ("type", "TYPE", SectionKind::Raw),
+                2 => {
+                    for _ in 0..section.u32()? {
+                        let entry = section.offset();
+                        let module = section.name()?;
+                        let name = section.name()?;
+
+                        match section.u8()? {
+                            // function
+                            0x00 => {
+                                section.u32()?;
+                                this.functions.push(entry);
+                                imported.push((module, name));
+                            }
+                            // table
+                            0x01 => {
+                                section.u8()?;
+                                section.limits()?;
+                            }
+                            // memory
+                            0x02 => section.limits()?,
+                            // global
+                            0x03 => {
+                                section.bytes(2)?;
+                            }
+                            // tag
+                            0x04 => {
+                                section.u8()?;
+                                section.u32()?;
+                            }
+                            _ => return Err(Error("Unknown kind of import")),
+                        }
+
+                        this.imports.push(Import {
+                            module: module.to_string(),
+                            name: name.to_string(),
+                            version: None,
+                        });
+                    }
+
+                    ("import", "IMPORT", SectionKind::Raw)
+                }
+                3 => ("function", "FUNCTION", SectionKind::Raw),
+                4 => ("table", "TABLE", SectionKind::Raw),
+                5 => ("memory", "MEMORY", SectionKind::Raw),
+                6 => ("global", "GLOBAL", SectionKind::Raw),
+                7 => {
+                    for _ in 0..section.u32()? {
+                        let name = section.name()?;
+                        let kind = section.u8()?;
+                        let idx = section.u32()?;
+                        if kind == 0x00 {
+                            exported.push((idx, name));
+                        }
+                        exports.push((name, kind, idx));
+                    }
+
+                    ("export", "EXPORT", SectionKind::Raw)
+                }
+                8 => {
+                    start = Some(section.u32()?);
+                    ("start", "START", SectionKind::Raw)
+                }
+                9 => ("element", "ELEMENT", SectionKind::Raw),
+                10 => {
+                    let count = section.u32()?;
+
+                    // the code starts after the number of functions
+                    let code = section.pos;
+                    for _ in 0..count {
+                        let body = section.offset();
+                        let size = section.u32()? as usize;
+                        section.bytes(size)?;
+
+                        this.functions.push(body);
+                        this.bodies.push(body);
+                        sizes.push(section.offset() - body);
+                    }
+
+                    // The file is memory mapped so the bytes are of lifetime &'static [u8].
+                    let bytes: &'static [u8] = unsafe { std::mem::transmute(&payload[code..]) };
+                    this.sections.push(Section::new(
+                        "code".to_string(),
+                        "CODE",
+                        SectionKind::Code,
+                        bytes,
+                        offset + code,
+                        offset + len,
+                    ));
+                    continue;
+                }
+                11 => ("data", "DATA", SectionKind::Raw),
+                12 => ("datacount", "DATACOUNT", SectionKind::Raw),
+                13 => ("tag", "TAG", SectionKind::Raw),
+                _ => ("unknown", "UNKNOWN", SectionKind::Raw),
+            };
+
+            // The file is memory mapped so the bytes are of lifetime &'static [u8].
+            let bytes: &'static [u8] = unsafe { std::mem::transmute(payload) };
+            this.sections.push(Section::new(
+                name.to_string(),
+                ident,
+                kind,
+                bytes,
+                offset,
+                offset + len,
+            ));
+        }
+
+        let function = |idx: u32| this.functions.get(idx as usize).copied();
+
+        this.exports = exports
+            .into_iter()
+            .map(|(name, kind, idx)| Export {
+                name: name.to_string(),
+                addr: if kind == 0x00 { function(idx) } else { None },
+                ordinal: None,
+            })
+            .collect();
+
+        this.entrypoint = start.and_then(function);
+
+        // the name section takes precedence over exports, which take precedence over imports
+        let mut names = vec![None; this.functions.len()];
+        for (idx, (module, name)) in imported.into_iter().enumerate() {
+            names[idx] = Some((name, Some(module)));
+        }
+
+        // a function that's exported more than once is named after it's first export
+        for (idx, name) in exported.into_iter().rev().chain(named) {
+            if let Some(slot) = names.get_mut(idx as usize) {
+                let module = slot.and_then(|(_, module)| module);
+                *slot = Some((name, module));
+            }
+        }
+
+        let imported_len = this.functions.len() - this.bodies.len();
+        for (idx, name) in names.into_iter().enumerate() {
+            if let Some((name, module)) = name {
+                // imports don't have a body
+                let size = idx.checked_sub(imported_len).map_or(0, |idx| sizes[idx]);
+
+                this.syms.push(Addressed {
+                    addr: this.functions[idx],
+                    item: RawSymbol {
+                        name,
+                        module,
+                        size,
+                        plt: false,
+                        kind: SymbolKind::Text,
+                        global: false,
+                    },
+                });
+            }
+        }
+
+        Ok(this)
+    }
+}
+
+/// Parses the function names of the name section.
+fn parse_names<'data>(
+    reader: &mut Reader<'data>,
+    named: &mut Vec<(u32, &'data str)>,
+) -> Result<(), Error> {
+    while reader.pos < reader.data.len() {
+        let id = reader.u8()?;
+        let len = reader.u32()? as usize;
+        let subsection_end = reader.pos + len;
+
+        // other subsections name things like locals and labels
+        if id == 1 {
+            for _ in 0..reader.u32()? {
+                named.push((reader.u32()?, reader.name()?));
+            }
+        }
+
+        reader.pos = subsection_end;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WasmDebugInfo;
+
+    #[test]
+    fn truncated_code_section() {
+        // an empty code section, whose count of functions would be read from the byte after it
+        let binary = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00];
+        assert!(WasmDebugInfo::parse(&binary).is_err());
+    }
+}
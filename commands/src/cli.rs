@@ -1,4 +1,7 @@
+use processor::{DisassemblyMode, ParseOptions, RawBinary, ARCHITECTURES};
+use std::iter::Peekable;
 use std::path::PathBuf;
+use tokenizing::{FormatterConfig, Radix, RegisterNaming};
 
 macro_rules! exit {
     ($code:expr => $($arg:tt)*) => {{
@@ -7,21 +10,91 @@ macro_rules! exit {
     }};
 }
 
-const HELP: &str = "OVERVIEW: Debugger/Decompilation tool
+const USAGE: &str = "OVERVIEW: Debugger/Decompilation tool
 
 USAGE: bite [options] <OBJECT>
 
 OPTIONS:
   -H, --help          Print usage information
-  -L, --libs          Print linked shared libraries 
+  -L, --libs          Print linked shared libraries, their versions and search paths
   -N, --names         Print all symbols exposed by object
-  -S, --simplify      Replace common types with shortened paths
+  -S, --simplify      Shorten symbols using the built-in and configured rules
   -D, --disassemble   Path to object you're disassembling
   -T, --tracing       Trace all syscalls performed
   -C, --config        Path to config used for disassembling
-  -B, --debug         Enable extra debug information";
+  -B, --debug         Enable extra debug information
+      --thumb         Decode 32-bit ARM code as thumb
+      --arm           Decode 32-bit ARM code as arm
+      --no-bytes      Hide the raw bytes of instructions
+      --hex           Show immediates in hexadecimal
+      --decimal       Show immediates in decimal
+      --numeric-regs  Name registers by their number instead of their ABI name
+      --uppercase     Show mnemonics in uppercase
+      --recursive     Only decode code reachable from the entrypoint and functions
+      --libs-recursive
+                      Resolve dependencies and their own dependencies with --libs
+      --text          Print the disassembly to stdout instead of opening the GUI
+      --lines         Show the source file and line instructions are compiled from
+      --source        Show the text of source lines too, implies --lines
+      --start <ADDR>  Address the printed disassembly starts at
+      --symbol <NAME> Only print the disassembly of the function named NAME
+      --raw <ARCH>    Disassemble the object as raw code of the given architecture
+      --base <ADDR>   Address raw code is loaded at, zero by default
+      --arch <ARCH>   Object of a universal binary that's opened, the first one by default
+      --debuginfod    Download debug info that isn't installed from the servers in
+                      DEBUGINFOD_URLS, which needs curl
+      --cfg <SYMBOL>  Print the control-flow graph of a function in DOT format
+      --sections      Print the section table of the object
+      --segments      Print the program headers and entry point of the object
+      --human         Print sizes in K, M and G with --sections
+      --strings       Print the strings of the object's data sections
+      --hexdump <SECTION|START..END>
+                      Print the bytes of a section or an address range in hexadecimal
+      --imports       Print the symbols the object imports, grouped by library
+      --exports       Print the symbols the object exports, with their ordinals
+      --relocs        Print the relocations of each relocation section of the object
+      --info          Print the machine, build-id and other facts that identify the object
+      --notes         Print the notes of each note section of the object
+      --backtrace <CORE>
+                      Print the backtrace of each thread of a core dump of the object
+      --min-length <N>
+                      Only print strings of at least N characters with --strings, 4 by default
+      --sort <KEY>    Order symbols printed with --names by address, size or name
+      --defined       Only print symbols the object defines with --names
+      --undefined     Only print symbols the object takes from elsewhere with --names
+      --symtab        Only print symbols of the static symbol table with --names
+      --dynsym        Only print symbols of the dynamic symbol table with --names
+      --filter <TEXT> Only print names containing TEXT with --names
+      --regex         Treat the text given to --filter as a regular expression
+      --mangled       Match --filter against names before they're demangled
+      --output <PATH> Write the control-flow graph to a file instead of stdout";
+
+/// Usage information, followed by the architectures accepted by `--raw` and `--arch`.
+struct Help;
+
+impl std::fmt::Display for Help {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(USAGE)?;
+        f.write_str("\n\nARCHITECTURES:")?;
+
+        let mut width = 0;
+        for (idx, (name, ..)) in ARCHITECTURES.iter().enumerate() {
+            if idx == 0 || width + name.len() + 2 > 90 {
+                f.write_str("\n ")?;
+                width = 1;
+            }
+
+            let sep = if idx + 1 == ARCHITECTURES.len() { "" } else { "," };
+            write!(f, " {name}{sep}")?;
+            width += name.len() + 1 + sep.len();
+        }
+
+        Ok(())
+    }
+}
+
+const HELP: Help = Help;
 
-const ABBRV: &[&str] = &["-H", "-L", "-S", "-D", "-C", "-T", "-B"];
 const NAMES: &[&str] = &[
     "--help",
     "--libs",
@@ -31,8 +104,60 @@ const NAMES: &[&str] = &[
     "--tracing",
     "--config",
     "--debug",
+    "--thumb",
+    "--arm",
+    "--no-bytes",
+    "--hex",
+    "--decimal",
+    "--numeric-regs",
+    "--uppercase",
+    "--recursive",
+    "--libs-recursive",
+    "--text",
+    "--lines",
+    "--source",
+    "--start",
+    "--symbol",
+    "--raw",
+    "--base",
+    "--arch",
+    "--debuginfod",
+    "--cfg",
+    "--output",
+    "--sections",
+    "--segments",
+    "--human",
+    "--strings",
+    "--hexdump",
+    "--imports",
+    "--exports",
+    "--relocs",
+    "--info",
+    "--notes",
+    "--backtrace",
+    "--min-length",
+    "--sort",
+    "--defined",
+    "--undefined",
+    "--symtab",
+    "--dynsym",
+    "--filter",
+    "--regex",
+    "--mangled",
 ];
 
+/// Bytes printed by `--hexdump`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dump {
+    Section(String),
+
+    /// Addresses from the first up to, but not including, the second.
+    Range(usize, usize),
+}
+
+/// Orders accepted by `--sort`.
+const SORT_KEYS: &[&str] = &["address", "size", "name"];
+
 #[derive(Default, Debug, Clone)]
 pub struct Cli {
     /// Print shared libraries the object is linked against.
@@ -53,6 +178,115 @@ pub struct Cli {
     /// Show egui debug overlay.
     pub debug: bool,
 
+    /// Override for whether 32-bit ARM code is `thumb`, instead of detecting it.
+    pub thumb: Option<bool>,
+
+    /// Hide the byte column of the assembly listing.
+    pub hide_bytes: bool,
+
+    /// Override for whether immediates are shown in hexadecimal, instead of the architecture's
+    /// usual base.
+    pub hex: Option<bool>,
+
+    /// Name registers by their number instead of their ABI name.
+    pub numeric_registers: bool,
+
+    /// Show mnemonics in uppercase.
+    pub uppercase: bool,
+
+    /// Disassemble by recursive descent instead of linearly.
+    pub recursive: bool,
+
+    /// Resolve the libraries printed with `--libs` and their own dependencies.
+    pub libs_recursive: bool,
+
+    /// Print the disassembly as text instead of showing it in the GUI.
+    pub text: bool,
+
+    /// Annotate the printed disassembly with the source lines of the line table.
+    pub lines: bool,
+
+    /// Include the text of source lines in the annotations.
+    pub source: bool,
+
+    /// Address the printed disassembly starts at.
+    pub start: Option<usize>,
+
+    /// Function whose disassembly is printed, instead of every code section.
+    pub symbol: Option<String>,
+
+    /// Architecture of an object that's disassembled as raw code.
+    pub raw: Option<String>,
+
+    /// Address raw code is loaded at.
+    pub base: Option<usize>,
+
+    /// Architecture of the object that's opened from a universal binary.
+    pub arch: Option<String>,
+
+    /// Download missing debug info with debuginfod.
+    pub debuginfod: bool,
+
+    /// Function whose control-flow graph is printed.
+    pub cfg: Option<String>,
+
+    /// File the control-flow graph is written to, instead of stdout.
+    pub output: Option<PathBuf>,
+
+    /// Print the section table.
+    pub sections: bool,
+
+    /// Print the program headers and where the object is loaded.
+    pub segments: bool,
+
+    /// Print section sizes in units instead of hexadecimal bytes.
+    pub human: bool,
+
+    /// Print the strings found in data sections.
+    pub strings: bool,
+
+    /// Number of characters printed strings have at least.
+    pub min_length: Option<usize>,
+
+    /// Bytes that are printed in hexadecimal.
+    pub hexdump: Option<Dump>,
+
+    /// Print the imported symbols by the library they're taken from.
+    pub imports: bool,
+
+    /// Print the exported symbols.
+    pub exports: bool,
+
+    /// Print the relocation sections.
+    pub relocs: bool,
+
+    /// Print what the object is built for and its build-id.
+    pub info: bool,
+
+    /// Print the note sections.
+    pub notes: bool,
+
+    /// Core dump of the object whose threads' backtraces are printed.
+    pub backtrace: Option<PathBuf>,
+
+    /// Order of the printed symbol table, by address if unset.
+    pub sort: Option<String>,
+
+    /// Only print defined symbols if set, or only undefined symbols if unset.
+    pub defined: Option<bool>,
+
+    /// Only print symbols of the dynamic symbol table if set, or of the static one if unset.
+    pub dynamic: Option<bool>,
+
+    /// Text or regular expression the printed symbols' names have to match.
+    pub filter: Option<String>,
+
+    /// Whether the filter is a regular expression.
+    pub regex: bool,
+
+    /// Match the filter against mangled names.
+    pub mangled: bool,
+
     /// Path to symbol being disassembled.
     pub path: Option<PathBuf>,
 
@@ -62,8 +296,13 @@ pub struct Cli {
 
 impl Cli {
     pub fn parse() -> Self {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    /// Parse the arguments that come after the name of the executable.
+    fn parse_from(args: impl IntoIterator<Item = String>) -> Self {
         let mut cli = Cli::default();
-        let mut args = std::env::args().skip(1).peekable();
+        let mut args = args.into_iter().peekable();
 
         while let Some(arg) = args.next() {
             match arg.as_str() {
@@ -72,32 +311,189 @@ impl Cli {
                 "-N" | "--names" => {
                     cli.names = true;
 
-                    if let Some(path) = args.next().as_deref() {
-                        if !NAMES.contains(&path) && !ABBRV.contains(&path) {
-                            cli.path = Some(PathBuf::from(path));
-                        }
-                    }
+                    cli.take_path(&mut args);
                 }
                 "-L" | "--libs" => {
                     cli.libs = true;
 
-                    if let Some(path) = args.next().as_deref() {
-                        if !NAMES.contains(&path) && !ABBRV.contains(&path) {
-                            cli.path = Some(PathBuf::from(path));
-                        }
-                    }
+                    cli.take_path(&mut args);
                 }
                 "-D" | "--disassemble" => {
                     cli.disassemble = true;
 
-                    if let Some(path) = args.next().as_deref() {
-                        if !NAMES.contains(&path) && !ABBRV.contains(&path) {
-                            cli.path = Some(PathBuf::from(path));
-                        }
-                    }
+                    cli.take_path(&mut args);
                 }
+                "-C" | "--config" => match args.next() {
+                    Some(path) => cli.config = Some(PathBuf::from(path)),
+                    None => exit!(1 => "Missing path after '--config'."),
+                },
                 "-T" | "--tracing" => cli.tracing = true,
                 "-B" | "--debug" => cli.debug = true,
+                "--thumb" => cli.thumb = Some(true),
+                "--arm" => cli.thumb = Some(false),
+                "--no-bytes" => cli.hide_bytes = true,
+                "--hex" => cli.hex = Some(true),
+                "--decimal" => cli.hex = Some(false),
+                "--numeric-regs" => cli.numeric_registers = true,
+                "--uppercase" => cli.uppercase = true,
+                "--debuginfod" => cli.debuginfod = true,
+                "--recursive" => cli.recursive = true,
+                "--libs-recursive" => cli.libs_recursive = true,
+                "--text" => cli.text = true,
+                "--lines" => cli.lines = true,
+                "--source" => {
+                    cli.lines = true;
+                    cli.source = true;
+                }
+                "--start" => cli.start = Some(parse_addr(args.next(), "--start")),
+                "--symbol" => match args.next() {
+                    Some(symbol) => cli.symbol = Some(symbol),
+                    None => exit!(1 => "Missing symbol after '--symbol'."),
+                },
+                "--base" => cli.base = Some(parse_addr(args.next(), "--base")),
+                "--cfg" => {
+                    match args.next() {
+                        Some(symbol) => cli.cfg = Some(symbol),
+                        None => exit!(1 => "Missing symbol after '--cfg'."),
+                    }
+
+                    cli.take_path(&mut args);
+                }
+                "--sections" => {
+                    cli.sections = true;
+
+                    cli.take_path(&mut args);
+                }
+                "--segments" => {
+                    cli.segments = true;
+
+                    cli.take_path(&mut args);
+                }
+                "--human" => cli.human = true,
+                "--strings" => {
+                    cli.strings = true;
+
+                    cli.take_path(&mut args);
+                }
+                "--hexdump" => {
+                    let target = match args.next() {
+                        Some(target) => target,
+                        None => exit!(1 => "Missing section or range after '--hexdump'."),
+                    };
+
+                    cli.hexdump = Some(match target.split_once("..") {
+                        Some((start, end)) => {
+                            let start = parse_addr(Some(start.to_string()), "--hexdump");
+                            let end = parse_addr(Some(end.to_string()), "--hexdump");
+                            if start >= end {
+                                exit!(1 => "Invalid range '{target}'.");
+                            }
+
+                            Dump::Range(start, end)
+                        }
+                        None => Dump::Section(target),
+                    });
+
+                    cli.take_path(&mut args);
+                }
+                "--imports" => {
+                    cli.imports = true;
+
+                    cli.take_path(&mut args);
+                }
+                "--exports" => {
+                    cli.exports = true;
+
+                    cli.take_path(&mut args);
+                }
+                "--relocs" => {
+                    cli.relocs = true;
+
+                    cli.take_path(&mut args);
+                }
+                "--info" => {
+                    cli.info = true;
+
+                    cli.take_path(&mut args);
+                }
+                "--notes" => {
+                    cli.notes = true;
+
+                    cli.take_path(&mut args);
+                }
+                "--backtrace" => {
+                    match args.next() {
+                        Some(core) => cli.backtrace = Some(PathBuf::from(core)),
+                        None => exit!(1 => "Missing path to a core dump after '--backtrace'."),
+                    }
+
+                    cli.take_path(&mut args);
+                }
+                "--min-length" => {
+                    let len = match args.next() {
+                        Some(len) => len,
+                        None => exit!(1 => "Missing length after '--min-length'."),
+                    };
+
+                    match len.parse() {
+                        Ok(len) if len > 0 => cli.min_length = Some(len),
+                        _ => exit!(1 => "Invalid length '{len}'."),
+                    }
+                }
+                "--defined" => cli.defined = Some(true),
+                "--undefined" => cli.defined = Some(false),
+                "--symtab" => cli.dynamic = Some(false),
+                "--dynsym" => cli.dynamic = Some(true),
+                "--filter" => match args.next() {
+                    Some(filter) => cli.filter = Some(filter),
+                    None => exit!(1 => "Missing pattern after '--filter'."),
+                },
+                "--regex" => cli.regex = true,
+                "--mangled" => cli.mangled = true,
+                "--sort" => {
+                    let key = match args.next() {
+                        Some(key) => key,
+                        None => exit!(1 => "Missing order after '--sort'."),
+                    };
+
+                    if !SORT_KEYS.contains(&key.as_str()) {
+                        exit!(1 => "Unknown order '{key}'.\n\n{HELP}");
+                    }
+
+                    cli.sort = Some(key);
+                }
+                "--output" => match args.next() {
+                    Some(path) => cli.output = Some(PathBuf::from(path)),
+                    None => exit!(1 => "Missing path after '--output'."),
+                },
+                "--raw" => {
+                    let arch = match args.next() {
+                        Some(arch) => arch,
+                        None => exit!(1 => "Missing architecture after '--raw'."),
+                    };
+
+                    if processor::architecture(&arch).is_none() {
+                        exit!(1 => "Unknown architecture '{arch}'.\n\n{HELP}");
+                    }
+
+                    cli.raw = Some(arch);
+                }
+                "--arch" => {
+                    let arch = match args.next() {
+                        Some(arch) => arch,
+                        None => exit!(1 => "Missing architecture after '--arch'."),
+                    };
+
+                    if processor::architecture(&arch).is_none() {
+                        exit!(1 => "Unknown architecture '{arch}'.\n\n{HELP}");
+                    }
+
+                    cli.arch = Some(arch);
+                }
+                // the object can also be given on its own, after the flags
+                path if !path.starts_with('-') && cli.path.is_none() => {
+                    cli.path = Some(PathBuf::from(path));
+                }
                 unknown => {
                     let mut distance = u32::MAX;
                     let mut best_guess = "";
@@ -123,8 +519,69 @@ impl Cli {
         cli
     }
 
+    /// Options for parsing objects, as given on the command line.
+    pub fn parse_options(&self) -> ParseOptions {
+        // views of an object's headers and tables don't show its code
+        let decoded = self.disassemble || self.cfg.is_some() || self.backtrace.is_some();
+
+        ParseOptions {
+            thumb: self.thumb,
+            mode: match self.recursive {
+                true => DisassemblyMode::Recursive,
+                false => DisassemblyMode::Linear,
+            },
+            style: FormatterConfig {
+                immediate_radix: self.hex.map(|hex| match hex {
+                    true => Radix::Hex,
+                    false => Radix::Decimal,
+                }),
+                register_naming: match self.numeric_registers {
+                    true => RegisterNaming::Numeric,
+                    false => RegisterNaming::Abi,
+                },
+                uppercase_mnemonics: self.uppercase,
+                ..FormatterConfig::default()
+            },
+            raw: self.raw.as_ref().and_then(|arch| RawBinary::new(arch, self.base.unwrap_or(0))),
+            arch: self
+                .arch
+                .as_ref()
+                .and_then(|arch| processor::architecture(arch))
+                .map(|(arch, _)| arch),
+            simplify: self.simplify,
+            debuginfod: self.debuginfod,
+            metadata_only: !decoded,
+            ..ParseOptions::default()
+        }
+    }
+
+    /// Take the path of the object given after an action, unless the next argument is another
+    /// flag, which is left for the next iteration.
+    fn take_path<I: Iterator<Item = String>>(&mut self, args: &mut Peekable<I>) {
+        if args.peek().is_some_and(|arg| !arg.starts_with('-')) {
+            self.path = args.next().map(PathBuf::from);
+        }
+    }
+
     fn validate_args(&mut self) {
-        if self.disassemble || self.libs || self.names {
+        let actions = [
+            self.disassemble,
+            self.libs,
+            self.names,
+            self.cfg.is_some(),
+            self.sections,
+            self.segments,
+            self.strings,
+            self.hexdump.is_some(),
+            self.imports,
+            self.exports,
+            self.relocs,
+            self.info,
+            self.notes,
+            self.backtrace.is_some(),
+        ];
+
+        if actions.contains(&true) {
             if self.path.is_none() {
                 exit!(1 => "Missing path to an object.");
             }
@@ -138,8 +595,124 @@ impl Cli {
             exit!(1 => "Invalid combination of arguements.\n\n{HELP}");
         }
 
-        if self.disassemble as usize + self.libs as usize + self.names as usize > 1 {
+        if actions.iter().filter(|&&action| action).count() > 1 {
             exit!(1 => "Invalid combination of arguements.\n\n{HELP}");
         }
+
+        if self.base.is_some() && self.raw.is_none() {
+            exit!(1 => "'--base' can only be given for raw code.\n\n{HELP}");
+        }
+
+        if self.arch.is_some() && self.raw.is_some() {
+            exit!(1 => "'--arch' can't be given for raw code.\n\n{HELP}");
+        }
+
+        if self.symbol.is_some() && !self.disassemble {
+            exit!(1 => "'--symbol' can only be given when disassembling.\n\n{HELP}");
+        }
+
+        if self.symbol.is_some() && self.start.is_some() {
+            exit!(1 => "'--symbol' and '--start' can't be given together.\n\n{HELP}");
+        }
+
+        if self.output.is_some() && self.cfg.is_none() {
+            exit!(1 => "'--output' can only be given with '--cfg'.\n\n{HELP}");
+        }
+
+        if self.human && !self.sections {
+            exit!(1 => "'--human' can only be given with '--sections'.\n\n{HELP}");
+        }
+
+        if self.libs_recursive && !self.libs {
+            exit!(1 => "'--libs-recursive' can only be given with '--libs'.\n\n{HELP}");
+        }
+
+        if self.min_length.is_some() && !self.strings {
+            exit!(1 => "'--min-length' can only be given with '--strings'.\n\n{HELP}");
+        }
+
+        if self.sort.is_some() && !self.names {
+            exit!(1 => "'--sort' can only be given with '--names'.\n\n{HELP}");
+        }
+
+        if self.defined.is_some() && !self.names {
+            exit!(1 => "'--defined' and '--undefined' can only be given with '--names'.\n\n{HELP}");
+        }
+
+        if self.dynamic.is_some() && !self.names {
+            exit!(1 => "'--symtab' and '--dynsym' can only be given with '--names'.\n\n{HELP}");
+        }
+
+        if self.filter.is_some() && !self.names {
+            exit!(1 => "'--filter' can only be given with '--names'.\n\n{HELP}");
+        }
+
+        if (self.regex || self.mangled) && self.filter.is_none() {
+            exit!(1 => "'--regex' and '--mangled' can only be given with '--filter'.\n\n{HELP}");
+        }
+    }
+}
+
+/// Address given after `arg`, either in decimal or in hexadecimal with a leading '0x'.
+fn parse_addr(addr: Option<String>, arg: &str) -> usize {
+    let addr = match addr {
+        Some(addr) => addr,
+        None => exit!(1 => "Missing address after '{arg}'."),
+    };
+
+    let parsed = match addr.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => addr.parse(),
+    };
+
+    match parsed {
+        Ok(parsed) => parsed,
+        Err(..) => exit!(1 => "Invalid address '{addr}'."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::parse_from(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn flag_after_action() {
+        let cli = parse(&["--sections", "--human", "a.out"]);
+        assert!(cli.sections && cli.human);
+        assert_eq!(cli.path, Some(PathBuf::from("a.out")));
+
+        let cli = parse(&["--libs", "--libs-recursive", "a.out"]);
+        assert!(cli.libs && cli.libs_recursive && !cli.recursive);
+        assert_eq!(cli.path, Some(PathBuf::from("a.out")));
+
+        let cli = parse(&["--names", "--simplify", "a.out"]);
+        assert!(cli.names && cli.simplify);
+        assert_eq!(cli.path, Some(PathBuf::from("a.out")));
+
+        let cli = parse(&["--cfg", "main", "--output", "main.dot", "a.out"]);
+        assert_eq!(cli.cfg.as_deref(), Some("main"));
+        assert_eq!(cli.output, Some(PathBuf::from("main.dot")));
+        assert_eq!(cli.path, Some(PathBuf::from("a.out")));
+    }
+
+    #[test]
+    fn path_after_action() {
+        let cli = parse(&["--backtrace", "core", "a.out", "--text"]);
+        assert_eq!(cli.backtrace, Some(PathBuf::from("core")));
+        assert_eq!(cli.path, Some(PathBuf::from("a.out")));
+        assert!(cli.text);
+    }
+
+    #[test]
+    fn raw_little_endian_powerpc() {
+        let cli = parse(&["--disassemble", "code.bin", "--raw", "powerpc64le"]);
+        assert_eq!(cli.raw.as_deref(), Some("powerpc64le"));
+
+        let raw = cli.parse_options().raw.unwrap();
+        assert_eq!(format!("{:?} {:?}", raw.arch, raw.endianness), "PowerPc64 Little");
     }
 }
@@ -2,7 +2,7 @@ mod cli;
 mod debug;
 mod gui;
 
-pub use cli::Cli;
+pub use cli::{Cli, Dump};
 pub use gui::{Command, Error as CommandError, HELP as CMD_HELP};
 use once_cell::sync::Lazy;
 
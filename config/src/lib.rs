@@ -1,16 +1,48 @@
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 
 pub static CONFIG: Lazy<Config> = Lazy::new(Config::parse);
 
 use egui::Color32;
+use regex::Regex;
 use serde::de::{self, Deserializer, Visitor};
 use serde::Deserialize;
 use std::fmt;
+use std::path::PathBuf;
+
+/// Config file that's read instead of the one in the data directory.
+static PATH: OnceCell<PathBuf> = OnceCell::new();
+
+/// Read the config from `path` instead of the data directory. Has no effect once [`CONFIG`]
+/// has been read.
+pub fn set_path(path: PathBuf) {
+    let _ = PATH.set(path);
+}
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
     #[serde(default = "defaults::colors")]
     pub colors: Colors,
+    #[serde(default = "defaults::simplify")]
+    pub simplify: Simplify,
+}
+
+/// How symbols are shortened when `--simplify` is set.
+#[derive(Debug, Deserialize)]
+pub struct Simplify {
+    /// Generic and template arguments nested deeper than this are elided.
+    #[serde(default = "defaults::max_depth")]
+    pub max_depth: usize,
+    /// Substitutions applied after the built-in ones.
+    #[serde(default)]
+    pub rules: Vec<SimplifyRule>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimplifyRule {
+    #[serde(deserialize_with = "regex")]
+    pub pattern: Regex,
+    /// Replacement of the matched text, where `$1` refers to the first capture group.
+    pub replacement: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -157,6 +189,14 @@ mod defaults {
         serde_yaml::from_str("").unwrap()
     }
 
+    pub fn simplify() -> super::Simplify {
+        serde_yaml::from_str("").unwrap()
+    }
+
+    pub fn max_depth() -> usize {
+        3
+    }
+
     pub fn anything() -> Color32 {
         Color32::from_rgb(0xc8, 0xc8, 0xc8)
     }
@@ -254,13 +294,14 @@ mod defaults {
 
 impl Config {
     pub fn parse() -> Self {
-        let path = match dirs::data_dir() {
-            Some(mut dir) => {
+        let path = match (PATH.get(), dirs::data_dir()) {
+            (Some(path), _) => path.clone(),
+            (None, Some(mut dir)) => {
                 dir.push("bite");
                 dir.push("config.yaml");
                 dir
             },
-            None => log::error!("You must have a data directory set."),
+            (None, None) => log::error!("You must have a data directory set."),
         };
 
         let raw = std::fs::read_to_string(path).unwrap_or_default();
@@ -292,3 +333,20 @@ fn color32<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color32, D::Err
 
     deserializer.deserialize_str(ColorParsing)
 }
+
+fn regex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+    struct RegexParsing;
+    impl<'de> Visitor<'de> for RegexParsing {
+        type Value = Regex;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("expected a regular expression")
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+            Regex::new(s).map_err(|err| E::custom(format!("{err}")))
+        }
+    }
+
+    deserializer.deserialize_str(RegexParsing)
+}
@@ -0,0 +1,207 @@
+//! Lookup of the debug info that stripped ELF binaries ship separately, like the `-dbgsym` and
+//! `-debuginfo` packages of distributions.
+
+use crate::{Addressed, RawSymbol};
+use object::{Object, ObjectSymbol, SymbolKind};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory the debug files of installed packages are stored in.
+const DEBUG_DIR: &str = "/usr/lib/debug";
+
+/// Table of the CRC-32 used by `.gnu_debuglink`, the one of zlib.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut idx = 0;
+    while idx < 256 {
+        let mut crc = idx as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb88320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[idx] = crc;
+        idx += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc = CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Contents of a file, mapped into memory.
+pub fn map(path: &Path) -> Option<memmap2::Mmap> {
+    let file = std::fs::File::open(path).ok()?;
+    unsafe { memmap2::Mmap::map(&file).ok() }
+}
+
+/// Whether the object at `path` has the build-id `build_id`.
+fn has_build_id(path: &Path, build_id: &[u8]) -> bool {
+    let mmap = match map(path) {
+        Some(mmap) => mmap,
+        None => return false,
+    };
+
+    match object::File::parse(&*mmap) {
+        Ok(obj) => matches!(obj.build_id(), Ok(Some(id)) if id == build_id),
+        Err(..) => false,
+    }
+}
+
+/// Whether the contents of the file at `path` have the checksum `crc`.
+fn has_crc(path: &Path, crc: u32) -> bool {
+    match map(path) {
+        Some(mmap) => crc32(&mmap) == crc,
+        None => false,
+    }
+}
+
+/// Directory `debuginfod` clients cache downloaded files in, shared with the likes of gdb.
+fn debuginfod_cache() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("DEBUGINFOD_CACHE_PATH") {
+        return Some(PathBuf::from(path));
+    }
+
+    dirs::cache_dir().map(|dir| dir.join("debuginfod_client"))
+}
+
+/// Download the debug file of `build_id` from the servers listed in `DEBUGINFOD_URLS`, unless
+/// it's cached already.
+fn debuginfod(build_id: &[u8]) -> Option<PathBuf> {
+    let urls = std::env::var("DEBUGINFOD_URLS").ok()?;
+    let build_id_hex = hex(build_id);
+    let cache = debuginfod_cache()?.join(&build_id_hex);
+    let cached = cache.join("debuginfo");
+    if has_build_id(&cached, build_id) {
+        return Some(cached);
+    }
+
+    std::fs::create_dir_all(&cache).ok()?;
+    let partial = cache.join(".debuginfo.partial");
+
+    // servers are separated by spaces and tried in order
+    for url in urls.split_whitespace() {
+        let url = format!("{}/buildid/{build_id_hex}/debuginfo", url.trim_end_matches('/'));
+
+        log::PROGRESS.set("Downloading debug info.", 1);
+        let status = Command::new("curl")
+            .args(["--fail", "--silent", "--location", "--output"])
+            .arg(&partial)
+            .arg(&url)
+            .status();
+        log::PROGRESS.step();
+
+        match status {
+            Ok(status) if status.success() && has_build_id(&partial, build_id) => {
+                std::fs::rename(&partial, &cached).ok()?;
+                return Some(cached);
+            }
+            Ok(..) => {}
+            Err(err) => {
+                log::complex!(
+                    w "[debuglink::debuginfod] ",
+                    y "Failed to run curl: ",
+                    y err.to_string(),
+                    y "."
+                );
+                break;
+            }
+        }
+    }
+
+    let _ = std::fs::remove_file(&partial);
+    None
+}
+
+/// Path of the separate debug file of the object at `path`.
+///
+/// The file is looked up by the object's build-id under `/usr/lib/debug/.build-id`, then by
+/// the name in its `.gnu_debuglink` section next to the object, in a `.debug` directory next
+/// to it and under `/usr/lib/debug`, like gdb does. Files that don't have the same build-id or
+/// checksum are skipped. Failing that and if `download` is set, it's downloaded from the
+/// servers in `DEBUGINFOD_URLS` by running `curl`.
+pub fn locate(obj: &object::File, path: &Path, download: bool) -> Option<PathBuf> {
+    let build_id = obj.build_id().ok().flatten().filter(|id| id.len() > 1);
+    if let Some(path) = build_id.and_then(by_build_id) {
+        return Some(path);
+    }
+
+    if let Some(path) = by_debuglink(obj, path) {
+        return Some(path);
+    }
+
+    match build_id {
+        Some(build_id) if download => debuginfod(build_id),
+        _ => None,
+    }
+}
+
+/// Debug file of `build_id` in the `.build-id` directory of installed debug files.
+fn by_build_id(build_id: &[u8]) -> Option<PathBuf> {
+    let hex = hex(build_id);
+    let path = Path::new(DEBUG_DIR)
+        .join(".build-id")
+        .join(&hex[..2])
+        .join(format!("{}.debug", &hex[2..]));
+
+    has_build_id(&path, build_id).then_some(path)
+}
+
+/// Debug file named by the `.gnu_debuglink` section of the object at `path`.
+fn by_debuglink(obj: &object::File, path: &Path) -> Option<PathBuf> {
+    let (name, crc) = obj.gnu_debuglink().ok().flatten()?;
+    let name = std::str::from_utf8(name).ok()?;
+    let path = path.canonicalize().ok()?;
+    let dir = path.parent()?;
+
+    let candidates = [
+        dir.join(name),
+        dir.join(".debug").join(name),
+        Path::new(DEBUG_DIR).join(dir.strip_prefix("/").unwrap_or(dir)).join(name),
+    ];
+
+    // the link may name the object itself if it was stripped in place
+    candidates.into_iter().find(|candidate| *candidate != path && has_crc(candidate, crc))
+}
+
+/// Symbols defined in the separate debug file `obj`.
+pub fn symbols<'data>(
+    obj: &object::File<'data>,
+) -> impl Iterator<Item = Addressed<RawSymbol<'data>>> + '_ {
+    // thread-local symbols are at offsets into the TLS template, not at addresses
+    let defined = |sym: &object::Symbol| !sym.is_undefined() && sym.kind() != SymbolKind::Tls;
+    obj.symbols().filter(defined).filter_map(|sym| {
+        let name = sym.name().ok().filter(|name| !binformat::is_mapping_symbol(name))?;
+        Some(Addressed {
+            addr: sym.address() as usize,
+            item: RawSymbol {
+                name,
+                module: None,
+                size: sym.size() as usize,
+                plt: false,
+                kind: sym.kind(),
+                global: sym.is_global(),
+            },
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::crc32;
+
+    #[test]
+    fn checksum() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    }
+}
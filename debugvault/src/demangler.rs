@@ -3,36 +3,67 @@
 use tokenizing::{Token, Color32};
 use config::CONFIG;
 
-pub fn parse(s: &str) -> TokenStream {
-    // symbols without leading underscores are accepted as
-    // dbghelp in windows strips them away
+/// Mangling scheme of a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    /// Windows msvc C/C++ symbols.
+    Msvc,
+    /// Gnu/llvm C++ symbols, and rust legacy symbols which are valid ones that end in a hash.
+    Itanium,
+    /// Rust symbols that match the v0 mangling scheme.
+    Rust,
+}
 
-    let s = s.strip_suffix("$got").unwrap_or(s);
-    let s = s.strip_suffix("$plt").unwrap_or(s);
-    let s = s.strip_suffix("$pltgot").unwrap_or(s);
+/// Suffixes of symbols that refer to an entry in the GOT or PLT.
+const SUFFIXES: [&str; 3] = ["$got", "$plt", "$pltgot"];
 
-    // parse rust symbols
-    if let Some(s) = crate::rust_legacy::parse(s) {
-        return s;
+/// Scheme a symbol is mangled by, picked by its prefix so symbols of mixed-language binaries
+/// can't be demangled by the wrong scheme.
+fn scheme(s: &str) -> Option<Scheme> {
+    // symbols without leading underscores are accepted as
+    // dbghelp in windows strips them away
+    let unprefixed = s.trim_start_matches('_');
+    if s.trim_start_matches('.').starts_with('?') {
+        Some(Scheme::Msvc)
+    } else if unprefixed.starts_with('Z') || s.starts_with("_GLOBAL_") {
+        Some(Scheme::Itanium)
+    } else if unprefixed.starts_with('R') {
+        Some(Scheme::Rust)
+    } else {
+        None
     }
+}
 
-    // parse gnu/llvm/C/C++ symbols
-    if let Some(s) = crate::itanium::parse(s) {
-        return s;
-    }
+/// Whether [`parse`] changes a symbol without simplifying it, as it's mangled or refers to an
+/// entry in the GOT or PLT.
+pub fn is_mangled(s: &str) -> bool {
+    SUFFIXES.iter().any(|suffix| s.ends_with(suffix)) || scheme(s).is_some()
+}
 
-    // parse rust symbols that match the v0 mangling scheme
-    if let Some(s) = crate::rust::parse(s) {
-        return s;
+/// Demangle a symbol. If `simplify` is set, the hash of rust legacy symbols is stripped and
+/// the symbol is shortened by the rules in [`crate::simplify`].
+pub fn parse(s: &str, simplify: bool) -> TokenStream {
+    let mut s = s;
+    for suffix in SUFFIXES {
+        s = s.strip_suffix(suffix).unwrap_or(s);
     }
 
-    // parse windows msvc C/C++ symbols
-    if let Some(s) = crate::msvc::parse(s) {
-        return s;
-    }
+    let demangled = match scheme(s) {
+        Some(Scheme::Msvc) => crate::msvc::parse(s),
+        Some(Scheme::Itanium) => {
+            crate::rust_legacy::parse(s, simplify).or_else(|| crate::itanium::parse(s))
+        }
+        Some(Scheme::Rust) => crate::rust::parse(s),
+        None => None,
+    };
 
     // return the original mangled symbol on failure
-    TokenStream::simple(s)
+    let mut demangled = demangled.unwrap_or_else(|| TokenStream::simple(s));
+    if simplify {
+        crate::simplify::apply(&mut demangled);
+    }
+
+    demangled
 }
 
 #[derive(Debug)]
@@ -82,6 +113,82 @@ impl TokenStream {
     pub fn tokens(&self) -> &[Token] {
         self.tokens.as_slice()
     }
+
+    /// Replace runs of tokens that spell out `pattern` with a single token.
+    pub(crate) fn replace(&mut self, pattern: &str, with: &'static str) {
+        let mut idx = 0;
+        while idx < self.tokens.len() {
+            let mut rest = pattern;
+            let mut end = idx;
+            while let Some(token) = self.tokens.get(end).filter(|_| !rest.is_empty()) {
+                match rest.strip_prefix(&token.text[..]) {
+                    Some(stripped) => rest = stripped,
+                    None => break,
+                }
+                end += 1;
+            }
+
+            if rest.is_empty() {
+                let color = self.tokens[idx].color;
+                self.tokens.splice(idx..end, [Token::from_str(with, color)]);
+            }
+
+            idx += 1;
+        }
+    }
+
+    /// Replace the tokens nested in more than `max_depth` angle brackets with `...`.
+    pub(crate) fn elide(&mut self, max_depth: usize) {
+        let mut depth = 0usize;
+        let mut idx = 0;
+        while idx < self.tokens.len() {
+            match self.bracket(idx) {
+                Some('<') => depth += 1,
+                Some(_) => depth = depth.saturating_sub(1),
+                None => {}
+            }
+
+            idx += 1;
+            if depth <= max_depth || self.bracket(idx - 1) != Some('<') {
+                continue;
+            }
+
+            // find the matching closing bracket
+            let mut nested = 1;
+            let mut end = idx;
+            while end < self.tokens.len() {
+                match self.bracket(end) {
+                    Some('<') => nested += 1,
+                    Some(_) if nested == 1 => break,
+                    Some(_) => nested -= 1,
+                    None => {}
+                }
+                end += 1;
+            }
+
+            // unbalanced brackets are left as is
+            if end == self.tokens.len() {
+                break;
+            }
+
+            let color = self.tokens[idx].color;
+            self.tokens.splice(idx..end, [Token::from_str("...", color)]);
+        }
+    }
+
+    /// Whether the token at `idx` is an opening or closing angle bracket, excluding the
+    /// comparison operators.
+    fn bracket(&self, idx: usize) -> Option<char> {
+        if idx > 0 && self.tokens[idx - 1].text.ends_with("operator") {
+            return None;
+        }
+
+        match &self.tokens[idx].text[..] {
+            "<" => Some('<'),
+            ">" => Some('>'),
+            _ => None,
+        }
+    }
 }
 
 impl PartialEq for TokenStream {
@@ -89,3 +196,103 @@ impl PartialEq for TokenStream {
         self.inner == other.inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{demangle, is_mangled};
+
+    #[test]
+    fn mangled() {
+        assert!(is_mangled("_ZN4core3fmt5write17h1c6c5e0a0a5e1c84E"));
+        assert!(is_mangled("?foo@@YAXXZ"));
+        assert!(is_mangled("puts$plt"));
+        assert!(!is_mangled("puts"));
+        assert!(!is_mangled("__libc_start_main"));
+    }
+
+    #[test]
+    fn rust() {
+        let legacy = "_ZN4core3fmt9Formatter9write_str17h1c6c5e0a0a5e1c84E";
+        assert_eq!(demangle(legacy, false), "core::fmt::Formatter::write_str::h1c6c5e0a0a5e1c84");
+        assert_eq!(demangle(legacy, true), "core::fmt::Formatter::write_str");
+
+        let v0 = "_RNvNtCs9ltgdHTiPiY_4core3fmt5write";
+        assert_eq!(demangle(v0, false), "core::fmt::write");
+        assert_eq!(demangle(v0, true), "core::fmt::write");
+    }
+
+    /// Mangled C++ symbols along with how they're shown, with and without `simplify`.
+    static ITANIUM: &[(&str, &str, &str)] = &[
+        (
+            "_ZNSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEE6appendEPKc",
+            "std::__cxx11::basic_string<char, std::char_traits<char>, std::allocator<char>>::append",
+            "std::string::append",
+        ),
+        (
+            "_ZNSt7__cxx1112basic_stringIwSt11char_traitsIwESaIwEE4swapERS4_",
+            "std::__cxx11::basic_string<wchar_t, std::char_traits<wchar_t>, std::allocator<wchar_t>>::swap",
+            "std::wstring::swap",
+        ),
+        (
+            "_ZNSt6vectorINSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEEESaIS5_EE9push_backERKS5_",
+            "std::vector<std::__cxx11::basic_string<char, std::char_traits<char>, std::allocator<char>>, std::allocator<std::__cxx11::basic_string<char, std::char_traits<char>, std::allocator<char>>>>::push_back",
+            "std::vector<std::string, std::allocator<std::string>>::push_back",
+        ),
+        (
+            "_ZNSt7__cxx114listIiSaIiEE5clearEv",
+            "std::__cxx11::list<int, std::allocator<int>>::clear",
+            "std::list<int, std::allocator<int>>::clear",
+        ),
+        (
+            "_ZNSt13basic_ostreamIcSt11char_traitsIcEE5flushEv",
+            "std::basic_ostream<char, std::char_traits<char>>::flush",
+            "std::ostream::flush",
+        ),
+        ("_ZNKSs4sizeEv", "std::string::size", "std::string::size"),
+        ("_ZNSolsEi", "std::ostream::operator<<", "std::ostream::operator<<"),
+        ("_ZN5space3fooEii", "space::foo", "space::foo"),
+        ("_ZN3FooC2Ev", "Foo::Foo", "Foo::Foo"),
+        ("_ZN3FooD0Ev", "Foo::~Foo", "Foo::~Foo"),
+        ("_ZN3FooplERKS_", "Foo::operator+", "Foo::operator+"),
+        ("_ZTV3Foo", "{{vtable(Foo)}}", "{{vtable(Foo)}}"),
+        ("_ZTI3Foo", "typeinfo for Foo", "typeinfo for Foo"),
+        ("_ZGVZ4mainE1x", "guard variable for main::x", "guard variable for main::x"),
+        (
+            "_ZZ4mainENKUlvE_clEv",
+            "main::{{lambda()#1}}::operator()",
+            "main::{{lambda()#1}}::operator()",
+        ),
+        (
+            "_ZSt4moveIRiEONSt16remove_referenceIT_E4typeEOS2_",
+            "std::remove_reference<int&>::type&& std::move<int&>",
+            "std::remove_reference<int&>::type&& std::move<int&>",
+        ),
+        ("_ZN1AIiE1fIcEEvT_", "void A<int>::f<char>", "void A<int>::f<char>"),
+        // invalid symbols are shown as is
+        ("_Z", "_Z", "_Z"),
+        ("_ZN3foo", "_ZN3foo", "_ZN3foo"),
+    ];
+
+    #[test]
+    fn itanium() {
+        for (mangled, demangled, simplified) in ITANIUM {
+            assert_eq!(demangle(mangled, false), *demangled);
+            assert_eq!(demangle(mangled, true), *simplified);
+        }
+    }
+
+    #[test]
+    fn mixed() {
+        assert_eq!(demangle("?x@@YAXMH@Z", false), "void __cdecl x(float, int)");
+        assert_eq!(demangle("_ZN5space3fooEii", false), "space::foo");
+        assert_eq!(demangle("_RNvC4bite6decode", false), "bite::decode");
+        assert_eq!(demangle("_GLOBAL__sub_I_main.cpp", false), "_GLOBAL__sub_I_main.cpp");
+    }
+
+    #[test]
+    fn not_mangled() {
+        assert_eq!(demangle("main", false), "main");
+        assert_eq!(demangle("_ZN4core", false), "_ZN4core");
+        assert_eq!(demangle("memcpy$plt", true), "memcpy");
+    }
+}
@@ -39,6 +39,9 @@ impl From<object::Error> for Error {
 pub struct Dwarf {
     /// Mapping from addresses starting at the header base to source files.
     pub file_attrs: AddressMap<FileAttr>,
+
+    /// Mapping from the start of functions to their size in bytes.
+    pub functions: AddressMap<usize>,
 }
 
 impl Dwarf {
@@ -59,8 +62,9 @@ impl Dwarf {
         let mut dwarf = gimli::Dwarf::load(&mut load_section)?;
         dwarf.populate_abbreviations_cache(gimli::AbbreviationsCacheStrategy::All);
         let file_attrs = dump_line(&dwarf)?;
+        let functions = dump_functions(&dwarf)?;
 
-        Ok(Dwarf { file_attrs })
+        Ok(Dwarf { file_attrs, functions })
     }
 
     pub fn load(path: &Path) -> Result<Self> {
@@ -72,6 +76,7 @@ impl Dwarf {
 
     pub fn merge(&mut self, other: Self) {
         self.file_attrs.extend(other.file_attrs);
+        self.functions.extend(other.functions);
     }
 }
 
@@ -356,6 +361,72 @@ fn dump_line<R: Reader>(dwarf: &gimli::Dwarf<R>) -> Result<AddressMap<FileAttr>>
     Ok(file_attrs)
 }
 
+/// Ranges of the subprograms that have a `DW_AT_low_pc` and `DW_AT_high_pc`. Functions split
+/// across multiple ranges, as in `DW_AT_ranges`, are left out.
+fn dump_functions<R: Reader>(dwarf: &gimli::Dwarf<R>) -> Result<AddressMap<usize>> {
+    let mut iter = dwarf.units();
+    let mut functions = AddressMap::default();
+
+    while let Some(header) = iter.next()? {
+        let unit = match dwarf.unit(header) {
+            Ok(unit) => unit,
+            Err(err) => {
+                log::complex!(
+                    w "[dwarf::dump_functions] ",
+                    y "Failed to parse unit root entry for dump_functions: ",
+                    y format!("{err:?}."),
+                );
+                continue;
+            }
+        };
+        if let Err(err) = dump_subprograms(&unit, dwarf, &mut functions) {
+            log::complex!(
+                w "[dwarf::dump_subprograms] ",
+                y "Failed to dump subprograms: ",
+                y format!("{err:?}."),
+            );
+        }
+    }
+
+    Ok(functions)
+}
+
+fn dump_subprograms<R: Reader>(
+    unit: &gimli::Unit<R>,
+    dwarf: &gimli::Dwarf<R>,
+    functions: &mut AddressMap<usize>,
+) -> Result<()> {
+    let mut entries = unit.entries();
+    while let Some((_, entry)) = entries.next_dfs()? {
+        if entry.tag() != gimli::DW_TAG_subprogram {
+            continue;
+        }
+
+        let low_pc = match entry.attr_value(gimli::DW_AT_low_pc)? {
+            Some(value) => dwarf.attr_address(unit, value)?,
+            None => None,
+        };
+
+        // since DWARF 4 the high pc is usually given as an offset from the low pc
+        let high_pc = match (low_pc, entry.attr_value(gimli::DW_AT_high_pc)?) {
+            (Some(low_pc), Some(gimli::AttributeValue::Udata(size))) => Some(low_pc + size),
+            (_, Some(value)) => dwarf.attr_address(unit, value)?,
+            (_, None) => None,
+        };
+
+        if let (Some(low_pc), Some(high_pc)) = (low_pc, high_pc) {
+            if high_pc > low_pc {
+                functions.push(Addressed {
+                    addr: low_pc as usize,
+                    item: (high_pc - low_pc) as usize,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn dump_line_program<R: Reader>(
     id: u64,
     path_cache: &InternMap<u64, Path>,
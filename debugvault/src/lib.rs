@@ -1,15 +1,19 @@
 use binformat::RawSymbol;
 use common::*;
+use config::CONFIG;
 use demangler::TokenStream;
 use dwarf::Dwarf;
+use object::SymbolKind;
 use processor_shared::{AddressMap, Addressed};
 use radix_trie::{Trie, TrieCommon};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 use std::{fmt, process::Command};
 use tokenizing::Token;
 
 mod common;
+mod debuglink;
 mod demangler;
 mod dwarf;
 mod error;
@@ -17,8 +21,12 @@ mod intern;
 mod itanium;
 mod msvc;
 mod pdb;
+mod resolve;
 mod rust;
 mod rust_legacy;
+mod simplify;
+
+pub use resolve::ResolvedSymbol;
 
 pub enum Error {
     Object(object::Error),
@@ -38,8 +46,13 @@ pub struct FileAttr {
 pub struct Symbol {
     name: TokenStream,
     name_as_str: ArcStr,
+    full: ArcStr,
+    mangled: ArcStr,
     module: Option<String>,
     is_intrinsics: bool,
+    size: usize,
+    kind: SymbolKind,
+    global: bool,
 }
 
 fn is_name_an_intrinsic(name: &str) -> bool {
@@ -67,8 +80,13 @@ impl Default for Symbol {
         Self {
             name: TokenStream::new(""),
             name_as_str: ArcStr::new(""),
+            full: ArcStr::new(""),
+            mangled: ArcStr::new(""),
             module: None,
             is_intrinsics: false,
+            size: 0,
+            kind: SymbolKind::Unknown,
+            global: false,
         }
     }
 }
@@ -88,6 +106,16 @@ impl Symbol {
         &self.name_as_str
     }
 
+    /// Demangled name without any of the shortening of `--simplify`.
+    pub fn full_name(&self) -> &str {
+        &self.full
+    }
+
+    /// Name as found in the object, before it's demangled.
+    pub fn mangled(&self) -> &str {
+        &self.mangled
+    }
+
     /// Is the function a unnamed compiler generated artifact.
     pub fn intrinsic(&self) -> bool {
         self.is_intrinsics
@@ -96,6 +124,21 @@ impl Symbol {
     pub fn imported(&self) -> bool {
         self.module.is_some()
     }
+
+    /// Number of bytes the symbol spans, zero if unknown.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// What the symbol refers to, `Section` for the name given to the start of a section.
+    pub fn kind(&self) -> SymbolKind {
+        self.kind
+    }
+
+    /// Whether the symbol is visible outside of the object that defines it.
+    pub fn global(&self) -> bool {
+        self.global
+    }
 }
 
 impl fmt::Debug for Symbol {
@@ -111,6 +154,17 @@ impl PartialEq for Symbol {
     }
 }
 
+/// Demangled name of a symbol, or the name itself if it isn't mangled.
+pub fn demangle(name: &str, simplify: bool) -> String {
+    let demangled = demangler::parse(name, simplify);
+    String::from_iter(demangled.tokens().iter().map(|t| &t.text[..]))
+}
+
+/// Whether [`demangle`] changes a name without simplifying it.
+pub fn is_mangled(name: &str) -> bool {
+    demangler::is_mangled(name)
+}
+
 #[derive(Default, Debug)]
 pub struct Index {
     /// Mapping from addresses starting at the header base to functions.
@@ -126,6 +180,13 @@ pub struct Index {
 
     /// Number of named compiler artifacts.
     named_len: usize,
+
+    /// Sections of the object, for addresses that no symbol spans.
+    /// The addresses are sorted.
+    sections: AddressMap<resolve::SectionRange>,
+
+    /// End of the furthest reaching symbol up to each symbol of `syms`.
+    reach: Vec<usize>,
 }
 
 impl Index {
@@ -133,17 +194,72 @@ impl Index {
         obj: &object::File<'data>,
         path: &Path,
         mut syms: AddressMap<RawSymbol<'data>>,
+        simplify: bool,
+        debuginfod: bool,
     ) -> Result<Self, Error> {
         let mut this = Self::default();
+        let mut syms: AddressMap<RawSymbol> = syms;
 
-        let dwarf = match obj {
+        let mut dwarf = match obj {
             object::File::MachO32(_) => macho_dwarf(obj, path)?,
             object::File::MachO64(_) => macho_dwarf(obj, path)?,
             _ => dwarf::Dwarf::parse(obj)?,
         };
 
+        // stripped ELF binaries may ship their symbols and DWARF in a separate file
+        let debug_path = match obj {
+            object::File::Elf32(_) | object::File::Elf64(_) => {
+                debuglink::locate(obj, path, debuginfod)
+            }
+            _ => None,
+        };
+        let debug_mmap = debug_path.as_deref().and_then(debuglink::map);
+        if let (Some(debug_path), Some(debug_mmap)) = (&debug_path, &debug_mmap) {
+            // a corrupt debug file only costs the object its debug info
+            let debug_obj = object::File::parse(&**debug_mmap)
+                .map_err(Error::Object)
+                .and_then(|debug_obj| Ok((Dwarf::parse(&debug_obj)?, debug_obj)));
+
+            match debug_obj {
+                Ok((debug_dwarf, debug_obj)) => {
+                    dwarf.merge(debug_dwarf);
+
+                    let known: HashSet<(usize, &str)> =
+                        syms.iter().map(|sym| (sym.addr, sym.item.name)).collect();
+                    let debug_syms: Vec<_> = debuglink::symbols(&debug_obj)
+                        .filter(|sym| !known.contains(&(sym.addr, sym.item.name)))
+                        .collect();
+                    syms.mapping.extend(debug_syms);
+
+                    log::complex!(
+                        w "[index::parse] loaded debug info from ",
+                        b debug_path.display().to_string(),
+                        w "."
+                    );
+                }
+                Err(err) => {
+                    log::complex!(
+                        w "[index::parse] ",
+                        y "Failed to load debug info from ",
+                        b debug_path.display().to_string(),
+                        y format!(": {err}.")
+                    );
+                }
+            }
+        }
+
         this.file_attrs.extend(dwarf.file_attrs);
 
+        // symbols without a size, like those of hand-written assembly or some object formats,
+        // span the subprogram starting at the same address
+        let sizes: HashMap<usize, usize> =
+            dwarf.functions.iter().map(|func| (func.addr, func.item)).collect();
+        for symbol in syms.iter_mut().filter(|symbol| symbol.item.size == 0) {
+            if let Some(&size) = sizes.get(&symbol.addr) {
+                symbol.item.size = size;
+            }
+        }
+
         let mut pdb = None;
         if let Some(parsed_pdb) = pdb::PDB::parse(obj) {
             pdb = Some(parsed_pdb?);
@@ -156,17 +272,48 @@ impl Index {
             syms.extend(std::mem::take(&mut pdb.syms));
         }
 
+        this.insert_syms(syms, simplify);
+        Ok(this)
+    }
+
+    /// Index of symbols that weren't parsed from an object, e.g. the names of a wasm module.
+    pub fn from_syms(syms: AddressMap<RawSymbol>, simplify: bool) -> Self {
+        let mut this = Self::default();
+        this.insert_syms(syms, simplify);
+        this
+    }
+
+    /// Demangles `syms`, stripping hashes and shortening common types if `simplify` is set.
+    fn insert_syms(&mut self, syms: AddressMap<RawSymbol>, simplify: bool) {
         log::PROGRESS.set("Parsing symbols.", syms.len());
-        parallel_compute(syms.mapping, &mut this.syms, |Addressed { addr, item }| {
-            let demangled = demangler::parse(item.name);
+        parallel_compute(syms.mapping, &mut self.syms, |Addressed { addr, item }| {
+            let mut demangled = demangler::parse(item.name, simplify);
+            if item.plt {
+                demangled.push("@plt", CONFIG.colors.asm.component);
+            }
+
             let is_intrinsics = is_name_an_intrinsic(item.name);
             let name_as_str = String::from_iter(demangled.tokens().iter().map(|t| &t.text[..]));
             let name_as_str = ArcStr::new(&name_as_str);
+            let full = if simplify {
+                let mut full = demangle(item.name, false);
+                if item.plt {
+                    full += "@plt";
+                }
+                ArcStr::new(&full)
+            } else {
+                name_as_str.clone()
+            };
             let symbol = Symbol {
                 name_as_str,
+                full,
                 name: demangled,
+                mangled: ArcStr::new(item.name),
                 module: item.module.map(|x| x.to_string()),
                 is_intrinsics,
+                size: item.size,
+                kind: item.kind,
+                global: item.global,
             };
 
             log::PROGRESS.step();
@@ -176,21 +323,29 @@ impl Index {
             }
         });
 
-        this.sort_and_validate();
-        this.build_prefix_tree();
+        self.sort_and_validate();
+        self.build_prefix_tree();
 
         log::complex!(
             w "[index::parse] found ",
-            g this.syms.len().to_string(),
+            g self.syms.len().to_string(),
             w " functions."
         );
-
-        Ok(this)
     }
 
     fn sort_and_validate(&mut self) {
-        // Only keep one symbol per address.
-        self.syms.dedup_by_key(|func| func.addr);
+        // Only keep one symbol per address, preferring global functions over their aliases.
+        self.syms.dedup_by(|next, kept| {
+            if next.addr != kept.addr {
+                return false;
+            }
+
+            if resolve::rank(&next.item) < resolve::rank(&kept.item) {
+                std::mem::swap(next, kept);
+            }
+
+            true
+        });
 
         // Only keep valid symbols.
         self.syms.retain(|Addressed { addr, item: func }| {
@@ -209,10 +364,17 @@ impl Index {
         self.named_len = self.syms.iter().filter(|func| !func.item.intrinsic()).count();
 
         // Keep functions sorted so it can be binary searched.
-        self.syms.sort_unstable();
+        self.sort_syms();
+
+        // Keep file attrs sorted so it can be binary searched. The sort is stable as rows at the
+        // same address have to stay in the order of the line table.
+        self.file_attrs.sort();
+    }
 
-        // Keep file attrs sorted so it can be binary searched.
-        self.file_attrs.sort_unstable();
+    /// Sort symbols by address, with the symbol that's preferred among aliases first.
+    fn sort_syms(&mut self) {
+        self.syms.sort_by_key(|func| (func.addr, resolve::rank(&func.item)));
+        self.compute_reach();
     }
 
     fn build_prefix_tree(&mut self) {
@@ -240,29 +402,120 @@ impl Index {
         }
     }
 
+    /// Symbol starting at `addr`, the one preferred by [`Index::resolve`] if it has aliases.
     pub fn get_sym_by_addr(&self, addr: usize) -> Option<Arc<Symbol>> {
-        match self.syms.search(addr) {
-            Ok(idx) => Some(self.syms[idx].item.clone()),
-            Err(..) => None,
+        let idx = self.syms.partition_point(|func| func.addr < addr);
+        let func = self.syms.get(idx).filter(|func| func.addr == addr)?;
+        Some(func.item.clone())
+    }
+
+    /// Symbol whose size is known to span over `addr` and the offset into it. Unlike
+    /// [`Index::resolve`], symbols without a size only span their own address.
+    pub fn get_sym_containing(&self, addr: usize) -> Option<(Arc<Symbol>, usize)> {
+        if let Some(symbol) = self.get_sym_by_addr(addr) {
+            return Some((symbol, 0));
+        }
+
+        let idx = self.syms.partition_point(|func| func.addr < addr).checked_sub(1)?;
+        let start = self.syms[idx].addr;
+        let symbol = self.get_sym_by_addr(start)?;
+        if addr < start + symbol.size {
+            return Some((symbol, addr - start));
+        }
+
+        None
+    }
+
+    /// Appends ` <symbol+offset>` for addresses that fall within a symbol or section, without
+    /// being the start of it. See [`Index::resolve`].
+    pub fn tokenize_offset(&self, stream: &mut tokenizing::TokenStream, addr: usize) {
+        if let Some(resolved) = self.resolve(addr) {
+            if resolved.offset == 0 {
+                return;
+            }
+
+            stream.push(" ", tokenizing::colors::WHITE);
+            stream.push("<", CONFIG.colors.asm.label);
+            resolved.tokenize(stream);
+            stream.push(">", CONFIG.colors.asm.label);
         }
     }
 
+    /// Address of the function named `name`, either demangled, in full or as found in the object.
     pub fn get_func_by_name(&self, name: &str) -> Option<usize> {
-        self.syms.iter().find(|func| func.item.as_str() == name).map(|func| func.addr)
+        self.syms
+            .iter()
+            .find(|func| {
+                let item = &func.item;
+                item.as_str() == name || item.full_name() == name || item.mangled() == name
+            })
+            .map(|func| func.addr)
+    }
+
+    /// Address of a label of the form `symbol` or `symbol+offset`.
+    pub fn get_addr_by_label(&self, label: &str) -> Option<usize> {
+        if let Some(addr) = self.get_func_by_name(label) {
+            return Some(addr);
+        }
+
+        let (name, offset) = label.rsplit_once("+0x")?;
+        let offset = usize::from_str_radix(offset, 16).ok()?;
+        self.get_func_by_name(name).map(|addr| addr + offset)
+    }
+
+    /// Names addresses the symbol table leaves unnamed, like functions that are only known by
+    /// being called. Names that are `local` to a function aren't counted as functions.
+    /// Addresses that already have a symbol keep it.
+    pub fn insert_generated(&mut self, names: Vec<(usize, String, bool)>) {
+        let names: Vec<_> =
+            names.into_iter().filter(|(addr, ..)| self.syms.search(*addr).is_err()).collect();
+
+        for (addr, name, local) in names {
+            let symbol = Arc::new(Symbol {
+                name: TokenStream::simple(&name),
+                name_as_str: ArcStr::new(&name),
+                full: ArcStr::new(&name),
+                mangled: ArcStr::new(&name),
+                module: None,
+                is_intrinsics: local,
+                size: 0,
+                kind: if local { SymbolKind::Label } else { SymbolKind::Text },
+                global: false,
+            });
+
+            self.trie.insert(symbol.name_as_str.clone(), Arc::clone(&symbol));
+            self.syms.push(Addressed { addr, item: symbol });
+        }
+
+        self.named_len = self.syms.iter().filter(|func| !func.item.intrinsic()).count();
+        self.sort_syms();
     }
 
     /// Only used for tests.
     #[doc(hidden)]
     pub fn insert_func(&mut self, addr: usize, name: &str) {
+        self.insert_sized_func(addr, 0, name)
+    }
+
+    /// Only used for tests.
+    #[doc(hidden)]
+    pub fn insert_sized_func(&mut self, addr: usize, size: usize, name: &str) {
         self.syms.push(Addressed {
             addr,
             item: Arc::new(Symbol {
                 name: TokenStream::simple(name),
                 name_as_str: ArcStr::new(name),
+                full: ArcStr::new(name),
+                mangled: ArcStr::new(name),
                 module: None,
                 is_intrinsics: false,
+                size,
+                kind: SymbolKind::Text,
+                global: true,
             }),
-        })
+        });
+
+        self.sort_syms();
     }
 
     pub fn prefix_match_func(&self, prefix: &str) -> Vec<String> {
@@ -1,7 +1,7 @@
 use crate::intern::InternMap;
 use crate::{AddressMap, Addressed, FileAttr, RawSymbol};
 use crossbeam_queue::SegQueue;
-use object::Object;
+use object::{Object, SymbolKind};
 use pdb::{FallibleIterator, SymbolData};
 use std::borrow::Cow;
 use std::path::Path;
@@ -156,7 +156,14 @@ fn parse_pdb<'data>(
 
                 this.syms.push(Addressed {
                     addr: base_addr + addr,
-                    item: RawSymbol { name, module: None },
+                    item: RawSymbol {
+                        name,
+                        module: None,
+                        size: 0,
+                        plt: false,
+                        kind: SymbolKind::Text,
+                        global: true,
+                    },
                 });
             }
             Ok(_) => {
@@ -203,7 +210,14 @@ fn parse_pdb_module<'data>(
 
                 syms.push(Addressed {
                     addr: base_addr + addr,
-                    item: RawSymbol { name, module: module_name },
+                    item: RawSymbol {
+                        name,
+                        module: module_name,
+                        size: 0,
+                        plt: false,
+                        kind: SymbolKind::Text,
+                        global: true,
+                    },
                 });
             }
             Ok(SymbolData::Procedure(proc)) => {
@@ -0,0 +1,272 @@
+//! Lookup of what an address falls into, for naming the addresses of operands, jump tables and
+//! the frames of a backtrace.
+
+use crate::{Index, Symbol};
+use config::CONFIG;
+use object::SymbolKind;
+use processor_shared::Addressed;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Symbol an address falls into, or the section if no symbol spans it.
+#[derive(Debug, Clone)]
+pub struct ResolvedSymbol {
+    /// Symbol spanning the address, `None` if the address is only known by its section.
+    pub symbol: Option<Arc<Symbol>>,
+    /// Name of the symbol or section.
+    pub name: Arc<str>,
+    /// Offset of the address into the symbol or section.
+    pub offset: usize,
+    /// Number of bytes the symbol spans, up to the next symbol if its size isn't known.
+    pub size: usize,
+}
+
+impl ResolvedSymbol {
+    /// Appends `symbol+offset`, or just the symbol if the address is the start of it.
+    pub fn tokenize(&self, stream: &mut tokenizing::TokenStream) {
+        match &self.symbol {
+            Some(symbol) => stream.inner.extend_from_slice(symbol.name()),
+            None => stream.push_owned(self.name.to_string(), CONFIG.colors.asm.section),
+        }
+
+        if self.offset != 0 {
+            stream.push_owned(format!("+{:#x}", self.offset), CONFIG.colors.asm.label);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SectionRange {
+    pub end: usize,
+    pub name: Arc<str>,
+}
+
+/// Preference among symbols at the same address, lowest first: global functions, other
+/// functions, anything else and lastly the names given to the start of sections.
+pub(crate) fn rank(symbol: &Symbol) -> u8 {
+    match symbol.kind {
+        SymbolKind::Text if symbol.global => 0,
+        SymbolKind::Text => 1,
+        SymbolKind::Section => 3,
+        _ => 2,
+    }
+}
+
+impl Index {
+    /// Let addresses that no symbol spans resolve to the section they're in, given as the
+    /// range of addresses and name of each section.
+    pub fn insert_sections<I>(&mut self, sections: I)
+    where
+        I: IntoIterator<Item = (Range<usize>, String)>,
+    {
+        for (range, name) in sections.into_iter().filter(|(range, _)| !range.is_empty()) {
+            self.sections.push(Addressed {
+                addr: range.start,
+                item: SectionRange { end: range.end, name: Arc::from(name) },
+            });
+        }
+
+        self.sections.sort_unstable();
+        self.compute_reach();
+    }
+
+    /// Symbol spanning `addr` and the offset into it, or the section containing `addr` if
+    /// there's no such symbol.
+    ///
+    /// Of overlapping symbols, the one with the tightest range around `addr` is taken, the one
+    /// starting closest to it if they're as tight, and of aliases the one
+    /// [`Index::get_sym_by_addr`] prefers. Symbols without a size span up to the next symbol,
+    /// within their section if the object's sections are known.
+    pub fn resolve(&self, addr: usize) -> Option<ResolvedSymbol> {
+        let mut tightest: Option<(usize, usize)> = None;
+        let mut end = self.syms.partition_point(|sym| sym.addr <= addr);
+        while end > 0 {
+            // none of the symbols starting before here reach `addr`
+            if self.reach.get(end - 1).is_some_and(|&reach| reach <= addr) {
+                break;
+            }
+
+            let start = self.syms[end - 1].addr;
+            let first = self.syms.partition_point(|sym| sym.addr < start);
+            for idx in first..end {
+                if self.syms[idx].item.kind == SymbolKind::Section {
+                    continue;
+                }
+
+                let size = self.extent(idx) - start;
+                let tighter = tightest.is_none_or(|(_, tightest)| size < tightest);
+                if addr < start + size && tighter {
+                    tightest = Some((idx, size));
+                }
+            }
+
+            end = first;
+        }
+
+        if let Some((idx, size)) = tightest {
+            let Addressed { addr: start, item: symbol } = &self.syms[idx];
+            return Some(ResolvedSymbol {
+                symbol: Some(Arc::clone(symbol)),
+                name: Arc::clone(&*symbol.name_as_str),
+                offset: addr - start,
+                size,
+            });
+        }
+
+        let section = self.section_containing(addr)?;
+        Some(ResolvedSymbol {
+            symbol: None,
+            name: Arc::clone(&section.item.name),
+            offset: addr - section.addr,
+            size: section.item.end - section.addr,
+        })
+    }
+
+    fn section_containing(&self, addr: usize) -> Option<&Addressed<SectionRange>> {
+        let idx = self.sections.partition_point(|section| section.addr <= addr).checked_sub(1)?;
+        let section = &self.sections[idx];
+        (addr < section.item.end).then_some(section)
+    }
+
+    /// Address after the last byte the symbol at `idx` spans.
+    fn extent(&self, idx: usize) -> usize {
+        let Addressed { addr, item: symbol } = &self.syms[idx];
+        if symbol.size != 0 {
+            return addr + symbol.size;
+        }
+
+        let next = self.syms.partition_point(|sym| sym.addr <= *addr);
+        let next = self.syms.get(next).map(|sym| sym.addr);
+
+        // without sections there's no telling where the last symbol ends
+        if self.sections.is_empty() {
+            return next.unwrap_or(addr + 1);
+        }
+
+        match self.section_containing(*addr) {
+            Some(section) => next.map_or(section.item.end, |next| next.min(section.item.end)),
+            None => addr + 1,
+        }
+    }
+
+    /// Update how far the symbols up to each symbol reach, after symbols or sections changed.
+    pub(crate) fn compute_reach(&mut self) {
+        let mut reach = 0;
+        let mut reaches = Vec::with_capacity(self.syms.len());
+        for idx in 0..self.syms.len() {
+            if self.syms[idx].item.kind != SymbolKind::Section {
+                reach = reach.max(self.extent(idx));
+            }
+
+            reaches.push(reach);
+        }
+
+        self.reach = reaches;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Index;
+    use binformat::RawSymbol;
+    use object::SymbolKind;
+    use processor_shared::{AddressMap, Addressed};
+
+    fn index_of(syms: &[(usize, usize, &'static str, SymbolKind, bool)]) -> Index {
+        let mut map = AddressMap::default();
+        for &(addr, size, name, kind, global) in syms {
+            map.push(Addressed {
+                addr,
+                item: RawSymbol { name, module: None, size, plt: false, kind, global },
+            });
+        }
+
+        Index::from_syms(map, false)
+    }
+
+    fn resolve(index: &Index, addr: usize) -> Option<(String, usize, usize)> {
+        let resolved = index.resolve(addr)?;
+        Some((resolved.name.to_string(), resolved.offset, resolved.size))
+    }
+
+    #[test]
+    fn overlapping() {
+        let index = index_of(&[
+            (0x1000, 0x100, "outer", SymbolKind::Text, true),
+            (0x1010, 0x10, "inner", SymbolKind::Text, false),
+        ]);
+
+        assert_eq!(resolve(&index, 0x1018), Some(("inner".into(), 0x8, 0x10)));
+        assert_eq!(resolve(&index, 0x1080), Some(("outer".into(), 0x80, 0x100)));
+        assert_eq!(resolve(&index, 0x1100), None);
+        assert_eq!(resolve(&index, 0xfff), None);
+
+        // the tightest symbol is taken over the one that starts closest
+        let index = index_of(&[
+            (0x1000, 0x20, "narrow", SymbolKind::Text, true),
+            (0x1010, 0x100, "wide", SymbolKind::Text, true),
+        ]);
+
+        assert_eq!(resolve(&index, 0x1018), Some(("narrow".into(), 0x18, 0x20)));
+        assert_eq!(resolve(&index, 0x1020), Some(("wide".into(), 0x10, 0x100)));
+    }
+
+    #[test]
+    fn unsized() {
+        let mut index = index_of(&[
+            (0x1000, 0, "a", SymbolKind::Text, true),
+            (0x1040, 0, "b", SymbolKind::Text, true),
+            (0x3000, 0, "c", SymbolKind::Text, true),
+        ]);
+
+        // without sections the last symbol only spans its own address
+        assert_eq!(resolve(&index, 0x1010), Some(("a".into(), 0x10, 0x40)));
+        assert_eq!(resolve(&index, 0x2000), Some(("b".into(), 0xfc0, 0x1fc0)));
+        assert_eq!(resolve(&index, 0x3000), Some(("c".into(), 0, 1)));
+        assert_eq!(resolve(&index, 0x3001), None);
+
+        // symbols don't span past the end of their section
+        index.insert_sections([(0x1000..0x2000, ".text".to_string())]);
+        assert_eq!(resolve(&index, 0x1050), Some(("b".into(), 0x10, 0xfc0)));
+        assert_eq!(resolve(&index, 0x2000), None);
+    }
+
+    #[test]
+    fn aliases() {
+        let index = index_of(&[
+            (0x1000, 0x10, "local", SymbolKind::Text, false),
+            (0x1000, 0x10, "object", SymbolKind::Data, true),
+            (0x1000, 0x10, "global", SymbolKind::Text, true),
+            (0x2000, 0x10, "_start", SymbolKind::Text, true),
+            (0x3000, 0x10, "other", SymbolKind::Text, true),
+            (0x2000, 0, "entry", SymbolKind::Text, false),
+        ]);
+
+        assert_eq!(resolve(&index, 0x1004), Some(("global".into(), 0x4, 0x10)));
+        assert_eq!(index.get_sym_by_addr(0x1000).unwrap().as_str(), "global");
+        assert_eq!(resolve(&index, 0x2004), Some(("_start".into(), 0x4, 0x10)));
+        assert_eq!(index.get_sym_by_addr(0x2000).unwrap().as_str(), "_start");
+
+        // aliases that aren't next to each other in the symbol table can still be looked up
+        assert_eq!(index.get_func_by_name("entry"), Some(0x2000));
+    }
+
+    #[test]
+    fn sections() {
+        let mut index = index_of(&[
+            (0x1000, 0x10, "f", SymbolKind::Text, true),
+            (0x1000, 0, ".text", SymbolKind::Section, false),
+            (0x5000, 0, ".data", SymbolKind::Section, false),
+        ]);
+        index.insert_sections([
+            (0x1000..0x2000, ".text".to_string()),
+            (0x5000..0x5100, ".data".to_string()),
+        ]);
+
+        assert_eq!(resolve(&index, 0x1004), Some(("f".into(), 0x4, 0x10)));
+        assert_eq!(resolve(&index, 0x1800), Some((".text".into(), 0x800, 0x1000)));
+        assert_eq!(resolve(&index, 0x5010), Some((".data".into(), 0x10, 0x100)));
+        assert!(index.resolve(0x5010).unwrap().symbol.is_none());
+        assert_eq!(resolve(&index, 0x6000), None);
+    }
+}
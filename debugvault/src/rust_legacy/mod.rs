@@ -3,7 +3,8 @@ use config::CONFIG;
 
 mod tests;
 
-pub fn parse(s: &str) -> Option<TokenStream> {
+/// Try to parse a rust legacy symbol, with the trailing hash if `simplify` isn't set.
+pub fn parse(s: &str, simplify: bool) -> Option<TokenStream> {
     // macOS prefixes symbols with an extra underscore therefore '__S' is allowed
     let s = s.strip_prefix("ZN").or(s.strip_prefix("_ZN")).or(s.strip_prefix("__ZN"))?;
 
@@ -43,6 +44,10 @@ pub fn parse(s: &str) -> Option<TokenStream> {
         unparsed = unparsed.get(len..)?;
 
         if is_rust_hash(part) {
+            if !simplify {
+                stream.push("::", CONFIG.colors.delimiter);
+                stream.push(part, CONFIG.colors.comment);
+            }
             break;
         }
 
@@ -4,7 +4,7 @@ use super::*;
 
 macro_rules! none {
     ($mangled:literal) => {
-        if parse($mangled).is_some() {
+        if parse($mangled, true).is_some() {
             panic!(
                 "Formatting '{}' succeeded when it wasn't supposed to.",
                 $mangled
@@ -15,7 +15,7 @@ macro_rules! none {
 
 macro_rules! eq {
     ($mangled:literal => $demangled:literal) => {
-        let symbol = parse($mangled).expect(&format!("Formatting '{}' failed.", $mangled));
+        let symbol = parse($mangled, true).expect(&format!("Formatting '{}' failed.", $mangled));
 
         assert_eq!(
             String::from_iter(symbol.tokens().iter().map(|t| &t.text[..])),
@@ -85,6 +85,15 @@ fn without_hash() {
     eq!("_ZN3foo17h05af221e174051e9E" => "foo");
 }
 
+#[test]
+fn with_hash() {
+    let symbol = parse("_ZN4core3fmt9Formatter9write_str17h1c6c5e0a0a5e1c84E", false).unwrap();
+    assert_eq!(
+        String::from_iter(symbol.tokens().iter().map(|t| &t.text[..])),
+        "core::fmt::Formatter::write_str::h1c6c5e0a0a5e1c84"
+    );
+}
+
 #[test]
 fn without_hash_edgecases() {
     // One element, no hash.
@@ -0,0 +1,100 @@
+//! Shortening of demangled symbols when `--simplify` is set.
+
+use crate::TokenStream;
+use config::{SimplifyRule, CONFIG};
+
+/// Spellings of standard library types and the names they're shortened to, in the order
+/// they're replaced.
+const SHORTHANDS: &[(&str, &str)] = &[
+    ("std::__cxx11::", "std::"),
+    ("std::basic_string<char, std::char_traits<char>, std::allocator<char>>", "std::string"),
+    (
+        "std::basic_string<wchar_t, std::char_traits<wchar_t>, std::allocator<wchar_t>>",
+        "std::wstring",
+    ),
+    ("std::basic_ostream<char, std::char_traits<char>>", "std::ostream"),
+    ("std::basic_istream<char, std::char_traits<char>>", "std::istream"),
+    ("std::basic_iostream<char, std::char_traits<char>>", "std::iostream"),
+    ("alloc::string::String", "String"),
+    ("alloc::vec::Vec", "Vec"),
+    ("alloc::boxed::Box", "Box"),
+    ("core::option::Option", "Option"),
+    ("core::result::Result", "Result"),
+];
+
+/// Shorten a symbol by the built-in rules, followed by the rules of the config.
+pub(crate) fn apply(stream: &mut TokenStream) {
+    apply_with(stream, CONFIG.simplify.max_depth, &CONFIG.simplify.rules);
+}
+
+fn apply_with(stream: &mut TokenStream, max_depth: usize, rules: &[SimplifyRule]) {
+    for (spelling, shorthand) in SHORTHANDS {
+        stream.replace(spelling, shorthand);
+    }
+
+    stream.elide(max_depth);
+
+    if rules.is_empty() {
+        return;
+    }
+
+    let name = String::from_iter(stream.tokens().iter().map(|t| &t.text[..]));
+    let mut simplified = name.clone();
+    for rule in rules {
+        simplified = rule.pattern.replace_all(&simplified, &rule.replacement).into_owned();
+    }
+
+    // the text no longer lines up with the tokens, so it's shown as a whole
+    if simplified != name {
+        *stream = TokenStream::simple(&simplified);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_with;
+    use config::SimplifyRule;
+
+    fn simplify(mangled: &str, max_depth: usize, rules: &[SimplifyRule]) -> String {
+        let mut stream = crate::rust_legacy::parse(mangled, true)
+            .or_else(|| crate::itanium::parse(mangled))
+            .unwrap_or_else(|| crate::TokenStream::simple(mangled));
+        apply_with(&mut stream, max_depth, rules);
+        String::from_iter(stream.tokens().iter().map(|t| &t.text[..]))
+    }
+
+    #[test]
+    fn shorthands() {
+        assert_eq!(
+            simplify("_ZN5alloc6string6String8push_str17h2b0f0c1ad5a36e43E", 3, &[]),
+            "String::push_str"
+        );
+        assert_eq!(
+            simplify("_ZNSt7__cxx114listIiSaIiEE5clearEv", 3, &[]),
+            "std::list<int, std::allocator<int>>::clear"
+        );
+    }
+
+    #[test]
+    fn depth() {
+        let mangled = "_ZN1aINS_IiEEE1fEv";
+        assert_eq!(simplify(mangled, 3, &[]), "a<a<int>>::f");
+        assert_eq!(simplify(mangled, 1, &[]), "a<a<...>>::f");
+        assert_eq!(simplify(mangled, 0, &[]), "a<...>::f");
+
+        // comparison operators aren't brackets
+        assert_eq!(simplify("_ZN1aIiEltERKS0_", 0, &[]), "a<...>::operator<");
+    }
+
+    #[test]
+    fn rules() {
+        let rules = [SimplifyRule {
+            pattern: regex::Regex::new("^core::ptr::drop_in_place<(.*)>$").unwrap(),
+            replacement: "drop<$1>".to_string(),
+        }];
+
+        let mangled = "_ZN4core3ptr42drop_in_place$LT$alloc..string..String$GT$17h0cd7e9a4b4d9f0b1E";
+        assert_eq!(simplify(mangled, 3, &rules), "drop<String>");
+        assert_eq!(simplify("main", 3, &rules), "main");
+    }
+}
@@ -3,9 +3,9 @@
 
 use core::fmt::{self, Display, Formatter};
 
-use decoder::{Decoded, Decodable, Error, ErrorKind, Reader, ToTokens};
+use decoder::{Decoded, Decodable, Error, ErrorKind, Reader, ToTokens, XrefKind};
 use debugvault::Index;
-use tokenizing::TokenStream;
+use tokenizing::{Radix, TokenStream};
 use config::CONFIG;
 
 mod thumb;
@@ -35,7 +35,7 @@ impl ToTokens for ConditionedOpcode {
         match self.0 {
             Opcode::UDF | Opcode::Invalid => {
                 // invalid_op
-                stream.push_owned(self.to_string(), CONFIG.colors.asm.invalid)
+                stream.push_fmt(format_args!("{self}"), CONFIG.colors.asm.invalid)
             }
             Opcode::TBB
             | Opcode::TBH
@@ -48,7 +48,7 @@ impl ToTokens for ConditionedOpcode {
             | Opcode::BX
             | Opcode::BXJ => {
                 // control_flow_op
-                stream.push_owned(self.to_string(), CONFIG.colors.asm.opcode)
+                stream.push_owned_mnemonic(self.to_string(), CONFIG.colors.asm.opcode)
             }
             Opcode::AND
             | Opcode::EOR
@@ -136,15 +136,15 @@ impl ToTokens for ConditionedOpcode {
             | Opcode::SMLAL
             | Opcode::SMLAL_halfword(_, _) => {
                 // arithmetic_op
-                stream.push_owned(self.to_string(), CONFIG.colors.asm.opcode)
+                stream.push_owned_mnemonic(self.to_string(), CONFIG.colors.asm.opcode)
             }
             Opcode::PUSH | Opcode::POP => {
                 // stack_op
-                stream.push_owned(self.to_string(), CONFIG.colors.asm.opcode)
+                stream.push_owned_mnemonic(self.to_string(), CONFIG.colors.asm.opcode)
             }
             Opcode::TST | Opcode::TEQ | Opcode::CMP | Opcode::CMN => {
                 // comparison_op
-                stream.push_owned(self.to_string(), CONFIG.colors.asm.opcode)
+                stream.push_owned_mnemonic(self.to_string(), CONFIG.colors.asm.opcode)
             }
             Opcode::LDRSH
             | Opcode::LDRSHT
@@ -216,7 +216,7 @@ impl ToTokens for ConditionedOpcode {
             | Opcode::MOVT
             | Opcode::MVN => {
                 // data_op
-                stream.push_owned(self.to_string(), CONFIG.colors.asm.opcode)
+                stream.push_owned_mnemonic(self.to_string(), CONFIG.colors.asm.opcode)
             }
             Opcode::HINT
             | Opcode::NOP
@@ -229,7 +229,7 @@ impl ToTokens for ConditionedOpcode {
             | Opcode::SRS(_, _)
             | Opcode::BKPT => {
                 // misc_op
-                stream.push_owned(self.to_string(), CONFIG.colors.asm.opcode)
+                stream.push_owned_mnemonic(self.to_string(), CONFIG.colors.asm.opcode)
             }
             Opcode::DBG
             | Opcode::CPS(_)
@@ -262,7 +262,7 @@ impl ToTokens for ConditionedOpcode {
             | Opcode::MRRC(_, _)
             | Opcode::CDP2(_, _, _) => {
                 // platform_op
-                stream.push_owned(self.to_string(), CONFIG.colors.asm.opcode)
+                stream.push_owned_mnemonic(self.to_string(), CONFIG.colors.asm.opcode)
             }
         }
     }
@@ -1718,7 +1718,7 @@ impl Operand {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(reg.as_str(), CONFIG.colors.asm.register);
                 stream.push("]", CONFIG.colors.brackets);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
 
                 if !*add {
                     stream.push("-", CONFIG.colors.asm.expr);
@@ -1733,7 +1733,7 @@ impl Operand {
             Operand::RegDerefPreindexReg(reg, offsreg, add, wback) => {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(reg.as_str(), CONFIG.colors.asm.register);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
 
                 if !*add {
                     stream.push("-", CONFIG.colors.asm.expr);
@@ -1786,6 +1786,7 @@ impl Operand {
                     None => {
                         stream.push("#", CONFIG.colors.asm.expr);
                         stream.push_owned(decoder::encode_uhex(*imm), CONFIG.colors.asm.immediate);
+                        symbols.tokenize_offset(stream, *imm as usize);
                     }
                 }
             }
@@ -1800,7 +1801,7 @@ impl Operand {
                 } else {
                     stream.push("$", CONFIG.colors.asm.immediate);
                 }
-                stream.push_owned(decoder::encode_hex((offs * 4) as i64), CONFIG.colors.asm.immediate);
+                stream.push_immediate((offs * 4) as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::BranchThumbOffset(offs) => {
                 if *offs >= 0 {
@@ -1809,11 +1810,11 @@ impl Operand {
                 } else {
                     stream.push("$", CONFIG.colors.asm.immediate);
                 }
-                stream.push_owned(decoder::encode_hex((offs * 2) as i64), CONFIG.colors.asm.immediate);
+                stream.push_immediate((offs * 2) as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::Coprocessor(num) => {
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(num.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{num}"), CONFIG.colors.asm.register);
             }
             Operand::CoprocOption(num) => {
                 stream.push("{", CONFIG.colors.brackets);
@@ -1856,6 +1857,9 @@ pub struct Instruction {
     pub thumb_w: bool,
     /// and generally speaking, was this just a thumb-encoded instruction?
     pub thumb: bool,
+    /// the `ITSTATE` after this instruction, in the same layout as the `cpsr` bits, filled in by
+    /// `update_rel_addrs` for instructions in or opening an `it` block.
+    pub it_state: u8,
 }
 
 impl Default for Instruction {
@@ -1873,6 +1877,7 @@ impl Default for Instruction {
             thumb_w: false,
             wide: false,
             thumb: false,
+            it_state: 0,
         }
     }
 }
@@ -1917,7 +1922,7 @@ fn format_reg_list(stream: &mut TokenStream, mut list: u16) {
         let present = (list & 1) == 1;
         if present {
             if tail {
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
             } else {
                 tail = true;
             }
@@ -1936,15 +1941,15 @@ fn format_shift(stream: &mut TokenStream, shift: RegShift) {
                 stream.push(imm_shift.shiftee().as_str(), CONFIG.colors.asm.register);
             } else {
                 stream.push(imm_shift.shiftee().as_str(), CONFIG.colors.asm.register);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 stream.push(imm_shift.stype().as_str(), CONFIG.colors.asm.segment);
                 stream.push(" ", CONFIG.colors.asm.expr);
-                stream.push_owned(imm_shift.imm().to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{}", imm_shift.imm()), CONFIG.colors.asm.immediate);
             }
         }
         RegShiftStyle::RegReg(reg_shift) => {
             stream.push(reg_shift.shiftee().as_str(), CONFIG.colors.asm.register);
-            stream.push(", ", CONFIG.colors.asm.expr);
+            stream.push_separator(CONFIG.colors.asm.expr);
             stream.push(reg_shift.stype().as_str(), CONFIG.colors.asm.segment);
             stream.push(" ", CONFIG.colors.asm.expr);
             stream.push(reg_shift.shifter().as_str(), CONFIG.colors.asm.register);
@@ -1966,7 +1971,7 @@ fn format_reg_shift_mem(
         (true, true) => {
             stream.push("[", CONFIG.colors.brackets);
             stream.push(rd.as_str(), CONFIG.colors.asm.register);
-            stream.push(", ", CONFIG.colors.asm.expr);
+            stream.push_separator(CONFIG.colors.asm.expr);
             stream.push(rd.as_str(), CONFIG.colors.asm.register);
             stream.push(op, CONFIG.colors.asm.immediate);
             format_shift(stream, shift);
@@ -1977,7 +1982,7 @@ fn format_reg_shift_mem(
         (true, false) => {
             stream.push("[", CONFIG.colors.brackets);
             stream.push(rd.as_str(), CONFIG.colors.asm.register);
-            stream.push(", ", CONFIG.colors.asm.expr);
+            stream.push_separator(CONFIG.colors.asm.expr);
             stream.push(rd.as_str(), CONFIG.colors.asm.register);
             stream.push(op, CONFIG.colors.asm.immediate);
             format_shift(stream, shift);
@@ -1993,7 +1998,7 @@ fn format_reg_shift_mem(
             stream.push("[", CONFIG.colors.brackets);
             stream.push(rd.as_str(), CONFIG.colors.asm.register);
             stream.push("]", CONFIG.colors.brackets);
-            stream.push(", ", CONFIG.colors.asm.expr);
+            stream.push_separator(CONFIG.colors.asm.expr);
             stream.push(op, CONFIG.colors.asm.immediate);
             format_shift(stream, shift)
         }
@@ -2013,11 +2018,11 @@ fn format_reg_imm_mem(
             (true, true) => {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(rn.as_str(), CONFIG.colors.asm.register);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 if add {
-                    stream.push_owned(decoder::encode_hex(imm as i64), CONFIG.colors.asm.immediate);
+                    stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 } else {
-                    stream.push_owned(decoder::encode_hex(imm as i64 * -1), CONFIG.colors.asm.immediate);
+                    stream.push_immediate(imm as i64 * -1, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
                 stream.push("]", CONFIG.colors.brackets);
                 stream.push("!", CONFIG.colors.asm.expr);
@@ -2025,11 +2030,11 @@ fn format_reg_imm_mem(
             (true, false) => {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(rn.as_str(), CONFIG.colors.asm.register);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 if add {
-                    stream.push_owned(decoder::encode_hex(imm as i64), CONFIG.colors.asm.immediate);
+                    stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 } else {
-                    stream.push_owned(decoder::encode_hex(imm as i64 * -1), CONFIG.colors.asm.immediate);
+                    stream.push_immediate(imm as i64 * -1, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
                 stream.push("]", CONFIG.colors.brackets);
             }
@@ -2037,11 +2042,11 @@ fn format_reg_imm_mem(
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(rn.as_str(), CONFIG.colors.asm.register);
                 stream.push("]", CONFIG.colors.brackets);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 if add {
-                    stream.push_owned(decoder::encode_hex(imm as i64), CONFIG.colors.asm.immediate);
+                    stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 } else {
-                    stream.push_owned(decoder::encode_hex(imm as i64 * -1), CONFIG.colors.asm.immediate);
+                    stream.push_immediate(imm as i64 * -1, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             }
         }
@@ -2070,27 +2075,62 @@ fn format_reg_imm_mem(
 impl Decoded for Instruction {
     #[inline]
     fn width(&self) -> usize {
-        4
+        if self.thumb && !self.wide {
+            2
+        } else {
+            4
+        }
     }
 
-    fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+    fn update_rel_addrs(&mut self, addr: usize, prev: Option<&Instruction>) {
+        if self.thumb {
+            self.update_it_state(prev);
+        }
+
+        // reads of pc see the address of the current instruction plus 8 in arm state
+        // and plus 4 in thumb state, literal addressing uses it word-aligned
+        let pc = if self.thumb { addr + 4 } else { addr + 8 };
+        let aligned_pc = (pc & !0b11) as u64;
+        let width = self.width();
+
         match self.opcode {
-            Opcode::ADR => {
-                let offs = self.operands[1].offset();
-                let addr = (addr as u64).saturating_add_signed(offs);
-                self.operands[1] = Operand::Imm64Special(addr);
+            Opcode::ADR => match self.operands {
+                [Operand::Reg(rd), Operand::Imm32(imm), Operand::Nothing, Operand::Nothing]
+                | [Operand::Reg(rd), Operand::Reg(Reg { bits: 15 }), Operand::Imm32(imm), Operand::Nothing] => {
+                    let addr = aligned_pc.wrapping_add(imm as u64);
+                    self.operands = [
+                        Operand::Reg(rd),
+                        Operand::Imm64Special(addr),
+                        Operand::Nothing,
+                        Operand::Nothing,
+                    ];
+                }
+                _ => {}
             },
             _ => {
                 for operand in self.operands.iter_mut() {
-                    match operand {
+                    match *operand {
                         Operand::BranchOffset(offs) => {
-                            let offs = (*offs as i64) << 2;
+                            // arm branch offsets are biased at decode time to be relative to
+                            // the instruction itself
+                            let offs = (offs as i64) << 2;
                             let addr = (addr as u64).saturating_add_signed(offs);
                             *operand = Operand::Imm64(addr);
                         }
                         Operand::BranchThumbOffset(offs) => {
-                            let offs = (*offs as i64) << 1;
-                            let addr = (addr as u64).saturating_add_signed(offs);
+                            // thumb branch offsets are relative to the end of the instruction,
+                            // `blx` in arm state isn't biased
+                            let base = if self.thumb { addr + width } else { pc };
+                            let offs = (offs as i64) << 1;
+                            let addr = (base as u64).saturating_add_signed(offs);
+                            *operand = Operand::Imm64(addr);
+                        }
+                        Operand::RegDerefPreindexOffset(Reg { bits: 15 }, offs, add, false) => {
+                            let addr = if add {
+                                aligned_pc.wrapping_add(offs as u64)
+                            } else {
+                                aligned_pc.wrapping_sub(offs as u64)
+                            };
                             *operand = Operand::Imm64(addr);
                         }
                         _ => {}
@@ -2099,6 +2139,73 @@ impl Decoded for Instruction {
             }
         }
     }
+
+    fn xref(&self) -> Option<(usize, XrefKind)> {
+        // addresses are only ever the result of resolving pc relative operands
+        let addr = self.operands.iter().find_map(|operand| match *operand {
+            Operand::Imm64(addr) | Operand::Imm64Special(addr) => Some(addr as usize),
+            _ => None,
+        })?;
+
+        let kind = match self.opcode {
+            Opcode::BL | Opcode::BLX => XrefKind::Call,
+            Opcode::B | Opcode::CBZ | Opcode::CBNZ => XrefKind::Jump,
+            _ => XrefKind::Data,
+        };
+
+        Some((addr, kind))
+    }
+
+    fn is_terminator(&self) -> bool {
+        if self.condition != ConditionCode::AL {
+            return false;
+        }
+
+        let pc = Reg::from_u8(15);
+        match self.opcode {
+            Opcode::B | Opcode::BX | Opcode::TBB | Opcode::TBH => true,
+            Opcode::ERET | Opcode::RFE(..) | Opcode::UDF => true,
+            Opcode::POP | Opcode::LDM(..) => self.operands.iter().any(|operand| {
+                matches!(operand, Operand::RegList(list) if list & (1 << pc.number()) != 0)
+            }),
+            Opcode::LDR | Opcode::MOV | Opcode::ADD => self.operands[0] == Operand::Reg(pc),
+            _ => false,
+        }
+    }
+}
+
+impl Instruction {
+    /// Applies the condition of an enclosing `it` block, as left behind by `prev`.
+    ///
+    /// Narrow instructions that set flags outside of an `it` block don't inside of one.
+    fn update_it_state(&mut self, prev: Option<&Instruction>) {
+        let it_state = match prev {
+            Some(prev) if prev.thumb => prev.it_state,
+            _ => 0,
+        };
+
+        if let (Opcode::IT, Operand::Imm32(cond), Operand::Imm32(mask)) =
+            (self.opcode, self.operands[0], self.operands[1])
+        {
+            self.it_state = ((cond << 4) | mask) as u8;
+            return;
+        }
+
+        if it_state & 0b1111 == 0 {
+            return;
+        }
+
+        self.condition = ConditionCode::build(it_state >> 4);
+        if !self.wide {
+            self.set_s(false);
+        }
+
+        self.it_state = if it_state & 0b111 == 0 {
+            0
+        } else {
+            (it_state & 0b1110_0000) | ((it_state << 1) & 0b1_1111)
+        };
+    }
 }
 
 impl Display for Instruction {
@@ -2143,7 +2250,7 @@ impl ToTokens for Instruction {
                             },
                             condition,
                         );
-                        stream.push_owned(op, CONFIG.colors.asm.opcode);
+                        stream.push_owned_mnemonic(op, CONFIG.colors.asm.opcode);
                     } else if mask & 0b0010 != 0 {
                         // two flags
                         let op = format!(
@@ -2160,7 +2267,7 @@ impl ToTokens for Instruction {
                             },
                             condition,
                         );
-                        stream.push_owned(op, CONFIG.colors.asm.opcode);
+                        stream.push_owned_mnemonic(op, CONFIG.colors.asm.opcode);
                     } else if mask & 0b0100 != 0 {
                         // one flag
                         let op = format!(
@@ -2172,15 +2279,15 @@ impl ToTokens for Instruction {
                             },
                             condition,
                         );
-                        stream.push_owned(op, CONFIG.colors.asm.opcode);
+                        stream.push_owned_mnemonic(op, CONFIG.colors.asm.opcode);
                     } else {
                         // no flags
                         let op = format!("it {}", condition);
-                        stream.push_owned(op, CONFIG.colors.asm.opcode);
+                        stream.push_owned_mnemonic(op, CONFIG.colors.asm.opcode);
                     }
                     // if the condition is AL, it won't get displayed. append it here.
                     if *cond == 14 {
-                        stream.push("al", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("al", CONFIG.colors.asm.opcode);
                     }
                     return;
                 } else {
@@ -2196,10 +2303,14 @@ impl ToTokens for Instruction {
                         if aif & 0b010 != 0 { "i" } else { "" },
                         if aif & 0b001 != 0 { "f" } else { "" },
                     );
-                    stream.push_owned(op, CONFIG.colors.asm.opcode);
+                    stream.push_owned_mnemonic(op, CONFIG.colors.asm.opcode);
                     if let Operand::Imm12(mode) = &self.operands[1] {
                         stream.push(", #", CONFIG.colors.asm.expr);
-                        stream.push_owned(decoder::encode_hex(*mode as i64), CONFIG.colors.asm.immediate);
+                        stream.push_immediate(
+                            *mode as i64,
+                            Radix::Hex,
+                            CONFIG.colors.asm.immediate,
+                        );
                     }
                     return;
                 } else {
@@ -2209,9 +2320,9 @@ impl ToTokens for Instruction {
             Opcode::SETEND => {
                 if let Operand::Imm12(i) = &self.operands[0] {
                     if *i == 0 {
-                        stream.push("setend le", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("setend le", CONFIG.colors.asm.opcode);
                     } else {
-                        stream.push("setend be", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("setend be", CONFIG.colors.asm.opcode);
                     }
                     return;
                 } else {
@@ -2292,7 +2403,7 @@ impl ToTokens for Instruction {
                     if wback {
                         stream.push("!", CONFIG.colors.asm.expr);
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     format_reg_list(stream, list);
                     return;
                 }
@@ -2301,192 +2412,192 @@ impl ToTokens for Instruction {
                 }
             },
             Opcode::STCL(coproc) => {
-                stream.push("stcl ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("stcl ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
             }
             Opcode::STC(coproc) => {
-                stream.push("stc ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("stc ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
             }
             Opcode::STC2L(coproc) => {
-                stream.push("stc2l ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("stc2l ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
             }
             Opcode::STC2(coproc) => {
-                stream.push("stc2 ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("stc2 ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
             }
             Opcode::LDC(coproc) => {
-                stream.push("ldc ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("ldc ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
             }
             Opcode::LDCL(coproc) => {
-                stream.push("ldcl ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("ldcl ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
             }
             Opcode::LDC2(coproc) => {
-                stream.push("ldc2 ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("ldc2 ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
             }
             Opcode::LDC2L(coproc) => {
-                stream.push("ldc2l ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("ldc2l ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
             }
             Opcode::MRRC2(coproc, opc) => {
-                stream.push("mrrc2 ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("mrrc2 ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
-                stream.push(", ", CONFIG.colors.asm.expr);
-                stream.push_owned(opc.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
+                stream.push_separator(CONFIG.colors.asm.expr);
+                stream.push_fmt(format_args!("{opc}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
             }
             Opcode::MCRR2(coproc, opc) => {
-                stream.push("mcrr2 ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("mcrr2 ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
-                stream.push(", ", CONFIG.colors.asm.expr);
-                stream.push_owned(opc.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
+                stream.push_separator(CONFIG.colors.asm.expr);
+                stream.push_fmt(format_args!("{opc}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
             }
             Opcode::MRC2(coproc, opc1, opc2) => {
-                stream.push("mrc2 ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("mrc2 ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
-                stream.push(", ", CONFIG.colors.asm.expr);
-                stream.push_owned(opc1.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
+                stream.push_separator(CONFIG.colors.asm.expr);
+                stream.push_fmt(format_args!("{opc1}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
 
-                stream.push(", ", CONFIG.colors.asm.expr);
-                stream.push_owned(opc2.to_string(), CONFIG.colors.asm.register);
+                stream.push_separator(CONFIG.colors.asm.expr);
+                stream.push_fmt(format_args!("{opc2}"), CONFIG.colors.asm.register);
             }
             Opcode::MCR2(coproc, opc1, opc2) => {
-                stream.push("mcr2 ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("mcr2 ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
-                stream.push(", ", CONFIG.colors.asm.expr);
-                stream.push_owned(opc1.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
+                stream.push_separator(CONFIG.colors.asm.expr);
+                stream.push_fmt(format_args!("{opc1}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
 
-                stream.push(", ", CONFIG.colors.asm.expr);
-                stream.push_owned(opc2.to_string(), CONFIG.colors.asm.register);
+                stream.push_separator(CONFIG.colors.asm.expr);
+                stream.push_fmt(format_args!("{opc2}"), CONFIG.colors.asm.register);
             }
             Opcode::CDP2(coproc, opc1, opc2) => {
-                stream.push("cdp2 ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("cdp2 ", CONFIG.colors.asm.opcode);
                 stream.push("p", CONFIG.colors.asm.register);
-                stream.push_owned(coproc.to_string(), CONFIG.colors.asm.register);
-                stream.push(", ", CONFIG.colors.asm.expr);
-                stream.push_owned(opc1.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{coproc}"), CONFIG.colors.asm.register);
+                stream.push_separator(CONFIG.colors.asm.expr);
+                stream.push_fmt(format_args!("{opc1}"), CONFIG.colors.asm.register);
                 let ops = self.operands.iter();
                 for op in ops {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, imm_override);
                 }
 
-                stream.push(", ", CONFIG.colors.asm.expr);
-                stream.push_owned(opc2.to_string(), CONFIG.colors.asm.register);
+                stream.push_separator(CONFIG.colors.asm.expr);
+                stream.push_fmt(format_args!("{opc2}"), CONFIG.colors.asm.register);
             }
             _ => {
                 ConditionedOpcode(self.opcode, self.s(), self.w(), self.condition)
@@ -2506,7 +2617,7 @@ impl ToTokens for Instruction {
                     if let Operand::Nothing = op {
                         break;
                     }
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     op.tokenize(stream, symbols, None);
                 }
             }
@@ -2641,7 +2752,7 @@ impl DecodeMode {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 enum ARMVersion {
     v4,
@@ -2670,7 +2781,7 @@ impl Default for ARMVersion {
 /// yet. this means any `Decoder` will decode all known instructions through the latest `ARMv7`
 /// extensions.
 #[allow(unused)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 pub struct Decoder {
     mode: DecodeMode,
     version: ARMVersion,
@@ -2825,13 +2936,31 @@ impl Decodable for Decoder {
 
     fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
         let mut inst = Instruction::default();
-        read(self, reader, &mut inst).map_err(|err| Error::new(err, 4))?;
+        reader.mark();
+        read(self, reader, &mut inst).map_err(|err| Error::new(err, reader.offset()))?;
         Ok(inst)
     }
 
     fn max_width(&self) -> usize {
         4
     }
+
+    fn alignment(&self) -> usize {
+        if self.thumb {
+            2
+        } else {
+            4
+        }
+    }
+}
+
+/// `tst`, `teq`, `cmp` and `cmn` always set flags and have no destination register.
+fn compare_operands(inst: &mut Instruction, opcode: u8) {
+    if (0b1000..0b1100).contains(&opcode) {
+        inst.set_s(false);
+        inst.operands.rotate_left(1);
+        inst.operands[3] = Operand::Nothing;
+    }
 }
 
 #[inline(always)]
@@ -4295,6 +4424,7 @@ fn read(decoder: &Decoder, words: &mut Reader, inst: &mut Instruction) -> Result
                             ];
                         }
                     }
+                    compare_operands(inst, opcode);
                 }
             }
         }
@@ -4392,7 +4522,8 @@ fn read(decoder: &Decoder, words: &mut Reader, inst: &mut Instruction) -> Result
                     let imm = (imm as u32).rotate_right(2 * (rot >> 8));
                     ((word >> 16) as u8 & 0x0f, (word >> 12) as u8 & 0x0f, imm)
                 };
-                if (opcode == 0b0010 || opcode == 0b0100) && Rn == 0b1111 {
+                // `sub rd, pc, #imm` is left as is, as `adr` operands don't carry a sign
+                if opcode == 0b0100 && Rn == 0b1111 {
                     inst.opcode = Opcode::ADR;
                 }
                 match opcode {
@@ -4413,6 +4544,7 @@ fn read(decoder: &Decoder, words: &mut Reader, inst: &mut Instruction) -> Result
                         ];
                     }
                 }
+                compare_operands(inst, opcode);
             }
             /* ... */
         }
@@ -8,7 +8,7 @@
 use core::fmt::{self, Display, Formatter};
 
 use debugvault::Index;
-use decoder::{Decodable, Decoded, Error, ErrorKind, Reader, ToTokens};
+use decoder::{Decodable, Decoded, Error, ErrorKind, Reader, ToTokens, XrefKind};
 use tokenizing::TokenStream;
 use config::CONFIG;
 
@@ -247,21 +247,22 @@ impl Decoded for Instruction {
                 self.operands[1] = Operand::Imm64Special(addr);
             },
             Opcode::LDR => {
-                let mut a_imm = 0;
-                let mut a_reg = 0;
-                if let Some(Instruction { opcode: Opcode::ADRP | Opcode::ADR, operands }) = prev_inst {
-                    if let [Operand::Register(_, reg), Operand::Imm64Special(imm), ..] = operands {
-                        a_imm = *imm;
-                        a_reg = *reg;
-                    } else {
-                        return;
-                    }
+                // literal loads are relative to the instruction
+                if let Operand::PCOffset(offs) = self.operands[1] {
+                    let addr = (addr as u64).saturating_add_signed(offs);
+                    self.operands[1] = Operand::Imm64(addr);
+                    return;
                 }
 
-                if let Operand::RegPreIndex(l_reg, off, _) = self.operands[1] {
-                    if a_reg == l_reg {
-                        let addr = a_imm.saturating_add_signed(off as i64);
-                        self.operands[1] = Operand::Imm64(addr);
+                // loads relative to a register set by a previous adrp or adr
+                if let Some(Instruction { opcode: Opcode::ADRP | Opcode::ADR, operands }) = prev_inst {
+                    if let [Operand::Register(_, a_reg), Operand::Imm64Special(a_imm), ..] = operands {
+                        if let Operand::RegPreIndex(l_reg, off, _) = self.operands[1] {
+                            if *a_reg == l_reg {
+                                let addr = a_imm.saturating_add_signed(off as i64);
+                                self.operands[1] = Operand::Imm64(addr);
+                            }
+                        }
                     }
                 }
             }
@@ -275,6 +276,45 @@ impl Decoded for Instruction {
             }
         }
     }
+
+    fn xref(&self) -> Option<(usize, XrefKind)> {
+        let kind = match self.opcode {
+            Opcode::BL => XrefKind::Call,
+            Opcode::B | Opcode::Bcc(_) | Opcode::CBZ | Opcode::CBNZ | Opcode::TBZ | Opcode::TBNZ => {
+                XrefKind::Jump
+            }
+            Opcode::ADR | Opcode::ADRP | Opcode::LDR | Opcode::LDRSW | Opcode::PRFM => {
+                XrefKind::Data
+            }
+            _ => return None,
+        };
+
+        // other instructions also have 64-bit immediates that aren't addresses
+        let addr = self.operands.iter().find_map(|operand| match *operand {
+            Operand::Imm64(addr) | Operand::Imm64Special(addr) => Some(addr as usize),
+            _ => None,
+        })?;
+
+        Some((addr, kind))
+    }
+
+    fn is_terminator(&self) -> bool {
+        matches!(
+            self.opcode,
+            Opcode::B
+                | Opcode::BR
+                | Opcode::BRAA
+                | Opcode::BRAAZ
+                | Opcode::BRAB
+                | Opcode::BRABZ
+                | Opcode::RET
+                | Opcode::RETAA
+                | Opcode::RETAB
+                | Opcode::ERET
+                | Opcode::ERETAA
+                | Opcode::ERETAB
+        )
+    }
 }
 
 impl Display for Instruction {
@@ -291,7 +331,7 @@ impl ToTokens for Instruction {
     fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
         match self.opcode {
             Opcode::ISB => {
-                stream.push("isb", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("isb", CONFIG.colors.asm.opcode);
 
                 // the default/reserved/expected value for the immediate in `isb` is `0b1111`.
                 if let Operand::Imm16(15) = self.operands[0] {
@@ -300,13 +340,13 @@ impl ToTokens for Instruction {
             }
             Opcode::SBC => {
                 if let Operand::Register(_, 31) = self.operands[1] {
-                    stream.push("ngc ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("ngc ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 } else {
-                    stream.push("sbc", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("sbc", CONFIG.colors.asm.opcode);
                 }
             }
             Opcode::MOVN => {
@@ -324,7 +364,7 @@ impl ToTokens for Instruction {
                 } else {
                     unreachable!("movn operand 0 is always Register");
                 };
-                stream.push("mov ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("mov ", CONFIG.colors.asm.opcode);
                 self.operands[0].tokenize(stream, symbols);
                 stream.push(", #", CONFIG.colors.asm.expr);
                 stream.push_owned(decoder::encode_uhex(imm), CONFIG.colors.asm.immediate);
@@ -345,7 +385,7 @@ impl ToTokens for Instruction {
                 } else {
                     unreachable!("movn operand 0 is always Register");
                 };
-                stream.push("mov ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("mov ", CONFIG.colors.asm.opcode);
                 self.operands[0].tokenize(stream, symbols);
                 stream.push(", #", CONFIG.colors.asm.expr);
                 stream.push_owned(decoder::encode_uhex(imm), CONFIG.colors.asm.immediate);
@@ -354,91 +394,91 @@ impl ToTokens for Instruction {
             Opcode::ORR => {
                 if let Operand::Register(_, 31) = self.operands[1] {
                     if let Operand::Immediate(0) = self.operands[2] {
-                        stream.push("mov ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("mov ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[1].tokenize(stream, symbols);
                         return;
                     } else if let Operand::RegShift(style, amt, size, r) = self.operands[2] {
                         if style == ShiftStyle::LSL && amt == 0 {
-                            stream.push("mov ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("mov ", CONFIG.colors.asm.opcode);
                             self.operands[0].tokenize(stream, symbols);
-                            stream.push(", ", CONFIG.colors.asm.expr);
+                            stream.push_separator(CONFIG.colors.asm.expr);
                             Operand::Register(size, r).tokenize(stream, symbols);
                             return;
                         }
                     } else {
-                        stream.push("mov ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("mov ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
                 } else if self.operands[1] == self.operands[2] {
-                    stream.push("mov ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("mov ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
                     return;
                 }
-                stream.push("orr", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("orr", CONFIG.colors.asm.opcode);
             }
             Opcode::ORN => {
                 if let Operand::Register(_, 31) = self.operands[1] {
-                    stream.push("mvn ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("mvn ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 }
-                stream.push("orn", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("orn", CONFIG.colors.asm.opcode);
             }
             Opcode::ANDS => {
                 if let Operand::Register(_, 31) = self.operands[0] {
-                    stream.push("tst ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("tst ", CONFIG.colors.asm.opcode);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 }
-                stream.push("ands", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("ands", CONFIG.colors.asm.opcode);
             }
             Opcode::NOT => {
                 // `This instruction is used by the alias MVN. The alias is always the preferred
                 // disassembly.`
-                stream.push("mvn", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("mvn", CONFIG.colors.asm.opcode);
             }
             Opcode::ADDS => {
                 if let Operand::Register(_, 31) = self.operands[0] {
-                    stream.push("cmn ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("cmn ", CONFIG.colors.asm.opcode);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 } else if let Operand::RegShift(ShiftStyle::LSL, 0, size, reg) = self.operands[2] {
-                    stream.push("adds ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("adds ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     Operand::Register(size, reg).tokenize(stream, symbols);
                     return;
                 }
-                stream.push("adds", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("adds", CONFIG.colors.asm.opcode);
             }
             Opcode::ADD => {
                 if let Operand::Immediate(0) = self.operands[2] {
                     if let Operand::RegisterOrSP(_, 31) = self.operands[0] {
-                        stream.push("mov ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("mov ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[1].tokenize(stream, symbols);
                         return;
                     }
                     if let Operand::RegisterOrSP(_, 31) = self.operands[1] {
-                        stream.push("mov ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("mov ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[1].tokenize(stream, symbols);
                         return;
                     }
@@ -446,67 +486,67 @@ impl ToTokens for Instruction {
                 //                } else if let Operand::Register(_, 31) = self.operands[1] {
                 //                    return write!(fmt, "mov {}, {}", self.operands[0], self.operands[2]);
                 } else if let Operand::RegShift(ShiftStyle::LSL, 0, size, reg) = self.operands[2] {
-                    stream.push("add ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("add ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     Operand::Register(size, reg).tokenize(stream, symbols);
                     return;
                 }
-                stream.push("add", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("add", CONFIG.colors.asm.opcode);
             }
             Opcode::SUBS => {
                 if let Operand::Register(_, 31) = self.operands[0] {
-                    stream.push("cmp ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("cmp ", CONFIG.colors.asm.opcode);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 } else if let Operand::Register(_, 31) = self.operands[1] {
-                    stream.push("negs ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("negs ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 } else if let Operand::RegShift(ShiftStyle::LSL, 0, size, reg) = self.operands[2] {
-                    stream.push("subs ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("subs ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     Operand::Register(size, reg).tokenize(stream, symbols);
                     return;
                 }
-                stream.push("subs", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("subs", CONFIG.colors.asm.opcode);
             }
             Opcode::SUB => {
                 if let Operand::Register(_, 31) = self.operands[1] {
-                    stream.push("neg ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("neg ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 } else if let Operand::RegShift(ShiftStyle::LSL, 0, size, reg) = self.operands[2] {
-                    stream.push("sub ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("sub ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     Operand::Register(size, reg).tokenize(stream, symbols);
                     return;
                 }
-                stream.push("sub", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("sub", CONFIG.colors.asm.opcode);
             }
             Opcode::SBCS => {
                 if let Operand::Register(_, 31) = self.operands[1] {
-                    stream.push("ngcs ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("ngcs ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 }
-                stream.push("sbcs", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("sbcs", CONFIG.colors.asm.opcode);
             }
             Opcode::UBFM => {
                 // TODO: handle ubfx alias
@@ -517,15 +557,15 @@ impl ToTokens for Instruction {
                 ) = (self.operands[0], self.operands[1], self.operands[2])
                 {
                     if let Operand::Immediate(7) = self.operands[3] {
-                        stream.push("uxtb ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("uxtb ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[1].tokenize(stream, symbols);
                         return;
                     } else if let Operand::Immediate(15) = self.operands[3] {
-                        stream.push("uxth ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("uxth ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[1].tokenize(stream, symbols);
                         return;
                     }
@@ -538,11 +578,11 @@ impl ToTokens for Instruction {
                     };
                     match (imms, size) {
                         (63, SizeCode::X) | (31, SizeCode::W) => {
-                            stream.push("lsr ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("lsr ", CONFIG.colors.asm.opcode);
                             self.operands[0].tokenize(stream, symbols);
-                            stream.push(", ", CONFIG.colors.asm.expr);
+                            stream.push_separator(CONFIG.colors.asm.expr);
                             self.operands[1].tokenize(stream, symbols);
-                            stream.push(", ", CONFIG.colors.asm.expr);
+                            stream.push_separator(CONFIG.colors.asm.expr);
                             self.operands[2].tokenize(stream, symbols);
                             return;
                         }
@@ -554,9 +594,9 @@ impl ToTokens for Instruction {
                                 unreachable!("operand 3 is a register");
                             };
                             if imms + 1 == immr {
-                                stream.push("lsl ", CONFIG.colors.asm.opcode);
+                                stream.push_mnemonic("lsl ", CONFIG.colors.asm.opcode);
                                 self.operands[0].tokenize(stream, symbols);
-                                stream.push(", ", CONFIG.colors.asm.expr);
+                                stream.push_separator(CONFIG.colors.asm.expr);
                                 self.operands[1].tokenize(stream, symbols);
                                 stream.push(", #", CONFIG.colors.asm.expr);
                                 stream.push_owned(
@@ -566,9 +606,9 @@ impl ToTokens for Instruction {
                                 return;
                             }
                             if imms < immr {
-                                stream.push("ubfiz ", CONFIG.colors.asm.opcode);
+                                stream.push_mnemonic("ubfiz ", CONFIG.colors.asm.opcode);
                                 self.operands[0].tokenize(stream, symbols);
-                                stream.push(", ", CONFIG.colors.asm.expr);
+                                stream.push_separator(CONFIG.colors.asm.expr);
                                 self.operands[1].tokenize(stream, symbols);
                                 stream.push(", #", CONFIG.colors.asm.expr);
                                 stream.push_owned(
@@ -595,13 +635,13 @@ impl ToTokens for Instruction {
                     unreachable!("last two operands of ubfm are always immediates");
                 };
 
-                stream.push("ubfx ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("ubfx ", CONFIG.colors.asm.opcode);
                 self.operands[0].tokenize(stream, symbols);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 self.operands[1].tokenize(stream, symbols);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 self.operands[2].tokenize(stream, symbols);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 width.tokenize(stream, symbols);
                 return;
             }
@@ -618,7 +658,7 @@ impl ToTokens for Instruction {
                                 ((-(immr as i8)) as u8) & 0x3f
                             };
                             if rn == 31 {
-                                stream.push("bfc ", CONFIG.colors.asm.opcode);
+                                stream.push_mnemonic("bfc ", CONFIG.colors.asm.opcode);
                                 self.operands[0].tokenize(stream, symbols);
                                 stream.push(", #", CONFIG.colors.asm.expr);
                                 stream.push_owned(
@@ -632,9 +672,9 @@ impl ToTokens for Instruction {
                                 );
                                 return;
                             } else {
-                                stream.push("bfi ", CONFIG.colors.asm.opcode);
+                                stream.push_mnemonic("bfi ", CONFIG.colors.asm.opcode);
                                 self.operands[0].tokenize(stream, symbols);
-                                stream.push(", ", CONFIG.colors.asm.expr);
+                                stream.push_separator(CONFIG.colors.asm.expr);
                                 self.operands[1].tokenize(stream, symbols);
                                 stream.push(", #", CONFIG.colors.asm.expr);
                                 stream.push_owned(
@@ -654,9 +694,9 @@ impl ToTokens for Instruction {
                         let lsb = immr;
                         let width = imms + 1 - lsb;
 
-                        stream.push("bfxil ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("bfxil ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[1].tokenize(stream, symbols);
                         stream.push(", #", CONFIG.colors.asm.expr);
                         stream.push_owned(decoder::encode_uhex(lsb as u64), CONFIG.colors.asm.immediate);
@@ -669,22 +709,22 @@ impl ToTokens for Instruction {
             Opcode::SBFM => {
                 if let Operand::Immediate(63) = self.operands[3] {
                     if let Operand::Register(SizeCode::X, _) = self.operands[0] {
-                        stream.push("asr ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("asr ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[1].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
                 }
                 if let Operand::Immediate(31) = self.operands[3] {
                     if let Operand::Register(SizeCode::W, _) = self.operands[0] {
-                        stream.push("asr ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("asr ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[1].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -696,14 +736,14 @@ impl ToTokens for Instruction {
                         unreachable!("operand 1 is always a register");
                     };
                     if let Operand::Immediate(7) = self.operands[3] {
-                        stream.push("sxtb ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("sxtb ", CONFIG.colors.asm.opcode);
                     } else if let Operand::Immediate(15) = self.operands[3] {
-                        stream.push("sxth ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("sxth ", CONFIG.colors.asm.opcode);
                     } else if let Operand::Immediate(31) = self.operands[3] {
-                        stream.push("sxtw ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("sxtw ", CONFIG.colors.asm.opcode);
                     }
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     newsrc.tokenize(stream, symbols);
                     return;
                 }
@@ -720,9 +760,9 @@ impl ToTokens for Instruction {
                         } else {
                             unreachable!("operand 0 is always a register");
                         };
-                        stream.push("sbfiz ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("sbfiz ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[1].tokenize(stream, symbols);
                         stream.push(", #", CONFIG.colors.asm.expr);
                         stream.push_owned(
@@ -746,13 +786,13 @@ impl ToTokens for Instruction {
                 } else {
                     unreachable!("last two operands of sbfm are always immediates");
                 };
-                stream.push("sbfx ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("sbfx ", CONFIG.colors.asm.opcode);
                 self.operands[0].tokenize(stream, symbols);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 self.operands[1].tokenize(stream, symbols);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 self.operands[2].tokenize(stream, symbols);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 width.tokenize(stream, symbols);
                 return;
             }
@@ -761,19 +801,19 @@ impl ToTokens for Instruction {
                     (self.operands[1], self.operands[2])
                 {
                     if Rn == Rm {
-                        stream.push("ror ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("ror ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[3].tokenize(stream, symbols);
                         return;
                     }
                 }
-                stream.push("extr", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("extr", CONFIG.colors.asm.opcode);
             }
             Opcode::RET => {
-                stream.push("ret", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("ret", CONFIG.colors.asm.opcode);
                 if let Operand::Register(SizeCode::X, 30) = self.operands[0] {
                     // C5.6.148:  Defaults to X30 if absent.
                     // so ret x30 is probably expected to be read as just `ret`
@@ -781,26 +821,26 @@ impl ToTokens for Instruction {
                 }
             }
             Opcode::SYS(ops) => {
-                stream.push("sys #", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("sys #", CONFIG.colors.asm.opcode);
                 stream.push_owned(decoder::encode_uhex(ops.op1() as u64), CONFIG.colors.asm.immediate);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 self.operands[1].tokenize(stream, symbols);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 self.operands[2].tokenize(stream, symbols);
                 stream.push(", #", CONFIG.colors.asm.expr);
                 stream.push_owned(decoder::encode_uhex(ops.op2() as u64), CONFIG.colors.asm.immediate);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 self.operands[0].tokenize(stream, symbols);
                 return;
             }
             Opcode::SYSL(ops) => {
-                stream.push("sysl ", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("sysl ", CONFIG.colors.asm.opcode);
                 self.operands[2].tokenize(stream, symbols);
                 stream.push(", #", CONFIG.colors.asm.expr);
                 stream.push_owned(decoder::encode_uhex(ops.op1() as u64), CONFIG.colors.asm.immediate);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 self.operands[0].tokenize(stream, symbols);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 self.operands[1].tokenize(stream, symbols);
                 stream.push(", #", CONFIG.colors.asm.expr);
                 stream.push_owned(decoder::encode_uhex(ops.op2() as u64), CONFIG.colors.asm.immediate);
@@ -812,18 +852,18 @@ impl ToTokens for Instruction {
                 {
                     let hint_num = (CRn << 3) | op2 as u16;
                     match hint_num & 0b111111 {
-                        0x0 => stream.push("nop", CONFIG.colors.asm.opcode),
-                        0x1 => stream.push("yield", CONFIG.colors.asm.opcode),
-                        0x2 => stream.push("wfe", CONFIG.colors.asm.opcode),
-                        0x3 => stream.push("wfi", CONFIG.colors.asm.opcode),
-                        0x4 => stream.push("sev", CONFIG.colors.asm.opcode),
-                        0x10 => stream.push("esb", CONFIG.colors.asm.opcode),
-                        0x11 => stream.push("psb csync", CONFIG.colors.asm.opcode),
-                        0x12 => stream.push("tsb csync", CONFIG.colors.asm.opcode),
-                        0x14 => stream.push("csdb", CONFIG.colors.asm.opcode),
-                        0x15 => stream.push("sevl", CONFIG.colors.asm.opcode),
+                        0x0 => stream.push_mnemonic("nop", CONFIG.colors.asm.opcode),
+                        0x1 => stream.push_mnemonic("yield", CONFIG.colors.asm.opcode),
+                        0x2 => stream.push_mnemonic("wfe", CONFIG.colors.asm.opcode),
+                        0x3 => stream.push_mnemonic("wfi", CONFIG.colors.asm.opcode),
+                        0x4 => stream.push_mnemonic("sev", CONFIG.colors.asm.opcode),
+                        0x10 => stream.push_mnemonic("esb", CONFIG.colors.asm.opcode),
+                        0x11 => stream.push_mnemonic("psb csync", CONFIG.colors.asm.opcode),
+                        0x12 => stream.push_mnemonic("tsb csync", CONFIG.colors.asm.opcode),
+                        0x14 => stream.push_mnemonic("csdb", CONFIG.colors.asm.opcode),
+                        0x15 => stream.push_mnemonic("sevl", CONFIG.colors.asm.opcode),
                         _ => {
-                            stream.push("hint #", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("hint #", CONFIG.colors.asm.opcode);
                             stream.push_owned(
                                 decoder::encode_uhex(hint_num as u64),
                                 CONFIG.colors.asm.immediate,
@@ -841,18 +881,18 @@ impl ToTokens for Instruction {
                 ) = (self.operands[1], self.operands[2], self.operands[3])
                 {
                     if cond < 0b1110 && rn == rm {
-                        stream.push("cneg ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("cneg ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         Operand::ConditionCode(cond ^ 0x01).tokenize(stream, symbols);
                         return;
                     }
                 } else {
                     unreachable!("operands 2 and 3 are always registers");
                 }
-                stream.push("csneg", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("csneg", CONFIG.colors.asm.opcode);
             }
             Opcode::CSINC => {
                 match (self.operands[1], self.operands[2], self.operands[3]) {
@@ -863,17 +903,17 @@ impl ToTokens for Instruction {
                     ) => {
                         if n == m && cond < 0b1110 {
                             if n == 31 {
-                                stream.push("cset ", CONFIG.colors.asm.opcode);
+                                stream.push_mnemonic("cset ", CONFIG.colors.asm.opcode);
                                 self.operands[0].tokenize(stream, symbols);
-                                stream.push(", ", CONFIG.colors.asm.expr);
+                                stream.push_separator(CONFIG.colors.asm.expr);
                                 Operand::ConditionCode(cond ^ 0x01).tokenize(stream, symbols);
                                 return;
                             } else {
-                                stream.push("cinc ", CONFIG.colors.asm.opcode);
+                                stream.push_mnemonic("cinc ", CONFIG.colors.asm.opcode);
                                 self.operands[0].tokenize(stream, symbols);
-                                stream.push(", ", CONFIG.colors.asm.expr);
+                                stream.push_separator(CONFIG.colors.asm.expr);
                                 self.operands[1].tokenize(stream, symbols);
-                                stream.push(", ", CONFIG.colors.asm.expr);
+                                stream.push_separator(CONFIG.colors.asm.expr);
                                 Operand::ConditionCode(cond ^ 0x01).tokenize(stream, symbols);
                                 return;
                             }
@@ -881,7 +921,7 @@ impl ToTokens for Instruction {
                     }
                     _ => {}
                 }
-                stream.push("csinc", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("csinc", CONFIG.colors.asm.opcode);
             }
             Opcode::CSINV => {
                 match (self.operands[1], self.operands[2], self.operands[3]) {
@@ -891,131 +931,131 @@ impl ToTokens for Instruction {
                         Operand::ConditionCode(cond),
                     ) => {
                         if n == m && n != 31 && cond < 0b1110 {
-                            stream.push("cinv ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("cinv ", CONFIG.colors.asm.opcode);
                             self.operands[0].tokenize(stream, symbols);
-                            stream.push(", ", CONFIG.colors.asm.expr);
+                            stream.push_separator(CONFIG.colors.asm.expr);
                             self.operands[1].tokenize(stream, symbols);
-                            stream.push(", ", CONFIG.colors.asm.expr);
+                            stream.push_separator(CONFIG.colors.asm.expr);
                             Operand::ConditionCode(cond ^ 0x01).tokenize(stream, symbols);
                             return;
                         } else if n == m && n == 31 && cond < 0b1110 {
-                            stream.push("csetm ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("csetm ", CONFIG.colors.asm.opcode);
                             self.operands[0].tokenize(stream, symbols);
-                            stream.push(", ", CONFIG.colors.asm.expr);
+                            stream.push_separator(CONFIG.colors.asm.expr);
                             Operand::ConditionCode(cond ^ 0x01).tokenize(stream, symbols);
                             return;
                         }
                     }
                     _ => {}
                 }
-                stream.push("csinv", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("csinv", CONFIG.colors.asm.opcode);
             }
             Opcode::MADD => {
                 if let Operand::Register(_, 31) = self.operands[3] {
-                    stream.push("mul ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("mul ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 }
-                stream.push("madd", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("madd", CONFIG.colors.asm.opcode);
             }
             Opcode::MSUB => {
                 if let Operand::Register(_, 31) = self.operands[3] {
-                    stream.push("mneg ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("mneg ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 }
-                stream.push("msub", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("msub", CONFIG.colors.asm.opcode);
             }
             Opcode::SMADDL => {
                 if let Operand::Register(_, 31) = self.operands[3] {
-                    stream.push("smull ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("smull ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 }
-                stream.push("smaddl", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("smaddl", CONFIG.colors.asm.opcode);
             }
             Opcode::SMSUBL => {
                 if let Operand::Register(_, 31) = self.operands[3] {
-                    stream.push("smnegl ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("smnegl ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 }
-                stream.push("smsubl", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("smsubl", CONFIG.colors.asm.opcode);
             }
             Opcode::UMADDL => {
                 if let Operand::Register(_, 31) = self.operands[3] {
-                    stream.push("umull ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("umull ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 }
-                stream.push("umaddl", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("umaddl", CONFIG.colors.asm.opcode);
             }
             Opcode::UMSUBL => {
                 if let Operand::Register(_, 31) = self.operands[3] {
-                    stream.push("umnegl ", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("umnegl ", CONFIG.colors.asm.opcode);
                     self.operands[0].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[1].tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     self.operands[2].tokenize(stream, symbols);
                     return;
                 }
-                stream.push("umsubl", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("umsubl", CONFIG.colors.asm.opcode);
             }
             Opcode::LSLV => {
                 // lslv == lsl (register) and, quoth the manual, `lsl is always the preferred
                 // disassembly`.
-                stream.push("lsl", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("lsl", CONFIG.colors.asm.opcode);
             }
             Opcode::LSRV => {
                 // lsrv == lsr (register) and, quoth the manual, `lsr is always the preferred
                 // disassembly`.
-                stream.push("lsr", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("lsr", CONFIG.colors.asm.opcode);
             }
             Opcode::ASRV => {
                 // asrv == asr (register) and, quoth the manual, `asr is always the preferred
                 // disassembly`.
-                stream.push("asr", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("asr", CONFIG.colors.asm.opcode);
             }
             Opcode::RORV => {
                 // rorv == ror (register) and, quoth the manual, `ror is always the preferred
                 // disassembly`.
-                stream.push("ror", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("ror", CONFIG.colors.asm.opcode);
             }
             Opcode::INS => {
                 // `ins (element)` and `ins (general)` both have `mov` as an alias. manual reports
                 // that `mov` is the preferred disassembly.
-                stream.push("mov", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("mov", CONFIG.colors.asm.opcode);
             }
             Opcode::DUP => {
                 if let Operand::Register(_, _) = self.operands[1] {
                     // `dup (general)`
-                    stream.push("dup", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("dup", CONFIG.colors.asm.opcode);
                 } else {
                     // `dup (element)`
                     // manual says `mov` is the preferred disassembly here? but capstone uses
                     // `dup`.
-                    stream.push("mov", CONFIG.colors.asm.opcode);
+                    stream.push_mnemonic("mov", CONFIG.colors.asm.opcode);
                 }
             }
             Opcode::UMOV => {
@@ -1027,13 +1067,13 @@ impl ToTokens for Instruction {
                     if (reg_sz == SizeCode::W && elem_sz == SIMDSizeCode::S)
                         || (reg_sz == SizeCode::X && elem_sz == SIMDSizeCode::D)
                     {
-                        stream.push("mov ", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("mov ", CONFIG.colors.asm.opcode);
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[1].tokenize(stream, symbols);
                         return;
                     } else {
-                        stream.push("umov", CONFIG.colors.asm.opcode);
+                        stream.push_mnemonic("umov", CONFIG.colors.asm.opcode);
                     }
                 }
             }
@@ -1041,12 +1081,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stadd ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stadd ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("staddl ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("staddl ", CONFIG.colors.asm.opcode);
                         }
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1057,12 +1097,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stclr ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stclr ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stclrl ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stclrl ", CONFIG.colors.asm.opcode);
                         }
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1073,12 +1113,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stset ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stset ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stsetl ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsetl ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1089,12 +1129,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stsmax ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsmax ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stsmaxl ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsmaxl ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1105,12 +1145,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stsmin ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsmin ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stsminl ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsminl ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1121,12 +1161,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stumax ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stumax ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stumaxl ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stumaxl ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1137,12 +1177,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stumin ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stumin ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stuminl ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stuminl ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1153,12 +1193,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("steor ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("steor ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("steorl ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("steorl ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1169,12 +1209,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("staddh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("staddh ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("staddlh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("staddlh ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1185,12 +1225,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stclrh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stclrh ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stclrlh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stclrlh ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1201,12 +1241,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stseth ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stseth ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stsetlh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsetlh ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1217,12 +1257,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stsmaxh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsmaxh ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stsmaxlh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsmaxlh ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1233,12 +1273,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stsminh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsminh ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stsminlh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsminlh ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1249,12 +1289,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stumaxh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stumaxh ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stumaxlh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stumaxlh ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1265,12 +1305,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stuminh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stuminh ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stuminlh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stuminlh ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1281,12 +1321,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("steorh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("steorh ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("steorlh ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("steorlh ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1297,12 +1337,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("staddb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("staddb ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("staddlb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("staddlb ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1313,12 +1353,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stclrb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stclrb ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stclrlb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stclrlb ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1329,12 +1369,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stsetb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsetb ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stsetlb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsetlb ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1345,12 +1385,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stsmaxb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsmaxb ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stsmaxlb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsmaxlb ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1361,12 +1401,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stsminb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsminb ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stsminlb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stsminlb ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1377,12 +1417,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stumaxb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stumaxb ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stumaxlb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stumaxlb ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1393,12 +1433,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("stuminb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stuminb ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("stuminlb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("stuminlb ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1409,12 +1449,12 @@ impl ToTokens for Instruction {
                 if let Operand::Register(_, rt) = self.operands[1] {
                     if rt == 31 && ar & 0b10 == 0b00 {
                         if ar & 0b01 == 0b00 {
-                            stream.push("steorb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("steorb ", CONFIG.colors.asm.opcode);
                         } else {
-                            stream.push("steorlb ", CONFIG.colors.asm.opcode);
+                            stream.push_mnemonic("steorlb ", CONFIG.colors.asm.opcode);
                         };
                         self.operands[0].tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
+                        stream.push_separator(CONFIG.colors.asm.expr);
                         self.operands[2].tokenize(stream, symbols);
                         return;
                     }
@@ -1432,21 +1472,21 @@ impl ToTokens for Instruction {
         }
 
         if self.operands[1] != Operand::Nothing {
-            stream.push(", ", CONFIG.colors.asm.expr);
+            stream.push_separator(CONFIG.colors.asm.expr);
             self.operands[1].tokenize(stream, symbols);
         } else {
             return;
         }
 
         if self.operands[2] != Operand::Nothing {
-            stream.push(", ", CONFIG.colors.asm.expr);
+            stream.push_separator(CONFIG.colors.asm.expr);
             self.operands[2].tokenize(stream, symbols);
         } else {
             return;
         }
 
         if self.operands[3] != Operand::Nothing {
-            stream.push(", ", CONFIG.colors.asm.expr);
+            stream.push_separator(CONFIG.colors.asm.expr);
             self.operands[3].tokenize(stream, symbols);
         } else {
             return;
@@ -2919,42 +2959,44 @@ impl ToTokens for Opcode {
             Opcode::SUBPS => "subps",
 
             Opcode::Bcc(cond) => {
-                stream.push("b.", CONFIG.colors.asm.opcode);
+                stream.push_mnemonic("b.", CONFIG.colors.asm.opcode);
                 Operand::ConditionCode(cond).tokenize(stream, symbols);
                 return;
             }
             Opcode::DMB(option) => {
                 return match option {
-                    0b0001 => stream.push("dmb oshld", CONFIG.colors.asm.opcode),
-                    0b0010 => stream.push("dmb oshst", CONFIG.colors.asm.opcode),
-                    0b0011 => stream.push("dmb osh", CONFIG.colors.asm.opcode),
-                    0b0101 => stream.push("dmb nshld", CONFIG.colors.asm.opcode),
-                    0b0110 => stream.push("dmb nshst", CONFIG.colors.asm.opcode),
-                    0b0111 => stream.push("dmb nsh", CONFIG.colors.asm.opcode),
-                    0b1001 => stream.push("dmb ishld", CONFIG.colors.asm.opcode),
-                    0b1010 => stream.push("dmb ishst", CONFIG.colors.asm.opcode),
-                    0b1011 => stream.push("dmb ish", CONFIG.colors.asm.opcode),
-                    0b1101 => stream.push("dmb ld", CONFIG.colors.asm.opcode),
-                    0b1110 => stream.push("dmb st", CONFIG.colors.asm.opcode),
-                    0b1111 => stream.push("dmb sy", CONFIG.colors.asm.opcode),
-                    _ => stream.push_owned(format!("dmb #{option}"), CONFIG.colors.asm.opcode),
+                    0b0001 => stream.push_mnemonic("dmb oshld", CONFIG.colors.asm.opcode),
+                    0b0010 => stream.push_mnemonic("dmb oshst", CONFIG.colors.asm.opcode),
+                    0b0011 => stream.push_mnemonic("dmb osh", CONFIG.colors.asm.opcode),
+                    0b0101 => stream.push_mnemonic("dmb nshld", CONFIG.colors.asm.opcode),
+                    0b0110 => stream.push_mnemonic("dmb nshst", CONFIG.colors.asm.opcode),
+                    0b0111 => stream.push_mnemonic("dmb nsh", CONFIG.colors.asm.opcode),
+                    0b1001 => stream.push_mnemonic("dmb ishld", CONFIG.colors.asm.opcode),
+                    0b1010 => stream.push_mnemonic("dmb ishst", CONFIG.colors.asm.opcode),
+                    0b1011 => stream.push_mnemonic("dmb ish", CONFIG.colors.asm.opcode),
+                    0b1101 => stream.push_mnemonic("dmb ld", CONFIG.colors.asm.opcode),
+                    0b1110 => stream.push_mnemonic("dmb st", CONFIG.colors.asm.opcode),
+                    0b1111 => stream.push_mnemonic("dmb sy", CONFIG.colors.asm.opcode),
+                    _ => stream
+                        .push_owned_mnemonic(format!("dmb #{option}"), CONFIG.colors.asm.opcode),
                 };
             }
             Opcode::DSB(option) => {
                 return match option {
-                    0b0001 => stream.push("dsb oshld", CONFIG.colors.asm.opcode),
-                    0b0010 => stream.push("dsb oshst", CONFIG.colors.asm.opcode),
-                    0b0011 => stream.push("dsb osh", CONFIG.colors.asm.opcode),
-                    0b0101 => stream.push("dsb nshld", CONFIG.colors.asm.opcode),
-                    0b0110 => stream.push("dsb nshst", CONFIG.colors.asm.opcode),
-                    0b0111 => stream.push("dsb nsh", CONFIG.colors.asm.opcode),
-                    0b1001 => stream.push("dsb ishld", CONFIG.colors.asm.opcode),
-                    0b1010 => stream.push("dsb ishst", CONFIG.colors.asm.opcode),
-                    0b1011 => stream.push("dsb ish", CONFIG.colors.asm.opcode),
-                    0b1101 => stream.push("dsb ld", CONFIG.colors.asm.opcode),
-                    0b1110 => stream.push("dsb st", CONFIG.colors.asm.opcode),
-                    0b1111 => stream.push("dsb sy", CONFIG.colors.asm.opcode),
-                    _ => stream.push_owned(format!("dsb #{option}"), CONFIG.colors.asm.opcode),
+                    0b0001 => stream.push_mnemonic("dsb oshld", CONFIG.colors.asm.opcode),
+                    0b0010 => stream.push_mnemonic("dsb oshst", CONFIG.colors.asm.opcode),
+                    0b0011 => stream.push_mnemonic("dsb osh", CONFIG.colors.asm.opcode),
+                    0b0101 => stream.push_mnemonic("dsb nshld", CONFIG.colors.asm.opcode),
+                    0b0110 => stream.push_mnemonic("dsb nshst", CONFIG.colors.asm.opcode),
+                    0b0111 => stream.push_mnemonic("dsb nsh", CONFIG.colors.asm.opcode),
+                    0b1001 => stream.push_mnemonic("dsb ishld", CONFIG.colors.asm.opcode),
+                    0b1010 => stream.push_mnemonic("dsb ishst", CONFIG.colors.asm.opcode),
+                    0b1011 => stream.push_mnemonic("dsb ish", CONFIG.colors.asm.opcode),
+                    0b1101 => stream.push_mnemonic("dsb ld", CONFIG.colors.asm.opcode),
+                    0b1110 => stream.push_mnemonic("dsb st", CONFIG.colors.asm.opcode),
+                    0b1111 => stream.push_mnemonic("dsb sy", CONFIG.colors.asm.opcode),
+                    _ => stream
+                        .push_owned_mnemonic(format!("dsb #{option}"), CONFIG.colors.asm.opcode),
                 };
             }
             Opcode::HINT => "hint",
@@ -3004,7 +3046,7 @@ impl ToTokens for Opcode {
             }
         };
 
-        stream.push(text, CONFIG.colors.asm.opcode);
+        stream.push_mnemonic(text, CONFIG.colors.asm.opcode);
     }
 }
 
@@ -3350,7 +3392,7 @@ impl ToTokens for Operand {
             }
             Operand::RegisterPair(size, reg) => {
                 Operand::Register(*size, *reg).tokenize(stream, symbols);
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
                 Operand::Register(*size, *reg + 1).tokenize(stream, symbols);
             }
             Operand::ControlReg(reg) => {
@@ -3362,7 +3404,7 @@ impl ToTokens for Operand {
                 let policy = op & 1;
 
                 if ty == 0b11 || target == 0b11 {
-                    stream.push_owned(format!("{:#02x}", op), CONFIG.colors.asm.immediate);
+                    stream.push_fmt(format_args!("{:#02x}", op), CONFIG.colors.asm.immediate);
                 } else {
                     let op = format!(
                         "{}{}{}",
@@ -3370,7 +3412,7 @@ impl ToTokens for Operand {
                         ["l1", "l2", "l3"][target as usize],
                         ["keep", "strm"][policy as usize],
                     );
-                    stream.push_owned(op, CONFIG.colors.asm.opcode);
+                    stream.push_owned_mnemonic(op, CONFIG.colors.asm.opcode);
                 }
             }
             Operand::SystemReg(reg) => {
@@ -3389,22 +3431,22 @@ impl ToTokens for Operand {
                         let op1 = (reg >> 11) & 0b111;
                         let op0 = ((reg >> 14) & 0b1) + 2;
 
-                        stream.push_owned(format!("s{op0}"), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("s{op0}"), CONFIG.colors.asm.immediate);
                         stream.push("_", CONFIG.colors.asm.expr);
-                        stream.push_owned(format!("{op1}"), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{op1}"), CONFIG.colors.asm.immediate);
                         stream.push("_", CONFIG.colors.asm.expr);
-                        stream.push_owned(format!("c{CRn}"), CONFIG.colors.asm.register);
+                        stream.push_fmt(format_args!("c{CRn}"), CONFIG.colors.asm.register);
                         stream.push("_", CONFIG.colors.asm.expr);
-                        stream.push_owned(format!("c{CRm}"), CONFIG.colors.asm.register);
+                        stream.push_fmt(format_args!("c{CRm}"), CONFIG.colors.asm.register);
                         stream.push("_", CONFIG.colors.asm.expr);
-                        stream.push_owned(format!("{op2}"), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{op2}"), CONFIG.colors.asm.immediate);
                     }
                 }
             }
             Operand::PstateField(reg) => {
                 // `MSR (immediate)` writes to the `PSTATE` registers, setting a few bit patterns as
                 // selected by `reg`.
-                stream.push_owned(format!("pstate.{:#x}", reg), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("pstate.{:#x}", reg), CONFIG.colors.asm.register);
             }
             Operand::SIMDRegister(size, reg) => match size {
                 SIMDSizeCode::B => format_register_b(stream, *reg),
@@ -3442,7 +3484,7 @@ impl ToTokens for Operand {
                 stream.push("{", CONFIG.colors.brackets);
                 format_reg(stream, *reg, num_items, *lane_width);
                 for i in 1..*group_size {
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     format_reg(stream, (*reg + i as u16) % 32, num_items, *lane_width);
                 }
                 stream.push("}", CONFIG.colors.brackets);
@@ -3456,11 +3498,11 @@ impl ToTokens for Operand {
                 stream.push("{", CONFIG.colors.brackets);
                 format_reg(stream, *reg, *lane_width);
                 for i in 1..*group_size {
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     format_reg(stream, (*reg + i as u16) % 32, *lane_width);
                 }
                 stream.push("}[", CONFIG.colors.brackets);
-                stream.push_owned(lane.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{lane}"), CONFIG.colors.asm.register);
                 stream.push("]", CONFIG.colors.brackets);
             }
             Operand::RegisterOrSP(size, reg) => {
@@ -3486,24 +3528,24 @@ impl ToTokens for Operand {
             }
             Operand::ConditionCode(cond) => {
                 match cond {
-                    0b0000 => stream.push("eq", CONFIG.colors.asm.opcode),
-                    0b0010 => stream.push("hs", CONFIG.colors.asm.opcode),
-                    0b0100 => stream.push("mi", CONFIG.colors.asm.opcode),
-                    0b0110 => stream.push("vs", CONFIG.colors.asm.opcode),
-                    0b1000 => stream.push("hi", CONFIG.colors.asm.opcode),
-                    0b1010 => stream.push("ge", CONFIG.colors.asm.opcode),
-                    0b1100 => stream.push("gt", CONFIG.colors.asm.opcode),
-                    0b1110 => stream.push("al", CONFIG.colors.asm.opcode),
-                    0b0001 => stream.push("ne", CONFIG.colors.asm.opcode),
-                    0b0011 => stream.push("lo", CONFIG.colors.asm.opcode),
-                    0b0101 => stream.push("pl", CONFIG.colors.asm.opcode),
-                    0b0111 => stream.push("vc", CONFIG.colors.asm.opcode),
-                    0b1001 => stream.push("ls", CONFIG.colors.asm.opcode),
-                    0b1011 => stream.push("lt", CONFIG.colors.asm.opcode),
-                    0b1101 => stream.push("le", CONFIG.colors.asm.opcode),
+                    0b0000 => stream.push_mnemonic("eq", CONFIG.colors.asm.opcode),
+                    0b0010 => stream.push_mnemonic("hs", CONFIG.colors.asm.opcode),
+                    0b0100 => stream.push_mnemonic("mi", CONFIG.colors.asm.opcode),
+                    0b0110 => stream.push_mnemonic("vs", CONFIG.colors.asm.opcode),
+                    0b1000 => stream.push_mnemonic("hi", CONFIG.colors.asm.opcode),
+                    0b1010 => stream.push_mnemonic("ge", CONFIG.colors.asm.opcode),
+                    0b1100 => stream.push_mnemonic("gt", CONFIG.colors.asm.opcode),
+                    0b1110 => stream.push_mnemonic("al", CONFIG.colors.asm.opcode),
+                    0b0001 => stream.push_mnemonic("ne", CONFIG.colors.asm.opcode),
+                    0b0011 => stream.push_mnemonic("lo", CONFIG.colors.asm.opcode),
+                    0b0101 => stream.push_mnemonic("pl", CONFIG.colors.asm.opcode),
+                    0b0111 => stream.push_mnemonic("vc", CONFIG.colors.asm.opcode),
+                    0b1001 => stream.push_mnemonic("ls", CONFIG.colors.asm.opcode),
+                    0b1011 => stream.push_mnemonic("lt", CONFIG.colors.asm.opcode),
+                    0b1101 => stream.push_mnemonic("le", CONFIG.colors.asm.opcode),
                     // `The Condition code NV exists only to provide a valid disassembly of the
                     // 0b1111 encoding, otherwise its behavior is identical to AL`.
-                    0b1111 => stream.push("nv", CONFIG.colors.asm.opcode),
+                    0b1111 => stream.push_mnemonic("nv", CONFIG.colors.asm.opcode),
                     _ => unreachable!(),
                 }
             }
@@ -3562,6 +3604,7 @@ impl ToTokens for Operand {
                 None => {
                     stream.push("#", CONFIG.colors.asm.expr);
                     stream.push_owned(decoder::encode_uhex(*imm as u64), CONFIG.colors.asm.immediate);
+                    symbols.tokenize_offset(stream, *imm as usize);
                 }
             },
             Operand::Imm64Special(imm) => {
@@ -3573,10 +3616,10 @@ impl ToTokens for Operand {
                 stream.push_owned(decoder::encode_uhex(*imm as u64), CONFIG.colors.asm.immediate);
 
                 if *shift != 0 {
-                    stream.push(", ", CONFIG.colors.asm.expr);
-                    stream.push("lsl ", CONFIG.colors.asm.opcode);
+                    stream.push_separator(CONFIG.colors.asm.expr);
+                    stream.push_mnemonic("lsl ", CONFIG.colors.asm.opcode);
                     stream.push("#", CONFIG.colors.asm.expr);
-                    stream.push_owned(shift.to_string(), CONFIG.colors.asm.immediate);
+                    stream.push_fmt(format_args!("{shift}"), CONFIG.colors.asm.immediate);
                 }
             }
             Operand::ImmShiftMSL(imm, shift) => {
@@ -3584,10 +3627,10 @@ impl ToTokens for Operand {
                 stream.push_owned(decoder::encode_uhex(*imm as u64), CONFIG.colors.asm.immediate);
 
                 if *shift != 0 {
-                    stream.push(", ", CONFIG.colors.asm.expr);
-                    stream.push("msl ", CONFIG.colors.asm.opcode);
+                    stream.push_separator(CONFIG.colors.asm.expr);
+                    stream.push_mnemonic("msl ", CONFIG.colors.asm.opcode);
                     stream.push("#", CONFIG.colors.asm.expr);
-                    stream.push_owned(shift.to_string(), CONFIG.colors.asm.immediate);
+                    stream.push_fmt(format_args!("{shift}"), CONFIG.colors.asm.immediate);
                 }
             }
             Operand::RegShift(shift_type, amount, size, reg) => match size {
@@ -3598,15 +3641,15 @@ impl ToTokens for Operand {
                         Operand::Register(SizeCode::X, *reg).tokenize(stream, symbols);
                     } else if *amount != 0 {
                         Operand::Register(SizeCode::X, *reg).tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
-                        stream.push(shift_type.as_str(), CONFIG.colors.asm.opcode);
+                        stream.push_separator(CONFIG.colors.asm.expr);
+                        stream.push_mnemonic(shift_type.as_str(), CONFIG.colors.asm.opcode);
                         stream.push(" ", CONFIG.colors.asm.expr);
                         stream.push("#", CONFIG.colors.asm.expr);
-                        stream.push_owned(amount.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{amount}"), CONFIG.colors.asm.immediate);
                     } else {
                         Operand::Register(SizeCode::X, *reg).tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
-                        stream.push(shift_type.as_str(), CONFIG.colors.asm.opcode);
+                        stream.push_separator(CONFIG.colors.asm.expr);
+                        stream.push_mnemonic(shift_type.as_str(), CONFIG.colors.asm.opcode);
                     }
                 }
                 SizeCode::W => {
@@ -3614,15 +3657,15 @@ impl ToTokens for Operand {
                         Operand::Register(SizeCode::W, *reg).tokenize(stream, symbols);
                     } else if *amount != 0 {
                         Operand::Register(SizeCode::W, *reg).tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
-                        stream.push(shift_type.as_str(), CONFIG.colors.asm.opcode);
+                        stream.push_separator(CONFIG.colors.asm.expr);
+                        stream.push_mnemonic(shift_type.as_str(), CONFIG.colors.asm.opcode);
                         stream.push(" ", CONFIG.colors.asm.expr);
                         stream.push("#", CONFIG.colors.asm.expr);
-                        stream.push_owned(amount.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{amount}"), CONFIG.colors.asm.immediate);
                     } else {
                         Operand::Register(SizeCode::W, *reg).tokenize(stream, symbols);
-                        stream.push(", ", CONFIG.colors.asm.expr);
-                        stream.push(shift_type.as_str(), CONFIG.colors.asm.opcode);
+                        stream.push_separator(CONFIG.colors.asm.expr);
+                        stream.push_mnemonic(shift_type.as_str(), CONFIG.colors.asm.opcode);
                     }
                 }
             },
@@ -3630,7 +3673,7 @@ impl ToTokens for Operand {
                 if extend == &ShiftStyle::LSL && *amount == 0 {
                     stream.push("[", CONFIG.colors.brackets);
                     Operand::RegisterOrSP(SizeCode::X, *reg).tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     Operand::Register(*index_size, *index_reg).tokenize(stream, symbols);
                     stream.push("]", CONFIG.colors.brackets);
                 } else if ((extend == &ShiftStyle::UXTW && index_size == &SizeCode::W)
@@ -3639,21 +3682,21 @@ impl ToTokens for Operand {
                 {
                     stream.push("[", CONFIG.colors.brackets);
                     Operand::RegisterOrSP(SizeCode::X, *reg).tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     Operand::Register(*index_size, *index_reg).tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
-                    stream.push(extend.as_str(), CONFIG.colors.asm.opcode);
+                    stream.push_separator(CONFIG.colors.asm.expr);
+                    stream.push_mnemonic(extend.as_str(), CONFIG.colors.asm.opcode);
                     stream.push("]", CONFIG.colors.brackets);
                 } else {
                     stream.push("[", CONFIG.colors.brackets);
                     Operand::RegisterOrSP(SizeCode::X, *reg).tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     Operand::Register(*index_size, *index_reg).tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
-                    stream.push(extend.as_str(), CONFIG.colors.asm.opcode);
+                    stream.push_separator(CONFIG.colors.asm.expr);
+                    stream.push_mnemonic(extend.as_str(), CONFIG.colors.asm.opcode);
                     stream.push(" ", CONFIG.colors.asm.expr);
                     stream.push("#", CONFIG.colors.asm.expr);
-                    stream.push_owned(amount.to_string(), CONFIG.colors.asm.immediate);
+                    stream.push_fmt(format_args!("{amount}"), CONFIG.colors.asm.immediate);
                     stream.push("]", CONFIG.colors.brackets);
                 }
             }
@@ -3661,7 +3704,7 @@ impl ToTokens for Operand {
                 if *offset != 0 || *wback_bit {
                     stream.push("[", CONFIG.colors.brackets);
                     Operand::RegisterOrSP(SizeCode::X, *reg).tokenize(stream, symbols);
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                     stream.push("#", CONFIG.colors.asm.expr);
                     stream.push_owned(decoder::encode_hex(*offset as i64), CONFIG.colors.asm.immediate);
                     stream.push("]", CONFIG.colors.brackets);
@@ -3715,6 +3758,10 @@ impl Decodable for Decoder {
     fn max_width(&self) -> usize {
         4
     }
+
+    fn alignment(&self) -> usize {
+        4
+    }
 }
 
 #[inline(always)]
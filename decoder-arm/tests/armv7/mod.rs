@@ -139,6 +139,7 @@ fn test_decode_str_ldr() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_decode(
@@ -156,6 +157,7 @@ fn test_decode_str_ldr() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_decode(
@@ -173,6 +175,7 @@ fn test_decode_str_ldr() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_decode(
@@ -190,6 +193,7 @@ fn test_decode_str_ldr() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_decode(
@@ -207,6 +211,7 @@ fn test_decode_str_ldr() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_decode(
@@ -224,6 +229,7 @@ fn test_decode_str_ldr() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_all([0x10, 0x00, 0x7f, 0xe5], "ldrb r0, [pc, -0x10]!");
@@ -345,6 +351,7 @@ fn test_decode_pop() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_display([0x04, 0x10, 0x9d, 0xe4], "pop {r1}");
@@ -363,6 +370,7 @@ fn test_decode_pop() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_display([0xf0, 0x40, 0x2d, 0xe9], "push {r4, r5, r6, r7, lr}");
@@ -381,6 +389,7 @@ fn test_decode_pop() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_display([0xf0, 0x80, 0xbd, 0x18], "popne {r4, r5, r6, r7, pc}");
@@ -403,6 +412,7 @@ fn test_decode_mov() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_display([0x0d, 0x20, 0xa0, 0xe1], "mov r2, sp");
@@ -422,6 +432,7 @@ fn test_decode_mov() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
 }
@@ -443,6 +454,7 @@ fn test_decode_arithmetic() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_display([0x18, 0x1d, 0x00, 0x00], "andeq r1, r0, r8, lsl sp");
@@ -461,6 +473,7 @@ fn test_decode_arithmetic() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_decode(
@@ -478,6 +491,7 @@ fn test_decode_arithmetic() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_decode(
@@ -495,6 +509,7 @@ fn test_decode_arithmetic() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_decode(
@@ -512,6 +527,7 @@ fn test_decode_arithmetic() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
 }
@@ -581,6 +597,7 @@ fn test_decode_mul() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_decode(
@@ -598,6 +615,7 @@ fn test_decode_mul() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
     test_decode(
@@ -615,6 +633,7 @@ fn test_decode_mul() {
             thumb_w: false,
             thumb: false,
             wide: false,
+            it_state: 0,
         },
     );
 }
@@ -743,3 +762,37 @@ pub fn bench_60000_instrs(b: &mut Bencher) {
     });
 }
 */
+
+#[test]
+fn test_relative() -> Result<(), Box<dyn std::error::Error>> {
+    let decoded = decode_instructions!(
+        InstDecoder::default(),
+        "armv7a-none-eabi",
+        "
+        .global _start
+        _start:
+        1:
+            cmp r0, #0
+            bne 1b
+            ldr r1, 2f
+            adr r2, 2f
+            b 1b
+        2:
+            .word 0xe1a00000
+        "
+    );
+
+    assert_eq!(
+        decoded,
+        [
+            "cmp r0, 0x0",
+            "bne #0x0",
+            "ldr r1, #0x14",
+            "adr r2, #0x14",
+            "b #0x0",
+            "mov r0, r0",
+        ]
+    );
+
+    Ok(())
+}
@@ -4136,3 +4136,56 @@ fn test_decode_simd_32b_cases() {
         "vstmdb r3!, {s11, s12, s13, s14, s15, s16, s17, s18, s19, s20, s21, s22, s23, s24, s25, s26, s27, s28, s29, s30, s31}"
     );
 }
+
+#[test]
+fn test_relative() -> Result<(), Box<dyn std::error::Error>> {
+    let decoded = decode_instructions!(
+        InstDecoder::default_thumb(),
+        "thumbv7em-none-eabi",
+        "
+        .global _start
+        .thumb_func
+        _start:
+        1:
+            cmp r0, #0
+            bne 1b
+            it eq
+            moveq r1, #2
+            ite ne
+            addne r1, r1, #1
+            subeq r1, r1, #1
+            ldr r3, 2f
+            ldr.w r4, [r3, #4]
+            bl 3f
+            b.w 1b
+        3:
+            bx lr
+            .balign 4
+        2:
+            .word 0xbf00bf00
+        "
+    );
+
+    assert_eq!(
+        decoded,
+        [
+            "cmp r0, 0x0",
+            "bne #0x0",
+            "it eq",
+            "moveq r1, 0x2",
+            "ite ne",
+            "addne r1, r1, 0x1",
+            "subeq r1, r1, 0x1",
+            "ldr r3, #0x20",
+            "ldr.w r4, [r3, 0x4]",
+            "bl.w #0x1c",
+            "b.w #0x0",
+            "bx lr",
+            "nop",
+            "nop",
+            "nop",
+        ]
+    );
+
+    Ok(())
+}
@@ -4923,3 +4923,53 @@ fn test_bitfield() {
 
     assert!(errs.is_empty());
 }
+
+#[test]
+fn test_relative() -> Result<(), Box<dyn std::error::Error>> {
+    let decoded = decode_instructions!(
+        InstDecoder::default(),
+        "aarch64-unknown-none",
+        "
+        .global _start
+        _start:
+            stp x29, x30, [sp, #-16]!
+            mov x29, sp
+        1:  subs x0, x0, #1
+            b.ne 1b
+            cbz x1, 2f
+            bl 2f
+            ldr x2, [x0, #8]
+            ldr x3, 3f
+            adr x4, 3f
+            adrp x5, 3f
+            ldr x6, [x5, #16]
+            ldp x29, x30, [sp], #16
+        2:  ret
+        3:  .quad 0
+   "
+    );
+
+    assert_eq!(
+        decoded,
+        [
+            "stp x29, x30, [sp, #-0x10]!",
+            "mov x29, sp",
+            "subs x0, x0, #0x1",
+            "b.ne #0x8",
+            "cbz x1, #0x30",
+            "bl #0x30",
+            "ldr x2, [x0, #0x8]",
+            "ldr x3, #0x34",
+            "adr x4, #0x34",
+            "adrp x5, #0x0",
+            "ldr x6, #0x10",
+            "ldp x29, x30, [sp], #0x10",
+            "ret",
+            // the .quad
+            "<IncompleteDecoder>",
+            "<IncompleteDecoder>",
+        ]
+    );
+
+    Ok(())
+}
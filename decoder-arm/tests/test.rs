@@ -1,7 +1,75 @@
+/// Assembles `$code` for `$target` and decodes its `.text` section using `$decoder`.
+macro_rules! decode_instructions {
+    ($decoder:expr, $target:literal, $code:literal) => {{
+        use object::{Object, ObjectSection, SectionKind};
+
+        static CRC: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
+
+        let code = format!(
+            "
+            #![deny(warnings)]
+            #![no_std]
+            #![no_main]
+
+            core::arch::global_asm!(\"{}\");
+
+            #[panic_handler]
+            fn panic(_: &core::panic::PanicInfo) -> ! {{
+                loop {{}}
+            }}
+        ",
+            $code
+        );
+
+        let mut out_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+        out_path.push("..");
+        out_path.push("target");
+        out_path.push(format!("test_{}{}", $target, CRC.checksum($code.as_bytes())));
+
+        let src_path = out_path.with_extension("rs");
+
+        std::fs::write(&src_path, code).unwrap();
+
+        if cfg!(target_family = "windows") {
+            out_path.set_extension("exe");
+        }
+
+        let rustc = std::process::Command::new("rustc")
+            .arg(format!("-o{}", out_path.display()))
+            .arg(concat!("--target=", $target))
+            .arg("-Cstrip=symbols")
+            .arg(format!("{}", src_path.display()))
+            .output()?;
+
+        if !rustc.stderr.is_empty() {
+            eprintln!("{}", String::from_utf8_lossy(&rustc.stderr[..]));
+        }
+
+        if !rustc.status.success() {
+            return Err(format!("rustc failed with exit code: {}", rustc.status).into());
+        }
+
+        let binary = std::fs::read(out_path).unwrap();
+        let binary = object::File::parse(&binary[..])?;
+        let section = binary
+            .sections()
+            .filter(|s| s.kind() == SectionKind::Text)
+            .find(|t| t.name() == Ok(".text"))
+            .expect("failed to find `.text` section");
+
+        // addresses are relative to the start of the section
+        let binary = section.uncompressed_data()?;
+        let symbols = debugvault::Index::default();
+        decoder::testing::decode_instructions($decoder, &binary, 0, &symbols)
+    }};
+}
+
 mod armv7;
 mod armv8;
 
-use decoder::{Decodable, Reader, TokenStream, ToTokens};
+use decoder::{Decodable, Reader, ToTokens};
+use tokenizing::TokenStream;
 use debugvault::Index;
 
 fn test_range<A: Decodable>(decoder: &A, start: u64, end: u64) {
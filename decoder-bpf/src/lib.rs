@@ -0,0 +1,875 @@
+//! eBPF disassembler.
+
+mod helpers;
+mod tests;
+
+use decoder::{Error, ErrorKind, InstKind, ToTokens, XrefKind};
+use debugvault::Index;
+use tokenizing::{colors, Radix, TokenStream};
+use config::CONFIG;
+
+macro_rules! operands {
+    [] => {([$crate::Operand::Nothing; 3], 0)};
+    [$($x:expr),+ $(,)?] => {{
+        let mut operands = [$crate::Operand::Nothing; 3];
+        let mut idx = 0;
+        $(
+            idx += 1;
+            operands[idx - 1] = $x;
+        )*
+
+        (operands, idx)
+    }};
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+#[rustfmt::skip]
+pub enum Register {
+    R0, R1, R2, R3, R4, R5, R6, R7, R8, R9, R10,
+}
+
+impl Register {
+    pub fn as_str(&self) -> &'static str {
+        const REG_LITERALS: [&str; 11] =
+            ["r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10"];
+
+        REG_LITERALS[*self as usize]
+    }
+
+    #[inline]
+    fn get(num: u8) -> Result<Self, ErrorKind> {
+        // register fields are 4 bits wide, but there are only 11 registers
+        if num > Register::R10 as u8 {
+            return Err(ErrorKind::InvalidRegister);
+        }
+
+        Ok(unsafe { std::mem::transmute::<u8, Register>(num) })
+    }
+}
+
+/// Opcodes for eBPF instructions.
+///
+/// *The sign extending moves into 32-bit registers of cpu v4 aren't supported.*
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum Opcode {
+    #[default]
+    Invalid,
+    // *alu*
+    ADD,
+    SUB,
+    MUL,
+    DIV,
+    SDIV,
+    OR,
+    AND,
+    LSH,
+    RSH,
+    NEG,
+    MOD,
+    SMOD,
+    XOR,
+    MOV,
+    MOVSX8,
+    MOVSX16,
+    MOVSX32,
+    ARSH,
+    // *32-bit alu*
+    ADD32,
+    SUB32,
+    MUL32,
+    DIV32,
+    SDIV32,
+    OR32,
+    AND32,
+    LSH32,
+    RSH32,
+    NEG32,
+    MOD32,
+    SMOD32,
+    XOR32,
+    MOV32,
+    ARSH32,
+    // *byte swaps*
+    LE16,
+    LE32,
+    LE64,
+    BE16,
+    BE32,
+    BE64,
+    BSWAP16,
+    BSWAP32,
+    BSWAP64,
+    // *loads*
+    LDDW,
+    LDABSB,
+    LDABSH,
+    LDABSW,
+    LDINDB,
+    LDINDH,
+    LDINDW,
+    LDXB,
+    LDXH,
+    LDXW,
+    LDXDW,
+    LDXSB,
+    LDXSH,
+    LDXSW,
+    // *stores*
+    STB,
+    STH,
+    STW,
+    STDW,
+    STXB,
+    STXH,
+    STXW,
+    STXDW,
+    // *atomics*
+    ATOMIC_ADD,
+    ATOMIC_OR,
+    ATOMIC_AND,
+    ATOMIC_XOR,
+    ATOMIC_FETCH_ADD,
+    ATOMIC_FETCH_OR,
+    ATOMIC_FETCH_AND,
+    ATOMIC_FETCH_XOR,
+    ATOMIC_XCHG,
+    ATOMIC_CMPXCHG,
+    // *32-bit atomics*
+    ATOMIC_ADD32,
+    ATOMIC_OR32,
+    ATOMIC_AND32,
+    ATOMIC_XOR32,
+    ATOMIC_FETCH_ADD32,
+    ATOMIC_FETCH_OR32,
+    ATOMIC_FETCH_AND32,
+    ATOMIC_FETCH_XOR32,
+    ATOMIC_XCHG32,
+    ATOMIC_CMPXCHG32,
+    // *jumps*
+    JA,
+    JEQ,
+    JGT,
+    JGE,
+    JSET,
+    JNE,
+    JSGT,
+    JSGE,
+    JLT,
+    JLE,
+    JSLT,
+    JSLE,
+    CALL,
+    EXIT,
+    // *32-bit jumps*
+    JEQ32,
+    JGT32,
+    JGE32,
+    JSET32,
+    JNE32,
+    JSGT32,
+    JSGE32,
+    JLT32,
+    JLE32,
+    JSLT32,
+    JSLE32,
+}
+
+static OPCODE_NAMES: [&str; 110] = [
+    "invalid",
+    "add",
+    "sub",
+    "mul",
+    "div",
+    "sdiv",
+    "or",
+    "and",
+    "lsh",
+    "rsh",
+    "neg",
+    "mod",
+    "smod",
+    "xor",
+    "mov",
+    "movsx8",
+    "movsx16",
+    "movsx32",
+    "arsh",
+    "add32",
+    "sub32",
+    "mul32",
+    "div32",
+    "sdiv32",
+    "or32",
+    "and32",
+    "lsh32",
+    "rsh32",
+    "neg32",
+    "mod32",
+    "smod32",
+    "xor32",
+    "mov32",
+    "arsh32",
+    "le16",
+    "le32",
+    "le64",
+    "be16",
+    "be32",
+    "be64",
+    "bswap16",
+    "bswap32",
+    "bswap64",
+    "lddw",
+    "ldabsb",
+    "ldabsh",
+    "ldabsw",
+    "ldindb",
+    "ldindh",
+    "ldindw",
+    "ldxb",
+    "ldxh",
+    "ldxw",
+    "ldxdw",
+    "ldxsb",
+    "ldxsh",
+    "ldxsw",
+    "stb",
+    "sth",
+    "stw",
+    "stdw",
+    "stxb",
+    "stxh",
+    "stxw",
+    "stxdw",
+    "atomic_add",
+    "atomic_or",
+    "atomic_and",
+    "atomic_xor",
+    "atomic_fetch_add",
+    "atomic_fetch_or",
+    "atomic_fetch_and",
+    "atomic_fetch_xor",
+    "atomic_xchg",
+    "atomic_cmpxchg",
+    "atomic_add32",
+    "atomic_or32",
+    "atomic_and32",
+    "atomic_xor32",
+    "atomic_fetch_add32",
+    "atomic_fetch_or32",
+    "atomic_fetch_and32",
+    "atomic_fetch_xor32",
+    "atomic_xchg32",
+    "atomic_cmpxchg32",
+    "ja",
+    "jeq",
+    "jgt",
+    "jge",
+    "jset",
+    "jne",
+    "jsgt",
+    "jsge",
+    "jlt",
+    "jle",
+    "jslt",
+    "jsle",
+    "call",
+    "exit",
+    "jeq32",
+    "jgt32",
+    "jge32",
+    "jset32",
+    "jne32",
+    "jsgt32",
+    "jsge32",
+    "jlt32",
+    "jle32",
+    "jslt32",
+    "jsle32",
+];
+
+impl Opcode {
+    pub fn as_str(&self) -> &'static str {
+        OPCODE_NAMES[*self as usize]
+    }
+
+    fn is_load(&self) -> bool {
+        (Opcode::LDABSB as u8..=Opcode::LDXSW as u8).contains(&(*self as u8))
+    }
+
+    /// Stores and atomic read-modify-writes.
+    fn is_store(&self) -> bool {
+        (Opcode::STB as u8..=Opcode::ATOMIC_CMPXCHG32 as u8).contains(&(*self as u8))
+    }
+
+    /// Conditional jumps, whose last operand is where they go.
+    fn is_conditional(&self) -> bool {
+        (Opcode::JEQ as u8..=Opcode::JSLE as u8).contains(&(*self as u8))
+            || (Opcode::JEQ32 as u8..=Opcode::JSLE32 as u8).contains(&(*self as u8))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Operand {
+    #[default]
+    Nothing,
+    Register(Register),
+    Immediate(i32),
+    /// 64-bit constant loaded by `lddw`.
+    Wide(u64),
+    /// Memory reference of the form `[base+offset]`.
+    Memory(Register, i16),
+    /// Number of instructions to skip, relative to the next instruction.
+    PCOffset(i32),
+    /// Absolute address, resolved from a [`Operand::PCOffset`].
+    Address(u64),
+    /// Function provided by the kernel, referred to by it's number.
+    Helper(u32),
+    /// Value the loader patches in, like a map's file descriptor, of the form `map_fd(3)`.
+    Pseudo(&'static str, i32),
+    /// Address of a map's value, of the form `map_value(3)+16`.
+    PseudoValue(&'static str, i32, u32),
+}
+
+impl ToTokens for Operand {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        match self {
+            Self::Register(reg) => stream.push(reg.as_str(), CONFIG.colors.asm.register),
+            Self::Immediate(imm) => {
+                stream.push_immediate(*imm as i64, Radix::Decimal, CONFIG.colors.asm.immediate)
+            }
+            Self::Wide(imm) => {
+                stream.push_fmt(format_args!("{imm:#x}"), CONFIG.colors.asm.immediate)
+            }
+            Self::Memory(base, offset) => {
+                stream.push("[", CONFIG.colors.brackets);
+                stream.push(base.as_str(), CONFIG.colors.asm.register);
+                if *offset != 0 {
+                    let sign = if *offset < 0 { "-" } else { "+" };
+                    stream.push(sign, CONFIG.colors.asm.expr);
+                    stream.push_immediate(
+                        offset.unsigned_abs() as i64,
+                        Radix::Decimal,
+                        CONFIG.colors.asm.immediate,
+                    );
+                }
+                stream.push("]", CONFIG.colors.brackets);
+            }
+            Self::PCOffset(offset) => {
+                stream.push_fmt(format_args!("{offset:+}"), CONFIG.colors.asm.immediate)
+            }
+            Self::Address(addr) => {
+                match symbols.get_sym_by_addr(*addr as usize) {
+                    Some(symbol) => {
+                        for token in symbol.name() {
+                            stream.push_token(token.clone());
+                        }
+                    }
+                    None => {
+                        stream.push_fmt(format_args!("{addr:#x}"), CONFIG.colors.asm.pointer);
+                        symbols.tokenize_offset(stream, *addr as usize);
+                    }
+                }
+            }
+            Self::Helper(id) => match helpers::name(*id) {
+                Some(name) => stream.push(name, CONFIG.colors.asm.label),
+                None => stream.push_fmt(format_args!("{id}"), CONFIG.colors.asm.immediate),
+            },
+            Self::Pseudo(kind, imm) => {
+                stream.push(kind, CONFIG.colors.asm.component);
+                stream.push("(", CONFIG.colors.brackets);
+                stream.push_fmt(format_args!("{imm}"), CONFIG.colors.asm.immediate);
+                stream.push(")", CONFIG.colors.brackets);
+            }
+            Self::PseudoValue(kind, imm, offset) => {
+                Self::Pseudo(kind, *imm).tokenize(stream, symbols);
+                if *offset != 0 {
+                    stream.push("+", CONFIG.colors.asm.expr);
+                    stream.push_fmt(format_args!("{offset}"), CONFIG.colors.asm.immediate);
+                }
+            }
+            Self::Nothing => unreachable!("empty operand encountered"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    opcode: Opcode,
+    operands: [Operand; 3],
+    operand_count: usize,
+    /// Whether the instruction takes up two slots, which only `lddw` does.
+    wide: bool,
+}
+
+impl decoder::Decoded for Instruction {
+    fn width(&self) -> usize {
+        if self.wide {
+            16
+        } else {
+            8
+        }
+    }
+
+    fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+        for operand in &mut self.operands[..self.operand_count] {
+            if let Operand::PCOffset(offset) = *operand {
+                // relocatable objects leave calls for the loader to patch as `call -1`
+                if self.opcode == Opcode::CALL && offset == -1 {
+                    continue;
+                }
+
+                let offset = (offset as i64 + 1) * 8;
+                *operand = Operand::Address((addr as u64).wrapping_add_signed(offset));
+            }
+        }
+    }
+
+    fn xref(&self) -> Option<(usize, XrefKind)> {
+        let addr = self.operands[..self.operand_count].iter().find_map(|operand| match operand {
+            Operand::Address(addr) => Some(*addr as usize),
+            _ => None,
+        })?;
+
+        let kind = match self.opcode {
+            Opcode::CALL => XrefKind::Call,
+            // the address of a function, passed as a callback
+            Opcode::LDDW => XrefKind::Data,
+            _ => XrefKind::Jump,
+        };
+
+        Some((addr, kind))
+    }
+
+    fn is_terminator(&self) -> bool {
+        matches!(self.opcode, Opcode::JA | Opcode::EXIT)
+    }
+
+    fn kind(&self) -> InstKind {
+        match self.opcode {
+            Opcode::JA => InstKind::Branch,
+            opcode if opcode.is_conditional() => InstKind::Branch,
+            Opcode::CALL => InstKind::Call,
+            Opcode::EXIT => InstKind::Return,
+            opcode if opcode.is_load() => InstKind::Load,
+            opcode if opcode.is_store() => InstKind::Store,
+            _ => InstKind::Other,
+        }
+    }
+
+    fn is_conditional(&self) -> bool {
+        self.opcode.is_conditional()
+    }
+}
+
+impl ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        stream.push_mnemonic(self.opcode.as_str(), CONFIG.colors.asm.opcode);
+
+        // there are operands
+        if self.operand_count > 0 {
+            stream.push(" ", colors::WHITE);
+
+            // iterate through operands
+            for idx in 0..self.operand_count {
+                self.operands[idx].tokenize(stream, symbols);
+
+                // separator
+                if idx != self.operand_count - 1 {
+                    stream.push_separator(CONFIG.colors.asm.expr);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Decoder {
+    pub big_endian: bool,
+}
+
+impl decoder::Decodable for Decoder {
+    type Instruction = Instruction;
+
+    fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
+        decode(reader, self).map_err(|err| Error::new(err, 8))
+    }
+
+    fn max_width(&self) -> usize {
+        16
+    }
+
+    fn alignment(&self) -> usize {
+        8
+    }
+}
+
+/// Fields of a single 8 byte instruction slot.
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    code: u8,
+    dst: u8,
+    src: u8,
+    off: i16,
+    imm: i32,
+}
+
+impl Slot {
+    fn read(reader: &mut decoder::Reader, big_endian: bool) -> Result<Self, ErrorKind> {
+        let mut bytes = [0u8; 8];
+        reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+
+        // the order of the register fields is swapped along with the byte order
+        let (dst, src) = if big_endian {
+            (bytes[1] >> 4, bytes[1] & 0xf)
+        } else {
+            (bytes[1] & 0xf, bytes[1] >> 4)
+        };
+
+        let (off, imm) = if big_endian {
+            (
+                i16::from_be_bytes([bytes[2], bytes[3]]),
+                i32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            )
+        } else {
+            (
+                i16::from_le_bytes([bytes[2], bytes[3]]),
+                i32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            )
+        };
+
+        Ok(Self { code: bytes[0], dst, src, off, imm })
+    }
+
+    fn dst(&self) -> Result<Operand, ErrorKind> {
+        Register::get(self.dst).map(Operand::Register)
+    }
+
+    fn src(&self) -> Result<Operand, ErrorKind> {
+        Register::get(self.src).map(Operand::Register)
+    }
+
+    /// Whether the second operand is the source register instead of the immediate.
+    fn uses_src(&self) -> bool {
+        self.code & 0x08 != 0
+    }
+
+    /// Source register or immediate, depending on the instruction's source bit.
+    fn source(&self) -> Result<Operand, ErrorKind> {
+        if self.uses_src() {
+            self.src()
+        } else {
+            Ok(Operand::Immediate(self.imm))
+        }
+    }
+}
+
+fn instruction(opcode: Opcode, (operands, operand_count): ([Operand; 3], usize)) -> Instruction {
+    Instruction {
+        opcode,
+        operands,
+        operand_count,
+        wide: false,
+    }
+}
+
+fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    let slot = Slot::read(reader, decoder.big_endian)?;
+
+    match slot.code & 0x07 {
+        0x00 => decode_ld(reader, decoder, slot),
+        0x01 => decode_ldx(slot),
+        0x02 => decode_st(slot),
+        0x03 => decode_stx(slot),
+        0x04 => decode_alu(slot, false),
+        0x05 => decode_jmp(slot, false),
+        0x06 => decode_jmp(slot, true),
+        0x07 => decode_alu(slot, true),
+        _ => unreachable!(),
+    }
+}
+
+/// Decodes `lddw`, which spans two slots, and the legacy packet loads.
+fn decode_ld(
+    reader: &mut decoder::Reader,
+    decoder: &Decoder,
+    slot: Slot,
+) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let (opcode, indirect) = match slot.code {
+        0x18 => return decode_lddw(reader, decoder, slot),
+        0x20 => (LDABSW, false),
+        0x28 => (LDABSH, false),
+        0x30 => (LDABSB, false),
+        0x40 => (LDINDW, true),
+        0x48 => (LDINDH, true),
+        0x50 => (LDINDB, true),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    if indirect {
+        Ok(instruction(opcode, operands![slot.src()?, Operand::Immediate(slot.imm)]))
+    } else {
+        Ok(instruction(opcode, operands![Operand::Immediate(slot.imm)]))
+    }
+}
+
+fn decode_lddw(
+    reader: &mut decoder::Reader,
+    decoder: &Decoder,
+    slot: Slot,
+) -> Result<Instruction, ErrorKind> {
+    // the upper half of the constant is the immediate of an otherwise empty slot
+    let next = Slot::read(reader, decoder.big_endian)?;
+    if next.code != 0 || next.dst != 0 || next.src != 0 || next.off != 0 {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
+    let imm = slot.imm as u32 as u64 | (next.imm as u32 as u64) << 32;
+    let operand = match slot.src {
+        0 => Operand::Wide(imm),
+        1 => Operand::Pseudo("map_fd", slot.imm),
+        2 => Operand::PseudoValue("map_value", slot.imm, next.imm as u32),
+        3 => Operand::Pseudo("btf_id", slot.imm),
+        4 => Operand::PCOffset(slot.imm),
+        5 => Operand::Pseudo("map_idx", slot.imm),
+        6 => Operand::PseudoValue("map_idx_value", slot.imm, next.imm as u32),
+        _ => return Err(ErrorKind::InvalidOperand),
+    };
+
+    let mut inst = instruction(Opcode::LDDW, operands![slot.dst()?, operand]);
+    inst.wide = true;
+    Ok(inst)
+}
+
+/// Size of a memory access, `w`, `h`, `b` or `dw`.
+fn size(slot: Slot) -> usize {
+    (slot.code as usize >> 3) & 0b11
+}
+
+fn decode_ldx(slot: Slot) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let opcode = match slot.code & 0xe0 {
+        0x60 => [LDXW, LDXH, LDXB, LDXDW][size(slot)],
+        // sign extending
+        0x80 => [LDXSW, LDXSH, LDXSB, Invalid][size(slot)],
+        _ => Invalid,
+    };
+
+    if opcode == Invalid {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    let memory = Operand::Memory(Register::get(slot.src)?, slot.off);
+    Ok(instruction(opcode, operands![slot.dst()?, memory]))
+}
+
+fn decode_st(slot: Slot) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    if slot.code & 0xe0 != 0x60 {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    let opcode = [STW, STH, STB, STDW][size(slot)];
+    let memory = Operand::Memory(Register::get(slot.dst)?, slot.off);
+    Ok(instruction(opcode, operands![memory, Operand::Immediate(slot.imm)]))
+}
+
+fn decode_stx(slot: Slot) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let opcode = match slot.code & 0xe0 {
+        0x60 => [STXW, STXH, STXB, STXDW][size(slot)],
+        0xc0 => decode_atomic(slot)?,
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    let memory = Operand::Memory(Register::get(slot.dst)?, slot.off);
+    Ok(instruction(opcode, operands![memory, slot.src()?]))
+}
+
+/// Atomic read-modify-writes, which are picked by the immediate.
+fn decode_atomic(slot: Slot) -> Result<Opcode, ErrorKind> {
+    use Opcode::*;
+
+    let ops = match size(slot) {
+        // dw
+        0b11 => [
+            ATOMIC_ADD,
+            ATOMIC_OR,
+            ATOMIC_AND,
+            ATOMIC_XOR,
+            ATOMIC_FETCH_ADD,
+            ATOMIC_FETCH_OR,
+            ATOMIC_FETCH_AND,
+            ATOMIC_FETCH_XOR,
+            ATOMIC_XCHG,
+            ATOMIC_CMPXCHG,
+        ],
+        // w
+        0b00 => [
+            ATOMIC_ADD32,
+            ATOMIC_OR32,
+            ATOMIC_AND32,
+            ATOMIC_XOR32,
+            ATOMIC_FETCH_ADD32,
+            ATOMIC_FETCH_OR32,
+            ATOMIC_FETCH_AND32,
+            ATOMIC_FETCH_XOR32,
+            ATOMIC_XCHG32,
+            ATOMIC_CMPXCHG32,
+        ],
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    let idx = match slot.imm {
+        0x00 => 0,
+        0x40 => 1,
+        0x50 => 2,
+        0xa0 => 3,
+        0x01 => 4,
+        0x41 => 5,
+        0x51 => 6,
+        0xa1 => 7,
+        0xe1 => 8,
+        0xf1 => 9,
+        _ => return Err(ErrorKind::InvalidOperand),
+    };
+
+    Ok(ops[idx])
+}
+
+fn decode_alu(slot: Slot, is_64: bool) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let op = slot.code >> 4;
+
+    // byte swaps
+    if op == 0xd {
+        let opcode = match (is_64, slot.uses_src(), slot.imm) {
+            (false, false, 16) => LE16,
+            (false, false, 32) => LE32,
+            (false, false, 64) => LE64,
+            (false, true, 16) => BE16,
+            (false, true, 32) => BE32,
+            (false, true, 64) => BE64,
+            (true, false, 16) => BSWAP16,
+            (true, false, 32) => BSWAP32,
+            (true, false, 64) => BSWAP64,
+            _ => return Err(ErrorKind::InvalidOperand),
+        };
+
+        return Ok(instruction(opcode, operands![slot.dst()?]));
+    }
+
+    // division and modulo are signed if the offset is 1, moves sign extend with an offset
+    let signed = match (op, slot.off) {
+        (_, 0) => false,
+        (0x3 | 0x9, 1) => true,
+        (0xb, 8 | 16 | 32) if is_64 && slot.uses_src() => true,
+        (0xb, 8 | 16) if slot.uses_src() => return Err(ErrorKind::IncompleteDecoder),
+        _ => return Err(ErrorKind::InvalidOperand),
+    };
+
+    let opcode = match (op, is_64, signed) {
+        (0x0, true, _) => ADD,
+        (0x1, true, _) => SUB,
+        (0x2, true, _) => MUL,
+        (0x3, true, false) => DIV,
+        (0x3, true, true) => SDIV,
+        (0x4, true, _) => OR,
+        (0x5, true, _) => AND,
+        (0x6, true, _) => LSH,
+        (0x7, true, _) => RSH,
+        (0x8, true, _) => NEG,
+        (0x9, true, false) => MOD,
+        (0x9, true, true) => SMOD,
+        (0xa, true, _) => XOR,
+        (0xb, true, false) => MOV,
+        (0xb, true, true) => [MOVSX8, MOVSX16, MOVSX32][slot.off as usize / 16],
+        (0xc, true, _) => ARSH,
+        (0x0, false, _) => ADD32,
+        (0x1, false, _) => SUB32,
+        (0x2, false, _) => MUL32,
+        (0x3, false, false) => DIV32,
+        (0x3, false, true) => SDIV32,
+        (0x4, false, _) => OR32,
+        (0x5, false, _) => AND32,
+        (0x6, false, _) => LSH32,
+        (0x7, false, _) => RSH32,
+        (0x8, false, _) => NEG32,
+        (0x9, false, false) => MOD32,
+        (0x9, false, true) => SMOD32,
+        (0xa, false, _) => XOR32,
+        (0xb, false, _) => MOV32,
+        (0xc, false, _) => ARSH32,
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    if matches!(opcode, NEG | NEG32) {
+        return Ok(instruction(opcode, operands![slot.dst()?]));
+    }
+
+    Ok(instruction(opcode, operands![slot.dst()?, slot.source()?]))
+}
+
+fn decode_jmp(slot: Slot, is_32: bool) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let op = slot.code >> 4;
+    let opcode = match (op, is_32) {
+        // the 32-bit form has a wider offset in the immediate
+        (0x0, false) => return Ok(instruction(JA, operands![Operand::PCOffset(slot.off as i32)])),
+        (0x0, true) => return Ok(instruction(JA, operands![Operand::PCOffset(slot.imm)])),
+        (0x8, false) => return decode_call(slot),
+        (0x9, false) => return Ok(instruction(EXIT, operands![])),
+        (0x1, false) => JEQ,
+        (0x2, false) => JGT,
+        (0x3, false) => JGE,
+        (0x4, false) => JSET,
+        (0x5, false) => JNE,
+        (0x6, false) => JSGT,
+        (0x7, false) => JSGE,
+        (0xa, false) => JLT,
+        (0xb, false) => JLE,
+        (0xc, false) => JSLT,
+        (0xd, false) => JSLE,
+        (0x1, true) => JEQ32,
+        (0x2, true) => JGT32,
+        (0x3, true) => JGE32,
+        (0x4, true) => JSET32,
+        (0x5, true) => JNE32,
+        (0x6, true) => JSGT32,
+        (0x7, true) => JSGE32,
+        (0xa, true) => JLT32,
+        (0xb, true) => JLE32,
+        (0xc, true) => JSLT32,
+        (0xd, true) => JSLE32,
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    let target = Operand::PCOffset(slot.off as i32);
+    Ok(instruction(opcode, operands![slot.dst()?, slot.source()?, target]))
+}
+
+/// Calls either go to a helper function, another function of the program or a function of
+/// the kernel.
+fn decode_call(slot: Slot) -> Result<Instruction, ErrorKind> {
+    if slot.uses_src() {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    let target = match slot.src {
+        0 => Operand::Helper(slot.imm as u32),
+        1 => Operand::PCOffset(slot.imm),
+        2 => Operand::Pseudo("kfunc", slot.imm),
+        _ => return Err(ErrorKind::InvalidOperand),
+    };
+
+    Ok(instruction(Opcode::CALL, operands![target]))
+}
@@ -0,0 +1,169 @@
+#![cfg(test)]
+
+use crate::Decoder;
+use decoder::{testing, Decodable, Decoded, ErrorKind, InstKind, XrefKind};
+
+fn decode(bytes: &[u8]) -> String {
+    testing::decode(&Decoder::default(), bytes)
+}
+
+fn decode_error(bytes: &[u8]) -> ErrorKind {
+    testing::decode_error(&Decoder::default(), bytes)
+}
+
+#[test]
+fn alu() {
+    assert_eq!(decode(&[0xb7, 0x01, 0, 0, 0, 0, 0, 0]), "mov r1, 0");
+    assert_eq!(decode(&[0xbf, 0xa2, 0, 0, 0, 0, 0, 0]), "mov r2, r10");
+    assert_eq!(decode(&[0x07, 0x02, 0, 0, 0xfc, 0xff, 0xff, 0xff]), "add r2, -4");
+    assert_eq!(decode(&[0x67, 0x01, 0, 0, 0x20, 0, 0, 0]), "lsh r1, 32");
+    assert_eq!(decode(&[0xc7, 0x01, 0, 0, 0x20, 0, 0, 0]), "arsh r1, 32");
+    assert_eq!(decode(&[0x87, 0x03, 0, 0, 0, 0, 0, 0]), "neg r3");
+    assert_eq!(decode(&[0x0c, 0x21, 0, 0, 0, 0, 0, 0]), "add32 r1, r2");
+    assert_eq!(decode(&[0xb4, 0x00, 0, 0, 0x01, 0, 0, 0]), "mov32 r0, 1");
+    assert_eq!(decode(&[0xa4, 0x05, 0, 0, 0xff, 0, 0, 0]), "xor32 r5, 255");
+    assert_eq!(decode_error(&[0xb7, 0x0b, 0, 0, 0, 0, 0, 0]), ErrorKind::InvalidRegister);
+    assert_eq!(decode_error(&[0xe7, 0x01, 0, 0, 0, 0, 0, 0]), ErrorKind::InvalidOpcode);
+}
+
+#[test]
+fn signed() {
+    assert_eq!(decode(&[0x3f, 0x21, 0x01, 0, 0, 0, 0, 0]), "sdiv r1, r2");
+    assert_eq!(decode(&[0x94, 0x01, 0x01, 0, 0x03, 0, 0, 0]), "smod32 r1, 3");
+    assert_eq!(decode(&[0xbf, 0x21, 0x10, 0, 0, 0, 0, 0]), "movsx16 r1, r2");
+    assert_eq!(decode(&[0xbf, 0x21, 0x20, 0, 0, 0, 0, 0]), "movsx32 r1, r2");
+    assert_eq!(decode_error(&[0x0f, 0x21, 0x01, 0, 0, 0, 0, 0]), ErrorKind::InvalidOperand);
+}
+
+#[test]
+fn byte_swaps() {
+    assert_eq!(decode(&[0xd4, 0x01, 0, 0, 0x10, 0, 0, 0]), "le16 r1");
+    assert_eq!(decode(&[0xdc, 0x01, 0, 0, 0x40, 0, 0, 0]), "be64 r1");
+    assert_eq!(decode(&[0xd7, 0x01, 0, 0, 0x20, 0, 0, 0]), "bswap32 r1");
+    assert_eq!(decode_error(&[0xdc, 0x01, 0, 0, 0x08, 0, 0, 0]), ErrorKind::InvalidOperand);
+}
+
+#[test]
+fn memory() {
+    assert_eq!(decode(&[0x61, 0x12, 0x04, 0, 0, 0, 0, 0]), "ldxw r2, [r1+4]");
+    assert_eq!(decode(&[0x79, 0x11, 0, 0, 0, 0, 0, 0]), "ldxdw r1, [r1]");
+    assert_eq!(decode(&[0x91, 0x12, 0x02, 0, 0, 0, 0, 0]), "ldxsb r2, [r1+2]");
+    assert_eq!(decode(&[0x63, 0x1a, 0xfc, 0xff, 0, 0, 0, 0]), "stxw [r10-4], r1");
+    assert_eq!(decode(&[0x7a, 0x0a, 0xf8, 0xff, 0x2a, 0, 0, 0]), "stdw [r10-8], 42");
+    assert_eq!(decode(&[0x72, 0x0a, 0xff, 0xff, 0x01, 0, 0, 0]), "stb [r10-1], 1");
+    assert_eq!(decode(&[0x30, 0, 0, 0, 0x0c, 0, 0, 0]), "ldabsb 12");
+    assert_eq!(decode(&[0x40, 0x10, 0, 0, 0x0e, 0, 0, 0]), "ldindw r1, 14");
+}
+
+#[test]
+fn atomics() {
+    assert_eq!(decode(&[0xdb, 0x21, 0, 0, 0, 0, 0, 0]), "atomic_add [r1], r2");
+    assert_eq!(decode(&[0xc3, 0x21, 0x08, 0, 0x01, 0, 0, 0]), "atomic_fetch_add32 [r1+8], r2");
+    assert_eq!(decode(&[0xdb, 0x21, 0, 0, 0xe1, 0, 0, 0]), "atomic_xchg [r1], r2");
+    assert_eq!(decode(&[0xdb, 0x21, 0, 0, 0xf1, 0, 0, 0]), "atomic_cmpxchg [r1], r2");
+    assert_eq!(decode_error(&[0xdb, 0x21, 0, 0, 0x10, 0, 0, 0]), ErrorKind::InvalidOperand);
+}
+
+#[test]
+fn lddw() {
+    #[rustfmt::skip]
+    let imm = [
+        0x18, 0x01, 0, 0, 0x78, 0x56, 0x34, 0x12,
+        0x00, 0x00, 0, 0, 0xef, 0xcd, 0xab, 0x90,
+    ];
+    assert_eq!(decode(&imm), "lddw r1, 0x90abcdef12345678");
+
+    #[rustfmt::skip]
+    let map_fd = [
+        0x18, 0x11, 0, 0, 0x03, 0, 0, 0,
+        0x00, 0x00, 0, 0, 0x00, 0, 0, 0,
+    ];
+    assert_eq!(decode(&map_fd), "lddw r1, map_fd(3)");
+
+    #[rustfmt::skip]
+    let map_value = [
+        0x18, 0x21, 0, 0, 0x03, 0, 0, 0,
+        0x00, 0x00, 0, 0, 0x10, 0, 0, 0,
+    ];
+    assert_eq!(decode(&map_value), "lddw r1, map_value(3)+16");
+
+    // the second slot has to be empty
+    let mut reader = decoder::Reader::new(&[0x18, 0x01, 0, 0, 0, 0, 0, 0, 0xb7, 0, 0, 0, 0, 0, 0, 0]);
+    assert!(Decoder::default().decode(&mut reader).is_err());
+    let mut reader = decoder::Reader::new(&[0x18, 0x01, 0, 0, 0, 0, 0, 0]);
+    assert!(Decoder::default().decode(&mut reader).is_err());
+}
+
+#[test]
+fn jumps() {
+    #[rustfmt::skip]
+    let code = [
+        0x15, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x5d, 0x21, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x26, 0x01, 0xfd, 0xff, 0x64, 0x00, 0x00, 0x00,
+        0x05, 0x00, 0xfc, 0xff, 0x00, 0x00, 0x00, 0x00,
+        0x95, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    let mut symbols = debugvault::Index::default();
+    symbols.insert_func(0x1000, "start");
+    assert_eq!(
+        testing::decode_instructions(Decoder::default(), &code, 0x1000, &symbols),
+        [
+            "jeq r1, 0, 0x1018",
+            "jne r1, r2, 0x1018",
+            "jgt32 r1, 100, start",
+            "ja start",
+            "exit",
+        ]
+    );
+
+    assert_eq!(decode(&[0x15, 0x01, 0x07, 0, 0, 0, 0, 0]), "jeq r1, 0, +7");
+    assert_eq!(decode(&[0x06, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]), "ja -1");
+}
+
+#[test]
+fn calls() {
+    assert_eq!(decode(&[0x85, 0, 0, 0, 0x01, 0, 0, 0]), "call bpf_map_lookup_elem");
+    assert_eq!(decode(&[0x85, 0, 0, 0, 0x06, 0, 0, 0]), "call bpf_trace_printk");
+    assert_eq!(decode(&[0x85, 0, 0, 0, 0xd3, 0, 0, 0]), "call bpf_cgrp_storage_delete");
+    assert_eq!(decode(&[0x85, 0, 0, 0, 0xe8, 0x03, 0, 0]), "call 1000");
+    assert_eq!(decode(&[0x85, 0x20, 0, 0, 0x2a, 0, 0, 0]), "call kfunc(42)");
+
+    // calls to functions of the program are relative
+    let decoder = Decoder::default();
+    let mut reader = decoder::Reader::new(&[0x85, 0x10, 0, 0, 0x07, 0, 0, 0]);
+    let mut inst = decoder.decode(&mut reader).unwrap();
+    inst.update_rel_addrs(0x50, None);
+    assert_eq!(inst.xref(), Some((0x90, XrefKind::Call)));
+
+    // waiting on a relocation
+    let mut reader = decoder::Reader::new(&[0x85, 0x10, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+    let mut inst = decoder.decode(&mut reader).unwrap();
+    inst.update_rel_addrs(0x50, None);
+    assert_eq!(inst.xref(), None);
+}
+
+#[test]
+fn big_endian() {
+    let decoder = Decoder { big_endian: true };
+    let bytes = [0x63, 0xa1, 0xff, 0xfc, 0, 0, 0, 0];
+    assert_eq!(testing::decode(&decoder, &bytes), "stxw [r10-4], r1");
+}
+
+#[test]
+fn kinds() {
+    let kind = |bytes: &[u8]| {
+        let decoder = Decoder::default();
+        let inst = decoder.decode(&mut decoder::Reader::new(bytes)).unwrap();
+        (inst.kind(), inst.is_conditional(), inst.is_terminator())
+    };
+
+    assert_eq!(kind(&[0x05, 0, 0x01, 0, 0, 0, 0, 0]), (InstKind::Branch, false, true));
+    assert_eq!(kind(&[0x15, 0x01, 0x01, 0, 0, 0, 0, 0]), (InstKind::Branch, true, false));
+    assert_eq!(kind(&[0x85, 0, 0, 0, 0x01, 0, 0, 0]), (InstKind::Call, false, false));
+    assert_eq!(kind(&[0x95, 0, 0, 0, 0, 0, 0, 0]), (InstKind::Return, false, true));
+    assert_eq!(kind(&[0x61, 0x12, 0, 0, 0, 0, 0, 0]), (InstKind::Load, false, false));
+    assert_eq!(kind(&[0xdb, 0x21, 0, 0, 0, 0, 0, 0]), (InstKind::Store, false, false));
+    assert_eq!(kind(&[0xb7, 0x01, 0, 0, 0, 0, 0, 0]), (InstKind::Other, false, false));
+}
@@ -1,17 +1,17 @@
-//! MIPS V disassembler.
+//! MIPS32/MIPS64 disassembler.
 
 mod tests;
 
-use decoder::{Error, ErrorKind};
+use decoder::{Error, ErrorKind, ToTokens, XrefKind};
 use debugvault::Index;
-use std::borrow::Cow;
-use tokenizing::{colors, TokenStream};
+use once_cell::sync::Lazy;
+use tokenizing::{colors, Radix, TokenStream};
 use config::CONFIG;
 
 macro_rules! operands {
-    [] => {([$crate::EMPTY_OPERAND; 3], 0)};
+    [] => {([$crate::Operand::Nothing; 4], 0)};
     [$($x:expr),+ $(,)?] => {{
-        let mut operands = [$crate::EMPTY_OPERAND; 3];
+        let mut operands = [$crate::Operand::Nothing; 4];
         let mut idx = 0;
         $(
             idx += 1;
@@ -22,16 +22,6 @@ macro_rules! operands {
     }};
 }
 
-#[rustfmt::skip]
-pub const REGISTERS: [&str; 32] = [
-    "zero", "at",
-    "v0", "v1",
-    "a0", "a1", "a2", "a3",
-    "t0", "t1", "t2", "t3", "t4", "t5", "t6", "t7",
-    "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7","t8", "t9",
-    "k0", "k1", "gp", "sp", "fp", "ra",
-];
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
 #[rustfmt::skip]
@@ -41,35 +31,526 @@ pub enum Register {
     A0, A1, A2, A3,
     T0, T1, T2, T3, T4, T5, T6, T7,
     S0, S1, S2, S3, S4, S5, S6, S7, T8, T9,
-    K0, K1, Gp, Sp, Fp, Ra
+    K0, K1, Gp, Sp, Fp, Ra,
+    F0, F1, F2, F3, F4, F5, F6, F7,
+    F8, F9, F10, F11, F12, F13, F14, F15,
+    F16, F17, F18, F19, F20, F21, F22, F23,
+    F24, F25, F26, F27, F28, F29, F30, F31
+}
+
+impl Register {
+    pub fn as_str(&self) -> &'static str {
+        #[rustfmt::skip]
+        const REG_LITERALS: [&str; 64] = [
+            "zero", "at",
+            "v0", "v1",
+            "a0", "a1", "a2", "a3",
+            "t0", "t1", "t2", "t3", "t4", "t5", "t6", "t7",
+            "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7", "t8", "t9",
+            "k0", "k1", "gp", "sp", "fp", "ra",
+            "f0", "f1", "f2", "f3", "f4", "f5", "f6", "f7",
+            "f8", "f9", "f10", "f11", "f12", "f13", "f14", "f15",
+            "f16", "f17", "f18", "f19", "f20", "f21", "f22", "f23",
+            "f24", "f25", "f26", "f27", "f28", "f29", "f30", "f31"
+        ];
+
+        REG_LITERALS[*self as usize]
+    }
+}
+
+impl Register {
+    #[inline]
+    fn get(num: u32) -> Self {
+        // register fields are 5 bits wide, so every value is one of the 32 variants
+        unsafe { std::mem::transmute(num & 0b11111) }
+    }
+
+    #[inline]
+    fn get_fp(num: u32) -> Self {
+        unsafe { std::mem::transmute((num & 0b11111) + 32) }
+    }
+}
+
+/// Opcodes for MIPS32 and MIPS64 instructions.
+///
+/// *Floating point arithmetic and the microMIPS/MIPS16e encodings aren't supported.*
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u32)]
+pub enum Opcode {
+    #[default]
+    Invalid,
+    // *psuedo instructions*
+    NOP,
+    MOVE,
+    LI,
+    B,
+    BAL,
+    BEQZ,
+    BNEZ,
+    NEGU,
+    NOT,
+    // *special*
+    SLL,
+    SRL,
+    ROTR,
+    SRA,
+    SLLV,
+    SRLV,
+    ROTRV,
+    SRAV,
+    JR,
+    JALR,
+    MOVZ,
+    MOVN,
+    SYSCALL,
+    BREAK,
+    SYNC,
+    MFHI,
+    MTHI,
+    MFLO,
+    MTLO,
+    DSLLV,
+    DSRLV,
+    DSRAV,
+    MULT,
+    MULTU,
+    DIV,
+    DIVU,
+    DMULT,
+    DMULTU,
+    DDIV,
+    DDIVU,
+    ADD,
+    ADDU,
+    SUB,
+    SUBU,
+    AND,
+    OR,
+    XOR,
+    NOR,
+    SLT,
+    SLTU,
+    DADD,
+    DADDU,
+    DSUB,
+    DSUBU,
+    TGE,
+    TGEU,
+    TLT,
+    TLTU,
+    TEQ,
+    TNE,
+    DSLL,
+    DSRL,
+    DSRA,
+    DSLL32,
+    DSRL32,
+    DSRA32,
+    // *regimm*
+    BLTZ,
+    BGEZ,
+    BLTZL,
+    BGEZL,
+    BLTZAL,
+    BGEZAL,
+    BLTZALL,
+    BGEZALL,
+    // *jumps, branches and immediates*
+    J,
+    JAL,
+    BEQ,
+    BNE,
+    BLEZ,
+    BGTZ,
+    BEQL,
+    BNEL,
+    BLEZL,
+    BGTZL,
+    ADDI,
+    ADDIU,
+    SLTI,
+    SLTIU,
+    ANDI,
+    ORI,
+    XORI,
+    LUI,
+    DADDI,
+    DADDIU,
+    // *coprocessor 0*
+    MFC0,
+    MTC0,
+    DMFC0,
+    DMTC0,
+    ERET,
+    DERET,
+    WAIT,
+    TLBR,
+    TLBWI,
+    TLBWR,
+    TLBP,
+    // *coprocessor 1 moves*
+    MFC1,
+    DMFC1,
+    MTC1,
+    DMTC1,
+    // *special2*
+    MADD,
+    MADDU,
+    MUL,
+    MSUB,
+    MSUBU,
+    CLZ,
+    CLO,
+    DCLZ,
+    DCLO,
+    // *special3*
+    EXT,
+    DEXT,
+    INS,
+    DINS,
+    WSBH,
+    SEB,
+    SEH,
+    RDHWR,
+    // *loads and stores*
+    LB,
+    LH,
+    LWL,
+    LW,
+    LBU,
+    LHU,
+    LWR,
+    LWU,
+    SB,
+    SH,
+    SWL,
+    SW,
+    SDL,
+    SDR,
+    SWR,
+    CACHE,
+    LL,
+    LWC1,
+    PREF,
+    LLD,
+    LDC1,
+    LD,
+    SC,
+    SWC1,
+    SCD,
+    SDC1,
+    SD,
+    LDL,
+    LDR,}
+
+static OPCODE_NAMES: [&str; 155] = [
+    "invalid",
+    "nop",
+    "move",
+    "li",
+    "b",
+    "bal",
+    "beqz",
+    "bnez",
+    "negu",
+    "not",
+    "sll",
+    "srl",
+    "rotr",
+    "sra",
+    "sllv",
+    "srlv",
+    "rotrv",
+    "srav",
+    "jr",
+    "jalr",
+    "movz",
+    "movn",
+    "syscall",
+    "break",
+    "sync",
+    "mfhi",
+    "mthi",
+    "mflo",
+    "mtlo",
+    "dsllv",
+    "dsrlv",
+    "dsrav",
+    "mult",
+    "multu",
+    "div",
+    "divu",
+    "dmult",
+    "dmultu",
+    "ddiv",
+    "ddivu",
+    "add",
+    "addu",
+    "sub",
+    "subu",
+    "and",
+    "or",
+    "xor",
+    "nor",
+    "slt",
+    "sltu",
+    "dadd",
+    "daddu",
+    "dsub",
+    "dsubu",
+    "tge",
+    "tgeu",
+    "tlt",
+    "tltu",
+    "teq",
+    "tne",
+    "dsll",
+    "dsrl",
+    "dsra",
+    "dsll32",
+    "dsrl32",
+    "dsra32",
+    "bltz",
+    "bgez",
+    "bltzl",
+    "bgezl",
+    "bltzal",
+    "bgezal",
+    "bltzall",
+    "bgezall",
+    "j",
+    "jal",
+    "beq",
+    "bne",
+    "blez",
+    "bgtz",
+    "beql",
+    "bnel",
+    "blezl",
+    "bgtzl",
+    "addi",
+    "addiu",
+    "slti",
+    "sltiu",
+    "andi",
+    "ori",
+    "xori",
+    "lui",
+    "daddi",
+    "daddiu",
+    "mfc0",
+    "mtc0",
+    "dmfc0",
+    "dmtc0",
+    "eret",
+    "deret",
+    "wait",
+    "tlbr",
+    "tlbwi",
+    "tlbwr",
+    "tlbp",
+    "mfc1",
+    "dmfc1",
+    "mtc1",
+    "dmtc1",
+    "madd",
+    "maddu",
+    "mul",
+    "msub",
+    "msubu",
+    "clz",
+    "clo",
+    "dclz",
+    "dclo",
+    "ext",
+    "dext",
+    "ins",
+    "dins",
+    "wsbh",
+    "seb",
+    "seh",
+    "rdhwr",
+    "lb",
+    "lh",
+    "lwl",
+    "lw",
+    "lbu",
+    "lhu",
+    "lwr",
+    "lwu",
+    "sb",
+    "sh",
+    "swl",
+    "sw",
+    "sdl",
+    "sdr",
+    "swr",
+    "cache",
+    "ll",
+    "lwc1",
+    "pref",
+    "lld",
+    "ldc1",
+    "ld",
+    "sc",
+    "swc1",
+    "scd",
+    "sdc1",
+    "sd",
+    "ldl",
+    "ldr",];
+
+impl Opcode {
+    pub fn as_str(&self) -> &'static str {
+        OPCODE_NAMES[*self as usize]
+    }
+
+    /// Whether the instruction following this one executes before the branch or jump is taken.
+    fn has_delay_slot(&self) -> bool {
+        use Opcode::*;
+
+        matches!(
+            self,
+            B | BAL
+                | BEQZ
+                | BNEZ
+                | JR
+                | JALR
+                | BLTZ
+                | BGEZ
+                | BLTZL
+                | BGEZL
+                | BLTZAL
+                | BGEZAL
+                | BLTZALL
+                | BGEZALL
+                | J
+                | JAL
+                | BEQ
+                | BNE
+                | BLEZ
+                | BGTZ
+                | BEQL
+                | BNEL
+                | BLEZL
+                | BGTZL
+        )
+    }
+
+    /// Whether the instruction only exists in MIPS64.
+    fn is_64(&self) -> bool {
+        use Opcode::*;
+
+        matches!(
+            self,
+            DSLLV
+                | DSRLV
+                | DSRAV
+                | DMULT
+                | DMULTU
+                | DDIV
+                | DDIVU
+                | DADD
+                | DADDU
+                | DSUB
+                | DSUBU
+                | DSLL
+                | DSRL
+                | DSRA
+                | DSLL32
+                | DSRL32
+                | DSRA32
+                | DADDI
+                | DADDIU
+                | DMFC0
+                | DMTC0
+                | DMFC1
+                | DMTC1
+                | DCLZ
+                | DCLO
+                | DEXT
+                | DINS
+                | LWU
+                | SDL
+                | SDR
+                | LLD
+                | LD
+                | SCD
+                | SD
+                | LDL
+                | LDR
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum Operand {
-    Register(Register),
-    Immediate(i64),
     #[default]
     Nothing,
+    Register(Register),
+    Immediate(i32),
+    /// Coprocessor or hardware register, referred to by it's number.
+    Coprocessor(u8),
+    /// Offset relative to the instruction's address, used by branches. Until it's resolved it's
+    /// shown as encoded, in instructions relative to the delay slot.
+    PCOffset(i32),
+    /// Target of a jump within the 256 MB region of the delay slot.
+    Region(u32),
+    /// Absolute address, resolved from a [`Operand::PCOffset`] or [`Operand::Region`].
+    Address(u64),
+    /// Memory reference of the form `offset(base)`.
+    Memory(Register, i32),
 }
 
-enum Format {
-    R,
-    I,
-    J,
-}
-
-struct TableInstruction {
-    mnemomic: &'static str,
-    #[allow(dead_code)]
-    desc: &'static str,
-    format: &'static [usize],
+impl ToTokens for Operand {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        match self {
+            Self::Register(reg) => stream.push(reg.as_str(), CONFIG.colors.asm.register),
+            Self::Immediate(imm) => {
+                stream.push_immediate(*imm as i64, Radix::Decimal, CONFIG.colors.asm.immediate)
+            }
+            Self::Coprocessor(num) => {
+                stream.push_fmt(format_args!("${num}"), CONFIG.colors.asm.register)
+            }
+            Self::PCOffset(offset) => {
+                let field = (*offset as i64 - 4) >> 2;
+                stream.push_immediate(field, Radix::Hex, CONFIG.colors.asm.immediate)
+            }
+            Self::Region(target) => {
+                stream.push_fmt(format_args!("{target:#x}"), CONFIG.colors.asm.immediate)
+            }
+            Self::Address(addr) => {
+                match symbols.get_sym_by_addr(*addr as usize) {
+                    Some(symbol) => {
+                        for token in symbol.name() {
+                            stream.push_token(token.clone());
+                        }
+                    }
+                    None => {
+                        stream.push_fmt(format_args!("{addr:#x}"), CONFIG.colors.asm.pointer);
+                        symbols.tokenize_offset(stream, *addr as usize);
+                    }
+                }
+            }
+            Self::Memory(base, offset) => {
+                stream.push_immediate(*offset as i64, Radix::Decimal, CONFIG.colors.asm.immediate);
+                stream.push("(", CONFIG.colors.brackets);
+                stream.push(base.as_str(), CONFIG.colors.asm.register);
+                stream.push(")", CONFIG.colors.brackets);
+            }
+            Self::Nothing => unreachable!("empty operand encountered"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Instruction {
-    mnemomic: &'static str,
-    operands: [Cow<'static, str>; 3],
+    opcode: Opcode,
+    operands: [Operand; 4],
     operand_count: usize,
+    /// Branch or jump this instruction executes in the delay slot of.
+    delay_slot: Option<Opcode>,
 }
 
 impl decoder::Decoded for Instruction {
@@ -77,176 +558,188 @@ impl decoder::Decoded for Instruction {
         4
     }
 
-    fn update_rel_addrs(&mut self, _: usize, _: Option<&Instruction>) {}
-}
+    fn update_rel_addrs(&mut self, addr: usize, prev_inst: Option<&Instruction>) {
+        for operand in &mut self.operands[..self.operand_count] {
+            match *operand {
+                Operand::PCOffset(offset) => {
+                    let addr = (addr as u64).wrapping_add_signed(offset as i64);
+                    *operand = Operand::Address(addr);
+                }
+                Operand::Region(target) => {
+                    // the upper bits are taken from the address of the delay slot
+                    let region = (addr as u64 + 4) & !0x0fff_ffff;
+                    *operand = Operand::Address(region | target as u64);
+                }
+                _ => {}
+            }
+        }
 
-#[derive(Default)]
-pub struct Decoder;
+        if let Some(prev_inst) = prev_inst {
+            if prev_inst.opcode.has_delay_slot() {
+                self.delay_slot = Some(prev_inst.opcode);
+            }
+            self.fuse(prev_inst);
+        }
+    }
 
-impl decoder::Decodable for Decoder {
-    type Instruction = Instruction;
+    fn xref(&self) -> Option<(usize, XrefKind)> {
+        use Opcode::*;
 
-    fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
-        decode(reader).map_err(|err| Error::new(err, 4))
+        // only branches and jumps have addresses
+        let addr = self.operands[..self.operand_count].iter().find_map(|operand| match operand {
+            Operand::Address(addr) => Some(*addr as usize),
+            _ => None,
+        })?;
+
+        let kind = match self.opcode {
+            JAL | BAL | BLTZAL | BGEZAL | BLTZALL | BGEZALL => XrefKind::Call,
+            _ => XrefKind::Jump,
+        };
+
+        Some((addr, kind))
     }
 
-    fn max_width(&self) -> usize {
-        4
+    fn is_terminator(&self) -> bool {
+        // unconditional jumps take effect after their delay slot
+        self.opcode == Opcode::ERET
+            || matches!(self.delay_slot, Some(Opcode::B | Opcode::J | Opcode::JR))
     }
 }
 
-fn decode(reader: &mut decoder::Reader) -> Result<Instruction, ErrorKind> {
-    let mut bytes = [0u8; 4];
-    reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
-    let dword = u32::from_be_bytes(bytes) as usize;
-
-    // nop instruction isn't included in any MIPS spec
-    if dword == 0b00000000_00000000_00000000_00000000 {
-        let (operands, operand_count) = operands![];
-        return Ok(Instruction {
-            mnemomic: "nop",
-            operands,
-            operand_count,
-        });
-    }
+impl Instruction {
+    /// Fuses an instruction with a previous `lui` that materializes the upper 16 bits of it's
+    /// constant.
+    fn fuse(&mut self, prev_inst: &Instruction) {
+        let (reg, hi) = match (prev_inst.opcode, prev_inst.operands[0], prev_inst.operands[1]) {
+            (Opcode::LUI, Operand::Register(reg), Operand::Immediate(hi)) => {
+                (reg, hi.wrapping_shl(16))
+            }
+            _ => return,
+        };
 
-    // break instruction has a unique instruction format
-    if dword & 0b111111 == 0b001101 {
-        let (operands, operand_count) = operands![];
-        return Ok(Instruction {
-            mnemomic: "break",
-            operands,
-            operand_count,
-        });
-    }
+        let operands = (self.operands[0], self.operands[1], self.operands[2]);
+        let imm = match (self.opcode, operands) {
+            // lui rt, hi; addiu rt, rt, lo => li rt, imm
+            (
+                Opcode::ADDIU | Opcode::DADDIU,
+                (Operand::Register(rt), Operand::Register(rs), Operand::Immediate(lo)),
+            ) if rt == reg && rs == reg => hi.wrapping_add(lo),
+            // lui rt, hi; ori rt, rt, lo => li rt, imm
+            (
+                Opcode::ORI,
+                (Operand::Register(rt), Operand::Register(rs), Operand::Immediate(lo)),
+            ) if rt == reg && rs == reg => hi | lo,
+            _ => return,
+        };
 
-    let mut operands = [EMPTY_OPERAND; 3];
-    let opcode = dword >> 26;
-    let funct = dword & 0b111111;
+        self.opcode = Opcode::LI;
+        self.operands[1] = Operand::Immediate(imm);
+        self.operand_count = 2;
+    }
+}
 
-    let (format, inst) = match opcode {
-        0 => (
-            Format::R,
-            R_TYPES.get(funct).ok_or(ErrorKind::InvalidOpcode)?,
-        ),
-        2 | 3 => (
-            Format::J,
-            J_TYPES.get(opcode).ok_or(ErrorKind::InvalidOpcode)?,
-        ),
-        _ => (
-            Format::I,
-            I_TYPES.get(opcode).ok_or(ErrorKind::InvalidOpcode)?,
-        ),
-    };
+#[derive(Debug, Clone, Copy)]
+pub struct Decoder {
+    pub is_64: bool,
+    pub big_endian: bool,
+}
 
-    let rs = dword >> 21 & 0b11111;
-    let rt = dword >> 16 & 0b11111;
-    let rd = dword >> 11 & 0b11111;
+impl decoder::Decodable for Decoder {
+    type Instruction = Instruction;
 
-    if inst.mnemomic.is_empty() {
-        return Err(ErrorKind::IncompleteDecoder);
+    fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
+        decode(reader, self).map_err(|err| Error::new(err, 4))
     }
 
-    match format {
-        Format::R => {
-            match (REGISTERS.get(rs), REGISTERS.get(rt), REGISTERS.get(rd)) {
-                (Some(_), Some(_), Some(_)) => {}
-                _ => return Err(ErrorKind::InvalidRegister),
-            }
+    fn max_width(&self) -> usize {
+        4
+    }
 
-            let shamt = dword >> 6 & 0b11111;
-
-            for idx in 0..inst.format.len() {
-                // index into next operand
-                let mask = inst.format[idx];
-
-                // operand specified by the bitmask
-                let operand = match mask {
-                    0 => rd,
-                    1 => rt,
-                    2 => rs,
-                    3 => shamt,
-                    _ => unsafe { core::hint::unreachable_unchecked() },
-                };
-
-                if operand == shamt {
-                    operands[idx] = Cow::Owned(format!("0x{shamt:x}"));
-                } else {
-                    operands[idx] = Cow::Borrowed(REGISTERS[operand]);
-                }
-            }
+    fn alignment(&self) -> usize {
+        4
+    }
+}
 
-            Ok(Instruction {
-                mnemomic: inst.mnemomic,
-                operands,
-                operand_count: inst.format.len(),
-            })
-        }
-        Format::I => {
-            match (REGISTERS.get(rs), REGISTERS.get(rt)) {
-                (Some(_), Some(_)) => {}
-                _ => return Err(ErrorKind::InvalidRegister),
-            }
+fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
 
-            let immediate = dword & 0b11111111_11111111;
-
-            // check if the instruction uses an offset (load/store instructions)
-            if inst.format == [1, 3, 2] {
-                let (operands, operand_count) = operands![
-                    Cow::Borrowed(REGISTERS[rt]),
-                    Cow::Borrowed(REGISTERS[rs]),
-                    Cow::Owned(format!("{immediate:#x}")),
-                ];
-
-                return Ok(Instruction {
-                    mnemomic: inst.mnemomic,
-                    operands,
-                    operand_count,
-                });
-            }
+    let mut bytes = [0u8; 4];
+    reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+    let dword = if decoder.big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    };
 
-            for idx in 0..inst.format.len() {
-                // index into next operand
-                let mask = inst.format[idx];
-
-                // operand specified by the bitmask
-                let operand = match mask {
-                    0 => rd,
-                    1 => rt,
-                    2 => rs,
-                    3 => immediate,
-                    _ => unsafe { core::hint::unreachable_unchecked() },
-                };
-
-                if operand == immediate {
-                    operands[idx] = Cow::Owned(format!("0x{immediate:x}"));
-                } else {
-                    operands[idx] = Cow::Borrowed(REGISTERS[operand]);
-                }
-            }
+    let inst = match dword >> 26 {
+        0b000000 => decode_special(dword)?,
+        0b000001 => decode_regimm(dword)?,
+        0b000010 => decode_jump(J, dword),
+        0b000011 => decode_jump(JAL, dword),
+        0b000100 => decode_branch(BEQ, dword),
+        0b000101 => decode_branch(BNE, dword),
+        0b000110 => decode_branch_zero(BLEZ, dword)?,
+        0b000111 => decode_branch_zero(BGTZ, dword)?,
+        0b001000 => decode_immediate(ADDI, dword),
+        0b001001 => decode_immediate(ADDIU, dword),
+        0b001010 => decode_immediate(SLTI, dword),
+        0b001011 => decode_immediate(SLTIU, dword),
+        0b001100 => decode_logical(ANDI, dword),
+        0b001101 => decode_logical(ORI, dword),
+        0b001110 => decode_logical(XORI, dword),
+        0b001111 => decode_lui(dword)?,
+        0b010000 => decode_cop0(dword)?,
+        0b010001 => decode_cop1(dword)?,
+        0b010100 => decode_branch(BEQL, dword),
+        0b010101 => decode_branch(BNEL, dword),
+        0b010110 => decode_branch_zero(BLEZL, dword)?,
+        0b010111 => decode_branch_zero(BGTZL, dword)?,
+        0b011000 => decode_immediate(DADDI, dword),
+        0b011001 => decode_immediate(DADDIU, dword),
+        0b011010 => decode_memory(LDL, dword),
+        0b011011 => decode_memory(LDR, dword),
+        0b011100 => decode_special2(dword)?,
+        0b011111 => decode_special3(dword)?,
+        0b100000 => decode_memory(LB, dword),
+        0b100001 => decode_memory(LH, dword),
+        0b100010 => decode_memory(LWL, dword),
+        0b100011 => decode_memory(LW, dword),
+        0b100100 => decode_memory(LBU, dword),
+        0b100101 => decode_memory(LHU, dword),
+        0b100110 => decode_memory(LWR, dword),
+        0b100111 => decode_memory(LWU, dword),
+        0b101000 => decode_memory(SB, dword),
+        0b101001 => decode_memory(SH, dword),
+        0b101010 => decode_memory(SWL, dword),
+        0b101011 => decode_memory(SW, dword),
+        0b101100 => decode_memory(SDL, dword),
+        0b101101 => decode_memory(SDR, dword),
+        0b101110 => decode_memory(SWR, dword),
+        0b101111 => decode_cache(CACHE, dword),
+        0b110000 => decode_memory(LL, dword),
+        0b110001 => decode_fp_memory(LWC1, dword),
+        0b110011 => decode_cache(PREF, dword),
+        0b110100 => decode_memory(LLD, dword),
+        0b110101 => decode_fp_memory(LDC1, dword),
+        0b110111 => decode_memory(LD, dword),
+        0b111000 => decode_memory(SC, dword),
+        0b111001 => decode_fp_memory(SWC1, dword),
+        0b111100 => decode_memory(SCD, dword),
+        0b111101 => decode_fp_memory(SDC1, dword),
+        0b111111 => decode_memory(SD, dword),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
 
-            Ok(Instruction {
-                mnemomic: inst.mnemomic,
-                operands,
-                operand_count: inst.format.len(),
-            })
-        }
-        Format::J => {
-            let immediate = dword & 0b11111111_11111111_11111111;
-            let (operands, operand_count) = operands![Cow::Owned(format!("0x{immediate:x}"))];
-
-            Ok(Instruction {
-                mnemomic: inst.mnemomic,
-                operands,
-                operand_count,
-            })
-        }
+    if !decoder.is_64 && inst.opcode.is_64() {
+        return Err(ErrorKind::InvalidOpcode);
     }
+
+    Ok(map_to_psuedo(inst))
 }
 
-impl decoder::ToTokens for Instruction {
-    fn tokenize(&self, stream: &mut TokenStream, _: &Index) {
-        stream.push(self.mnemomic, CONFIG.colors.asm.opcode);
+impl ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        stream.push_mnemonic(self.opcode.as_str(), CONFIG.colors.asm.opcode);
 
         // there are operands
         if self.operand_count > 0 {
@@ -254,244 +747,498 @@ impl decoder::ToTokens for Instruction {
 
             // iterate through operands
             for idx in 0..self.operand_count {
-                let operand = self.operands[idx].clone();
-
-                match operand {
-                    Cow::Owned(s) => stream.push_owned(s, CONFIG.colors.asm.immediate),
-                    Cow::Borrowed(s) => stream.push(s, CONFIG.colors.asm.register),
-                };
+                self.operands[idx].tokenize(stream, symbols);
 
                 // separator
                 if idx != self.operand_count - 1 {
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                 }
             }
         }
+
+        if self.delay_slot.is_some() {
+            stream.push("  # delay slot", CONFIG.colors.asm.annotation);
+        }
     }
 }
 
-/// Bitmask for order of operands [rd, rt, rs, imm].
-macro_rules! mips {
-    () => {
-        $crate::TableInstruction {
-            mnemomic: "",
-            desc: "",
-            format: &[],
+// NOTE: doing closure assignment in `map_to_psuedo` makes the compiler
+// assign function mappings in the array on each call.
+static MAPPING: Lazy<[fn(&mut Instruction); 155]> = Lazy::new(|| unsafe {
+    const DO_NOTHING: fn(&mut Instruction) = |_| {};
+    static mut MAPPING: [fn(&mut Instruction); 155] = [DO_NOTHING; 155];
+
+    MAPPING[Opcode::SLL as usize] = |inst| {
+        if inst.operands[0] == Operand::Register(Register::Zero)
+            && inst.operands[1] == Operand::Register(Register::Zero)
+            && inst.operands[2] == Operand::Immediate(0)
+        {
+            inst.opcode = Opcode::NOP;
+            inst.operand_count = 0;
         }
     };
 
-    ($mnemomic:literal : $desc:literal, rd, rt, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[0, 1, 3],
+    const MOVE: fn(&mut Instruction) = |inst| {
+        if inst.operands[2] == Operand::Register(Register::Zero) {
+            inst.opcode = Opcode::MOVE;
+            inst.operand_count = 2;
+            return;
         }
-    };
 
-    ($mnemomic:literal : $desc:literal, rt, rs, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[1, 2, 3],
+        if inst.operands[1] == Operand::Register(Register::Zero) {
+            inst.opcode = Opcode::MOVE;
+            inst.operands[1] = inst.operands[2];
+            inst.operand_count = 2;
         }
     };
 
-    ($mnemomic:literal : $desc:literal, rs, rt, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[2, 1, 3],
+    MAPPING[Opcode::OR as usize] = MOVE;
+    MAPPING[Opcode::ADDU as usize] = MOVE;
+    MAPPING[Opcode::DADDU as usize] = MOVE;
+
+    const LI: fn(&mut Instruction) = |inst| {
+        if inst.operands[1] == Operand::Register(Register::Zero) {
+            inst.opcode = Opcode::LI;
+            inst.operands[1] = inst.operands[2];
+            inst.operand_count = 2;
         }
     };
 
-    ($mnemomic:literal : $desc:literal, rd, rt, rs) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[0, 1, 2],
+    MAPPING[Opcode::ADDIU as usize] = LI;
+    MAPPING[Opcode::ORI as usize] = LI;
+
+    MAPPING[Opcode::SUBU as usize] = |inst| {
+        if inst.operands[1] == Operand::Register(Register::Zero) {
+            inst.opcode = Opcode::NEGU;
+            inst.operands[1] = inst.operands[2];
+            inst.operand_count = 2;
         }
     };
 
-    ($mnemomic:literal : $desc:literal, rd, rs, rt) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[0, 2, 1],
+    MAPPING[Opcode::NOR as usize] = |inst| {
+        if inst.operands[2] == Operand::Register(Register::Zero) {
+            inst.opcode = Opcode::NOT;
+            inst.operand_count = 2;
         }
     };
 
-    ($mnemomic:literal : $desc:literal, rt, imm, rs) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[1, 3, 2],
+    MAPPING[Opcode::BEQ as usize] = |inst| {
+        if inst.operands[1] != Operand::Register(Register::Zero) {
+            return;
         }
-    };
 
-    ($mnemomic:literal : $desc:literal, rs, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[2, 3],
+        if inst.operands[0] == Operand::Register(Register::Zero) {
+            inst.opcode = Opcode::B;
+            inst.operands[0] = inst.operands[2];
+            inst.operand_count = 1;
+            return;
         }
+
+        inst.opcode = Opcode::BEQZ;
+        inst.operands[1] = inst.operands[2];
+        inst.operand_count = 2;
     };
 
-    ($mnemomic:literal : $desc:literal, rt, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[1, 3],
+    MAPPING[Opcode::BNE as usize] = |inst| {
+        if inst.operands[1] == Operand::Register(Register::Zero) {
+            inst.opcode = Opcode::BNEZ;
+            inst.operands[1] = inst.operands[2];
+            inst.operand_count = 2;
         }
     };
 
-    ($mnemomic:literal : $desc:literal, rd, rs) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[0, 2],
+    MAPPING[Opcode::BGEZAL as usize] = |inst| {
+        if inst.operands[0] == Operand::Register(Register::Zero) {
+            inst.opcode = Opcode::BAL;
+            inst.operands[0] = inst.operands[1];
+            inst.operand_count = 1;
         }
     };
 
-    ($mnemomic:literal : $desc:literal, rs, rt) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[2, 1],
+    MAPPING[Opcode::JALR as usize] = |inst| {
+        if inst.operands[0] == Operand::Register(Register::Ra) {
+            inst.operands[0] = inst.operands[1];
+            inst.operand_count = 1;
         }
     };
 
-    ($mnemomic:literal : $desc:literal, imm) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[3],
+    MAPPING
+});
+
+#[inline]
+fn map_to_psuedo(mut inst: Instruction) -> Instruction {
+    MAPPING[inst.opcode as usize](&mut inst);
+    inst
+}
+
+#[inline]
+fn rs(dword: u32) -> Register {
+    Register::get(dword >> 21)
+}
+
+#[inline]
+fn rt(dword: u32) -> Register {
+    Register::get(dword >> 16)
+}
+
+#[inline]
+fn rd(dword: u32) -> Register {
+    Register::get(dword >> 11)
+}
+
+#[inline]
+fn sa(dword: u32) -> u32 {
+    dword >> 6 & 0b11111
+}
+
+fn instruction(opcode: Opcode, (operands, operand_count): ([Operand; 4], usize)) -> Instruction {
+    Instruction {
+        opcode,
+        operands,
+        operand_count,
+        delay_slot: None,
+    }
+}
+
+/// Decode's instructions with an opcode of `SPECIAL`, which are selected by their function
+/// field.
+fn decode_special(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let (rs, rt, rd, sa) = (rs(dword), rt(dword), rd(dword), sa(dword));
+    let reg = Operand::Register;
+    let imm = |imm: u32| Operand::Immediate(imm as i32);
+
+    let inst = match dword & 0b111111 {
+        0b000000 => instruction(SLL, operands![reg(rd), reg(rt), imm(sa)]),
+        // bit 21 selects the rotate introduced in release 2
+        0b000010 if dword >> 21 & 0b11111 == 1 => {
+            instruction(ROTR, operands![reg(rd), reg(rt), imm(sa)])
         }
+        0b000010 => instruction(SRL, operands![reg(rd), reg(rt), imm(sa)]),
+        0b000011 => instruction(SRA, operands![reg(rd), reg(rt), imm(sa)]),
+        0b000100 => instruction(SLLV, operands![reg(rd), reg(rt), reg(rs)]),
+        // bit 6 selects the rotate introduced in release 2
+        0b000110 if sa == 1 => instruction(ROTRV, operands![reg(rd), reg(rt), reg(rs)]),
+        0b000110 => instruction(SRLV, operands![reg(rd), reg(rt), reg(rs)]),
+        0b000111 => instruction(SRAV, operands![reg(rd), reg(rt), reg(rs)]),
+        0b001000 => instruction(JR, operands![reg(rs)]),
+        0b001001 => instruction(JALR, operands![reg(rd), reg(rs)]),
+        0b001010 => instruction(MOVZ, operands![reg(rd), reg(rs), reg(rt)]),
+        0b001011 => instruction(MOVN, operands![reg(rd), reg(rs), reg(rt)]),
+        0b001100 => decode_code(SYSCALL, dword >> 6 & 0xfffff),
+        0b001101 => decode_code(BREAK, dword >> 16 & 0x3ff),
+        0b001111 => decode_code(SYNC, sa),
+        0b010000 => instruction(MFHI, operands![reg(rd)]),
+        0b010001 => instruction(MTHI, operands![reg(rs)]),
+        0b010010 => instruction(MFLO, operands![reg(rd)]),
+        0b010011 => instruction(MTLO, operands![reg(rs)]),
+        0b010100 => instruction(DSLLV, operands![reg(rd), reg(rt), reg(rs)]),
+        0b010110 => instruction(DSRLV, operands![reg(rd), reg(rt), reg(rs)]),
+        0b010111 => instruction(DSRAV, operands![reg(rd), reg(rt), reg(rs)]),
+        0b011000 => instruction(MULT, operands![reg(rs), reg(rt)]),
+        0b011001 => instruction(MULTU, operands![reg(rs), reg(rt)]),
+        0b011010 => instruction(DIV, operands![reg(rs), reg(rt)]),
+        0b011011 => instruction(DIVU, operands![reg(rs), reg(rt)]),
+        0b011100 => instruction(DMULT, operands![reg(rs), reg(rt)]),
+        0b011101 => instruction(DMULTU, operands![reg(rs), reg(rt)]),
+        0b011110 => instruction(DDIV, operands![reg(rs), reg(rt)]),
+        0b011111 => instruction(DDIVU, operands![reg(rs), reg(rt)]),
+        0b100000 => instruction(ADD, operands![reg(rd), reg(rs), reg(rt)]),
+        0b100001 => instruction(ADDU, operands![reg(rd), reg(rs), reg(rt)]),
+        0b100010 => instruction(SUB, operands![reg(rd), reg(rs), reg(rt)]),
+        0b100011 => instruction(SUBU, operands![reg(rd), reg(rs), reg(rt)]),
+        0b100100 => instruction(AND, operands![reg(rd), reg(rs), reg(rt)]),
+        0b100101 => instruction(OR, operands![reg(rd), reg(rs), reg(rt)]),
+        0b100110 => instruction(XOR, operands![reg(rd), reg(rs), reg(rt)]),
+        0b100111 => instruction(NOR, operands![reg(rd), reg(rs), reg(rt)]),
+        0b101010 => instruction(SLT, operands![reg(rd), reg(rs), reg(rt)]),
+        0b101011 => instruction(SLTU, operands![reg(rd), reg(rs), reg(rt)]),
+        0b101100 => instruction(DADD, operands![reg(rd), reg(rs), reg(rt)]),
+        0b101101 => instruction(DADDU, operands![reg(rd), reg(rs), reg(rt)]),
+        0b101110 => instruction(DSUB, operands![reg(rd), reg(rs), reg(rt)]),
+        0b101111 => instruction(DSUBU, operands![reg(rd), reg(rs), reg(rt)]),
+        0b110000 => instruction(TGE, operands![reg(rs), reg(rt)]),
+        0b110001 => instruction(TGEU, operands![reg(rs), reg(rt)]),
+        0b110010 => instruction(TLT, operands![reg(rs), reg(rt)]),
+        0b110011 => instruction(TLTU, operands![reg(rs), reg(rt)]),
+        0b110100 => instruction(TEQ, operands![reg(rs), reg(rt)]),
+        0b110110 => instruction(TNE, operands![reg(rs), reg(rt)]),
+        0b111000 => instruction(DSLL, operands![reg(rd), reg(rt), imm(sa)]),
+        0b111010 => instruction(DSRL, operands![reg(rd), reg(rt), imm(sa)]),
+        0b111011 => instruction(DSRA, operands![reg(rd), reg(rt), imm(sa)]),
+        0b111100 => instruction(DSLL32, operands![reg(rd), reg(rt), imm(sa)]),
+        0b111110 => instruction(DSRL32, operands![reg(rd), reg(rt), imm(sa)]),
+        0b111111 => instruction(DSRA32, operands![reg(rd), reg(rt), imm(sa)]),
+        _ => return Err(ErrorKind::InvalidOpcode),
     };
 
-    ($mnemomic:literal : $desc:literal, rs) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[2],
-        }
+    Ok(inst)
+}
+
+/// Decode's syscall, break and sync, which only show their code when it's set.
+fn decode_code(opcode: Opcode, code: u32) -> Instruction {
+    if code == 0 {
+        instruction(opcode, operands![])
+    } else {
+        instruction(opcode, operands![Operand::Immediate(code as i32)])
+    }
+}
+
+/// Decode's instructions with an opcode of `REGIMM`, which are branches comparing against
+/// zero selected by their rt field.
+fn decode_regimm(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let opcode = match dword >> 16 & 0b11111 {
+        0b00000 => BLTZ,
+        0b00001 => BGEZ,
+        0b00010 => BLTZL,
+        0b00011 => BGEZL,
+        0b10000 => BLTZAL,
+        0b10001 => BGEZAL,
+        0b10010 => BLTZALL,
+        0b10011 => BGEZALL,
+        _ => return Err(ErrorKind::InvalidOpcode),
     };
 
-    ($mnemomic:literal : $desc:literal, rd) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[0],
+    Ok(instruction(
+        opcode,
+        operands![Operand::Register(rs(dword)), branch_offset(dword)],
+    ))
+}
+
+/// Branch offsets are relative to the delay slot and in units of instructions.
+#[inline]
+fn branch_offset(dword: u32) -> Operand {
+    Operand::PCOffset(((dword as i16 as i32) << 2) + 4)
+}
+
+/// Decode's j and jal instructions.
+fn decode_jump(opcode: Opcode, dword: u32) -> Instruction {
+    let target = (dword & 0x03ff_ffff) << 2;
+    instruction(opcode, operands![Operand::Region(target)])
+}
+
+/// Decode's beq, bne, beql and bnel branch instructions.
+fn decode_branch(opcode: Opcode, dword: u32) -> Instruction {
+    instruction(
+        opcode,
+        operands![
+            Operand::Register(rs(dword)),
+            Operand::Register(rt(dword)),
+            branch_offset(dword),
+        ],
+    )
+}
+
+/// Decode's blez, bgtz, blezl and bgtzl branch instructions.
+fn decode_branch_zero(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    if dword >> 16 & 0b11111 != 0 {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
+    Ok(instruction(
+        opcode,
+        operands![Operand::Register(rs(dword)), branch_offset(dword)],
+    ))
+}
+
+/// Decode's instructions that have two registers and a sign extended immediate.
+fn decode_immediate(opcode: Opcode, dword: u32) -> Instruction {
+    instruction(
+        opcode,
+        operands![
+            Operand::Register(rt(dword)),
+            Operand::Register(rs(dword)),
+            Operand::Immediate(dword as i16 as i32),
+        ],
+    )
+}
+
+/// Decode's andi, ori and xori, which zero extend their immediate.
+fn decode_logical(opcode: Opcode, dword: u32) -> Instruction {
+    instruction(
+        opcode,
+        operands![
+            Operand::Register(rt(dword)),
+            Operand::Register(rs(dword)),
+            Operand::Immediate(dword as u16 as i32),
+        ],
+    )
+}
+
+/// Decode's lui instruction.
+fn decode_lui(dword: u32) -> Result<Instruction, ErrorKind> {
+    if dword >> 21 & 0b11111 != 0 {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
+    Ok(instruction(
+        Opcode::LUI,
+        operands![
+            Operand::Register(rt(dword)),
+            Operand::Immediate(dword as u16 as i32),
+        ],
+    ))
+}
+
+/// Decode's loads and stores of the form `rt, offset(base)`.
+fn decode_memory(opcode: Opcode, dword: u32) -> Instruction {
+    instruction(
+        opcode,
+        operands![
+            Operand::Register(rt(dword)),
+            Operand::Memory(rs(dword), dword as i16 as i32),
+        ],
+    )
+}
+
+/// Decode's floating point loads and stores of the form `ft, offset(base)`.
+fn decode_fp_memory(opcode: Opcode, dword: u32) -> Instruction {
+    instruction(
+        opcode,
+        operands![
+            Operand::Register(Register::get_fp(dword >> 16)),
+            Operand::Memory(rs(dword), dword as i16 as i32),
+        ],
+    )
+}
+
+/// Decode's cache and pref, where the rt field is an operation.
+fn decode_cache(opcode: Opcode, dword: u32) -> Instruction {
+    instruction(
+        opcode,
+        operands![
+            Operand::Immediate((dword >> 16 & 0b11111) as i32),
+            Operand::Memory(rs(dword), dword as i16 as i32),
+        ],
+    )
+}
+
+/// Decode's coprocessor 0 moves and the instructions with the `CO` bit set.
+fn decode_cop0(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let rt = Operand::Register(rt(dword));
+    let rd = Operand::Coprocessor((dword >> 11 & 0b11111) as u8);
+    let sel = dword & 0b111;
+
+    let opcode = match dword >> 21 & 0b11111 {
+        0b00000 => MFC0,
+        0b00001 => DMFC0,
+        0b00100 => MTC0,
+        0b00101 => DMTC0,
+        0b10000..=0b11111 => {
+            let opcode = match dword & 0b111111 {
+                0b000001 => TLBR,
+                0b000010 => TLBWI,
+                0b000110 => TLBWR,
+                0b001000 => TLBP,
+                0b011000 => ERET,
+                0b011111 => DERET,
+                0b100000 => WAIT,
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            return Ok(instruction(opcode, operands![]));
         }
+        _ => return Err(ErrorKind::InvalidOpcode),
     };
 
-    ($mnemomic:literal : $desc:literal) => {
-        $crate::TableInstruction {
-            mnemomic: $mnemomic,
-            desc: $desc,
-            format: &[],
-        }
+    if sel == 0 {
+        Ok(instruction(opcode, operands![rt, rd]))
+    } else {
+        Ok(instruction(opcode, operands![rt, rd, Operand::Immediate(sel as i32)]))
+    }
+}
+
+/// Decode's moves between general purpose and floating point registers.
+fn decode_cop1(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let opcode = match dword >> 21 & 0b11111 {
+        0b00000 => MFC1,
+        0b00001 => DMFC1,
+        0b00100 => MTC1,
+        0b00101 => DMTC1,
+        // floating point arithmetic isn't supported
+        _ => return Err(ErrorKind::IncompleteDecoder),
     };
+
+    Ok(instruction(
+        opcode,
+        operands![
+            Operand::Register(rt(dword)),
+            Operand::Register(Register::get_fp(dword >> 11)),
+        ],
+    ))
 }
 
-const I_TYPES: [TableInstruction; 44] = [
-    mips!("bgez" : "Branch to immediate if value of $rs is greater than or equal to zero", rs, imm),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!("beq" : "Branch to immediate if values of $rs and $rt are equal", rs, rt, imm),
-    mips!("bne" : "Branch to immediate if values of $rs and $rt are not equal", rs, rt, imm),
-    mips!("blez" : "Branch to immediate if value of $rs is less than or equal to zero", rs, imm),
-    mips!("bgtz" : "Branch to immediate if value of $rs is greater than or equal to zero", rs, imm),
-    mips!("addi" : "Add $rs to the immediate and store result in $rt (signed)", rt, rs, imm),
-    mips!("addiu" : "Add $rs to the immediate and store result in $rt (unsigned)", rt, rs, imm),
-    mips!("slti" : "If $rs is less then immediate, $rt is set to 1 otherwise to 0 (signed)", rt, rs, imm),
-    mips!("sltiu" : "If $rs is less then immediate, $rt is set to 1 otherwise to 0 (unsigned)", rt, rs, imm),
-    mips!("andi" : "Bitwise AND between $rs and immediate storing the result in $rt", rt, rs, imm),
-    mips!("ori" : "Bitwise OR between $rs and immediate storing the result in $rt", rt, rs, imm),
-    mips!("xori" : "Bitwise XOR between $rs and immediate storing the result in $rt", rt, rs, imm),
-    mips!("lui" : "Stores immediate in the upper 16 bits of $rt", rt, imm),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!("lb" : "Load byte from value at address in $rs with a given offset into $rt sign extending it to 32 bits (signed)", rt, imm, rs),
-    mips!("lh" : "Load 2 bytes from value at address in $rs with a given offset into $rt sign extending it to 32 bits (signed)", rt, imm, rs),
-    mips!("lwl" : "Load the most-significant part of a word as a signed value from an unaligned memory address", rt, imm, rs),
-    mips!("lw" : "Load 4 bytes from value at address in $rs with a given offset into $rt (signed)", rt, imm, rs),
-    mips!("lbu" : "Load byte from value at address in $rs with a given offset into $rt sign extending it to 32 bits (unsigned)", rt, imm, rs),
-    mips!("lhu" : "Load 2 bytes from value at address in $rs with a given offset into $rt sign extending it to 32 bits (unsigned)", rt, imm, rs),
-    mips!("lwr" : "Load the least-significant part of a word as a signed value from an unaligned memory address", rt, imm, rs),
-    mips!("lwu" : "Load 4 bytes from value at address in $rs with a given offset into $rt (unsigned)", rt, imm, rs),
-    mips!("sb" : "Store byte at address in $rs with a given offset into $rt", rt, imm, rs),
-    mips!("sh" : "Store 2 bytes at address in $rs with a given offset into $rt", rt, imm, rs),
-    mips!(),
-    mips!("sw" : "Store 4 bytes at address in $rs with a given offset into $rt", rt, imm, rs),
-];
-
-const J_TYPES: [TableInstruction; 4] = [
-    mips!(),
-    mips!(),
-    mips!("j" : "Jump to target address", imm),
-    mips!("jr" : "Call the target address and save return addr in $ra", imm),
-];
-
-const R_TYPES: [TableInstruction; 44] = [
-    mips!("sll" : "Shift value in $rt `immediate` number of times to the left storing the result in $rd and zero extending the shifted bits", rd, rt, imm),
-    mips!(),
-    mips!("srl" : "Shift value in $rt `immediate` number of times to the right storing the result in $rd and zero extending the shifted bits", rd, rt, imm),
-    mips!("sra" : "Shift value in $rt `immediate` number of times to the right storing the result in $rd and sign extending the shifted bits", rd, rt, imm),
-    mips!("sllv" : "Shift value in $rt `$rs` number of times to the left storing the result in $rd and zero extending the shifted bits", rd, rt, rs),
-    mips!(),
-    mips!("srlv" : "Shift value in $rt `$rs` number of times to the right storing the result in $rd and zero extending the shifted bits", rd, rt, rs),
-    mips!("srav" : "Shift value in $rt `$rs` number of times to the right storing the result in $rd and sign extending the shifted bits", rd, rt, rs),
-    mips!("jr" : "Jump to address of $rs", rs),
-    mips!(),
-    mips!(),
-    mips!("syscall" : "Trigger exception tranfering control from user space to kernel space where the call is handled"),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!("mfhi" : "Store value from $hi (internal register used for multiplication/division) in $rd", rd),
-    mips!("mthi" : "Store value from $rs in $hi (internal register used for multiplication/division)", rs),
-    mips!("mflo" : "Store value from $lo (internal register used for multiplication/division) in $rd", rd),
-    mips!("mtlo" : "Store value from $rs in $lo (internal register used for multiplication/division)", rs),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!("mult" : "Multiply $rs and $rt (signed) storing the result in the split between $hi and $lo (internal registers)", rs, rt),
-    mips!("multu" : "Multiply $rs and $rt (unsigned) storing the result in the split between $hi and $lo (internal registers)", rs, rt),
-    mips!("div" : "Divide $rs by $rt (signed) storing the result in the split between $hi and $lo (internal registers)", rs, rt),
-    mips!("divu" : "Divide $rs by $rt (unsigned) storing the result in the split between $hi and $lo (internal registers)", rs, rt),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!(),
-    mips!("add" : "Add $rs to $rt storing the result in $rd (signed)", rd, rs, rt),
-    mips!("addu" : "Add $rs to $rt storing the result in $rd (unsigned)", rd, rs, rt),
-    mips!("sub" : "Subtract $rs from $rt storing the result in $rd (signed)", rd, rs, rt),
-    mips!("subu" : "Subtract $rs from $rt storing the result in $rd (unsigned)", rd, rs, rt),
-    mips!("and" : "Bitwise AND between $rs and $rt storing the result in $rd", rd, rs, rt),
-    mips!("or" : "Bitwise OR between $rs and $rt storing the result in $rd", rd, rs, rt),
-    mips!("xor" : "Bitwise XOR between $rs and $rt storing the result in $rd", rd, rs, rt),
-    mips!("nor" : "Bitwise NOR between $rs and $rt storing the result in $rd", rd, rs, rt),
-    mips!(),
-    mips!(),
-    mips!("slt" : "If $rs is less then $rt, $rd is set to 1 otherwise to 0 (signed)", rd, rs, rt),
-    mips!("sltu" : "If $rs is less then $rt, $rd is set to 1 otherwise to 0 (unsigned)", rd, rs, rt),
-];
-
-const EMPTY_OPERAND: std::borrow::Cow<'static, str> = std::borrow::Cow::Borrowed("");
+/// Decode's instructions with an opcode of `SPECIAL2`, the multiply-accumulate's and bit
+/// counting.
+fn decode_special2(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let (rs, rt, rd) = (rs(dword), rt(dword), rd(dword));
+    let reg = Operand::Register;
+
+    let inst = match dword & 0b111111 {
+        0b000000 => instruction(MADD, operands![reg(rs), reg(rt)]),
+        0b000001 => instruction(MADDU, operands![reg(rs), reg(rt)]),
+        0b000010 => instruction(MUL, operands![reg(rd), reg(rs), reg(rt)]),
+        0b000100 => instruction(MSUB, operands![reg(rs), reg(rt)]),
+        0b000101 => instruction(MSUBU, operands![reg(rs), reg(rt)]),
+        0b100000 => instruction(CLZ, operands![reg(rd), reg(rs)]),
+        0b100001 => instruction(CLO, operands![reg(rd), reg(rs)]),
+        0b100100 => instruction(DCLZ, operands![reg(rd), reg(rs)]),
+        0b100101 => instruction(DCLO, operands![reg(rd), reg(rs)]),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+/// Decode's instructions with an opcode of `SPECIAL3`, the bit field operations introduced in
+/// release 2.
+fn decode_special3(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let (rs, rt, rd) = (rs(dword), rt(dword), rd(dword));
+    let reg = Operand::Register;
+    let imm = |imm: u32| Operand::Immediate(imm as i32);
+
+    // fields used by ext and ins
+    let lsb = sa(dword);
+    let msb = dword >> 11 & 0b11111;
+
+    let inst = match dword & 0b111111 {
+        0b000000 => instruction(EXT, operands![reg(rt), reg(rs), imm(lsb), imm(msb + 1)]),
+        0b000011 => instruction(DEXT, operands![reg(rt), reg(rs), imm(lsb), imm(msb + 1)]),
+        0b000100 | 0b000111 => {
+            if msb < lsb {
+                return Err(ErrorKind::InvalidOperand);
+            }
+
+            let opcode = if dword & 0b111111 == 0b000100 { INS } else { DINS };
+            instruction(opcode, operands![reg(rt), reg(rs), imm(lsb), imm(msb - lsb + 1)])
+        }
+        0b100000 => {
+            let opcode = match sa(dword) {
+                0b00010 => WSBH,
+                0b10000 => SEB,
+                0b11000 => SEH,
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            instruction(opcode, operands![reg(rd), reg(rt)])
+        }
+        0b111011 => instruction(
+            RDHWR,
+            operands![reg(rt), Operand::Coprocessor((dword >> 11 & 0b11111) as u8)],
+        ),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
@@ -1,45 +1,199 @@
 #![cfg(test)]
 
-use decoder::{ToTokens, Decodable};
+use crate::Decoder;
+use decoder::testing::{decode, decode_instructions, Endianness};
+use decoder::{Decoded, ErrorKind};
 
-fn test_display(bytes: &[u8], str: &str) {
-    let mut reader = decoder::Reader::new(bytes);
-    let mut line = tokenizing::TokenStream::new();
-    let symbols = debugvault::Index::default();
-    let decoder = crate::Decoder::default();
+impl Endianness for Decoder {
+    fn new(is_64: bool, big_endian: bool) -> Self {
+        Self { is_64, big_endian }
+    }
+}
+
+/// Decodes a sequence of big endian instructions, resolving addresses relative to 0x400000.
+fn decode_mips32(dwords: &[u32]) -> Vec<String> {
+    let decoder = Decoder { is_64: false, big_endian: true };
+    let bytes: Vec<u8> = dwords.iter().flat_map(|dword| dword.to_be_bytes()).collect();
+    decode_instructions(decoder, &bytes, 0x400000, &debugvault::Index::default())
+}
 
-    let decoded = match decoder.decode(&mut reader) {
-        Ok(inst) => {
-            inst.tokenize(&mut line, &symbols);
-            line.to_string()
-        }
-        Err(err) => format!("{err:?}"),
-    };
+#[test]
+fn endianness() {
+    let be = Decoder { is_64: false, big_endian: true };
+    let le = Decoder { is_64: false, big_endian: false };
 
-    assert_eq!(decoded, str);
+    assert_eq!(decode(&be, &[0x27, 0xbd, 0xff, 0xe0]), "addiu sp, sp, -32");
+    assert_eq!(decode(&le, &[0xe0, 0xff, 0xbd, 0x27]), "addiu sp, sp, -32");
+    assert_eq!(decode(&le, &[0x27, 0xbd, 0xff, 0xe0]), "sc ra, -17113(a3)");
 }
 
 #[test]
 fn jump() {
-    test_display(&[0x9, 0, 0, 0], "j 0x0");
+    Decoder::test_display(0x08000000, "j 0x0");
+    Decoder::test_display(0x0c100004, "jal 0x400010");
+    Decoder::test_display(0x03e00008, "jr ra");
+    Decoder::test_display(0x0320f809, "jalr t9");
+    Decoder::test_display(0x03201009, "jalr v0, t9");
 }
 
 #[test]
-fn beq() {
-    test_display(&[0x11, 0x2a, 0x10, 0x0], "beq t1, t2, 0x1000");
+fn branch() {
+    Decoder::test_display(0x112a1000, "beq t1, t2, 0x1000");
+    Decoder::test_display(0x1000ffff, "b -0x1");
+    Decoder::test_display(0x1080fffe, "beqz a0, -0x2");
+    Decoder::test_display(0x14800003, "bnez a0, 0x3");
+    Decoder::test_display(0x04110001, "bal 0x1");
+    Decoder::test_display(0x0481ffff, "bgez a0, -0x1");
+    Decoder::test_display(0x1c80ffff, "bgtz a0, -0x1");
+    Decoder::test_error(0x1c85ffff, ErrorKind::InvalidOperand);
 }
 
 #[test]
-fn sll() {
-    test_display(&[0x0, 0xa, 0x4c, 0x80], "sll t1, t2, 0x12");
+fn arith() {
+    Decoder::test_display(0x00000000, "nop");
+    Decoder::test_display(0x00851021, "addu v0, a0, a1");
+    Decoder::test_display(0x27bdffe0, "addiu sp, sp, -32");
+    Decoder::test_display(0x3462ffff, "ori v0, v1, 65535");
+    Decoder::test_display(0x3c011234, "lui at, 4660");
+    Decoder::test_display(0x0064102a, "slt v0, v1, a0");
+    Decoder::test_display(0x2c620001, "sltiu v0, v1, 1");
+    Decoder::test_display(0x0064100a, "movz v0, v1, a0");
 }
 
 #[test]
-fn sllv() {
-    test_display(&[0x1, 0x49, 0x48, 0x4], "sllv t1, t1, t2");
+fn pseudo() {
+    Decoder::test_display(0x00801025, "move v0, a0");
+    Decoder::test_display(0x24020005, "li v0, 5");
+    Decoder::test_display(0x00031023, "negu v0, v1");
+    Decoder::test_display(0x00601027, "not v0, v1");
 }
 
 #[test]
-fn lb() {
-    test_display(&[0x81, 0x49, 0x0, 0x10], "lb t1, t2, 0x10");
+fn shifts() {
+    Decoder::test_display(0x000a4c80, "sll t1, t2, 18");
+    Decoder::test_display(0x01494804, "sllv t1, t1, t2");
+    Decoder::test_display(0x00031102, "srl v0, v1, 4");
+    Decoder::test_display(0x00231102, "rotr v0, v1, 4");
+    Decoder::test_display(0x000317c3, "sra v0, v1, 31");
+}
+
+#[test]
+fn hi_lo() {
+    Decoder::test_display(0x00850018, "mult a0, a1");
+    Decoder::test_display(0x00850019, "multu a0, a1");
+    Decoder::test_display(0x0085001a, "div a0, a1");
+    Decoder::test_display(0x00001010, "mfhi v0");
+    Decoder::test_display(0x00001812, "mflo v1");
+    Decoder::test_display(0x00400011, "mthi v0");
+    Decoder::test_display(0x00600013, "mtlo v1");
+    Decoder::test_display(0x70850000, "madd a0, a1");
+    Decoder::test_display(0x70641002, "mul v0, v1, a0");
+    Decoder::test_display(0x70621020, "clz v0, v1");
+}
+
+#[test]
+fn load_store() {
+    Decoder::test_display(0x81490010, "lb t1, 16(t2)");
+    Decoder::test_display(0x8fbf001c, "lw ra, 28(sp)");
+    Decoder::test_display(0xafbffffc, "sw ra, -4(sp)");
+    Decoder::test_display(0x90820001, "lbu v0, 1(a0)");
+    Decoder::test_display(0xc0820000, "ll v0, 0(a0)");
+    Decoder::test_display(0xe0820000, "sc v0, 0(a0)");
+    Decoder::test_display(0xc7a20008, "lwc1 f2, 8(sp)");
+    Decoder::test_display(0xd7a20010, "ldc1 f2, 16(sp)");
+    Decoder::test_display(0xbc940000, "cache 20, 0(a0)");
+    Decoder::test_display(0xcc800010, "pref 0, 16(a0)");
+}
+
+#[test]
+fn mips64() {
+    Decoder::test_display(0xdfa20008, "ld v0, 8(sp)");
+    Decoder::test_display(0xffa20008, "sd v0, 8(sp)");
+    Decoder::test_display(0x67bdfff0, "daddiu sp, sp, -16");
+    Decoder::test_display(0x0064102d, "daddu v0, v1, a0");
+    Decoder::test_display(0x0003113c, "dsll32 v0, v1, 4");
+
+    Decoder::test_error_32(0xdfa20008, ErrorKind::InvalidOpcode);
+    Decoder::test_error_32(0x0064102d, ErrorKind::InvalidOpcode);
+    Decoder::test_display_32(0x8fbf001c, "lw ra, 28(sp)");
+}
+
+#[test]
+fn system() {
+    Decoder::test_display(0x0000000c, "syscall");
+    Decoder::test_display(0x0000000d, "break");
+    Decoder::test_display(0x0000000f, "sync");
+    Decoder::test_display(0x00850034, "teq a0, a1");
+    Decoder::test_display(0x40026000, "mfc0 v0, $12");
+    Decoder::test_display(0x40826001, "mtc0 v0, $12, 1");
+    Decoder::test_display(0x42000018, "eret");
+    Decoder::test_display(0x44022000, "mfc1 v0, f4");
+    Decoder::test_display(0x7c03e83b, "rdhwr v1, $29");
+}
+
+#[test]
+fn bit_field() {
+    Decoder::test_display(0x7c623900, "ext v0, v1, 4, 8");
+    Decoder::test_display(0x7c625904, "ins v0, v1, 4, 8");
+    Decoder::test_display(0x7c031420, "seb v0, v1");
+    Decoder::test_display(0x7c031620, "seh v0, v1");
+    Decoder::test_display(0x7c0310a0, "wsbh v0, v1");
+}
+
+#[test]
+fn delay_slot() {
+    assert_eq!(
+        decode_mips32(&[
+            0x3c021234, // lui v0, 0x1234
+            0x24425678, // addiu v0, v0, 0x5678
+            0x3c03dead, // lui v1, 0xdead
+            0x3463beef, // ori v1, v1, 0xbeef
+            0x1040fffb, // beqz v0, 0x400000
+            0x00000000, // nop
+            0x0c100000, // jal 0x400000
+            0x00801025, // move v0, a0
+            0x03e00008, // jr ra
+            0x27bd0020, // addiu sp, sp, 32
+        ]),
+        [
+            "lui v0, 4660",
+            "li v0, 305419896",
+            "lui v1, 57005",
+            "li v1, -559038737",
+            "beqz v0, 0x400000",
+            "nop  # delay slot",
+            "jal 0x400000",
+            "move v0, a0  # delay slot",
+            "jr ra",
+            "addiu sp, sp, 32  # delay slot",
+        ]
+    );
+}
+
+#[test]
+fn terminators() {
+    let decoder = Decoder { is_64: false, big_endian: true };
+    let dwords: [u32; 7] = [
+        0x1040fffb, // beqz v0, 0x400000
+        0x00000000, // nop
+        0x03e00008, // jr ra
+        0x27bd0020, // addiu sp, sp, 32
+        0x08100000, // j 0x400000
+        0x00000000, // nop
+        0x42000018, // eret
+    ];
+
+    let bytes: Vec<u8> = dwords.iter().flat_map(|dword| dword.to_be_bytes()).collect();
+    let mut terminators = Vec::new();
+    let mut prev = None;
+
+    for (ip, inst) in decoder::InstructionStream::new(&bytes, 0x400000, decoder) {
+        let Ok(mut inst) = inst else { break };
+        inst.update_rel_addrs(ip, prev.as_ref());
+        terminators.push(inst.is_terminator());
+        prev = Some(inst);
+    }
+
+    // jumps only take effect after their delay slot
+    assert_eq!(terminators, [false, false, false, true, false, true, true]);
 }
@@ -0,0 +1,30 @@
+; Fixture for the ppc64le end-to-end test, built with:
+;   llc -O2 -mtriple=powerpc64le-linux-gnu -filetype=obj sum.ll -o sum.o
+
+define dso_local signext i32 @sum(i32* nocapture readonly %values, i32 signext %len) {
+entry:
+  %empty = icmp sgt i32 %len, 0
+  br i1 %empty, label %loop, label %exit
+
+loop:
+  %i = phi i32 [ %next, %loop ], [ 0, %entry ]
+  %acc = phi i32 [ %add, %loop ], [ 0, %entry ]
+  %idx = zext i32 %i to i64
+  %ptr = getelementptr inbounds i32, i32* %values, i64 %idx
+  %value = load i32, i32* %ptr, align 4
+  %add = add nsw i32 %value, %acc
+  %next = add nuw nsw i32 %i, 1
+  %done = icmp eq i32 %next, %len
+  br i1 %done, label %exit, label %loop
+
+exit:
+  %result = phi i32 [ 0, %entry ], [ %add, %loop ]
+  ret i32 %result
+}
+
+define dso_local signext i32 @caller(i32* %values) {
+entry:
+  %result = call signext i32 @sum(i32* %values, i32 signext 16)
+  %shifted = shl i32 %result, 3
+  ret i32 %shifted
+}
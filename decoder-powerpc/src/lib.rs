@@ -0,0 +1,1535 @@
+//! PowerPC/PowerPC64 disassembler.
+
+mod tests;
+
+use decoder::{Error, ErrorKind, ToTokens, XrefKind};
+use debugvault::Index;
+use once_cell::sync::Lazy;
+use tokenizing::{colors, Radix, TokenStream};
+use config::CONFIG;
+
+macro_rules! operands {
+    [] => {([$crate::Operand::Nothing; 5], 0)};
+    [$($x:expr),+ $(,)?] => {{
+        let mut operands = [$crate::Operand::Nothing; 5];
+        let mut idx = 0;
+        $(
+            idx += 1;
+            operands[idx - 1] = $x;
+        )*
+
+        (operands, idx)
+    }};
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+#[rustfmt::skip]
+pub enum Register {
+    R0, R1, R2, R3, R4, R5, R6, R7,
+    R8, R9, R10, R11, R12, R13, R14, R15,
+    R16, R17, R18, R19, R20, R21, R22, R23,
+    R24, R25, R26, R27, R28, R29, R30, R31,
+    F0, F1, F2, F3, F4, F5, F6, F7,
+    F8, F9, F10, F11, F12, F13, F14, F15,
+    F16, F17, F18, F19, F20, F21, F22, F23,
+    F24, F25, F26, F27, F28, F29, F30, F31,
+    Cr0, Cr1, Cr2, Cr3, Cr4, Cr5, Cr6, Cr7
+}
+
+impl Register {
+    pub fn as_str(&self) -> &'static str {
+        #[rustfmt::skip]
+        const REG_LITERALS: [&str; 72] = [
+            "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7",
+            "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15",
+            "r16", "r17", "r18", "r19", "r20", "r21", "r22", "r23",
+            "r24", "r25", "r26", "r27", "r28", "r29", "r30", "r31",
+            "f0", "f1", "f2", "f3", "f4", "f5", "f6", "f7",
+            "f8", "f9", "f10", "f11", "f12", "f13", "f14", "f15",
+            "f16", "f17", "f18", "f19", "f20", "f21", "f22", "f23",
+            "f24", "f25", "f26", "f27", "f28", "f29", "f30", "f31",
+            "cr0", "cr1", "cr2", "cr3", "cr4", "cr5", "cr6", "cr7"
+        ];
+
+        REG_LITERALS[*self as usize]
+    }
+}
+
+impl Register {
+    #[inline]
+    fn get(num: u32) -> Self {
+        // register fields are 5 bits wide, so every value is one of the 32 variants
+        unsafe { std::mem::transmute(num & 0b11111) }
+    }
+
+    #[inline]
+    fn get_fp(num: u32) -> Self {
+        unsafe { std::mem::transmute((num & 0b11111) + 32) }
+    }
+
+    #[inline]
+    fn get_cr(num: u32) -> Self {
+        unsafe { std::mem::transmute((num & 0b111) + 64) }
+    }
+}
+
+/// Opcodes for 32-bit and 64-bit PowerPC instructions.
+///
+/// *Floating point arithmetic, vector and VSX instructions aren't supported.*
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u32)]
+pub enum Opcode {
+    #[default]
+    Invalid,
+    // *psuedo instructions*
+    NOP,
+    LI,
+    LIS,
+    MR,
+    NOT,
+    SUB,
+    SUBC,
+    MFLR,
+    MTLR,
+    MFCTR,
+    MTCTR,
+    MFXER,
+    MTXER,
+    CMPWI,
+    CMPDI,
+    CMPLWI,
+    CMPLDI,
+    CMPW,
+    CMPD,
+    CMPLW,
+    CMPLD,
+    CRCLR,
+    CRSET,
+    CRMOVE,
+    CRNOT,
+    TRAP,
+    // *branches*
+    B,
+    BC,
+    BCLR,
+    BCCTR,
+    BLR,
+    BCTR,
+    BDNZ,
+    BDZ,
+    BDNZLR,
+    BDZLR,
+    BLT,
+    BGT,
+    BEQ,
+    BSO,
+    BGE,
+    BLE,
+    BNE,
+    BNS,
+    BLTLR,
+    BGTLR,
+    BEQLR,
+    BSOLR,
+    BGELR,
+    BLELR,
+    BNELR,
+    BNSLR,
+    BLTCTR,
+    BGTCTR,
+    BEQCTR,
+    BSOCTR,
+    BGECTR,
+    BLECTR,
+    BNECTR,
+    BNSCTR,
+    SC,
+    // *d-form arithmetic and logical*
+    TDI,
+    TWI,
+    MULLI,
+    SUBFIC,
+    CMPLI,
+    CMPI,
+    ADDIC,
+    ADDIC_,
+    ADDI,
+    ADDIS,
+    ORI,
+    ORIS,
+    XORI,
+    XORIS,
+    ANDI_,
+    ANDIS_,
+    // *rotate and mask*
+    RLWIMI,
+    RLWINM,
+    RLWNM,
+    RLDICL,
+    RLDICR,
+    RLDIC,
+    RLDIMI,
+    RLDCL,
+    RLDCR,
+    // *condition register*
+    MCRF,
+    CRNOR,
+    CRANDC,
+    CRXOR,
+    CRNAND,
+    CRAND,
+    CREQV,
+    CRORC,
+    CROR,
+    MFCR,
+    MTCRF,
+    // *x-form and xo-form*
+    CMP,
+    CMPL,
+    TW,
+    TD,
+    ADD,
+    ADDC,
+    ADDE,
+    ADDME,
+    ADDZE,
+    SUBF,
+    SUBFC,
+    SUBFE,
+    SUBFME,
+    SUBFZE,
+    NEG,
+    MULLW,
+    MULLD,
+    MULHW,
+    MULHWU,
+    MULHD,
+    MULHDU,
+    DIVW,
+    DIVWU,
+    DIVD,
+    DIVDU,
+    AND,
+    ANDC,
+    OR,
+    ORC,
+    XOR,
+    NOR,
+    NAND,
+    EQV,
+    SLW,
+    SRW,
+    SRAW,
+    SRAWI,
+    SLD,
+    SRD,
+    SRAD,
+    SRADI,
+    CNTLZW,
+    CNTLZD,
+    EXTSB,
+    EXTSH,
+    EXTSW,
+    MFSPR,
+    MTSPR,
+    SYNC,
+    LWSYNC,
+    ISYNC,
+    EIEIO,
+    DCBST,
+    DCBF,
+    DCBZ,
+    ICBI,
+    // *indexed loads and stores*
+    LBZX,
+    LBZUX,
+    LHZX,
+    LHZUX,
+    LHAX,
+    LHAUX,
+    LWZX,
+    LWZUX,
+    LWAX,
+    LWAUX,
+    LDX,
+    LDUX,
+    STBX,
+    STBUX,
+    STHX,
+    STHUX,
+    STWX,
+    STWUX,
+    STDX,
+    STDUX,
+    LWBRX,
+    STWBRX,
+    LWARX,
+    LDARX,
+    STWCX_,
+    STDCX_,
+    // *d-form loads and stores*
+    LBZ,
+    LBZU,
+    LHZ,
+    LHZU,
+    LHA,
+    LHAU,
+    LWZ,
+    LWZU,
+    STB,
+    STBU,
+    STH,
+    STHU,
+    STW,
+    STWU,
+    LMW,
+    STMW,
+    LFS,
+    LFSU,
+    LFD,
+    LFDU,
+    STFS,
+    STFSU,
+    STFD,
+    STFDU,
+    LD,
+    LDU,
+    LWA,
+    STD,
+    STDU,
+}
+
+static OPCODE_NAMES: [&str; 209] = [
+    "invalid",
+    "nop",
+    "li",
+    "lis",
+    "mr",
+    "not",
+    "sub",
+    "subc",
+    "mflr",
+    "mtlr",
+    "mfctr",
+    "mtctr",
+    "mfxer",
+    "mtxer",
+    "cmpwi",
+    "cmpdi",
+    "cmplwi",
+    "cmpldi",
+    "cmpw",
+    "cmpd",
+    "cmplw",
+    "cmpld",
+    "crclr",
+    "crset",
+    "crmove",
+    "crnot",
+    "trap",
+    "b",
+    "bc",
+    "bclr",
+    "bcctr",
+    "blr",
+    "bctr",
+    "bdnz",
+    "bdz",
+    "bdnzlr",
+    "bdzlr",
+    "blt",
+    "bgt",
+    "beq",
+    "bso",
+    "bge",
+    "ble",
+    "bne",
+    "bns",
+    "bltlr",
+    "bgtlr",
+    "beqlr",
+    "bsolr",
+    "bgelr",
+    "blelr",
+    "bnelr",
+    "bnslr",
+    "bltctr",
+    "bgtctr",
+    "beqctr",
+    "bsoctr",
+    "bgectr",
+    "blectr",
+    "bnectr",
+    "bnsctr",
+    "sc",
+    "tdi",
+    "twi",
+    "mulli",
+    "subfic",
+    "cmpli",
+    "cmpi",
+    "addic",
+    "addic.",
+    "addi",
+    "addis",
+    "ori",
+    "oris",
+    "xori",
+    "xoris",
+    "andi.",
+    "andis.",
+    "rlwimi",
+    "rlwinm",
+    "rlwnm",
+    "rldicl",
+    "rldicr",
+    "rldic",
+    "rldimi",
+    "rldcl",
+    "rldcr",
+    "mcrf",
+    "crnor",
+    "crandc",
+    "crxor",
+    "crnand",
+    "crand",
+    "creqv",
+    "crorc",
+    "cror",
+    "mfcr",
+    "mtcrf",
+    "cmp",
+    "cmpl",
+    "tw",
+    "td",
+    "add",
+    "addc",
+    "adde",
+    "addme",
+    "addze",
+    "subf",
+    "subfc",
+    "subfe",
+    "subfme",
+    "subfze",
+    "neg",
+    "mullw",
+    "mulld",
+    "mulhw",
+    "mulhwu",
+    "mulhd",
+    "mulhdu",
+    "divw",
+    "divwu",
+    "divd",
+    "divdu",
+    "and",
+    "andc",
+    "or",
+    "orc",
+    "xor",
+    "nor",
+    "nand",
+    "eqv",
+    "slw",
+    "srw",
+    "sraw",
+    "srawi",
+    "sld",
+    "srd",
+    "srad",
+    "sradi",
+    "cntlzw",
+    "cntlzd",
+    "extsb",
+    "extsh",
+    "extsw",
+    "mfspr",
+    "mtspr",
+    "sync",
+    "lwsync",
+    "isync",
+    "eieio",
+    "dcbst",
+    "dcbf",
+    "dcbz",
+    "icbi",
+    "lbzx",
+    "lbzux",
+    "lhzx",
+    "lhzux",
+    "lhax",
+    "lhaux",
+    "lwzx",
+    "lwzux",
+    "lwax",
+    "lwaux",
+    "ldx",
+    "ldux",
+    "stbx",
+    "stbux",
+    "sthx",
+    "sthux",
+    "stwx",
+    "stwux",
+    "stdx",
+    "stdux",
+    "lwbrx",
+    "stwbrx",
+    "lwarx",
+    "ldarx",
+    "stwcx.",
+    "stdcx.",
+    "lbz",
+    "lbzu",
+    "lhz",
+    "lhzu",
+    "lha",
+    "lhau",
+    "lwz",
+    "lwzu",
+    "stb",
+    "stbu",
+    "sth",
+    "sthu",
+    "stw",
+    "stwu",
+    "lmw",
+    "stmw",
+    "lfs",
+    "lfsu",
+    "lfd",
+    "lfdu",
+    "stfs",
+    "stfsu",
+    "stfd",
+    "stfdu",
+    "ld",
+    "ldu",
+    "lwa",
+    "std",
+    "stdu",
+];
+
+impl Opcode {
+    pub fn as_str(&self) -> &'static str {
+        OPCODE_NAMES[*self as usize]
+    }
+
+    /// Whether the instruction only exists in 64-bit implementations.
+    fn is_64(&self) -> bool {
+        use Opcode::*;
+
+        matches!(
+            self,
+            TDI | TD
+                | MULLD
+                | MULHD
+                | MULHDU
+                | DIVD
+                | DIVDU
+                | SLD
+                | SRD
+                | SRAD
+                | SRADI
+                | CNTLZD
+                | EXTSW
+                | RLDICL
+                | RLDICR
+                | RLDIC
+                | RLDIMI
+                | RLDCL
+                | RLDCR
+                | LWAX
+                | LWAUX
+                | LDX
+                | LDUX
+                | STDX
+                | STDUX
+                | LDARX
+                | STDCX_
+                | LD
+                | LDU
+                | LWA
+                | STD
+                | STDU
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Operand {
+    #[default]
+    Nothing,
+    Register(Register),
+    Immediate(i64),
+    /// Bit of the condition register, rendered as `4*cr<n>+<lt|gt|eq|un>`.
+    CrBit(u8),
+    /// Offset relative to the instruction's address, used by branches.
+    PCOffset(i64),
+    /// Absolute address, either encoded directly or resolved from a [`Operand::PCOffset`].
+    Address(u64),
+    /// Memory reference of the form `offset(base)`, where a base of `r0` reads as zero.
+    Memory(Register, i32),
+}
+
+impl ToTokens for Operand {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        match self {
+            Self::Register(reg) => stream.push(reg.as_str(), CONFIG.colors.asm.register),
+            Self::Immediate(imm) => {
+                stream.push_immediate(*imm, Radix::Decimal, CONFIG.colors.asm.immediate)
+            }
+            Self::CrBit(bit) => {
+                let cond = ["lt", "gt", "eq", "un"][*bit as usize % 4];
+                if *bit >= 4 {
+                    let field = Register::get_cr(*bit as u32 / 4);
+                    stream.push("4*", CONFIG.colors.asm.expr);
+                    stream.push(field.as_str(), CONFIG.colors.asm.register);
+                    stream.push("+", CONFIG.colors.asm.expr);
+                }
+                stream.push(cond, CONFIG.colors.asm.register);
+            }
+            Self::PCOffset(offset) => {
+                stream.push_immediate(*offset, Radix::Hex, CONFIG.colors.asm.immediate)
+            }
+            Self::Address(addr) => match symbols.get_sym_by_addr(*addr as usize) {
+                Some(symbol) => {
+                    for token in symbol.name() {
+                        stream.push_token(token.clone());
+                    }
+                }
+                None => {
+                    stream.push_fmt(format_args!("{addr:#x}"), CONFIG.colors.asm.pointer);
+                    symbols.tokenize_offset(stream, *addr as usize);
+                }
+            },
+            Self::Memory(base, offset) => {
+                stream.push_immediate(*offset as i64, Radix::Decimal, CONFIG.colors.asm.immediate);
+                stream.push("(", CONFIG.colors.brackets);
+                match base {
+                    Register::R0 => stream.push("0", CONFIG.colors.asm.immediate),
+                    base => stream.push(base.as_str(), CONFIG.colors.asm.register),
+                }
+                stream.push(")", CONFIG.colors.brackets);
+            }
+            Self::Nothing => unreachable!("empty operand encountered"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    opcode: Opcode,
+    operands: [Operand; 5],
+    operand_count: usize,
+    /// Whether the result is recorded in `cr0`, shown as a `.` suffix.
+    rc: bool,
+    /// Whether overflow is recorded in `xer`, shown as an `o` suffix.
+    oe: bool,
+    /// Whether a branch saves the return address in `lr`, shown as an `l` suffix.
+    lk: bool,
+    /// Whether a branch target is absolute, shown as an `a` suffix.
+    aa: bool,
+}
+
+impl decoder::Decoded for Instruction {
+    fn width(&self) -> usize {
+        4
+    }
+
+    fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
+        for operand in &mut self.operands[..self.operand_count] {
+            if let Operand::PCOffset(offset) = operand {
+                let addr = (addr as u64).wrapping_add_signed(*offset);
+                *operand = Operand::Address(addr);
+            }
+        }
+    }
+
+    fn xref(&self) -> Option<(usize, XrefKind)> {
+        // only branches have addresses
+        let addr = self.operands[..self.operand_count].iter().find_map(|operand| match operand {
+            Operand::Address(addr) => Some(*addr as usize),
+            _ => None,
+        })?;
+
+        let kind = if self.lk { XrefKind::Call } else { XrefKind::Jump };
+        Some((addr, kind))
+    }
+
+    fn is_terminator(&self) -> bool {
+        match self.opcode {
+            Opcode::B | Opcode::BLR | Opcode::BCTR => !self.lk,
+            Opcode::TRAP => true,
+            _ => false,
+        }
+    }
+}
+
+impl Instruction {
+    fn new(opcode: Opcode, (operands, operand_count): ([Operand; 5], usize)) -> Self {
+        Self {
+            opcode,
+            operands,
+            operand_count,
+            rc: false,
+            oe: false,
+            lk: false,
+            aa: false,
+        }
+    }
+
+    fn with_rc(mut self, dword: u32) -> Self {
+        self.rc = dword & 1 == 1;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Decoder {
+    pub is_64: bool,
+    pub big_endian: bool,
+}
+
+impl decoder::Decodable for Decoder {
+    type Instruction = Instruction;
+
+    fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
+        decode(reader, self).map_err(|err| Error::new(err, 4))
+    }
+
+    fn max_width(&self) -> usize {
+        4
+    }
+
+    fn alignment(&self) -> usize {
+        4
+    }
+}
+
+fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let mut bytes = [0u8; 4];
+    reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+    let dword = if decoder.big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    };
+
+    let inst = match dword >> 26 {
+        2 => decode_trap_immediate(TDI, dword),
+        3 => decode_trap_immediate(TWI, dword),
+        7 => decode_immediate(MULLI, dword),
+        8 => decode_immediate(SUBFIC, dword),
+        10 => decode_compare_immediate(CMPLI, dword)?,
+        11 => decode_compare_immediate(CMPI, dword)?,
+        12 => decode_immediate(ADDIC, dword),
+        13 => decode_immediate(ADDIC_, dword),
+        14 => decode_immediate(ADDI, dword),
+        15 => decode_immediate(ADDIS, dword),
+        16 => decode_bc(dword),
+        17 if dword & 0b10 != 0 => Instruction::new(SC, operands![]),
+        18 => decode_branch(dword),
+        19 => decode_19(dword)?,
+        20 => decode_rotate(RLWIMI, dword),
+        21 => decode_rotate(RLWINM, dword),
+        23 => decode_rotate(RLWNM, dword),
+        24 => decode_logical(ORI, dword),
+        25 => decode_logical(ORIS, dword),
+        26 => decode_logical(XORI, dword),
+        27 => decode_logical(XORIS, dword),
+        28 => decode_logical(ANDI_, dword),
+        29 => decode_logical(ANDIS_, dword),
+        30 => decode_rotate_64(dword)?,
+        31 => decode_31(dword)?,
+        32 => decode_memory(LWZ, dword),
+        33 => decode_memory(LWZU, dword),
+        34 => decode_memory(LBZ, dword),
+        35 => decode_memory(LBZU, dword),
+        36 => decode_memory(STW, dword),
+        37 => decode_memory(STWU, dword),
+        38 => decode_memory(STB, dword),
+        39 => decode_memory(STBU, dword),
+        40 => decode_memory(LHZ, dword),
+        41 => decode_memory(LHZU, dword),
+        42 => decode_memory(LHA, dword),
+        43 => decode_memory(LHAU, dword),
+        44 => decode_memory(STH, dword),
+        45 => decode_memory(STHU, dword),
+        46 => decode_memory(LMW, dword),
+        47 => decode_memory(STMW, dword),
+        48 => decode_fp_memory(LFS, dword),
+        49 => decode_fp_memory(LFSU, dword),
+        50 => decode_fp_memory(LFD, dword),
+        51 => decode_fp_memory(LFDU, dword),
+        52 => decode_fp_memory(STFS, dword),
+        53 => decode_fp_memory(STFSU, dword),
+        54 => decode_fp_memory(STFD, dword),
+        55 => decode_fp_memory(STFDU, dword),
+        58 => match dword & 0b11 {
+            0b00 => decode_memory_ds(LD, dword),
+            0b01 => decode_memory_ds(LDU, dword),
+            0b10 => decode_memory_ds(LWA, dword),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        62 => match dword & 0b11 {
+            0b00 => decode_memory_ds(STD, dword),
+            0b01 => decode_memory_ds(STDU, dword),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        // floating point arithmetic, vector and VSX instructions aren't supported
+        4 | 59 | 60 | 61 | 63 => return Err(ErrorKind::IncompleteDecoder),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    if !decoder.is_64 {
+        if inst.opcode.is_64() {
+            return Err(ErrorKind::InvalidOpcode);
+        }
+
+        // doubleword comparisons
+        if matches!(inst.opcode, CMP | CMPL | CMPI | CMPLI)
+            && inst.operands[1] == Operand::Immediate(1)
+        {
+            return Err(ErrorKind::InvalidOperand);
+        }
+    }
+
+    Ok(map_to_psuedo(inst))
+}
+
+impl ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        if self.rc || self.oe || self.lk || self.aa {
+            let mut mnemomic = self.opcode.as_str().to_string();
+            for (set, suffix) in [
+                (self.lk, 'l'),
+                (self.aa, 'a'),
+                (self.oe, 'o'),
+                (self.rc, '.'),
+            ] {
+                if set {
+                    mnemomic.push(suffix);
+                }
+            }
+            stream.push_owned_mnemonic(mnemomic, CONFIG.colors.asm.opcode);
+        } else {
+            stream.push_mnemonic(self.opcode.as_str(), CONFIG.colors.asm.opcode);
+        }
+
+        // there are operands
+        if self.operand_count > 0 {
+            stream.push(" ", colors::WHITE);
+
+            // iterate through operands
+            for idx in 0..self.operand_count {
+                self.operands[idx].tokenize(stream, symbols);
+
+                // separator
+                if idx != self.operand_count - 1 {
+                    stream.push_separator(CONFIG.colors.asm.expr);
+                }
+            }
+        }
+    }
+}
+
+// NOTE: doing closure assignment in `map_to_psuedo` makes the compiler
+// assign function mappings in the array on each call.
+static MAPPING: Lazy<[fn(&mut Instruction); 209]> = Lazy::new(|| unsafe {
+    const DO_NOTHING: fn(&mut Instruction) = |_| {};
+    static mut MAPPING: [fn(&mut Instruction); 209] = [DO_NOTHING; 209];
+
+    MAPPING[Opcode::ADDI as usize] = |inst| {
+        if inst.operands[1] == Operand::Register(Register::R0) {
+            inst.opcode = Opcode::LI;
+            inst.operands[1] = inst.operands[2];
+            inst.operand_count = 2;
+        }
+    };
+
+    MAPPING[Opcode::ADDIS as usize] = |inst| {
+        if inst.operands[1] == Operand::Register(Register::R0) {
+            inst.opcode = Opcode::LIS;
+            inst.operands[1] = inst.operands[2];
+            inst.operand_count = 2;
+        }
+    };
+
+    MAPPING[Opcode::ORI as usize] = |inst| {
+        if inst.operands[0] == Operand::Register(Register::R0)
+            && inst.operands[1] == Operand::Register(Register::R0)
+            && inst.operands[2] == Operand::Immediate(0)
+        {
+            inst.opcode = Opcode::NOP;
+            inst.operand_count = 0;
+        }
+    };
+
+    MAPPING[Opcode::OR as usize] = |inst| {
+        if inst.operands[1] == inst.operands[2] {
+            inst.opcode = Opcode::MR;
+            inst.operand_count = 2;
+        }
+    };
+
+    MAPPING[Opcode::NOR as usize] = |inst| {
+        if inst.operands[1] == inst.operands[2] {
+            inst.opcode = Opcode::NOT;
+            inst.operand_count = 2;
+        }
+    };
+
+    // subf rd, ra, rb computes rb - ra
+    MAPPING[Opcode::SUBF as usize] = |inst| {
+        inst.opcode = Opcode::SUB;
+        inst.operands.swap(1, 2);
+    };
+
+    MAPPING[Opcode::SUBFC as usize] = |inst| {
+        inst.opcode = Opcode::SUBC;
+        inst.operands.swap(1, 2);
+    };
+
+    MAPPING[Opcode::MFSPR as usize] = |inst| {
+        inst.opcode = match inst.operands[1] {
+            Operand::Immediate(1) => Opcode::MFXER,
+            Operand::Immediate(8) => Opcode::MFLR,
+            Operand::Immediate(9) => Opcode::MFCTR,
+            _ => return,
+        };
+        inst.operand_count = 1;
+    };
+
+    MAPPING[Opcode::MTSPR as usize] = |inst| {
+        inst.opcode = match inst.operands[0] {
+            Operand::Immediate(1) => Opcode::MTXER,
+            Operand::Immediate(8) => Opcode::MTLR,
+            Operand::Immediate(9) => Opcode::MTCTR,
+            _ => return,
+        };
+        inst.operands[0] = inst.operands[1];
+        inst.operand_count = 1;
+    };
+
+    // cmp crf, l, ra, rb => cmpw/cmpd [crf,] ra, rb
+    const COMPARE: fn(&mut Instruction) = |inst| {
+        let (word, double) = match inst.opcode {
+            Opcode::CMP => (Opcode::CMPW, Opcode::CMPD),
+            Opcode::CMPL => (Opcode::CMPLW, Opcode::CMPLD),
+            Opcode::CMPI => (Opcode::CMPWI, Opcode::CMPDI),
+            Opcode::CMPLI => (Opcode::CMPLWI, Opcode::CMPLDI),
+            _ => return,
+        };
+
+        inst.opcode = if inst.operands[1] == Operand::Immediate(1) {
+            double
+        } else {
+            word
+        };
+
+        if inst.operands[0] == Operand::Register(Register::Cr0) {
+            inst.operands = [
+                inst.operands[2],
+                inst.operands[3],
+                Operand::Nothing,
+                Operand::Nothing,
+                Operand::Nothing,
+            ];
+            inst.operand_count = 2;
+        } else {
+            inst.operands[1] = inst.operands[2];
+            inst.operands[2] = inst.operands[3];
+            inst.operand_count = 3;
+        }
+    };
+
+    MAPPING[Opcode::CMP as usize] = COMPARE;
+    MAPPING[Opcode::CMPL as usize] = COMPARE;
+    MAPPING[Opcode::CMPI as usize] = COMPARE;
+    MAPPING[Opcode::CMPLI as usize] = COMPARE;
+
+    MAPPING[Opcode::CRXOR as usize] = |inst| {
+        if inst.operands[0] == inst.operands[1] && inst.operands[1] == inst.operands[2] {
+            inst.opcode = Opcode::CRCLR;
+            inst.operand_count = 1;
+        }
+    };
+
+    MAPPING[Opcode::CREQV as usize] = |inst| {
+        if inst.operands[0] == inst.operands[1] && inst.operands[1] == inst.operands[2] {
+            inst.opcode = Opcode::CRSET;
+            inst.operand_count = 1;
+        }
+    };
+
+    MAPPING[Opcode::CROR as usize] = |inst| {
+        if inst.operands[1] == inst.operands[2] {
+            inst.opcode = Opcode::CRMOVE;
+            inst.operand_count = 2;
+        }
+    };
+
+    MAPPING[Opcode::CRNOR as usize] = |inst| {
+        if inst.operands[1] == inst.operands[2] {
+            inst.opcode = Opcode::CRNOT;
+            inst.operand_count = 2;
+        }
+    };
+
+    MAPPING[Opcode::TW as usize] = |inst| {
+        if inst.operands[0] == Operand::Immediate(31)
+            && inst.operands[1] == Operand::Register(Register::R0)
+            && inst.operands[2] == Operand::Register(Register::R0)
+        {
+            inst.opcode = Opcode::TRAP;
+            inst.operand_count = 0;
+        }
+    };
+
+    MAPPING
+});
+
+#[inline]
+fn map_to_psuedo(mut inst: Instruction) -> Instruction {
+    MAPPING[inst.opcode as usize](&mut inst);
+    inst
+}
+
+/// Register in bits 6..11, which is either rD, rS, BO or TO.
+#[inline]
+fn rd(dword: u32) -> Register {
+    Register::get(dword >> 21)
+}
+
+/// Register in bits 11..16, which is either rA or BI.
+#[inline]
+fn ra(dword: u32) -> Register {
+    Register::get(dword >> 16)
+}
+
+/// Register in bits 16..21, which is either rB or SH.
+#[inline]
+fn rb(dword: u32) -> Register {
+    Register::get(dword >> 11)
+}
+
+/// Decode's instructions of the form `rD, rA, SIMM`.
+fn decode_immediate(opcode: Opcode, dword: u32) -> Instruction {
+    Instruction::new(
+        opcode,
+        operands![
+            Operand::Register(rd(dword)),
+            Operand::Register(ra(dword)),
+            Operand::Immediate(dword as i16 as i64),
+        ],
+    )
+}
+
+/// Decode's logical instructions of the form `rA, rS, UIMM`.
+fn decode_logical(opcode: Opcode, dword: u32) -> Instruction {
+    Instruction::new(
+        opcode,
+        operands![
+            Operand::Register(ra(dword)),
+            Operand::Register(rd(dword)),
+            Operand::Immediate(dword as u16 as i64),
+        ],
+    )
+}
+
+/// Decode's twi and tdi.
+fn decode_trap_immediate(opcode: Opcode, dword: u32) -> Instruction {
+    Instruction::new(
+        opcode,
+        operands![
+            Operand::Immediate((dword >> 21 & 0b11111) as i64),
+            Operand::Register(ra(dword)),
+            Operand::Immediate(dword as i16 as i64),
+        ],
+    )
+}
+
+/// Decode's cmpi and cmpli of the form `crfD, L, rA, IMM`.
+fn decode_compare_immediate(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    if dword >> 22 & 1 != 0 {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
+    let imm = if opcode == Opcode::CMPI {
+        dword as i16 as i64
+    } else {
+        dword as u16 as i64
+    };
+
+    Ok(Instruction::new(
+        opcode,
+        operands![
+            Operand::Register(Register::get_cr(dword >> 23)),
+            Operand::Immediate((dword >> 21 & 1) as i64),
+            Operand::Register(ra(dword)),
+            Operand::Immediate(imm),
+        ],
+    ))
+}
+
+/// Decode's loads and stores of the form `rD, d(rA)`.
+fn decode_memory(opcode: Opcode, dword: u32) -> Instruction {
+    Instruction::new(
+        opcode,
+        operands![
+            Operand::Register(rd(dword)),
+            Operand::Memory(ra(dword), dword as i16 as i32),
+        ],
+    )
+}
+
+/// Decode's floating point loads and stores of the form `frD, d(rA)`.
+fn decode_fp_memory(opcode: Opcode, dword: u32) -> Instruction {
+    Instruction::new(
+        opcode,
+        operands![
+            Operand::Register(Register::get_fp(dword >> 21)),
+            Operand::Memory(ra(dword), dword as i16 as i32),
+        ],
+    )
+}
+
+/// Decode's ld, ldu, lwa, std and stdu, who's displacement is a multiple of 4.
+fn decode_memory_ds(opcode: Opcode, dword: u32) -> Instruction {
+    Instruction::new(
+        opcode,
+        operands![
+            Operand::Register(rd(dword)),
+            Operand::Memory(ra(dword), (dword & !0b11) as i16 as i32),
+        ],
+    )
+}
+
+/// Decode's b, ba, bl and bla.
+fn decode_branch(dword: u32) -> Instruction {
+    // sign extend the 26-bit displacement
+    let offset = ((dword & 0x03ff_fffc) << 6) as i32 as i64 >> 6;
+    let aa = dword & 0b10 != 0;
+
+    let target = if aa {
+        Operand::Address(offset as u64)
+    } else {
+        Operand::PCOffset(offset)
+    };
+
+    let mut inst = Instruction::new(Opcode::B, operands![target]);
+    inst.aa = aa;
+    inst.lk = dword & 1 == 1;
+    inst
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BranchTo {
+    Offset,
+    Lr,
+    Ctr,
+}
+
+/// Picks the extended mnemonic for a conditional branch, based on it's BO and BI fields.
+fn simplify_branch(bo: u32, bi: u32, to: BranchTo) -> Option<(Opcode, bool)> {
+    use Opcode::*;
+
+    // branch always
+    if bo & 0b10100 == 0b10100 {
+        let opcode = match to {
+            BranchTo::Offset => B,
+            BranchTo::Lr => BLR,
+            BranchTo::Ctr => BCTR,
+        };
+
+        return Some((opcode, false));
+    }
+
+    // branch if the condition bit is true or false
+    let conditions = match bo & 0b11100 {
+        0b01100 => Some([
+            BLT, BGT, BEQ, BSO, BLTLR, BGTLR, BEQLR, BSOLR, BLTCTR, BGTCTR, BEQCTR, BSOCTR,
+        ]),
+        0b00100 => Some([
+            BGE, BLE, BNE, BNS, BGELR, BLELR, BNELR, BNSLR, BGECTR, BLECTR, BNECTR, BNSCTR,
+        ]),
+        _ => None,
+    };
+
+    if let Some(conditions) = conditions {
+        let kind = match to {
+            BranchTo::Offset => 0,
+            BranchTo::Lr => 4,
+            BranchTo::Ctr => 8,
+        };
+
+        return Some((conditions[kind + bi as usize % 4], true));
+    }
+
+    // decrement ctr and branch if it's non-zero or zero, which bcctr can't do
+    match (bo & 0b10110, to) {
+        (0b10000, BranchTo::Offset) => Some((BDNZ, false)),
+        (0b10000, BranchTo::Lr) => Some((BDNZLR, false)),
+        (0b10010, BranchTo::Offset) => Some((BDZ, false)),
+        (0b10010, BranchTo::Lr) => Some((BDZLR, false)),
+        _ => None,
+    }
+}
+
+/// Builds a conditional branch, using it's extended mnemonic when there is one.
+fn decode_conditional(dword: u32, to: BranchTo, target: Option<Operand>) -> Instruction {
+    let bo = dword >> 21 & 0b11111;
+    let bi = dword >> 16 & 0b11111;
+
+    let mut inst = match simplify_branch(bo, bi, to) {
+        Some((opcode, conditional)) => {
+            let mut inst = Instruction::new(opcode, operands![]);
+
+            // the condition register field is implied when it's cr0
+            if conditional && bi / 4 != 0 {
+                inst.operands[0] = Operand::Register(Register::get_cr(bi / 4));
+                inst.operand_count = 1;
+            }
+
+            inst
+        }
+        None => {
+            let opcode = match to {
+                BranchTo::Offset => Opcode::BC,
+                BranchTo::Lr => Opcode::BCLR,
+                BranchTo::Ctr => Opcode::BCCTR,
+            };
+
+            Instruction::new(
+                opcode,
+                operands![Operand::Immediate(bo as i64), Operand::CrBit(bi as u8)],
+            )
+        }
+    };
+
+    if let Some(target) = target {
+        inst.operands[inst.operand_count] = target;
+        inst.operand_count += 1;
+    }
+
+    inst.lk = dword & 1 == 1;
+    inst
+}
+
+/// Decode's bc, bca, bcl and bcla.
+fn decode_bc(dword: u32) -> Instruction {
+    let offset = (dword & 0xfffc) as i16 as i64;
+    let aa = dword & 0b10 != 0;
+
+    let target = if aa {
+        Operand::Address(offset as u64)
+    } else {
+        Operand::PCOffset(offset)
+    };
+
+    let mut inst = decode_conditional(dword, BranchTo::Offset, Some(target));
+    inst.aa = aa;
+    inst
+}
+
+/// Decode's instructions with a primary opcode of 19, branches to `lr` or `ctr` and
+/// condition register logic.
+fn decode_19(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let crbit = |shift: u32| Operand::CrBit((dword >> shift & 0b11111) as u8);
+
+    let opcode = match dword >> 1 & 0x3ff {
+        0 => {
+            return Ok(Instruction::new(
+                MCRF,
+                operands![
+                    Operand::Register(Register::get_cr(dword >> 23)),
+                    Operand::Register(Register::get_cr(dword >> 18)),
+                ],
+            ))
+        }
+        16 => return Ok(decode_conditional(dword, BranchTo::Lr, None)),
+        528 => {
+            // bcctr can't decrement ctr
+            if dword >> 23 & 1 == 0 {
+                return Err(ErrorKind::InvalidOperand);
+            }
+
+            return Ok(decode_conditional(dword, BranchTo::Ctr, None));
+        }
+        150 => return Ok(Instruction::new(ISYNC, operands![])),
+        33 => CRNOR,
+        129 => CRANDC,
+        193 => CRXOR,
+        225 => CRNAND,
+        257 => CRAND,
+        289 => CREQV,
+        417 => CRORC,
+        449 => CROR,
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(Instruction::new(
+        opcode,
+        operands![crbit(21), crbit(16), crbit(11)],
+    ))
+}
+
+/// Decode's rlwimi, rlwinm and rlwnm of the form `rA, rS, SH, MB, ME`.
+fn decode_rotate(opcode: Opcode, dword: u32) -> Instruction {
+    let shift = if opcode == Opcode::RLWNM {
+        Operand::Register(rb(dword))
+    } else {
+        Operand::Immediate((dword >> 11 & 0b11111) as i64)
+    };
+
+    Instruction::new(
+        opcode,
+        operands![
+            Operand::Register(ra(dword)),
+            Operand::Register(rd(dword)),
+            shift,
+            Operand::Immediate((dword >> 6 & 0b11111) as i64),
+            Operand::Immediate((dword >> 1 & 0b11111) as i64),
+        ],
+    )
+    .with_rc(dword)
+}
+
+/// Decode's the 64-bit rotates of the form `rA, rS, SH, MB` or `rA, rS, rB, MB`.
+fn decode_rotate_64(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    // 6-bit fields are split, with their most significant bit stored separately
+    let sh = (dword >> 11 & 0b11111) | (dword >> 1 & 1) << 5;
+    let mb = (dword >> 6 & 0b11111) | (dword >> 5 & 1) << 5;
+
+    let (opcode, shift) = match dword >> 2 & 0b111 {
+        0b000 => (RLDICL, Operand::Immediate(sh as i64)),
+        0b001 => (RLDICR, Operand::Immediate(sh as i64)),
+        0b010 => (RLDIC, Operand::Immediate(sh as i64)),
+        0b011 => (RLDIMI, Operand::Immediate(sh as i64)),
+        0b100 => match dword >> 1 & 0b1111 {
+            0b1000 => (RLDCL, Operand::Register(rb(dword))),
+            0b1001 => (RLDCR, Operand::Register(rb(dword))),
+            _ => return Err(ErrorKind::InvalidOpcode),
+        },
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(Instruction::new(
+        opcode,
+        operands![
+            Operand::Register(ra(dword)),
+            Operand::Register(rd(dword)),
+            shift,
+            Operand::Immediate(mb as i64),
+        ],
+    )
+    .with_rc(dword))
+}
+
+/// Decode's instructions with a primary opcode of 31, the register to register arithmetic,
+/// logical operations and indexed loads and stores.
+fn decode_31(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let (rd, ra, rb) = (rd(dword), ra(dword), rb(dword));
+    let reg = Operand::Register;
+
+    // XO-form arithmetic of the form `rD, rA, rB` with an OE bit
+    let arith = match dword >> 1 & 0x1ff {
+        8 => Some((SUBFC, true)),
+        9 => Some((MULHDU, true)),
+        10 => Some((ADDC, true)),
+        11 => Some((MULHWU, true)),
+        40 => Some((SUBF, true)),
+        73 => Some((MULHD, true)),
+        75 => Some((MULHW, true)),
+        104 => Some((NEG, false)),
+        136 => Some((SUBFE, true)),
+        138 => Some((ADDE, true)),
+        200 => Some((SUBFZE, false)),
+        202 => Some((ADDZE, false)),
+        232 => Some((SUBFME, false)),
+        233 => Some((MULLD, true)),
+        234 => Some((ADDME, false)),
+        235 => Some((MULLW, true)),
+        266 => Some((ADD, true)),
+        457 => Some((DIVDU, true)),
+        459 => Some((DIVWU, true)),
+        489 => Some((DIVD, true)),
+        491 => Some((DIVW, true)),
+        _ => None,
+    };
+
+    if let Some((opcode, has_rb)) = arith {
+        let mut inst = if has_rb {
+            Instruction::new(opcode, operands![reg(rd), reg(ra), reg(rb)])
+        } else {
+            Instruction::new(opcode, operands![reg(rd), reg(ra)])
+        };
+
+        // the high word multiplies don't have an OE bit
+        let oe = dword >> 10 & 1 == 1;
+        if oe && matches!(opcode, MULHW | MULHWU | MULHD | MULHDU) {
+            return Err(ErrorKind::InvalidOpcode);
+        }
+
+        inst.oe = oe;
+        return Ok(inst.with_rc(dword));
+    }
+
+    let xo = dword >> 1 & 0x3ff;
+    let inst = match xo {
+        0 | 32 => {
+            if dword >> 22 & 1 != 0 {
+                return Err(ErrorKind::InvalidOperand);
+            }
+
+            Instruction::new(
+                if xo == 0 { CMP } else { CMPL },
+                operands![
+                    Operand::Register(Register::get_cr(dword >> 23)),
+                    Operand::Immediate((dword >> 21 & 1) as i64),
+                    reg(ra),
+                    reg(rb),
+                ],
+            )
+        }
+        4 | 68 => Instruction::new(
+            if xo == 4 { TW } else { TD },
+            operands![
+                Operand::Immediate((dword >> 21 & 0b11111) as i64),
+                reg(ra),
+                reg(rb)
+            ],
+        ),
+        19 => Instruction::new(MFCR, operands![reg(rd)]),
+        144 => Instruction::new(
+            MTCRF,
+            operands![Operand::Immediate((dword >> 12 & 0xff) as i64), reg(rd)],
+        ),
+        339 | 467 => {
+            // the two halves of the spr field are swapped
+            let spr = (dword >> 16 & 0b11111) | (dword >> 11 & 0b11111) << 5;
+            let spr = Operand::Immediate(spr as i64);
+
+            if xo == 339 {
+                Instruction::new(MFSPR, operands![reg(rd), spr])
+            } else {
+                Instruction::new(MTSPR, operands![spr, reg(rd)])
+            }
+        }
+        598 => match dword >> 21 & 0b11 {
+            0 => Instruction::new(SYNC, operands![]),
+            1 => Instruction::new(LWSYNC, operands![]),
+            _ => return Err(ErrorKind::InvalidOperand),
+        },
+        854 => Instruction::new(EIEIO, operands![]),
+        54 | 86 | 982 | 1014 => {
+            let opcode = match xo {
+                54 => DCBST,
+                86 => DCBF,
+                982 => ICBI,
+                _ => DCBZ,
+            };
+
+            Instruction::new(opcode, operands![reg(ra), reg(rb)])
+        }
+        // logical operations of the form `rA, rS, rB`
+        24 | 27 | 28 | 60 | 124 | 284 | 316 | 412 | 444 | 476 | 536 | 539 | 792 | 794 => {
+            let opcode = match xo {
+                24 => SLW,
+                27 => SLD,
+                28 => AND,
+                60 => ANDC,
+                124 => NOR,
+                284 => EQV,
+                316 => XOR,
+                412 => ORC,
+                444 => OR,
+                476 => NAND,
+                536 => SRW,
+                539 => SRD,
+                792 => SRAW,
+                _ => SRAD,
+            };
+
+            Instruction::new(opcode, operands![reg(ra), reg(rd), reg(rb)]).with_rc(dword)
+        }
+        // unary operations of the form `rA, rS`
+        26 | 58 | 922 | 954 | 986 => {
+            let opcode = match xo {
+                26 => CNTLZW,
+                58 => CNTLZD,
+                922 => EXTSH,
+                954 => EXTSB,
+                _ => EXTSW,
+            };
+
+            Instruction::new(opcode, operands![reg(ra), reg(rd)]).with_rc(dword)
+        }
+        824 => Instruction::new(
+            SRAWI,
+            operands![
+                reg(ra),
+                reg(rd),
+                Operand::Immediate((dword >> 11 & 0b11111) as i64)
+            ],
+        )
+        .with_rc(dword),
+        // sradi has a 6-bit shift, so the lowest bit of the extended opcode is part of it
+        826 | 827 => {
+            let sh = (dword >> 11 & 0b11111) | (dword >> 1 & 1) << 5;
+            Instruction::new(
+                SRADI,
+                operands![reg(ra), reg(rd), Operand::Immediate(sh as i64)],
+            )
+            .with_rc(dword)
+        }
+        // indexed loads and stores of the form `rD, rA, rB`
+        _ => {
+            let opcode = match xo {
+                20 => LWARX,
+                21 => LDX,
+                23 => LWZX,
+                53 => LDUX,
+                55 => LWZUX,
+                84 => LDARX,
+                87 => LBZX,
+                119 => LBZUX,
+                149 => STDX,
+                150 => STWCX_,
+                151 => STWX,
+                181 => STDUX,
+                183 => STWUX,
+                214 => STDCX_,
+                215 => STBX,
+                247 => STBUX,
+                279 => LHZX,
+                311 => LHZUX,
+                341 => LWAX,
+                343 => LHAX,
+                373 => LWAUX,
+                375 => LHAUX,
+                407 => STHX,
+                439 => STHUX,
+                534 => LWBRX,
+                662 => STWBRX,
+                _ => return Err(ErrorKind::InvalidOpcode),
+            };
+
+            Instruction::new(opcode, operands![reg(rd), reg(ra), reg(rb)])
+        }
+    };
+
+    Ok(inst)
+}
@@ -0,0 +1,217 @@
+#![cfg(test)]
+
+use crate::Decoder;
+use decoder::testing::{decode, decode_error, decode_instructions, Endianness};
+use decoder::ErrorKind;
+use object::{Object, ObjectSection, SectionKind};
+
+impl Endianness for Decoder {
+    fn new(is_64: bool, big_endian: bool) -> Self {
+        Self { is_64, big_endian }
+    }
+}
+
+/// Decodes the `.text` section of a little endian object, resolving addresses relative to the
+/// start of the section.
+fn decode_object(binary: &[u8]) -> Vec<String> {
+    let binary = object::File::parse(binary).unwrap();
+    let section = binary
+        .sections()
+        .filter(|s| s.kind() == SectionKind::Text)
+        .find(|t| t.name() == Ok(".text"))
+        .expect("failed to find `.text` section");
+
+    let binary = section.uncompressed_data().unwrap();
+    let decoder = Decoder {
+        is_64: true,
+        big_endian: false,
+    };
+
+    decode_instructions(decoder, &binary, 0, &debugvault::Index::default())
+}
+
+#[test]
+fn endianness() {
+    let be = Decoder {
+        is_64: true,
+        big_endian: true,
+    };
+    let le = Decoder {
+        is_64: true,
+        big_endian: false,
+    };
+
+    assert_eq!(decode(&be, &[0x38, 0x61, 0xff, 0xf0]), "addi r3, r1, -16");
+    assert_eq!(decode(&le, &[0xf0, 0xff, 0x61, 0x38]), "addi r3, r1, -16");
+    assert_eq!(decode_error(&le, &[0x38, 0x61, 0xff, 0xf0]), ErrorKind::IncompleteDecoder);
+}
+
+#[test]
+fn arith() {
+    Decoder::test_display(0x7c642a14, "add r3, r4, r5");
+    Decoder::test_display(0x7c642e15, "addo. r3, r4, r5");
+    Decoder::test_display(0x7c642850, "sub r3, r5, r4");
+    Decoder::test_display(0x7c6400d0, "neg r3, r4");
+    Decoder::test_display(0x7c6429d6, "mullw r3, r4, r5");
+    Decoder::test_display(0x7c642b92, "divdu r3, r4, r5");
+    Decoder::test_display(0x3861fff0, "addi r3, r1, -16");
+    Decoder::test_display(0x34640001, "addic. r3, r4, 1");
+    Decoder::test_display(0x7c831e70, "srawi r3, r4, 3");
+    Decoder::test_display(0x7c831e76, "sradi r3, r4, 35");
+    Decoder::test_display(0x7c8307b4, "extsw r3, r4");
+}
+
+#[test]
+fn logical() {
+    Decoder::test_display(0x6083ffff, "ori r3, r4, 65535");
+    Decoder::test_display(0x708300ff, "andi. r3, r4, 255");
+    Decoder::test_display(0x7c832b78, "or r3, r4, r5");
+    Decoder::test_display(0x7c832839, "and. r3, r4, r5");
+}
+
+#[test]
+fn pseudo() {
+    Decoder::test_display(0x60000000, "nop");
+    Decoder::test_display(0x3860002a, "li r3, 42");
+    Decoder::test_display(0x3c601234, "lis r3, 4660");
+    Decoder::test_display(0x7c832378, "mr r3, r4");
+    Decoder::test_display(0x7c8320f8, "not r3, r4");
+    Decoder::test_display(0x7c0802a6, "mflr r0");
+    Decoder::test_display(0x7ca903a6, "mtctr r5");
+    Decoder::test_display(0x7fe00008, "trap");
+}
+
+#[test]
+fn load_store() {
+    Decoder::test_display(0x80610008, "lwz r3, 8(r1)");
+    Decoder::test_display(0x8060fffc, "lwz r3, -4(0)");
+    Decoder::test_display(0x9421ffe0, "stwu r1, -32(r1)");
+    Decoder::test_display(0xe8010010, "ld r0, 16(r1)");
+    Decoder::test_display(0xf821ffd1, "stdu r1, -48(r1)");
+    Decoder::test_display(0x7c64282e, "lwzx r3, r4, r5");
+    Decoder::test_display(0x7c60212d, "stwcx. r3, r0, r4");
+    Decoder::test_display(0xc8230008, "lfd f1, 8(r3)");
+}
+
+#[test]
+fn branch() {
+    Decoder::test_display(0x48000010, "b 0x10");
+    Decoder::test_display(0x4bfffff9, "bl -0x8");
+    Decoder::test_display(0x48000102, "ba 0x100");
+    Decoder::test_display(0x48000103, "bla 0x100");
+    Decoder::test_display(0x41820008, "beq 0x8");
+    Decoder::test_display(0x40860008, "bne cr1, 0x8");
+    Decoder::test_display(0x4200fff8, "bdnz -0x8");
+    Decoder::test_display(0x42400004, "bdz 0x4");
+    Decoder::test_display(0x40010008, "bc 0, gt, 0x8");
+    Decoder::test_display(0x40410009, "bcl 2, gt, 0x8");
+    Decoder::test_display(0x44000002, "sc");
+}
+
+#[test]
+fn branch_register() {
+    Decoder::test_display(0x4e800020, "blr");
+    Decoder::test_display(0x4e800420, "bctr");
+    Decoder::test_display(0x4e800421, "bctrl");
+    Decoder::test_display(0x4d800020, "bltlr");
+    Decoder::test_display(0x4c800420, "bgectr");
+    Decoder::test_error(0x4c000420, ErrorKind::InvalidOperand);
+}
+
+#[test]
+fn compare() {
+    Decoder::test_display(0x7c032000, "cmpw r3, r4");
+    Decoder::test_display(0x7ca32000, "cmpd cr1, r3, r4");
+    Decoder::test_display(0x2803000a, "cmplwi r3, 10");
+    Decoder::test_display(0x2fa3ffff, "cmpdi cr7, r3, -1");
+}
+
+#[test]
+fn condition_register() {
+    Decoder::test_display(0x4cc63182, "crclr 4*cr1+eq");
+    Decoder::test_display(0x4cc63242, "crset 4*cr1+eq");
+    Decoder::test_display(0x4c452b82, "crmove eq, 4*cr1+gt");
+    Decoder::test_display(0x4c055202, "crand lt, 4*cr1+gt, 4*cr2+eq");
+    Decoder::test_display(0x4c800000, "mcrf cr1, cr0");
+    Decoder::test_display(0x7c600026, "mfcr r3");
+    Decoder::test_display(0x7c680120, "mtcrf 128, r3");
+}
+
+#[test]
+fn rotate() {
+    Decoder::test_display(0x5483103a, "rlwinm r3, r4, 2, 0, 29");
+    Decoder::test_display(0x5483463f, "rlwinm. r3, r4, 8, 24, 31");
+    Decoder::test_display(0x5c83283e, "rlwnm r3, r4, r5, 0, 31");
+    Decoder::test_display(0x78830020, "rldicl r3, r4, 0, 32");
+    Decoder::test_display(0x78832ea4, "rldicr r3, r4, 5, 58");
+    Decoder::test_display(0x7883000e, "rldimi r3, r4, 32, 0");
+}
+
+#[test]
+fn system() {
+    Decoder::test_display(0x7c0004ac, "sync");
+    Decoder::test_display(0x7c2004ac, "lwsync");
+    Decoder::test_display(0x4c00012c, "isync");
+    Decoder::test_display(0x7c0006ac, "eieio");
+    Decoder::test_display(0x7c001fec, "dcbz r0, r3");
+    Decoder::test_display(0x7c6c42a6, "mfspr r3, 268");
+    Decoder::test_display(0x7c6043a6, "mtspr 256, r3");
+    Decoder::test_display(0x0c830000, "twi 4, r3, 0");
+}
+
+#[test]
+fn powerpc32() {
+    Decoder::test_error_32(0xe8010010, ErrorKind::InvalidOpcode);
+    Decoder::test_error_32(0x78830020, ErrorKind::InvalidOpcode);
+    Decoder::test_error_32(0x7ca32000, ErrorKind::InvalidOperand);
+    Decoder::test_display_32(0x9421ffe0, "stwu r1, -32(r1)");
+}
+
+/// `fixtures/sum.o` is `fixtures/sum.ll` compiled for ppc64le with llc.
+#[test]
+fn fixture() {
+    assert_eq!(
+        decode_object(include_bytes!("../fixtures/sum.o")),
+        [
+            // sum
+            "cmpwi r4, 1",
+            "blt 0x34",
+            "addi r4, r4, -1",
+            "rldicl r4, r4, 0, 32",
+            "addi r5, r4, 1",
+            "addi r4, r3, -4",
+            "li r3, 0",
+            "mtctr r5",
+            "lwzu r5, 4(r4)",
+            "add r3, r5, r3",
+            "bdnz 0x20",
+            "extsw r3, r3",
+            "blr",
+            "li r3, 0",
+            "extsw r3, r3",
+            "blr",
+            // traceback table
+            "<InvalidOpcode>",
+            "<InvalidOpcode>",
+            "<InvalidOpcode>",
+            "nop",
+            // caller
+            "addis r2, r12, 0",
+            "addi r2, r2, 0",
+            "mflr r0",
+            "std r0, 16(r1)",
+            "stdu r1, -32(r1)",
+            "li r4, 16",
+            "bl 0x68",
+            "rlwinm r3, r3, 3, 0, 28",
+            "extsw r3, r3",
+            "addi r1, r1, 32",
+            "ld r0, 16(r1)",
+            "mtlr r0",
+            "blr",
+            "<InvalidOpcode>",
+            "<InvalidOpcode>",
+            "<InvalidOpcode>",
+        ]
+    );
+}
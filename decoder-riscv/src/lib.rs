@@ -2,16 +2,16 @@
 
 mod tests;
 
-use decoder::{Error, ErrorKind, ToTokens};
+use decoder::{Error, ErrorKind, InstKind, ToTokens, XrefKind};
 use debugvault::Index;
 use once_cell::sync::Lazy;
-use tokenizing::{TokenStream, colors};
+use tokenizing::{Radix, RegisterNaming, TokenStream, colors};
 use config::CONFIG;
 
 macro_rules! operands {
-    [] => {([$crate::Operand::Nothing; 3], 0)};
+    [] => {([$crate::Operand::Nothing; 4], 0)};
     [$($x:expr),+ $(,)?] => {{
-        let mut operands = [$crate::Operand::Nothing; 3];
+        let mut operands = [$crate::Operand::Nothing; 4];
         let mut idx = 0;
         $(
             idx += 1;
@@ -36,13 +36,17 @@ pub enum Register {
     Fs0, Fs1,
     Fa0, Fa1, Fa2, Fa3, Fa4, Fa5, Fa6, Fa7,
     Fs2, Fs3, Fs4, Fs5, Fs6, Fs7, Fs8, Fs9, Fs10, Fs11,
-    Ft8, Ft9, Ft10, Ft11
+    Ft8, Ft9, Ft10, Ft11,
+    V0, V1, V2, V3, V4, V5, V6, V7,
+    V8, V9, V10, V11, V12, V13, V14, V15,
+    V16, V17, V18, V19, V20, V21, V22, V23,
+    V24, V25, V26, V27, V28, V29, V30, V31
 }
 
 impl Register {
     pub fn as_str(&self) -> &'static str {
         #[rustfmt::skip]
-        const REG_LITERALS: [&str; 64] = [
+        const REG_LITERALS: [&str; 96] = [
             "zero", "ra", "sp", "gp", "tp",
             "t0", "t1", "t2",
             "s0", "s1",
@@ -53,11 +57,43 @@ impl Register {
             "fs0", "fs1",
             "fa0", "fa1", "fa2", "fa3", "fa4", "fa5", "fa6", "fa7",
             "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9", "fs10", "fs11",
-            "ft8", "ft9", "ft10", "ft11"
+            "ft8", "ft9", "ft10", "ft11",
+            "v0", "v1", "v2", "v3", "v4", "v5", "v6", "v7",
+            "v8", "v9", "v10", "v11", "v12", "v13", "v14", "v15",
+            "v16", "v17", "v18", "v19", "v20", "v21", "v22", "v23",
+            "v24", "v25", "v26", "v27", "v28", "v29", "v30", "v31"
         ];
 
         REG_LITERALS[*self as usize]
     }
+
+    /// Name of the register, either as it's ABI name or as it's number.
+    pub fn name(&self, naming: RegisterNaming) -> &'static str {
+        #[rustfmt::skip]
+        const REG_NUMBERS: [&str; 64] = [
+            "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7",
+            "x8", "x9", "x10", "x11", "x12", "x13", "x14", "x15",
+            "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23",
+            "x24", "x25", "x26", "x27", "x28", "x29", "x30", "x31",
+            "f0", "f1", "f2", "f3", "f4", "f5", "f6", "f7",
+            "f8", "f9", "f10", "f11", "f12", "f13", "f14", "f15",
+            "f16", "f17", "f18", "f19", "f20", "f21", "f22", "f23",
+            "f24", "f25", "f26", "f27", "f28", "f29", "f30", "f31"
+        ];
+
+        match naming {
+            // vector registers only have a number
+            RegisterNaming::Numeric if (*self as usize) < REG_NUMBERS.len() => {
+                REG_NUMBERS[*self as usize]
+            }
+            _ => self.as_str(),
+        }
+    }
+
+    /// Number of the register within it's register file, e.g. 10 for both `a0` and `fa0`.
+    pub fn number(&self) -> u32 {
+        *self as u32 % 32
+    }
 }
 
 impl Register {
@@ -90,6 +126,16 @@ impl Register {
 
         Ok(unsafe { std::mem::transmute(num as u32 + 40) })
     }
+
+    #[inline]
+    fn get_vec(num: u32) -> Result<Self, ErrorKind> {
+        // if the num isn't between $v0 and $v31
+        if num >= 32 {
+            return Err(ErrorKind::InvalidRegister);
+        }
+
+        Ok(unsafe { std::mem::transmute(num + 64) })
+    }
 }
 
 /// Opcodes for risc-v 32-bit and 64-bit instructions.
@@ -101,6 +147,8 @@ impl Register {
 pub enum Opcode {
     #[default]
     Invalid,
+    /// Encoding that isn't known, shown by its raw bits.
+    INSN,
     // *psuedo instructions*
     LA,
     LLA,
@@ -111,6 +159,7 @@ pub enum Opcode {
     NEG,
     NEGW,
     SEXT_W,
+    ZEXT_W,
     SEQZ,
     SNEZ,
     SLTZ,
@@ -191,6 +240,8 @@ pub enum Opcode {
     OR,
     AND,
     FENCE,
+    FENCE_TSO,
+    PAUSE,
     ECALL,
     EBREAK,
     // *rv64i instructions*
@@ -356,6 +407,116 @@ pub enum Opcode {
     FCVT_LU_Q,
     FCVT_Q_L,
     FCVT_Q_LU,
+    // *rv32/rv64 zba instructions*
+    SH1ADD,
+    SH2ADD,
+    SH3ADD,
+    // *rv64 zba instructions*
+    ADD_UW,
+    SH1ADD_UW,
+    SH2ADD_UW,
+    SH3ADD_UW,
+    SLLI_UW,
+    // *rv32/rv64 zbb instructions*
+    ANDN,
+    ORN,
+    XNOR,
+    CLZ,
+    CTZ,
+    CPOP,
+    MAX,
+    MAXU,
+    MIN,
+    MINU,
+    SEXT_B,
+    SEXT_H,
+    ZEXT_H,
+    ROL,
+    ROR,
+    RORI,
+    ORC_B,
+    REV8,
+    // *rv64 zbb instructions*
+    CLZW,
+    CTZW,
+    CPOPW,
+    ROLW,
+    RORW,
+    RORIW,
+    // *rv32/rv64 zbs instructions*
+    BCLR,
+    BCLRI,
+    BEXT,
+    BEXTI,
+    BINV,
+    BINVI,
+    BSET,
+    BSETI,
+    // *rv32v/rv64v instructions*
+    VSETVLI,
+    VSETIVLI,
+    VSETVL,
+    VLE8_V,
+    VLE16_V,
+    VLE32_V,
+    VLE64_V,
+    VSE8_V,
+    VSE16_V,
+    VSE32_V,
+    VSE64_V,
+    VADD_VV,
+    VADD_VX,
+    VADD_VI,
+    VSUB_VV,
+    VSUB_VX,
+    VRSUB_VX,
+    VRSUB_VI,
+    VMINU_VV,
+    VMINU_VX,
+    VMIN_VV,
+    VMIN_VX,
+    VMAXU_VV,
+    VMAXU_VX,
+    VMAX_VV,
+    VMAX_VX,
+    VAND_VV,
+    VAND_VX,
+    VAND_VI,
+    VOR_VV,
+    VOR_VX,
+    VOR_VI,
+    VXOR_VV,
+    VXOR_VX,
+    VXOR_VI,
+    VMSEQ_VV,
+    VMSEQ_VX,
+    VMSEQ_VI,
+    VMSNE_VV,
+    VMSNE_VX,
+    VMSNE_VI,
+    VSLL_VV,
+    VSLL_VX,
+    VSLL_VI,
+    VSRL_VV,
+    VSRL_VX,
+    VSRL_VI,
+    VSRA_VV,
+    VSRA_VX,
+    VSRA_VI,
+    VMERGE_VVM,
+    VMERGE_VXM,
+    VMERGE_VIM,
+    VMV_V_V,
+    VMV_V_X,
+    VMV_V_I,
+    // *privileged instructions*
+    SRET,
+    MRET,
+    DRET,
+    WFI,
+    SFENCE_VMA,
+    HFENCE_VVMA,
+    HFENCE_GVMA,
     // *rv32c/rv64c instructions*
     C_ADDI4SPN,
     C_FLD,
@@ -404,33 +565,9 @@ pub enum Opcode {
     C_SDSP,
 }
 
-impl Opcode {
-    fn is_relative(&self) -> bool {
-        matches!(
-            self,
-            Self::JAL |
-            Self::JALR |
-            Self::BEQ |
-            Self::BNE |
-            Self::BLT |
-            Self::BGE |
-            Self::BLTU |
-            Self::BGEU |
-            Self::BEQZ |
-            Self::BNEZ |
-            Self::BLEZ |
-            Self::BGEZ |
-            Self::BLTZ |
-            Self::BGTZ |
-            Self::C_JAL |
-            Self::C_BEQZ |
-            Self::C_BNEZ
-        )
-    }
-}
-
-static OPCODE_NAMES: [&str; 284] = [
+static OPCODE_NAMES: [&str; 391] = [
     "invalid",
+    ".insn",
     "la",
     "lla",
     "nop",
@@ -440,6 +577,7 @@ static OPCODE_NAMES: [&str; 284] = [
     "neg",
     "negw",
     "sext.w",
+    "zext.w",
     "seqz",
     "snez",
     "sltz",
@@ -519,6 +657,8 @@ static OPCODE_NAMES: [&str; 284] = [
     "or",
     "and",
     "fence",
+    "fence.tso",
+    "pause",
     "ecall",
     "ebreak",
     "lwu",
@@ -669,6 +809,109 @@ static OPCODE_NAMES: [&str; 284] = [
     "fcvt.lu.q",
     "fcvt.q.l",
     "fcvt.q.lu",
+    "sh1add",
+    "sh2add",
+    "sh3add",
+    "add.uw",
+    "sh1add.uw",
+    "sh2add.uw",
+    "sh3add.uw",
+    "slli.uw",
+    "andn",
+    "orn",
+    "xnor",
+    "clz",
+    "ctz",
+    "cpop",
+    "max",
+    "maxu",
+    "min",
+    "minu",
+    "sext.b",
+    "sext.h",
+    "zext.h",
+    "rol",
+    "ror",
+    "rori",
+    "orc.b",
+    "rev8",
+    "clzw",
+    "ctzw",
+    "cpopw",
+    "rolw",
+    "rorw",
+    "roriw",
+    "bclr",
+    "bclri",
+    "bext",
+    "bexti",
+    "binv",
+    "binvi",
+    "bset",
+    "bseti",
+    "vsetvli",
+    "vsetivli",
+    "vsetvl",
+    "vle8.v",
+    "vle16.v",
+    "vle32.v",
+    "vle64.v",
+    "vse8.v",
+    "vse16.v",
+    "vse32.v",
+    "vse64.v",
+    "vadd.vv",
+    "vadd.vx",
+    "vadd.vi",
+    "vsub.vv",
+    "vsub.vx",
+    "vrsub.vx",
+    "vrsub.vi",
+    "vminu.vv",
+    "vminu.vx",
+    "vmin.vv",
+    "vmin.vx",
+    "vmaxu.vv",
+    "vmaxu.vx",
+    "vmax.vv",
+    "vmax.vx",
+    "vand.vv",
+    "vand.vx",
+    "vand.vi",
+    "vor.vv",
+    "vor.vx",
+    "vor.vi",
+    "vxor.vv",
+    "vxor.vx",
+    "vxor.vi",
+    "vmseq.vv",
+    "vmseq.vx",
+    "vmseq.vi",
+    "vmsne.vv",
+    "vmsne.vx",
+    "vmsne.vi",
+    "vsll.vv",
+    "vsll.vx",
+    "vsll.vi",
+    "vsrl.vv",
+    "vsrl.vx",
+    "vsrl.vi",
+    "vsra.vv",
+    "vsra.vx",
+    "vsra.vi",
+    "vmerge.vvm",
+    "vmerge.vxm",
+    "vmerge.vim",
+    "vmv.v.v",
+    "vmv.v.x",
+    "vmv.v.i",
+    "sret",
+    "mret",
+    "dret",
+    "wfi",
+    "sfence.vma",
+    "hfence.vvma",
+    "hfence.gvma",
     "c.addi4spn",
     "c.fld",
     "c.lw",
@@ -728,12 +971,30 @@ pub enum Operand {
     Nothing,
     Register(Register),
     Immediate(i32),
+    /// Predecessor or successor set of a fence, encoded as `iorw` bits.
+    FenceSet(u8),
+    /// Offset relative to the instruction's address, used by branches and jumps.
+    PCOffset(i32),
+    /// Absolute address, resolved from a [`Operand::PCOffset`].
+    Address(u64),
+    /// Memory reference of the form `offset(base)`.
+    Memory(Register, i32),
+    /// Memory reference of the form `(base)`, used by vector loads and stores.
+    Base(Register),
+    /// Vector type of the form `e32, m2, ta, ma`, encoded as in `vtype`.
+    VType(u16),
+    /// Vector mask register `v0.t`.
+    VMask,
+    /// Length and raw bits of an encoding that isn't known, of the form `4, 0x0000000b`.
+    Encoding(u8, u64),
 }
 
 impl ToTokens for Operand {
     fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
         match self {
-            Self::Register(reg) => stream.push(reg.as_str(), CONFIG.colors.asm.register),
+            Self::Register(reg) => {
+                stream.push(reg.name(stream.config.register_naming), CONFIG.colors.asm.register)
+            }
             Self::Immediate(imm) => {
                 match symbols.get_sym_by_addr(*imm as usize) {
                     Some(symbol) => {
@@ -741,9 +1002,100 @@ impl ToTokens for Operand {
                             stream.push_token(token.clone());
                         }
                     }
-                    None => stream.push_owned(imm.to_string(), CONFIG.colors.asm.immediate),
+                    None => stream.push_immediate(
+                        *imm as i64,
+                        Radix::Decimal,
+                        CONFIG.colors.asm.immediate,
+                    ),
+                }
+            }
+            Self::PCOffset(offset) => {
+                stream.push_immediate(*offset as i64, Radix::Decimal, CONFIG.colors.asm.immediate)
+            }
+            Self::Address(addr) => {
+                match symbols.get_sym_by_addr(*addr as usize) {
+                    Some(symbol) => {
+                        for token in symbol.name() {
+                            stream.push_token(token.clone());
+                        }
+                    }
+                    None => {
+                        stream.push_fmt(format_args!("{addr:#x}"), CONFIG.colors.asm.pointer);
+                        symbols.tokenize_offset(stream, *addr as usize);
+                    }
+                }
+            }
+            Self::FenceSet(set) => {
+                let mut s = String::with_capacity(4);
+                for (bit, chr) in [(0b1000, 'i'), (0b0100, 'o'), (0b0010, 'r'), (0b0001, 'w')] {
+                    if set & bit != 0 {
+                        s.push(chr);
+                    }
+                }
+
+                // an empty set is valid, albeit useless
+                if s.is_empty() {
+                    s.push('0');
+                }
+
+                stream.push_owned(s, CONFIG.colors.asm.annotation);
+            }
+            Self::Memory(base, offset) => {
+                stream.push_immediate(*offset as i64, Radix::Decimal, CONFIG.colors.asm.immediate);
+                stream.push("(", CONFIG.colors.brackets);
+                stream.push(base.name(stream.config.register_naming), CONFIG.colors.asm.register);
+                stream.push(")", CONFIG.colors.brackets);
+            }
+            Self::Base(base) => {
+                stream.push("(", CONFIG.colors.brackets);
+                stream.push(base.name(stream.config.register_naming), CONFIG.colors.asm.register);
+                stream.push(")", CONFIG.colors.brackets);
+            }
+            Self::VType(vtype) => {
+                let sew = match vtype >> 3 & 0b111 {
+                    0b000 => "e8",
+                    0b001 => "e16",
+                    0b010 => "e32",
+                    0b011 => "e64",
+                    _ => "",
+                };
+
+                let lmul = match vtype & 0b111 {
+                    0b000 => "m1",
+                    0b001 => "m2",
+                    0b010 => "m4",
+                    0b011 => "m8",
+                    0b101 => "mf8",
+                    0b110 => "mf4",
+                    0b111 => "mf2",
+                    _ => "",
+                };
+
+                // reserved encodings are shown as the raw immediate
+                if sew.is_empty() || lmul.is_empty() || vtype >> 8 != 0 {
+                    stream.push_fmt(format_args!("{vtype}"), CONFIG.colors.asm.immediate);
+                    return;
+                }
+
+                let ta = if vtype & 0b1000000 != 0 { "ta" } else { "tu" };
+                let ma = if vtype & 0b10000000 != 0 { "ma" } else { "mu" };
+
+                for (idx, field) in [sew, lmul, ta, ma].into_iter().enumerate() {
+                    if idx != 0 {
+                        stream.push_separator(CONFIG.colors.asm.expr);
+                    }
+
+                    stream.push(field, CONFIG.colors.asm.annotation);
                 }
             }
+            Self::VMask => stream.push("v0.t", CONFIG.colors.asm.register),
+            Self::Encoding(len, bits) => {
+                stream.push_fmt(format_args!("{len}"), CONFIG.colors.asm.immediate);
+                stream.push_separator(CONFIG.colors.asm.expr);
+                // two digits per byte, after the `0x`
+                let width = *len as usize * 2 + 2;
+                stream.push_fmt(format_args!("{bits:#0width$x}"), CONFIG.colors.asm.immediate);
+            }
             Self::Nothing => unreachable!("empty operand encountered"),
         }
     }
@@ -752,7 +1104,7 @@ impl ToTokens for Operand {
 #[derive(Debug, Clone)]
 pub struct Instruction {
     opcode: Opcode,
-    operands: [Operand; 3],
+    operands: [Operand; 4],
     operand_count: usize,
     len: usize,
 }
@@ -762,21 +1114,212 @@ impl decoder::Decoded for Instruction {
         self.len
     }
 
-    fn update_rel_addrs(&mut self, addr: usize, _: Option<&Instruction>) {
-        if !self.opcode.is_relative() {
+    fn update_rel_addrs(&mut self, addr: usize, prev_inst: Option<&Instruction>) {
+        for operand in &mut self.operands[..self.operand_count] {
+            if let Operand::PCOffset(offset) = operand {
+                let addr = (addr as u64).wrapping_add_signed(*offset as i64);
+                *operand = Operand::Address(addr);
+            }
+        }
+
+        if let Some(prev_inst) = prev_inst {
+            self.fuse(addr, prev_inst);
+        }
+    }
+
+    fn xref(&self) -> Option<(usize, XrefKind)> {
+        use Opcode::*;
+
+        let addr = self.operands[..self.operand_count].iter().find_map(|operand| match operand {
+            Operand::Address(addr) => Some(*addr as usize),
+            _ => None,
+        })?;
+
+        let kind = match self.opcode {
+            CALL | JAL | C_JAL => XrefKind::Call,
+            TAIL | J | C_J | BEQ | BNE | BLT | BGE | BLTU | BGEU | BEQZ | BNEZ | BLEZ | BGEZ
+            | BLTZ | BGTZ | BGT | BLE | BGTU | BLEU | C_BEQZ | C_BNEZ => XrefKind::Jump,
+            _ => XrefKind::Data,
+        };
+
+        Some((addr, kind))
+    }
+
+    fn is_terminator(&self) -> bool {
+        use Opcode::*;
+
+        match self.opcode {
+            J | C_J | TAIL | JR | C_JR | RET | MRET | SRET | DRET => true,
+            // `jalr` that doesn't link, but isn't a plain `jr` or `ret`
+            JALR => self.operand_count == 2 && self.operands[0] == Operand::Register(Register::Zero),
+            _ => false,
+        }
+    }
+
+    fn kind(&self) -> InstKind {
+        use Opcode::*;
+
+        match self.opcode {
+            CALL | C_JAL | C_JALR => InstKind::Call,
+            JAL => match self.operands[0] {
+                Operand::Register(Register::Zero) => InstKind::Branch,
+                _ => InstKind::Call,
+            },
+            JALR => match self.as_jalr() {
+                Some((Register::Zero, ..)) => InstKind::Branch,
+                _ => InstKind::Call,
+            },
+            J | C_J | TAIL | JR | C_JR => InstKind::Branch,
+            _ if self.is_conditional() => InstKind::Branch,
+            RET | MRET | SRET | DRET => InstKind::Return,
+            ECALL | EBREAK | C_EBREAK => InstKind::Trap,
+            LB | LH | LW | LBU | LHU | LWU | LD | FLW | FLD | FLQ | LR_W | LR_D | C_LW | C_LD
+            | C_FLW | C_FLD | C_LWSP | C_LDSP | C_FLWSP | C_FLDSP | VLE8_V | VLE16_V | VLE32_V
+            | VLE64_V => InstKind::Load,
+            SB | SH | SW | SD | FSW | FSD | FSQ | SC_W | SC_D | C_SW | C_SD | C_FSW | C_FSD
+            | C_SWSP | C_SDSP | C_FSWSP | C_FSDSP | VSE8_V | VSE16_V | VSE32_V | VSE64_V
+            | AMOSWAP_W | AMOADD_W | AMOXOR_W | AMOAND_W | AMOOR_W | AMOMIN_W | AMOMAX_W
+            | AMOMINU_W | AMOMAXU_W | AMOSWAP_D | AMOADD_D | AMOXOR_D | AMOAND_D | AMOOR_D
+            | AMOMIN_D | AMOMAX_D | AMOMINU_D | AMOMAXU_D => InstKind::Store,
+            _ => InstKind::Other,
+        }
+    }
+
+    fn is_conditional(&self) -> bool {
+        use Opcode::*;
+
+        matches!(
+            self.opcode,
+            BEQ | BNE | BLT | BGE | BLTU | BGEU | BEQZ | BNEZ | BLEZ | BGEZ | BLTZ | BGTZ | BGT
+                | BLE | BGTU | BLEU | C_BEQZ | C_BNEZ
+        )
+    }
+}
+
+impl Instruction {
+    /// `.insn` directive holding the bytes of an encoding that isn't known.
+    fn unknown(bytes: &[u8]) -> Self {
+        let mut bits = [0u8; 8];
+        bits[..bytes.len()].copy_from_slice(bytes);
+
+        let (operands, operand_count) =
+            operands![Operand::Encoding(bytes.len() as u8, u64::from_le_bytes(bits))];
+
+        Self {
+            opcode: Opcode::INSN,
+            operands,
+            operand_count,
+            len: bytes.len(),
+        }
+    }
+
+    /// Opcode of the instruction, which is a pseudo-instruction if it's been fused or rewritten.
+    pub fn opcode(&self) -> Opcode {
+        self.opcode
+    }
+
+    /// Operands in the order they're shown.
+    pub fn operands(&self) -> &[Operand] {
+        &self.operands[..self.operand_count]
+    }
+
+    /// Splits a (pseudo) jalr into it's link register, base register and offset.
+    fn as_jalr(&self) -> Option<(Register, Register, i32)> {
+        match (self.opcode, self.operand_count, self.operands[0], self.operands[1]) {
+            (Opcode::JALR, 2, Operand::Register(link), Operand::Memory(base, offset)) => {
+                Some((link, base, offset))
+            }
+            (Opcode::JALR, 1, Operand::Register(base), _) => Some((Register::Ra, base, 0)),
+            (Opcode::JR, 1, Operand::Register(base), _) => Some((Register::Zero, base, 0)),
+            _ => None,
+        }
+    }
+
+    /// Splits a (pseudo) addi into it's destination register, source register and immediate.
+    fn as_addi(&self) -> Option<(Register, Register, i32)> {
+        let operands = (self.operands[0], self.operands[1], self.operands[2]);
+
+        match (self.opcode, self.operand_count, operands) {
+            (
+                Opcode::ADDI | Opcode::ADDIW,
+                3,
+                (Operand::Register(rd), Operand::Register(rs), Operand::Immediate(imm)),
+            ) => Some((rd, rs, imm)),
+            (
+                Opcode::ADDI | Opcode::ADDIW | Opcode::C_ADDI | Opcode::C_ADDIW,
+                2,
+                (Operand::Register(rd), Operand::Immediate(imm), _),
+            ) => Some((rd, rd, imm)),
+            (Opcode::MV, 2, (Operand::Register(rd), Operand::Register(rs), _)) => Some((rd, rs, 0)),
+            _ => None,
+        }
+    }
+
+    /// Fuses an instruction with a previous `auipc` or `lui` that materializes the upper 20
+    /// bits of it's address or constant.
+    fn fuse(&mut self, addr: usize, prev_inst: &Instruction) {
+        let (reg, hi) = match (prev_inst.opcode, prev_inst.operands[0], prev_inst.operands[1]) {
+            (
+                Opcode::AUIPC | Opcode::LUI | Opcode::C_LUI,
+                Operand::Register(reg),
+                Operand::Immediate(hi),
+            ) => (reg, hi.wrapping_shl(12)),
+            _ => return,
+        };
+
+        if prev_inst.opcode != Opcode::AUIPC {
+            // lui rd, hi; addi rd, rd, lo => li rd, imm
+            if let Some((rd, rs, lo)) = self.as_addi() {
+                if rd == reg && rs == reg {
+                    self.opcode = Opcode::LI;
+                    self.operands[1] = Operand::Immediate(hi.wrapping_add(lo));
+                    self.operand_count = 2;
+                }
+            }
+
             return;
         }
 
-        for operand in &mut self.operands[..self.operand_count] {
-            if let Operand::Immediate(imm) = operand {
-                // questionable at best.
-                // ensuring all the i64 conversion are correct is hard
-                *imm = imm.saturating_add_unsigned(addr as u32);
+        // auipc adds the sign extended upper bits to it's own address
+        let pc = addr.wrapping_sub(prev_inst.len) as u64;
+        let target = |lo: i32| pc.wrapping_add_signed(hi as i64 + lo as i64);
+
+        // auipc rd, hi; jalr link, lo(rd) => call/tail target
+        if let Some((link, base, lo)) = self.as_jalr() {
+            if base == reg {
+                self.opcode = match link {
+                    Register::Ra => Opcode::CALL,
+                    Register::Zero => Opcode::TAIL,
+                    _ => return,
+                };
+                self.operands[0] = Operand::Address(target(lo));
+                self.operand_count = 1;
+            }
+
+            return;
+        }
+
+        // auipc rd, hi; addi rd, rd, lo => la rd, target
+        if let Some((rd, rs, lo)) = self.as_addi() {
+            if rd == reg && rs == reg {
+                self.opcode = Opcode::LA;
+                self.operands[1] = Operand::Address(target(lo));
+                self.operand_count = 2;
+            }
+
+            return;
+        }
+
+        // auipc rd, hi; ld rd, lo(rd) => ld rd, target
+        if let Operand::Memory(base, lo) = self.operands[1] {
+            if base == reg {
+                self.operands[1] = Operand::Address(target(lo));
             }
         }
     }
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct Decoder {
     pub is_64: bool,
 }
@@ -785,12 +1328,26 @@ impl decoder::Decodable for Decoder {
     type Instruction = Instruction;
 
     fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
-        decode(reader, self).map_err(|err| Error::new(err, 4))
+        // unknown encodings are kept as `.insn` as wide as the encoding, so decoding can
+        // continue after them
+        reader.mark();
+        match decode(reader, self) {
+            Err(ErrorKind::ExhaustedInput) => {
+                Err(Error::new(ErrorKind::ExhaustedInput, reader.offset()))
+            }
+            Err(..) => Ok(Instruction::unknown(reader.marked())),
+            Ok(inst) => Ok(inst),
+        }
     }
 
     fn max_width(&self) -> usize {
         4
     }
+
+    fn alignment(&self) -> usize {
+        // compressed instructions
+        2
+    }
 }
 
 fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
@@ -828,10 +1385,11 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
                 0b011 if bytes >> 7 & 0b11111 == 2 => decode_addi16sp(bytes),
                 0b011 if bytes >> 7 & 0b11111 != 2 => decode_comp_li(C_LUI, bytes),
                 0b100 => match bytes >> 10 & 0b11 {
-                    0b00 => decode_comp_shift(C_SRLI, bytes),
-                    0b01 => decode_comp_shift(C_SRAI, bytes),
+                    0b00 => decode_comp_shift(C_SRLI, bytes, decoder),
+                    0b01 => decode_comp_shift(C_SRAI, bytes, decoder),
+                    0b10 => decode_comp_andi(bytes),
                     0b11 => match (bytes >> 5 & 0b11, bytes >> 12 & 0b1) {
-                        (0b00, 0b0) if !is_64 => decode_comp_arith(C_SUB, bytes),
+                        (0b00, 0b0) => decode_comp_arith(C_SUB, bytes),
                         (0b01, 0b0) => decode_comp_arith(C_XOR, bytes),
                         (0b10, 0b0) => decode_comp_arith(C_OR, bytes),
                         (0b11, 0b0) => decode_comp_arith(C_AND, bytes),
@@ -847,16 +1405,18 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
                 _ => Err(ErrorKind::InvalidOpcode),
             },
             0b10 => match jump3 {
-                0b000 => decode_comp_shift(C_SLLI, bytes),
+                0b000 => decode_comp_shift(C_SLLI, bytes, decoder),
                 0b001 => decode_comp_ldsp(C_FLDSP, bytes),
                 0b010 => decode_comp_lwsp(C_LWSP, bytes),
                 0b011 if !is_64 => decode_comp_lwsp(C_FLWSP, bytes),
                 0b011 if is_64 => decode_comp_ldsp(C_LDSP, bytes),
-                0b100 => match (bytes >> 12 & 0b1, bytes >> 2 & 0b11111) {
-                    (0b0, 0b0) => decode_comp_jumpr(bytes),
-                    (0b0, _) => decode_comp_mv(bytes),
-                    (0b1, 0b0) => decode_comp_unique(C_EBREAK),
-                    (0b1, _) => decode_comp_add(bytes),
+                0b100 => match (bytes >> 12 & 0b1, bytes >> 7 & 0b11111, bytes >> 2 & 0b11111) {
+                    (0b0, 0b0, 0b0) => Err(ErrorKind::InvalidOpcode),
+                    (0b0, _, 0b0) => decode_comp_jumpr(C_JR, bytes),
+                    (0b0, _, _) => decode_comp_mv(bytes),
+                    (0b1, 0b0, 0b0) => decode_comp_unique(C_EBREAK),
+                    (0b1, _, 0b0) => decode_comp_jumpr(C_JALR, bytes),
+                    (0b1, _, _) => decode_comp_add(bytes),
                     _ => Err(ErrorKind::InvalidOpcode),
                 },
                 0b101 => decode_comp_sdsp(C_FSDSP, bytes),
@@ -871,6 +1431,19 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
         return decoded_inst.map(map_to_psuedo);
     }
 
+    // 48-bit and 64-bit encodings aren't supported, skip over them as a whole
+    let width = match word1[0] & 0b1111111 {
+        0b0011111 | 0b1011111 => 6,
+        0b0111111 => 8,
+        _ => 4,
+    };
+
+    if width > 4 {
+        let mut parcels = [0u8; 6];
+        reader.next_n(&mut parcels[..width - 2]).ok_or(ErrorKind::ExhaustedInput)?;
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
     let mut word2 = [0u8; 2];
     reader.next_n(&mut word2).ok_or(ErrorKind::ExhaustedInput)?;
     let dword = u32::from_le_bytes([word1[0], word1[1], word2[0], word2[1]]);
@@ -879,7 +1452,21 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
     let decoded_inst = match opcode {
         _ if dword == 0b000000000000_00000_000_00000_1110011 => decode_unique(ECALL),
         _ if dword == 0b000000000001_00000_000_00000_1110011 => decode_unique(EBREAK),
-        0b0001111 => decode_unique(FENCE),
+        _ if dword == 0b000100000010_00000_000_00000_1110011 => decode_unique(SRET),
+        _ if dword == 0b001100000010_00000_000_00000_1110011 => decode_unique(MRET),
+        _ if dword == 0b011110110010_00000_000_00000_1110011 => decode_unique(DRET),
+        _ if dword == 0b000100000101_00000_000_00000_1110011 => decode_unique(WFI),
+        0b1110011 if dword >> 7 & 0b111_11111 == 0 => match dword >> 25 {
+            0b0001001 => decode_fence_vma(SFENCE_VMA, dword),
+            0b0010001 => decode_fence_vma(HFENCE_VVMA, dword),
+            0b0110001 => decode_fence_vma(HFENCE_GVMA, dword),
+            _ => Err(ErrorKind::InvalidOpcode),
+        },
+        0b0001111 => match dword >> 12 & 0b111 {
+            0b000 => decode_fence(dword),
+            0b001 => decode_unique(FENCE_I),
+            _ => Err(ErrorKind::InvalidOpcode),
+        },
         0b0110111 => decode_double(LUI, dword),
         0b0010111 => decode_double(AUIPC, dword),
         0b1101111 => decode_jump(dword),
@@ -894,13 +1481,13 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
             _ => Err(ErrorKind::InvalidOpcode),
         },
         0b0000011 => match dword >> 12 & 0b111 {
-            0b000 => decode_immediate(LB, dword),
-            0b001 => decode_immediate(LH, dword),
-            0b010 => decode_immediate(LW, dword),
-            0b011 if is_64 => decode_immediate(LD, dword),
-            0b100 => decode_immediate(LBU, dword),
-            0b101 => decode_immediate(LHU, dword),
-            0b110 if is_64 => decode_immediate(LWU, dword),
+            0b000 => decode_load(LB, dword),
+            0b001 => decode_load(LH, dword),
+            0b010 => decode_load(LW, dword),
+            0b011 if is_64 => decode_load(LD, dword),
+            0b100 => decode_load(LBU, dword),
+            0b101 => decode_load(LHU, dword),
+            0b110 if is_64 => decode_load(LWU, dword),
             _ => Err(ErrorKind::InvalidOpcode),
         },
         0b0100011 => match dword >> 12 & 0b111 {
@@ -917,17 +1504,47 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
             0b100 => decode_immediate(XORI, dword),
             0b110 => decode_immediate(ORI, dword),
             0b111 => decode_immediate(ANDI, dword),
-            0b001 => decode_arith(SLLI, dword, decoder),
-            0b101 if dword >> 26 == 0b0000001 => decode_arith(SRAI, dword, decoder),
-            0b101 if dword >> 26 == 0b0000000 => decode_arith(SRLI, dword, decoder),
+            0b001 => match dword >> 26 {
+                0b000000 => decode_arith(SLLI, dword, decoder),
+                0b010010 => decode_arith(BCLRI, dword, decoder),
+                0b011010 => decode_arith(BINVI, dword, decoder),
+                0b001010 => decode_arith(BSETI, dword, decoder),
+                0b011000 => match dword >> 20 & 0b111111 {
+                    0b000000 => decode_unary(CLZ, dword),
+                    0b000001 => decode_unary(CTZ, dword),
+                    0b000010 => decode_unary(CPOP, dword),
+                    0b000100 => decode_unary(SEXT_B, dword),
+                    0b000101 => decode_unary(SEXT_H, dword),
+                    _ => Err(ErrorKind::InvalidOpcode),
+                },
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            0b101 => match (dword >> 26, dword >> 20 & 0b111111) {
+                (0b000000, _) => decode_arith(SRLI, dword, decoder),
+                (0b010000, _) => decode_arith(SRAI, dword, decoder),
+                (0b010010, _) => decode_arith(BEXTI, dword, decoder),
+                (0b011000, _) => decode_arith(RORI, dword, decoder),
+                (0b001010, 0b000111) => decode_unary(ORC_B, dword),
+                (0b011010, 0b111000) if is_64 => decode_unary(REV8, dword),
+                (0b011010, 0b011000) if !is_64 => decode_unary(REV8, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
             _ => Err(ErrorKind::InvalidOpcode),
         },
         0b0011011 => match dword >> 12 & 0b111 {
             _ if !is_64 => Err(ErrorKind::InvalidOpcode),
             0b000 => decode_immediate(ADDIW, dword),
-            0b001 => decode_arith(SLLIW, dword, decoder),
+            0b001 if dword >> 25 == 0b0000000 => decode_arith(SLLIW, dword, decoder),
+            0b001 if dword >> 26 == 0b000010 => decode_arith(SLLI_UW, dword, decoder),
+            0b001 if dword >> 25 == 0b0110000 => match dword >> 20 & 0b11111 {
+                0b00000 => decode_unary(CLZW, dword),
+                0b00001 => decode_unary(CTZW, dword),
+                0b00010 => decode_unary(CPOPW, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
             0b101 if dword >> 25 == 0b0000000 => decode_arith(SRLIW, dword, decoder),
             0b101 if dword >> 25 == 0b0100000 => decode_arith(SRAIW, dword, decoder),
+            0b101 if dword >> 25 == 0b0110000 => decode_arith(RORIW, dword, decoder),
             _ => Err(ErrorKind::InvalidOpcode),
         },
         0b0110011 => match dword >> 25 {
@@ -944,11 +1561,54 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
             },
             0b0100000 => match dword >> 12 & 0b111 {
                 0b000 => decode_triplet(SUB, dword),
+                0b100 => decode_triplet(XNOR, dword),
                 0b101 => decode_triplet(SRA, dword),
+                0b110 => decode_triplet(ORN, dword),
+                0b111 => decode_triplet(ANDN, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            0b0000100 => match dword >> 12 & 0b111 {
+                0b100 if !is_64 && dword >> 20 & 0b11111 == 0 => decode_unary(ZEXT_H, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            0b0000101 => match dword >> 12 & 0b111 {
+                0b100 => decode_triplet(MIN, dword),
+                0b101 => decode_triplet(MINU, dword),
+                0b110 => decode_triplet(MAX, dword),
+                0b111 => decode_triplet(MAXU, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            0b0010000 => match dword >> 12 & 0b111 {
+                0b010 => decode_triplet(SH1ADD, dword),
+                0b100 => decode_triplet(SH2ADD, dword),
+                0b110 => decode_triplet(SH3ADD, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            0b0010100 => match dword >> 12 & 0b111 {
+                0b001 => decode_triplet(BSET, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            0b0100100 => match dword >> 12 & 0b111 {
+                0b001 => decode_triplet(BCLR, dword),
+                0b101 => decode_triplet(BEXT, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            0b0110000 => match dword >> 12 & 0b111 {
+                0b001 => decode_triplet(ROL, dword),
+                0b101 => decode_triplet(ROR, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            0b0110100 => match dword >> 12 & 0b111 {
+                0b001 => decode_triplet(BINV, dword),
                 _ => Err(ErrorKind::InvalidOpcode),
             },
             _ => Err(ErrorKind::InvalidOpcode),
         },
+        0b1010111 => match dword >> 12 & 0b111 {
+            0b111 => decode_vsetvl(dword),
+            _ => decode_vector_arith(dword),
+        },
+        0b0000111 | 0b0100111 => decode_vector_mem(dword),
         0b0111011 => match dword >> 25 {
             _ if !is_64 => Err(ErrorKind::InvalidOpcode),
             0b0000000 => match dword >> 12 & 0b111 {
@@ -962,6 +1622,22 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
                 0b101 => decode_triplet(SRAW, dword),
                 _ => Err(ErrorKind::InvalidOpcode),
             },
+            0b0000100 => match dword >> 12 & 0b111 {
+                0b000 => decode_triplet(ADD_UW, dword),
+                0b100 if dword >> 20 & 0b11111 == 0 => decode_unary(ZEXT_H, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            0b0010000 => match dword >> 12 & 0b111 {
+                0b010 => decode_triplet(SH1ADD_UW, dword),
+                0b100 => decode_triplet(SH2ADD_UW, dword),
+                0b110 => decode_triplet(SH3ADD_UW, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
+            0b0110000 => match dword >> 12 & 0b111 {
+                0b001 => decode_triplet(ROLW, dword),
+                0b101 => decode_triplet(RORW, dword),
+                _ => Err(ErrorKind::InvalidOpcode),
+            },
             _ => Err(ErrorKind::InvalidOpcode),
         },
         _ => Err(ErrorKind::InvalidOpcode),
@@ -972,7 +1648,7 @@ fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction
 
 impl ToTokens for Instruction {
     fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
-        stream.push(self.opcode.as_str(), CONFIG.colors.asm.opcode);
+        stream.push_mnemonic(self.opcode.as_str(), CONFIG.colors.asm.opcode);
 
         // there are operands
         if self.operand_count > 0 {
@@ -984,7 +1660,7 @@ impl ToTokens for Instruction {
 
                 // separator
                 if idx != self.operand_count - 1 {
-                    stream.push(", ", CONFIG.colors.asm.expr);
+                    stream.push_separator(CONFIG.colors.asm.expr);
                 }
             }
         }
@@ -993,9 +1669,9 @@ impl ToTokens for Instruction {
 
 // NOTE: doing closure assignment in `map_to_psuedo` makes the compiler
 // assign function mappings in the array on each call.
-static MAPPING: Lazy<[fn(&mut Instruction); 284]> = Lazy::new(|| unsafe {
+static MAPPING: Lazy<[fn(&mut Instruction); 391]> = Lazy::new(|| unsafe {
     const DO_NOTHING: fn(&mut Instruction) = |_| {};
-    static mut MAPPING: [fn(&mut Instruction); 284] = [DO_NOTHING; 284];
+    static mut MAPPING: [fn(&mut Instruction); 391] = [DO_NOTHING; 391];
 
     MAPPING[Opcode::C_ADDI as usize] = |inst| {
         if inst.operands[0] == Operand::Register(Register::Zero)
@@ -1171,8 +1847,7 @@ static MAPPING: Lazy<[fn(&mut Instruction); 284]> = Lazy::new(|| unsafe {
     };
 
     MAPPING[Opcode::C_SUB as usize] = |inst| {
-        if inst.operands[1] == Operand::Register(Register::Zero) {
-            inst.opcode = Opcode::NEG;
+        if inst.operands[0] == inst.operands[1] {
             inst.operands.swap(1, 2);
             inst.operand_count = 2;
         }
@@ -1187,8 +1862,7 @@ static MAPPING: Lazy<[fn(&mut Instruction); 284]> = Lazy::new(|| unsafe {
     };
 
     MAPPING[Opcode::C_SUBW as usize] = |inst| {
-        if inst.operands[1] == Operand::Register(Register::Zero) {
-            inst.opcode = Opcode::NEG;
+        if inst.operands[0] == inst.operands[1] {
             inst.operands.swap(1, 2);
             inst.operand_count = 2;
         }
@@ -1351,144 +2025,32 @@ static MAPPING: Lazy<[fn(&mut Instruction); 284]> = Lazy::new(|| unsafe {
         }
     };
 
-    MAPPING[Opcode::C_JALR as usize] = |inst| {
-        if inst.operands[0] == Operand::Register(Register::Zero)
-            && inst.operands[1] == Operand::Register(Register::Ra)
-            && inst.operands[2] == Operand::Immediate(0)
-        {
-            inst.opcode = Opcode::RET;
-            inst.operand_count = 0;
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::Zero)
-            && inst.operands[2] == Operand::Immediate(0)
-        {
-            inst.opcode = Opcode::C_JR;
-            inst.operands.swap(0, 2);
-            inst.operand_count = 1;
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::Ra)
-            && inst.operands[1] == Operand::Register(Register::Ra)
-            && inst.operands[2] == Operand::Immediate(0)
-        {
+    MAPPING[Opcode::C_JR as usize] = |inst| {
+        if inst.operands[0] == Operand::Register(Register::Ra) {
             inst.opcode = Opcode::RET;
             inst.operand_count = 0;
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::Ra)
-            && inst.operands[2] == Operand::Immediate(0)
-        {
-            inst.operands.swap(0, 1);
-            inst.operand_count = 1;
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::Ra)
-            && inst.operands[1] == Operand::Register(Register::Ra)
-        {
-            inst.opcode = Opcode::CALL;
-            inst.operands.swap(0, 1);
-            inst.operand_count = 1;
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::Zero)
-            && inst.operands[1] == Operand::Register(Register::T1)
-        {
-            inst.opcode = Opcode::TAIL;
-            inst.operands.swap(0, 1);
-            inst.operand_count = 1;
         }
     };
 
     MAPPING[Opcode::JALR as usize] = |inst| {
-        if inst.operands[0] == Operand::Register(Register::Zero)
-            && inst.operands[1] == Operand::Register(Register::Ra)
-            && inst.operands[2] == Operand::Immediate(0)
-        {
-            inst.opcode = Opcode::RET;
-            inst.operand_count = 0;
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::Zero)
-            && inst.operands[2] == Operand::Immediate(0)
-        {
-            inst.opcode = Opcode::JR;
-            inst.operands.swap(0, 2);
-            inst.operand_count = 1;
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::Ra)
-            && inst.operands[1] == Operand::Register(Register::Ra)
-            && inst.operands[2] == Operand::Immediate(0)
-        {
-            inst.opcode = Opcode::RET;
-            inst.operand_count = 0;
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::Ra)
-            && inst.operands[2] == Operand::Immediate(0)
-        {
-            inst.operands.swap(0, 1);
-            inst.operand_count = 1;
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::Ra)
-            && inst.operands[1] == Operand::Register(Register::Ra)
-        {
-            inst.opcode = Opcode::CALL;
-            inst.operands.swap(0, 1);
-            inst.operand_count = 1;
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::Zero)
-            && inst.operands[1] == Operand::Register(Register::T1)
+        if let (Operand::Register(rd), Operand::Memory(rs, 0)) = (inst.operands[0], inst.operands[1])
         {
-            inst.opcode = Opcode::TAIL;
-            inst.operands.swap(0, 1);
-            inst.operand_count = 1;
-        }
-    };
-
-    MAPPING[Opcode::AUIPC as usize] = |inst| {
-        if inst.operands[0] == Operand::Register(Register::Ra) {
-            inst.opcode = Opcode::CALL;
-
-            match (inst.operands[0], inst.operands[1]) {
-                (Operand::Register(reg), Operand::Immediate(mut imm)) => {
-                    // offset[31 : 12] + offset[11] where register is bit's [11:6]
-                    imm <<= 1;
-                    imm |= ((reg as u16 & 0b10000) >> 4) as i32;
-                    inst.operands[0] = Operand::Immediate(imm);
-                    inst.operand_count = 1;
-                }
-                _ => unreachable!(),
+            if rd == Register::Zero && rs == Register::Ra {
+                inst.opcode = Opcode::RET;
+                inst.operand_count = 0;
+                return;
             }
 
-            return;
-        }
-
-        if inst.operands[0] == Operand::Register(Register::T1) {
-            inst.opcode = Opcode::TAIL;
+            if rd == Register::Zero {
+                inst.opcode = Opcode::JR;
+                inst.operands[0] = Operand::Register(rs);
+                inst.operand_count = 1;
+                return;
+            }
 
-            match (inst.operands[0], inst.operands[1]) {
-                (Operand::Register(reg), Operand::Immediate(mut imm)) => {
-                    // offset[31 : 12] + offset[11] where register is bit's [11:6]
-                    imm <<= 1;
-                    imm |= ((reg as u16 & 0b10000) >> 4) as i32;
-                    inst.operands[0] = Operand::Immediate(imm);
-                    inst.operand_count = 1;
-                }
-                _ => unreachable!(),
+            if rd == Register::Ra {
+                inst.operands[0] = Operand::Register(rs);
+                inst.operand_count = 1;
             }
         }
     };
@@ -1514,6 +2076,20 @@ static MAPPING: Lazy<[fn(&mut Instruction); 284]> = Lazy::new(|| unsafe {
         }
     };
 
+    MAPPING[Opcode::ADD_UW as usize] = |inst| {
+        if inst.operands[2] == Operand::Register(Register::Zero) {
+            inst.opcode = Opcode::ZEXT_W;
+            inst.operand_count = 2;
+        }
+    };
+
+    MAPPING[Opcode::C_ANDI as usize] = |inst| {
+        if inst.operands[0] == inst.operands[1] {
+            inst.operands.swap(1, 2);
+            inst.operand_count = 2;
+        }
+    };
+
     MAPPING[Opcode::C_SRLI as usize] = |inst| {
         if inst.operands[0] == inst.operands[1] {
             inst.operands.swap(1, 2);
@@ -1583,11 +2159,7 @@ fn decode_comp_branch(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKin
         imm |= (imm | 0b1111111000000000) as i16 as i32;
     }
 
-    let (operands, operand_count) = operands![
-        Operand::Register(rs),
-        Operand::Register(rs),
-        Operand::Immediate(imm)
-    ];
+    let (operands, operand_count) = operands![Operand::Register(rs), Operand::PCOffset(imm)];
 
     Ok(Instruction {
         opcode,
@@ -1597,7 +2169,7 @@ fn decode_comp_branch(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKin
     })
 }
 
-/// Decode's beqz and bnez instructions.
+/// Decode's j and jal instructions.
 fn decode_comp_jump(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
     let mut imm = 0;
 
@@ -1610,14 +2182,14 @@ fn decode_comp_jump(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
     imm |= word >> 7 & 0b000000010000;
     imm |= word >> 2 & 0b000000001110;
 
-    // cast to i64 to prevent rust overflowing literal complaints
+    // cast to i32 to prevent rust overflowing literal complaints
     let mut imm = imm as i32;
 
     if imm & 0b100000000000 != 0 {
         imm |= (imm | 0b1111000000000000) as i16 as i32;
     }
 
-    let (operands, operand_count) = operands![Operand::Immediate(imm)];
+    let (operands, operand_count) = operands![Operand::PCOffset(imm)];
 
     Ok(Instruction {
         opcode,
@@ -1627,18 +2199,14 @@ fn decode_comp_jump(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
     })
 }
 
-/// Decode's j and jal instructions.
-fn decode_comp_jumpr(word: u16) -> Result<Instruction, ErrorKind> {
+/// Decode's jr and jalr instructions.
+fn decode_comp_jumpr(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
     let rs = Register::get((word >> 7 & 0b11111) as u32)?;
 
-    let (operands, operand_count) = operands![
-        Operand::Register(Register::Ra),
-        Operand::Register(rs),
-        Operand::Immediate(0)
-    ];
+    let (operands, operand_count) = operands![Operand::Register(rs)];
 
     Ok(Instruction {
-        opcode: Opcode::C_JALR,
+        opcode,
         operands,
         operand_count,
         len: 2,
@@ -1665,10 +2233,20 @@ fn decode_comp_arith(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind
 }
 
 /// Decode's srli, srai and slli instructions.
-fn decode_comp_shift(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
-    let rd = Register::get_int(word >> 7 & 0b111)?;
+fn decode_comp_shift(opcode: Opcode, word: u16, opts: &Decoder) -> Result<Instruction, ErrorKind> {
+    // slli can target any register, srli and srai only x8-x15
+    let rd = match opcode {
+        Opcode::C_SLLI => Register::get((word >> 7 & 0b11111) as u32)?,
+        _ => Register::get_int(word >> 7 & 0b111)?,
+    };
+
     let shamt = (word >> 7 & 0b100000) | (word >> 2 & 0b11111);
 
+    // shamt[5] is reserved on rv32
+    if !opts.is_64 && shamt & 0b100000 != 0 {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
     let (operands, operand_count) = operands![
         Operand::Register(rd),
         Operand::Register(rd),
@@ -1683,6 +2261,29 @@ fn decode_comp_shift(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind
     })
 }
 
+/// Decode's andi instruction.
+fn decode_comp_andi(word: u16) -> Result<Instruction, ErrorKind> {
+    let rd = Register::get_int(word >> 7 & 0b111)?;
+    let mut imm = (((word >> 7) & 0b100000) | ((word >> 2) & 0b11111)) as i16;
+
+    if imm & 0b100000 != 0 {
+        imm = (imm | 0b11000000) as i8 as i16;
+    }
+
+    let (operands, operand_count) = operands![
+        Operand::Register(rd),
+        Operand::Register(rd),
+        Operand::Immediate(imm as i32),
+    ];
+
+    Ok(Instruction {
+        opcode: Opcode::C_ANDI,
+        operands,
+        operand_count,
+        len: 2,
+    })
+}
+
 /// Decode's addi and addiw instructions.
 fn decode_comp_addi(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
     let rd = Register::get((word >> 7 & 0b11111) as u32)?;
@@ -1742,6 +2343,11 @@ fn decode_addi4spn(word: u16) -> Result<Instruction, ErrorKind> {
     imm |= word >> 2 & 0b0000001000;
     imm |= word >> 4 & 0b0000000100;
 
+    // a zero immediate is reserved, which makes the all zero word illegal
+    if imm == 0 {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
     let (operands, operand_count) =
         operands![Operand::Register(rd), Operand::Immediate(imm as i32)];
 
@@ -1794,11 +2400,15 @@ fn decode_comp_li(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
 
 /// Decode's store word relative to sp instruction for both integers and floats.
 fn decode_comp_swsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
-    let rd = Register::get((word >> 2 & 0b11111) as u32)?;
+    let rd = (word >> 2 & 0b11111) as u32;
+    let rd = match opcode {
+        Opcode::C_FSWSP => Register::get(rd + 32)?,
+        _ => Register::get(rd)?,
+    };
     let imm = (word >> 1 & 0b11000000) | (word >> 7 & 0b111100);
 
     let (operands, operand_count) =
-        operands![Operand::Register(rd), Operand::Immediate(imm as i32)];
+        operands![Operand::Register(rd), Operand::Memory(Register::Sp, imm as i32)];
 
     Ok(Instruction {
         opcode,
@@ -1810,11 +2420,15 @@ fn decode_comp_swsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
 
 /// Decode's store double relative to sp instruction for both integers and floats.
 fn decode_comp_sdsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
-    let rd = Register::get((word >> 2 & 0b11111) as u32)?;
+    let rd = (word >> 2 & 0b11111) as u32;
+    let rd = match opcode {
+        Opcode::C_FSDSP => Register::get(rd + 32)?,
+        _ => Register::get(rd)?,
+    };
     let imm = (word >> 1 & 0b111000000) | (word >> 7 & 0b111000);
 
     let (operands, operand_count) =
-        operands![Operand::Register(rd), Operand::Immediate(imm as i32)];
+        operands![Operand::Register(rd), Operand::Memory(Register::Sp, imm as i32)];
 
     Ok(Instruction {
         opcode,
@@ -1826,27 +2440,47 @@ fn decode_comp_sdsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
 
 /// Decode's load word relative to sp instruction for both integers and floats.
 fn decode_comp_lwsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
-    let rd = Register::get((word >> 7 & 0b11111) as u32)?;
+    let rd = (word >> 7 & 0b11111) as u32;
+    let rd = match opcode {
+        Opcode::C_FLWSP => Register::get(rd + 32)?,
+        _ => Register::get(rd)?,
+    };
+
+    // loading into $zero is reserved
+    if rd == Register::Zero {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
     let imm = (word << 4 & 0b11000000) | (word >> 7 & 0b100000) | (word >> 2 & 0b11100);
 
     let (operands, operand_count) =
-        operands![Operand::Register(rd), Operand::Immediate(imm as i32)];
+        operands![Operand::Register(rd), Operand::Memory(Register::Sp, imm as i32)];
 
     Ok(Instruction {
         opcode,
         operands,
         operand_count,
-        len: 4,
+        len: 2,
     })
 }
 
 /// Decode's load double relative to sp instruction for both integers and floats.
 fn decode_comp_ldsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
-    let rd = Register::get((word >> 7 & 0b11111) as u32)?;
+    let rd = (word >> 7 & 0b11111) as u32;
+    let rd = match opcode {
+        Opcode::C_FLDSP => Register::get(rd + 32)?,
+        _ => Register::get(rd)?,
+    };
+
+    // loading into $zero is reserved
+    if rd == Register::Zero {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
     let imm = (word << 4 & 0b111000000) | (word >> 7 & 0b100000) | (word >> 2 & 0b11000);
 
     let (operands, operand_count) =
-        operands![Operand::Register(rd), Operand::Immediate(imm as i32)];
+        operands![Operand::Register(rd), Operand::Memory(Register::Sp, imm as i32)];
 
     Ok(Instruction {
         opcode,
@@ -1856,17 +2490,14 @@ fn decode_comp_ldsp(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
     })
 }
 
-/// Decode's load word instruction for integers.
+/// Decode's load and store word instructions for integers.
 fn decode_comp_slw(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
-    let rs1 = Register::get_int(word >> 2 & 0b111)?;
-    let rs2 = Register::get_int(word >> 7 & 0b111)?;
+    let rd = Register::get_int(word >> 2 & 0b111)?;
+    let base = Register::get_int(word >> 7 & 0b111)?;
     let imm = (word << 1 & 0b1000000) | (word >> 7 & 0b111000) | (word >> 4 & 0b100);
 
-    let (operands, operand_count) = operands![
-        Operand::Register(rs1),
-        Operand::Register(rs2),
-        Operand::Immediate(imm as i32)
-    ];
+    let (operands, operand_count) =
+        operands![Operand::Register(rd), Operand::Memory(base, imm as i32)];
 
     Ok(Instruction {
         opcode,
@@ -1876,17 +2507,14 @@ fn decode_comp_slw(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
     })
 }
 
-/// Decode's load double instruction for integers.
+/// Decode's load and store double instructions for integers.
 fn decode_comp_sld(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
-    let rs1 = Register::get_int(word >> 2 & 0b111)?;
-    let rs2 = Register::get_int(word >> 7 & 0b111)?;
+    let rd = Register::get_int(word >> 2 & 0b111)?;
+    let base = Register::get_int(word >> 7 & 0b111)?;
     let imm = (word << 1 & 0b11000000) | (word >> 7 & 0b111000);
 
-    let (operands, operand_count) = operands![
-        Operand::Register(rs1),
-        Operand::Register(rs2),
-        Operand::Immediate(imm as i32)
-    ];
+    let (operands, operand_count) =
+        operands![Operand::Register(rd), Operand::Memory(base, imm as i32)];
 
     Ok(Instruction {
         opcode,
@@ -1896,17 +2524,14 @@ fn decode_comp_sld(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
     })
 }
 
-/// Decode's load word instruction for floats.
+/// Decode's load and store word instructions for floats.
 fn decode_comp_fslw(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
-    let rs1 = Register::get_fp(word >> 2 & 0b111)?;
-    let rs2 = Register::get_fp(word >> 7 & 0b111)?;
+    let rd = Register::get_fp(word >> 2 & 0b111)?;
+    let base = Register::get_int(word >> 7 & 0b111)?;
     let imm = (word << 1 & 0b1000000) | (word >> 7 & 0b111000) | (word >> 4 & 0b100);
 
-    let (operands, operand_count) = operands![
-        Operand::Register(rs1),
-        Operand::Register(rs2),
-        Operand::Immediate(imm as i32)
-    ];
+    let (operands, operand_count) =
+        operands![Operand::Register(rd), Operand::Memory(base, imm as i32)];
 
     Ok(Instruction {
         opcode,
@@ -1916,17 +2541,14 @@ fn decode_comp_fslw(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind>
     })
 }
 
-/// Decode's load double instruction for floats.
+/// Decode's load and store double instructions for floats.
 fn decode_comp_fsld(opcode: Opcode, word: u16) -> Result<Instruction, ErrorKind> {
-    let rs1 = Register::get_fp(word >> 2 & 0b111)?;
-    let rs2 = Register::get_fp(word >> 7 & 0b111)?;
+    let rd = Register::get_fp(word >> 2 & 0b111)?;
+    let base = Register::get_int(word >> 7 & 0b111)?;
     let imm = (word << 1 & 0b11000000) | (word >> 7 & 0b111000);
 
-    let (operands, operand_count) = operands![
-        Operand::Register(rs1),
-        Operand::Register(rs2),
-        Operand::Immediate(imm as i32)
-    ];
+    let (operands, operand_count) =
+        operands![Operand::Register(rd), Operand::Memory(base, imm as i32)];
 
     Ok(Instruction {
         opcode,
@@ -1975,6 +2597,57 @@ fn decode_unique(opcode: Opcode) -> Result<Instruction, ErrorKind> {
     })
 }
 
+/// Decode's fence, fence.tso and pause instructions.
+fn decode_fence(dword: u32) -> Result<Instruction, ErrorKind> {
+    let fm = dword >> 28;
+    let pred = (dword >> 24 & 0b1111) as u8;
+    let succ = (dword >> 20 & 0b1111) as u8;
+
+    // fence.tso is a fence rw, rw with the fm field set to 0b1000
+    if fm == 0b1000 && pred == 0b0011 && succ == 0b0011 {
+        return decode_unique(Opcode::FENCE_TSO);
+    }
+
+    // pause is a fence w, 0 with both rd and rs1 set to zero
+    if fm == 0 && pred == 0b0001 && succ == 0 && dword & 0b11111_000_11111_0000000 == 0 {
+        return decode_unique(Opcode::PAUSE);
+    }
+
+    // a full fence iorw, iorw is written without it's operands
+    let (operands, operand_count) = if pred == 0b1111 && succ == 0b1111 {
+        operands![]
+    } else {
+        operands![Operand::FenceSet(pred), Operand::FenceSet(succ)]
+    };
+
+    Ok(Instruction {
+        opcode: Opcode::FENCE,
+        operands,
+        operand_count,
+        len: 4,
+    })
+}
+
+/// Decode's sfence.vma, hfence.vvma and hfence.gvma instructions.
+fn decode_fence_vma(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    let rs1 = Register::get(dword >> 15 & 0b11111)?;
+    let rs2 = Register::get(dword >> 20 & 0b11111)?;
+
+    // trailing $zero operands are implied
+    let (operands, operand_count) = match (rs1, rs2) {
+        (Register::Zero, Register::Zero) => operands![],
+        (_, Register::Zero) => operands![Operand::Register(rs1)],
+        _ => operands![Operand::Register(rs1), Operand::Register(rs2)],
+    };
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+    })
+}
+
 /// Decode's sb, sh, sw and sd store instructions.
 fn decode_store(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
     let mut imm = 0;
@@ -1986,11 +2659,8 @@ fn decode_store(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
     let rs1 = Register::get(dword >> 15 & 0b11111)?;
     let rs2 = Register::get(dword >> 20 & 0b11111)?;
 
-    let (operands, operand_count) = operands![
-        Operand::Register(rs2),
-        Operand::Register(rs1),
-        Operand::Immediate(imm),
-    ];
+    let (operands, operand_count) =
+        operands![Operand::Register(rs2), Operand::Memory(rs1, imm)];
 
     Ok(Instruction {
         opcode,
@@ -2016,7 +2686,7 @@ fn decode_branch(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
     let (operands, operand_count) = operands![
         Operand::Register(rs1),
         Operand::Register(rs2),
-        Operand::Immediate(imm as i32),
+        Operand::PCOffset(imm as i32),
     ];
 
     Ok(Instruction {
@@ -2032,14 +2702,15 @@ fn decode_jump(dword: u32) -> Result<Instruction, ErrorKind> {
     let mut imm = 0;
     let rd = Register::get(dword >> 7 & 0b11111)?;
 
-    // 18 bits (riscv instruction jumps are 16-byte alligned)
+    // imm[20|10:1|11|19:12] where imm[20] is sign extended into the upper bits
+    // and imm[0] is always zero as jumps are 2-byte aligned
     imm |= ((dword & 0b10000000000000000000000000000000) as i32 >> 11) as u32;
     imm |= dword & 0b11111111000000000000;
     imm |= dword >> 9 & 0b00000000100000000000;
     imm |= dword >> 20 & 0b00000000011111111110;
 
     let (operands, operand_count) =
-        operands![Operand::Register(rd), Operand::Immediate(imm as i32)];
+        operands![Operand::Register(rd), Operand::PCOffset(imm as i32)];
 
     Ok(Instruction {
         opcode: Opcode::JAL,
@@ -2049,11 +2720,13 @@ fn decode_jump(dword: u32) -> Result<Instruction, ErrorKind> {
     })
 }
 
-/// Decode's ret instruction.
+/// Decode's jalr instruction.
 fn decode_jumpr(bytes: u32) -> Result<Instruction, ErrorKind> {
     let imm = bytes as i32 >> 20;
     let rd = Register::get(bytes >> 7 & 0b11111)?;
-    let (operands, operand_count) = operands![Operand::Register(rd), Operand::Immediate(imm)];
+    let rs = Register::get(bytes >> 15 & 0b11111)?;
+    let (operands, operand_count) =
+        operands![Operand::Register(rd), Operand::Memory(rs, imm)];
 
     Ok(Instruction {
         opcode: Opcode::JALR,
@@ -2063,6 +2736,23 @@ fn decode_jumpr(bytes: u32) -> Result<Instruction, ErrorKind> {
     })
 }
 
+/// Decode's lb, lh, lw, ld, lbu, lhu and lwu load instructions.
+fn decode_load(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    let rd = Register::get(dword >> 7 & 0b11111)?;
+    let rs = Register::get(dword >> 15 & 0b11111)?;
+    let imm = dword as i32 >> 20;
+
+    let (operands, operand_count) =
+        operands![Operand::Register(rd), Operand::Memory(rs, imm)];
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+    })
+}
+
 /// Decode's instructions that have two registers and an immediate.
 fn decode_immediate(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
     let rd = Register::get(dword >> 7 & 0b11111)?;
@@ -2088,11 +2778,16 @@ fn decode_arith(opcode: Opcode, dword: u32, opts: &Decoder) -> Result<Instructio
     let rd = Register::get(dword >> 7 & 0b11111)?;
     let rs = Register::get(dword >> 15 & 0b11111)?;
 
-    let shamt = if opts.is_64 {
-        dword >> 20 & 0b111111
-    } else {
-        dword >> 20 & 0b11111
-    };
+    let shamt = dword >> 20 & 0b111111;
+    let is_word = matches!(
+        opcode,
+        Opcode::SLLIW | Opcode::SRLIW | Opcode::SRAIW | Opcode::RORIW
+    );
+
+    // shamt[5] is only available to the non-word shifts on rv64
+    if shamt & 0b100000 != 0 && (!opts.is_64 || is_word) {
+        return Err(ErrorKind::InvalidOperand);
+    }
 
     let (operands, operand_count) = operands![
         Operand::Register(rd),
@@ -2108,6 +2803,188 @@ fn decode_arith(opcode: Opcode, dword: u32, opts: &Decoder) -> Result<Instructio
     })
 }
 
+/// Decode's vsetvli, vsetivli and vsetvl instructions.
+fn decode_vsetvl(dword: u32) -> Result<Instruction, ErrorKind> {
+    let rd = Register::get(dword >> 7 & 0b11111)?;
+    let rs1 = dword >> 15 & 0b11111;
+
+    let (opcode, (operands, operand_count)) = match dword >> 30 {
+        0b00 | 0b01 => (
+            Opcode::VSETVLI,
+            operands![
+                Operand::Register(rd),
+                Operand::Register(Register::get(rs1)?),
+                Operand::VType((dword >> 20 & 0b11111111111) as u16)
+            ],
+        ),
+        0b11 => (
+            Opcode::VSETIVLI,
+            operands![
+                Operand::Register(rd),
+                Operand::Immediate(rs1 as i32),
+                Operand::VType((dword >> 20 & 0b1111111111) as u16)
+            ],
+        ),
+        0b10 if dword >> 25 & 0b11111 == 0 => (
+            Opcode::VSETVL,
+            operands![
+                Operand::Register(rd),
+                Operand::Register(Register::get(rs1)?),
+                Operand::Register(Register::get(dword >> 20 & 0b11111)?)
+            ],
+        ),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+    })
+}
+
+/// Decode's unit-stride vector load and store instructions.
+fn decode_vector_mem(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    // only unit-stride accesses without segments are supported (nf, mew, mop and lumop are 0)
+    if dword >> 26 != 0 || dword >> 20 & 0b11111 != 0 {
+        return Err(ErrorKind::InvalidOpcode);
+    }
+
+    let is_store = dword & 0b1111111 == 0b0100111;
+    let opcode = match (dword >> 12 & 0b111, is_store) {
+        (0b000, false) => VLE8_V,
+        (0b101, false) => VLE16_V,
+        (0b110, false) => VLE32_V,
+        (0b111, false) => VLE64_V,
+        (0b000, true) => VSE8_V,
+        (0b101, true) => VSE16_V,
+        (0b110, true) => VSE32_V,
+        (0b111, true) => VSE64_V,
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    let vd = Register::get_vec(dword >> 7 & 0b11111)?;
+    let base = Register::get(dword >> 15 & 0b11111)?;
+
+    let (operands, operand_count) = if dword >> 25 & 0b1 == 0 {
+        operands![Operand::Register(vd), Operand::Base(base), Operand::VMask]
+    } else {
+        operands![Operand::Register(vd), Operand::Base(base)]
+    };
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+    })
+}
+
+/// Decode's integer vector-vector, vector-scalar and vector-immediate instructions.
+fn decode_vector_arith(dword: u32) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let funct3 = dword >> 12 & 0b111;
+    let is_masked = dword >> 25 & 0b1 == 0;
+    let vd = Register::get_vec(dword >> 7 & 0b11111)?;
+    let vs2 = dword >> 20 & 0b11111;
+    let src = dword >> 15 & 0b11111;
+
+    let opcode = match (dword >> 26, funct3) {
+        (0b000000, 0b000) => VADD_VV,
+        (0b000000, 0b100) => VADD_VX,
+        (0b000000, 0b011) => VADD_VI,
+        (0b000010, 0b000) => VSUB_VV,
+        (0b000010, 0b100) => VSUB_VX,
+        (0b000011, 0b100) => VRSUB_VX,
+        (0b000011, 0b011) => VRSUB_VI,
+        (0b000100, 0b000) => VMINU_VV,
+        (0b000100, 0b100) => VMINU_VX,
+        (0b000101, 0b000) => VMIN_VV,
+        (0b000101, 0b100) => VMIN_VX,
+        (0b000110, 0b000) => VMAXU_VV,
+        (0b000110, 0b100) => VMAXU_VX,
+        (0b000111, 0b000) => VMAX_VV,
+        (0b000111, 0b100) => VMAX_VX,
+        (0b001001, 0b000) => VAND_VV,
+        (0b001001, 0b100) => VAND_VX,
+        (0b001001, 0b011) => VAND_VI,
+        (0b001010, 0b000) => VOR_VV,
+        (0b001010, 0b100) => VOR_VX,
+        (0b001010, 0b011) => VOR_VI,
+        (0b001011, 0b000) => VXOR_VV,
+        (0b001011, 0b100) => VXOR_VX,
+        (0b001011, 0b011) => VXOR_VI,
+        (0b010111, 0b000) if is_masked => VMERGE_VVM,
+        (0b010111, 0b100) if is_masked => VMERGE_VXM,
+        (0b010111, 0b011) if is_masked => VMERGE_VIM,
+        (0b010111, 0b000) if vs2 == 0 => VMV_V_V,
+        (0b010111, 0b100) if vs2 == 0 => VMV_V_X,
+        (0b010111, 0b011) if vs2 == 0 => VMV_V_I,
+        (0b011000, 0b000) => VMSEQ_VV,
+        (0b011000, 0b100) => VMSEQ_VX,
+        (0b011000, 0b011) => VMSEQ_VI,
+        (0b011001, 0b000) => VMSNE_VV,
+        (0b011001, 0b100) => VMSNE_VX,
+        (0b011001, 0b011) => VMSNE_VI,
+        (0b100101, 0b000) => VSLL_VV,
+        (0b100101, 0b100) => VSLL_VX,
+        (0b100101, 0b011) => VSLL_VI,
+        (0b101000, 0b000) => VSRL_VV,
+        (0b101000, 0b100) => VSRL_VX,
+        (0b101000, 0b011) => VSRL_VI,
+        (0b101001, 0b000) => VSRA_VV,
+        (0b101001, 0b100) => VSRA_VX,
+        (0b101001, 0b011) => VSRA_VI,
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    let src = match funct3 {
+        0b000 => Operand::Register(Register::get_vec(src)?),
+        0b100 => Operand::Register(Register::get(src)?),
+        // shift amounts are unsigned, all other immediates are sign extended
+        _ if matches!(opcode, VSLL_VI | VSRL_VI | VSRA_VI) => Operand::Immediate(src as i32),
+        _ => Operand::Immediate((src as i32) << 27 >> 27),
+    };
+
+    let vd = Operand::Register(vd);
+    let vs2 = Operand::Register(Register::get_vec(vs2)?);
+
+    let (operands, operand_count) = match opcode {
+        VMV_V_V | VMV_V_X | VMV_V_I => operands![vd, src],
+        VMERGE_VVM | VMERGE_VXM | VMERGE_VIM => {
+            operands![vd, vs2, src, Operand::Register(Register::V0)]
+        }
+        _ if is_masked => operands![vd, vs2, src, Operand::VMask],
+        _ => operands![vd, vs2, src],
+    };
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+    })
+}
+
+/// Decode's instructions that have two registers.
+fn decode_unary(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
+    let rd = Register::get(dword >> 7 & 0b11111)?;
+    let rs = Register::get(dword >> 15 & 0b11111)?;
+
+    let (operands, operand_count) = operands![Operand::Register(rd), Operand::Register(rs)];
+
+    Ok(Instruction {
+        opcode,
+        operands,
+        operand_count,
+        len: 4,
+    })
+}
+
 /// Decode's instructions that have three registers.
 fn decode_triplet(opcode: Opcode, dword: u32) -> Result<Instruction, ErrorKind> {
     let rd = Register::get(dword >> 7 & 0b11111)?;
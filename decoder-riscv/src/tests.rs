@@ -1,10 +1,13 @@
 #![cfg(test)]
 
-use decoder::{Decodable, ToTokens};
+use decoder::{Decodable, Decoded, InstKind, ToTokens};
 use object::{Object, ObjectSection, SectionKind};
 
 macro_rules! decode_instructions {
-    ($code:literal) => {{
+    ($code:literal) => {
+        decode_instructions!($code, "riscv64gc-unknown-none-elf", true)
+    };
+    ($code:literal, $target:literal, $is_64:literal) => {{
         static CRC: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
 
         let code = format!(
@@ -27,7 +30,11 @@ macro_rules! decode_instructions {
 
         out_path.push("..");
         out_path.push("target");
-        out_path.push(format!("test_riscv{}", CRC.checksum($code.as_bytes())));
+        out_path.push(format!(
+            "test_{}{}",
+            $target,
+            CRC.checksum($code.as_bytes())
+        ));
 
         let src_path = out_path.with_extension("rs");
 
@@ -39,7 +46,7 @@ macro_rules! decode_instructions {
 
         let rustc = std::process::Command::new("rustc")
             .arg(format!("-o{}", out_path.display()))
-            .arg("--target=riscv64gc-unknown-none-elf")
+            .arg(concat!("--target=", $target))
             .arg("-Cstrip=symbols")
             .arg(format!("{}", src_path.display()))
             .output()?;
@@ -60,32 +67,339 @@ macro_rules! decode_instructions {
             .find(|t| t.name() == Ok(".text"))
             .expect("failed to find `.text` section");
 
+        // addresses are relative to the start of the section
         let binary = section.uncompressed_data()?;
-        let mut decoded = Vec::new();
-        let mut reader = decoder::Reader::new(&binary[..]);
-        let mut line = tokenizing::TokenStream::new();
-        let decoder = crate::Decoder { is_64: true };
+        let decoder = crate::Decoder { is_64: $is_64 };
         let symbols = debugvault::Index::default();
+        decoder::testing::decode_instructions(decoder, &binary, 0, &symbols)
+    }};
+}
+
+fn test_display(bytes: &[u8], is_64: bool, str: &str) {
+    assert_eq!(decoder::testing::decode(&crate::Decoder { is_64 }, bytes), str);
+}
+
+#[test]
+fn fence() {
+    test_display(&0x0ff0000fu32.to_le_bytes(), true, "fence");
+    test_display(&0x0310000fu32.to_le_bytes(), true, "fence rw, w");
+    test_display(&0x0a50000fu32.to_le_bytes(), true, "fence ir, ow");
+    test_display(&0x8330000fu32.to_le_bytes(), true, "fence.tso");
+    test_display(&0x0100000fu32.to_le_bytes(), true, "pause");
+    test_display(&0x0000100fu32.to_le_bytes(), true, "fence.i");
+}
+
+#[test]
+fn jal() {
+    test_display(&[0xef, 0xf0, 0xdf, 0xff], true, "jal -4");
+    test_display(&[0x6f, 0x00, 0x10, 0x00], true, "j 2048");
+    test_display(&[0xef, 0x00, 0x00, 0x80], true, "jal -1048576");
+    test_display(&[0x6f, 0xf0, 0xff, 0x7f], true, "j 1048574");
+    test_display(&[0x6f, 0x05, 0xe0, 0x7f], true, "jal a0, 2046");
+}
+
+#[test]
+fn jalr() {
+    test_display(&[0x02, 0x85], true, "c.jr a0");
+    test_display(&[0x02, 0x95], true, "c.jalr a0");
+    test_display(&[0x02, 0x90], true, "c.ebreak");
+    test_display(&[0x82, 0x80], true, "ret");
+    test_display(&[0xe7, 0x80, 0x05, 0x00], true, "jalr a1");
+    test_display(&[0x67, 0x80, 0x05, 0x00], true, "jr a1");
+    test_display(&[0x67, 0x05, 0x85, 0x00], true, "jalr a0, 8(a0)");
+}
+
+#[test]
+fn comp_jump() -> Result<(), Box<dyn std::error::Error>> {
+    let decoded = decode_instructions!(
+        "
+        .global _start
+        _start:
+        0:  c.nop
+        1:  c.addi a0, -1
+            c.j 1b
+            c.j 0b
+            c.j 2f
+            c.nop
+        2:  ret
+   "
+    );
+
+    assert_eq!(
+        decoded,
+        [
+            "c.nop",
+            "c.addi a0, -1",
+            "c.j 0x2",
+            "c.j 0x0",
+            "c.j 0xc",
+            "c.nop",
+            "ret"
+        ]
+    );
+
+    let decoded = decode_instructions!(
+        "
+        .global _start
+        _start:
+        1:  c.addi a0, -1
+            c.jal 1b
+            c.jal 1b
+   ",
+        "riscv32imac-unknown-none-elf",
+        false
+    );
+
+    assert_eq!(decoded, ["c.addi a0, -1", "c.jal 0x0", "c.jal 0x0"]);
+
+    Ok(())
+}
+
+#[test]
+fn comp_branch() -> Result<(), Box<dyn std::error::Error>> {
+    let decoded = decode_instructions!(
+        "
+        .global _start
+        _start:
+        1:  c.addi s0, -1
+            c.bnez s0, 1b
+            c.beqz a5, 2f
+            c.nop
+        2:  c.beqz a0, 1b
+            ret
+   "
+    );
+
+    assert_eq!(
+        decoded,
+        [
+            "c.addi s0, -1",
+            "c.bnez s0, 0x0",
+            "c.beqz a5, 0x8",
+            "c.nop",
+            "c.beqz a0, 0x0",
+            "ret"
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn load_store() {
+    test_display(&[0x22, 0xe4], true, "c.sdsp s0, 8(sp)");
+    test_display(&[0x22, 0x64], true, "c.ldsp s0, 8(sp)");
+    test_display(&[0x36, 0xc6], true, "c.swsp a3, 12(sp)");
+    test_display(&[0xb2, 0x46], true, "c.lwsp a3, 12(sp)");
+    test_display(&[0x2a, 0xbc], true, "c.fsdsp fa0, 56(sp)");
+    test_display(&[0x62, 0x25], true, "c.fldsp fa0, 24(sp)");
+    test_display(&[0x2a, 0xe2], false, "c.fswsp fa0, 4(sp)");
+    test_display(&[0x7e, 0x75], false, "c.flwsp fa0, 252(sp)");
+    test_display(&[0x02, 0x40], true, ".insn 2, 0x4002");
+    test_display(&[0x23, 0x2c, 0xc1, 0xfe], true, "sw a2, -8(sp)");
+    test_display(&[0x03, 0xb5, 0x05, 0x01], true, "ld a0, 16(a1)");
+    test_display(&[0x88, 0x69], true, "c.ld a0, 16(a1)");
+    test_display(&[0x88, 0x25], true, "c.fld fa0, 8(a1)");
+    test_display(&[0xc8, 0x41], true, "c.lw a0, 4(a1)");
+}
+
+#[test]
+fn shifts() {
+    test_display(&[0x13, 0x95, 0x05, 0x02], true, "slli a0, a1, 32");
+    test_display(&[0x13, 0xd5, 0x05, 0x42], true, "srai a0, a1, 32");
+    test_display(&[0x13, 0x95, 0x05, 0x02], false, ".insn 4, 0x02059513");
+    test_display(&[0x13, 0xd5, 0x05, 0x42], false, ".insn 4, 0x4205d513");
+    test_display(&[0x7e, 0x06], true, "c.slli a2, 31");
+    test_display(&[0x0d, 0x8d], true, "c.sub a0, a1");
+}
+
+#[test]
+fn riscv32() -> Result<(), Box<dyn std::error::Error>> {
+    let decoded = decode_instructions!(
+        "
+        .global _start
+        _start:
+            c.jal foo
+            sub a0, a0, a1
+            andi a0, a0, -3
+            slli a2, a2, 31
+            srai a3, a3, 4
+            srli a4, a4, 1
+            lw a5, 4(a0)
+            srai s0, s0, 3
+            .option norvc
+            slli a0, a1, 31
+            srai a0, a1, 7
+            srli a0, a1, 1
+            .option rvc
+        foo:
+            ret
+   ",
+        "riscv32imac-unknown-none-elf",
+        false
+    );
+
+    let test = [
+        "c.jal 0x1c",
+        "c.sub a0, a1",
+        "c.andi a0, -3",
+        "c.slli a2, 31",
+        "c.srai a3, 4",
+        "c.srli a4, 1",
+        "c.lw a5, 4(a0)",
+        "c.srai s0, 3",
+        "slli a0, a1, 31",
+        "srai a0, a1, 7",
+        "srli a0, a1, 1",
+        "ret",
+    ];
 
-        loop {
-            match decoder.decode(&mut reader) {
-                Ok(inst) => {
-                    inst.tokenize(&mut line, &symbols);
-                    decoded.push(line.to_string());
-                    line = tokenizing::TokenStream::new();
-                }
-                Err(err) => {
-                    if err.kind == decoder::ErrorKind::ExhaustedInput {
-                        break;
-                    }
-
-                    decoded.push(format!("{err:?}"));
-                }
-            }
+    assert_eq!(decoded.len(), test.len());
+    for (test, decoded) in test.iter().zip(decoded) {
+        if *test != decoded {
+            eprintln!("objdump: '{test}' != our: '{decoded}'");
+            panic!("instructions don't match");
         }
+    }
 
-        decoded
-    }};
+    Ok(())
+}
+
+#[test]
+fn bitmanip() {
+    test_display(&[0x33, 0xa5, 0xc5, 0x20], true, "sh1add a0, a1, a2");
+    test_display(&[0x33, 0xc5, 0xc5, 0x20], true, "sh2add a0, a1, a2");
+    test_display(&[0x33, 0xe5, 0xc5, 0x20], true, "sh3add a0, a1, a2");
+    test_display(&[0x3b, 0x85, 0xc5, 0x08], true, "add.uw a0, a1, a2");
+    test_display(&[0x3b, 0x85, 0x05, 0x08], true, "zext.w a0, a1");
+    test_display(&[0x3b, 0xa5, 0xc5, 0x20], true, "sh1add.uw a0, a1, a2");
+    test_display(&[0x3b, 0xe5, 0xc5, 0x20], true, "sh3add.uw a0, a1, a2");
+    test_display(&[0x1b, 0x95, 0x85, 0x0a], true, "slli.uw a0, a1, 40");
+    test_display(&[0x33, 0xf5, 0xc5, 0x40], true, "andn a0, a1, a2");
+    test_display(&[0x33, 0xe5, 0xc5, 0x40], true, "orn a0, a1, a2");
+    test_display(&[0x33, 0xc5, 0xc5, 0x40], true, "xnor a0, a1, a2");
+    test_display(&[0x13, 0x95, 0x05, 0x60], true, "clz a0, a1");
+    test_display(&[0x13, 0x95, 0x15, 0x60], true, "ctz a0, a1");
+    test_display(&[0x13, 0x95, 0x25, 0x60], true, "cpop a0, a1");
+    test_display(&[0x1b, 0x95, 0x05, 0x60], true, "clzw a0, a1");
+    test_display(&[0x1b, 0x95, 0x15, 0x60], true, "ctzw a0, a1");
+    test_display(&[0x1b, 0x95, 0x25, 0x60], true, "cpopw a0, a1");
+    test_display(&[0x33, 0xe5, 0xc5, 0x0a], true, "max a0, a1, a2");
+    test_display(&[0x33, 0xf5, 0xc5, 0x0a], true, "maxu a0, a1, a2");
+    test_display(&[0x33, 0xc5, 0xc5, 0x0a], true, "min a0, a1, a2");
+    test_display(&[0x33, 0xd5, 0xc5, 0x0a], true, "minu a0, a1, a2");
+    test_display(&[0x13, 0x95, 0x45, 0x60], true, "sext.b a0, a1");
+    test_display(&[0x13, 0x95, 0x55, 0x60], true, "sext.h a0, a1");
+    test_display(&[0x3b, 0xc5, 0x05, 0x08], true, "zext.h a0, a1");
+    test_display(&[0x33, 0x95, 0xc5, 0x60], true, "rol a0, a1, a2");
+    test_display(&[0x33, 0xd5, 0xc5, 0x60], true, "ror a0, a1, a2");
+    test_display(&[0x13, 0xd5, 0x15, 0x62], true, "rori a0, a1, 33");
+    test_display(&[0x3b, 0x95, 0xc5, 0x60], true, "rolw a0, a1, a2");
+    test_display(&[0x3b, 0xd5, 0xc5, 0x60], true, "rorw a0, a1, a2");
+    test_display(&[0x1b, 0xd5, 0x55, 0x60], true, "roriw a0, a1, 5");
+    test_display(&[0x13, 0xd5, 0x85, 0x6b], true, "rev8 a0, a1");
+    test_display(&[0x13, 0xd5, 0x75, 0x28], true, "orc.b a0, a1");
+    test_display(&[0x33, 0x95, 0xc5, 0x48], true, "bclr a0, a1, a2");
+    test_display(&[0x13, 0x95, 0xf5, 0x4b], true, "bclri a0, a1, 63");
+    test_display(&[0x33, 0xd5, 0xc5, 0x48], true, "bext a0, a1, a2");
+    test_display(&[0x13, 0xd5, 0x35, 0x48], true, "bexti a0, a1, 3");
+    test_display(&[0x33, 0x95, 0xc5, 0x68], true, "binv a0, a1, a2");
+    test_display(&[0x13, 0x95, 0x75, 0x68], true, "binvi a0, a1, 7");
+    test_display(&[0x33, 0x95, 0xc5, 0x28], true, "bset a0, a1, a2");
+    test_display(&[0x13, 0x95, 0x85, 0x2a], true, "bseti a0, a1, 40");
+    test_display(&[0x33, 0xc5, 0x05, 0x08], false, "zext.h a0, a1");
+    test_display(&[0x13, 0xd5, 0x85, 0x69], false, "rev8 a0, a1");
+    test_display(&[0x13, 0xd5, 0x15, 0x62], false, ".insn 4, 0x6215d513");
+}
+
+#[test]
+fn unknown() -> Result<(), Box<dyn std::error::Error>> {
+    let decoded = decode_instructions!(
+        "
+        .global _start
+        _start:
+            .2byte 0x0000
+            .4byte 0x0000000b
+            .2byte 0x001f
+            .4byte 0x00000000
+            .2byte 0x003f
+            .2byte 0x0000
+            .4byte 0x00000000
+            li a0, 0
+   "
+    );
+
+    let test = [
+        ".insn 2, 0x0000",
+        ".insn 4, 0x0000000b",
+        ".insn 6, 0x00000000001f",
+        ".insn 8, 0x000000000000003f",
+        "c.li a0, 0",
+    ];
+
+    assert_eq!(decoded.len(), test.len());
+    for (test, decoded) in test.iter().zip(decoded) {
+        if *test != decoded {
+            eprintln!("objdump: '{test}' != our: '{decoded}'");
+            panic!("instructions don't match");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn vector() {
+    test_display(&[0x57, 0xf5, 0x15, 0x0d], true, "vsetvli a0, a1, e32, m2, ta, ma");
+    test_display(&[0x57, 0xf0, 0x75, 0x00], true, "vsetvli zero, a1, e8, mf2, tu, mu");
+    test_display(&[0x57, 0x75, 0x88, 0xc5], true, "vsetivli a0, 16, e64, m1, ta, mu");
+    test_display(&[0x57, 0xf5, 0xc5, 0x80], true, "vsetvl a0, a1, a2");
+    test_display(&[0x07, 0x04, 0x05, 0x02], true, "vle8.v v8, (a0)");
+    test_display(&[0x07, 0x54, 0x05, 0x00], true, "vle16.v v8, (a0), v0.t");
+    test_display(&[0x87, 0x60, 0x01, 0x02], true, "vle32.v v1, (sp)");
+    test_display(&[0x87, 0xff, 0x05, 0x02], true, "vle64.v v31, (a1)");
+    test_display(&[0x27, 0x04, 0x05, 0x02], true, "vse8.v v8, (a0)");
+    test_display(&[0x27, 0x72, 0x06, 0x00], true, "vse64.v v4, (a2), v0.t");
+    test_display(&[0xd7, 0x80, 0x21, 0x02], true, "vadd.vv v1, v2, v3");
+    test_display(&[0xd7, 0x40, 0x25, 0x00], true, "vadd.vx v1, v2, a0, v0.t");
+    test_display(&[0xd7, 0x30, 0x28, 0x02], true, "vadd.vi v1, v2, -16");
+    test_display(&[0xd7, 0x80, 0x21, 0x0a], true, "vsub.vv v1, v2, v3");
+    test_display(&[0xd7, 0xb0, 0x27, 0x0e], true, "vrsub.vi v1, v2, 15");
+    test_display(&[0x57, 0xc2, 0x52, 0x12], true, "vminu.vx v4, v5, t0");
+    test_display(&[0x57, 0x02, 0x53, 0x1e], true, "vmax.vv v4, v5, v6");
+    test_display(&[0x57, 0xb2, 0x50, 0x26], true, "vand.vi v4, v5, 1");
+    test_display(&[0x57, 0x02, 0x53, 0x2a], true, "vor.vv v4, v5, v6");
+    test_display(&[0x57, 0x42, 0x55, 0x2e], true, "vxor.vx v4, v5, a0");
+    test_display(&[0x57, 0xb0, 0x51, 0x62], true, "vmseq.vi v0, v5, 3");
+    test_display(&[0x57, 0x00, 0x53, 0x66], true, "vmsne.vv v0, v5, v6");
+    test_display(&[0x57, 0xb2, 0x5f, 0x96], true, "vsll.vi v4, v5, 31");
+    test_display(&[0x57, 0xc2, 0x55, 0xa2], true, "vsrl.vx v4, v5, a1");
+    test_display(&[0x57, 0x02, 0x53, 0xa6], true, "vsra.vv v4, v5, v6");
+    test_display(&[0x57, 0x02, 0x53, 0x5c], true, "vmerge.vvm v4, v5, v6, v0");
+    test_display(&[0x57, 0xb2, 0x5f, 0x5c], true, "vmerge.vim v4, v5, -1, v0");
+    test_display(&[0x57, 0x02, 0x03, 0x5e], true, "vmv.v.v v4, v6");
+    test_display(&[0x57, 0x42, 0x05, 0x5e], true, "vmv.v.x v4, a0");
+    test_display(&[0x57, 0xb2, 0x03, 0x5e], true, "vmv.v.i v4, 7");
+    test_display(&[0x57, 0x20, 0x00, 0x02], true, ".insn 4, 0x02002057");
+    test_display(&[0xd7, 0x80, 0x21, 0x06], true, ".insn 4, 0x062180d7");
+    test_display(&[0x87, 0x60, 0xb5, 0x0a], true, ".insn 4, 0x0ab56087");
+}
+
+#[test]
+fn privileged() {
+    test_display(&[0x73, 0x00, 0x00, 0x00], true, "ecall");
+    test_display(&[0x73, 0x00, 0x10, 0x00], true, "ebreak");
+    test_display(&[0x73, 0x00, 0x20, 0x10], true, "sret");
+    test_display(&[0x73, 0x00, 0x20, 0x30], true, "mret");
+    test_display(&[0x73, 0x00, 0x20, 0x7b], true, "dret");
+    test_display(&[0x73, 0x00, 0x50, 0x10], true, "wfi");
+    test_display(&[0x73, 0x00, 0x00, 0x12], true, "sfence.vma");
+    test_display(&[0x73, 0x00, 0x05, 0x12], true, "sfence.vma a0");
+    test_display(&[0x73, 0x00, 0xb5, 0x12], true, "sfence.vma a0, a1");
+    test_display(&[0x73, 0x00, 0xb0, 0x12], true, "sfence.vma zero, a1");
+    test_display(&[0x73, 0x00, 0xb5, 0x22], true, "hfence.vvma a0, a1");
+    test_display(&[0x73, 0x00, 0x06, 0x62], true, "hfence.gvma a2");
+    test_display(&[0xf3, 0x00, 0x00, 0x12], true, ".insn 4, 0x120000f3");
 }
 
 #[test]
@@ -105,7 +419,7 @@ fn deref() -> Result<(), Box<dyn std::error::Error>> {
     let test = [
         "lui a0, 4096",
         "c.li a1, 12",
-        "c.sw a1, a0, 0",
+        "c.sw a1, 0(a0)",
         "c.li a0, 0",
         "ret",
     ];
@@ -120,6 +434,52 @@ fn deref() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn pseudo() -> Result<(), Box<dyn std::error::Error>> {
+    let decoded = decode_instructions!(
+        "
+        .global _start
+        _start:
+            call foo
+            tail foo
+            la a0, foo
+            li a2, 0x12345678
+            lui a3, 0x12345
+            addiw a3, a3, -1
+        1:  auipc a4, %pcrel_hi(foo)
+            ld a4, %pcrel_lo(1b)(a4)
+        foo:
+            ret
+   "
+    );
+
+    let test = [
+        "auipc ra, 0",
+        "call 0x2e",
+        "auipc t1, 0",
+        "tail 0x2e",
+        "auipc a0, 0",
+        "la a0, 0x2e",
+        "lui a2, 74565",
+        "li a2, 305419896",
+        "lui a3, 74565",
+        "li a3, 305418239",
+        "auipc a4, 0",
+        "ld a4, 0x2e",
+        "ret",
+    ];
+
+    assert_eq!(decoded.len(), test.len());
+    for (test, decoded) in test.iter().zip(decoded) {
+        if *test != decoded {
+            eprintln!("objdump: '{test}' != our: '{decoded}'");
+            panic!("instructions don't match");
+        }
+    }
+
+    Ok(())
+}
+
 #[test]
 fn sha256() -> Result<(), Box<dyn std::error::Error>> {
     let decoded = decode_instructions!(
@@ -532,50 +892,50 @@ fn sha256() -> Result<(), Box<dyn std::error::Error>> {
 
     let test = [
         "c.li a3, 0",
-        "beq a2, a3, 18",
+        "beq a2, a3, 0x14",
         "add a4, a0, a3",
-        "sb a1, a4, 0",
+        "sb a1, 0(a4)",
         "c.addi a3, 1",
-        "bne a2, a3, -10",
+        "bne a2, a3, 0x6",
         "ret",
         "c.addi16sp -352",
-        "c.sdsp s0, 344",
-        "c.sdsp s1, 336",
-        "c.sdsp s2, 328",
-        "c.sdsp s3, 320",
-        "c.sdsp s4, 312",
-        "c.sdsp s5, 304",
-        "c.sdsp s6, 296",
-        "c.sdsp s7, 288",
-        "c.sdsp s8, 280",
-        "c.sdsp s9, 272",
-        "c.sdsp s10, 264",
-        "c.sdsp s11, 256",
+        "c.sdsp s0, 344(sp)",
+        "c.sdsp s1, 336(sp)",
+        "c.sdsp s2, 328(sp)",
+        "c.sdsp s3, 320(sp)",
+        "c.sdsp s4, 312(sp)",
+        "c.sdsp s5, 304(sp)",
+        "c.sdsp s6, 296(sp)",
+        "c.sdsp s7, 288(sp)",
+        "c.sdsp s8, 280(sp)",
+        "c.sdsp s9, 272(sp)",
+        "c.sdsp s10, 264(sp)",
+        "c.sdsp s11, 256(sp)",
         "c.li a2, 0",
         "li a3, 64",
         "c.mv a6, sp",
-        "beq a2, a3, 48",
+        "beq a2, a3, 0x68",
         "add a5, a1, a2",
-        "lb s1, a5, 0",
-        "lbu s0, a5, 1",
+        "lb s1, 0(a5)",
+        "lbu s0, 1(a5)",
         "c.slli s1, 24",
-        "lbu a4, a5, 2",
+        "lbu a4, 2(a5)",
         "c.slli s0, 16",
-        "lbu a5, a5, 3",
+        "lbu a5, 3(a5)",
         "c.or s1, s0",
         "c.slli a4, 8",
         "c.or a4, s1",
         "c.or a4, a5",
         "add a5, a6, a2",
-        "c.sw a4, a5, 0",
+        "c.sw a4, 0(a5)",
         "c.addi a2, 4",
-        "bne a2, a3, -40",
+        "bne a2, a3, 0x3c",
         "c.li a1, 0",
         "li a7, 192",
         "c.mv a6, sp",
-        "beq a1, a7, 88",
+        "beq a1, a7, 0xc8",
         "add a4, a6, a1",
-        "lwu a5, a4, 56",
+        "lwu a5, 56(a4)",
         "srli s1, a5, 17",
         "slliw s0, a5, 15",
         "c.or s1, s0",
@@ -583,8 +943,8 @@ fn sha256() -> Result<(), Box<dyn std::error::Error>> {
         "slliw a3, a5, 13",
         "c.or a3, s0",
         "c.xor a3, s1",
-        "c.lw s1, a4, 36",
-        "lwu s0, a4, 4",
+        "c.lw s1, 36(a4)",
+        "lwu s0, 4(a4)",
         "c.srli a5, 10",
         "c.xor a3, a5",
         "c.addw a3, s1",
@@ -594,24 +954,24 @@ fn sha256() -> Result<(), Box<dyn std::error::Error>> {
         "srli s1, s0, 18",
         "slli a2, s0, 14",
         "c.or a2, s1",
-        "c.lw s1, a4, 0",
+        "c.lw s1, 0(a4)",
         "c.xor a2, a5",
         "srli a5, s0, 3",
         "c.xor a2, a5",
         "c.addw a3, s1",
         "c.addw a2, a3",
-        "c.sw a2, a4, 64",
+        "c.sw a2, 64(a4)",
         "c.addi a1, 4",
-        "bne a1, a7, -80",
+        "bne a1, a7, 0x74",
         "c.li s9, 0",
-        "lw t5, a0, 80",
-        "lw t4, a0, 84",
-        "lw t3, a0, 88",
-        "lw t2, a0, 92",
-        "lw t1, a0, 96",
-        "lw t0, a0, 100",
-        "lw a7, a0, 104",
-        "lw a6, a0, 108",
+        "lw t5, 80(a0)",
+        "lw t4, 84(a0)",
+        "lw t3, 88(a0)",
+        "lw t2, 92(a0)",
+        "lw t1, 96(a0)",
+        "lw t0, 100(a0)",
+        "lw a7, 104(a0)",
+        "lw a6, 108(a0)",
         "li t6, 256",
         "c.lui a2, 16",
         "addi s3, a2, 344",
@@ -628,7 +988,7 @@ fn sha256() -> Result<(), Box<dyn std::error::Error>> {
         "c.mv s10, a3",
         "c.mv a3, s6",
         "c.mv s1, s7",
-        "beq s9, t6, 146",
+        "beq s9, t6, 0x1a0",
         "srliw a4, a3, 6",
         "slliw a1, a3, 26",
         "c.or a1, a4",
@@ -644,9 +1004,9 @@ fn sha256() -> Result<(), Box<dyn std::error::Error>> {
         "not a4, a3",
         "and a4, s11, a4",
         "add a5, s3, s9",
-        "c.lw a5, a5, 0",
+        "c.lw a5, 0(a5)",
         "add a1, s2, s9",
-        "c.lw a1, a1, 0",
+        "c.lw a1, 0(a1)",
         "addw a2, s6, a2",
         "addw a2, s5",
         "c.addw a2, a4",
@@ -675,60 +1035,60 @@ fn sha256() -> Result<(), Box<dyn std::error::Error>> {
         "c.addi s9, 4",
         "c.mv s4, s1",
         "c.mv s5, s11",
-        "c.j -152",
+        "c.j 0x106",
         "addw a1, s0, t5",
-        "c.sw a1, a0, 80",
+        "c.sw a1, 80(a0)",
         "addw a1, s8, t4",
-        "c.sw a1, a0, 84",
+        "c.sw a1, 84(a0)",
         "addw a1, s1, t3",
-        "c.sw a1, a0, 88",
+        "c.sw a1, 88(a0)",
         "addw a1, s4, t2",
-        "c.sw a1, a0, 92",
+        "c.sw a1, 92(a0)",
         "addw a1, a3, t1",
-        "c.sw a1, a0, 96",
+        "c.sw a1, 96(a0)",
         "addw a1, s10, t0",
-        "c.sw a1, a0, 100",
+        "c.sw a1, 100(a0)",
         "addw a1, s11, a7",
-        "c.sw a1, a0, 104",
+        "c.sw a1, 104(a0)",
         "addw a1, s5, a6",
-        "c.sw a1, a0, 108",
-        "c.ldsp s0, 344",
-        "c.ldsp s1, 336",
-        "c.ldsp s2, 328",
-        "c.ldsp s3, 320",
-        "c.ldsp s4, 312",
-        "c.ldsp s5, 304",
-        "c.ldsp s6, 296",
-        "c.ldsp s7, 288",
-        "c.ldsp s8, 280",
-        "c.ldsp s9, 272",
-        "c.ldsp s10, 264",
-        "c.ldsp s11, 256",
+        "c.sw a1, 108(a0)",
+        "c.ldsp s0, 344(sp)",
+        "c.ldsp s1, 336(sp)",
+        "c.ldsp s2, 328(sp)",
+        "c.ldsp s3, 320(sp)",
+        "c.ldsp s4, 312(sp)",
+        "c.ldsp s5, 304(sp)",
+        "c.ldsp s6, 296(sp)",
+        "c.ldsp s7, 288(sp)",
+        "c.ldsp s8, 280(sp)",
+        "c.ldsp s9, 272(sp)",
+        "c.ldsp s10, 264(sp)",
+        "c.ldsp s11, 256(sp)",
         "c.addi16sp 352",
         "ret",
         "c.lui a1, 18",
-        "ld a1, a1, 1632",
+        "ld a1, 1632(a1)",
         "c.lui a2, 18",
-        "ld a2, a2, 1640",
-        "c.sd a1, a0, 80",
+        "ld a2, 1640(a2)",
+        "c.sd a1, 80(a0)",
         "c.lui a1, 18",
-        "ld a1, a1, 1648",
-        "c.sd a2, a0, 88",
+        "ld a1, 1648(a1)",
+        "c.sd a2, 88(a0)",
         "c.lui a2, 18",
-        "ld a2, a2, 1656",
-        "c.sd a1, a0, 96",
+        "ld a2, 1656(a2)",
+        "c.sd a1, 96(a0)",
         "c.li a1, 0",
-        "c.sw a1, a0, 64",
-        "c.sd a1, a0, 72",
-        "c.sd a2, a0, 104",
+        "c.sw a1, 64(a0)",
+        "c.sd a1, 72(a0)",
+        "c.sd a2, 104(a0)",
         "ret",
         "c.addi16sp -48",
-        "c.sdsp ra, 40",
-        "c.sdsp s0, 32",
-        "c.sdsp s1, 24",
-        "c.sdsp s2, 16",
-        "c.sdsp s3, 8",
-        "c.sdsp s4, 0",
+        "c.sdsp ra, 40(sp)",
+        "c.sdsp s0, 32(sp)",
+        "c.sdsp s1, 24(sp)",
+        "c.sdsp s2, 16(sp)",
+        "c.sdsp s3, 8(sp)",
+        "c.sdsp s4, 0(sp)",
         "c.mv s3, a2",
         "c.mv s2, a1",
         "c.mv s1, a0",
@@ -736,154 +1096,154 @@ fn sha256() -> Result<(), Box<dyn std::error::Error>> {
         "li s4, 64",
         "slli a0, s0, 32",
         "c.srli a0, 32",
-        "bgeu a0, s3, 54",
+        "bgeu a0, s3, 0x26a",
         "c.add a0, s2",
-        "lwu a1, s1, 64",
-        "lb a0, a0, 0",
+        "lwu a1, 64(s1)",
+        "lb a0, 0(a0)",
         "c.add a1, s1",
-        "sb a0, a1, 0",
-        "c.lw a0, s1, 64",
+        "sb a0, 0(a1)",
+        "c.lw a0, 64(s1)",
         "c.addiw a0, 1",
-        "c.sw a0, s1, 64",
-        "bne a0, s4, 24",
+        "c.sw a0, 64(s1)",
+        "bne a0, s4, 0x266",
         "c.mv a0, s1",
         "c.mv a1, s1",
-        "jal -576",
-        "c.ld a0, s1, 72",
+        "jal 0x16",
+        "c.ld a0, 72(s1)",
         "addi a0, 512",
-        "c.sd a0, s1, 72",
-        "sw zero, s1, 64",
+        "c.sd a0, 72(s1)",
+        "sw zero, 64(s1)",
         "c.addiw s0, 1",
-        "c.j -58",
-        "c.ldsp ra, 40",
-        "c.ldsp s0, 32",
-        "c.ldsp s1, 24",
-        "c.ldsp s2, 16",
-        "c.ldsp s3, 8",
-        "c.ldsp s4, 0",
+        "c.j 0x22e",
+        "c.ldsp ra, 40(sp)",
+        "c.ldsp s0, 32(sp)",
+        "c.ldsp s1, 24(sp)",
+        "c.ldsp s2, 16(sp)",
+        "c.ldsp s3, 8(sp)",
+        "c.ldsp s4, 0(sp)",
         "c.addi16sp 48",
         "ret",
         "c.addi sp, -32",
-        "c.sdsp ra, 24",
-        "c.sdsp s0, 16",
-        "c.sdsp s1, 8",
+        "c.sdsp ra, 24(sp)",
+        "c.sdsp s0, 16(sp)",
+        "c.sdsp s1, 8(sp)",
         "c.mv s0, a0",
-        "lwu a0, a0, 64",
+        "lwu a0, 64(a0)",
         "c.mv s1, a1",
         "sext.w a1, a0",
         "add a2, s0, a0",
         "li a3, 128",
         "li a4, 56",
-        "sb a3, a2, 0",
-        "bgeu a1, a4, 32",
+        "sb a3, 0(a2)",
+        "bgeu a1, a4, 0x2be",
         "addi a1, s0, 1",
         "li a2, 55",
-        "beq a0, a2, 60",
+        "beq a0, a2, 0x2e6",
         "add a3, a1, a0",
         "c.addi a0, 1",
-        "sb zero, a3, 0",
-        "bne a0, a2, -10",
-        "c.j 42",
+        "sb zero, 0(a3)",
+        "bne a0, a2, 0x2ae",
+        "c.j 0x2e6",
         "li a1, 63",
         "c.addiw a0, 1",
-        "bltu a1, a0, 14",
+        "bltu a1, a0, 0x2d2",
         "add a2, s0, a0",
-        "sb zero, a2, 0",
-        "c.j -14",
+        "sb zero, 0(a2)",
+        "c.j 0x2c2",
         "c.mv a0, s0",
         "c.mv a1, s0",
-        "jal -704",
+        "jal 0x16",
         "li a2, 56",
         "c.mv a0, s0",
         "c.li a1, 0",
-        "jal -738",
-        "c.lw a0, s0, 64",
-        "c.ld a1, s0, 72",
+        "jal 0x0",
+        "c.lw a0, 64(s0)",
+        "c.ld a1, 72(s0)",
         "c.slli a0, 35",
         "c.srli a0, 32",
         "c.add a0, a1",
-        "c.sd a0, s0, 72",
-        "sb a0, s0, 63",
+        "c.sd a0, 72(s0)",
+        "sb a0, 63(s0)",
         "srli a1, a0, 8",
-        "sb a1, s0, 62",
+        "sb a1, 62(s0)",
         "srli a1, a0, 16",
-        "sb a1, s0, 61",
+        "sb a1, 61(s0)",
         "srli a1, a0, 24",
-        "sb a1, s0, 60",
+        "sb a1, 60(s0)",
         "srli a1, a0, 32",
-        "sb a1, s0, 59",
+        "sb a1, 59(s0)",
         "srli a1, a0, 40",
-        "sb a1, s0, 58",
+        "sb a1, 58(s0)",
         "srli a1, a0, 48",
-        "sb a1, s0, 57",
+        "sb a1, 57(s0)",
         "c.srli a0, 56",
-        "sb a0, s0, 56",
+        "sb a0, 56(s0)",
         "c.mv a0, s0",
         "c.mv a1, s0",
-        "jal -794",
+        "jal 0x16",
         "c.li a0, 0",
         "addi a1, s1, 16",
         "c.li a2, 4",
         "c.li a3, 24",
-        "beq a0, a2, 102",
-        "c.lw a4, s0, 80",
+        "beq a0, a2, 0x3a4",
+        "c.lw a4, 80(s0)",
         "slliw a5, a0, 3",
         "subw a5, a3, a5",
         "srlw a4, a4, a5",
         "add s1, a1, a0",
-        "sb a4, s1, -16",
-        "c.lw a4, s0, 84",
+        "sb a4, -16(s1)",
+        "c.lw a4, 84(s0)",
         "srlw a4, a4, a5",
-        "sb a4, s1, -12",
-        "c.lw a4, s0, 88",
+        "sb a4, -12(s1)",
+        "c.lw a4, 88(s0)",
         "srlw a4, a4, a5",
-        "sb a4, s1, -8",
-        "c.lw a4, s0, 92",
+        "sb a4, -8(s1)",
+        "c.lw a4, 92(s0)",
         "srlw a4, a4, a5",
-        "sb a4, s1, -4",
-        "c.lw a4, s0, 96",
+        "sb a4, -4(s1)",
+        "c.lw a4, 96(s0)",
         "srlw a4, a4, a5",
-        "sb a4, s1, 0",
-        "c.lw a4, s0, 100",
+        "sb a4, 0(s1)",
+        "c.lw a4, 100(s0)",
         "srlw a4, a4, a5",
-        "sb a4, s1, 4",
-        "c.lw a4, s0, 104",
+        "sb a4, 4(s1)",
+        "c.lw a4, 104(s0)",
         "srlw a4, a4, a5",
-        "sb a4, s1, 8",
-        "c.lw a4, s0, 108",
+        "sb a4, 8(s1)",
+        "c.lw a4, 108(s0)",
         "srlw a4, a4, a5",
-        "sb a4, s1, 12",
+        "sb a4, 12(s1)",
         "c.addi a0, 1",
-        "bne a0, a2, -94",
-        "c.ldsp ra, 24",
-        "c.ldsp s0, 16",
-        "c.ldsp s1, 8",
+        "bne a0, a2, 0x342",
+        "c.ldsp ra, 24(sp)",
+        "c.ldsp s0, 16(sp)",
+        "c.ldsp s1, 8(sp)",
         "c.addi16sp 32",
         "ret",
         "c.addi16sp -128",
-        "c.sdsp ra, 120",
-        "c.swsp zero, 72",
-        "c.sdsp zero, 80",
+        "c.sdsp ra, 120(sp)",
+        "c.swsp zero, 72(sp)",
+        "c.sdsp zero, 80(sp)",
         "c.lui a0, 18",
-        "ld a0, a0, 1664",
+        "ld a0, 1664(a0)",
         "c.lui a1, 18",
-        "ld a1, a1, 1672",
+        "ld a1, 1672(a1)",
         "c.lui a2, 18",
-        "ld a2, a2, 1680",
+        "ld a2, 1680(a2)",
         "c.lui a3, 18",
-        "ld a3, a3, 1688",
-        "c.sdsp a0, 88",
-        "c.sdsp a1, 96",
-        "c.sdsp a2, 104",
-        "c.sdsp a3, 112",
+        "ld a3, 1688(a3)",
+        "c.sdsp a0, 88(sp)",
+        "c.sdsp a1, 96(sp)",
+        "c.sdsp a2, 104(sp)",
+        "c.sdsp a3, 112(sp)",
         "c.addi4spn a0, 8",
         "c.lui a1, 1",
         "li a2, 1024",
-        "jal -458",
+        "jal 0x214",
         "c.addi4spn a0, 8",
         "c.lui a1, 2",
-        "jal -364",
-        "c.ldsp ra, 120",
+        "jal 0x27a",
+        "c.ldsp ra, 120(sp)",
         "c.addi16sp 128",
         "ret",
     ];
@@ -897,3 +1257,135 @@ fn sha256() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn stream_seek() {
+    let bytes = [0x0d, 0x8d, 0x23, 0x2c, 0xc1, 0xfe, 0x88, 0x69];
+    let decoder = crate::Decoder { is_64: true };
+    let mut stream = decoder::InstructionStream::new(&bytes, 0x1000, decoder);
+
+    let addrs: Vec<usize> = stream.by_ref().map(|(addr, _)| addr).collect();
+    assert_eq!(addrs, [0x1000, 0x1002, 0x1006]);
+    assert_eq!(stream.ip(), stream.end());
+
+    // rounded down to the start of the 2-byte aligned instruction
+    stream.seek(0x1003).unwrap();
+    let (addr, inst) = stream.next().unwrap();
+    let mut line = tokenizing::TokenStream::new();
+    inst.unwrap().tokenize(&mut line, &debugvault::Index::default());
+    assert_eq!(addr, 0x1002);
+    assert_eq!(line.to_string(), "sw a2, -8(sp)");
+
+    let out_of_bounds = decoder::Error::new(decoder::ErrorKind::OutOfBounds, 0);
+    assert_eq!(stream.seek(0xfff), Err(out_of_bounds));
+    assert_eq!(stream.seek(0x100a), Err(out_of_bounds));
+    assert_eq!(stream.seek(0x1008), Ok(()));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn stream_skips_unknown() {
+    // unknown compressed instruction, unknown 32-bit instruction on rv32, then `c.sub a0, a1`
+    let bytes = [0x02, 0x40, 0x13, 0x95, 0x05, 0x02, 0x0d, 0x8d];
+    let decoder = crate::Decoder { is_64: false };
+    let stream = decoder::InstructionStream::new(&bytes, 0x1000, decoder);
+
+    let decoded: Vec<_> = stream
+        .map(|(addr, inst)| (addr, inst.map(|inst| inst.width()).map_err(|err| err.size())))
+        .collect();
+    assert_eq!(decoded, [(0x1000, Ok(2)), (0x1002, Ok(4)), (0x1006, Ok(2))]);
+}
+
+#[test]
+fn kinds() {
+    use InstKind::*;
+
+    // jal ra, 0x10; c.jalr a5; c.jr a5; c.jr ra; c.bnez a0, 0x12; c.beqz a0, 0x10; c.j 0xe;
+    // ecall; c.ebreak; c.ldsp a0, 8(sp); c.lw a1, 0(a0); c.sdsp a0, 8(sp);
+    // sw a0, 12(a1); c.add a0, a1; mret
+    let bytes = [
+        0xef, 0x00, 0x00, 0x01, 0x82, 0x97, 0x82, 0x87, 0x82, 0x80, 0x01, 0xe5, 0x11, 0xc1,
+        0x01, 0xa0, 0x73, 0x00, 0x00, 0x00, 0x02, 0x90, 0x22, 0x65, 0x0c, 0x41, 0x2a, 0xe4,
+        0x23, 0xa6, 0xa5, 0x00, 0x2e, 0x95, 0x73, 0x00, 0x20, 0x30,
+    ];
+    let decoder = crate::Decoder { is_64: true };
+    let stream = decoder::InstructionStream::new(&bytes, 0x1000, decoder);
+
+    let kinds: Vec<_> = stream
+        .map(|(addr, inst)| {
+            let mut inst = inst.unwrap();
+            inst.update_rel_addrs(addr, None);
+            (inst.kind(), inst.is_conditional(), inst.xref().map(|(addr, _)| addr))
+        })
+        .collect();
+
+    assert_eq!(
+        kinds,
+        [
+            (Call, false, Some(0x1010)),
+            (Call, false, None),
+            (Branch, false, None),
+            (Return, false, None),
+            (Branch, true, Some(0x1012)),
+            (Branch, true, Some(0x1010)),
+            (Branch, false, Some(0x100e)),
+            (Trap, false, None),
+            (Trap, false, None),
+            (Load, false, None),
+            (Load, false, None),
+            (Store, false, None),
+            (Store, false, None),
+            (Other, false, None),
+            (Return, false, None),
+        ]
+    );
+}
+
+#[test]
+fn styles() {
+    use tokenizing::{FormatterConfig, Radix, RegisterNaming};
+
+    let decoder = crate::Decoder { is_64: true };
+    let symbols = debugvault::Index::default();
+    let inst = decoder.decode(&mut decoder::Reader::new(&[0x23, 0x2c, 0xc1, 0xfe])).unwrap();
+
+    let format = |config: FormatterConfig| {
+        let mut line = tokenizing::TokenStream::with_config(config);
+        inst.tokenize(&mut line, &symbols);
+        line.to_string()
+    };
+
+    assert_eq!(format(FormatterConfig::default()), "sw a2, -8(sp)");
+    assert_eq!(
+        format(FormatterConfig {
+            immediate_radix: Some(Radix::Hex),
+            register_naming: RegisterNaming::Numeric,
+            uppercase_mnemonics: true,
+            operand_separator: ",",
+        }),
+        "SW x12,-0x8(x2)"
+    );
+}
+
+#[test]
+fn operands() {
+    use crate::{Opcode, Operand, Register};
+
+    let decoder = crate::Decoder { is_64: true };
+    let decode = |bytes: &[u8]| decoder.decode(&mut decoder::Reader::new(bytes)).unwrap();
+
+    let inst = decode(&[0x23, 0x2c, 0xc1, 0xfe]);
+    assert_eq!(inst.opcode(), Opcode::SW);
+    assert_eq!(
+        inst.operands(),
+        [Operand::Register(Register::A2), Operand::Memory(Register::Sp, -8)]
+    );
+
+    let inst = decode(&[0x82, 0x80]);
+    assert_eq!(inst.opcode(), Opcode::RET);
+    assert_eq!(inst.operands(), []);
+
+    assert_eq!(Register::A0.number(), 10);
+    assert_eq!(Register::Fa0.number(), 10);
+    assert_eq!(Register::V31.number(), 31);
+}
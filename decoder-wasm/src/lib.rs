@@ -0,0 +1,1076 @@
+//! WebAssembly disassembler.
+
+mod tests;
+
+use decoder::{Error, ErrorKind, InstKind, ToTokens, XrefKind};
+use debugvault::Index;
+use tokenizing::{colors, Radix, TokenStream};
+use config::CONFIG;
+
+macro_rules! operands {
+    [] => {(Default::default(), 0)};
+    [$($x:expr),+ $(,)?] => {{
+        let mut operands: [$crate::Operand; 2] = Default::default();
+        let mut idx = 0;
+        $(
+            idx += 1;
+            operands[idx - 1] = $x;
+        )*
+
+        (operands, idx)
+    }};
+}
+
+/// Types of values on the stack, in locals and in globals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValType {
+    I32,
+    I64,
+    F32,
+    F64,
+    V128,
+    FuncRef,
+    ExternRef,
+}
+
+impl ValType {
+    fn get(byte: u8) -> Option<Self> {
+        Some(match byte {
+            0x7f => Self::I32,
+            0x7e => Self::I64,
+            0x7d => Self::F32,
+            0x7c => Self::F64,
+            0x7b => Self::V128,
+            0x70 => Self::FuncRef,
+            0x6f => Self::ExternRef,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+            Self::V128 => "v128",
+            Self::FuncRef => "funcref",
+            Self::ExternRef => "externref",
+        }
+    }
+}
+
+/// Opcodes of the WebAssembly 2.0 instruction set, named as in the text format.
+///
+/// *Vector instructions aren't supported.*
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u16)]
+pub enum Opcode {
+    #[default]
+    Invalid,
+    // *pseudo instructions*
+    LOCALS,
+    // *control*
+    UNREACHABLE,
+    NOP,
+    BLOCK,
+    LOOP,
+    IF,
+    ELSE,
+    END,
+    BR,
+    BR_IF,
+    BR_TABLE,
+    RETURN,
+    CALL,
+    CALL_INDIRECT,
+    RETURN_CALL,
+    RETURN_CALL_INDIRECT,
+    // *parametric*
+    DROP,
+    SELECT,
+    // *variables*
+    LOCAL_GET,
+    LOCAL_SET,
+    LOCAL_TEE,
+    GLOBAL_GET,
+    GLOBAL_SET,
+    TABLE_GET,
+    TABLE_SET,
+    // *memory*
+    I32_LOAD,
+    I64_LOAD,
+    F32_LOAD,
+    F64_LOAD,
+    I32_LOAD8_S,
+    I32_LOAD8_U,
+    I32_LOAD16_S,
+    I32_LOAD16_U,
+    I64_LOAD8_S,
+    I64_LOAD8_U,
+    I64_LOAD16_S,
+    I64_LOAD16_U,
+    I64_LOAD32_S,
+    I64_LOAD32_U,
+    I32_STORE,
+    I64_STORE,
+    F32_STORE,
+    F64_STORE,
+    I32_STORE8,
+    I32_STORE16,
+    I64_STORE8,
+    I64_STORE16,
+    I64_STORE32,
+    MEMORY_SIZE,
+    MEMORY_GROW,
+    // *constants*
+    I32_CONST,
+    I64_CONST,
+    F32_CONST,
+    F64_CONST,
+    // *numeric*
+    I32_EQZ,
+    I32_EQ,
+    I32_NE,
+    I32_LT_S,
+    I32_LT_U,
+    I32_GT_S,
+    I32_GT_U,
+    I32_LE_S,
+    I32_LE_U,
+    I32_GE_S,
+    I32_GE_U,
+    I64_EQZ,
+    I64_EQ,
+    I64_NE,
+    I64_LT_S,
+    I64_LT_U,
+    I64_GT_S,
+    I64_GT_U,
+    I64_LE_S,
+    I64_LE_U,
+    I64_GE_S,
+    I64_GE_U,
+    F32_EQ,
+    F32_NE,
+    F32_LT,
+    F32_GT,
+    F32_LE,
+    F32_GE,
+    F64_EQ,
+    F64_NE,
+    F64_LT,
+    F64_GT,
+    F64_LE,
+    F64_GE,
+    I32_CLZ,
+    I32_CTZ,
+    I32_POPCNT,
+    I32_ADD,
+    I32_SUB,
+    I32_MUL,
+    I32_DIV_S,
+    I32_DIV_U,
+    I32_REM_S,
+    I32_REM_U,
+    I32_AND,
+    I32_OR,
+    I32_XOR,
+    I32_SHL,
+    I32_SHR_S,
+    I32_SHR_U,
+    I32_ROTL,
+    I32_ROTR,
+    I64_CLZ,
+    I64_CTZ,
+    I64_POPCNT,
+    I64_ADD,
+    I64_SUB,
+    I64_MUL,
+    I64_DIV_S,
+    I64_DIV_U,
+    I64_REM_S,
+    I64_REM_U,
+    I64_AND,
+    I64_OR,
+    I64_XOR,
+    I64_SHL,
+    I64_SHR_S,
+    I64_SHR_U,
+    I64_ROTL,
+    I64_ROTR,
+    F32_ABS,
+    F32_NEG,
+    F32_CEIL,
+    F32_FLOOR,
+    F32_TRUNC,
+    F32_NEAREST,
+    F32_SQRT,
+    F32_ADD,
+    F32_SUB,
+    F32_MUL,
+    F32_DIV,
+    F32_MIN,
+    F32_MAX,
+    F32_COPYSIGN,
+    F64_ABS,
+    F64_NEG,
+    F64_CEIL,
+    F64_FLOOR,
+    F64_TRUNC,
+    F64_NEAREST,
+    F64_SQRT,
+    F64_ADD,
+    F64_SUB,
+    F64_MUL,
+    F64_DIV,
+    F64_MIN,
+    F64_MAX,
+    F64_COPYSIGN,
+    I32_WRAP_I64,
+    I32_TRUNC_F32_S,
+    I32_TRUNC_F32_U,
+    I32_TRUNC_F64_S,
+    I32_TRUNC_F64_U,
+    I64_EXTEND_I32_S,
+    I64_EXTEND_I32_U,
+    I64_TRUNC_F32_S,
+    I64_TRUNC_F32_U,
+    I64_TRUNC_F64_S,
+    I64_TRUNC_F64_U,
+    F32_CONVERT_I32_S,
+    F32_CONVERT_I32_U,
+    F32_CONVERT_I64_S,
+    F32_CONVERT_I64_U,
+    F32_DEMOTE_F64,
+    F64_CONVERT_I32_S,
+    F64_CONVERT_I32_U,
+    F64_CONVERT_I64_S,
+    F64_CONVERT_I64_U,
+    F64_PROMOTE_F32,
+    I32_REINTERPRET_F32,
+    I64_REINTERPRET_F64,
+    F32_REINTERPRET_I32,
+    F64_REINTERPRET_I64,
+    I32_EXTEND8_S,
+    I32_EXTEND16_S,
+    I64_EXTEND8_S,
+    I64_EXTEND16_S,
+    I64_EXTEND32_S,
+    // *reference*
+    REF_NULL,
+    REF_IS_NULL,
+    REF_FUNC,
+    // *saturating truncation and bulk memory*
+    I32_TRUNC_SAT_F32_S,
+    I32_TRUNC_SAT_F32_U,
+    I32_TRUNC_SAT_F64_S,
+    I32_TRUNC_SAT_F64_U,
+    I64_TRUNC_SAT_F32_S,
+    I64_TRUNC_SAT_F32_U,
+    I64_TRUNC_SAT_F64_S,
+    I64_TRUNC_SAT_F64_U,
+    MEMORY_INIT,
+    DATA_DROP,
+    MEMORY_COPY,
+    MEMORY_FILL,
+    TABLE_INIT,
+    ELEM_DROP,
+    TABLE_COPY,
+    TABLE_GROW,
+    TABLE_SIZE,
+    TABLE_FILL,
+}
+
+static OPCODE_NAMES: [&str; 204] = [
+    "invalid",
+    "locals",
+    "unreachable",
+    "nop",
+    "block",
+    "loop",
+    "if",
+    "else",
+    "end",
+    "br",
+    "br_if",
+    "br_table",
+    "return",
+    "call",
+    "call_indirect",
+    "return_call",
+    "return_call_indirect",
+    "drop",
+    "select",
+    "local.get",
+    "local.set",
+    "local.tee",
+    "global.get",
+    "global.set",
+    "table.get",
+    "table.set",
+    "i32.load",
+    "i64.load",
+    "f32.load",
+    "f64.load",
+    "i32.load8_s",
+    "i32.load8_u",
+    "i32.load16_s",
+    "i32.load16_u",
+    "i64.load8_s",
+    "i64.load8_u",
+    "i64.load16_s",
+    "i64.load16_u",
+    "i64.load32_s",
+    "i64.load32_u",
+    "i32.store",
+    "i64.store",
+    "f32.store",
+    "f64.store",
+    "i32.store8",
+    "i32.store16",
+    "i64.store8",
+    "i64.store16",
+    "i64.store32",
+    "memory.size",
+    "memory.grow",
+    "i32.const",
+    "i64.const",
+    "f32.const",
+    "f64.const",
+    "i32.eqz",
+    "i32.eq",
+    "i32.ne",
+    "i32.lt_s",
+    "i32.lt_u",
+    "i32.gt_s",
+    "i32.gt_u",
+    "i32.le_s",
+    "i32.le_u",
+    "i32.ge_s",
+    "i32.ge_u",
+    "i64.eqz",
+    "i64.eq",
+    "i64.ne",
+    "i64.lt_s",
+    "i64.lt_u",
+    "i64.gt_s",
+    "i64.gt_u",
+    "i64.le_s",
+    "i64.le_u",
+    "i64.ge_s",
+    "i64.ge_u",
+    "f32.eq",
+    "f32.ne",
+    "f32.lt",
+    "f32.gt",
+    "f32.le",
+    "f32.ge",
+    "f64.eq",
+    "f64.ne",
+    "f64.lt",
+    "f64.gt",
+    "f64.le",
+    "f64.ge",
+    "i32.clz",
+    "i32.ctz",
+    "i32.popcnt",
+    "i32.add",
+    "i32.sub",
+    "i32.mul",
+    "i32.div_s",
+    "i32.div_u",
+    "i32.rem_s",
+    "i32.rem_u",
+    "i32.and",
+    "i32.or",
+    "i32.xor",
+    "i32.shl",
+    "i32.shr_s",
+    "i32.shr_u",
+    "i32.rotl",
+    "i32.rotr",
+    "i64.clz",
+    "i64.ctz",
+    "i64.popcnt",
+    "i64.add",
+    "i64.sub",
+    "i64.mul",
+    "i64.div_s",
+    "i64.div_u",
+    "i64.rem_s",
+    "i64.rem_u",
+    "i64.and",
+    "i64.or",
+    "i64.xor",
+    "i64.shl",
+    "i64.shr_s",
+    "i64.shr_u",
+    "i64.rotl",
+    "i64.rotr",
+    "f32.abs",
+    "f32.neg",
+    "f32.ceil",
+    "f32.floor",
+    "f32.trunc",
+    "f32.nearest",
+    "f32.sqrt",
+    "f32.add",
+    "f32.sub",
+    "f32.mul",
+    "f32.div",
+    "f32.min",
+    "f32.max",
+    "f32.copysign",
+    "f64.abs",
+    "f64.neg",
+    "f64.ceil",
+    "f64.floor",
+    "f64.trunc",
+    "f64.nearest",
+    "f64.sqrt",
+    "f64.add",
+    "f64.sub",
+    "f64.mul",
+    "f64.div",
+    "f64.min",
+    "f64.max",
+    "f64.copysign",
+    "i32.wrap_i64",
+    "i32.trunc_f32_s",
+    "i32.trunc_f32_u",
+    "i32.trunc_f64_s",
+    "i32.trunc_f64_u",
+    "i64.extend_i32_s",
+    "i64.extend_i32_u",
+    "i64.trunc_f32_s",
+    "i64.trunc_f32_u",
+    "i64.trunc_f64_s",
+    "i64.trunc_f64_u",
+    "f32.convert_i32_s",
+    "f32.convert_i32_u",
+    "f32.convert_i64_s",
+    "f32.convert_i64_u",
+    "f32.demote_f64",
+    "f64.convert_i32_s",
+    "f64.convert_i32_u",
+    "f64.convert_i64_s",
+    "f64.convert_i64_u",
+    "f64.promote_f32",
+    "i32.reinterpret_f32",
+    "i64.reinterpret_f64",
+    "f32.reinterpret_i32",
+    "f64.reinterpret_i64",
+    "i32.extend8_s",
+    "i32.extend16_s",
+    "i64.extend8_s",
+    "i64.extend16_s",
+    "i64.extend32_s",
+    "ref.null",
+    "ref.is_null",
+    "ref.func",
+    "i32.trunc_sat_f32_s",
+    "i32.trunc_sat_f32_u",
+    "i32.trunc_sat_f64_s",
+    "i32.trunc_sat_f64_u",
+    "i64.trunc_sat_f32_s",
+    "i64.trunc_sat_f32_u",
+    "i64.trunc_sat_f64_s",
+    "i64.trunc_sat_f64_u",
+    "memory.init",
+    "data.drop",
+    "memory.copy",
+    "memory.fill",
+    "table.init",
+    "elem.drop",
+    "table.copy",
+    "table.grow",
+    "table.size",
+    "table.fill",
+];
+
+impl Opcode {
+    pub fn as_str(&self) -> &'static str {
+        OPCODE_NAMES[*self as usize]
+    }
+
+    /// Opcode that's `idx` opcodes after `self`, for decoding ranges of consecutive encodings.
+    fn offset(self, idx: u8) -> Self {
+        // every range this is used for lies within the enum
+        unsafe { std::mem::transmute(self as u16 + idx as u16) }
+    }
+
+    /// Whether the instruction starts a block, which is indented until it's `end`.
+    fn opens_block(&self) -> bool {
+        matches!(self, Opcode::BLOCK | Opcode::LOOP | Opcode::IF | Opcode::ELSE)
+    }
+
+    /// Whether the instruction ends a block, `else` both ends and starts one.
+    fn closes_block(&self) -> bool {
+        matches!(self, Opcode::ELSE | Opcode::END)
+    }
+
+    fn is_load(&self) -> bool {
+        (Opcode::I32_LOAD as u16..=Opcode::I64_LOAD32_U as u16).contains(&(*self as u16))
+    }
+
+    fn is_store(&self) -> bool {
+        (Opcode::I32_STORE as u16..=Opcode::I64_STORE32 as u16).contains(&(*self as u16))
+            || matches!(self, Opcode::MEMORY_INIT | Opcode::MEMORY_COPY | Opcode::MEMORY_FILL)
+    }
+
+    /// Log2 of the number of bytes a load or store accesses, which is it's default alignment.
+    fn natural_alignment(&self) -> u32 {
+        use Opcode::*;
+
+        match self {
+            I32_LOAD8_S | I32_LOAD8_U | I64_LOAD8_S | I64_LOAD8_U | I32_STORE8 | I64_STORE8 => 0,
+            I32_LOAD16_S | I32_LOAD16_U | I64_LOAD16_S | I64_LOAD16_U | I32_STORE16
+            | I64_STORE16 => 1,
+            I64_LOAD | F64_LOAD | I64_STORE | F64_STORE => 3,
+            _ => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Operand {
+    #[default]
+    Nothing,
+    /// Result of a `block`, `loop` or `if`.
+    BlockType(ValType),
+    /// Index into the type section, the signature of a block or indirect call.
+    Type(u32),
+    /// Block to branch to, counting outwards from the innermost block.
+    Label(u32),
+    /// Labels of a `br_table`, the last one being the default.
+    Labels(Box<[u32]>),
+    /// Index of a function and it's address, if it's known.
+    Function(u32, Option<usize>),
+    Local(u32),
+    Global(u32),
+    Table(u32),
+    Memory(u32),
+    Data(u32),
+    Elem(u32),
+    /// Offset added to the address of a load or store.
+    Offset(u64),
+    /// Alignment in bytes of a load or store, only given if it isn't the natural alignment.
+    Align(u64),
+    Int(i64),
+    F32(u32),
+    F64(u64),
+    /// Type of a null reference.
+    RefType(ValType),
+    /// Result of a typed `select`.
+    Types(Box<[ValType]>),
+    /// Locals a function declares, as runs of the same type.
+    Locals(Box<[(u32, ValType)]>),
+}
+
+impl ToTokens for Operand {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        let index = |stream: &mut TokenStream, idx: u32| {
+            stream.push_fmt(format_args!("{idx}"), CONFIG.colors.asm.immediate)
+        };
+
+        match self {
+            Self::BlockType(ty) => {
+                stream.push("(", CONFIG.colors.brackets);
+                stream.push("result ", CONFIG.colors.asm.component);
+                stream.push(ty.as_str(), CONFIG.colors.asm.primitive);
+                stream.push(")", CONFIG.colors.brackets);
+            }
+            Self::Type(idx) => {
+                stream.push("(", CONFIG.colors.brackets);
+                stream.push("type ", CONFIG.colors.asm.component);
+                index(stream, *idx);
+                stream.push(")", CONFIG.colors.brackets);
+            }
+            Self::Label(label) => stream.push_fmt(format_args!("{label}"), CONFIG.colors.asm.label),
+            Self::Labels(labels) => {
+                for (idx, label) in labels.iter().enumerate() {
+                    if idx != 0 {
+                        stream.push(" ", colors::WHITE);
+                    }
+                    stream.push_fmt(format_args!("{label}"), CONFIG.colors.asm.label);
+                }
+            }
+            Self::Function(idx, addr) => {
+                match addr.and_then(|addr| symbols.get_sym_by_addr(addr)) {
+                    Some(symbol) => {
+                        for token in symbol.name() {
+                            stream.push_token(token.clone());
+                        }
+                    }
+                    None => index(stream, *idx),
+                }
+            }
+            Self::Local(idx) | Self::Global(idx) => {
+                stream.push_fmt(format_args!("{idx}"), CONFIG.colors.asm.register)
+            }
+            Self::Table(idx) | Self::Memory(idx) | Self::Data(idx) | Self::Elem(idx) => {
+                index(stream, *idx)
+            }
+            Self::Offset(offset) => {
+                stream.push("offset=", CONFIG.colors.asm.component);
+                stream.push_immediate(*offset as i64, Radix::Decimal, CONFIG.colors.asm.immediate);
+            }
+            Self::Align(align) => {
+                stream.push("align=", CONFIG.colors.asm.component);
+                stream.push_fmt(format_args!("{align}"), CONFIG.colors.asm.immediate);
+            }
+            Self::Int(imm) => {
+                stream.push_immediate(*imm, Radix::Decimal, CONFIG.colors.asm.immediate)
+            }
+            Self::F32(bits) => {
+                let float = f32::from_bits(*bits);
+                stream.push_fmt(format_args!("{float}"), CONFIG.colors.asm.immediate)
+            }
+            Self::F64(bits) => {
+                let float = f64::from_bits(*bits);
+                stream.push_fmt(format_args!("{float}"), CONFIG.colors.asm.immediate)
+            }
+            Self::RefType(ty) => {
+                let name = match ty {
+                    ValType::ExternRef => "extern",
+                    _ => "func",
+                };
+                stream.push(name, CONFIG.colors.asm.primitive);
+            }
+            Self::Types(types) => {
+                stream.push("(", CONFIG.colors.brackets);
+                stream.push("result", CONFIG.colors.asm.component);
+                for ty in types.iter() {
+                    stream.push(" ", colors::WHITE);
+                    stream.push(ty.as_str(), CONFIG.colors.asm.primitive);
+                }
+                stream.push(")", CONFIG.colors.brackets);
+            }
+            Self::Locals(locals) => {
+                for (idx, (count, ty)) in locals.iter().enumerate() {
+                    if idx != 0 {
+                        stream.push_separator(CONFIG.colors.asm.expr);
+                    }
+                    if *count != 1 {
+                        stream.push_fmt(format_args!("{count} x "), CONFIG.colors.asm.immediate);
+                    }
+                    stream.push(ty.as_str(), CONFIG.colors.asm.primitive);
+                }
+            }
+            Self::Nothing => unreachable!("empty operand encountered"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    opcode: Opcode,
+    operands: [Operand; 2],
+    operand_count: usize,
+    width: usize,
+    /// Number of blocks the instruction is nested in, which it's indented by.
+    depth: usize,
+}
+
+impl decoder::Decoded for Instruction {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn update_rel_addrs(&mut self, _: usize, prev_inst: Option<&Instruction>) {
+        // function bodies are a block of their own, which isn't indented
+        let depth = match prev_inst {
+            Some(prev_inst) if self.opcode != Opcode::LOCALS => {
+                prev_inst.depth + prev_inst.opcode.opens_block() as usize
+            }
+            _ => 0,
+        };
+
+        self.depth = if self.opcode.closes_block() {
+            depth.saturating_sub(1)
+        } else {
+            depth
+        };
+    }
+
+    // nothing is a terminator, as branches go to the end of a block instead of to an address,
+    // the code following a `br` can only be reached through the `end` that recursive descent
+    // wouldn't know about
+
+    fn xref(&self) -> Option<(usize, XrefKind)> {
+        let addr = match self.operands[0] {
+            Operand::Function(_, Some(addr)) => addr,
+            _ => return None,
+        };
+
+        let kind = match self.opcode {
+            Opcode::CALL => XrefKind::Call,
+            Opcode::RETURN_CALL => XrefKind::Jump,
+            _ => XrefKind::Data,
+        };
+
+        Some((addr, kind))
+    }
+
+    fn kind(&self) -> InstKind {
+        use Opcode::*;
+
+        match self.opcode {
+            BR | BR_IF | BR_TABLE | IF | ELSE | RETURN_CALL | RETURN_CALL_INDIRECT => {
+                InstKind::Branch
+            }
+            CALL | CALL_INDIRECT => InstKind::Call,
+            RETURN => InstKind::Return,
+            UNREACHABLE => InstKind::Trap,
+            opcode if opcode.is_load() => InstKind::Load,
+            opcode if opcode.is_store() => InstKind::Store,
+            _ => InstKind::Other,
+        }
+    }
+
+    fn is_conditional(&self) -> bool {
+        matches!(self.opcode, Opcode::BR_IF | Opcode::IF)
+    }
+}
+
+/// Decodes the function bodies of a module's code section.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Decoder<'a> {
+    /// Address of every function, by index. Used to find out where calls go.
+    pub functions: &'a [usize],
+
+    /// Whether the next instruction is at the start of a function body, which starts by
+    /// declaring it's locals.
+    pub at_body: bool,
+}
+
+impl decoder::Decodable for Decoder<'_> {
+    type Instruction = Instruction;
+
+    fn decode(&self, reader: &mut decoder::Reader) -> Result<Self::Instruction, Error> {
+        reader.mark();
+        match decode(reader, self) {
+            Ok(mut inst) => {
+                inst.width = reader.offset();
+                Ok(inst)
+            }
+            Err(err) => Err(Error::new(err, reader.offset().clamp(1, u8::MAX as usize))),
+        }
+    }
+
+    fn max_width(&self) -> usize {
+        8
+    }
+}
+
+fn decode(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    if decoder.at_body {
+        return decode_locals(reader);
+    }
+
+    let byte = reader.next().ok_or(ErrorKind::ExhaustedInput)?;
+    let inst = match byte {
+        0x00 => instruction(UNREACHABLE, operands![]),
+        0x01 => instruction(NOP, operands![]),
+        0x02 => decode_block(BLOCK, reader)?,
+        0x03 => decode_block(LOOP, reader)?,
+        0x04 => decode_block(IF, reader)?,
+        0x05 => instruction(ELSE, operands![]),
+        0x0b => instruction(END, operands![]),
+        0x0c => instruction(BR, operands![Operand::Label(uleb32(reader)?)]),
+        0x0d => instruction(BR_IF, operands![Operand::Label(uleb32(reader)?)]),
+        0x0e => {
+            // the default label follows the table
+            let count = uleb32(reader)?;
+            let mut labels = Vec::new();
+            for _ in 0..=count {
+                labels.push(uleb32(reader)?);
+            }
+
+            instruction(BR_TABLE, operands![Operand::Labels(labels.into_boxed_slice())])
+        }
+        0x0f => instruction(RETURN, operands![]),
+        0x10 => instruction(CALL, operands![function(reader, decoder)?]),
+        0x11 => decode_call_indirect(CALL_INDIRECT, reader)?,
+        0x12 => instruction(RETURN_CALL, operands![function(reader, decoder)?]),
+        0x13 => decode_call_indirect(RETURN_CALL_INDIRECT, reader)?,
+        0x1a => instruction(DROP, operands![]),
+        0x1b => instruction(SELECT, operands![]),
+        0x1c => {
+            let count = uleb32(reader)?;
+            let mut types = Vec::new();
+            for _ in 0..count {
+                types.push(valtype(reader)?);
+            }
+
+            instruction(SELECT, operands![Operand::Types(types.into_boxed_slice())])
+        }
+        0x20..=0x22 => {
+            instruction(LOCAL_GET.offset(byte - 0x20), operands![Operand::Local(uleb32(reader)?)])
+        }
+        0x23..=0x24 => instruction(
+            GLOBAL_GET.offset(byte - 0x23),
+            operands![Operand::Global(uleb32(reader)?)],
+        ),
+        0x25..=0x26 => {
+            instruction(TABLE_GET.offset(byte - 0x25), operands![Operand::Table(uleb32(reader)?)])
+        }
+        0x28..=0x3e => decode_memory(I32_LOAD.offset(byte - 0x28), reader)?,
+        0x3f..=0x40 => {
+            let opcode = MEMORY_SIZE.offset(byte - 0x3f);
+            match uleb32(reader)? {
+                0 => instruction(opcode, operands![]),
+                memory => instruction(opcode, operands![Operand::Memory(memory)]),
+            }
+        }
+        0x41 => instruction(I32_CONST, operands![Operand::Int(sleb(reader, 32)?)]),
+        0x42 => instruction(I64_CONST, operands![Operand::Int(sleb(reader, 64)?)]),
+        0x43 => {
+            let mut bytes = [0u8; 4];
+            reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+            instruction(F32_CONST, operands![Operand::F32(u32::from_le_bytes(bytes))])
+        }
+        0x44 => {
+            let mut bytes = [0u8; 8];
+            reader.next_n(&mut bytes).ok_or(ErrorKind::ExhaustedInput)?;
+            instruction(F64_CONST, operands![Operand::F64(u64::from_le_bytes(bytes))])
+        }
+        0x45..=0xc4 => instruction(I32_EQZ.offset(byte - 0x45), operands![]),
+        0xd0 => {
+            let ty = valtype(reader)?;
+            if !matches!(ty, ValType::FuncRef | ValType::ExternRef) {
+                return Err(ErrorKind::InvalidOperand);
+            }
+
+            instruction(REF_NULL, operands![Operand::RefType(ty)])
+        }
+        0xd1 => instruction(REF_IS_NULL, operands![]),
+        0xd2 => instruction(REF_FUNC, operands![function(reader, decoder)?]),
+        0xfc => decode_prefixed(reader)?,
+        // vector instructions aren't supported
+        0xfd => return Err(ErrorKind::IncompleteDecoder),
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
+
+impl ToTokens for Instruction {
+    fn tokenize(&self, stream: &mut TokenStream, symbols: &Index) {
+        if self.depth > 0 {
+            stream.push_owned("  ".repeat(self.depth), colors::WHITE);
+        }
+
+        stream.push_mnemonic(self.opcode.as_str(), CONFIG.colors.asm.opcode);
+
+        // immediates are separated by spaces, like in the text format
+        for operand in &self.operands[..self.operand_count] {
+            stream.push(" ", colors::WHITE);
+            operand.tokenize(stream, symbols);
+        }
+    }
+}
+
+fn instruction(opcode: Opcode, (operands, operand_count): ([Operand; 2], usize)) -> Instruction {
+    Instruction {
+        opcode,
+        operands,
+        operand_count,
+        width: 0,
+        depth: 0,
+    }
+}
+
+/// Decodes an unsigned LEB128 integer of at most `bits` bits.
+fn uleb(reader: &mut decoder::Reader, bits: u32) -> Result<u64, ErrorKind> {
+    let mut value = 0u64;
+    let mut shift = 0;
+
+    loop {
+        let byte = reader.next().ok_or(ErrorKind::ExhaustedInput)?;
+        if shift >= bits {
+            return Err(ErrorKind::InvalidOperand);
+        }
+
+        value |= ((byte & 0x7f) as u64) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+#[inline]
+fn uleb32(reader: &mut decoder::Reader) -> Result<u32, ErrorKind> {
+    uleb(reader, 32).map(|value| value as u32)
+}
+
+/// Decodes a signed LEB128 integer of at most `bits` bits, of which `first` is the first byte.
+fn sleb_from(first: u8, reader: &mut decoder::Reader, bits: u32) -> Result<i64, ErrorKind> {
+    let mut value = 0i64;
+    let mut shift = 0;
+    let mut byte = first;
+
+    loop {
+        if shift >= bits {
+            return Err(ErrorKind::InvalidOperand);
+        }
+
+        value |= ((byte & 0x7f) as i64) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            break;
+        }
+
+        byte = reader.next().ok_or(ErrorKind::ExhaustedInput)?;
+    }
+
+    // sign extend
+    if shift < 64 && byte & 0x40 != 0 {
+        value |= -1 << shift;
+    }
+
+    Ok(value)
+}
+
+#[inline]
+fn sleb(reader: &mut decoder::Reader, bits: u32) -> Result<i64, ErrorKind> {
+    let first = reader.next().ok_or(ErrorKind::ExhaustedInput)?;
+    sleb_from(first, reader, bits)
+}
+
+fn valtype(reader: &mut decoder::Reader) -> Result<ValType, ErrorKind> {
+    let byte = reader.next().ok_or(ErrorKind::ExhaustedInput)?;
+    ValType::get(byte).ok_or(ErrorKind::InvalidOperand)
+}
+
+/// Index of a function, resolved to it's address if the decoder knows about it.
+fn function(reader: &mut decoder::Reader, decoder: &Decoder) -> Result<Operand, ErrorKind> {
+    let idx = uleb32(reader)?;
+    Ok(Operand::Function(idx, decoder.functions.get(idx as usize).copied()))
+}
+
+/// Decodes the locals at the start of a function body, along with the size of the body.
+fn decode_locals(reader: &mut decoder::Reader) -> Result<Instruction, ErrorKind> {
+    uleb32(reader)?;
+
+    let count = uleb32(reader)?;
+    let mut locals = Vec::new();
+    for _ in 0..count {
+        locals.push((uleb32(reader)?, valtype(reader)?));
+    }
+
+    if locals.is_empty() {
+        return Ok(instruction(Opcode::LOCALS, operands![]));
+    }
+
+    Ok(instruction(Opcode::LOCALS, operands![Operand::Locals(locals.into_boxed_slice())]))
+}
+
+/// Decodes `block`, `loop` and `if`, which are followed by the type of the block.
+fn decode_block(opcode: Opcode, reader: &mut decoder::Reader) -> Result<Instruction, ErrorKind> {
+    let byte = reader.next().ok_or(ErrorKind::ExhaustedInput)?;
+
+    // the type is either empty, a single result or an index into the type section
+    if byte == 0x40 {
+        return Ok(instruction(opcode, operands![]));
+    }
+
+    if let Some(ty) = ValType::get(byte) {
+        return Ok(instruction(opcode, operands![Operand::BlockType(ty)]));
+    }
+
+    match sleb_from(byte, reader, 33)? {
+        idx @ 0.. => Ok(instruction(opcode, operands![Operand::Type(idx as u32)])),
+        _ => Err(ErrorKind::InvalidOperand),
+    }
+}
+
+/// Decodes `call_indirect` and `return_call_indirect`, the table is only shown if it isn't the
+/// first one.
+fn decode_call_indirect(
+    opcode: Opcode,
+    reader: &mut decoder::Reader,
+) -> Result<Instruction, ErrorKind> {
+    let ty = Operand::Type(uleb32(reader)?);
+    match uleb32(reader)? {
+        0 => Ok(instruction(opcode, operands![ty])),
+        table => Ok(instruction(opcode, operands![Operand::Table(table), ty])),
+    }
+}
+
+/// Decodes loads and stores, which are followed by their alignment and offset.
+fn decode_memory(opcode: Opcode, reader: &mut decoder::Reader) -> Result<Instruction, ErrorKind> {
+    let mut align = uleb32(reader)?;
+
+    // multiple memories are indicated by the 6th bit of the alignment
+    if align & 0x40 != 0 {
+        uleb32(reader)?;
+        align &= !0x40;
+    }
+
+    if align >= 64 {
+        return Err(ErrorKind::InvalidOperand);
+    }
+
+    let offset = uleb(reader, 64)?;
+    let inst = match (offset, align == opcode.natural_alignment()) {
+        (0, true) => instruction(opcode, operands![]),
+        (0, false) => instruction(opcode, operands![Operand::Align(1 << align)]),
+        (_, true) => instruction(opcode, operands![Operand::Offset(offset)]),
+        (_, false) => {
+            instruction(opcode, operands![Operand::Offset(offset), Operand::Align(1 << align)])
+        }
+    };
+
+    Ok(inst)
+}
+
+/// Decodes instructions prefixed by `0xfc`, the saturating truncations and bulk memory and table
+/// operations.
+fn decode_prefixed(reader: &mut decoder::Reader) -> Result<Instruction, ErrorKind> {
+    use Opcode::*;
+
+    let inst = match uleb32(reader)? {
+        op @ 0..=7 => instruction(I32_TRUNC_SAT_F32_S.offset(op as u8), operands![]),
+        8 => {
+            let data = Operand::Data(uleb32(reader)?);
+            uleb32(reader)?;
+            instruction(MEMORY_INIT, operands![data])
+        }
+        9 => instruction(DATA_DROP, operands![Operand::Data(uleb32(reader)?)]),
+        10 => {
+            uleb32(reader)?;
+            uleb32(reader)?;
+            instruction(MEMORY_COPY, operands![])
+        }
+        11 => {
+            uleb32(reader)?;
+            instruction(MEMORY_FILL, operands![])
+        }
+        12 => {
+            let elem = Operand::Elem(uleb32(reader)?);
+            let table = Operand::Table(uleb32(reader)?);
+            instruction(TABLE_INIT, operands![table, elem])
+        }
+        13 => instruction(ELEM_DROP, operands![Operand::Elem(uleb32(reader)?)]),
+        14 => {
+            let dst = Operand::Table(uleb32(reader)?);
+            let src = Operand::Table(uleb32(reader)?);
+            instruction(TABLE_COPY, operands![dst, src])
+        }
+        op @ 15..=17 => {
+            let table = Operand::Table(uleb32(reader)?);
+            instruction(TABLE_GROW.offset(op as u8 - 15), operands![table])
+        }
+        _ => return Err(ErrorKind::InvalidOpcode),
+    };
+
+    Ok(inst)
+}
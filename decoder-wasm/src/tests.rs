@@ -0,0 +1,185 @@
+#![cfg(test)]
+
+use decoder::{testing, Decodable, Decoded, ErrorKind, InstKind, ToTokens, XrefKind};
+
+fn decode(bytes: &[u8]) -> String {
+    testing::decode(&crate::Decoder::default(), bytes)
+}
+
+fn decode_error(bytes: &[u8]) -> ErrorKind {
+    testing::decode_error(&crate::Decoder::default(), bytes)
+}
+
+/// Decodes a function body at address 0x100, whose functions are located at `functions`.
+fn decode_body(bytes: &[u8], functions: &[usize], symbols: &debugvault::Index) -> Vec<String> {
+    let decoder = crate::Decoder { functions, at_body: true };
+    let mut stream = decoder::InstructionStream::new(bytes, 0x100, decoder);
+    let mut decoded = Vec::new();
+    let mut prev = None;
+
+    loop {
+        stream.decoder_mut().at_body = stream.ip() == 0x100;
+        let Some((ip, inst)) = stream.next() else { break };
+        let mut inst = inst.unwrap();
+        let mut line = tokenizing::TokenStream::new();
+        inst.update_rel_addrs(ip, prev.as_ref());
+        inst.tokenize(&mut line, symbols);
+        decoded.push(line.to_string());
+        prev = Some(inst);
+    }
+
+    decoded
+}
+
+#[test]
+fn control() {
+    assert_eq!(decode(&[0x00]), "unreachable");
+    assert_eq!(decode(&[0x02, 0x40]), "block");
+    assert_eq!(decode(&[0x03, 0x7f]), "loop (result i32)");
+    assert_eq!(decode(&[0x04, 0x03]), "if (type 3)");
+    assert_eq!(decode(&[0x0c, 0x01]), "br 1");
+    assert_eq!(decode(&[0x0d, 0x80, 0x01]), "br_if 128");
+    assert_eq!(decode(&[0x0e, 0x02, 0x00, 0x01, 0x02]), "br_table 0 1 2");
+    assert_eq!(decode(&[0x10, 0x05]), "call 5");
+    assert_eq!(decode(&[0x11, 0x02, 0x00]), "call_indirect (type 2)");
+    assert_eq!(decode(&[0x11, 0x02, 0x01]), "call_indirect 1 (type 2)");
+    assert_eq!(decode(&[0x1c, 0x01, 0x7e]), "select (result i64)");
+    assert_eq!(decode_error(&[0x06]), ErrorKind::InvalidOpcode);
+}
+
+#[test]
+fn variables() {
+    assert_eq!(decode(&[0x20, 0x00]), "local.get 0");
+    assert_eq!(decode(&[0x22, 0x03]), "local.tee 3");
+    assert_eq!(decode(&[0x24, 0x01]), "global.set 1");
+    assert_eq!(decode(&[0x25, 0x00]), "table.get 0");
+}
+
+#[test]
+fn memory() {
+    assert_eq!(decode(&[0x28, 0x02, 0x00]), "i32.load");
+    assert_eq!(decode(&[0x28, 0x02, 0x10]), "i32.load offset=16");
+    assert_eq!(decode(&[0x29, 0x02, 0x08]), "i64.load offset=8 align=4");
+    assert_eq!(decode(&[0x2d, 0x00, 0x00]), "i32.load8_u");
+    assert_eq!(decode(&[0x3b, 0x00, 0x00]), "i32.store16 align=1");
+    assert_eq!(decode(&[0x3e, 0x02, 0xff, 0x01]), "i64.store32 offset=255");
+    assert_eq!(decode(&[0x3f, 0x00]), "memory.size");
+    assert_eq!(decode(&[0x40, 0x00]), "memory.grow");
+    assert_eq!(decode(&[0xfc, 0x0a, 0x00, 0x00]), "memory.copy");
+    assert_eq!(decode(&[0xfc, 0x08, 0x01, 0x00]), "memory.init 1");
+}
+
+#[test]
+fn numeric() {
+    assert_eq!(decode(&[0x41, 0x7f]), "i32.const -1");
+    assert_eq!(decode(&[0x41, 0xe5, 0x8e, 0x26]), "i32.const 624485");
+    assert_eq!(decode(&[0x42, 0x80, 0x80, 0x80, 0x80, 0x10]), "i64.const 4294967296");
+    assert_eq!(decode(&[0x43, 0x00, 0x00, 0xc0, 0x3f]), "f32.const 1.5");
+    assert_eq!(decode(&[0x44, 0, 0, 0, 0, 0, 0, 0xf0, 0xbf]), "f64.const -1");
+    assert_eq!(decode(&[0x45]), "i32.eqz");
+    assert_eq!(decode(&[0x6a]), "i32.add");
+    assert_eq!(decode(&[0x8a]), "i64.rotr");
+    assert_eq!(decode(&[0xa7]), "i32.wrap_i64");
+    assert_eq!(decode(&[0xc4]), "i64.extend32_s");
+    assert_eq!(decode(&[0xfc, 0x07]), "i64.trunc_sat_f64_u");
+}
+
+#[test]
+fn reference() {
+    assert_eq!(decode(&[0xd0, 0x70]), "ref.null func");
+    assert_eq!(decode(&[0xd0, 0x6f]), "ref.null extern");
+    assert_eq!(decode_error(&[0xd0, 0x7f]), ErrorKind::InvalidOperand);
+    assert_eq!(decode(&[0xfc, 0x0c, 0x02, 0x01]), "table.init 1 2");
+    assert_eq!(decode(&[0xfc, 0x10, 0x00]), "table.size 0");
+}
+
+#[test]
+fn nesting() {
+    #[rustfmt::skip]
+    let body = [
+        0x1a, 0x01, 0x01, 0x7f,
+        0x02, 0x40,
+        0x03, 0x40,
+        0x20, 0x00,
+        0x04, 0x7f,
+        0x41, 0x01,
+        0x05,
+        0x41, 0x02,
+        0x0b,
+        0x0d, 0x01,
+        0x0c, 0x00,
+        0x0b,
+        0x0b,
+        0x20, 0x00,
+        0x0b,
+    ];
+
+    assert_eq!(
+        decode_body(&body, &[], &debugvault::Index::default()),
+        [
+            "locals i32",
+            "block",
+            "  loop",
+            "    local.get 0",
+            "    if (result i32)",
+            "      i32.const 1",
+            "    else",
+            "      i32.const 2",
+            "    end",
+            "    br_if 1",
+            "    br 0",
+            "  end",
+            "end",
+            "local.get 0",
+            "end",
+        ]
+    );
+}
+
+#[test]
+fn locals() {
+    let body = [0x06, 0x02, 0x03, 0x7f, 0x01, 0x7c, 0x0b];
+    let symbols = debugvault::Index::default();
+    assert_eq!(decode_body(&body, &[], &symbols), ["locals 3 x i32, f64", "end"]);
+    assert_eq!(decode_body(&[0x02, 0x00, 0x0b], &[], &symbols), ["locals", "end"]);
+}
+
+#[test]
+fn calls() {
+    let decoder = crate::Decoder { functions: &[0x20, 0x100], at_body: false };
+    let mut reader = decoder::Reader::new(&[0x10, 0x00]);
+    let inst = decoder.decode(&mut reader).unwrap();
+    assert_eq!(inst.xref(), Some((0x20, XrefKind::Call)));
+    assert_eq!(inst.kind(), InstKind::Call);
+
+    let mut reader = decoder::Reader::new(&[0xd2, 0x01]);
+    let inst = decoder.decode(&mut reader).unwrap();
+    assert_eq!(inst.xref(), Some((0x100, XrefKind::Data)));
+
+    // calls are named after the function
+    let mut symbols = debugvault::Index::default();
+    symbols.insert_func(0x20, "print");
+    let body = [0x08, 0x00, 0x41, 0x00, 0x10, 0x00, 0x10, 0x02, 0x0b];
+    assert_eq!(
+        decode_body(&body, &[0x20, 0x100], &symbols),
+        ["locals", "i32.const 0", "call print", "call 2", "end"]
+    );
+}
+
+#[test]
+fn kinds() {
+    let kind = |bytes: &[u8]| {
+        let decoder = crate::Decoder::default();
+        let inst = decoder.decode(&mut decoder::Reader::new(bytes)).unwrap();
+        (inst.kind(), inst.is_conditional())
+    };
+
+    assert_eq!(kind(&[0x0c, 0x00]), (InstKind::Branch, false));
+    assert_eq!(kind(&[0x0d, 0x00]), (InstKind::Branch, true));
+    assert_eq!(kind(&[0x0f]), (InstKind::Return, false));
+    assert_eq!(kind(&[0x00]), (InstKind::Trap, false));
+    assert_eq!(kind(&[0x11, 0x00, 0x00]), (InstKind::Call, false));
+    assert_eq!(kind(&[0x2c, 0x00, 0x00]), (InstKind::Load, false));
+    assert_eq!(kind(&[0x36, 0x02, 0x00]), (InstKind::Store, false));
+    assert_eq!(kind(&[0x6a]), (InstKind::Other, false));
+}
@@ -12,7 +12,7 @@ pub mod protected_mode;
 mod safer_unchecked;
 
 use debugvault::Index;
-use tokenizing::TokenStream;
+use tokenizing::{Radix, TokenStream};
 use config::CONFIG;
 
 const MEM_SIZE_STRINGS: [&str; 64] = [
@@ -33,10 +33,10 @@ impl decoder::ToTokens for Number {
             stream.push("0x7fffffff", CONFIG.colors.asm.immediate);
         } else if self.0 < 0 {
             stream.push(" - ", CONFIG.colors.asm.expr);
-            stream.push_owned(decoder::encode_hex(-self.0 as i64), CONFIG.colors.asm.immediate);
+            stream.push_immediate(-self.0 as i64, Radix::Hex, CONFIG.colors.asm.immediate);
         } else {
             stream.push(" + ", CONFIG.colors.asm.expr);
-            stream.push_owned(decoder::encode_hex(self.0 as i64), CONFIG.colors.asm.immediate);
+            stream.push_immediate(self.0 as i64, Radix::Hex, CONFIG.colors.asm.immediate);
         }
     }
 }
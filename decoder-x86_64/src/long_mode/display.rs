@@ -6,9 +6,9 @@ use crate::long_mode::{
 use crate::safer_unchecked::GetSaferUnchecked as _;
 use crate::{Number, MEM_SIZE_STRINGS};
 
-use decoder::ToTokens;
+use decoder::{Decoded, ToTokens, XrefKind};
 use debugvault::Index;
-use tokenizing::{colors, TokenStream};
+use tokenizing::{colors, Radix, TokenStream};
 use config::CONFIG;
 
 impl fmt::Display for Decoder {
@@ -273,8 +273,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateU8(_) => match symbols.get_sym_by_addr(addr) {
@@ -284,8 +283,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateI16(_) => match symbols.get_sym_by_addr(addr) {
@@ -295,8 +293,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateU16(_) => match symbols.get_sym_by_addr(addr) {
@@ -306,8 +303,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateI32(_) => match symbols.get_sym_by_addr(addr) {
@@ -317,8 +313,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateU32(_) => match symbols.get_sym_by_addr(addr) {
@@ -328,8 +323,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateI64(_) => match symbols.get_sym_by_addr(addr) {
@@ -339,8 +333,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateU64(_) => match symbols.get_sym_by_addr(addr) {
@@ -350,8 +343,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::DisplacementU32(_) => {
@@ -364,8 +356,7 @@ impl Operand {
                         }
                     }
                     None => {
-                        let text = decoder::encode_hex(addr as i64);
-                        stream.push_owned(text, CONFIG.colors.asm.immediate);
+                        stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -380,8 +371,7 @@ impl Operand {
                         }
                     }
                     None => {
-                        let text = decoder::encode_hex(addr as i64);
-                        stream.push_owned(text, CONFIG.colors.asm.immediate);
+                        stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -412,7 +402,7 @@ impl Operand {
                     None => {
                         stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -428,7 +418,7 @@ impl Operand {
                     None => {
                         stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(format!("{scale}"), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                         Number(disp).tokenize(stream, symbols);
                     }
                 }
@@ -480,7 +470,7 @@ impl Operand {
                         stream.push(" + ", CONFIG.colors.asm.expr);
                         stream.push(regspec_label(index), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -498,7 +488,7 @@ impl Operand {
                         stream.push(" + ", CONFIG.colors.asm.expr);
                         stream.push(regspec_label(index), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                         Number(disp).tokenize(stream, symbols);
                     }
                 }
@@ -534,7 +524,7 @@ impl Operand {
                     None => {
                         stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.register);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.register);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -554,7 +544,7 @@ impl Operand {
                     None => {
                         stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.register);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.register);
                         stream.push(" ", colors::WHITE);
                         Number(disp).tokenize(stream, symbols);
                     }
@@ -620,7 +610,7 @@ impl Operand {
                         stream.push(" + ", CONFIG.colors.asm.expr);
                         stream.push(regspec_label(index), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -648,7 +638,7 @@ impl Operand {
                         stream.push(" + ", CONFIG.colors.asm.expr);
                         stream.push(regspec_label(index), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                         Number(disp).tokenize(stream, symbols);
                     }
                 }
@@ -681,36 +671,28 @@ impl Operand {
 
         match *self {
             Operand::ImmediateU8(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateI8(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateU16(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateI16(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateU32(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateI32(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateU64(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateI64(imm) => {
-                let text = decoder::encode_hex(imm);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::Register(ref spec) => {
                 stream.push(regspec_label(spec), CONFIG.colors.asm.register);
@@ -765,12 +747,12 @@ impl Operand {
             }
             Operand::DisplacementU32(imm) => {
                 stream.push("[", CONFIG.colors.brackets);
-                stream.push_owned(decoder::encode_hex(imm as i64), CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 stream.push("]", CONFIG.colors.brackets);
             }
             Operand::DisplacementU64(imm) => {
                 stream.push("[", CONFIG.colors.brackets);
-                stream.push_owned(decoder::encode_hex(imm as i64), CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 stream.push("]", CONFIG.colors.brackets);
             }
             Operand::RegDisp(ref spec, disp) => {
@@ -788,14 +770,14 @@ impl Operand {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 stream.push("]", CONFIG.colors.brackets);
             }
             Operand::RegScaleDisp(ref spec, scale, disp) => {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(format!("{scale}"), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 Number(disp).tokenize(stream, symbols);
                 stream.push("]", CONFIG.colors.brackets);
             }
@@ -820,7 +802,7 @@ impl Operand {
                 stream.push(" + ", CONFIG.colors.asm.expr);
                 stream.push(regspec_label(index), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 stream.push("]", CONFIG.colors.brackets);
             }
             Operand::RegIndexBaseScaleDisp(ref base, ref index, scale, disp) => {
@@ -829,7 +811,7 @@ impl Operand {
                 stream.push(" + ", CONFIG.colors.asm.expr);
                 stream.push(regspec_label(index), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 Number(disp).tokenize(stream, symbols);
                 stream.push("]", CONFIG.colors.brackets);
             }
@@ -856,7 +838,7 @@ impl Operand {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.register);
                 stream.push("]", CONFIG.colors.brackets);
 
                 stream.push("{", CONFIG.colors.brackets);
@@ -867,7 +849,7 @@ impl Operand {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.register);
                 stream.push(" ", colors::WHITE);
                 Number(disp).tokenize(stream, symbols);
                 stream.push("]", CONFIG.colors.brackets);
@@ -906,7 +888,7 @@ impl Operand {
                 stream.push(" + ", CONFIG.colors.asm.expr);
                 stream.push(regspec_label(index), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 stream.push("]", CONFIG.colors.brackets);
 
                 stream.push("{", CONFIG.colors.brackets);
@@ -925,7 +907,7 @@ impl Operand {
                 stream.push(" + ", CONFIG.colors.asm.expr);
                 stream.push(regspec_label(index), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 Number(disp).tokenize(stream, symbols);
                 stream.push("]", CONFIG.colors.brackets);
 
@@ -945,7 +927,8 @@ impl fmt::Display for Opcode {
 }
 
 const MNEMONICS: &[&str] = &[
-    "invalid",
+    // bytes that don't decode are shown as data
+    ".byte",
     "add",
     "or",
     "adc",
@@ -2416,7 +2399,7 @@ impl ToTokens for Instruction {
         }
 
         op.push_str(opcode_name);
-        stream.push_owned(op, CONFIG.colors.asm.opcode);
+        stream.push_owned_mnemonic(op, CONFIG.colors.asm.opcode);
 
         // slightly hacky but for `int` instructions we tend to incorrectly try to
         // do symbolic resolution on the immediate which isn't correct
@@ -2440,7 +2423,7 @@ impl ToTokens for Instruction {
             }
 
             if let Some(prefix) = self.segment_override_for_op(0) {
-                stream.push_owned(prefix.to_string(), CONFIG.colors.asm.segment);
+                stream.push_fmt(format_args!("{prefix}"), CONFIG.colors.asm.segment);
                 stream.push(":", CONFIG.colors.asm.expr);
             }
 
@@ -2451,7 +2434,7 @@ impl ToTokens for Instruction {
                     continue;
                 }
 
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
 
                 let op = Operand::from_spec(self, self.operands[idx as usize]);
                 if op.is_memory() {
@@ -2461,7 +2444,7 @@ impl ToTokens for Instruction {
                     );
                 }
                 if let Some(prefix) = self.segment_override_for_op(idx) {
-                    stream.push_owned(prefix.to_string(), CONFIG.colors.asm.segment);
+                    stream.push_fmt(format_args!("{prefix}"), CONFIG.colors.asm.segment);
                     stream.push(":", CONFIG.colors.asm.expr);
                 }
 
@@ -2528,11 +2511,23 @@ impl ToTokens for Instruction {
 
                         stream.push("{", CONFIG.colors.brackets);
                         stream.push("1to", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                         stream.push("}", CONFIG.colors.brackets);
                     }
                 }
             }
         }
+
+        // only branch targets and ip-relative operands are addresses, other immediates that
+        // happen to fall within a symbol are left as is
+        let target = match self.xref() {
+            Some((addr, XrefKind::Call | XrefKind::Jump)) => Some(addr),
+            Some((addr, XrefKind::Data)) if self.regs[1] == RegSpec::RIP => Some(addr),
+            _ => None,
+        };
+
+        if let Some(addr) = target {
+            symbols.tokenize_offset(stream, addr);
+        }
     }
 }
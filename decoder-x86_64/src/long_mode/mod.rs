@@ -11,7 +11,7 @@ use std::hash::{Hash, Hasher};
 use crate::safer_unchecked::unreachable_kinda_unchecked as unreachable_unchecked;
 pub use crate::MemoryAccessSize;
 
-use decoder::{Decoded, Decodable, Error, ErrorKind, Reader, ToTokens};
+use decoder::{Decoded, Decodable, Error, ErrorKind, Reader, ToTokens, XrefKind};
 use debugvault::Index;
 use tokenizing::TokenStream;
 use config::CONFIG;
@@ -2737,6 +2737,51 @@ impl Decoded for Instruction {
             self.imm_override = true;
         }
     }
+
+    fn xref(&self) -> Option<(usize, XrefKind)> {
+        if !self.imm_override {
+            return None;
+        }
+
+        let addr = self.imm as usize;
+        let operands = &self.operands[..self.operand_count as usize];
+
+        // the overridden immediate is either the address of a memory operand..
+        if let Some(spec) = operands.iter().find(|spec| spec.is_memory()) {
+            return match spec {
+                OperandSpec::DispU32 | OperandSpec::DispU64 => Some((addr, XrefKind::Data)),
+                _ if self.regs[1] == RegSpec::RIP => Some((addr, XrefKind::Data)),
+                _ => None,
+            };
+        }
+
+        // ..or the target of a relative branch
+        match self.opcode {
+            Opcode::CALL => Some((addr, XrefKind::Call)),
+            Opcode::JMP | Opcode::LOOP | Opcode::LOOPZ | Opcode::LOOPNZ | Opcode::JRCXZ => {
+                Some((addr, XrefKind::Jump))
+            }
+            opcode if opcode.is_jcc() => Some((addr, XrefKind::Jump)),
+            _ => None,
+        }
+    }
+
+    fn is_terminator(&self) -> bool {
+        matches!(
+            self.opcode,
+            Opcode::RETURN
+                | Opcode::RETF
+                | Opcode::IRET
+                | Opcode::IRETD
+                | Opcode::IRETQ
+                | Opcode::SYSRET
+                | Opcode::SYSEXIT
+                | Opcode::JMP
+                | Opcode::JMPF
+                | Opcode::UD2
+                | Opcode::HLT
+        )
+    }
 }
 
 impl Decodable for Decoder {
@@ -2761,6 +2806,11 @@ impl Decodable for Decoder {
     fn max_width(&self) -> usize {
         6
     }
+
+    /// Decoding resumes at the next byte, so the bytes that don't decode are shown one at a time.
+    fn unknown(&self, bytes: &[u8]) -> Option<Self::Instruction> {
+        bytes.first().map(|&byte| Instruction::byte(byte))
+    }
 }
 
 #[allow(non_camel_case_types)]
@@ -4391,6 +4441,17 @@ impl Instruction {
         }
     }
 
+    /// a `.byte` directive, for a byte that doesn't start a valid instruction.
+    pub fn byte(byte: u8) -> Instruction {
+        let mut inst = Instruction::invalid();
+        inst.opcode = Opcode::Invalid;
+        inst.imm = byte as u64;
+        inst.operands[0] = OperandSpec::ImmU8;
+        inst.operand_count = 1;
+        inst.length = 1;
+        inst
+    }
+
     /// get the `Segment` that will *actually* be used for accessing the operand at index `i`.
     ///
     /// `stos`, `lods`, `movs`, and `cmps` specifically name some segments for use regardless of
@@ -74,6 +74,44 @@ fn test_display_under(dekoder: &Decoder, data: &[u8], expected: &'static str) {
     }
 }
 
+#[test]
+fn unknown_bytes() {
+    let data = [0x90, 0x48, 0xd6, 0x0f, 0x0b, 0xc3];
+    let symbols = debugvault::Index::default();
+
+    // decoding resumes right after the first byte of the invalid instruction
+    assert_eq!(
+        decoder::testing::decode_instructions(Decoder::default(), &data, 0, &symbols),
+        ["nop", ".byte 0x48", ".byte 0xd6", "ud2", "ret"]
+    );
+}
+
+#[test]
+fn symbolized_targets() {
+    let mut symbols = debugvault::Index::default();
+    symbols.insert_sized_func(0x12a30, 0x40, "memcpy");
+
+    let decode = |data: &[u8]| {
+        let mut reader = Reader::new(data);
+        let mut inst = Decoder::default().decode(&mut reader).unwrap();
+        let mut stream = tokenizing::TokenStream::new();
+        inst.update_rel_addrs(0x1000, None);
+        inst.tokenize(&mut stream, &symbols);
+        stream.to_string()
+    };
+
+    assert_eq!(decode(&[0xe8, 0x2b, 0x1a, 0x01, 0x00]), "call memcpy");
+    assert_eq!(decode(&[0xe8, 0x3b, 0x1a, 0x01, 0x00]), "call 0x12a40 <memcpy+0x10>");
+    assert_eq!(decode(&[0xe9, 0x3b, 0x1a, 0x01, 0x00]), "jmp 0x12a40 <memcpy+0x10>");
+    // past the end of the symbol
+    assert_eq!(decode(&[0xe8, 0x6b, 0x1a, 0x01, 0x00]), "call 0x12a70");
+    // rip-relative operands are addresses too
+    assert_eq!(
+        decode(&[0x48, 0x8b, 0x05, 0x39, 0x1a, 0x01, 0x00]),
+        "mov rax, qword [rip + 0x11a39] <memcpy+0x10>"
+    );
+}
+
 #[test]
 fn modrm_decode() {
     // just modrm
@@ -6457,3 +6495,15 @@ mod reg_masks {
         RegSpec::zmm(32);
     }
 }
+
+#[test]
+fn stream_skips_invalid() {
+    // `push es` doesn't exist in long mode, decoding continues at the `nop` after it
+    let bytes = [0x06, 0x90];
+    let stream = decoder::InstructionStream::new(&bytes, 0x1000, Decoder::default());
+
+    let decoded: Vec<_> = stream
+        .map(|(addr, inst)| (addr, inst.map(|inst| inst.width()).map_err(|err| err.size())))
+        .collect();
+    assert_eq!(decoded, [(0x1000, Err(1)), (0x1001, Ok(1))]);
+}
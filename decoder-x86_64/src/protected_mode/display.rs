@@ -6,9 +6,9 @@ use crate::protected_mode::{
 use crate::safer_unchecked::GetSaferUnchecked as _;
 use crate::{Number, MEM_SIZE_STRINGS};
 
-use decoder::ToTokens;
+use decoder::{Decoded, ToTokens, XrefKind};
 use debugvault::Index;
-use tokenizing::{colors, TokenStream};
+use tokenizing::{colors, Radix, TokenStream};
 use config::CONFIG;
 
 impl fmt::Display for Decoder {
@@ -266,8 +266,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateU8(_) => match symbols.get_sym_by_addr(addr) {
@@ -277,8 +276,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateI16(_) => match symbols.get_sym_by_addr(addr) {
@@ -288,8 +286,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateU16(_) => match symbols.get_sym_by_addr(addr) {
@@ -299,8 +296,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateI32(_) => match symbols.get_sym_by_addr(addr) {
@@ -310,8 +306,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::ImmediateU32(_) => match symbols.get_sym_by_addr(addr) {
@@ -321,8 +316,7 @@ impl Operand {
                     }
                 }
                 None => {
-                    let text = decoder::encode_hex(addr as i64);
-                    stream.push_owned(text, CONFIG.colors.asm.immediate);
+                    stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 }
             },
             Operand::DisplacementU32(_) => {
@@ -335,8 +329,7 @@ impl Operand {
                         }
                     }
                     None => {
-                        let text = decoder::encode_hex(addr as i64);
-                        stream.push_owned(text, CONFIG.colors.asm.immediate);
+                        stream.push_immediate(addr as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -367,7 +360,7 @@ impl Operand {
                     None => {
                         stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -383,7 +376,7 @@ impl Operand {
                     None => {
                         stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(format!("{scale}"), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                         Number(disp).tokenize(stream, symbols);
                     }
                 }
@@ -435,7 +428,7 @@ impl Operand {
                         stream.push(" + ", CONFIG.colors.asm.expr);
                         stream.push(regspec_label(index), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -453,7 +446,7 @@ impl Operand {
                         stream.push(" + ", CONFIG.colors.asm.expr);
                         stream.push(regspec_label(index), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                         Number(disp).tokenize(stream, symbols);
                     }
                 }
@@ -489,7 +482,7 @@ impl Operand {
                     None => {
                         stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.register);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.register);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -509,7 +502,7 @@ impl Operand {
                     None => {
                         stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.register);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.register);
                         stream.push(" ", colors::WHITE);
                         Number(disp).tokenize(stream, symbols);
                     }
@@ -575,7 +568,7 @@ impl Operand {
                         stream.push(" + ", CONFIG.colors.asm.expr);
                         stream.push(regspec_label(index), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                     }
                 }
                 stream.push("]", CONFIG.colors.brackets);
@@ -603,7 +596,7 @@ impl Operand {
                         stream.push(" + ", CONFIG.colors.asm.expr);
                         stream.push(regspec_label(index), CONFIG.colors.asm.register);
                         stream.push(" * ", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                         Number(disp).tokenize(stream, symbols);
                     }
                 }
@@ -636,33 +629,27 @@ impl Operand {
 
         match *self {
             Operand::ImmediateU8(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateI8(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateU16(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateI16(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateU32(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::ImmediateI32(imm) => {
-                let text = decoder::encode_hex(imm as i64);
-                stream.push_owned(text, CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::AbsoluteFarAddress { segment, address } => {
-                stream.push_owned(decoder::encode_hex(segment as i64), CONFIG.colors.asm.immediate);
+                stream.push_immediate(segment as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 stream.push(":", CONFIG.colors.asm.expr);
-                stream.push_owned(decoder::encode_hex(address as i64), CONFIG.colors.asm.immediate);
+                stream.push_immediate(address as i64, Radix::Hex, CONFIG.colors.asm.immediate);
             }
             Operand::Register(ref spec) => {
                 stream.push(regspec_label(spec), CONFIG.colors.asm.register);
@@ -717,12 +704,12 @@ impl Operand {
             }
             Operand::DisplacementU16(imm) => {
                 stream.push("[", CONFIG.colors.brackets);
-                stream.push_owned(decoder::encode_hex(imm as i64), CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 stream.push("]", CONFIG.colors.brackets);
             }
             Operand::DisplacementU32(imm) => {
                 stream.push("[", CONFIG.colors.brackets);
-                stream.push_owned(decoder::encode_hex(imm as i64), CONFIG.colors.asm.immediate);
+                stream.push_immediate(imm as i64, Radix::Hex, CONFIG.colors.asm.immediate);
                 stream.push("]", CONFIG.colors.brackets);
             }
             Operand::RegDisp(ref spec, disp) => {
@@ -740,14 +727,14 @@ impl Operand {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 stream.push("]", CONFIG.colors.brackets);
             }
             Operand::RegScaleDisp(ref spec, scale, disp) => {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(format!("{scale}"), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 Number(disp).tokenize(stream, symbols);
                 stream.push("]", CONFIG.colors.brackets);
             }
@@ -772,7 +759,7 @@ impl Operand {
                 stream.push(" + ", CONFIG.colors.asm.expr);
                 stream.push(regspec_label(index), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 stream.push("]", CONFIG.colors.brackets);
             }
             Operand::RegIndexBaseScaleDisp(ref base, ref index, scale, disp) => {
@@ -781,7 +768,7 @@ impl Operand {
                 stream.push(" + ", CONFIG.colors.asm.expr);
                 stream.push(regspec_label(index), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 Number(disp).tokenize(stream, symbols);
                 stream.push("]", CONFIG.colors.brackets);
             }
@@ -808,7 +795,7 @@ impl Operand {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.register);
                 stream.push("]", CONFIG.colors.brackets);
 
                 stream.push("{", CONFIG.colors.brackets);
@@ -819,7 +806,7 @@ impl Operand {
                 stream.push("[", CONFIG.colors.brackets);
                 stream.push(regspec_label(spec), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.register);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.register);
                 Number(disp).tokenize(stream, symbols);
                 stream.push("]", CONFIG.colors.brackets);
 
@@ -856,7 +843,7 @@ impl Operand {
                 stream.push(" + ", CONFIG.colors.asm.expr);
                 stream.push(regspec_label(index), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 stream.push("]", CONFIG.colors.brackets);
 
                 stream.push("{", CONFIG.colors.brackets);
@@ -875,7 +862,7 @@ impl Operand {
                 stream.push(" + ", CONFIG.colors.asm.expr);
                 stream.push(regspec_label(index), CONFIG.colors.asm.register);
                 stream.push(" * ", CONFIG.colors.asm.expr);
-                stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                 Number(disp).tokenize(stream, symbols);
                 stream.push("]", CONFIG.colors.brackets);
 
@@ -895,7 +882,8 @@ impl fmt::Display for Opcode {
 }
 
 const MNEMONICS: &[&str] = &[
-    "invalid",
+    // bytes that don't decode are shown as data
+    ".byte",
     "add",
     "or",
     "adc",
@@ -2380,7 +2368,7 @@ impl ToTokens for Instruction {
         }
 
         op.push_str(opcode_name);
-        stream.push_owned(op, CONFIG.colors.asm.opcode);
+        stream.push_owned_mnemonic(op, CONFIG.colors.asm.opcode);
 
         // slightly hacky but for `int` instructions we tend to incorrectly try to
         // do symbolic resolution on the immediate which isn't correct
@@ -2404,7 +2392,7 @@ impl ToTokens for Instruction {
             }
 
             if let Some(prefix) = self.segment_override_for_op(0) {
-                stream.push_owned(prefix.to_string(), CONFIG.colors.asm.segment);
+                stream.push_fmt(format_args!("{prefix}"), CONFIG.colors.asm.segment);
                 stream.push(":", CONFIG.colors.asm.expr);
             }
 
@@ -2415,7 +2403,7 @@ impl ToTokens for Instruction {
                     continue;
                 }
 
-                stream.push(", ", CONFIG.colors.asm.expr);
+                stream.push_separator(CONFIG.colors.asm.expr);
 
                 let op = Operand::from_spec(self, self.operands[idx as usize]);
                 if op.is_memory() {
@@ -2425,7 +2413,7 @@ impl ToTokens for Instruction {
                     );
                 }
                 if let Some(prefix) = self.segment_override_for_op(idx) {
-                    stream.push_owned(prefix.to_string(), CONFIG.colors.asm.segment);
+                    stream.push_fmt(format_args!("{prefix}"), CONFIG.colors.asm.segment);
                     stream.push(":", CONFIG.colors.asm.expr);
                 }
 
@@ -2492,11 +2480,23 @@ impl ToTokens for Instruction {
 
                         stream.push("{", CONFIG.colors.brackets);
                         stream.push("1to", CONFIG.colors.asm.expr);
-                        stream.push_owned(scale.to_string(), CONFIG.colors.asm.immediate);
+                        stream.push_fmt(format_args!("{scale}"), CONFIG.colors.asm.immediate);
                         stream.push("}", CONFIG.colors.brackets);
                     }
                 }
             }
         }
+
+        // only branch targets and ip-relative operands are addresses, other immediates that
+        // happen to fall within a symbol are left as is
+        let target = match self.xref() {
+            Some((addr, XrefKind::Call | XrefKind::Jump)) => Some(addr),
+            Some((addr, XrefKind::Data)) if self.regs[1] == RegSpec::EIP => Some(addr),
+            _ => None,
+        };
+
+        if let Some(addr) = target {
+            symbols.tokenize_offset(stream, addr);
+        }
     }
 }
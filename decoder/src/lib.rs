@@ -2,7 +2,10 @@
 
 use std::fmt::Debug;
 use debugvault::Index;
-use tokenizing::{TokenStream, Token};
+use tokenizing::{FormatterConfig, TokenStream, Token};
+
+#[cfg(feature = "testing")]
+pub mod testing;
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub struct Error {
@@ -54,6 +57,38 @@ pub enum ErrorKind {
     Undefined,
     /// the input encodes an instruction with unpredictable behavior.
     Unpredictable,
+    /// Address lies outside of the bytes being decoded.
+    OutOfBounds,
+}
+
+/// How an instruction refers to an address.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum XrefKind {
+    /// Calls a function at the address.
+    Call,
+    /// Jumps or branches to the address.
+    Jump,
+    /// Reads from, writes to or takes the address of data.
+    Data,
+}
+
+/// What an instruction does, as far as control flow and memory goes.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum InstKind {
+    /// Jumps or branches somewhere else, possibly only if a condition holds.
+    Branch,
+    /// Calls a function, execution continues at the next instruction once it returns.
+    Call,
+    /// Returns from a function or a trap handler.
+    Return,
+    /// Traps into the environment, like a system call or a breakpoint.
+    Trap,
+    /// Reads from memory.
+    Load,
+    /// Writes to memory, including atomic read-modify-writes.
+    Store,
+    /// Anything else.
+    Other,
 }
 
 pub trait ToTokens {
@@ -62,12 +97,34 @@ pub trait ToTokens {
 
 pub trait Decoded: ToTokens {
     fn width(&self) -> usize;
-    fn tokens(&self, symbols: &Index) -> Vec<Token> {
-        let mut stream = TokenStream::new();
+    fn tokens(&self, symbols: &Index, config: FormatterConfig) -> Vec<Token> {
+        let mut stream = TokenStream::with_config(config);
         self.tokenize(&mut stream, symbols);
         stream.inner
     }
     fn update_rel_addrs(&mut self, addr: usize, prev_inst: Option<&Self>);
+
+    /// Address the instruction refers to, only known after calling [`Decoded::update_rel_addrs`].
+    fn xref(&self) -> Option<(usize, XrefKind)> {
+        None
+    }
+
+    /// Whether execution never continues at the next instruction, e.g. returns and unconditional
+    /// jumps.
+    fn is_terminator(&self) -> bool {
+        false
+    }
+
+    /// What the instruction does. Where a branch or call goes to, if it's known, is given by
+    /// [`Decoded::xref`].
+    fn kind(&self) -> InstKind {
+        InstKind::Other
+    }
+
+    /// Whether the instruction only branches if some condition holds.
+    fn is_conditional(&self) -> bool {
+        false
+    }
 }
 
 pub trait Decodable {
@@ -75,6 +132,18 @@ pub trait Decodable {
 
     fn decode(&self, reader: &mut Reader) -> Result<Self::Instruction, Error>;
     fn max_width(&self) -> usize;
+
+    /// Every instruction starts at a multiple of this many bytes. This is also the least number
+    /// of bytes skipped over after an error.
+    fn alignment(&self) -> usize {
+        1
+    }
+
+    /// What to show in place of `bytes` that failed to decode, e.g. a `.byte` directive spanning
+    /// them. By default the error is passed on.
+    fn unknown(&self, _bytes: &[u8]) -> Option<Self::Instruction> {
+        None
+    }
 }
 
 pub struct Reader<'data> {
@@ -149,6 +218,13 @@ impl<'data> Reader<'data> {
         self.position as usize - self.mark as usize
     }
 
+    /// the bytes read since the last `mark`.
+    #[inline]
+    pub fn marked(&self) -> &'data [u8] {
+        let len = self.position as usize - self.mark as usize;
+        unsafe { core::slice::from_raw_parts(self.mark, len) }
+    }
+
     /// the difference, between the current [`Reader`] position and the initial offset
     /// when constructed.
     #[inline]
@@ -157,6 +233,97 @@ impl<'data> Reader<'data> {
     }
 }
 
+/// Decodes instructions from the bytes of a section, keeping track of their addresses.
+pub struct InstructionStream<'data, D: Decodable> {
+    decoder: D,
+    bytes: &'data [u8],
+    reader: Reader<'data>,
+
+    /// Address of the first byte.
+    start: usize,
+
+    /// Address of the next instruction.
+    ip: usize,
+}
+
+impl<'data, D: Decodable> InstructionStream<'data, D> {
+    /// Stream over `bytes`, where the first byte is at address `start`.
+    pub fn new(bytes: &'data [u8], start: usize, decoder: D) -> Self {
+        Self {
+            decoder,
+            bytes,
+            reader: Reader::new(bytes),
+            start,
+            ip: start,
+        }
+    }
+
+    /// Address of the next instruction.
+    #[inline]
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// Address right after the last byte.
+    #[inline]
+    pub fn end(&self) -> usize {
+        self.start + self.bytes.len()
+    }
+
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.reader.as_ptr()
+    }
+
+    /// For decoders that have to switch modes, like ARM with thumb.
+    #[inline]
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Continue decoding at `addr`, rounded down to the alignment of instructions.
+    /// Seeking to the end is allowed, after which the stream is exhausted.
+    pub fn seek(&mut self, addr: usize) -> Result<(), Error> {
+        let addr = addr - addr % self.decoder.alignment();
+        if addr < self.start || addr > self.end() {
+            return Err(Error::new(ErrorKind::OutOfBounds, 0));
+        }
+
+        self.reader = Reader::new(&self.bytes[addr - self.start..]);
+        self.ip = addr;
+        Ok(())
+    }
+}
+
+impl<D: Decodable> Iterator for InstructionStream<'_, D> {
+    /// Address of an instruction, or of the bytes that failed to decode.
+    type Item = (usize, Result<D::Instruction, Error>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ip = self.ip;
+        match self.decoder.decode(&mut self.reader) {
+            Ok(instruction) => {
+                self.ip += instruction.width();
+                Some((ip, Ok(instruction)))
+            }
+            Err(error) if error.kind == ErrorKind::ExhaustedInput => None,
+            Err(error) => {
+                // skip exactly the width of the invalid encoding, regardless of how many bytes the
+                // decoder read before failing
+                let width = error.size().max(self.decoder.alignment());
+                let offset = ip - self.start;
+                let bytes = &self.bytes[offset..(offset + width).min(self.bytes.len())];
+                self.ip += width;
+                self.reader = Reader::new(self.bytes.get(self.ip - self.start..).unwrap_or(&[]));
+                match self.decoder.unknown(bytes) {
+                    Some(instruction) => Some((ip, Ok(instruction))),
+                    None => Some((ip, Err(Error::new(error.kind, width)))),
+                }
+            }
+        }
+    }
+}
+
 const HEX_NUGGET: [u8; 16] = *b"0123456789abcdef";
 
 #[inline]
@@ -0,0 +1,96 @@
+//! Helpers shared between the tests of decoder crates.
+
+use crate::{Decodable, Decoded, ErrorKind, InstructionStream, Reader};
+use debugvault::Index;
+use tokenizing::TokenStream;
+
+/// Decodes a single instruction that spans all of `bytes`.
+#[track_caller]
+pub fn decode<D: Decodable>(decoder: &D, bytes: &[u8]) -> String {
+    let mut reader = Reader::new(bytes);
+    let mut line = TokenStream::new();
+    let inst = match decoder.decode(&mut reader) {
+        Ok(inst) => inst,
+        Err(err) => panic!("failed to decode {bytes:02x?}: {:?}", err.kind),
+    };
+
+    assert_eq!(inst.width(), bytes.len(), "instruction doesn't span all bytes");
+    inst.tokenize(&mut line, &Index::default());
+    line.to_string()
+}
+
+/// Decodes a single instruction that must fail, returning why.
+#[track_caller]
+pub fn decode_error<D: Decodable>(decoder: &D, bytes: &[u8]) -> ErrorKind {
+    let mut reader = Reader::new(bytes);
+    match decoder.decode(&mut reader) {
+        Ok(inst) => {
+            let mut line = TokenStream::new();
+            inst.tokenize(&mut line, &Index::default());
+            panic!("{bytes:02x?} decoded as `{line}`");
+        }
+        Err(err) => err.kind,
+    }
+}
+
+/// Decodes a sequence of instructions, resolving addresses relative to `start`. Errors are
+/// rendered the way the listing shows them, e.g. `<InvalidOpcode>`.
+pub fn decode_instructions<D: Decodable>(
+    decoder: D,
+    bytes: &[u8],
+    start: usize,
+    symbols: &Index,
+) -> Vec<String> {
+    let mut decoded = Vec::new();
+    let mut prev = None;
+
+    for (ip, inst) in InstructionStream::new(bytes, start, decoder) {
+        let mut inst = match inst {
+            Ok(inst) => inst,
+            Err(err) => {
+                decoded.push(format!("<{:?}>", err.kind));
+                prev = None;
+                continue;
+            }
+        };
+
+        let mut line = TokenStream::new();
+        inst.update_rel_addrs(ip, prev.as_ref());
+        inst.tokenize(&mut line, symbols);
+        decoded.push(line.to_string());
+        prev = Some(inst);
+    }
+
+    decoded
+}
+
+/// Decoders of 4 byte instructions that come in both byte orders, like MIPS and PowerPC.
+pub trait Endianness: Decodable + Sized {
+    fn new(is_64: bool, big_endian: bool) -> Self;
+
+    /// Decodes `dword` as both a big and little endian 64-bit instruction.
+    #[track_caller]
+    fn test_display(dword: u32, expected: &str) {
+        assert_eq!(decode(&Self::new(true, true), &dword.to_be_bytes()), expected);
+        assert_eq!(decode(&Self::new(true, false), &dword.to_le_bytes()), expected);
+    }
+
+    /// Decodes `dword` as a big endian 32-bit instruction.
+    #[track_caller]
+    fn test_display_32(dword: u32, expected: &str) {
+        assert_eq!(decode(&Self::new(false, true), &dword.to_be_bytes()), expected);
+    }
+
+    /// Decodes `dword` as both a big and little endian 64-bit instruction, which must fail.
+    #[track_caller]
+    fn test_error(dword: u32, kind: ErrorKind) {
+        assert_eq!(decode_error(&Self::new(true, true), &dword.to_be_bytes()), kind);
+        assert_eq!(decode_error(&Self::new(true, false), &dword.to_le_bytes()), kind);
+    }
+
+    /// Decodes `dword` as a big endian 32-bit instruction, which must fail.
+    #[track_caller]
+    fn test_error_32(dword: u32, kind: ErrorKind) {
+        assert_eq!(decode_error(&Self::new(false, true), &dword.to_be_bytes()), kind);
+    }
+}
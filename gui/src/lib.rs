@@ -58,6 +58,8 @@ pub enum UIEvent {
     BinaryFailed(processor::Error),
     BinaryLoaded(processor::Processor),
     GotoAddr(usize),
+    /// Show the source line an address is compiled from.
+    GotoSource(usize),
 }
 
 #[derive(Clone)]
@@ -150,8 +152,9 @@ impl UI {
         self.panels.start_loading();
         let ui_queue = self.ui_queue.clone();
 
+        let options = commands::ARGS.parse_options();
         std::thread::spawn(move || {
-            match processor::Processor::parse(&path) {
+            match processor::Processor::parse_with(&path, options) {
                 Ok(diss) => ui_queue.push(UIEvent::BinaryLoaded(diss)),
                 Err(err) => ui_queue.push(UIEvent::BinaryFailed(err)),
             };
@@ -205,6 +208,10 @@ impl UI {
                         self.panels.goto_window(panes::DISASSEMBLY);
                     }
                 }
+                UIEvent::GotoSource(addr) => {
+                    self.panels.load_src(addr);
+                    self.panels.goto_window(panes::SOURCE);
+                }
             }
         }
     }
@@ -30,10 +30,11 @@ impl Functions {
     }
 }
 
-fn tokenize_functions(index: &debugvault::Index, range: std::ops::Range<usize>) -> Vec<(usize, Vec<Token>)> {
+fn tokenize_functions(processor: &Processor, range: std::ops::Range<usize>) -> Vec<(usize, Vec<Token>)> {
     let mut functions = Vec::new();
     let lines_to_read = range.end - range.start;
-    let lines = index
+    let lines = processor
+        .index
         .functions()
         .filter(|func| !func.item.intrinsic())
         .skip(range.start)
@@ -44,6 +45,20 @@ fn tokenize_functions(index: &debugvault::Index, range: std::ops::Range<usize>)
         tokens.push(Token::from_string(format!("{addr:0>10X}"), colors::WHITE));
         tokens.push(Token::from_str(" | ", colors::WHITE));
 
+        // functions without a known size are marked, as they're assumed to end at the next one
+        match processor.function_range(*addr) {
+            Some(func) => {
+                let size = format!("{:>8X}", func.end - func.start);
+                tokens.push(Token::from_string(size, colors::WHITE));
+                match func.guessed {
+                    true => tokens.push(Token::from_str("?", CONFIG.colors.comment)),
+                    false => tokens.push(Token::from_str(" ", colors::WHITE)),
+                }
+            }
+            None => tokens.push(Token::from_str("         ", colors::WHITE)),
+        }
+        tokens.push(Token::from_str(" | ", colors::WHITE));
+
         if let Some(module) = item.module() {
             tokens.push(Token::from_string(module.to_string(), CONFIG.colors.asm.component));
             tokens.push(Token::from_str("!", CONFIG.colors.delimiter));
@@ -65,7 +80,7 @@ impl Display for Functions {
 
         area.show_rows(ui, FONT.size, self.lines_count, |ui, row_range| {
             if row_range != (self.min_row..self.max_row) {
-                self.lines = tokenize_functions(&self.processor.index, row_range.clone());
+                self.lines = tokenize_functions(&self.processor, row_range.clone());
                 self.lines_count = self.processor.index.named_funcs_count();
                 self.min_row = row_range.start;
                 self.max_row = row_range.end;
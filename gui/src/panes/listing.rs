@@ -4,7 +4,7 @@ use debugvault::Index;
 use egui::mutex::RwLock;
 use egui::Color32;
 use infinite_scroll::{Callback, InfiniteScroll};
-use processor::{Block, BlockContent, Processor};
+use processor::{Block, BlockContent, ListingFormatter, Processor};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokenizing::{colors, Token, TokenStream};
@@ -15,6 +15,7 @@ pub struct Listing {
     ui_queue: Arc<UiQueue>,
     boundaries: Arc<RwLock<Vec<usize>>>,
     scroll: InfiniteScroll<Block, usize>,
+    listing: ListingFormatter,
     reset_position: Arc<AtomicUsize>,
     current_addr: usize,
     jump_list: Vec<usize>,
@@ -128,9 +129,14 @@ impl Listing {
 
         let scroll = InfiniteScroll::new().start_loader(start_loader).end_loader(end_loader);
         let current_addr = processor.sections().next().unwrap().start;
+        let mut listing = processor.listing_formatter();
+        listing.show_bytes = !commands::ARGS.hide_bytes;
+        listing.show_source = true;
+        listing.show_source_text = true;
 
         Self {
             scroll,
+            listing,
             ui_queue,
             boundaries,
             processor,
@@ -140,15 +146,17 @@ impl Listing {
         }
     }
 
+    /// Jump to the block containing `addr`, which doesn't have to be the start of it.
     pub fn jump(&mut self, addr: usize) -> bool {
-        if let Ok(boundary) = self.boundaries.read().binary_search(&addr) {
-            self.jump_list.push(self.current_addr);
-            self.reset_position.store(boundary, Ordering::SeqCst);
-            self.scroll.reset();
-            return true;
-        }
+        let boundary = match self.processor.block_containing(&self.boundaries.read(), addr) {
+            Some(boundary) => boundary,
+            None => return false,
+        };
 
-        false
+        self.jump_list.push(self.current_addr);
+        self.reset_position.store(boundary, Ordering::SeqCst);
+        self.scroll.reset();
+        true
     }
 
     pub fn record_input(&mut self, events: &mut Vec<egui::Event>) {
@@ -212,7 +220,7 @@ fn draw_instruction(ui: &mut egui::Ui, tokens: Vec<Token>, index: &Index, ui_que
         ui.label(tokens_to_layoutjob(a));
         if ui.link(label).clicked() {
             let label_without_arrows = &label_text[1..][..label_text.len() - 2];
-            if let Some(addr) = index.get_func_by_name(label_without_arrows) {
+            if let Some(addr) = index.get_addr_by_label(label_without_arrows) {
                 ui_queue.push(UIEvent::GotoAddr(addr));
             }
         }
@@ -243,10 +251,10 @@ impl Display for Listing {
                 }
 
                 let mut stream = TokenStream::new();
-                block.tokenize(&mut stream);
+                block.tokenize(&mut stream, &self.listing);
 
                 match block.content {
-                    BlockContent::Instruction { .. } => {
+                    BlockContent::Instruction { .. } | BlockContent::Word { .. } => {
                         draw_instruction(ui, stream.inner, &self.processor.index, &self.ui_queue);
                     }
                     BlockContent::Label { .. } => {
@@ -254,6 +262,11 @@ impl Display for Listing {
                             self.ui_queue.push(UIEvent::GotoAddr(block.addr));
                         }
                     }
+                    BlockContent::Source { .. } => {
+                        if ui.link(tokens_to_layoutjob(stream.inner)).clicked() {
+                            self.ui_queue.push(UIEvent::GotoSource(block.addr));
+                        }
+                    }
                     _ => {
                         ui.label(tokens_to_layoutjob(stream.inner));
                     }
@@ -190,12 +190,22 @@ impl Panels {
 
     /// Jump to both the source and the assembly.
     pub fn load_src(&mut self, addr: usize) {
-        let file_attr = match self.processor().and_then(|proc| proc.index.get_file_by_addr(addr)) {
+        let processor = match self.processor() {
+            Some(processor) => Arc::clone(processor),
+            None => return,
+        };
+
+        let file_attr = match processor.index.get_file_by_addr(addr) {
             Some(file_attr) => file_attr,
             None => return,
         };
 
-        if let Ok(src) = std::fs::read_to_string(&file_attr.path) {
+        // relative paths are usually relative to where the object was built
+        let dir = processor.path.parent().unwrap_or(std::path::Path::new("."));
+        let src = std::fs::read_to_string(&file_attr.path)
+            .or_else(|_| std::fs::read_to_string(dir.join(&file_attr.path)));
+
+        if let Ok(src) = src {
             let src = source_code::Source::new(&src, file_attr);
             self.panes.mapping.insert(SOURCE, PanelKind::Source(src));
         }
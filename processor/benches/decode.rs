@@ -0,0 +1,55 @@
+//! Compares decoding on a single thread with decoding on every core.
+//!
+//! Run with `cargo bench -p processor`, optionally with `BITE_BENCH_PATH` set to a binary with a
+//! large text section.
+
+use processor::{ParseOptions, Processor};
+use processor_shared::SectionKind;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 5;
+
+/// Fastest of a couple of runs, along with the last parse.
+fn parse(path: &Path, threads: Option<usize>) -> (Processor, Duration) {
+    let mut fastest = Duration::MAX;
+    let mut processor = None;
+
+    for _ in 0..RUNS {
+        let options = ParseOptions {
+            threads,
+            ..ParseOptions::default()
+        };
+
+        let now = Instant::now();
+        processor = Some(Processor::parse_with(path, options).unwrap());
+        fastest = fastest.min(now.elapsed());
+    }
+
+    (processor.unwrap(), fastest)
+}
+
+fn main() {
+    let path = match std::env::var_os("BITE_BENCH_PATH") {
+        Some(path) => path.into(),
+        None => std::env::current_exe().unwrap(),
+    };
+
+    let (serial, serial_time) = parse(&path, Some(1));
+    let (parallel, parallel_time) = parse(&path, None);
+
+    let code_size: usize = serial
+        .sections()
+        .filter(|s| s.kind == SectionKind::Code)
+        .map(|s| s.bytes().len())
+        .sum();
+
+    println!("{path:?}: {} KiB of code", code_size / 1024);
+    println!("serial:   {serial_time:?}");
+    println!("parallel: {parallel_time:?}");
+
+    // both have to agree on where instructions start
+    let serial = serial.instructions().map(|(addr, _)| addr);
+    let parallel = parallel.instructions().map(|(addr, _)| addr);
+    assert!(serial.eq(parallel), "serial and parallel decoding disagree");
+}
@@ -0,0 +1,43 @@
+// Fixture for attributing addresses to symbols, an unlinked object with the symbols of
+// hand-written assembly, built with:
+//   llvm-mc -triple=aarch64 -filetype=obj attribution.s -o attribution-aarch64.o
+
+    .text
+
+    // symbols at address zero aren't indexed
+    nop
+
+    // hand-written function without a size
+    .globl unsized
+unsized:
+    mov x3, x0
+    ret
+
+    // function with a literal pool, which is marked by a `$d` mapping symbol and followed by `$x`
+    .globl outer
+    .type outer, %function
+outer:
+    nop
+    nop
+
+    // nested in `outer`
+    .type inner, %function
+inner:
+    nop
+
+    // starts within `inner` and spans the literal pool
+    .type spill, %function
+spill:
+    nop
+    nop
+    ret
+    .word 0x12345678
+    ret
+    .size spill, 0x14
+    .size inner, 0xc
+    .size outer, . - outer
+
+    // last function without a size, spanning up to the end of the section
+    .globl tail
+tail:
+    ret
@@ -0,0 +1,62 @@
+; Fixture for the eBPF relocation tests, built with:
+;   llc -opaque-pointers -O2 -mtriple=bpfel -filetype=obj bpf.ll -o bpf.o
+;
+; from:
+;
+;   struct {
+;       int (*type)[BPF_MAP_TYPE_ARRAY];
+;       int (*max_entries)[1];
+;       __u32 *key;
+;       __u64 *value;
+;   } counts SEC(".maps");
+;
+;   static __noinline int bump(__u64 *value) {
+;       __sync_fetch_and_add(value, 1);
+;       return *value > 100;
+;   }
+;
+;   SEC("xdp")
+;   int count(struct xdp_md *ctx) {
+;       __u32 key = 0;
+;       __u64 *value = bpf_map_lookup_elem(&counts, &key);
+;       if (!value)
+;           return XDP_PASS;
+;       return bump(value) ? XDP_DROP : XDP_PASS;
+;   }
+
+%struct.anon = type { ptr, ptr, ptr, ptr }
+
+@counts = dso_local global %struct.anon zeroinitializer, section ".maps", align 8
+@_license = dso_local global [4 x i8] c"GPL\00", section "license", align 1
+@llvm.compiler.used = appending global [3 x ptr] [ptr @count, ptr @counts, ptr @_license], section "llvm.metadata"
+
+define dso_local i32 @count(ptr nocapture readnone %ctx) #0 section "xdp" {
+entry:
+  %key = alloca i32, align 4
+  store i32 0, ptr %key, align 4
+  %lookup = call ptr inttoptr (i64 1 to ptr)(ptr @counts, ptr %key)
+  %null = icmp eq ptr %lookup, null
+  br i1 %null, label %exit, label %found
+
+found:
+  %bumped = call i32 @bump(ptr %lookup)
+  %over = icmp ne i32 %bumped, 0
+  %verdict = select i1 %over, i32 1, i32 2
+  br label %exit
+
+exit:
+  %ret = phi i32 [ 2, %entry ], [ %verdict, %found ]
+  ret i32 %ret
+}
+
+define internal i32 @bump(ptr %value) #1 section "xdp" {
+entry:
+  %old = atomicrmw add ptr %value, i64 1 seq_cst
+  %new = load i64, ptr %value, align 8
+  %over = icmp ugt i64 %new, 100
+  %ret = zext i1 %over to i32
+  ret i32 %ret
+}
+
+attributes #0 = { nounwind }
+attributes #1 = { noinline nounwind }
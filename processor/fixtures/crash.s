@@ -0,0 +1,20 @@
+# Fixture for reading core dumps, an executable that crashes writing to address zero, built
+# and run with:
+#   gcc -nostdlib -static -no-pie -Wl,--build-id=none crash.s -o crash
+#   ulimit -c unlimited; env -i ./crash; mv core crash.core
+# with an empty environment so the dumped stack doesn't carry it.
+    .globl _start
+    .type _start, @function
+_start:
+    mov $0x1234, %rax
+    call fault
+    ud2
+    .size _start, .-_start
+
+    .globl fault
+    .type fault, @function
+fault:
+    mov $0x2a, %rbx
+    movq $0, 0
+    ret
+    .size fault, .-fault
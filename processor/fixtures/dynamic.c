@@ -0,0 +1,13 @@
+// Fixture for the dependency tests, a dynamically linked executable built with:
+//   gcc -Os -Wl,--build-id=none -Wl,-rpath,'$ORIGIN/lib' dynamic.c -o dynamic && strip dynamic
+// and a stripped shared library that only has its exports left in `.dynsym`:
+//   gcc -Os -shared -fPIC -Wl,--build-id=none dynamic.c -o libdynamic.so && strip libdynamic.so
+// and a stripped executable with a `.plt.sec` section, as built for Intel CET:
+//   gcc -Os -fcf-protection -Wl,--build-id=none -Wl,-z,ibtplt dynamic.c -o dynamic-ibt
+//   strip dynamic-ibt
+
+#include <stdio.h>
+
+int main(void) {
+    return puts("hi");
+}
@@ -0,0 +1,29 @@
+# Fixture for the identity of an object, a stripped executable with a build-id and the build
+# info of a Go binary, built with:
+#   gcc -nostdlib -static -no-pie -Wl,--build-id=0x0123456789abcdef0123456789abcdef01234567 \
+#       identity.s -o identity
+#   strip --remove-section=.note.gnu.property identity
+
+    .intel_syntax noprefix
+    .text
+
+    .globl _start
+    .type _start, @function
+_start:
+    xor edi, edi
+    mov eax, 60
+    syscall
+    .size _start, . - _start
+
+    # header of Go 1.18 and later, followed by the version and module info inline
+    .section .go.buildinfo, "aw"
+    .ascii "\377 Go buildinf:"
+    .byte 8, 2
+    .zero 16
+    .byte 8
+    .ascii "go1.22.1"
+    .byte 86
+    .byte 0x30, 0x77, 0xaf, 0x0c, 0x92, 0x74, 0x08, 0x02, 0x41, 0xe1, 0xc1, 0x07, 0xe6, 0xd6, 0x18, 0xe6
+    .ascii "path\texample.com/hello\n"
+    .ascii "mod\texample.com/hello\t(devel)\t\n"
+    .byte 0xf9, 0x32, 0x43, 0x31, 0x86, 0x18, 0x20, 0x72, 0x00, 0x82, 0x42, 0x10, 0x41, 0x16, 0xd8, 0xf2
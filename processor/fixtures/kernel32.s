@@ -0,0 +1,8 @@
+# Stand-in for KERNEL32.dll that `tiny-pe.s` links against, see `tiny-pe.s`.
+
+    .intel_syntax noprefix
+    .text
+
+    .globl ExitProcess
+ExitProcess:
+    ret
@@ -0,0 +1,21 @@
+// Fixture for interleaving source with the disassembly, built with:
+//   gcc -O1 -g -nostdlib -static -no-pie -fdebug-prefix-map=$PWD=. -Wl,--build-id=none \
+//       lines.c -o lines
+
+__attribute__((noinline)) int square(int x) {
+    return x * x;
+}
+
+void _start(void) {
+    int result = square(7);
+    __asm__ volatile("mov %0, %%edi; mov $60, %%eax; syscall" ::"r"(result));
+}
+
+// The line table refers to the lines above, so how the other fixtures are derived from `lines`
+// is noted down here. Stripped, with its debug info kept in a separate file, with:
+//   objcopy --only-keep-debug lines lines.debug
+//   strip --strip-all lines -o lines-stripped
+//   objcopy --add-gnu-debuglink=lines.debug lines-stripped
+// and with its debug info compressed, with:
+//   objcopy --compress-debug-sections=zlib lines lines-zlib
+//   objcopy --compress-debug-sections=zstd lines lines-zstd
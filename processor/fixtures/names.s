@@ -0,0 +1,22 @@
+# Fixture for filtering names, built with:
+#   as names.s -o names.o
+
+    .text
+
+    .globl _ZN3foo3barEv
+    .type _ZN3foo3barEv, @function
+_ZN3foo3barEv:
+    ret
+    .size _ZN3foo3barEv, . - _ZN3foo3barEv
+
+    .globl _ZN3foo3bazEi
+    .type _ZN3foo3bazEi, @function
+_ZN3foo3bazEi:
+    ret
+    .size _ZN3foo3bazEi, . - _ZN3foo3bazEi
+
+    .globl bar
+    .type bar, @function
+bar:
+    ret
+    .size bar, . - bar
@@ -0,0 +1,51 @@
+# Fixture for decoding notes, an unlinked object with the notes of a hardened binary and one of
+# a type that isn't known, built with:
+#   as notes.s -o notes.o
+
+    .text
+    .globl _start
+_start:
+    endbr64
+    ret
+
+    # control-flow protection and the ISA level the code needs
+    .section .note.gnu.property, "a", @note
+    .balign 8
+    .long 4
+    .long 2f - 1f
+    .long 5 # NT_GNU_PROPERTY_TYPE_0
+    .asciz "GNU"
+1:
+    .balign 8
+    .long 0xc0000002 # GNU_PROPERTY_X86_FEATURE_1_AND
+    .long 4
+    .long 3 # IBT, SHSTK
+    .balign 8
+    .long 0xc0008002 # GNU_PROPERTY_X86_ISA_1_NEEDED
+    .long 4
+    .long 1 # x86-64-baseline
+    .balign 8
+2:
+
+    .section .note.gnu.gold-version, "a", @note
+    .balign 4
+    .long 4
+    .long 2f - 1f
+    .long 4 # NT_GNU_GOLD_VERSION
+    .asciz "GNU"
+1:
+    .asciz "gold 1.16"
+2:
+    .balign 4
+
+    .section .note.bite, "a", @note
+    .balign 4
+    .long 5
+    .long 2f - 1f
+    .long 0x1234
+    .asciz "bite"
+    .balign 4
+1:
+    .byte 0xde, 0xad, 0xbe, 0xef, 0x01, 0x02
+2:
+    .balign 4
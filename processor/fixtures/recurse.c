@@ -0,0 +1,28 @@
+// Fixture for backtraces of core dumps, an executable that crashes a few calls deep, built
+// with frame pointers and run with:
+//   gcc -O0 -g -fno-omit-frame-pointer -nostdlib -static -no-pie -fdebug-prefix-map=$PWD=. \
+//       -Wl,--build-id=none recurse.c -o recurse
+//   ulimit -c unlimited; env -i ./recurse; mv core recurse.core
+// and as a position-independent executable without frame pointers, run the same way, with:
+//   gcc -O1 -g -fomit-frame-pointer -fpie -nostdlib -static-pie -fdebug-prefix-map=$PWD=. \
+//       -Wl,--build-id=none recurse.c -o recurse-nofp
+// Both run with an empty environment so the dumped stack doesn't carry it.
+
+__attribute__((noinline)) void crash(int depth) {
+    *(volatile int *)0x10 = depth;
+}
+
+__attribute__((noinline)) void recurse(int depth) {
+    if (depth == 0)
+        crash(depth);
+    else
+        recurse(depth - 1);
+    __asm__ volatile("" ::: "memory");
+}
+
+void _start(void) {
+    // like the entry point of the C runtime, it doesn't have a caller to unwind to
+    __asm__(".cfi_undefined rip");
+    recurse(2);
+    __asm__ volatile("mov $60, %eax; syscall");
+}
@@ -0,0 +1,24 @@
+# Fixture for the relocation tests, an unlinked object built with:
+#   llvm-mc -triple=riscv64 -mattr=+c -filetype=obj relocs-riscv64.s -o relocs-riscv64.o
+
+    .text
+
+    .globl caller
+    .type caller, @function
+caller:
+    addi sp, sp, -16
+    sd ra, 8(sp)
+    call foo
+    la a0, table
+    bnez a0, fail
+    jal helper
+    beqz a0, .Ldone
+    jal local
+.Ldone:
+    ld ra, 8(sp)
+    addi sp, sp, 16
+    tail bar
+
+    .type local, @function
+local:
+    ret
@@ -0,0 +1,18 @@
+# Fixture for the relocation tests, an unlinked object with a section per function, built with:
+#   llvm-mc -triple=x86_64-unknown-linux-gnu -filetype=obj relocs-sections.s -o relocs-sections.o
+
+    .intel_syntax noprefix
+
+    .section .text.first,"ax",@progbits
+    .globl first
+    .type first, @function
+first:
+    call foo
+    ret
+
+    .section .text.second,"ax",@progbits
+    .globl second
+    .type second, @function
+second:
+    call bar
+    ret
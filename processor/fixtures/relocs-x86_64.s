@@ -0,0 +1,22 @@
+# Fixture for the relocation tests, an unlinked object built with:
+#   llvm-mc -triple=x86_64-unknown-linux-gnu -filetype=obj relocs-x86_64.s -o relocs-x86_64.o
+
+    .intel_syntax noprefix
+    .text
+
+    .globl caller
+    .type caller, @function
+caller:
+    call foo
+    lea rax, [rip + table + 8]
+    mov eax, dword ptr [rip + counter]
+    mov rcx, qword ptr [rip + errno@GOTPCREL]
+    test eax, eax
+    jne .Ldone
+    call local
+.Ldone:
+    jmp bar
+
+    .type local, @function
+local:
+    ret
@@ -0,0 +1,26 @@
+# Fixture for the string tests, a static x86_64 executable built with:
+#   as strings.s -o strings.o && ld --build-id=none strings.o -o strings
+
+    .intel_syntax noprefix
+    .text
+
+    .globl _start
+    .type _start, @function
+_start:
+    lea rdi, [rip + .Lerror]
+    lea rsi, [rip + greeting + 7]
+    ret
+    .size _start, . - _start
+
+    .section .rodata
+    .type greeting, @object
+greeting:
+    .asciz "hello, world"
+    .size greeting, . - greeting
+    .byte 1, 2, 3
+    .asciz "abc"
+.Lerror:
+    .asciz "error: %s\n"
+
+    .data
+    .asciz "gr\xc3\xb6\xc3\x9f""e"
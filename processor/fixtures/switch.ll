@@ -0,0 +1,69 @@
+; Fixture for the inline data tests, built with:
+;   llc -O2 -mtriple=armv7-none-eabi -relocation-model=static -filetype=obj switch.ll -o switch.o
+;
+; from:
+;
+;   int dispatch(int op, int a, int b) {
+;       switch (op) {
+;       case 0: return a + b;
+;       case 1: return a - b;
+;       case 2: return a * b;
+;       case 3: return a & b;
+;       case 4: return a | b;
+;       case 5: return a ^ b;
+;       default: return 0;
+;       }
+;   }
+;
+;   int main(int argc, char **argv) { return dispatch(argc, 3, 4); }
+
+define dso_local i32 @dispatch(i32 %op, i32 %a, i32 %b) #0 {
+entry:
+  switch i32 %op, label %default [
+    i32 0, label %add
+    i32 1, label %sub
+    i32 2, label %mul
+    i32 3, label %and
+    i32 4, label %or
+    i32 5, label %xor
+  ]
+
+add:
+  %0 = add nsw i32 %a, %b
+  br label %ret
+
+sub:
+  %1 = sub nsw i32 %a, %b
+  br label %ret
+
+mul:
+  %2 = mul nsw i32 %a, %b
+  br label %ret
+
+and:
+  %3 = and i32 %a, %b
+  br label %ret
+
+or:
+  %4 = or i32 %a, %b
+  br label %ret
+
+xor:
+  %5 = xor i32 %a, %b
+  br label %ret
+
+default:
+  br label %ret
+
+ret:
+  %r = phi i32 [ %0, %add ], [ %1, %sub ], [ %2, %mul ], [ %3, %and ], [ %4, %or ], [ %5, %xor ], [ 0, %default ]
+  ret i32 %r
+}
+
+define dso_local i32 @main(i32 %argc, i8** %argv) #0 {
+entry:
+  %r = tail call i32 @dispatch(i32 %argc, i32 3, i32 4)
+  ret i32 %r
+}
+
+attributes #0 = { nounwind }
@@ -0,0 +1,21 @@
+# Fixture for naming the thunks of 32-bit x86 position independent code, built with:
+#   as --32 thunks.s -o thunks.o && ld -m elf_i386 --build-id=none thunks.o -o thunks
+#   strip thunks
+
+    .intel_syntax noprefix
+    .text
+
+    .globl _start
+_start:
+    call __x86.get_pc_thunk.bx
+    add ebx, 0x1000
+    call __x86.get_pc_thunk.ax
+    hlt
+
+__x86.get_pc_thunk.bx:
+    mov ebx, [esp]
+    ret
+
+__x86.get_pc_thunk.ax:
+    mov eax, [esp]
+    ret
@@ -0,0 +1,23 @@
+# Fixture for parsing PE executables, built with:
+#   as kernel32.s -o kernel32.o && objcopy -O pe-x86-64 kernel32.o kernel32.obj
+#   ld -m i386pep --shared --export-all-symbols --entry=0 kernel32.obj -o KERNEL32.dll
+#   as tiny-pe.s -o tiny-pe.o && objcopy -O pe-x86-64 tiny-pe.o tiny-pe.obj
+#   ld -m i386pep --entry=_start --export-all-symbols tiny-pe.obj KERNEL32.dll -o tiny.exe
+#   objcopy --strip-all --keep-symbol=add --keep-symbol=_start tiny.exe
+
+    .intel_syntax noprefix
+    .text
+
+    .globl add
+add:
+    lea eax, [rcx + rdx]
+    ret
+
+    .globl _start
+_start:
+    xor ecx, ecx
+    mov edx, ecx
+    call add
+    mov ecx, eax
+    call ExitProcess
+    hlt
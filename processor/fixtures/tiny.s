@@ -0,0 +1,31 @@
+# Fixture for the text listing golden test, built with:
+#   gcc -nostdlib -static -no-pie -Wl,--build-id=none tiny.s -o tiny
+#   objcopy --strip-symbol=_start --remove-section=.note.gnu.property tiny
+# and its code without any headers, for disassembling raw binaries:
+#   objcopy -O binary -j .text tiny tiny.bin
+# and a static archive of its unlinked object and the relocation fixture:
+#   as tiny.s -o tiny.o && ar rcs libtiny.a tiny.o relocs-x86_64.o
+
+    .intel_syntax noprefix
+    .text
+
+    # so `add` doesn't share an address with the section
+    nop
+
+    .globl add
+    .type add, @function
+add:
+    lea eax, [rdi + rsi]
+    ret
+    .size add, . - add
+
+    .globl _start
+    .type _start, @function
+_start:
+    xor edi, edi
+    mov esi, edi
+    call add
+    mov edi, eax
+    syscall
+    hlt
+    .size _start, . - _start
@@ -0,0 +1,43 @@
+;; Source of `tiny.wasm`, with a name section that names `print`, `add` and `count`.
+(module
+  (import "env" "print" (func $print (param i32)))
+  (memory (export "memory") 1)
+
+  (func $add (export "add") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add)
+
+  (func $count (param i32) (result i32) (local i32)
+    block
+      loop
+        local.get 1
+        local.get 0
+        i32.ge_u
+        br_if 1
+        local.get 1
+        i32.const 1
+        i32.add
+        local.set 1
+        br 0
+      end
+    end
+    local.get 1)
+
+  ;; only named by its export
+  (func (export "_start")
+    i32.const 0
+    i32.load offset=16
+    if (result i32)
+      i32.const 2
+    else
+      call 4
+    end
+    i32.const 7
+    call $add
+    call $count
+    call $print)
+
+  ;; not named at all
+  (func (result i32)
+    i32.const 42))
@@ -0,0 +1,33 @@
+# Fixture for thread-local storage, a static executable whose thread-local variables have
+# offsets that collide with the addresses of its code, built with:
+#   gcc -nostdlib -static -no-pie -Wl,--build-id=none \
+#       -Wl,--section-start=.text=0x10 tls.s -o tls
+
+    .intel_syntax noprefix
+    .text
+
+    .globl _start
+    .type _start, @function
+_start:
+    mov eax, dword ptr fs:counter@tpoff
+    mov dword ptr fs:buffer@tpoff, eax
+    xor edi, edi
+    mov eax, 60
+    syscall
+    .size _start, . - _start
+
+    .section .tdata, "awT", @progbits
+    .balign 4
+    .globl counter
+    .type counter, @tls_object
+counter:
+    .long 1
+    .size counter, 4
+
+    .section .tbss, "awT", @nobits
+    .balign 16
+    .globl buffer
+    .type buffer, @tls_object
+buffer:
+    .zero 16
+    .size buffer, 16
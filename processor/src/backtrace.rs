@@ -0,0 +1,536 @@
+use crate::source::display_path;
+use crate::Processor;
+use binformat::coredump::{CoreDump, Thread};
+use debugvault::{FileAttr, ResolvedSymbol};
+use gimli::{BaseAddresses, CfaRule, DebugFrame, EhFrame, EndianSlice, Register};
+use gimli::{RegisterRule, RunTimeEndian, UnwindContext, UnwindSection};
+use object::{Architecture, Endianness, Object, ObjectSection, ObjectSegment};
+use processor_shared::PhysAddr;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Frames a backtrace is cut off at, in case the chain of frames doesn't end.
+const MAX_FRAMES: usize = 256;
+
+/// Function call that was in progress when a thread was stopped.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// Address execution continues at in the frame, the return address for all but the first.
+    pub pc: PhysAddr,
+    /// Stack pointer of the frame, as it was after the frame's call returned.
+    pub sp: PhysAddr,
+    /// Function or section the pc is in.
+    pub symbol: Option<ResolvedSymbol>,
+    /// Source line of the call, or of the instruction that was stopped at in the first frame.
+    pub line: Option<FileAttr>,
+    /// Path of the mapped file the pc is in, if it's outside of the object.
+    pub file: Option<String>,
+}
+
+/// Values of a frame's registers by their DWARF register number.
+type Registers = HashMap<u16, u64>;
+
+/// Registers a core dump stores, by their DWARF register number.
+struct Abi {
+    registers: &'static [&'static str],
+    sp: u16,
+    fp: u16,
+}
+
+fn abi(arch: Architecture) -> Option<Abi> {
+    let abi = match arch {
+        Architecture::X86_64 => Abi {
+            registers: &[
+                "rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11",
+                "r12", "r13", "r14", "r15", "rip",
+            ],
+            sp: 7,
+            fp: 6,
+        },
+        Architecture::I386 => Abi {
+            registers: &["eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "eip"],
+            sp: 4,
+            fp: 5,
+        },
+        Architecture::Aarch64 => Abi {
+            registers: &[
+                "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12",
+                "x13", "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24",
+                "x25", "x26", "x27", "x28", "x29", "x30", "sp",
+            ],
+            sp: 31,
+            fp: 29,
+        },
+        _ => return None,
+    };
+
+    Some(abi)
+}
+
+/// What unwinding a frame found out about its caller.
+enum Step {
+    /// Registers of the caller, the address it continues at and whether the frame was a
+    /// signal handler's trampoline, which isn't entered through a call.
+    Caller { registers: Registers, pc: u64, signal: bool },
+    /// The frame is the outermost one, or its caller can't be recovered.
+    End,
+}
+
+/// Call frame information of an object mapped into a dumped process.
+struct Module {
+    /// Difference between the addresses the object is mapped at and the ones it's linked at.
+    bias: PhysAddr,
+    endian: RunTimeEndian,
+    address_size: u8,
+    /// Address and contents of `.eh_frame`.
+    eh_frame: Option<(u64, Vec<u8>)>,
+    debug_frame: Option<Vec<u8>>,
+    /// Address of `.text`, which pointers in `.eh_frame` may be relative to.
+    text: u64,
+}
+
+impl Module {
+    fn new<'s>(
+        bias: PhysAddr,
+        endian: RunTimeEndian,
+        address_size: u8,
+        sections: impl Iterator<Item = (&'s str, u64, Cow<'s, [u8]>)>,
+    ) -> Self {
+        let mut module = Self {
+            bias,
+            endian,
+            address_size,
+            eh_frame: None,
+            debug_frame: None,
+            text: 0,
+        };
+
+        for (name, addr, bytes) in sections {
+            match name {
+                ".eh_frame" => module.eh_frame = Some((addr, bytes.into_owned())),
+                ".debug_frame" => module.debug_frame = Some(bytes.into_owned()),
+                ".text" => module.text = addr,
+                _ => {}
+            }
+        }
+
+        module
+    }
+
+    /// Call frame information of the library at `path`, biased by where it's mapped in `core`.
+    fn load(core: &CoreDump, path: &str) -> Option<Self> {
+        let start = core.files.iter().find(|file| file.path == path && file.offset == 0)?.start;
+        let bytes = std::fs::read(path).ok()?;
+        let obj = object::File::parse(&bytes[..]).ok()?;
+
+        let image_base = obj.segments().map(|segment| segment.address()).min().unwrap_or(0);
+        let endian = match obj.is_little_endian() {
+            true => RunTimeEndian::Little,
+            false => RunTimeEndian::Big,
+        };
+
+        let sections = obj.sections().filter_map(|section| {
+            Some((section.name().ok()?, section.address(), section.uncompressed_data().ok()?))
+        });
+
+        let address_size = if obj.is_64() { 8 } else { 4 };
+        let bias = start.wrapping_sub(image_base as PhysAddr);
+        Some(Self::new(bias, endian, address_size, sections))
+    }
+
+    /// Unwind the frame executing `addr` with the unwind tables of `.eh_frame`, or those of
+    /// `.debug_frame`. Returns `None` if neither covers `addr`.
+    fn unwind(&self, core: &CoreDump, abi: &Abi, regs: &Registers, addr: u64) -> Option<Step> {
+        let bases = BaseAddresses::default().set_text(self.text);
+        if let Some((eh_frame_addr, bytes)) = &self.eh_frame {
+            let mut section = EhFrame::new(bytes, self.endian);
+            section.set_address_size(self.address_size);
+            let bases = bases.clone().set_eh_frame(*eh_frame_addr);
+            if let Some(step) = unwind_with(&section, &bases, core, abi, regs, addr) {
+                return Some(step);
+            }
+        }
+
+        let mut section = DebugFrame::new(self.debug_frame.as_ref()?, self.endian);
+        section.set_address_size(self.address_size);
+        unwind_with(&section, &bases, core, abi, regs, addr)
+    }
+}
+
+/// Recover the caller of the frame executing `addr` by the rules of the unwind table row
+/// that covers it.
+fn unwind_with<'a, S: UnwindSection<EndianSlice<'a, RunTimeEndian>>>(
+    section: &S,
+    bases: &BaseAddresses,
+    core: &CoreDump,
+    abi: &Abi,
+    regs: &Registers,
+    addr: u64,
+) -> Option<Step> {
+    let fde = section.fde_for_address(bases, addr, S::cie_from_offset).ok()?;
+    let mut ctx = UnwindContext::new();
+    let row = fde.unwind_info_for_address(section, bases, &mut ctx, addr).ok()?;
+
+    // frames whose CFA is computed by an expression are left to the frame pointers
+    let cfa = match *row.cfa() {
+        CfaRule::RegisterAndOffset { register, offset } => {
+            regs.get(&register.0)?.wrapping_add_signed(offset)
+        }
+        CfaRule::Expression(..) => return None,
+    };
+
+    let ra = fde.cie().return_address_register();
+    let mut caller = regs.clone();
+    for reg in (0..abi.registers.len() as u16).chain([ra.0]) {
+        let value = match row.register(Register(reg)) {
+            // a return address that isn't saved marks the outermost frame, like `_start`
+            RegisterRule::Undefined if reg == ra.0 => return Some(Step::End),
+            RegisterRule::Undefined | RegisterRule::SameValue => continue,
+            RegisterRule::Offset(offset) => {
+                match core.read_word(cfa.wrapping_add_signed(offset) as PhysAddr) {
+                    Ok(value) => value,
+                    Err(..) => return Some(Step::End),
+                }
+            }
+            RegisterRule::ValOffset(offset) => cfa.wrapping_add_signed(offset),
+            RegisterRule::Register(other) => match regs.get(&other.0) {
+                Some(&value) => value,
+                None => {
+                    caller.remove(&reg);
+                    continue;
+                }
+            },
+            _ => {
+                caller.remove(&reg);
+                continue;
+            }
+        };
+
+        caller.insert(reg, value);
+    }
+
+    // the caller's stack pointer is the CFA by definition
+    caller.insert(abi.sp, cfa);
+
+    match caller.get(&ra.0) {
+        Some(&pc) => Some(Step::Caller {
+            registers: caller,
+            pc,
+            signal: fde.cie().is_signal_trampoline(),
+        }),
+        None => Some(Step::End),
+    }
+}
+
+/// Recover the caller of a frame built with frame pointers, from the frame pointer and return
+/// address saved at the frame's base. Each hop has to stay on the thread's stack and move
+/// towards its base.
+fn unwind_frame_pointer(
+    core: &CoreDump,
+    abi: &Abi,
+    regs: &Registers,
+    sp: PhysAddr,
+    stack: &Range<PhysAddr>,
+) -> Option<(Registers, u64)> {
+    let word = core.word_size();
+    let fp = *regs.get(&abi.fp)? as PhysAddr;
+
+    // frames of callers are further up the stack than the ones they called
+    if fp < sp || !stack.contains(&fp) || !stack.contains(&(fp + word * 2 - 1)) {
+        return None;
+    }
+
+    let saved_fp = core.read_word(fp).ok()?;
+    let ret = core.read_word(fp + word).ok()?;
+
+    // the outermost frame doesn't have a caller
+    if saved_fp == 0 {
+        return None;
+    }
+
+    let mut caller = regs.clone();
+    caller.insert(abi.fp, saved_fp);
+    caller.insert(abi.sp, (fp + word * 2) as u64);
+    Some((caller, ret))
+}
+
+/// Objects mapped into a dumped process, the processed one and any libraries that a backtrace
+/// reaches, which are loaded from their paths when it does.
+struct Modules<'a> {
+    core: &'a CoreDump,
+    /// Addresses the processed object is mapped at, all of them if the core dump doesn't say.
+    range: Range<PhysAddr>,
+    object: Rc<Module>,
+    /// Libraries by path, `None` if they couldn't be read.
+    libraries: HashMap<&'a str, Option<Rc<Module>>>,
+}
+
+impl Modules<'_> {
+    fn module(&mut self, pc: PhysAddr) -> Option<Rc<Module>> {
+        if self.range.contains(&pc) {
+            return Some(self.object.clone());
+        }
+
+        let core = self.core;
+        let file = core.files.iter().find(|file| (file.start..file.end).contains(&pc))?;
+        self.libraries
+            .entry(file.path.as_str())
+            .or_insert_with(|| Module::load(core, &file.path).map(Rc::new))
+            .clone()
+    }
+}
+
+impl Processor {
+    /// Line table row spanning `addr`.
+    fn line_containing(&self, addr: PhysAddr) -> Option<&FileAttr> {
+        let file_attrs = &self.index.file_attrs;
+        let idx = file_attrs.partition_point(|row| row.addr <= addr).checked_sub(1)?;
+        let row = &file_attrs[idx].item;
+        (row.line != 0).then_some(row)
+    }
+
+    /// Where this object is mapped in `core`, found by the file name of its mappings, and
+    /// its call frame information.
+    fn modules<'a>(&self, core: &'a CoreDump) -> Modules<'a> {
+        let file_name = self.path.file_name();
+        let mappings: Vec<_> = core
+            .files
+            .iter()
+            .filter(|file| Path::new(&file.path).file_name() == file_name)
+            .collect();
+
+        let (range, bias) = match mappings.iter().find(|file| file.offset == 0) {
+            Some(first) => {
+                let start = mappings.iter().map(|file| file.start).min().unwrap_or(0);
+                let end = mappings.iter().map(|file| file.end).max().unwrap_or(0);
+                (start..end, first.start.wrapping_sub(self.image_base.unwrap_or(0)))
+            }
+            None => (0..PhysAddr::MAX, 0),
+        };
+
+        let endian = match self.endianness {
+            Endianness::Little => RunTimeEndian::Little,
+            Endianness::Big => RunTimeEndian::Big,
+        };
+
+        let sections = self.sections.iter().map(|section| {
+            (section.name.as_str(), section.start as u64, Cow::Borrowed(section.bytes()))
+        });
+
+        let object = Module::new(bias, endian, core.word_size() as u8, sections);
+        Modules { core, range, object: Rc::new(object), libraries: HashMap::new() }
+    }
+
+    fn frame(&self, modules: &Modules, pc: PhysAddr, sp: PhysAddr, is_caller: bool) -> Frame {
+        if !modules.range.contains(&pc) {
+            let file = modules.core.files.iter().find(|file| (file.start..file.end).contains(&pc));
+            let file = file.map(|file| file.path.clone());
+            return Frame { pc, sp, symbol: None, line: None, file };
+        }
+
+        // a return address is past the call, which may be the last instruction of a line
+        let call = if is_caller { pc.saturating_sub(1) } else { pc };
+        let addr = call.wrapping_sub(modules.object.bias);
+        let symbol = self.index.resolve(addr).map(|mut symbol| {
+            symbol.offset += pc - call;
+            symbol
+        });
+
+        let line = self.line_containing(addr).cloned();
+        Frame { pc, sp, symbol, line, file: None }
+    }
+
+    /// Frames of `thread` of a core dump of this object, innermost first.
+    ///
+    /// Frames are unwound by the call frame information in `.eh_frame` or `.debug_frame` of
+    /// this object, or of the libraries the core dump maps when they can still be read from
+    /// their paths, so code built without frame pointers can be walked through. Frames that
+    /// aren't covered by it are walked by following the chain of saved frame pointers. A
+    /// caller has to be further up the stack than the frame it called and has to return to
+    /// mapped memory, so a corrupted stack ends the backtrace early instead of producing
+    /// garbage frames.
+    pub fn backtrace(&self, core: &CoreDump, thread: &Thread) -> Vec<Frame> {
+        self.unwind(core, thread, true)
+    }
+
+    fn unwind(&self, core: &CoreDump, thread: &Thread, use_cfi: bool) -> Vec<Frame> {
+        let (mut pc, mut sp) = match (thread.pc(), thread.sp()) {
+            (Some(pc), Some(sp)) => (pc as PhysAddr, sp as PhysAddr),
+            _ => return Vec::new(),
+        };
+
+        let mut modules = self.modules(core);
+        let mut frames = vec![self.frame(&modules, pc, sp, false)];
+        let (stack, abi) = match (core.region(sp), abi(self.arch)) {
+            (Some(stack), Some(abi)) => (stack, abi),
+            _ => return frames,
+        };
+
+        let mut regs: Registers = abi
+            .registers
+            .iter()
+            .enumerate()
+            .filter_map(|(reg, name)| Some((reg as u16, thread.register(name)?)))
+            .collect();
+
+        let mut is_caller = false;
+        while frames.len() < MAX_FRAMES {
+            // look up the call rather than the return address, which may be past the function
+            let addr = if is_caller { pc.saturating_sub(1) } else { pc };
+            let step = match use_cfi {
+                true => modules.module(pc).and_then(|module| {
+                    let addr = addr.wrapping_sub(module.bias) as u64;
+                    module.unwind(core, &abi, &regs, addr)
+                }),
+                false => None,
+            };
+
+            let (caller, ret, signal) = match step {
+                Some(Step::Caller { registers, pc, signal }) => (registers, pc, signal),
+                Some(Step::End) => break,
+                None => match unwind_frame_pointer(core, &abi, &regs, sp, &stack) {
+                    Some((registers, pc)) => (registers, pc, false),
+                    None => break,
+                },
+            };
+
+            let ret = ret as PhysAddr;
+            let caller_sp = match caller.get(&abi.sp) {
+                Some(&caller_sp) => caller_sp as PhysAddr,
+                None => break,
+            };
+
+            // callers are further up the stack, a frame that doesn't move is stuck in a loop
+            if caller_sp < sp || caller_sp > stack.end || (caller_sp == sp && ret == pc) {
+                break;
+            }
+
+            if ret == 0 || core.region(ret).is_none() {
+                break;
+            }
+
+            frames.push(self.frame(&modules, ret, caller_sp, true));
+            (pc, sp, regs) = (ret, caller_sp, caller);
+
+            // a signal handler returns to the interrupted instruction rather than past a call
+            is_caller = !signal;
+        }
+
+        frames
+    }
+
+    /// Write the backtrace of each thread of a core dump of this object, with the function and
+    /// source line of each frame when they're known, or the file it's in when it's outside of
+    /// the object.
+    ///
+    /// ```text
+    /// Thread 5603:
+    ///   #0 0x00007f8bb3768000 in crash at recurse.c:12
+    ///   #1 0x00007f8bb3768015 in recurse+0xd at recurse.c:17
+    /// ```
+    pub fn write_backtrace<W: Write>(&self, w: &mut W, core: &CoreDump) -> io::Result<()> {
+        for (idx, thread) in core.threads.iter().enumerate() {
+            if idx != 0 {
+                writeln!(w)?;
+            }
+
+            writeln!(w, "Thread {}:", thread.pid)?;
+            for (idx, frame) in self.backtrace(core, thread).iter().enumerate() {
+                write!(w, "  #{idx} {:#018x}", frame.pc)?;
+                if let Some(symbol) = &frame.symbol {
+                    match symbol.offset {
+                        0 => write!(w, " in {}", symbol.name)?,
+                        offset => write!(w, " in {}+{offset:#x}", symbol.name)?,
+                    }
+                }
+
+                if let Some(line) = &frame.line {
+                    write!(w, " at {}:{}", display_path(&line.path).display(), line.line)?;
+                }
+
+                if let Some(file) = &frame.file {
+                    write!(w, " from {file}")?;
+                }
+
+                writeln!(w)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Processor;
+    use std::path::Path;
+
+    /// Executable with frame pointers that crashes a few calls deep, see `fixtures/recurse.c`.
+    const RECURSE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/recurse");
+
+    /// Core dumped by [`RECURSE`].
+    const CORE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/recurse.core");
+
+    /// [`RECURSE`] built as a position-independent executable without frame pointers.
+    const RECURSE_NOFP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/recurse-nofp");
+
+    /// Core dumped by [`RECURSE_NOFP`].
+    const CORE_NOFP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/recurse-nofp.core");
+
+    #[test]
+    fn frame_pointers() {
+        let dump = Processor::parse(Path::new(CORE)).unwrap();
+        let core = dump.core_dump().unwrap();
+        let processor = Processor::parse(Path::new(RECURSE)).unwrap();
+
+        let mut out = Vec::new();
+        processor.write_backtrace(&mut out, core).unwrap();
+        let expected = "\
+Thread 5596:
+  #0 0x000000000040100f in crash+0xf at recurse.c:12
+  #1 0x000000000040102f in recurse+0x1b at recurse.c:17
+  #2 0x000000000040103e in recurse+0x2a at recurse.c:19
+  #3 0x000000000040103e in recurse+0x2a at recurse.c:19
+  #4 0x000000000040104f in _start+0xe at recurse.c:26
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        // following the saved frame pointers finds the same frames
+        let thread = &core.threads[0];
+        let frames = processor.unwind(core, thread, false);
+        let pcs: Vec<_> = frames.iter().map(|frame| frame.pc).collect();
+        assert_eq!(pcs, [0x40100f, 0x40102f, 0x40103e, 0x40103e, 0x40104f]);
+
+        // a frame pointer that doesn't point into the stack ends the backtrace
+        let mut thread = thread.clone();
+        for (name, value) in &mut thread.registers {
+            if *name == "rbp" {
+                *value = 0x10;
+            }
+        }
+        assert_eq!(processor.unwind(core, &thread, false).len(), 1);
+    }
+
+    #[test]
+    fn call_frame_information() {
+        let dump = Processor::parse(Path::new(CORE_NOFP)).unwrap();
+        let core = dump.core_dump().unwrap();
+        let processor = Processor::parse(Path::new(RECURSE_NOFP)).unwrap();
+
+        let mut out = Vec::new();
+        processor.write_backtrace(&mut out, core).unwrap();
+        let expected = "\
+Thread 5603:
+  #0 0x00007f8bb3768000 in crash at recurse.c:12
+  #1 0x00007f8bb3768015 in recurse+0xd at recurse.c:17
+  #2 0x00007f8bb3768022 in recurse+0x1a at recurse.c:19
+  #3 0x00007f8bb3768022 in recurse+0x1a at recurse.c:19
+  #4 0x00007f8bb3768032 in _start+0xe at recurse.c:26
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+}
@@ -1,12 +1,15 @@
-use crate::Processor;
+use crate::relocations::{self, Relocation, RelocationUse};
+use crate::{source, FunctionRange, Instruction, ListingFormatter, Processor};
 use binformat::elf::{Elf32Dyn, Elf32Sym, Elf64Dyn, Elf64Sym};
 use binformat::pe::ExceptionDirectoryEntry;
 use binformat::ToData;
 use config::CONFIG;
-use debugvault::Symbol;
-use object::Endian;
-use processor_shared::{encode_hex_bytes_truncated, Section, SectionKind};
+use debugvault::{Index, ResolvedSymbol, Symbol};
+use object::{Architecture, Endian};
+use processor_shared::{Section, SectionKind};
 use std::mem::size_of;
+use std::ops::Range;
+use std::path::Path;
 use std::sync::Arc;
 use tokenizing::{colors, Token, TokenStream};
 
@@ -23,13 +26,27 @@ pub enum BlockContent {
     Label {
         symbol: Arc<Symbol>,
     },
+    /// Source line the instructions that follow are compiled from.
+    Source {
+        path: Arc<Path>,
+        line: usize,
+        /// Text of the line, if the source file can be found.
+        text: Option<String>,
+    },
     Instruction {
         inst: Vec<Token>,
-        bytes: String,
+        bytes: Vec<u8>,
     },
     Error {
         err: decoder::ErrorKind,
-        bytes: String,
+        bytes: Vec<u8>,
+    },
+    /// Entry of a jump table or literal in a code section.
+    Word {
+        value: u64,
+        /// Symbol or section the value points into and the offset into it.
+        symbol: Option<ResolvedSymbol>,
+        bytes: Vec<u8>,
     },
     CString {
         bytes: Vec<u8>,
@@ -64,9 +81,12 @@ impl Block {
         match &self.content {
             BlockContent::SectionStart { .. } => 2,
             BlockContent::SectionEnd { .. } => 2,
-            BlockContent::Label { .. } => 2,
+            // labels local to a function aren't preceded by an empty line
+            BlockContent::Label { symbol } => 1 + !symbol.intrinsic() as usize,
+            BlockContent::Source { .. } => 1,
             BlockContent::Instruction { .. } => 1,
             BlockContent::Error { .. } => 1,
+            BlockContent::Word { .. } => 1,
             BlockContent::CString { bytes } => bytes.len() + 1,
             BlockContent::Pointer { .. } => 1,
             BlockContent::Got { .. } => 1,
@@ -75,57 +95,81 @@ impl Block {
         }
     }
 
-    pub fn tokenize(&self, stream: &mut TokenStream) {
+    pub fn tokenize(&self, stream: &mut TokenStream, listing: &ListingFormatter) {
         match &self.content {
             BlockContent::Label { symbol } => {
-                stream.push("\n<", CONFIG.colors.asm.label);
+                if !symbol.intrinsic() {
+                    stream.push("\n", CONFIG.colors.asm.label);
+                }
+                stream.push("<", CONFIG.colors.asm.label);
                 stream.inner.extend_from_slice(symbol.name());
                 stream.push(">", CONFIG.colors.asm.label);
             }
             BlockContent::SectionStart { section } => {
                 stream.push("section started", colors::WHITE);
-                stream.push_owned(format!(" {} ", section.name), CONFIG.colors.asm.section);
+                stream.push_fmt(format_args!(" {} ", section.name), CONFIG.colors.asm.section);
                 stream.push("{", CONFIG.colors.brackets);
                 if section.ident == "UNKNOWN" {
-                    stream.push_owned(format!("{:?}", section.kind), CONFIG.colors.asm.component);
+                    let kind = section.kind;
+                    stream.push_fmt(format_args!("{kind:?}"), CONFIG.colors.asm.component);
                 } else {
                     stream.push(section.ident, CONFIG.colors.asm.component);
                 }
                 stream.push("} ", CONFIG.colors.brackets);
-                stream.push_owned(format!("{:x}", section.start), colors::GREEN);
+                stream.push_fmt(format_args!("{:x}", section.start), colors::GREEN);
                 stream.push("-", CONFIG.colors.delimiter);
-                stream.push_owned(format!("{:x}", section.end), colors::GREEN);
+                stream.push_fmt(format_args!("{:x}", section.end), colors::GREEN);
             }
             BlockContent::SectionEnd { section } => {
                 stream.push("section ended", colors::WHITE);
-                stream.push_owned(format!(" {} ", section.name), CONFIG.colors.asm.section);
+                stream.push_fmt(format_args!(" {} ", section.name), CONFIG.colors.asm.section);
                 stream.push("{", CONFIG.colors.brackets);
-                stream.push_owned(format!("{:?}", section.kind), CONFIG.colors.asm.component);
+                stream.push_fmt(format_args!("{:?}", section.kind), CONFIG.colors.asm.component);
                 stream.push("} ", CONFIG.colors.brackets);
-                stream.push_owned(format!("{:x}", section.start), colors::GREEN);
+                stream.push_fmt(format_args!("{:x}", section.start), colors::GREEN);
                 stream.push("-", CONFIG.colors.delimiter);
-                stream.push_owned(format!("{:x}", section.end), colors::GREEN);
+                stream.push_fmt(format_args!("{:x}", section.end), colors::GREEN);
+            }
+            BlockContent::Source { path, line, text } => {
+                let location = format!("; {}:{line}", source::display_path(path).display());
+                stream.push_owned(location, CONFIG.colors.comment);
+
+                if listing.show_source_text {
+                    if let Some(text) = text {
+                        stream.push_fmt(format_args!(": {}", text.trim()), CONFIG.colors.comment);
+                    }
+                }
             }
             BlockContent::Instruction { inst, bytes } => {
-                stream.push_owned(format!("{:0>10X}  ", self.addr), CONFIG.colors.address);
-                stream.push_owned(bytes.clone(), CONFIG.colors.bytes);
-                stream.inner.extend_from_slice(&inst);
+                listing.instruction(stream, self.addr, bytes, inst);
             }
             BlockContent::Error { err, bytes } => {
-                stream.push_owned(format!("{:0>10X}  ", self.addr), CONFIG.colors.address);
-                stream.push_owned(bytes.clone(), CONFIG.colors.bytes);
+                listing.address(stream, self.addr);
+                listing.bytes(stream, bytes);
                 stream.push("<", CONFIG.colors.brackets);
-                stream.push_owned(format!("{err:?}"), CONFIG.colors.asm.invalid);
+                stream.push_fmt(format_args!("{err:?}"), CONFIG.colors.asm.invalid);
                 stream.push(">", CONFIG.colors.brackets);
             }
+            BlockContent::Word { value, symbol, bytes } => {
+                listing.address(stream, self.addr);
+                listing.bytes(stream, bytes);
+                let directive = if bytes.len() == 8 { ".quad " } else { ".word " };
+                stream.push(directive, CONFIG.colors.asm.opcode);
+                stream.push_fmt(format_args!("{value:#x}"), CONFIG.colors.asm.immediate);
+                if let Some(symbol) = symbol {
+                    stream.push(" <", CONFIG.colors.asm.label);
+                    symbol.tokenize(stream);
+                    stream.push(">", CONFIG.colors.asm.label);
+                }
+            }
             BlockContent::CString { bytes } => {
-                stream.push_owned(format!("{:0>10X}  ", self.addr), CONFIG.colors.address);
+                listing.address(stream, self.addr);
                 let lossy_string = String::from_utf8_lossy(&bytes);
                 let escaped = format!("\"{}\"", lossy_string.escape_debug());
                 stream.push_owned(escaped, CONFIG.colors.asm.string);
             }
             BlockContent::Got { symbol, .. } => {
-                stream.push_owned(format!("{:0>10X}  ", self.addr), CONFIG.colors.address);
+                listing.address(stream, self.addr);
                 stream.push("<", CONFIG.colors.asm.label);
                 let name = symbol.name();
                 if name.is_empty() {
@@ -142,12 +186,12 @@ impl Block {
                 // addr  }
                 let start_addr = fields[0].0;
                 let end_addr = fields[fields.len() - 1].0;
-                stream.push_owned(format!("{:0>10X}  ", start_addr), CONFIG.colors.address);
+                listing.address(stream, start_addr);
                 stream.push("struct ", CONFIG.colors.src.keyword);
                 stream.push(ident, CONFIG.colors.src.tipe);
                 stream.push(" {\n", CONFIG.colors.delimiter);
                 for (addr, name, tipe, value) in fields {
-                    stream.push_owned(format!("{:0>10X}  ", addr), CONFIG.colors.address);
+                    listing.address(stream, *addr);
                     stream.push("    ", colors::WHITE);
                     stream.push(name, CONFIG.colors.src.field);
                     stream.push(": ", colors::WHITE);
@@ -156,12 +200,12 @@ impl Block {
                     stream.push_owned(value.clone(), CONFIG.colors.src.constant);
                     stream.push("\n", colors::WHITE);
                 }
-                stream.push_owned(format!("{:0>10X}  ", end_addr), CONFIG.colors.address);
+                listing.address(stream, end_addr);
                 stream.push("}", CONFIG.colors.delimiter);
             }
             BlockContent::Pointer { value, symbol, .. } => {
-                stream.push_owned(format!("{:0>10X}  ", self.addr), CONFIG.colors.address);
-                stream.push_owned(format!("{:#x}", value), CONFIG.colors.bytes);
+                listing.address(stream, self.addr);
+                stream.push_fmt(format_args!("{:#x}", value), CONFIG.colors.bytes);
                 if let Some(symbol) = symbol {
                     stream.push(" <", CONFIG.colors.asm.label);
                     stream.inner.extend_from_slice(symbol.name());
@@ -171,7 +215,7 @@ impl Block {
             BlockContent::Bytes { bytes } => {
                 let mut off = 0;
                 for chunk in bytes.chunks(32) {
-                    stream.push_owned(format!("{:0>10X}  ", self.addr + off), CONFIG.colors.address);
+                    listing.address(stream, self.addr + off);
                     let s = processor_shared::encode_hex_bytes_truncated(chunk, usize::MAX, false);
                     stream.push_owned(s, CONFIG.colors.bytes);
                     stream.push("\n", colors::WHITE);
@@ -341,11 +385,66 @@ impl Processor {
         });
     }
 
+    /// Relocation that patches the instruction at `addr` of `section`, along with the address it
+    /// patches.
+    fn relocation_of(
+        &self,
+        section: &Section,
+        addr: usize,
+        inst: &Instruction,
+    ) -> Option<(usize, &Relocation)> {
+        let relocations = self.relocations.of(&section.name)?;
+        let offset = addr - section.start;
+        let width = self.instruction_width(inst);
+        let idx = relocations.partition_point(|reloc| reloc.addr < offset);
+        if let Some(reloc) = relocations.get(idx) {
+            if reloc.addr < offset + width && reloc.item.usage != RelocationUse::Pair {
+                return Some((section.start + reloc.addr, &reloc.item));
+            }
+        }
+
+        // the instruction completing an `auipc` pair
+        let auipc = relocations.get(relocations.search(offset.checked_sub(4)?).ok()?)?;
+        if auipc.item.usage == RelocationUse::Pair && (self.instruction_xref)(inst).is_some() {
+            return Some((section.start + auipc.addr, &auipc.item));
+        }
+
+        None
+    }
+
+    /// Tokens of an instruction of a relocatable object, with the operand that's patched by a
+    /// relocation replaced by its symbol.
+    pub(crate) fn relocated_tokens(
+        &self,
+        section: &Section,
+        addr: usize,
+        inst: &Instruction,
+    ) -> Option<Vec<Token>> {
+        let (reloc_addr, reloc) = self.relocation_of(section, addr, inst)?;
+        let offset = reloc.offset(reloc_addr, addr, self.instruction_width(inst));
+
+        let mut symbol = TokenStream::new();
+        reloc.tokenize(&mut symbol, offset);
+
+        // without symbols the address the instruction refers to is shown as a number
+        let mut tokens = self.instruction_tokens(inst, &Index::default());
+        let operand = reloc.operand((self.instruction_xref)(inst).map(|(_, kind)| kind));
+        let separator = self.style.operand_separator;
+        if !relocations::substitute(&mut tokens, operand, separator, &symbol.inner) {
+            tokens.push(Token::from_str(" <", CONFIG.colors.asm.label));
+            tokens.extend(symbol.inner);
+            tokens.push(Token::from_str(">", CONFIG.colors.asm.label));
+        }
+
+        Some(tokens)
+    }
+
     fn parse_code(&self, addr: usize, section: &Section, blocks: &mut Vec<Block>) {
         let opt_inst = self.instruction_by_addr(addr);
         let opt_err = self.error_by_addr(addr);
+        let opt_word = self.inline_data_by_addr(addr);
 
-        if opt_inst.is_some() || opt_err.is_some() {
+        if opt_inst.is_some() || opt_err.is_some() || opt_word.is_some() {
             if let Some(symbol) = self.get_symbol_by_addr(addr, section) {
                 blocks.push(Block {
                     addr,
@@ -355,11 +454,33 @@ impl Processor {
         }
 
         if let Some(inst) = opt_inst {
-            let width = self.instruction_width(&inst);
-            let inst = self.instruction_tokens(&inst, &self.index);
-            let bytes = section.bytes_by_addr(addr, width);
-            let bytes =
-                encode_hex_bytes_truncated(&bytes, self.max_instruction_width * 3 + 1, true);
+            if let Some(file_attr) = self.source_line_at(addr) {
+                blocks.push(Block {
+                    addr,
+                    content: BlockContent::Source {
+                        path: Arc::clone(&file_attr.path),
+                        line: file_attr.line,
+                        text: self.source_text(file_attr),
+                    },
+                });
+            }
+
+            let inst = match self.relocated_tokens(section, addr, inst) {
+                Some(tokens) => tokens,
+                None => {
+                    let mut tokens = self.instruction_tokens(inst, &self.index);
+
+                    // operands that refer to a string are followed by the string
+                    let target = (self.instruction_xref)(inst).map(|(target, _)| target);
+                    if let Some(text) = target.and_then(|target| self.string_at(target)) {
+                        let comment = format!(" ; \"{}\"", text.escape_debug());
+                        tokens.push(Token::from_string(comment, CONFIG.colors.comment));
+                    }
+
+                    tokens
+                }
+            };
+            let bytes = self.instruction_bytes(addr).unwrap_or_default().to_vec();
 
             blocks.push(Block {
                 addr,
@@ -368,10 +489,23 @@ impl Processor {
             return;
         }
 
+        if let Some(word) = opt_word {
+            let bytes = self.instruction_bytes(addr).unwrap_or_default().to_vec();
+            let target = if self.arch == Architecture::Arm { word.value & !1 } else { word.value };
+
+            blocks.push(Block {
+                addr,
+                content: BlockContent::Word {
+                    value: word.value,
+                    symbol: self.index.resolve(target as usize),
+                    bytes,
+                },
+            });
+            return;
+        }
+
         if let Some(err) = opt_err {
-            let bytes = section.bytes_by_addr(addr, err.size());
-            let bytes =
-                encode_hex_bytes_truncated(&bytes, self.max_instruction_width * 3 + 1, true);
+            let bytes = self.instruction_bytes(addr).unwrap_or_default().to_vec();
 
             blocks.push(Block {
                 addr,
@@ -402,7 +536,12 @@ impl Processor {
                 break;
             }
 
-            if self.get_symbol_by_addr(addr, section).is_some() {
+            if self.inline_data_by_addr(baddr).is_some() {
+                break;
+            }
+
+            // labelled bytes are in a different block
+            if addr != baddr && self.get_symbol_by_addr(baddr, section).is_some() {
                 break;
             }
 
@@ -438,7 +577,14 @@ impl Processor {
         boundaries
     }
 
-    fn compute_section_boundaries(&self, section: &Section) -> Vec<usize> {
+    /// Index of the block in `boundaries` that `addr` falls in. Addresses in the middle of an
+    /// instruction or some data get rounded down to the start of it.
+    pub fn block_containing(&self, boundaries: &[usize], addr: usize) -> Option<usize> {
+        self.section_by_addr(addr)?;
+        boundaries.partition_point(|&boundary| boundary <= addr).checked_sub(1)
+    }
+
+    pub(crate) fn compute_section_boundaries(&self, section: &Section) -> Vec<usize> {
         let mut boundaries = Vec::new();
 
         if let SectionKind::Unloaded | SectionKind::Debug = section.kind {
@@ -456,7 +602,9 @@ impl Processor {
 
         boundaries.push(section.start);
         match section.kind {
-            SectionKind::Code => self.compute_code_boundaries(section, &mut boundaries),
+            SectionKind::Code => {
+                self.compute_code_boundaries(section.start..section.end, &mut boundaries)
+            }
             SectionKind::CString => self.compute_cstring_boundaries(section, &mut boundaries),
             SectionKind::Ptr32 | SectionKind::Got32 => {
                 let mut addr = section.start;
@@ -521,14 +669,19 @@ impl Processor {
         boundaries
     }
 
-    fn compute_code_boundaries(&self, section: &Section, boundaries: &mut Vec<usize>) {
-        let mut addr = section.start;
+    /// Boundaries of the blocks of a function, which are cut off at the end of the function.
+    pub(crate) fn compute_function_boundaries(&self, function: FunctionRange) -> Vec<usize> {
+        let mut boundaries = Vec::new();
+        self.compute_code_boundaries(function.start..function.end, &mut boundaries);
+        boundaries.dedup();
+        boundaries
+    }
 
-        loop {
-            if addr == section.end {
-                break;
-            }
+    fn compute_code_boundaries(&self, range: Range<usize>, boundaries: &mut Vec<usize>) {
+        let mut addr = range.start;
 
+        // the last instruction of a function might cross its end, if its size is wrong
+        while addr < range.end {
             if self.index.get_sym_by_addr(addr).is_some() {
                 boundaries.push(addr);
             }
@@ -539,6 +692,12 @@ impl Processor {
                 continue;
             }
 
+            if let Some(word) = self.inline_data_by_addr(addr) {
+                boundaries.push(addr);
+                addr += word.size;
+                continue;
+            }
+
             if let Some(err) = self.error_by_addr(addr) {
                 boundaries.push(addr);
                 addr += err.size();
@@ -547,7 +706,7 @@ impl Processor {
 
             let mut baddr = addr;
             loop {
-                if baddr == section.end {
+                if baddr == range.end {
                     break;
                 }
 
@@ -559,6 +718,10 @@ impl Processor {
                     break;
                 }
 
+                if self.inline_data_by_addr(baddr).is_some() {
+                    break;
+                }
+
                 // We found some labelled bytes, so those would have to be in a different block.
                 if addr != baddr && self.index.get_sym_by_addr(baddr).is_some() {
                     break;
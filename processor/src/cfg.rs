@@ -0,0 +1,301 @@
+use crate::{FunctionRange, Processor};
+use decoder::XrefKind;
+use processor_shared::{PhysAddr, SectionKind};
+use std::io::{self, Write};
+use std::ops::Range;
+
+/// How control passes from one basic block to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// Execution continues with the instruction that follows.
+    Fallthrough,
+
+    /// A branch is taken.
+    Taken,
+
+    /// A call returns to the instruction that follows it.
+    CallReturn,
+}
+
+/// Edge between the basic blocks starting at `from` and `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub from: PhysAddr,
+    pub to: PhysAddr,
+    pub kind: EdgeKind,
+}
+
+/// Run of instructions that's only entered at the start and only left at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub start: PhysAddr,
+
+    /// Address following the last instruction.
+    pub end: PhysAddr,
+
+    /// Formatted instructions and the address they're located at.
+    pub lines: Vec<(PhysAddr, String)>,
+}
+
+/// Control-flow graph of a single function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cfg {
+    pub name: String,
+    pub blocks: Vec<BasicBlock>,
+    pub edges: Vec<Edge>,
+}
+
+impl Cfg {
+    /// Write the graph in Graphviz's DOT format, with a node for each block.
+    ///
+    /// ```text
+    /// digraph "count" {
+    ///     node [shape=box, fontname="monospace"];
+    ///     bb_0 [label="0000000000000000: r1 = 0\l..."];
+    ///     bb_0 -> bb_88 [label="taken"];
+    /// }
+    /// ```
+    pub fn write_dot<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "digraph \"{}\" {{", escape(&self.name))?;
+        writeln!(w, "    node [shape=box, fontname=\"monospace\"];")?;
+
+        for block in &self.blocks {
+            write!(w, "    bb_{:x} [label=\"", block.start)?;
+            for (addr, line) in &block.lines {
+                write!(w, "{addr:016x}: {}\\l", escape(line))?;
+            }
+            writeln!(w, "\"];")?;
+        }
+
+        for edge in &self.edges {
+            let label = match edge.kind {
+                EdgeKind::Fallthrough => "fallthrough",
+                EdgeKind::Taken => "taken",
+                EdgeKind::CallReturn => "call-return",
+            };
+
+            writeln!(w, "    bb_{:x} -> bb_{:x} [label=\"{label}\"];", edge.from, edge.to)?;
+        }
+
+        writeln!(w, "}}")
+    }
+}
+
+/// Escape quotes and backslashes of a DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Instruction's `(addr, width, xref, is_terminator)`.
+type Flow = (PhysAddr, usize, Option<(PhysAddr, XrefKind)>, bool);
+
+/// Splits the instructions of a function spanning `start..end` into basic blocks, returned as
+/// ranges of `insts`, along with the edges between them.
+///
+/// `insts` are the function's decoded instructions in order. Blocks start at branch targets and
+/// after branches, calls, terminators and gaps, like inline data.
+fn split(insts: &[Flow], start: PhysAddr, end: PhysAddr) -> (Vec<Range<usize>>, Vec<Edge>) {
+    let mut leaders = vec![start];
+    for &(addr, width, xref, is_terminator) in insts {
+        match xref {
+            Some((to, XrefKind::Jump)) => {
+                if (start..end).contains(&to) {
+                    leaders.push(to);
+                }
+                leaders.push(addr + width);
+            }
+            Some((_, XrefKind::Call)) => leaders.push(addr + width),
+            _ if is_terminator => leaders.push(addr + width),
+            _ => {}
+        }
+    }
+
+    leaders.sort_unstable();
+    leaders.dedup();
+
+    let mut blocks = Vec::new();
+    let mut block_start = 0;
+    for idx in 1..=insts.len() {
+        let (addr, width, ..) = insts[idx - 1];
+        let split = match insts.get(idx) {
+            Some(&(next, ..)) => next != addr + width || leaders.binary_search(&next).is_ok(),
+            None => true,
+        };
+
+        if split {
+            blocks.push(block_start..idx);
+            block_start = idx;
+        }
+    }
+
+    // branches into the middle of an instruction don't get an edge
+    let is_block = |addr: PhysAddr| {
+        blocks.binary_search_by_key(&addr, |block: &Range<usize>| insts[block.start].0).is_ok()
+    };
+
+    let mut edges = Vec::new();
+    for (idx, block) in blocks.iter().enumerate() {
+        let from = insts[block.start].0;
+        let (addr, width, xref, is_terminator) = insts[block.end - 1];
+
+        if let Some((to, XrefKind::Jump)) = xref {
+            if is_block(to) {
+                edges.push(Edge { from, to, kind: EdgeKind::Taken });
+            }
+        }
+
+        if is_terminator {
+            continue;
+        }
+
+        let next = match blocks.get(idx + 1) {
+            Some(next) if insts[next.start].0 == addr + width => insts[next.start].0,
+            _ => continue,
+        };
+
+        let kind = match xref {
+            Some((_, XrefKind::Call)) => EdgeKind::CallReturn,
+            _ => EdgeKind::Fallthrough,
+        };
+
+        edges.push(Edge { from, to: next, kind });
+    }
+
+    (blocks, edges)
+}
+
+impl Processor {
+    /// Control-flow graph of the function named `symbol`, if there's such a function.
+    ///
+    /// The function spans the range given by [`Processor::function_range`].
+    pub fn build_cfg(&self, symbol: &str) -> Option<Cfg> {
+        let FunctionRange { start, end, .. } = self.function_range_by_name(symbol)?;
+
+        let first = self.instructions.partition_point(|inst| inst.addr < start);
+        let last = self.instructions.partition_point(|inst| inst.addr < end);
+        let insts = &self.instructions[first..last];
+
+        let flow: Vec<Flow> = insts
+            .iter()
+            .map(|inst| {
+                (
+                    inst.addr,
+                    self.instruction_width(&inst.item),
+                    (self.instruction_xref)(&inst.item),
+                    (self.instruction_is_terminator)(&inst.item),
+                )
+            })
+            .collect();
+
+        let section = self
+            .sections()
+            .find(|s| s.kind == SectionKind::Code && (s.start..s.end).contains(&start));
+
+        let (ranges, edges) = split(&flow, start, end);
+        let blocks = ranges
+            .into_iter()
+            .map(|range| {
+                let (addr, width, ..) = flow[range.end - 1];
+                let lines = insts[range.clone()]
+                    .iter()
+                    .map(|inst| {
+                        let relocated = section.and_then(|section| {
+                            self.relocated_tokens(section, inst.addr, &inst.item)
+                        });
+                        let tokens = match relocated {
+                            Some(tokens) => tokens,
+                            None => self.instruction_tokens(&inst.item, &self.index),
+                        };
+                        let text = tokens.iter().map(|token| &token.text as &str).collect();
+                        (inst.addr, text)
+                    })
+                    .collect();
+
+                BasicBlock { start: flow[range.start].0, end: addr + width, lines }
+            })
+            .collect();
+
+        Some(Cfg { name: symbol.to_string(), blocks, edges })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split, BasicBlock, Cfg, Edge, EdgeKind};
+    use decoder::XrefKind;
+
+    #[test]
+    fn branches() {
+        let insts = [
+            (0x1000, 4, None, false),
+            (0x1004, 4, Some((0x1014, XrefKind::Jump)), false),
+            (0x1008, 4, Some((0x2000, XrefKind::Call)), false),
+            (0x100c, 4, None, false),
+            (0x1010, 4, Some((0x1000, XrefKind::Jump)), true),
+            (0x1014, 4, None, true),
+        ];
+
+        let (blocks, edges) = split(&insts, 0x1000, 0x1018);
+        assert_eq!(blocks, [0..2, 2..3, 3..5, 5..6]);
+        assert_eq!(
+            edges,
+            [
+                Edge { from: 0x1000, to: 0x1014, kind: EdgeKind::Taken },
+                Edge { from: 0x1000, to: 0x1008, kind: EdgeKind::Fallthrough },
+                Edge { from: 0x1008, to: 0x100c, kind: EdgeKind::CallReturn },
+                Edge { from: 0x100c, to: 0x1000, kind: EdgeKind::Taken },
+            ]
+        );
+    }
+
+    #[test]
+    fn outside_of_function() {
+        let insts = [
+            // tail call
+            (0x1000, 4, Some((0x2000, XrefKind::Jump)), false),
+            // into the middle of an instruction
+            (0x1004, 8, Some((0x1008, XrefKind::Jump)), false),
+            // after inline data
+            (0x1014, 4, None, true),
+        ];
+
+        let (blocks, edges) = split(&insts, 0x1000, 0x1018);
+        assert_eq!(blocks, [0..1, 1..2, 2..3]);
+        assert_eq!(edges, [Edge { from: 0x1000, to: 0x1004, kind: EdgeKind::Fallthrough }]);
+    }
+
+    #[test]
+    fn dot() {
+        let cfg = Cfg {
+            name: "main".to_string(),
+            blocks: vec![
+                BasicBlock {
+                    start: 0x1000,
+                    end: 0x1004,
+                    lines: vec![(0x1000, "beqz a0, loc_1004".to_string())],
+                },
+                BasicBlock {
+                    start: 0x1004,
+                    end: 0x1008,
+                    lines: vec![(0x1004, "la a0, \"str\"".to_string())],
+                },
+            ],
+            edges: vec![
+                Edge { from: 0x1000, to: 0x1004, kind: EdgeKind::Taken },
+                Edge { from: 0x1000, to: 0x1004, kind: EdgeKind::Fallthrough },
+            ],
+        };
+
+        let mut out = Vec::new();
+        cfg.write_dot(&mut out).unwrap();
+        let expected = r#"digraph "main" {
+    node [shape=box, fontname="monospace"];
+    bb_1000 [label="0000000000001000: beqz a0, loc_1004\l"];
+    bb_1004 [label="0000000000001004: la a0, \"str\"\l"];
+    bb_1000 -> bb_1004 [label="taken"];
+    bb_1000 -> bb_1004 [label="fallthrough"];
+}
+"#;
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+}
@@ -0,0 +1,97 @@
+use processor_shared::{PhysAddr, Section, SectionKind};
+use std::ops::Range;
+
+/// Smallest number of bytes worth handing to a thread, decoding less than this is quicker than
+/// spawning work for it.
+const MIN_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Splits code sections into ranges that can be decoded independently.
+///
+/// Ranges only start at a section or at one of `splits`, usually the start of functions, since
+/// those are the only places where the decoder is known to be in sync. Neighbouring functions get
+/// merged until they're at least [`MIN_CHUNK_SIZE`] bytes. The ranges are sorted.
+pub(crate) fn code_chunks<'a>(
+    sections: &'a [Section],
+    splits: &[PhysAddr],
+) -> Vec<(&'a Section, Range<PhysAddr>)> {
+    let mut splits = splits.to_vec();
+    splits.sort_unstable();
+    splits.dedup();
+
+    let mut chunks = Vec::new();
+    for section in sections.iter().filter(|s| s.kind == SectionKind::Code) {
+        let first = splits.partition_point(|&addr| addr <= section.start);
+        let last = splits.partition_point(|&addr| addr < section.end);
+
+        let mut start = section.start;
+        for &addr in &splits[first..last] {
+            if addr - start >= MIN_CHUNK_SIZE {
+                chunks.push((section, start..addr));
+                start = addr;
+            }
+        }
+
+        if start != section.end {
+            chunks.push((section, start..section.end));
+        }
+    }
+
+    chunks
+}
+
+/// Number of threads to decode with, one per core unless `threads` is given.
+pub(crate) fn thread_count(threads: Option<usize>) -> usize {
+    match threads {
+        Some(threads) => threads.max(1),
+        None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{code_chunks, MIN_CHUNK_SIZE};
+    use processor_shared::{PhysAddr, Section, SectionKind};
+    use std::ops::Range;
+
+    fn ranges(sections: &[Section], splits: &[PhysAddr]) -> Vec<Range<PhysAddr>> {
+        code_chunks(sections, splits).into_iter().map(|(_, range)| range).collect()
+    }
+
+    fn section(kind: SectionKind, bytes: &'static [u8], start: usize) -> Section {
+        Section::new(".text".to_string(), "PROGBITS", kind, bytes, start, start + bytes.len())
+    }
+
+    #[test]
+    fn function_ranges() {
+        static CODE: [u8; 4 * MIN_CHUNK_SIZE] = [0; 4 * MIN_CHUNK_SIZE];
+        static DATA: [u8; 16] = [0; 16];
+
+        let start = 0x1000;
+        let sections = [
+            section(SectionKind::Code, &CODE, start),
+            section(SectionKind::Raw, &DATA, start + CODE.len()),
+        ];
+
+        // small functions get merged, functions outside of code are ignored
+        let splits = [
+            start + 3 * MIN_CHUNK_SIZE,
+            start + 16,
+            start + MIN_CHUNK_SIZE + 32,
+            start + MIN_CHUNK_SIZE,
+            start + CODE.len(),
+            0,
+        ];
+
+        assert_eq!(
+            ranges(&sections, &splits),
+            [
+                start..start + MIN_CHUNK_SIZE,
+                start + MIN_CHUNK_SIZE..start + 3 * MIN_CHUNK_SIZE,
+                start + 3 * MIN_CHUNK_SIZE..start + CODE.len(),
+            ]
+        );
+
+        // without any functions the section is decoded as a whole
+        assert_eq!(ranges(&sections, &[]), [start..start + CODE.len()]);
+    }
+}
@@ -15,6 +15,17 @@ impl fmt::Debug for super::Error {
             Self::UnknownArchitecture(arch) => {
                 f.write_fmt(format_args!("Unsupported architecture: '{arch:?}'."))
             }
+            Self::MissingSlice(arch) => {
+                f.write_fmt(format_args!("Universal binary has no '{arch:?}' object."))
+            }
+            Self::Archive(members) => f.write_fmt(format_args!(
+                "Object is a static archive, pick one of its members like 'libfoo.a(bar.o)': {}.",
+                members.join(", ")
+            )),
+            Self::MissingMember(member) => {
+                f.write_fmt(format_args!("Static archive has no member named '{member}'."))
+            }
+            Self::Wasm(err) => f.write_fmt(format_args!("Failed to parse wasm module: '{err}'.")),
         }
     }
 }
@@ -0,0 +1,149 @@
+use crate::Processor;
+use processor_shared::{PhysAddr, SectionKind};
+
+/// Bytes spanned by a function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionRange {
+    pub start: PhysAddr,
+
+    /// Address following the function.
+    pub end: PhysAddr,
+
+    /// Whether the function's symbol doesn't have a size, so it's assumed to run up to the next
+    /// function or the end of its section.
+    pub guessed: bool,
+}
+
+impl Processor {
+    /// Range of the function starting at `addr`, if there's a function symbol in a code section
+    /// at that address.
+    ///
+    /// The size comes from the symbol, or from the debug info of the function if the symbol
+    /// doesn't have one.
+    pub fn function_range(&self, addr: PhysAddr) -> Option<FunctionRange> {
+        let symbol = self.index.get_sym_by_addr(addr)?;
+        let section = self.section_by_addr(addr)?;
+        if section.kind != SectionKind::Code {
+            return None;
+        }
+
+        let (end, guessed) = match symbol.size() {
+            0 => {
+                let next = self.index.syms.partition_point(|func| func.addr <= addr);
+                let next = self.index.syms[next..].iter().find(|func| !func.item.intrinsic());
+                (next.map_or(section.end, |func| func.addr), true)
+            }
+            size => (addr + size, false),
+        };
+
+        Some(FunctionRange { start: addr, end: end.min(section.end), guessed })
+    }
+
+    /// Range of the function named `name`, see [`Processor::function_range`].
+    pub fn function_range_by_name(&self, name: &str) -> Option<FunctionRange> {
+        self.function_range(self.index.get_func_by_name(name)?)
+    }
+
+    /// Ranges of all named functions, sorted by address.
+    pub fn function_ranges(&self) -> impl Iterator<Item = FunctionRange> + '_ {
+        self.index
+            .functions()
+            .filter(|func| !func.item.intrinsic())
+            .filter_map(|func| self.function_range(func.addr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FunctionRange;
+    use crate::Processor;
+    use std::path::Path;
+
+    /// Statically linked x86_64 executable with debug info, see `fixtures/lines.c`.
+    const LINES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/lines");
+
+    /// [`LINES`] stripped, with a `.gnu_debuglink` to `fixtures/lines.debug`.
+    const STRIPPED: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/lines-stripped");
+
+    /// Unlinked x86_64 object without symbol sizes, see `fixtures/relocs-x86_64.s`.
+    const RELOCS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/relocs-x86_64.o");
+
+    /// Unlinked aarch64 object with unsized, nested and overlapping functions, see
+    /// `fixtures/attribution.s`.
+    const ATTRIBUTION: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/attribution-aarch64.o");
+
+    /// Static executable with thread-local variables at the offsets of its code, see
+    /// `fixtures/tls.s`.
+    const TLS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/tls");
+
+    #[test]
+    fn sized() {
+        let processor = Processor::parse(Path::new(LINES)).unwrap();
+        let square = processor.function_range_by_name("square").unwrap();
+        assert_eq!(square, FunctionRange { start: 0x401000, end: 0x401006, guessed: false });
+
+        let listing = processor.listing_formatter();
+        let mut out = Vec::new();
+        processor.write_function(&mut out, &listing, square).unwrap();
+        let listing = String::from_utf8(out).unwrap();
+        assert!(listing.starts_with("Disassembly of function square:\n\n<square>:\n"), "{listing}");
+        assert!(!listing.contains("<_start>"), "{listing}");
+    }
+
+    #[test]
+    fn separate_debug_info() {
+        let processor = Processor::parse(Path::new(STRIPPED)).unwrap();
+        let square = processor.function_range_by_name("square").unwrap();
+        assert_eq!(square, FunctionRange { start: 0x401000, end: 0x401006, guessed: false });
+        assert!(processor.index.get_file_by_addr(0x401000).is_some());
+    }
+
+    #[test]
+    fn thread_local_symbols() {
+        let processor = Processor::parse(Path::new(TLS)).unwrap();
+        let start = processor.function_range_by_name("_start").unwrap();
+        assert_eq!(start, FunctionRange { start: 0x10, end: 0x29, guessed: false });
+        assert!(processor.function_range_by_name("buffer").is_none());
+    }
+
+    #[test]
+    fn attribution() {
+        let processor = Processor::parse(Path::new(ATTRIBUTION)).unwrap();
+        let resolve = |addr| {
+            let resolved = processor.index.resolve(addr).unwrap();
+            (resolved.name.to_string(), resolved.offset, resolved.size)
+        };
+
+        // unsized symbols span up to the next symbol, or the end of their section
+        assert_eq!(resolve(0x8), ("unsized".to_string(), 0x4, 0x8));
+        assert_eq!(resolve(0x2e), ("tail".to_string(), 0x2, 0x4));
+
+        // the tightest of the symbols containing an address is blamed for it
+        assert_eq!(resolve(0x10), ("outer".to_string(), 0x4, 0x20));
+        assert_eq!(resolve(0x1c), ("inner".to_string(), 0x8, 0xc));
+        assert_eq!(resolve(0x20), ("spill".to_string(), 0x8, 0x14));
+
+        // `$d` and `$x` don't name the literal pool and the code after it
+        assert_eq!(resolve(0x24), ("spill".to_string(), 0xc, 0x14));
+        assert_eq!(resolve(0x28), ("spill".to_string(), 0x10, 0x14));
+        assert!(processor.index.get_sym_by_addr(0x28).is_none());
+
+        let unsized = processor.function_range_by_name("unsized").unwrap();
+        assert_eq!(unsized, FunctionRange { start: 0x4, end: 0xc, guessed: true });
+    }
+
+    #[test]
+    fn guessed() {
+        let processor = Processor::parse(Path::new(RELOCS)).unwrap();
+        let local = processor.function_range_by_name("local").unwrap();
+        assert_eq!(local, FunctionRange { start: 0x27, end: 0x28, guessed: true });
+
+        let listing = processor.listing_formatter();
+        let mut out = Vec::new();
+        processor.write_function(&mut out, &listing, local).unwrap();
+        let listing = String::from_utf8(out).unwrap();
+        let header = "Disassembly of function local (size unknown, up to the next symbol):\n";
+        assert!(listing.starts_with(header), "{listing}");
+    }
+}
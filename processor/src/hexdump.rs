@@ -0,0 +1,269 @@
+use crate::Processor;
+use processor_shared::{PhysAddr, Section};
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::Range;
+
+/// Number of bytes on each line of a dump.
+const LINE_WIDTH: usize = 16;
+
+/// Bytes written by [`Processor::write_hexdump`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DumpTarget<'a> {
+    /// Every byte of the section with the given name.
+    Section(&'a str),
+
+    /// Bytes of the sections loaded at the given addresses.
+    Range(Range<PhysAddr>),
+}
+
+/// Why bytes couldn't be dumped.
+#[derive(Debug)]
+pub enum DumpError {
+    UnknownSection(String),
+
+    /// Address of the range that isn't part of a section with contents.
+    Unmapped(PhysAddr),
+
+    /// The range doesn't contain any bytes.
+    Empty,
+
+    IO(io::Error),
+}
+
+impl fmt::Display for DumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSection(name) => write!(f, "there's no section named '{name}'"),
+            Self::Unmapped(addr) => {
+                write!(f, "address {addr:#x} isn't part of any section with contents")
+            }
+            Self::Empty => f.write_str("there are no bytes to dump"),
+            Self::IO(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DumpError {}
+
+impl From<io::Error> for DumpError {
+    fn from(err: io::Error) -> Self {
+        Self::IO(err)
+    }
+}
+
+/// Write a line of up to [`LINE_WIDTH`] bytes, like `hexdump -C` does.
+///
+/// ```text
+/// 0000000000402000  68 65 6c 6c 6f 2c 20 77  6f 72 6c 64 00 01 02 03  |hello, world....|
+/// ```
+fn write_line<W: Write>(w: &mut W, addr: PhysAddr, bytes: &[u8]) -> io::Result<()> {
+    write!(w, "{addr:016x} ")?;
+    for idx in 0..LINE_WIDTH {
+        if idx % 8 == 0 {
+            write!(w, " ")?;
+        }
+
+        match bytes.get(idx) {
+            Some(byte) => write!(w, "{byte:02x} ")?,
+            None => write!(w, "   ")?,
+        }
+    }
+
+    let ascii: String = bytes
+        .iter()
+        .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+        .collect();
+
+    writeln!(w, " |{ascii}|")
+}
+
+/// Write `chunks` of contiguous bytes starting at `addr` a line at a time, followed by the
+/// address after the last byte.
+///
+/// Lines that repeat the line before them are collapsed into a single `*`.
+fn write_lines<'a, W: Write>(
+    w: &mut W,
+    addr: PhysAddr,
+    chunks: impl IntoIterator<Item = &'a [u8]>,
+) -> io::Result<()> {
+    let mut line = [0u8; LINE_WIDTH];
+    let mut line_len = 0;
+    let mut line_addr = addr;
+    let mut prev = None;
+    let mut squeezed = false;
+
+    for byte in chunks.into_iter().flatten() {
+        line[line_len] = *byte;
+        line_len += 1;
+        if line_len < LINE_WIDTH {
+            continue;
+        }
+
+        if prev == Some(line) {
+            if !squeezed {
+                writeln!(w, "*")?;
+                squeezed = true;
+            }
+        } else {
+            write_line(w, line_addr, &line)?;
+            prev = Some(line);
+            squeezed = false;
+        }
+
+        line_addr += LINE_WIDTH;
+        line_len = 0;
+    }
+
+    if line_len != 0 {
+        write_line(w, line_addr, &line[..line_len])?;
+    }
+
+    writeln!(w, "{:016x}", line_addr + line_len)
+}
+
+impl Processor {
+    /// Contents of the sections spanning `range`, split where one section ends and the next
+    /// one starts.
+    fn mapped_bytes(&self, range: Range<PhysAddr>) -> Result<Vec<&[u8]>, DumpError> {
+        // sections that aren't loaded all claim to be at address zero, which `sections()` skips
+        let mut sections = self.sections();
+
+        let mut chunks = Vec::new();
+        let mut addr = range.start;
+        while addr < range.end {
+            let contents = |section: &Section| section.start..section.start + section.bytes().len();
+            let section = sections
+                .by_ref()
+                .find(|section| contents(section).contains(&addr))
+                .ok_or(DumpError::Unmapped(addr))?;
+
+            let end = range.end.min(contents(section).end);
+            chunks.push(&section.bytes()[addr - section.start..end - section.start]);
+            addr = end;
+        }
+
+        Ok(chunks)
+    }
+
+    /// Write the bytes of a section or an address range in the canonical format of
+    /// `hexdump -C`, with the address of each line.
+    ///
+    /// ```text
+    /// 0000000000402000  68 65 6c 6c 6f 2c 20 77  6f 72 6c 64 00 01 02 03  |hello, world....|
+    /// 0000000000402010  61 62 63 00                                       |abc.|
+    /// 0000000000402014
+    /// ```
+    ///
+    /// Nothing is written if any part of the range isn't mapped. The bytes are written as
+    /// they're formatted, so large sections don't have to fit in memory twice.
+    pub fn write_hexdump<W: Write>(&self, w: &mut W, target: DumpTarget) -> Result<(), DumpError> {
+        let (start, chunks) = match target {
+            // sections that aren't loaded, like debug info, can still be dumped by name
+            DumpTarget::Section(name) => {
+                let section = self
+                    .sections
+                    .iter()
+                    .find(|section| section.name == name)
+                    .ok_or_else(|| DumpError::UnknownSection(name.to_string()))?;
+
+                (section.start, vec![section.bytes()])
+            }
+            DumpTarget::Range(range) => (range.start, self.mapped_bytes(range)?),
+        };
+
+        if chunks.iter().all(|chunk| chunk.is_empty()) {
+            return Err(DumpError::Empty);
+        }
+
+        write_lines(w, start, chunks)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_lines, DumpError, DumpTarget};
+    use crate::Processor;
+    use std::path::Path;
+
+    /// Statically linked x86_64 executable with strings, see `fixtures/strings.s`.
+    const STRINGS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/strings");
+
+    /// Statically linked x86_64 executable with debug info, see `fixtures/lines.c`.
+    const LINES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/lines");
+
+    #[test]
+    fn lines() {
+        let mut out = Vec::new();
+        let zeroes = [0; 64];
+        write_lines(&mut out, 0x1000, [&zeroes[..40], &b"abc\n"[..]]).unwrap();
+
+        let expected = "\
+0000000000001000  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|
+*
+0000000000001020  00 00 00 00 00 00 00 00  61 62 63 0a              |........abc.|
+000000000000102c
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn sections() {
+        let processor = Processor::parse(Path::new(STRINGS)).unwrap();
+
+        let mut out = Vec::new();
+        processor.write_hexdump(&mut out, DumpTarget::Section(".rodata")).unwrap();
+        let expected = "\
+0000000000402000  68 65 6c 6c 6f 2c 20 77  6f 72 6c 64 00 01 02 03  |hello, world....|
+0000000000402010  61 62 63 00 65 72 72 6f  72 3a 20 25 73 0a 00     |abc.error: %s..|
+000000000040201f
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let mut out = Vec::new();
+        let range = DumpTarget::Range(0x402004..0x402008);
+        processor.write_hexdump(&mut out, range).unwrap();
+        let expected = "\
+0000000000402004  6f 2c 20 77                                       |o, w|
+0000000000402008
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let mut out = Vec::new();
+        processor.write_hexdump(&mut out, DumpTarget::Section(".shstrtab")).unwrap();
+        let expected = "\
+0000000000000000  00 2e 73 79 6d 74 61 62  00 2e 73 74 72 74 61 62  |..symtab..strtab|
+0000000000000010  00 2e 73 68 73 74 72 74  61 62 00 2e 74 65 78 74  |..shstrtab..text|
+0000000000000020  00 2e 72 6f 64 61 74 61  00 2e 64 61 74 61 00     |..rodata..data.|
+000000000000002f
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let result = processor.write_hexdump(&mut Vec::new(), DumpTarget::Section(".nope"));
+        assert!(matches!(result, Err(DumpError::UnknownSection(..))));
+
+        // .rodata and .data aren't adjacent
+        let range = DumpTarget::Range(0x402010..0x403020);
+        let result = processor.write_hexdump(&mut Vec::new(), range);
+        assert!(matches!(result, Err(DumpError::Unmapped(0x40201f))));
+    }
+
+    #[test]
+    fn compressed() {
+        let dump = |path: &str| {
+            let processor = Processor::parse(Path::new(path)).unwrap();
+            let mut out = Vec::new();
+            processor.write_hexdump(&mut out, DumpTarget::Section(".debug_info")).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        // compressed sections are dumped as they are once decompressed
+        let expected = dump(LINES);
+        assert!(expected.ends_with("\n00000000000000ad\n"), "{expected}");
+        for fixture in ["lines-zlib", "lines-zstd"] {
+            let path = format!("{}/fixtures/{fixture}", env!("CARGO_MANIFEST_DIR"));
+            assert_eq!(dump(&path), expected, "{fixture}");
+        }
+    }
+}
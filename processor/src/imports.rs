@@ -0,0 +1,182 @@
+use crate::Processor;
+use binformat::{Export, Import};
+use std::io::{self, Write};
+
+/// Symbols imported from a single library.
+#[derive(Debug, Clone)]
+pub struct LibraryImports<'a> {
+    /// Name of the library, empty for ELF symbols that aren't bound to one through symbol
+    /// versioning, which are looked up in every library.
+    pub library: &'a str,
+
+    /// Symbols in the order the object lists them.
+    pub imports: Vec<&'a Import>,
+}
+
+impl Processor {
+    /// Symbols the object takes from other modules, in the order the object lists them.
+    /// Their names are mangled, see [`Processor::demangle`].
+    pub fn imports(&self) -> &[Import] {
+        &self.imports
+    }
+
+    /// Symbols the object makes available to other modules, with mangled names.
+    pub fn exports(&self) -> &[Export] {
+        &self.exports
+    }
+
+    /// Imports grouped by the library they're taken from, in the order the libraries are
+    /// loaded, followed by the symbols that aren't bound to a library.
+    pub fn imports_by_library(&self) -> Vec<LibraryImports> {
+        let mut groups: Vec<LibraryImports> = Vec::new();
+        for import in &self.imports {
+            match groups.iter_mut().find(|group| group.library == import.module) {
+                Some(group) => group.imports.push(import),
+                None => {
+                    groups.push(LibraryImports { library: &import.module, imports: vec![import] })
+                }
+            }
+        }
+
+        let libraries = &self.dependencies.libraries;
+        groups.sort_by_key(|group| match group.library {
+            "" => usize::MAX,
+            library => libraries.iter().position(|lib| lib == library).unwrap_or(usize::MAX - 1),
+        });
+
+        groups
+    }
+
+    /// Write the imports grouped by library, along with the version they're required to have.
+    ///
+    /// ```text
+    /// libc.so.6:
+    ///   puts@GLIBC_2.2.5
+    ///
+    /// Any library:
+    ///   __gmon_start__
+    /// ```
+    pub fn write_imports<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let groups = self.imports_by_library();
+        if groups.is_empty() {
+            return writeln!(w, "The object doesn't import any symbols.");
+        }
+
+        for (idx, group) in groups.iter().enumerate() {
+            if idx != 0 {
+                writeln!(w)?;
+            }
+
+            match group.library {
+                "" => writeln!(w, "Any library:")?,
+                library => writeln!(w, "{library}:")?,
+            }
+
+            for import in &group.imports {
+                let name = self.demangle(&import.name);
+                match &import.version {
+                    Some(version) => writeln!(w, "  {name}@{version}")?,
+                    None => writeln!(w, "  {name}")?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the exports with their address, and their ordinal if the format has them.
+    ///
+    /// ```text
+    /// Ordinal Address          Name
+    ///       1 0000000140001004 _start
+    ///       2 0000000140001000 add
+    /// ```
+    pub fn write_exports<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        if self.exports.is_empty() {
+            return writeln!(w, "The object doesn't export any symbols.");
+        }
+
+        let ordinals = self.exports.iter().any(|export| export.ordinal.is_some());
+        if ordinals {
+            write!(w, "Ordinal ")?;
+        }
+        writeln!(w, "{:16} Name", "Address")?;
+
+        for export in &self.exports {
+            if ordinals {
+                match export.ordinal {
+                    Some(ordinal) => write!(w, "{ordinal:>7} ")?,
+                    None => write!(w, "{:7} ", "")?,
+                }
+            }
+
+            // forwarded exports and wasm's non-function exports aren't located anywhere
+            let name = self.demangle(&export.name);
+            match export.addr {
+                Some(addr) => writeln!(w, "{addr:016x} {name}")?,
+                None => writeln!(w, "{:16} {name}", "")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Processor;
+    use std::path::Path;
+
+    /// Dynamically linked x86_64 executable, see `fixtures/dynamic.c`.
+    const DYNAMIC: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/dynamic");
+
+    /// PE executable importing from KERNEL32.dll, see `fixtures/tiny-pe.s`.
+    const PE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/tiny.exe");
+
+    #[test]
+    fn elf() {
+        let processor = Processor::parse(Path::new(DYNAMIC)).unwrap();
+
+        let groups = processor.imports_by_library();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].library, "libc.so.6");
+        assert_eq!(groups[1].library, "");
+
+        let mut out = Vec::new();
+        processor.write_imports(&mut out).unwrap();
+        let expected = "\
+libc.so.6:
+  __libc_start_main@GLIBC_2.34
+  puts@GLIBC_2.2.5
+  __cxa_finalize@GLIBC_2.2.5
+
+Any library:
+  _ITM_deregisterTMCloneTable
+  __gmon_start__
+  _ITM_registerTMCloneTable
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let mut out = Vec::new();
+        processor.write_exports(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "The object doesn't export any symbols.\n");
+    }
+
+    #[test]
+    fn pe() {
+        let processor = Processor::parse(Path::new(PE)).unwrap();
+
+        let mut out = Vec::new();
+        processor.write_imports(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "KERNEL32.dll:\n  ExitProcess\n");
+
+        let mut out = Vec::new();
+        processor.write_exports(&mut out).unwrap();
+        let expected = "\
+Ordinal Address          Name
+      1 0000000140001004 _start
+      2 0000000140001000 add
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+}
@@ -0,0 +1,234 @@
+use object::read::File as ObjectFile;
+use object::{Endian, Endianness, Object, ObjectSection, RelocationKind};
+use processor_shared::{AddressMap, Addressed, PhysAddr, Section, SectionKind};
+
+/// Shortest run of pointers into code that isn't referenced by an instruction, but is still
+/// assumed to be a jump table.
+const MIN_TABLE_LEN: usize = 4;
+
+/// Word of data embedded in a code section, e.g. an entry of a jump table or a literal pool.
+#[derive(Debug, Clone, Copy)]
+pub struct InlineWord {
+    /// Width of the word in bytes.
+    pub size: usize,
+
+    /// Value of the word, usually an address.
+    pub value: u64,
+}
+
+/// Heuristics for finding data that compilers embed in code sections.
+pub(crate) struct InlineDataScanner<'a> {
+    sections: &'a [Section],
+
+    /// Width of a pointer in bytes.
+    width: usize,
+
+    endianness: Endianness,
+
+    /// Whether the lowest bit of a code pointer selects the instruction set, like on ARM.
+    interworking: bool,
+}
+
+impl<'a> InlineDataScanner<'a> {
+    pub fn new(
+        sections: &'a [Section],
+        width: usize,
+        endianness: Endianness,
+        interworking: bool,
+    ) -> Self {
+        Self {
+            sections,
+            width,
+            endianness,
+            interworking,
+        }
+    }
+
+    fn code_section(&self, addr: PhysAddr) -> Option<&'a Section> {
+        self.sections
+            .iter()
+            .find(|s| s.kind == SectionKind::Code && (s.start..s.end).contains(&addr))
+    }
+
+    fn read(&self, addr: PhysAddr) -> Option<u64> {
+        let bytes = self.code_section(addr)?.bytes_by_addr(addr, self.width);
+        match *bytes {
+            [a, b, c, d] => Some(self.endianness.read_u32_bytes([a, b, c, d]) as u64),
+            [a, b, c, d, e, f, g, h] => {
+                Some(self.endianness.read_u64_bytes([a, b, c, d, e, f, g, h]))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether `value` could be the address of an instruction. Null never is.
+    fn is_code_ptr(&self, value: u64) -> bool {
+        let value = if self.interworking { value & !1 } else { value };
+        value != 0 && self.code_section(value as PhysAddr).is_some()
+    }
+
+    fn word(&self, addr: PhysAddr) -> Option<Addressed<InlineWord>> {
+        let value = self.read(addr)?;
+        Some(Addressed {
+            addr,
+            item: InlineWord {
+                size: self.width,
+                value,
+            },
+        })
+    }
+
+    /// Number of consecutive pointers into code starting at `addr`.
+    fn run_len(&self, addr: PhysAddr) -> usize {
+        let mut len = 0;
+        while let Some(value) = self.read(addr + len * self.width) {
+            if !self.is_code_ptr(value) {
+                break;
+            }
+
+            len += 1;
+        }
+
+        len
+    }
+
+    /// Aligned runs of pointers into code, long enough to be a jump table.
+    pub fn pointer_runs(&self) -> AddressMap<InlineWord> {
+        let mut words = AddressMap::default();
+
+        for section in self.sections.iter().filter(|s| s.kind == SectionKind::Code) {
+            let mut addr = section.start.next_multiple_of(self.width);
+            while addr < section.end {
+                let len = self.run_len(addr);
+                if len >= MIN_TABLE_LEN {
+                    let table = (0..len).filter_map(|idx| self.word(addr + idx * self.width));
+                    words.mapping.extend(table);
+                }
+
+                addr += len.max(1) * self.width;
+            }
+        }
+
+        words
+    }
+
+    /// Pointer-sized words in code that get an absolute address written to them by a relocation.
+    ///
+    /// This isn't meaningful on x86, where relocations also patch the immediates of instructions.
+    pub fn relocated_words(&self, obj: &ObjectFile) -> AddressMap<InlineWord> {
+        let mut words = AddressMap::default();
+        let mut push = |addr: PhysAddr, kind: RelocationKind, size: u8| {
+            if kind == RelocationKind::Absolute && size as usize == self.width * 8 {
+                words.mapping.extend(self.word(addr));
+            }
+        };
+
+        // executables and shared libraries
+        if let Some(relocations) = obj.dynamic_relocations() {
+            for (addr, reloc) in relocations {
+                push(addr as PhysAddr, reloc.kind(), reloc.size());
+            }
+        }
+
+        // relocatable objects, where every section starts at zero
+        for section in obj.sections().filter(|s| s.kind() == object::SectionKind::Text) {
+            for (offset, reloc) in section.relocations() {
+                let addr = section.address() + offset;
+                push(addr as PhysAddr, reloc.kind(), reloc.size());
+            }
+        }
+
+        words
+    }
+
+    /// Data loaded from `addr` in code by an instruction, either a table of pointers or a single
+    /// literal if `literal_pools` is set.
+    pub fn loaded_words(&self, addr: PhysAddr, literal_pools: bool) -> Vec<Addressed<InlineWord>> {
+        if self.code_section(addr).is_none() {
+            return Vec::new();
+        }
+
+        let len = match self.run_len(addr) {
+            0 | 1 if literal_pools => 1,
+            0 | 1 => 0,
+            len => len,
+        };
+
+        (0..len).filter_map(|idx| self.word(addr + idx * self.width)).collect()
+    }
+}
+
+/// Whether any word in the sorted `words` overlaps with `addr..addr + len`.
+pub(crate) fn overlaps(words: &AddressMap<InlineWord>, addr: PhysAddr, len: usize) -> bool {
+    let idx = words.partition_point(|word| word.addr < addr + len);
+    match idx.checked_sub(1) {
+        Some(idx) => words[idx].addr + words[idx].item.size > addr,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{overlaps, InlineDataScanner};
+    use object::read::File as ObjectFile;
+    use object::{Endianness, Object, ObjectSection};
+    use processor_shared::{Section, SectionKind};
+
+    /// `switch` statement compiled at -O2 for armv7, see `fixtures/switch.ll`.
+    static SWITCH: &[u8] = include_bytes!("../fixtures/switch.o");
+
+    fn text(obj: &ObjectFile<'static>) -> Section {
+        let text = obj.section_by_name(".text").unwrap();
+        let bytes = text.data().unwrap();
+        let start = text.address() as usize;
+
+        Section::new(
+            ".text".to_string(),
+            "PROGBITS",
+            SectionKind::Code,
+            bytes,
+            start,
+            start + bytes.len(),
+        )
+    }
+
+    #[test]
+    fn jump_table() {
+        let obj = ObjectFile::parse(SWITCH).unwrap();
+        let sections = [text(&obj)];
+        let scanner = InlineDataScanner::new(&sections, 4, Endianness::Little, true);
+
+        // `ldr pc, [r3, r0, lsl #2]` is followed by a table of the six cases
+        let targets = [0x2c, 0x34, 0x3c, 0x44, 0x4c, 0x54];
+        let table: Vec<_> = (0..6).map(|idx| 0x14 + idx * 4).collect();
+
+        let runs = scanner.pointer_runs();
+        assert_eq!(runs.iter().map(|word| word.addr).collect::<Vec<_>>(), table);
+        assert_eq!(runs.iter().map(|word| word.item.value).collect::<Vec<_>>(), targets);
+
+        let relocated = scanner.relocated_words(&obj);
+        assert_eq!(relocated.iter().map(|word| word.addr).collect::<Vec<_>>(), table);
+
+        let loaded = scanner.loaded_words(0x14, false);
+        assert_eq!(loaded.iter().map(|word| word.addr).collect::<Vec<_>>(), table);
+
+        // the instructions around the table are left alone
+        assert!(!overlaps(&runs, 0x10, 4));
+        assert!(overlaps(&runs, 0x28, 4));
+        assert!(!overlaps(&runs, 0x2c, 4));
+    }
+
+    #[test]
+    fn literal_pool() {
+        let obj = ObjectFile::parse(SWITCH).unwrap();
+        let sections = [text(&obj)];
+        let scanner = InlineDataScanner::new(&sections, 4, Endianness::Little, true);
+
+        // `bx lr` isn't a pointer, but is data when loaded by a pc relative `ldr`
+        assert!(scanner.loaded_words(0x30, false).is_empty());
+        assert_eq!(scanner.loaded_words(0x30, true)[0].item.value, 0xe12fff1e);
+
+        // outside of code
+        assert!(scanner.loaded_words(0x1000, true).is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::xrefs::XrefMap;
+use debugvault::Index;
+use decoder::XrefKind;
+use object::Architecture;
+use processor_shared::PhysAddr;
+
+/// Whether an instruction is filler that compilers put in between functions to align them.
+pub(crate) fn is_padding(arch: Architecture, bytes: &[u8]) -> bool {
+    if bytes.iter().all(|&byte| byte == 0) {
+        return true;
+    }
+
+    match arch {
+        Architecture::X86_64 | Architecture::X86_64_X32 | Architecture::I386 => {
+            // `nop`, `int3` and multi-byte nops like `66 2e 0f 1f 84 00 00 00 00 00`
+            let start = bytes.iter().position(|&b| b != 0x66 && b != 0x2e).unwrap_or(0);
+            let bytes = &bytes[start..];
+            bytes == [0x90] || bytes == [0xcc] || bytes.starts_with(&[0x0f, 0x1f])
+        }
+        Architecture::Riscv32 | Architecture::Riscv64 => {
+            bytes == [0x13, 0x00, 0x00, 0x00] || bytes == [0x01, 0x00]
+        }
+        Architecture::Arm => bytes == [0x00, 0xf0, 0x20, 0xe3] || bytes == [0x00, 0xbf],
+        Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => bytes == [0x1f, 0x20, 0x03, 0xd5],
+        Architecture::PowerPc | Architecture::PowerPc64 => {
+            bytes == [0x60, 0x00, 0x00, 0x00] || bytes == [0x00, 0x00, 0x00, 0x60]
+        }
+        _ => false,
+    }
+}
+
+/// Names for functions and branch targets that the symbol table doesn't name, `sub_401a20` for
+/// functions and `loc_401a20` for targets local to a function. Returned as
+/// `(addr, name, local)`, sorted by address.
+///
+/// `insts` are the addresses of the decoded instructions in order, along with whether they're a
+/// terminator and whether they're padding. `tables` are the sorted targets of jump tables.
+///
+/// Functions are found by being called. Functions that are only called indirectly are guessed
+/// to start at an `alignment` aligned instruction that follows a terminator and possibly some
+/// padding, that nothing branches to.
+pub(crate) fn generate(
+    insts: &[(PhysAddr, bool, bool)],
+    xrefs: &XrefMap,
+    tables: &[PhysAddr],
+    index: &Index,
+    alignment: usize,
+) -> Vec<(PhysAddr, String, bool)> {
+    let decoded = |addr: &PhysAddr| insts.binary_search_by_key(addr, |(addr, ..)| *addr).is_ok();
+    let referenced_by = |addr: PhysAddr, kind: XrefKind| {
+        xrefs.get(addr as u64).iter().any(|xref| xref.kind == kind)
+    };
+
+    let mut functions = Vec::new();
+    let mut after_terminator = false;
+    for &(addr, is_terminator, is_padding) in insts {
+        let in_table = tables.binary_search(&addr).is_ok();
+        let branched_to = referenced_by(addr, XrefKind::Jump) || in_table;
+        let named = index.get_sym_containing(addr).is_some();
+
+        let called = referenced_by(addr, XrefKind::Call);
+        if called || (after_terminator && !branched_to && !named && addr % alignment == 0) {
+            functions.push(addr);
+        }
+
+        // padding between functions doesn't end the search for the next one
+        if is_terminator {
+            after_terminator = true;
+        } else if !is_padding || branched_to || named {
+            after_terminator = false;
+        }
+    }
+
+    let mut locals: Vec<PhysAddr> = xrefs
+        .to
+        .iter()
+        .filter(|(_, xrefs)| xrefs.iter().any(|xref| xref.kind == XrefKind::Jump))
+        .map(|(&addr, _)| addr as PhysAddr)
+        .chain(tables.iter().copied())
+        .filter(|addr| decoded(addr) && functions.binary_search(addr).is_err())
+        .collect();
+
+    locals.sort_unstable();
+    locals.dedup();
+
+    let mut names: Vec<_> = functions
+        .into_iter()
+        .map(|addr| (addr, format!("sub_{addr:x}"), false))
+        .chain(locals.into_iter().map(|addr| (addr, format!("loc_{addr:x}"), true)))
+        .filter(|(addr, ..)| index.get_sym_by_addr(*addr).is_none())
+        .collect();
+
+    names.sort_unstable_by_key(|(addr, ..)| *addr);
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate, is_padding};
+    use object::Architecture;
+    use crate::xrefs::XrefMap;
+    use debugvault::Index;
+    use decoder::XrefKind;
+
+    #[test]
+    fn called_and_branched_to() {
+        let insts = [
+            (0x1000, false, false),
+            (0x1004, false, false),
+            (0x1008, true, false),
+            (0x100c, true, false),
+        ];
+        let mut xrefs = XrefMap::default();
+        xrefs.insert(0x1000, 0x100c, XrefKind::Call);
+        xrefs.insert(0x1004, 0x1008, XrefKind::Jump);
+        // tail call
+        xrefs.insert(0x1008, 0x100c, XrefKind::Jump);
+        // outside of the decoded code
+        xrefs.insert(0x1008, 0x2000, XrefKind::Jump);
+
+        let labels = generate(&insts, &xrefs, &[], &Index::default(), 4);
+        assert_eq!(
+            labels,
+            [(0x1008, "loc_1008".to_string(), true), (0x100c, "sub_100c".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn after_terminator() {
+        let insts = [
+            (0x1000, true, false),
+            // padding
+            (0x1001, false, true),
+            (0x1002, false, true),
+            (0x1010, false, false),
+            (0x1014, true, false),
+            // reached through a jump table
+            (0x1020, true, false),
+            (0x1030, false, false),
+            // not preceded by a terminator
+            (0x1040, false, false),
+        ];
+
+        let mut index = Index::default();
+        index.insert_sized_func(0x1000, 0x1, "entry");
+
+        let labels = generate(&insts, &XrefMap::default(), &[0x1020], &index, 16);
+        assert_eq!(
+            labels,
+            [
+                (0x1010, "sub_1010".to_string(), false),
+                (0x1020, "loc_1020".to_string(), true),
+                (0x1030, "sub_1030".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn symbols_are_kept() {
+        let insts = [(0x1000, false, false), (0x1004, true, false), (0x1008, true, false)];
+        let mut xrefs = XrefMap::default();
+        xrefs.insert(0x1000, 0x1008, XrefKind::Call);
+        xrefs.insert(0x1004, 0x1004, XrefKind::Jump);
+
+        let mut index = Index::default();
+        index.insert_sized_func(0x1000, 0x8, "main");
+        index.insert_func(0x1008, "exit");
+
+        // branches within a named function still get a label
+        let labels = generate(&insts, &xrefs, &[], &index, 4);
+        assert_eq!(labels, [(0x1004, "loc_1004".to_string(), true)]);
+    }
+
+    #[test]
+    fn padding() {
+        assert!(is_padding(Architecture::X86_64, &[0x90]));
+        assert!(is_padding(Architecture::X86_64, &[0xcc]));
+        assert!(is_padding(Architecture::X86_64, &[0x66, 0x2e, 0x0f, 0x1f, 0x84, 0, 0, 0, 0, 0]));
+        assert!(is_padding(Architecture::X86_64, &[0x00, 0x00]));
+        assert!(!is_padding(Architecture::X86_64, &[0x31, 0xff]));
+        assert!(is_padding(Architecture::Riscv64, &[0x01, 0x00]));
+        assert!(!is_padding(Architecture::Riscv64, &[0x82, 0x80]));
+    }
+}
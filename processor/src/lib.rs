@@ -1,14 +1,36 @@
 mod fmt;
+mod backtrace;
 mod blocks;
+mod cfg;
+mod chunks;
+mod functions;
+mod hexdump;
+mod imports;
+mod listing;
+mod text;
+mod inline_data;
+mod labels;
+mod libs;
+mod relocations;
+mod source;
+mod strings;
+mod xrefs;
 
 use decoder::{Decodable, Decoded};
-use object::{Endianness, Object, ObjectSegment};
-use object::{Architecture, BinaryFormat};
+use object::{Endianness, Object, ObjectSection, ObjectSegment, ObjectSymbol};
+use object::{SymbolKind, SymbolSection};
+use object::{AddressSize, Architecture, BinaryFormat, ObjectKind};
 use object::read::File as ObjectFile;
 use processor_shared::{AddressMap, Addressed, PhysAddr, Section, SectionKind, Segment};
 use debugvault::Index;
-use tokenizing::Token;
-use binformat::{elf, macho, pe, RawSymbol};
+use tokenizing::{FormatterConfig, Token};
+use binformat::{archive, elf, macho, pe, Binding, RawSymbol};
+use binformat::TableSymbol;
+use binformat::{Dependencies, Identity, RelocationTable, SectionHeader, SegmentHeader};
+use binformat::notes::NoteSection;
+use binformat::wasm::WasmDebugInfo;
+use inline_data::InlineDataScanner;
+use relocations::CodeRelocations;
 
 use memmap2::Mmap;
 use x86_64::long_mode as x64;
@@ -16,10 +38,23 @@ use x86_64::protected_mode as x86;
 use arm::armv7 as armv7;
 use arm::armv8::a64 as aarch64;
 
+use std::collections::HashMap;
 use std::fs::File;
+use std::path::Path;
 use std::mem::ManuallyDrop;
 
 pub use blocks::{BlockContent, Block};
+pub use cfg::{BasicBlock, Cfg, Edge, EdgeKind};
+pub use functions::FunctionRange;
+pub use backtrace::Frame;
+pub use hexdump::{DumpError, DumpTarget};
+pub use imports::LibraryImports;
+pub use strings::MIN_STRING_LEN;
+pub use listing::ListingFormatter;
+pub use inline_data::InlineWord;
+pub use xrefs::{Xref, XrefMap};
+pub use binformat::{Export, Import, SymbolTable};
+pub use binformat::coredump::{CoreDump, MappedFile, Thread};
 
 /// FIXME: This is way too large and way too broad.
 ///        Especially since these are being started for any address with a faulty decoding.
@@ -30,6 +65,172 @@ pub enum Error {
     NotAnExecutable,
     DecompressionFailed(object::Error),
     UnknownArchitecture(object::Architecture),
+    MissingSlice(object::Architecture),
+    Archive(Vec<String>),
+    MissingMember(String),
+    Wasm(binformat::wasm::Error),
+}
+
+/// How code sections are walked when decoding instructions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DisassemblyMode {
+    /// Decode every code section from start to end.
+    #[default]
+    Linear,
+
+    /// Decode starting from the entrypoint and function symbols, following direct branches and
+    /// calls. Anything that isn't reached is left as data.
+    Recursive,
+}
+
+/// Order of the symbol table written by [`Processor::write_names`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolOrder {
+    /// Undefined symbols first, then by address.
+    #[default]
+    Address,
+
+    /// Largest symbols first.
+    Size,
+
+    /// By demangled name.
+    Name,
+}
+
+/// Symbols written by [`Processor::write_names`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolFilter {
+    #[default]
+    All,
+
+    /// Symbols the object defines, along with its exports.
+    Defined,
+
+    /// Symbols the object expects from elsewhere, along with its imports.
+    Undefined,
+}
+
+/// Pattern the names written by [`Processor::write_names`] have to match.
+#[derive(Debug, Clone)]
+pub enum NamePattern {
+    /// Names that contain the text.
+    Substring(String),
+
+    /// Names that the expression matches part of.
+    Regex(regex::Regex),
+}
+
+impl NamePattern {
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        regex::Regex::new(pattern).map(Self::Regex)
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        match self {
+            Self::Substring(text) => name.contains(text.as_str()),
+            Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+/// Options for [`Processor::write_names`].
+#[derive(Debug, Default, Clone)]
+pub struct NameOptions {
+    /// Order of the symbol table.
+    pub order: SymbolOrder,
+
+    /// Whether defined or undefined symbols are left out.
+    pub filter: SymbolFilter,
+
+    /// Only list the symbols of one table, like `.dynsym`, instead of merging them.
+    pub table: Option<SymbolTable>,
+
+    /// Only list names that match the pattern.
+    pub pattern: Option<NamePattern>,
+
+    /// Match the pattern against mangled names instead of demangled ones.
+    pub mangled: bool,
+}
+
+/// Options for [`Processor::parse_with`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ParseOptions {
+    /// Overrides whether 32-bit ARM code is decoded as `thumb` instead of picking the mode from
+    /// the entrypoint and mapping symbols.
+    pub thumb: Option<bool>,
+
+    /// How code sections are walked.
+    pub mode: DisassemblyMode,
+
+    /// Number of threads used for decoding code sections linearly, one per core if unset.
+    pub threads: Option<usize>,
+
+    /// Style instructions are formatted in.
+    pub style: FormatterConfig,
+
+    /// Treat the file as raw code instead of parsing it as an object.
+    pub raw: Option<RawBinary>,
+
+    /// Architecture of the object that's parsed from a universal Mach-O binary, the first one
+    /// if unset.
+    pub arch: Option<Architecture>,
+
+    /// Strip the hashes of demangled Rust symbols and shorten common C++ types, like
+    /// `std::string`.
+    pub simplify: bool,
+
+    /// Download the separate debug info of ELF objects that isn't installed from the servers
+    /// in `DEBUGINFOD_URLS`, which runs `curl`.
+    pub debuginfod: bool,
+
+    /// Leave code undecoded, for when only the object's headers, tables and symbols are read.
+    pub metadata_only: bool,
+}
+
+/// Layout of a binary that isn't wrapped in an object format, like a firmware dump or
+/// shellcode. The whole file is decoded as code.
+#[derive(Debug, Clone, Copy)]
+pub struct RawBinary {
+    pub arch: Architecture,
+    pub endianness: Endianness,
+
+    /// Address the first byte of the file is loaded at.
+    pub base: PhysAddr,
+}
+
+impl RawBinary {
+    /// Raw binary of the architecture named `arch`, loaded at `base`.
+    pub fn new(arch: &str, base: PhysAddr) -> Option<Self> {
+        let (arch, endianness) = architecture(arch)?;
+        Some(Self { arch, endianness, base })
+    }
+}
+
+/// Architectures raw code can be decoded as, by the name they're given on the command line.
+pub const ARCHITECTURES: &[(&str, Architecture, Endianness)] = &[
+    ("x86", Architecture::I386, Endianness::Little),
+    ("x86_64", Architecture::X86_64, Endianness::Little),
+    ("arm", Architecture::Arm, Endianness::Little),
+    ("aarch64", Architecture::Aarch64, Endianness::Little),
+    ("riscv32", Architecture::Riscv32, Endianness::Little),
+    ("riscv64", Architecture::Riscv64, Endianness::Little),
+    ("mips", Architecture::Mips, Endianness::Big),
+    ("mipsel", Architecture::Mips, Endianness::Little),
+    ("mips64", Architecture::Mips64, Endianness::Big),
+    ("mips64el", Architecture::Mips64, Endianness::Little),
+    ("powerpc", Architecture::PowerPc, Endianness::Big),
+    ("powerpc64", Architecture::PowerPc64, Endianness::Big),
+    ("powerpc64le", Architecture::PowerPc64, Endianness::Little),
+    ("bpf", Architecture::Bpf, Endianness::Little),
+    ("bpfeb", Architecture::Bpf, Endianness::Big),
+];
+
+/// Architecture and endianness named `name`, like `x86_64` or `mips64el`.
+pub fn architecture(name: &str) -> Option<(Architecture, Endianness)> {
+    ARCHITECTURES
+        .iter()
+        .find(|(arch_name, ..)| *arch_name == name)
+        .map(|&(_, arch, endianness)| (arch, endianness))
 }
 
 pub union Instruction {
@@ -37,14 +238,39 @@ pub union Instruction {
     x64: ManuallyDrop<x86_64::long_mode::Instruction>,
     riscv: ManuallyDrop<riscv::Instruction>,
     mips: ManuallyDrop<mips::Instruction>,
+    powerpc: ManuallyDrop<powerpc::Instruction>,
     armv7: ManuallyDrop<armv7::Instruction>,
     aarch64: ManuallyDrop<aarch64::Instruction>,
+    wasm: ManuallyDrop<wasm::Instruction>,
+    bpf: ManuallyDrop<bpf::Instruction>,
 }
 
 macro_rules! impl_recursion {
-    ($symbols:expr, $errors:expr, $instructions:expr, $sections:expr,
-     $max_instruction_width:expr, $decoder:expr, $arch:ident) => {{
-        $max_instruction_width = $decoder.max_width();
+    ($symbols:expr, $errors:expr, $instructions:expr, $sections:expr, $inline_data:expr,
+     $mode:expr, $roots:expr, $threads:expr, $max_instruction_width:expr, $decoder:expr,
+     $arch:ident) => {
+        impl_recursion!(
+            $symbols,
+            $errors,
+            $instructions,
+            $sections,
+            $inline_data,
+            $mode,
+            $roots,
+            $threads,
+            $max_instruction_width,
+            $decoder,
+            $arch,
+            |_, _| {}
+        )
+    };
+    ($symbols:expr, $errors:expr, $instructions:expr, $sections:expr, $inline_data:expr,
+     $mode:expr, $roots:expr, $threads:expr, $max_instruction_width:expr, $decoder:expr,
+     $arch:ident, $select_mode:expr) => {{
+        let decoder = $decoder;
+        let select_mode = $select_mode;
+
+        $max_instruction_width = decoder.max_width();
 
         let width_guess = if $max_instruction_width == 4 {
             4
@@ -52,75 +278,220 @@ macro_rules! impl_recursion {
             5
         };
 
-        for section in $sections.iter().filter(|s| s.kind == SectionKind::Code) {
-            let mut prev_inst = None;
-            let mut reader = decoder::Reader::new(section.bytes());
-            let mut ip = section.start;
+        match $mode {
+            DisassemblyMode::Linear => {
+                for section in $sections.iter().filter(|s| s.kind == SectionKind::Code) {
+                    log::complex!(
+                        w "[processor::recurse] analyzing section ",
+                        b &*section.name,
+                        w " <",
+                        g format!("{:x}", section.start),
+                        w "..",
+                        g format!("{:x}", section.end),
+                        w ">.",
+                    );
+                }
 
-            log::complex!(
-                w "[processor::recurse] analyzing section ",
-                b &*section.name,
-                w " <",
-                g format!("{:x}", section.start),
-                w "..",
-                g format!("{:x}", section.end),
-                w ">.",
-            );
+                let code_size = $sections
+                    .iter()
+                    .filter(|s| s.kind == SectionKind::Code)
+                    .map(|s| s.bytes().len())
+                    .sum::<usize>();
 
-            // guessing an average of 5 byte long instructions
-            log::PROGRESS.set("Decoding instructions", section.bytes().len() / width_guess);
+                // guessing an average of 5 byte long instructions
+                log::PROGRESS.set("Decoding instructions", code_size / width_guess);
 
-            loop {
-                // prefetch next cache line line
-                #[cfg(target_arch = "x86")]
-                unsafe {
-                    core::arch::x86::_mm_prefetch(
-                        reader.as_ptr() as *const i8,
-                        core::arch::x86::_MM_HINT_NTA
-                    );
-                }
+                let chunks = chunks::code_chunks(&*$sections, &$roots);
+                let next_chunk = std::sync::atomic::AtomicUsize::new(0);
+                let thread_count = chunks::thread_count($threads).min(chunks.len());
 
-                #[cfg(target_arch = "x86_64")]
-                unsafe {
-                    core::arch::x86_64::_mm_prefetch(
-                        reader.as_ptr() as *const i8,
-                        core::arch::x86_64::_MM_HINT_NTA
-                    );
-                }
+                // every thread keeps taking the next chunk of code, until there are none left
+                let decode_chunks = || {
+                    let mut decoded = Vec::new();
+
+                    loop {
+                        let idx = next_chunk.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        let (section, chunk) = match chunks.get(idx) {
+                            Some(chunk) => chunk,
+                            None => break,
+                        };
+
+                        let mut instructions = AddressMap::default();
+                        let mut errors = AddressMap::default();
+                        let mut prev_inst = None;
+
+                        // instructions can't cross into the next chunk
+                        let bytes = section.bytes_by_addr(chunk.start, chunk.end - chunk.start);
+                        let mut stream =
+                            decoder::InstructionStream::new(bytes, chunk.start, decoder);
 
-                match $decoder.decode(&mut reader) {
-                    Ok(mut instruction) => {
-                        instruction.update_rel_addrs(ip, prev_inst);
+                        loop {
+                            // prefetch next cache line line
+                            #[cfg(target_arch = "x86")]
+                            unsafe {
+                                core::arch::x86::_mm_prefetch(
+                                    stream.as_ptr() as *const i8,
+                                    core::arch::x86::_MM_HINT_NTA
+                                );
+                            }
+
+                            #[cfg(target_arch = "x86_64")]
+                            unsafe {
+                                core::arch::x86_64::_mm_prefetch(
+                                    stream.as_ptr() as *const i8,
+                                    core::arch::x86_64::_MM_HINT_NTA
+                                );
+                            }
+
+                            let ip = stream.ip();
+
+                            // skip over data so it doesn't desynchronize the decoding that follows
+                            if let Ok(idx) = $inline_data.search(ip) {
+                                if stream.seek(ip + $inline_data[idx].item.size).is_err() {
+                                    break;
+                                }
+
+                                prev_inst = None;
+                                continue;
+                            }
+
+                            select_mode(stream.decoder_mut(), ip);
+
+                            match stream.next() {
+                                Some((ip, Ok(mut instruction))) => {
+                                    instruction.update_rel_addrs(ip, prev_inst);
+
+                                    instructions.push(Addressed {
+                                        addr: ip,
+                                        item: Instruction {
+                                            $arch: std::mem::ManuallyDrop::new(instruction)
+                                        }
+                                    });
 
-                        let width = instruction.width();
-                        $instructions.push(Addressed {
-                            addr: ip,
-                            item: Instruction {
-                                $arch: std::mem::ManuallyDrop::new(instruction)
+                                    prev_inst = instructions.last().map(|inst| {
+                                        unsafe { &*inst.item.$arch }
+                                    });
+                                }
+                                Some((ip, Err(error))) => {
+                                    errors.push(Addressed {
+                                        addr: ip,
+                                        item: error
+                                    });
+                                    prev_inst = None;
+                                }
+                                None => break,
                             }
-                        });
 
-                        prev_inst = $instructions.last().map(|inst| {
-                            unsafe { &*inst.item.$arch }
-                        });
-                        ip += width;
+                            log::PROGRESS.step();
+                        }
+
+                        decoded.push((idx, instructions, errors));
                     }
-                    Err(error) => {
-                        if error.kind == decoder::ErrorKind::ExhaustedInput {
+
+                    decoded
+                };
+
+                let mut decoded = if thread_count <= 1 {
+                    decode_chunks()
+                } else {
+                    std::thread::scope(|s| {
+                        let threads: Vec<_> =
+                            (0..thread_count).map(|_| s.spawn(decode_chunks)).collect();
+
+                        threads.into_iter().flat_map(|thread| thread.join().unwrap()).collect()
+                    })
+                };
+
+                // stitch the chunks back together in order of their address
+                decoded.sort_unstable_by_key(|(idx, ..)| *idx);
+                for (_, instructions, errors) in decoded {
+                    $instructions.mapping.extend(instructions.mapping);
+                    $errors.mapping.extend(errors.mapping);
+                }
+            }
+            DisassemblyMode::Recursive => {
+                let code_size = $sections
+                    .iter()
+                    .filter(|s| s.kind == SectionKind::Code)
+                    .map(|s| s.bytes().len())
+                    .sum::<usize>();
+
+                // guessing an average of 5 byte long instructions
+                log::PROGRESS.set("Decoding instructions", code_size / width_guess);
+
+                let mut visited = std::collections::HashSet::new();
+                let mut queue: Vec<PhysAddr> = $roots;
+
+                while let Some(addr) = queue.pop() {
+                    let section = match $sections
+                        .iter()
+                        .find(|s| s.kind == SectionKind::Code && (s.start..s.end).contains(&addr))
+                    {
+                        Some(section) => section,
+                        None => continue,
+                    };
+
+                    let mut prev_inst = None;
+                    let mut stream = decoder::InstructionStream::new(
+                        section.bytes(),
+                        section.start,
+                        decoder
+                    );
+
+                    // the mode decides how instructions are aligned
+                    select_mode(stream.decoder_mut(), addr);
+                    if stream.seek(addr).is_err() {
+                        continue;
+                    }
+
+                    // decode until we hit a terminator or code that was already decoded
+                    loop {
+                        let ip = stream.ip();
+                        if $inline_data.search(ip).is_ok() || !visited.insert(ip) {
                             break;
                         }
 
-                        let width = error.size();
-                        $errors.push(Addressed {
-                            addr: ip,
-                            item: error
-                        });
-                        prev_inst = None;
-                        ip += width;
+                        select_mode(stream.decoder_mut(), ip);
+
+                        match stream.next() {
+                            Some((ip, Ok(mut instruction))) => {
+                                instruction.update_rel_addrs(ip, prev_inst);
+
+                                if let Some((target, kind)) = instruction.xref() {
+                                    if kind != decoder::XrefKind::Data {
+                                        queue.push(target);
+                                    }
+                                }
+
+                                let is_terminator = instruction.is_terminator();
+                                $instructions.push(Addressed {
+                                    addr: ip,
+                                    item: Instruction {
+                                        $arch: std::mem::ManuallyDrop::new(instruction)
+                                    }
+                                });
+
+                                prev_inst = $instructions.last().map(|inst| {
+                                    unsafe { &*inst.item.$arch }
+                                });
+
+                                if is_terminator {
+                                    break;
+                                }
+                            }
+                            Some((ip, Err(error))) => {
+                                $errors.push(Addressed {
+                                    addr: ip,
+                                    item: error
+                                });
+                                break;
+                            }
+                            None => break,
+                        }
+
+                        log::PROGRESS.step();
                     }
                 }
-
-                log::PROGRESS.step();
             }
         }
     }};
@@ -149,6 +520,27 @@ pub struct Processor {
     /// Object's segments sorted by address.
     segments: Vec<Segment>,
 
+    /// Object's section table, in the order it's stored.
+    section_headers: Vec<SectionHeader>,
+
+    /// Object's program headers, or segments if it doesn't have any.
+    segment_headers: Vec<SegmentHeader>,
+
+    /// Dynamic linker that loads the object.
+    interpreter: Option<String>,
+
+    /// Address the object expects to be loaded at.
+    image_base: Option<PhysAddr>,
+
+    /// Whether the object is an executable, a library, etc.
+    object_kind: ObjectKind,
+
+    /// Container format of the object, if it isn't raw code or a wasm module.
+    format: Option<BinaryFormat>,
+
+    /// Build-id and other facts that identify the object.
+    identity: Identity,
+
     /// Errors occurred in decoding instructions.
     /// Sorted by address.
     errors: AddressMap<decoder::Error>,
@@ -157,15 +549,67 @@ pub struct Processor {
     /// Sorted by address.
     instructions: AddressMap<Instruction>,
 
+    /// Jump tables and literals found in code sections.
+    /// Sorted by address.
+    inline_data: AddressMap<InlineWord>,
+
+    /// Symbols referred to by instructions of relocatable objects, by the section they patch.
+    relocations: CodeRelocations,
+
+    /// Object's relocation sections, in the order they're stored.
+    relocation_tables: Vec<RelocationTable>,
+
+    /// Object's note sections, in the order they're stored.
+    note_sections: Vec<NoteSection>,
+
+    /// Threads and memory of the process, if the object is a core dump.
+    core_dump: Option<CoreDump>,
+
+    /// Symbols the object takes from other modules.
+    imports: Vec<Import>,
+
+    /// Symbols the object makes available to other modules.
+    exports: Vec<Export>,
+
+    /// Symbols of the object's symbol table, in the order they're stored.
+    /// Like those of imports and exports, their names are only demangled once they're written,
+    /// see [`Processor::demangle`].
+    symbols: Vec<TableSymbol>,
+
+    /// Strings of the object's data sections.
+    /// Sorted by address.
+    strings: AddressMap<String>,
+
+    /// Libraries the object is linked against, in the order they're loaded.
+    dependencies: Dependencies,
+
+    /// Whether names are simplified when they're demangled.
+    simplify: bool,
+
     /// How many bytes an instruction given the architecture.
     max_instruction_width: usize,
 
     /// Function pointer to an [`Instruction`]'s implementation of [`Decoded::tokens`].
-    instruction_tokens: fn(&Instruction, &Index) -> Vec<Token>,
+    instruction_tokens: fn(&Instruction, &Index, FormatterConfig) -> Vec<Token>,
+
+    /// Style instructions are formatted in.
+    style: FormatterConfig,
 
     /// Function pointer to an [`Instruction`]'s implementation of [`Decoded::width`].
     instruction_width: fn(&Instruction) -> usize,
 
+    /// Function pointer to an [`Instruction`]'s implementation of [`Decoded::xref`].
+    instruction_xref: fn(&Instruction) -> Option<(usize, decoder::XrefKind)>,
+
+    /// Function pointer to an [`Instruction`]'s implementation of [`Decoded::is_terminator`].
+    instruction_is_terminator: fn(&Instruction) -> bool,
+
+    /// References to code and data made by the decoded instructions.
+    xrefs: XrefMap,
+
+    /// Source files of the line table that have been read.
+    sources: source::SourceCache,
+
     /// Target's instruction set.
     arch: Architecture,
 
@@ -173,45 +617,247 @@ pub struct Processor {
     endianness: Endianness,
 }
 
+/// Addresses at which ARM code switches between the `arm` (`$a`) and `thumb` (`$t`)
+/// instruction sets, sorted by address.
+fn arm_mapping_symbols(obj: &ObjectFile) -> Vec<(PhysAddr, bool)> {
+    let mut mapping = Vec::new();
+
+    for symbol in obj.symbols() {
+        let name = match symbol.name() {
+            Ok(name) => name,
+            Err(..) => continue,
+        };
+
+        // mapping symbols may have a suffix, e.g. `$t.12`
+        let thumb = match name.split('.').next() {
+            Some("$t") => true,
+            Some("$a") => false,
+            _ => continue,
+        };
+
+        mapping.push((symbol.address() as PhysAddr, thumb));
+    }
+
+    mapping.sort_unstable_by_key(|(addr, _)| *addr);
+    mapping
+}
+
+/// Symbols an object imports, exports and defines.
+fn object_names(obj: &ObjectFile) -> (Vec<Import>, Vec<Export>, Vec<TableSymbol>) {
+    // `object` doesn't know the library of ELF imports, nor about ordinals
+    let imports = match obj {
+        ObjectFile::Elf32(elf) => elf::imports(elf),
+        ObjectFile::Elf64(elf) => elf::imports(elf),
+        ObjectFile::Pe32(pe) => pe::imports(pe),
+        ObjectFile::Pe64(pe) => pe::imports(pe),
+        _ => obj
+            .imports()
+            .unwrap_or_default()
+            .iter()
+            .map(|import| Import {
+                module: String::from_utf8_lossy(import.library()).into_owned(),
+                name: String::from_utf8_lossy(import.name()).into_owned(),
+                version: None,
+            })
+            .collect(),
+    };
+
+    let exports = match obj {
+        ObjectFile::Pe32(pe) => pe::exports(pe),
+        ObjectFile::Pe64(pe) => pe::exports(pe),
+        _ => obj
+            .exports()
+            .unwrap_or_default()
+            .iter()
+            .map(|export| Export {
+                name: String::from_utf8_lossy(export.name()).into_owned(),
+                addr: Some(export.address() as PhysAddr),
+                ordinal: None,
+            })
+            .collect(),
+    };
+
+    let mut symbols: Vec<TableSymbol> = obj
+        .symbols()
+        .filter_map(|symbol| table_symbol(obj, &symbol, SymbolTable::Static))
+        .collect();
+
+    // symbols that are in both tables are only listed once
+    let mut listed: HashMap<(String, PhysAddr), usize> = HashMap::new();
+    for (idx, symbol) in symbols.iter().enumerate() {
+        listed.insert((symbol.name.clone(), symbol.addr), idx);
+    }
+
+    for symbol in obj.dynamic_symbols() {
+        let symbol = match table_symbol(obj, &symbol, SymbolTable::Dynamic) {
+            Some(symbol) => symbol,
+            None => continue,
+        };
+
+        match listed.get(&(symbol.name.clone(), symbol.addr)) {
+            Some(&idx) => symbols[idx].table = SymbolTable::Both,
+            None => symbols.push(symbol),
+        }
+    }
+
+    (imports, exports, symbols)
+}
+
+/// Symbol as listed by `--names`, unless it's nameless or refers to a section or file.
+fn table_symbol(
+    obj: &ObjectFile,
+    symbol: &object::read::Symbol,
+    table: SymbolTable,
+) -> Option<TableSymbol> {
+    if matches!(symbol.kind(), SymbolKind::Section | SymbolKind::File) {
+        return None;
+    }
+
+    let name = symbol.name().ok().filter(|name| !name.is_empty())?;
+    let binding = if symbol.is_weak() {
+        Binding::Weak
+    } else if symbol.is_global() {
+        Binding::Global
+    } else {
+        Binding::Local
+    };
+
+    let section = match symbol.section() {
+        SymbolSection::Undefined => None,
+        SymbolSection::Absolute => Some("ABS".to_string()),
+        SymbolSection::Common => Some("COM".to_string()),
+        SymbolSection::Section(index) => Some(match obj.section_by_index(index) {
+            Ok(section) => section.name().unwrap_or("unknown").to_string(),
+            Err(..) => "unknown".to_string(),
+        }),
+        _ => Some("unknown".to_string()),
+    };
+
+    Some(TableSymbol {
+        name: name.to_string(),
+        addr: symbol.address() as PhysAddr,
+        size: symbol.size(),
+        kind: symbol.kind(),
+        binding,
+        section,
+        table,
+    })
+}
+
+/// Static archive and the name of its member a path like `libfoo.a(bar.o)` refers to, unless
+/// there's a file at the path.
+fn split_archive_member(path: &Path) -> Option<(&Path, &str)> {
+    if path.exists() {
+        return None;
+    }
+
+    let (archive, member) = path.to_str()?.strip_suffix(')')?.rsplit_once('(')?;
+    Some((Path::new(archive), member))
+}
+
+/// Names of the members of the static archive at `path`, `None` if it isn't an archive.
+pub fn archive_members<P: AsRef<Path>>(path: P) -> Result<Option<Vec<String>>, Error> {
+    let binary = std::fs::read(path).map_err(Error::IO)?;
+    if !archive::is_archive(&binary) {
+        return Ok(None);
+    }
+
+    let members = archive::members(&binary)?;
+    Ok(Some(members.into_iter().map(|(name, _)| name).collect()))
+}
+
 impl Processor {
+    /// Parse the object at `path`, which can also be a member of a static archive given as
+    /// `libfoo.a(bar.o)`.
     pub fn parse<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
-        let file = std::fs::File::open(path.as_ref()).map_err(Error::IO)?;
+        Self::parse_with(path, ParseOptions::default())
+    }
+
+    /// Like [`Processor::parse`], with [`ParseOptions`] that change how the code gets decoded.
+    pub fn parse_with<P: AsRef<std::path::Path>>(
+        path: P,
+        options: ParseOptions,
+    ) -> Result<Self, Error> {
+        let (file_path, member) = match split_archive_member(path.as_ref()) {
+            Some((archive, member)) => (archive, Some(member)),
+            None => (path.as_ref(), None),
+        };
+
+        let file = std::fs::File::open(file_path).map_err(Error::IO)?;
         let mmap = unsafe { Mmap::map(&file).map_err(Error::IO)? };
         let binary: &'static [u8] = unsafe { std::mem::transmute(&mmap[..]) };
-        let obj = ObjectFile::parse(binary)?;
+        let binary = match (options.raw, member) {
+            (None, Some(member)) if archive::is_archive(binary) => {
+                match archive::members(binary)?.into_iter().find(|(name, _)| name == member) {
+                    Some((_, binary)) => binary,
+                    None => return Err(Error::MissingMember(member.to_string())),
+                }
+            }
+            (None, None) if archive::is_archive(binary) => {
+                let members = archive::members(binary)?;
+                return Err(Error::Archive(members.into_iter().map(|(name, _)| name).collect()));
+            }
+            _ => binary,
+        };
+        let binary = match options.raw {
+            None => match (macho::fat_slice(binary, options.arch)?, options.arch) {
+                (Some(binary), _) => binary,
+                (None, Some(arch)) => return Err(Error::MissingSlice(arch)),
+                (None, None) => return Err(Error::NotAnExecutable),
+            },
+            Some(_) => binary,
+        };
+        let mut wasm_module = match options.raw {
+            None if binary.starts_with(binformat::wasm::MAGIC) => {
+                Some(WasmDebugInfo::parse(binary).map_err(Error::Wasm)?)
+            }
+            _ => None,
+        };
+        let obj = match (options.raw, &wasm_module) {
+            (None, None) => Some(ObjectFile::parse(binary)?),
+            _ => None,
+        };
 
         let path = path.as_ref().to_path_buf();
         let now = std::time::Instant::now();
 
         let mut syms = AddressMap::default();
         let mut sections = Vec::new();
+        let mut dependencies = Dependencies::default();
+        let mut synthesized = Vec::new();
         match &obj {
-            object::File::MachO32(macho) => {
+            Some(object::File::MachO32(macho)) => {
                 let debug_info = macho::MachoDebugInfo::parse(macho)?;
+                dependencies.libraries.extend(debug_info.libraries().map(str::to_string));
                 sections.extend(debug_info.sections);
                 syms.extend(debug_info.syms);
             }
-            object::File::MachO64(macho) => {
+            Some(object::File::MachO64(macho)) => {
                 let debug_info = macho::MachoDebugInfo::parse(macho)?;
+                dependencies.libraries.extend(debug_info.libraries().map(str::to_string));
                 sections.extend(debug_info.sections);
                 syms.extend(debug_info.syms);
             }
-            object::File::Elf32(elf) => {
+            Some(object::File::Elf32(elf)) => {
+                dependencies = elf::dependencies(elf);
                 let debug_info = elf::ElfDebugInfo::parse(elf)?;
                 sections.extend(debug_info.sections);
                 syms.extend(debug_info.syms);
+                synthesized = debug_info.synthesized;
             }
-            object::File::Elf64(elf) => {
+            Some(object::File::Elf64(elf)) => {
+                dependencies = elf::dependencies(elf);
                 let debug_info = elf::ElfDebugInfo::parse(elf)?;
                 sections.extend(debug_info.sections);
                 syms.extend(debug_info.syms);
+                synthesized = debug_info.synthesized;
             }
-            object::File::Pe32(pe) => {
+            Some(object::File::Pe32(pe)) => {
                 let debug_info = pe::PeDebugInfo::parse(pe)?;
                 sections.extend(debug_info.sections);
                 syms.extend(debug_info.syms);
             }
-            object::File::Pe64(pe) => {
+            Some(object::File::Pe64(pe)) => {
                 let debug_info = pe::PeDebugInfo::parse(pe)?;
                 sections.extend(debug_info.sections);
                 syms.extend(debug_info.syms);
@@ -219,15 +865,52 @@ impl Processor {
             _ => {}
         }
 
+        if let Some(wasm_module) = &mut wasm_module {
+            syms.extend(std::mem::take(&mut wasm_module.syms));
+        }
+
         for section in sections.iter() {
             syms.push(Addressed {
                 addr: section.start,
-                item: RawSymbol { name: &section.name, module: None }
+                item: RawSymbol {
+                    name: &section.name,
+                    module: None,
+                    size: 0,
+                    plt: false,
+                    kind: SymbolKind::Section,
+                    global: false,
+                }
             });
         }
 
-        let index = Index::parse(&obj, &path, syms).map_err(Error::Debug)?;
-        let entrypoint = index.get_func_by_name("entry").unwrap_or(0);
+        let mut index = match &obj {
+            Some(obj) => Index::parse(obj, &path, syms, options.simplify, options.debuginfod)
+                .map_err(Error::Debug)?,
+            None => Index::from_syms(syms, options.simplify),
+        };
+
+        // addresses that no symbol spans are named after their section
+        index.insert_sections(
+            sections.iter().map(|section| (section.start..section.end, section.name.clone())),
+        );
+
+        // wasm functions without a name are named after their index
+        if let Some(wasm_module) = &wasm_module {
+            let names = wasm_module
+                .functions
+                .iter()
+                .enumerate()
+                .map(|(idx, &addr)| (addr, format!("func{idx}"), false))
+                .collect();
+
+            index.insert_generated(names);
+        }
+
+        let entrypoint = index
+            .get_func_by_name("entry")
+            .or(options.raw.map(|raw| raw.base))
+            .or(wasm_module.as_ref().and_then(|wasm_module| wasm_module.entrypoint))
+            .unwrap_or(0);
 
         if entrypoint != 0 {
             log::complex!(
@@ -237,8 +920,40 @@ impl Processor {
             );
         }
 
+        let section_headers: Vec<SectionHeader> = obj
+            .iter()
+            .flat_map(|obj| obj.sections())
+            .map(|section| SectionHeader::parse(&section))
+            .collect();
+
+        let (segment_headers, interpreter) = match &obj {
+            Some(object::File::Elf32(elf)) => elf::program_headers(elf),
+            Some(object::File::Elf64(elf)) => elf::program_headers(elf),
+            Some(obj) => {
+                let headers = obj.segments().map(|segment| SegmentHeader::parse(&segment));
+                (headers.collect(), None)
+            }
+            None => (Vec::new(), None),
+        };
+
+        // PE sections start a page after the image base
+        let image_base = match &obj {
+            Some(obj) if obj.format() == BinaryFormat::Pe => {
+                Some(obj.relative_address_base() as PhysAddr)
+            }
+            _ => segment_headers
+                .iter()
+                .filter(|header| header.load && header.file_size > 0)
+                .map(|header| header.addr)
+                .min(),
+        };
+
+        let object_kind = obj.as_ref().map_or(ObjectKind::Unknown, |obj| obj.kind());
+        let format = obj.as_ref().map(|obj| obj.format());
+        let identity = obj.as_ref().map(Identity::parse).unwrap_or_default();
+
         let mut segments = Vec::new();
-        for segment in obj.segments() {
+        for segment in obj.iter().flat_map(|obj| obj.segments()) {
             let name = segment.name()?.unwrap_or("unknown").to_string();
             let start = segment.address() as PhysAddr;
             let end = start + segment.size() as PhysAddr;
@@ -246,10 +961,29 @@ impl Processor {
             segments.push(Segment { name, start, end });
         }
 
+        if let Some(raw) = options.raw {
+            let end = raw.base + binary.len();
+            let section =
+                Section::new("raw".to_string(), "RAW", SectionKind::Code, binary, raw.base, end);
+
+            sections.push(section);
+            segments.push(Segment { name: "raw".to_string(), start: raw.base, end });
+        }
+
+        if let Some(wasm_module) = &mut wasm_module {
+            let end = binary.len();
+
+            sections.extend(std::mem::take(&mut wasm_module.sections));
+            segments.push(Segment { name: "module".to_string(), start: 0, end });
+        }
+
         segments.sort_unstable_by_key(|s| s.start);
         sections.sort_unstable_by_key(|s| s.start);
 
-        if sections.is_empty() {
+        // core dumps don't have sections either, but they're the memory of a process rather
+        // than code and are only read for the state of the process
+        let is_core = object_kind == ObjectKind::Core;
+        if let Some(obj) = obj.as_ref().filter(|_| sections.is_empty() && !is_core) {
             let base = if obj.format() == BinaryFormat::Pe {
                 0x1000
             } else {
@@ -271,7 +1005,7 @@ impl Processor {
             sections.push(section);
         }
 
-        if segments.is_empty() {
+        if let Some(obj) = obj.as_ref().filter(|_| segments.is_empty()) {
             let start = obj.relative_address_base() as PhysAddr;
             let end = start + binary.len();
             let segment = Segment {
@@ -283,48 +1017,162 @@ impl Processor {
             segments.push(segment);
         }
 
-        let arch = obj.architecture();
-        let (instruction_tokens, instruction_width) = unsafe {
+        let (arch, entry, endianness, is_64) = match (&obj, options.raw) {
+            (Some(obj), _) => {
+                (obj.architecture(), obj.entry() as PhysAddr, obj.endianness(), obj.is_64())
+            }
+            (None, Some(raw)) => {
+                let is_64 = raw.arch.address_size() == Some(AddressSize::U64);
+                (raw.arch, raw.base, raw.endianness, is_64)
+            }
+            // anything else that isn't an object is a wasm module
+            (None, None) => (Architecture::Wasm32, entrypoint, Endianness::Little, false),
+        };
+        let (instruction_tokens, instruction_width, instruction_xref, is_terminator) = unsafe {
             match arch {
                 Architecture::Riscv32 | Architecture::Riscv64 => (
                     std::mem::transmute(<riscv::Instruction as Decoded>::tokens as usize),
                     std::mem::transmute(<riscv::Instruction as Decoded>::width as usize),
+                    std::mem::transmute(<riscv::Instruction as Decoded>::xref as usize),
+                    std::mem::transmute(<riscv::Instruction as Decoded>::is_terminator as usize),
                 ),
                 Architecture::Mips | Architecture::Mips64 => (
                     std::mem::transmute(<mips::Instruction as Decoded>::tokens as usize),
                     std::mem::transmute(<mips::Instruction as Decoded>::width as usize),
+                    std::mem::transmute(<mips::Instruction as Decoded>::xref as usize),
+                    std::mem::transmute(<mips::Instruction as Decoded>::is_terminator as usize),
+                ),
+                Architecture::PowerPc | Architecture::PowerPc64 => (
+                    std::mem::transmute(<powerpc::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<powerpc::Instruction as Decoded>::width as usize),
+                    std::mem::transmute(<powerpc::Instruction as Decoded>::xref as usize),
+                    std::mem::transmute(<powerpc::Instruction as Decoded>::is_terminator as usize),
                 ),
                 Architecture::X86_64_X32 | Architecture::I386 => (
                     std::mem::transmute(<x86::Instruction as Decoded>::tokens as usize),
                     std::mem::transmute(<x86::Instruction as Decoded>::width as usize),
+                    std::mem::transmute(<x86::Instruction as Decoded>::xref as usize),
+                    std::mem::transmute(<x86::Instruction as Decoded>::is_terminator as usize),
                 ),
                 Architecture::X86_64 => (
                     std::mem::transmute(<x64::Instruction as Decoded>::tokens as usize),
                     std::mem::transmute(<x64::Instruction as Decoded>::width as usize),
+                    std::mem::transmute(<x64::Instruction as Decoded>::xref as usize),
+                    std::mem::transmute(<x64::Instruction as Decoded>::is_terminator as usize),
                 ),
                 Architecture::Arm => (
                     std::mem::transmute(<armv7::Instruction as Decoded>::tokens as usize),
                     std::mem::transmute(<armv7::Instruction as Decoded>::width as usize),
+                    std::mem::transmute(<armv7::Instruction as Decoded>::xref as usize),
+                    std::mem::transmute(<armv7::Instruction as Decoded>::is_terminator as usize),
                 ),
                 Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => (
                     std::mem::transmute(<aarch64::Instruction as Decoded>::tokens as usize),
                     std::mem::transmute(<aarch64::Instruction as Decoded>::width as usize),
+                    std::mem::transmute(<aarch64::Instruction as Decoded>::xref as usize),
+                    std::mem::transmute(<aarch64::Instruction as Decoded>::is_terminator as usize),
+                ),
+                Architecture::Wasm32 => (
+                    std::mem::transmute(<wasm::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<wasm::Instruction as Decoded>::width as usize),
+                    std::mem::transmute(<wasm::Instruction as Decoded>::xref as usize),
+                    std::mem::transmute(<wasm::Instruction as Decoded>::is_terminator as usize),
+                ),
+                Architecture::Bpf => (
+                    std::mem::transmute(<bpf::Instruction as Decoded>::tokens as usize),
+                    std::mem::transmute(<bpf::Instruction as Decoded>::width as usize),
+                    std::mem::transmute(<bpf::Instruction as Decoded>::xref as usize),
+                    std::mem::transmute(<bpf::Instruction as Decoded>::is_terminator as usize),
                 ),
                 arch => return Err(Error::UnknownArchitecture(arch)),
             }
         };
 
+        // where recursive descent starts, thumb functions have their lowest bit set
+        let mut roots: Vec<PhysAddr> = index.functions().map(|func| func.addr).collect();
+        roots.push(entrypoint);
+        roots.push(entry);
+        if arch == Architecture::Arm {
+            roots.iter_mut().for_each(|addr| *addr &= !1);
+        }
+
+        let pointer_width = if is_64 { 8 } else { 4 };
+        let scanner = InlineDataScanner::new(
+            &sections,
+            pointer_width,
+            endianness,
+            arch == Architecture::Arm,
+        );
+
+        // data in code that can be found before decoding anything, wasm and eBPF code can't
+        // contain any
+        let mut inline_data = match arch {
+            Architecture::Wasm32 | Architecture::Bpf => AddressMap::default(),
+            _ => scanner.pointer_runs(),
+        };
+        // relocations of x86 and eBPF code patch immediates, not words of data
+        if !matches!(
+            arch,
+            Architecture::X86_64 | Architecture::X86_64_X32 | Architecture::I386 | Architecture::Bpf
+        ) {
+            if let Some(obj) = &obj {
+                inline_data.extend(scanner.relocated_words(obj));
+            }
+        }
+
+        inline_data.sort_unstable();
+        inline_data.dedup();
+
+        let relocations = match &obj {
+            Some(obj) => CodeRelocations::parse(obj),
+            None => CodeRelocations::default(),
+        };
+        let relocation_tables = obj.as_ref().map(binformat::relocations).unwrap_or_default();
+        let note_sections = obj.as_ref().map(binformat::notes).unwrap_or_default();
+        let core_dump = obj.as_ref().and_then(binformat::core_dump);
+
+        let (imports, exports, mut symbols) = match (&obj, &mut wasm_module) {
+            (Some(obj), _) => object_names(obj),
+            (None, Some(wasm_module)) => (
+                std::mem::take(&mut wasm_module.imports),
+                std::mem::take(&mut wasm_module.exports),
+                Vec::new(),
+            ),
+            (None, None) => (Vec::new(), Vec::new(), Vec::new()),
+        };
+
+        // PLT entries and thunks that were given a name are listed after the symbol table
+        symbols.extend(synthesized);
+
+        for import in &imports {
+            let libraries = &mut dependencies.libraries;
+            if !import.module.is_empty() && !libraries.contains(&import.module) {
+                libraries.push(import.module.clone());
+            }
+        }
+
         let mut instructions = AddressMap::default();
         let mut errors = AddressMap::default();
         let max_instruction_width;
 
+        // the code a core dump maps is decoded from the object that dumped it
+        let mut no_sections = Vec::new();
+        let code_sections = match is_core || options.metadata_only {
+            true => &mut no_sections,
+            false => &mut sections,
+        };
+
         match arch {
             Architecture::Riscv32 => {
                 impl_recursion!(
                     &index,
                     &mut errors,
                     &mut instructions,
-                    &mut sections,
+                    code_sections,
+                    &inline_data,
+                    options.mode,
+                    roots,
+                    options.threads,
                     max_instruction_width,
                     riscv::Decoder { is_64: false },
                     riscv
@@ -335,7 +1183,11 @@ impl Processor {
                     &index,
                     &mut errors,
                     &mut instructions,
-                    &mut sections,
+                    code_sections,
+                    &inline_data,
+                    options.mode,
+                    roots,
+                    options.threads,
                     max_instruction_width,
                     riscv::Decoder { is_64: true },
                     riscv
@@ -346,18 +1198,47 @@ impl Processor {
                     &index,
                     &mut errors,
                     &mut instructions,
-                    &mut sections,
+                    code_sections,
+                    &inline_data,
+                    options.mode,
+                    roots,
+                    options.threads,
                     max_instruction_width,
-                    mips::Decoder::default(),
+                    mips::Decoder {
+                        is_64: arch == Architecture::Mips64,
+                        big_endian: endianness == Endianness::Big,
+                    },
                     mips
                 )
             }
+            Architecture::PowerPc | Architecture::PowerPc64 => {
+                impl_recursion!(
+                    &index,
+                    &mut errors,
+                    &mut instructions,
+                    code_sections,
+                    &inline_data,
+                    options.mode,
+                    roots,
+                    options.threads,
+                    max_instruction_width,
+                    powerpc::Decoder {
+                        is_64: arch == Architecture::PowerPc64,
+                        big_endian: endianness == Endianness::Big,
+                    },
+                    powerpc
+                )
+            }
             Architecture::X86_64_X32 | Architecture::I386 => {
                 impl_recursion!(
                     &index,
                     &mut errors,
                     &mut instructions,
-                    &mut sections,
+                    code_sections,
+                    &inline_data,
+                    options.mode,
+                    roots,
+                    options.threads,
                     max_instruction_width,
                     x86::Decoder::default(),
                     x86
@@ -368,21 +1249,46 @@ impl Processor {
                     &index,
                     &mut errors,
                     &mut instructions,
-                    &mut sections,
+                    code_sections,
+                    &inline_data,
+                    options.mode,
+                    roots,
+                    options.threads,
                     max_instruction_width,
                     x64::Decoder::default(),
                     x64
                 )
             }
             Architecture::Arm => {
+                let mapping = obj.as_ref().map(arm_mapping_symbols).unwrap_or_default();
+                let initial_thumb = options.thumb.unwrap_or(entry & 1 == 1);
+
                 impl_recursion!(
                     &index,
                     &mut errors,
                     &mut instructions,
-                    &mut sections,
+                    code_sections,
+                    &inline_data,
+                    options.mode,
+                    roots,
+                    options.threads,
                     max_instruction_width,
-                    armv7::Decoder::default(),
-                    armv7
+                    armv7::Decoder::default().with_thumb_mode(initial_thumb),
+                    armv7,
+                    |decoder: &mut armv7::Decoder, ip| {
+                        // mapping symbols only get followed if the mode wasn't overridden
+                        if options.thumb.is_some() {
+                            return;
+                        }
+
+                        // code isn't necessarily decoded in order, so find the closest mapping
+                        let is_thumb = match mapping.partition_point(|&(addr, _)| addr <= ip) {
+                            0 => initial_thumb,
+                            idx => mapping[idx - 1].1,
+                        };
+
+                        decoder.set_thumb_mode(is_thumb);
+                    }
                 )
             },
             Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => {
@@ -390,12 +1296,57 @@ impl Processor {
                     &index,
                     &mut errors,
                     &mut instructions,
-                    &mut sections,
+                    code_sections,
+                    &inline_data,
+                    options.mode,
+                    roots,
+                    options.threads,
                     max_instruction_width,
                     aarch64::Decoder::default(),
                     aarch64
                 )
             }
+            Architecture::Bpf => {
+                impl_recursion!(
+                    &index,
+                    &mut errors,
+                    &mut instructions,
+                    code_sections,
+                    &inline_data,
+                    options.mode,
+                    roots,
+                    options.threads,
+                    max_instruction_width,
+                    bpf::Decoder {
+                        big_endian: endianness == Endianness::Big,
+                    },
+                    bpf
+                )
+            }
+            Architecture::Wasm32 => {
+                let (functions, bodies) = match &wasm_module {
+                    Some(wasm_module) => (&wasm_module.functions[..], &wasm_module.bodies[..]),
+                    None => (&[][..], &[][..]),
+                };
+
+                impl_recursion!(
+                    &index,
+                    &mut errors,
+                    &mut instructions,
+                    code_sections,
+                    &inline_data,
+                    options.mode,
+                    roots,
+                    options.threads,
+                    max_instruction_width,
+                    wasm::Decoder { functions, at_body: false },
+                    wasm,
+                    |decoder: &mut wasm::Decoder, ip| {
+                        // function bodies start by declaring their locals
+                        decoder.at_body = bodies.binary_search(&ip).is_ok();
+                    }
+                )
+            }
             _ => unreachable!(),
         };
 
@@ -409,34 +1360,150 @@ impl Processor {
             w format!("{path:?}.")
         );
 
-        Ok(Self {
+        let strings = strings::scan(&sections, &section_headers, MIN_STRING_LEN);
+
+        let mut processor = Self {
             entrypoint,
             path,
             sections,
             segments,
+            section_headers,
+            segment_headers,
+            interpreter,
+            image_base,
+            object_kind,
+            format,
+            identity,
             errors,
             instructions,
+            inline_data,
+            relocations,
+            relocation_tables,
+            note_sections,
+            core_dump,
+            imports,
+            exports,
+            symbols,
+            strings,
+            dependencies,
+            simplify: options.simplify,
             index,
             _file: file,
             _mmap: mmap,
             max_instruction_width,
             instruction_tokens,
+            style: options.style,
             instruction_width,
+            instruction_xref,
+            instruction_is_terminator: is_terminator,
+            xrefs: XrefMap::default(),
+            sources: source::SourceCache::default(),
             arch,
-            endianness: obj.endianness(),
-        })
+            endianness,
+        };
+
+        // tables and literals only known by the instructions that load them
+        let scanner = InlineDataScanner::new(
+            &processor.sections,
+            pointer_width,
+            processor.endianness,
+            arch == Architecture::Arm,
+        );
+
+        let mut loaded = Vec::new();
+        for inst in processor.instructions.iter() {
+            if let Some((addr, decoder::XrefKind::Data)) = (processor.instruction_xref)(&inst.item) {
+                // functions have their address taken, but aren't data
+                if processor.index.get_sym_by_addr(addr).is_none() {
+                    loaded.extend(scanner.loaded_words(addr, arch == Architecture::Arm));
+                }
+            }
+        }
+
+        processor.inline_data.extend(AddressMap { mapping: loaded });
+        processor.inline_data.sort_unstable();
+        processor.inline_data.dedup();
+
+        // anything decoded from data is garbage
+        let words = &processor.inline_data;
+        let instruction_width = processor.instruction_width;
+        processor.instructions.retain_mut(|inst| {
+            let overlaps = inline_data::overlaps(words, inst.addr, instruction_width(&inst.item));
+            if overlaps {
+                unsafe { inst.item.drop_as(arch) };
+            }
+            !overlaps
+        });
+        processor.errors.retain(|err| !inline_data::overlaps(words, err.addr, err.item.size()));
+
+        for inst in processor.instructions.iter() {
+            if let Some((to, kind)) = (processor.instruction_xref)(&inst.item) {
+                processor.xrefs.insert(inst.addr as u64, to as u64, kind);
+            }
+        }
+
+        // every wasm function is already named, and branches don't go to addresses
+        if arch == Architecture::Wasm32 {
+            return Ok(processor);
+        }
+
+        // name functions and branch targets the symbol table doesn't name
+        let insts: Vec<_> = processor
+            .instructions
+            .iter()
+            .map(|inst| {
+                let is_terminator = (processor.instruction_is_terminator)(&inst.item);
+                let bytes = processor.instruction_bytes(inst.addr).unwrap_or_default();
+                (inst.addr, is_terminator, labels::is_padding(arch, bytes))
+            })
+            .collect();
+        let mut tables: Vec<_> = processor
+            .inline_data
+            .iter()
+            .map(|word| word.item.value as PhysAddr)
+            .map(|addr| if arch == Architecture::Arm { addr & !1 } else { addr })
+            .collect();
+        tables.sort_unstable();
+        tables.dedup();
+
+        let alignment = match arch {
+            Architecture::X86_64 | Architecture::X86_64_X32 | Architecture::I386 => 16,
+            Architecture::Bpf => 8,
+            _ => 4,
+        };
+
+        let names =
+            labels::generate(&insts, &processor.xrefs, &tables, &processor.index, alignment);
+        processor.index.insert_generated(names);
+
+        Ok(processor)
     }
 
     /// Relatively slow tokenization of an [`Instruction`].
     /// Xref's get resolved which requires some extra computation.
     pub fn instruction_tokens(&self, instruction: &Instruction, symbols: &Index) -> Vec<Token> {
-        (self.instruction_tokens)(instruction, symbols)
+        (self.instruction_tokens)(instruction, symbols, self.style)
+    }
+
+    /// ELF build-id of the object, which its separate debug info is looked up by.
+    pub fn build_id(&self) -> Option<&[u8]> {
+        self.identity.build_id.as_deref()
     }
 
     pub fn instruction_width(&self, instruction: &Instruction) -> usize {
         (self.instruction_width)(instruction)
     }
 
+    /// References to code and data, keyed by the address referred to.
+    pub fn xrefs(&self) -> &XrefMap {
+        &self.xrefs
+    }
+
+    /// Default listing layout, with a byte column wide enough for the target's instructions.
+    pub fn listing_formatter(&self) -> ListingFormatter {
+        ListingFormatter::new(self.max_instruction_width)
+    }
+
     pub fn error_by_addr(&self, addr: PhysAddr) -> Option<&decoder::Error> {
         match self.errors.search(addr) {
             Ok(idx) => Some(&self.errors[idx].item),
@@ -451,6 +1518,38 @@ impl Processor {
         }
     }
 
+    /// Jump table entry or literal embedded in code located at `addr`.
+    pub fn inline_data_by_addr(&self, addr: PhysAddr) -> Option<&InlineWord> {
+        match self.inline_data.search(addr) {
+            Ok(idx) => Some(&self.inline_data[idx].item),
+            Err(..) => None,
+        }
+    }
+
+    /// Raw encoding of the instruction, invalid instruction or inline data located at `addr`.
+    pub fn instruction_bytes(&self, addr: PhysAddr) -> Option<&[u8]> {
+        let width = match self.instruction_by_addr(addr) {
+            Some(inst) => self.instruction_width(inst),
+            None => match self.inline_data_by_addr(addr) {
+                Some(word) => word.size,
+                None => self.error_by_addr(addr)?.size(),
+            },
+        };
+
+        let section = self.section_by_addr(addr)?;
+        Some(section.bytes_by_addr(addr, width))
+    }
+
+    /// Iterate through all decoded instructions and the address they're located at.
+    pub fn instructions(&self) -> impl DoubleEndedIterator<Item = (PhysAddr, &Instruction)> {
+        self.instructions.iter().map(|inst| (inst.addr, &inst.item))
+    }
+
+    /// Threads, mapped files and memory of the process, if the object is a core dump.
+    pub fn core_dump(&self) -> Option<&CoreDump> {
+        self.core_dump.as_ref()
+    }
+
     pub fn segments(&self) -> impl DoubleEndedIterator<Item = &Segment> {
         self.segments.iter()
     }
@@ -483,25 +1582,41 @@ impl Processor {
             .find(|s| (s.start..=s.end).contains(&addr))
             .map(|s| &s.name as &str)
     }
+
+    /// Demangled name of an import, export or symbol, simplified if the object was parsed
+    /// with [`ParseOptions::simplify`].
+    pub fn demangle(&self, name: &str) -> String {
+        debugvault::demangle(name, self.simplify)
+    }
+}
+
+impl Instruction {
+    /// Drops the field of the union that was decoded for `arch`.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called once, with the architecture the instruction was decoded for.
+    unsafe fn drop_as(&mut self, arch: Architecture) {
+        match arch {
+            Architecture::X86_64 => ManuallyDrop::drop(&mut self.x64),
+            Architecture::X86_64_X32 | Architecture::I386 => ManuallyDrop::drop(&mut self.x86),
+            Architecture::Riscv64 | Architecture::Riscv32 => ManuallyDrop::drop(&mut self.riscv),
+            Architecture::Mips | Architecture::Mips64 => ManuallyDrop::drop(&mut self.mips),
+            Architecture::PowerPc | Architecture::PowerPc64 => {
+                ManuallyDrop::drop(&mut self.powerpc)
+            }
+            Architecture::Wasm32 => ManuallyDrop::drop(&mut self.wasm),
+            Architecture::Bpf => ManuallyDrop::drop(&mut self.bpf),
+            _ => {}
+        }
+    }
 }
 
 impl Drop for Processor {
     /// Required `Drop` impl as [`Instruction`]'s a non-copy union.
     fn drop(&mut self) {
         for Addressed { item: inst, .. } in self.instructions.iter_mut() {
-            match self.arch {
-                Architecture::X86_64 => unsafe { ManuallyDrop::drop(&mut inst.x64) },
-                Architecture::X86_64_X32 | Architecture::I386 => unsafe {
-                    ManuallyDrop::drop(&mut inst.x86)
-                },
-                Architecture::Riscv64 | Architecture::Riscv32 => unsafe {
-                    ManuallyDrop::drop(&mut inst.riscv)
-                },
-                Architecture::Mips | Architecture::Mips64 => unsafe {
-                    ManuallyDrop::drop(&mut inst.mips)
-                },
-                _ => {}
-            }
+            unsafe { inst.drop_as(self.arch) }
         }
     }
 }
@@ -0,0 +1,293 @@
+use crate::Processor;
+use binformat::{elf, Dependencies};
+use object::{Architecture, BinaryFormat, Object};
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Directories searched after everything else, like the dynamic linker does.
+const DEFAULT_DIRS: [&str; 4] = ["/lib64", "/usr/lib64", "/lib", "/usr/lib"];
+
+/// Cache of the libraries in the dynamic linker's trusted directories.
+const LD_SO_CACHE: &str = "/etc/ld.so.cache";
+
+/// Libraries listed in an `ld.so.cache`, as `(name, path)`.
+///
+/// Only the format used since glibc 2.2 is understood. Older caches embed it after the old one.
+fn parse_cache(data: &[u8]) -> Vec<(String, PathBuf)> {
+    const MAGIC: &[u8] = b"glibc-ld.so.cache1.1";
+    const HEADER_SIZE: usize = 48;
+    const ENTRY_SIZE: usize = 24;
+
+    let start = match data.windows(MAGIC.len()).position(|window| window == MAGIC) {
+        Some(start) => start,
+        None => return Vec::new(),
+    };
+
+    let u32_at = |offset: usize| -> Option<usize> {
+        let bytes = data.get(offset..offset + 4)?;
+        Some(u32::from_ne_bytes(bytes.try_into().unwrap()) as usize)
+    };
+
+    // strings are referred to by their offset from the start of the cache
+    let str_at = |offset: usize| -> Option<&str> {
+        let bytes = data.get(start + offset..)?;
+        let len = bytes.iter().position(|&b| b == 0)?;
+        std::str::from_utf8(&bytes[..len]).ok()
+    };
+
+    let count = u32_at(start + MAGIC.len()).unwrap_or(0);
+    let mut libraries = Vec::new();
+    for idx in 0..count {
+        let entry = start + HEADER_SIZE + idx * ENTRY_SIZE;
+        let (key, value) = match (u32_at(entry + 4), u32_at(entry + 8)) {
+            (Some(key), Some(value)) => (key, value),
+            _ => break,
+        };
+
+        if let (Some(name), Some(path)) = (str_at(key), str_at(value)) {
+            libraries.push((name.to_string(), PathBuf::from(path)));
+        }
+    }
+
+    libraries
+}
+
+/// Replace `$ORIGIN` in search paths with the directory of the object they're from.
+fn expand(dirs: &[String], origin: &Path) -> Vec<PathBuf> {
+    let origin = origin.to_string_lossy();
+    dirs.iter()
+        .map(|dir| PathBuf::from(dir.replace("${ORIGIN}", &origin).replace("$ORIGIN", &origin)))
+        .collect()
+}
+
+/// Directory of the object at `path`, which `$ORIGIN` refers to.
+fn origin(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if parent != Path::new("") => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Dependencies of the ELF object at `path`, if it's of the architecture `arch`.
+fn load(path: &Path, arch: Architecture) -> Option<Dependencies> {
+    let data = std::fs::read(path).ok()?;
+    match object::File::parse(&data[..]).ok()? {
+        object::File::Elf32(elf) if elf.architecture() == arch => Some(elf::dependencies(&elf)),
+        object::File::Elf64(elf) if elf.architecture() == arch => Some(elf::dependencies(&elf)),
+        _ => None,
+    }
+}
+
+/// Resolves libraries the way the dynamic linker does, without loading anything.
+struct Resolver {
+    arch: Architecture,
+
+    /// Directories of `LD_LIBRARY_PATH`.
+    env: Vec<PathBuf>,
+
+    /// Libraries of the `ld.so.cache`.
+    cache: Vec<(String, PathBuf)>,
+
+    /// Libraries already printed along with their dependencies.
+    seen: HashSet<PathBuf>,
+}
+
+impl Resolver {
+    fn new(arch: Architecture) -> Self {
+        let env = std::env::var("LD_LIBRARY_PATH").unwrap_or_default();
+        let env = env.split([':', ';']).filter(|dir| !dir.is_empty()).map(PathBuf::from);
+        let cache = std::fs::read(LD_SO_CACHE).map(|data| parse_cache(&data)).unwrap_or_default();
+
+        Self { arch, env: env.collect(), cache, seen: HashSet::new() }
+    }
+
+    /// Path to the library `name` of a matching architecture, searching `dirs` before the
+    /// cache and default directories.
+    fn find(&self, name: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+        let is_match = |path: &Path| path.is_file() && load(path, self.arch).is_some();
+
+        // names with a slash aren't searched for
+        if name.contains('/') {
+            let path = PathBuf::from(name);
+            return is_match(&path).then_some(path);
+        }
+
+        let cached = self.cache.iter().filter(|(key, _)| key == name).map(|(_, path)| path);
+        let defaults = DEFAULT_DIRS.iter().map(|dir| Path::new(dir).join(name));
+
+        dirs.iter()
+            .map(|dir| dir.join(name))
+            .chain(cached.cloned())
+            .chain(defaults)
+            .find(|path| is_match(path))
+    }
+
+    /// Write the libraries in `deps` and, the first time they're found, their dependencies.
+    ///
+    /// `inherited` are the `RPATH` directories of the objects that loaded this one.
+    fn write_tree<W: Write>(
+        &mut self,
+        w: &mut W,
+        deps: &Dependencies,
+        origin: &Path,
+        inherited: &[PathBuf],
+        depth: usize,
+    ) -> io::Result<()> {
+        let rpath = expand(&deps.rpath, origin);
+        let runpath = expand(&deps.runpath, origin);
+
+        // `RPATH` is ignored if there's a `RUNPATH`
+        let mut dirs = Vec::new();
+        if runpath.is_empty() {
+            dirs.extend(rpath.iter().chain(inherited).cloned());
+        }
+        dirs.extend(self.env.iter().chain(&runpath).cloned());
+
+        let inherited: Vec<PathBuf> = rpath.into_iter().chain(inherited.iter().cloned()).collect();
+        for library in &deps.libraries {
+            write!(w, "{:indent$}", "", indent = depth * 4)?;
+            write_library(w, library, deps)?;
+
+            let path = match self.find(library, &dirs) {
+                Some(path) => path,
+                None => {
+                    writeln!(w, " => not found")?;
+                    continue;
+                }
+            };
+
+            writeln!(w, " => {}", path.display())?;
+            if !self.seen.insert(path.clone()) {
+                continue;
+            }
+
+            if let Some(child) = load(&path, self.arch) {
+                self.write_tree(w, &child, &origin_of(&path), &inherited, depth + 1)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Directory of a resolved library, with symbolic links followed like the dynamic linker does.
+fn origin_of(path: &Path) -> PathBuf {
+    origin(&std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()))
+}
+
+/// Write the name of a library followed by the versions required from it.
+fn write_library<W: Write>(w: &mut W, library: &str, deps: &Dependencies) -> io::Result<()> {
+    write!(w, "{library}")?;
+
+    let versions = deps.versions_of(library);
+    if !versions.is_empty() {
+        write!(w, " ({})", versions.join(", "))?;
+    }
+
+    Ok(())
+}
+
+impl Processor {
+    /// Write the libraries the object is linked against, one per line, followed by its search
+    /// paths.
+    ///
+    /// ```text
+    /// libc.so.6 (GLIBC_2.2.5, GLIBC_2.34)
+    ///
+    /// RUNPATH: $ORIGIN/lib
+    /// ```
+    ///
+    /// If `recursive` is set, the libraries of ELF objects are resolved to a path, followed by
+    /// their own libraries.
+    ///
+    /// ```text
+    /// libc.so.6 (GLIBC_2.2.5, GLIBC_2.34) => /usr/lib/libc.so.6
+    ///     ld-linux-x86-64.so.2 (GLIBC_2.2.5, GLIBC_PRIVATE) => /usr/lib/ld-linux-x86-64.so.2
+    /// ```
+    pub fn write_libs<W: Write>(&self, w: &mut W, recursive: bool) -> io::Result<()> {
+        let deps = &self.dependencies;
+
+        if recursive && self.format == Some(BinaryFormat::Elf) {
+            let mut resolver = Resolver::new(self.arch);
+            resolver.seen.insert(self.path.clone());
+            return resolver.write_tree(w, deps, &origin_of(&self.path), &[], 0);
+        }
+
+        for library in &deps.libraries {
+            write_library(w, library, deps)?;
+            writeln!(w)?;
+        }
+
+        for (name, dirs) in [("RPATH", &deps.rpath), ("RUNPATH", &deps.runpath)] {
+            if !dirs.is_empty() {
+                write!(w, "\n{name}: {}\n", dirs.join(":"))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_cache, Resolver};
+    use crate::Processor;
+    use object::Architecture;
+    use std::path::{Path, PathBuf};
+
+    /// Dynamically linked x86_64 executable, see `fixtures/dynamic.c`.
+    const DYNAMIC: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/dynamic");
+
+    #[test]
+    fn versions() {
+        let processor = Processor::parse(Path::new(DYNAMIC)).unwrap();
+
+        let mut out = Vec::new();
+        processor.write_libs(&mut out, false).unwrap();
+        let expected = "\
+libc.so.6 (GLIBC_2.2.5, GLIBC_2.34)
+
+RUNPATH: $ORIGIN/lib
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn cache() {
+        let mut data = b"glibc-ld.so.cache1.1".to_vec();
+        data.extend(2u32.to_ne_bytes());
+        data.resize(48, 0);
+
+        // strings follow the two entries
+        for (key, value) in [(96u32, 106u32), (121, 131)] {
+            data.extend(0i32.to_ne_bytes());
+            data.extend(key.to_ne_bytes());
+            data.extend(value.to_ne_bytes());
+            data.resize(data.len() + 12, 0);
+        }
+        data.extend(b"libc.so.6\0/lib/libc.so.6\0libm.so.6\0/lib/libm.so.6\0");
+
+        assert_eq!(
+            parse_cache(&data),
+            [
+                ("libc.so.6".to_string(), PathBuf::from("/lib/libc.so.6")),
+                ("libm.so.6".to_string(), PathBuf::from("/lib/libm.so.6")),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_paths() {
+        let fixtures = Path::new(DYNAMIC).parent().unwrap();
+        let resolver = Resolver::new(Architecture::X86_64);
+
+        let dirs = super::expand(&["$ORIGIN".to_string()], fixtures);
+        assert_eq!(resolver.find("tiny", &dirs), Some(fixtures.join("tiny")));
+        assert_eq!(resolver.find("tiny", &[]), None);
+
+        // objects of another architecture aren't a match
+        let resolver = Resolver::new(Architecture::Aarch64);
+        assert_eq!(resolver.find("tiny", &dirs), None);
+    }
+}
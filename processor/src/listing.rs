@@ -0,0 +1,108 @@
+use config::CONFIG;
+use processor_shared::encode_hex_bytes_truncated;
+use tokenizing::{Token, TokenStream};
+
+/// Column layout of the assembly listing.
+///
+/// Lines are formatted as `address: bytes  instruction`, for example:
+/// `0000000000011a4c: 13 05 00 00   li a0, 0`.
+#[derive(Debug, Clone)]
+pub struct ListingFormatter {
+    /// Number of hex digits the address column is zero-padded to.
+    pub address_width: usize,
+
+    /// Number of bytes the byte column fits, longer encodings get truncated.
+    pub bytes_width: usize,
+
+    /// Whether to show the byte column.
+    pub show_bytes: bool,
+
+    /// Whether to show the source file and line instructions are compiled from, where the line
+    /// starts.
+    pub show_source: bool,
+
+    /// Whether to show the text of source lines after their file and line.
+    pub show_source_text: bool,
+}
+
+impl ListingFormatter {
+    pub fn new(bytes_width: usize) -> Self {
+        Self {
+            address_width: 16,
+            bytes_width,
+            show_bytes: true,
+            show_source: false,
+            show_source_text: false,
+        }
+    }
+
+    /// Address column, including the separator.
+    pub fn address(&self, stream: &mut TokenStream, addr: usize) {
+        stream.push_fmt(
+            format_args!("{addr:0>width$x}: ", width = self.address_width),
+            CONFIG.colors.address,
+        );
+    }
+
+    /// Byte column, padded such that the instructions that follow are aligned.
+    pub fn bytes(&self, stream: &mut TokenStream, bytes: &[u8]) {
+        if !self.show_bytes {
+            return;
+        }
+
+        // every byte takes up 3 characters, 2 more to separate it from the instruction
+        let max_width = self.bytes_width * 3 + 2;
+        stream.push_owned(
+            encode_hex_bytes_truncated(bytes, max_width, true),
+            CONFIG.colors.bytes,
+        );
+    }
+
+    /// Line of an instruction given it's address, encoding and tokens.
+    pub fn instruction(&self, stream: &mut TokenStream, addr: usize, bytes: &[u8], inst: &[Token]) {
+        self.address(stream, addr);
+        self.bytes(stream, bytes);
+        stream.inner.extend_from_slice(inst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ListingFormatter;
+    use tokenizing::{colors, TokenStream};
+
+    fn format(listing: &ListingFormatter, addr: usize, bytes: &[u8], inst: &'static str) -> String {
+        let mut inst_stream = TokenStream::new();
+        inst_stream.push(inst, colors::WHITE);
+
+        let mut stream = TokenStream::new();
+        listing.instruction(&mut stream, addr, bytes, &inst_stream.inner);
+        stream.to_string()
+    }
+
+    #[test]
+    fn columns() {
+        let listing = ListingFormatter::new(4);
+        assert_eq!(
+            format(&listing, 0x11a4c, &[0x13, 0x05, 0x00, 0x00], "li a0, 0"),
+            "0000000000011a4c: 13 05 00 00   li a0, 0"
+        );
+        assert_eq!(
+            format(&listing, 0x11a50, &[0x01, 0x45], "c.li a0, 0"),
+            "0000000000011a50: 01 45         c.li a0, 0"
+        );
+    }
+
+    #[test]
+    fn widths() {
+        let mut listing = ListingFormatter::new(2);
+        listing.address_width = 8;
+        assert_eq!(
+            format(&listing, 0x1000, &[0x90, 0x90, 0x90, 0x90], "nop"),
+            "00001000: 90 ..  nop"
+        );
+
+        listing.show_bytes = false;
+        assert_eq!(format(&listing, 0x1000, &[0x90], "nop"), "00001000: nop");
+    }
+}
@@ -0,0 +1,407 @@
+use config::CONFIG;
+use decoder::XrefKind;
+use object::read::File as ObjectFile;
+use object::{elf, Architecture, Object, ObjectSection, ObjectSymbol};
+use object::{RelocationKind, RelocationTarget, SymbolKind};
+use processor_shared::{AddressMap, Addressed, PhysAddr};
+use std::collections::HashMap;
+use tokenizing::{Token, TokenStream};
+
+/// How the address a relocation resolves to is encoded in an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationUse {
+    /// Relative to the end of the instruction, like x86's `call` and `[rip + disp]`.
+    EndRelative,
+    /// Relative to the start of the instruction, like riscv's `jal` and branches.
+    StartRelative,
+    /// Upper bits of an `auipc` pair on riscv, only the instruction that follows refers to the
+    /// whole address.
+    Pair,
+    /// Absolute address or an index the loader resolves, like eBPF's `lddw` of a map.
+    Absolute,
+}
+
+/// Operand of an instruction that a relocation patches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchedOperand {
+    /// The last operand, like the target of a branch or the address of riscv's `la`.
+    Last,
+    /// The address of a memory operand, of which x86 instructions have at most one.
+    Memory,
+}
+
+/// Reference to a symbol that the linker writes into an instruction of a relocatable object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocation {
+    /// Name of the symbol referred to.
+    pub symbol: String,
+
+    /// Offset from the symbol, as encoded in the object.
+    pub addend: i64,
+
+    /// Whether the instruction refers to the symbol's GOT entry instead of the symbol.
+    pub got: bool,
+
+    pub usage: RelocationUse,
+}
+
+impl Relocation {
+    /// Offset from the symbol of the address the instruction at `addr` refers to.
+    ///
+    /// x86 encodes end relative addresses relative to the relocation, so the distance from the
+    /// relocation to the end of the instruction has to be added back.
+    pub fn offset(&self, reloc_addr: PhysAddr, addr: PhysAddr, width: usize) -> i64 {
+        match self.usage {
+            RelocationUse::EndRelative => self.addend + (addr + width - reloc_addr) as i64,
+            RelocationUse::StartRelative | RelocationUse::Pair | RelocationUse::Absolute => {
+                self.addend
+            }
+        }
+    }
+
+    /// Operand the relocation patches in an instruction referring to an address of `xref` kind.
+    ///
+    /// x86 relocations patch either the target of a branch or the displacement of a `rip`
+    /// relative memory operand, all other architectures patch the last operand.
+    pub fn operand(&self, xref: Option<XrefKind>) -> PatchedOperand {
+        match (self.usage, xref) {
+            (RelocationUse::EndRelative, Some(XrefKind::Call | XrefKind::Jump)) => {
+                PatchedOperand::Last
+            }
+            (RelocationUse::EndRelative, _) => PatchedOperand::Memory,
+            _ => PatchedOperand::Last,
+        }
+    }
+
+    /// Symbol and offset of the form `symbol+0x8`.
+    pub fn tokenize(&self, stream: &mut TokenStream, offset: i64) {
+        stream.push_owned(self.symbol.clone(), CONFIG.colors.asm.label);
+        if offset < 0 {
+            stream.push_fmt(format_args!("-{:#x}", offset.unsigned_abs()), CONFIG.colors.asm.label);
+        } else if offset > 0 {
+            stream.push_fmt(format_args!("+{offset:#x}"), CONFIG.colors.asm.label);
+        }
+
+        if self.got {
+            stream.push("@GOTPCREL", CONFIG.colors.asm.label);
+        }
+    }
+}
+
+/// What instructions make of relocations, `None` for kinds that don't patch an address operand.
+fn usage(arch: Architecture, kind: RelocationKind) -> Option<(RelocationUse, bool)> {
+    match arch {
+        Architecture::X86_64 => match kind {
+            RelocationKind::Relative | RelocationKind::PltRelative => {
+                Some((RelocationUse::EndRelative, false))
+            }
+            RelocationKind::GotRelative
+            | RelocationKind::Elf(elf::R_X86_64_GOTPCRELX | elf::R_X86_64_REX_GOTPCRELX) => {
+                Some((RelocationUse::EndRelative, true))
+            }
+            _ => None,
+        },
+        Architecture::Riscv32 | Architecture::Riscv64 => match kind {
+            RelocationKind::Elf(
+                elf::R_RISCV_JAL
+                | elf::R_RISCV_BRANCH
+                | elf::R_RISCV_RVC_JUMP
+                | elf::R_RISCV_RVC_BRANCH,
+            ) => Some((RelocationUse::StartRelative, false)),
+            RelocationKind::Elf(
+                elf::R_RISCV_CALL | elf::R_RISCV_CALL_PLT | elf::R_RISCV_PCREL_HI20,
+            ) => Some((RelocationUse::Pair, false)),
+            _ => None,
+        },
+        // maps loaded by `lddw` and calls to other functions of the program
+        Architecture::Bpf => match kind {
+            RelocationKind::Absolute
+            | RelocationKind::Elf(elf::R_BPF_64_64 | elf::R_BPF_64_32) => {
+                Some((RelocationUse::Absolute, false))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Relocations of instructions in a relocatable object.
+///
+/// Every section of a relocatable object starts at zero, so relocations are kept per section and
+/// sorted by the offset they patch in it. Linked binaries usually don't have any, those only keep
+/// dynamic relocations.
+#[derive(Debug, Default)]
+pub(crate) struct CodeRelocations {
+    sections: HashMap<String, AddressMap<Relocation>>,
+}
+
+impl CodeRelocations {
+    pub fn parse(obj: &ObjectFile) -> Self {
+        let mut sections: HashMap<String, AddressMap<Relocation>> = HashMap::new();
+
+        for section in obj.sections().filter(|s| s.kind() == object::SectionKind::Text) {
+            let section_name = match section.name() {
+                Ok(name) => name,
+                Err(..) => continue,
+            };
+
+            for (offset, reloc) in section.relocations() {
+                let (usage, got) = match usage(obj.architecture(), reloc.kind()) {
+                    Some(usage) => usage,
+                    None => continue,
+                };
+
+                let symbol = match reloc.target() {
+                    RelocationTarget::Symbol(idx) => match obj.symbol_by_index(idx) {
+                        Ok(symbol) => symbol,
+                        Err(..) => continue,
+                    },
+                    _ => continue,
+                };
+
+                // section symbols are nameless, they're named after their section
+                let name = match symbol.kind() {
+                    SymbolKind::Section => symbol
+                        .section_index()
+                        .and_then(|idx| obj.section_by_index(idx).ok())
+                        .and_then(|section| section.name().ok().map(str::to_string)),
+                    _ => symbol.name().ok().map(str::to_string),
+                };
+
+                if let Some(symbol) = name {
+                    sections.entry(section_name.to_string()).or_default().push(Addressed {
+                        addr: offset as PhysAddr,
+                        item: Relocation {
+                            symbol,
+                            addend: reloc.addend(),
+                            got,
+                            usage,
+                        },
+                    });
+                }
+            }
+        }
+
+        for relocations in sections.values_mut() {
+            relocations.sort_unstable();
+        }
+
+        Self { sections }
+    }
+
+    /// Relocations of the code section named `section`, sorted by the offset they patch.
+    pub fn of(&self, section: &str) -> Option<&AddressMap<Relocation>> {
+        self.sections.get(section)
+    }
+}
+
+/// Replaces the `operand` of an instruction's `tokens` with `symbol`.
+///
+/// The tokens must come from an instruction tokenized without symbols, with operands split by
+/// `separator`. Returns whether the operand was found.
+pub(crate) fn substitute(
+    tokens: &mut Vec<Token>,
+    operand: PatchedOperand,
+    separator: &str,
+    symbol: &[Token],
+) -> bool {
+    match operand {
+        PatchedOperand::Last => {
+            // the first operand follows the space after the mnemonic
+            let start = match tokens.iter().rposition(|t| *t.text == *separator) {
+                Some(idx) => idx + 1,
+                None => match tokens.iter().position(|t| &*t.text == " ") {
+                    Some(idx) => idx + 1,
+                    None => return false,
+                },
+            };
+
+            tokens.splice(start.., symbol.iter().cloned());
+            true
+        }
+        PatchedOperand::Memory => {
+            let open = match tokens.iter().position(|t| &*t.text == "[") {
+                Some(open) => open,
+                None => return false,
+            };
+
+            match tokens[open..].iter().position(|t| &*t.text == "]") {
+                Some(close) => {
+                    tokens.splice(open + 1..open + close, symbol.iter().cloned());
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{substitute, CodeRelocations, PatchedOperand, Relocation, RelocationUse};
+    use decoder::XrefKind;
+    use object::read::File as ObjectFile;
+    use tokenizing::{colors, TokenStream};
+
+    /// Unlinked x86_64 object, see `fixtures/relocs-x86_64.s`.
+    static X86_64: &[u8] = include_bytes!("../fixtures/relocs-x86_64.o");
+
+    /// Unlinked riscv64 object, see `fixtures/relocs-riscv64.s`.
+    static RISCV64: &[u8] = include_bytes!("../fixtures/relocs-riscv64.o");
+
+    /// Unlinked eBPF object, see `fixtures/bpf.ll`.
+    static BPF: &[u8] = include_bytes!("../fixtures/bpf.o");
+
+    /// Unlinked x86_64 object with a section per function, see `fixtures/relocs-sections.s`.
+    static SECTIONS: &[u8] = include_bytes!("../fixtures/relocs-sections.o");
+
+    fn relocations(bytes: &[u8], section: &str) -> Vec<(usize, String, i64, RelocationUse)> {
+        let obj = ObjectFile::parse(bytes).unwrap();
+        CodeRelocations::parse(&obj)
+            .of(section)
+            .unwrap()
+            .iter()
+            .map(|r| (r.addr, r.item.symbol.clone(), r.item.addend, r.item.usage))
+            .collect()
+    }
+
+    fn tokens(text: &[&'static str]) -> TokenStream {
+        let mut stream = TokenStream::new();
+        for text in text {
+            stream.push(text, colors::WHITE);
+        }
+        stream
+    }
+
+    fn symbol(name: &str, offset: i64, got: bool) -> TokenStream {
+        let reloc = Relocation {
+            symbol: name.to_string(),
+            addend: 0,
+            got,
+            usage: RelocationUse::EndRelative,
+        };
+
+        let mut stream = TokenStream::new();
+        reloc.tokenize(&mut stream, offset);
+        stream
+    }
+
+    /// Text of the instruction made of `text` with `operand` replaced by `symbol`.
+    fn substituted(
+        text: &[&'static str],
+        operand: PatchedOperand,
+        symbol: TokenStream,
+    ) -> Option<String> {
+        let mut tokens = tokens(text);
+        substitute(&mut tokens.inner, operand, ", ", &symbol.inner).then(|| tokens.to_string())
+    }
+
+    #[test]
+    fn x86_64() {
+        use RelocationUse::EndRelative;
+
+        assert_eq!(
+            relocations(X86_64, ".text"),
+            [
+                (0x01, "foo".to_string(), -4, EndRelative),
+                (0x08, "table".to_string(), 4, EndRelative),
+                (0x0e, "counter".to_string(), -4, EndRelative),
+                (0x15, "errno".to_string(), -4, EndRelative),
+                (0x23, "bar".to_string(), -4, EndRelative),
+            ]
+        );
+
+        let obj = ObjectFile::parse(X86_64).unwrap();
+        let relocs = CodeRelocations::parse(&obj);
+        let relocs = relocs.of(".text").unwrap();
+
+        // `lea rax, [rip + table + 8]` is 7 bytes, with the displacement in the last 4
+        assert_eq!(relocs[1].item.offset(0x08, 0x05, 7), 8);
+        // `call foo` is 5 bytes, with the displacement in the last 4
+        assert_eq!(relocs[0].item.offset(0x01, 0x00, 5), 0);
+        assert!(relocs[3].item.got);
+    }
+
+    #[test]
+    fn riscv64() {
+        use RelocationUse::{Pair, StartRelative};
+
+        // `%pcrel_lo` refers to the `auipc`, not to a symbol
+        assert_eq!(
+            relocations(RISCV64, ".text"),
+            [
+                (0x04, "foo".to_string(), 0, Pair),
+                (0x0c, "table".to_string(), 0, Pair),
+                (0x14, "fail".to_string(), 0, StartRelative),
+                (0x18, "helper".to_string(), 0, StartRelative),
+                (0x26, "bar".to_string(), 0, Pair),
+            ]
+        );
+    }
+
+    #[test]
+    fn bpf() {
+        // the map loaded by `lddw`, the call to `bump` is resolved by the compiler
+        assert_eq!(
+            relocations(BPF, "xdp"),
+            [(0x20, "counts".to_string(), 0, RelocationUse::Absolute)]
+        );
+    }
+
+    #[test]
+    fn sections() {
+        // both calls are at offset 1 of their section, which both start at zero
+        let foo = (0x01, "foo".to_string(), -4, RelocationUse::EndRelative);
+        let bar = (0x01, "bar".to_string(), -4, RelocationUse::EndRelative);
+        assert_eq!(relocations(SECTIONS, ".text.first"), [foo]);
+        assert_eq!(relocations(SECTIONS, ".text.second"), [bar]);
+    }
+
+    #[test]
+    fn branch_target() {
+        use PatchedOperand::Last;
+
+        let call = Relocation {
+            symbol: "foo".to_string(),
+            addend: -4,
+            got: false,
+            usage: RelocationUse::EndRelative,
+        };
+        assert_eq!(call.operand(Some(XrefKind::Call)), Last);
+
+        let call = substituted(&["call", " ", "5"], Last, symbol("foo", 0, false));
+        assert_eq!(call.as_deref(), Some("call foo"));
+
+        let bnez = substituted(&["bnez", " ", "a0", ", ", "0x14"], Last, symbol("fail", -8, false));
+        assert_eq!(bnez.as_deref(), Some("bnez a0, fail-0x8"));
+    }
+
+    #[test]
+    fn rip_relative() {
+        use PatchedOperand::Memory;
+
+        let lea = Relocation {
+            symbol: "table".to_string(),
+            addend: 4,
+            got: false,
+            usage: RelocationUse::EndRelative,
+        };
+        assert_eq!(lea.operand(Some(XrefKind::Data)), Memory);
+
+        let lea = ["lea", " ", "rax", ", ", "qword ", "[", "rip", "]"];
+        let lea = substituted(&lea, Memory, symbol("table", 8, false));
+        assert_eq!(lea.as_deref(), Some("lea rax, qword [table+0x8]"));
+
+        // the memory operand isn't always the last one
+        let mov = ["mov", " ", "dword ", "[", "rip", "]", ", ", "0x1"];
+        let mov = substituted(&mov, Memory, symbol("counter", 0, false));
+        assert_eq!(mov.as_deref(), Some("mov dword [counter], 0x1"));
+
+        let mov = ["mov", " ", "rcx", ", ", "qword ", "[", "rip", "]"];
+        let mov = substituted(&mov, Memory, symbol("errno", 0, true));
+        assert_eq!(mov.as_deref(), Some("mov rcx, qword [errno@GOTPCREL]"));
+
+        // nothing to substitute
+        assert_eq!(substituted(&["ret"], Memory, symbol("foo", 0, false)), None);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::Processor;
+use debugvault::FileAttr;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Source files read for showing them along with the disassembly.
+#[derive(Debug, Default)]
+pub(crate) struct SourceCache {
+    /// Lines of every file that's been read, `None` if it couldn't be read.
+    files: Mutex<HashMap<Arc<Path>, Option<Arc<Vec<String>>>>>,
+}
+
+impl SourceCache {
+    /// Text of `line` in the file at `path`. Relative paths that don't exist are tried relative
+    /// to `dir` as well, as they're usually relative to where the object was built.
+    fn line(&self, path: &Arc<Path>, line: usize, dir: &Path) -> Option<String> {
+        let mut files = self.files.lock().unwrap();
+        let lines = files.entry(Arc::clone(path)).or_insert_with(|| {
+            let text = std::fs::read_to_string(path)
+                .or_else(|_| std::fs::read_to_string(dir.join(path)))
+                .ok()?;
+
+            Some(Arc::new(text.lines().map(str::to_string).collect()))
+        });
+
+        lines.as_ref()?.get(line.checked_sub(1)?).cloned()
+    }
+}
+
+/// Path of a source file without `.` components, which line tables tend to be full of.
+pub(crate) fn display_path(path: &Path) -> PathBuf {
+    path.components().filter(|component| *component != Component::CurDir).collect()
+}
+
+impl Processor {
+    /// Source location of the line table row starting at `addr`, unless the row before it is
+    /// for the same line.
+    pub fn source_line_at(&self, addr: usize) -> Option<&FileAttr> {
+        let file_attrs = &self.index.file_attrs;
+        file_attrs.search(addr).ok()?;
+
+        // of multiple rows at the same address, the last one applies
+        let idx = file_attrs.partition_point(|row| row.addr <= addr) - 1;
+        let row = &file_attrs[idx].item;
+        if row.line == 0 {
+            return None;
+        }
+
+        let prev = file_attrs[..idx].iter().rev().find(|prev| prev.addr != addr);
+        match prev {
+            Some(prev) if prev.item.line == row.line && prev.item.path == row.path => None,
+            _ => Some(row),
+        }
+    }
+
+    /// Text of the source line at `file_attr`, if the source file can be found.
+    pub fn source_text(&self, file_attr: &FileAttr) -> Option<String> {
+        let dir = match self.path.parent() {
+            Some(parent) => parent,
+            None => Path::new("."),
+        };
+
+        self.sources.line(&file_attr.path, file_attr.line, dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::display_path;
+    use crate::Processor;
+    use std::path::Path;
+
+    /// Statically linked x86_64 executable with a line table, see `fixtures/lines.c`.
+    const LINES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/lines");
+
+    #[test]
+    fn interleaved() {
+        let processor = Processor::parse(Path::new(LINES)).unwrap();
+        let mut listing = processor.listing_formatter();
+
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &listing, None).unwrap();
+        let plain = String::from_utf8(out).unwrap();
+        assert!(!plain.contains(';'), "source shown in:\n{plain}");
+
+        listing.show_source = true;
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &listing, None).unwrap();
+        let lines = String::from_utf8(out).unwrap();
+        assert!(lines.contains("<square>:\n; lines.c:6\n0000000000401000:"), "{lines}");
+        assert!(lines.contains("; lines.c:7\n0000000000401003:"), "{lines}");
+
+        // a line is only annotated where it starts
+        assert_eq!(lines.matches("; lines.c:10").count(), 1, "{lines}");
+
+        listing.show_source_text = true;
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &listing, None).unwrap();
+        let source = String::from_utf8(out).unwrap();
+        assert!(source.contains("; lines.c:6: return x * x;\n"), "{source}");
+        assert!(source.contains("; lines.c:10: int result = square(7);\n"), "{source}");
+    }
+
+    #[test]
+    fn compressed() {
+        for fixture in ["lines-zlib", "lines-zstd"] {
+            let path = format!("{}/fixtures/{fixture}", env!("CARGO_MANIFEST_DIR"));
+            let processor = Processor::parse(Path::new(&path)).unwrap();
+            let mut listing = processor.listing_formatter();
+            listing.show_source = true;
+
+            let mut out = Vec::new();
+            processor.write_listing(&mut out, &listing, None).unwrap();
+            let lines = String::from_utf8(out).unwrap();
+            assert!(lines.contains("<square>:\n; lines.c:6\n0000000000401000:"), "{lines}");
+        }
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(display_path(Path::new("././lines.c")), Path::new("lines.c"));
+        assert_eq!(display_path(Path::new("/src/./main.rs")), Path::new("/src/main.rs"));
+    }
+}
@@ -0,0 +1,210 @@
+use crate::Processor;
+use binformat::{SectionHeader, TableSymbol};
+use object::SymbolKind;
+use processor_shared::{AddressMap, Addressed, Section, SectionKind};
+use std::io::{self, Write};
+
+/// Number of characters a string has to have by default, like the `strings` tool.
+pub const MIN_STRING_LEN: usize = 4;
+
+/// Whether `c` can be part of a string, which includes the whitespace common in string literals.
+fn is_printable(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r') || !c.is_control()
+}
+
+/// UTF-8 encoded character at the start of `bytes` and its width in bytes.
+fn char_at(bytes: &[u8]) -> Option<(char, usize)> {
+    let width = match bytes.first()? {
+        0x00..=0x7f => 1,
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        _ => return None,
+    };
+
+    let c = std::str::from_utf8(bytes.get(..width)?).ok()?.chars().next()?;
+    Some((c, width))
+}
+
+/// Runs of at least `min_len` printable characters in `bytes`, as their offset and text.
+fn printable_runs(bytes: &[u8], min_len: usize) -> Vec<(usize, String)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut len = 0;
+    let mut offset = 0;
+
+    let mut end_run = |start: usize, end: usize, len: usize| {
+        if len >= min_len.max(1) {
+            let text = std::str::from_utf8(&bytes[start..end]).unwrap_or_default();
+            runs.push((start, text.to_string()));
+        }
+    };
+
+    while offset < bytes.len() {
+        match char_at(&bytes[offset..]) {
+            Some((c, width)) if is_printable(c) => {
+                if len == 0 {
+                    start = offset;
+                }
+
+                len += 1;
+                offset += width;
+            }
+            _ => {
+                end_run(start, offset, len);
+                len = 0;
+                offset += 1;
+            }
+        }
+    }
+
+    end_run(start, offset, len);
+    runs
+}
+
+/// Whether a section holds data a string could be in, as opposed to code or metadata like
+/// symbol tables.
+fn is_data(kind: object::SectionKind) -> bool {
+    use object::SectionKind as Kind;
+
+    matches!(
+        kind,
+        Kind::Data | Kind::ReadOnlyData | Kind::ReadOnlyDataWithRel | Kind::ReadOnlyString
+    )
+}
+
+/// Strings of at least `min_len` characters in the data sections, sorted by address.
+pub(crate) fn scan(
+    sections: &[Section],
+    headers: &[SectionHeader],
+    min_len: usize,
+) -> AddressMap<String> {
+    let mut strings = AddressMap::default();
+
+    for section in sections {
+        if matches!(section.kind, SectionKind::Code | SectionKind::Debug | SectionKind::Unloaded) {
+            continue;
+        }
+
+        if !headers.iter().any(|header| header.name == section.name && is_data(header.kind)) {
+            continue;
+        }
+
+        for (offset, text) in printable_runs(section.bytes(), min_len) {
+            strings.push(Addressed { addr: section.start + offset, item: text });
+        }
+    }
+
+    // sections that overlap would otherwise list the same string twice
+    strings.sort_unstable();
+    strings.dedup();
+    strings
+}
+
+impl Processor {
+    /// String of at least [`MIN_STRING_LEN`] characters starting at `addr`.
+    pub fn string_at(&self, addr: usize) -> Option<&str> {
+        let idx = self.strings.search(addr).ok()?;
+        Some(&self.strings[idx].item)
+    }
+
+    /// Write the strings of at least `min_len` characters found in the data sections, along
+    /// with the section they're in and the object symbol they're part of.
+    ///
+    /// ```text
+    /// 0000000000402000 .rodata "hello, world" <greeting>
+    /// 0000000000402014 .rodata "error: %s\n"
+    /// ```
+    pub fn write_strings<W: Write>(&self, w: &mut W, min_len: usize) -> io::Result<()> {
+        let scanned;
+        let strings = match min_len {
+            MIN_STRING_LEN => &self.strings,
+            _ => {
+                scanned = scan(&self.sections, &self.section_headers, min_len);
+                &scanned
+            }
+        };
+
+        let mut objects: Vec<&TableSymbol> = self
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.kind == SymbolKind::Data && symbol.section.is_some())
+            .filter(|symbol| symbol.size > 0)
+            .collect();
+        objects.sort_by_key(|symbol| symbol.addr);
+
+        let object_containing = |addr: usize| {
+            let idx = objects.partition_point(|symbol| symbol.addr <= addr).checked_sub(1)?;
+            let symbol = objects[idx];
+            (addr < symbol.addr + symbol.size as usize).then_some((symbol, addr - symbol.addr))
+        };
+
+        let section_names: Vec<&str> = strings
+            .iter()
+            .map(|string| self.section_name(string.addr).unwrap_or(""))
+            .collect();
+        let width = section_names.iter().map(|name| name.len()).max().unwrap_or(0);
+
+        for (string, section) in strings.iter().zip(section_names) {
+            let text = string.item.escape_debug();
+            write!(w, "{:016x} {section:width$} \"{text}\"", string.addr)?;
+
+            match object_containing(string.addr) {
+                Some((symbol, 0)) => write!(w, " <{}>", self.demangle(&symbol.name))?,
+                Some((symbol, offset)) => {
+                    write!(w, " <{}+{offset:#x}>", self.demangle(&symbol.name))?
+                }
+                None => {}
+            }
+
+            writeln!(w)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::printable_runs;
+    use crate::Processor;
+    use std::path::Path;
+
+    /// Statically linked x86_64 executable with strings, see `fixtures/strings.s`.
+    const STRINGS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/strings");
+
+    #[test]
+    fn runs() {
+        let bytes = b"\x01abc\0hello\tworld\n\xffgr\xc3\xb6\xc3\x9fe\xc3";
+        assert_eq!(
+            printable_runs(bytes, 4),
+            [(5, "hello\tworld\n".to_string()), (18, "größe".to_string())]
+        );
+        assert_eq!(printable_runs(bytes, 3)[0], (1, "abc".to_string()));
+        assert_eq!(printable_runs(b"", 4), []);
+    }
+
+    #[test]
+    fn strings() {
+        let processor = Processor::parse(Path::new(STRINGS)).unwrap();
+
+        let mut out = Vec::new();
+        processor.write_strings(&mut out, 4).unwrap();
+        let expected = r#"0000000000402000 .rodata "hello, world" <greeting>
+0000000000402014 .rodata "error: %s\n"
+000000000040301f .data   "größe"
+"#;
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let mut out = Vec::new();
+        processor.write_strings(&mut out, 3).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("0000000000402010 .rodata \"abc\"\n"));
+
+        // operands referring to a string are annotated with it
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &processor.listing_formatter(), None).unwrap();
+        let listing = String::from_utf8(out).unwrap();
+        assert!(listing.contains(r#" ; "error: %s\n""#), "{listing}");
+        assert_eq!(listing.matches(" ; \"").count(), 1, "{listing}");
+    }
+}
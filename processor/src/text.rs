@@ -0,0 +1,1373 @@
+use crate::{BlockContent, FunctionRange, ListingFormatter, NameOptions, Processor};
+use crate::{SymbolFilter, SymbolOrder};
+use binformat::{Binding, Export, Import, SymbolTable, TableSymbol};
+use object::{BinaryFormat, ObjectKind};
+use processor_shared::{PhysAddr, SectionKind};
+use std::io::{self, Write};
+use tokenizing::TokenStream;
+
+/// Short name of what a symbol refers to, for the symbol table.
+fn symbol_kind_name(kind: object::SymbolKind) -> &'static str {
+    use object::SymbolKind as Kind;
+
+    match kind {
+        Kind::Text => "FUNC",
+        Kind::Data => "OBJECT",
+        Kind::Tls => "TLS",
+        _ => "NOTYPE",
+    }
+}
+
+/// Short name of what a section contains, for the section table.
+fn kind_name(kind: object::SectionKind) -> &'static str {
+    use object::SectionKind as Kind;
+
+    match kind {
+        Kind::Text => "CODE",
+        Kind::Data => "DATA",
+        Kind::ReadOnlyData | Kind::ReadOnlyDataWithRel | Kind::ReadOnlyString => "RODATA",
+        Kind::UninitializedData | Kind::Common => "BSS",
+        Kind::Tls | Kind::UninitializedTls | Kind::TlsVariables => "TLS",
+        Kind::Debug => "DEBUG",
+        Kind::Note => "NOTE",
+        Kind::Linker => "LINKER",
+        Kind::Metadata => "METADATA",
+        _ => "OTHER",
+    }
+}
+
+/// Name of an architecture like it's given to `--raw`, or a name in the same style for
+/// architectures that can't be decoded.
+fn arch_name(arch: object::Architecture, endianness: object::Endianness) -> String {
+    use object::Architecture as Arch;
+
+    let known = crate::ARCHITECTURES
+        .iter()
+        .find(|(_, known, known_endianness)| *known == arch && *known_endianness == endianness);
+
+    if let Some((name, ..)) = known {
+        return name.to_string();
+    }
+
+    let name = match arch {
+        Arch::X86_64_X32 => "x32",
+        Arch::Aarch64_Ilp32 => "aarch64_ilp32",
+        Arch::Wasm32 => "wasm32",
+        arch => return format!("{arch:?}").to_lowercase(),
+    };
+
+    name.to_string()
+}
+
+/// UTC date and time of a unix timestamp, like `2024-02-29 13:37:00`.
+fn utc_date(timestamp: u32) -> String {
+    let days = timestamp / 86400;
+    let secs = timestamp % 86400;
+
+    // days since the epoch to the civil calendar, shifted to start with march so leap days are
+    // the last day of the year
+    let days = days + 719468;
+    let era = days / 146097;
+    let doe = days % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let month = (5 * doy + 2) / 153;
+    let day = doy - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = era * 400 + yoe + (month <= 2) as u32;
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Bytes in lowercase hexadecimal.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// UUID grouped like `01234567-89AB-CDEF-0123-456789ABCDEF`. The first three groups of a GUID
+/// are little-endian integers, which are printed byte-swapped.
+fn uuid(bytes: [u8; 16], guid: bool) -> String {
+    let mut bytes = bytes;
+    if guid {
+        bytes[..4].reverse();
+        bytes[4..6].reverse();
+        bytes[6..8].reverse();
+    }
+
+    let hex = hex(&bytes).to_uppercase();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// Size like `ls -h`, in bytes below a KiB and with a single decimal and unit above.
+fn human_size(size: u64) -> String {
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut size = size as f64;
+    for unit in ["K", "M", "G"] {
+        size /= 1024.0;
+        if size < 1024.0 {
+            return format!("{size:.1}{unit}");
+        }
+    }
+
+    format!("{:.1}T", size / 1024.0)
+}
+
+/// Name of a Linux signal, like `SIGSEGV`, for the signals a process usually dumps core on.
+fn signal_name(signal: u16) -> Option<&'static str> {
+    let name = match signal {
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        5 => "SIGTRAP",
+        6 => "SIGABRT",
+        7 => "SIGBUS",
+        8 => "SIGFPE",
+        11 => "SIGSEGV",
+        24 => "SIGXCPU",
+        25 => "SIGXFSZ",
+        31 => "SIGSYS",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+impl Processor {
+    /// Write an objdump-style listing of all code sections, with the same columns as the GUI.
+    ///
+    /// ```text
+    /// Disassembly of section .text:
+    ///
+    /// <add>:
+    /// 0000000000401001: 8d 04 37            lea eax, dword [rdi + rsi * 1]
+    /// 0000000000401004: c3                  ret
+    /// ```
+    ///
+    /// The listing begins at the instruction containing `start` if it's given.
+    pub fn write_listing<W: Write>(
+        &self,
+        w: &mut W,
+        listing: &ListingFormatter,
+        start: Option<PhysAddr>,
+    ) -> io::Result<()> {
+        let start = start.unwrap_or(0);
+        let code = self
+            .sections()
+            .filter(|section| section.kind == SectionKind::Code && section.end > start);
+
+        for (idx, section) in code.enumerate() {
+            if idx != 0 {
+                writeln!(w)?;
+            }
+
+            writeln!(w, "Disassembly of section {}:", section.name)?;
+
+            let mut boundaries = self.compute_section_boundaries(section);
+            boundaries.sort_unstable();
+            boundaries.dedup();
+            if boundaries.last() == Some(&section.end) {
+                boundaries.pop();
+            }
+
+            let first = self.block_containing(&boundaries, start).unwrap_or(0);
+            self.write_blocks(w, listing, &boundaries[first..])?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the listing of a single function, in the same format as
+    /// [`Processor::write_listing`].
+    ///
+    /// ```text
+    /// Disassembly of function add:
+    ///
+    /// <add>:
+    /// 0000000000401001: 8d 04 37            lea eax, dword [rdi + rsi * 1]
+    /// 0000000000401004: c3                  ret
+    /// ```
+    ///
+    /// Functions whose size isn't known are marked as such, as the listing might include code
+    /// that doesn't belong to them.
+    pub fn write_function<W: Write>(
+        &self,
+        w: &mut W,
+        listing: &ListingFormatter,
+        function: FunctionRange,
+    ) -> io::Result<()> {
+        let name = self.index.get_sym_by_addr(function.start);
+        let name = name.as_ref().map_or("", |symbol| symbol.as_str());
+        write!(w, "Disassembly of function {name}")?;
+        if function.guessed {
+            write!(w, " (size unknown, up to the next symbol)")?;
+        }
+        writeln!(w, ":")?;
+
+        let boundaries = self.compute_function_boundaries(function);
+        self.write_blocks(w, listing, &boundaries)
+    }
+
+    /// Write the blocks starting at each of `boundaries`.
+    fn write_blocks<W: Write>(
+        &self,
+        w: &mut W,
+        listing: &ListingFormatter,
+        boundaries: &[PhysAddr],
+    ) -> io::Result<()> {
+        let mut line = TokenStream::new();
+        for &addr in boundaries {
+            for block in self.parse_blocks(addr) {
+                match &block.content {
+                    BlockContent::SectionStart { .. } | BlockContent::SectionEnd { .. } => {}
+                    BlockContent::Source { .. } if !listing.show_source => {}
+                    BlockContent::Label { symbol } if symbol.intrinsic() => {
+                        writeln!(w, "<{}>:", symbol.as_str())?;
+                    }
+                    BlockContent::Label { symbol } => {
+                        writeln!(w, "\n<{}>:", symbol.as_str())?;
+                    }
+                    _ => {
+                        line.clear();
+                        block.tokenize(&mut line, listing);
+                        writeln!(w, "{line}")?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the symbols the object takes from and makes available to other modules, followed
+    /// by a table of its own symbols, merged from its static and dynamic symbol tables.
+    ///
+    /// ```text
+    /// Imports:
+    ///   env::print
+    ///
+    /// Exports:
+    ///                    memory
+    ///   000000000000005a add
+    ///
+    /// Symbols:
+    ///   Address          Size     Type   Bind   Section Table  Name
+    ///   0000000000401001 00000004 FUNC   GLOBAL .text   symtab add
+    /// ```
+    pub fn write_names<W: Write>(&self, w: &mut W, options: NameOptions) -> io::Result<()> {
+        let NameOptions { order, filter, table, pattern, mangled } = options;
+
+        // names are demangled once they're known to be written, as a pattern tends to leave
+        // out most of them. those that demangle to themselves, like the names of C functions,
+        // are matched without being demangled at all
+        let name_of = |name: &str| -> Option<String> {
+            let pattern = match &pattern {
+                Some(pattern) => pattern,
+                None => return Some(self.demangle(name)),
+            };
+
+            if mangled || (!self.simplify && !debugvault::is_mangled(name)) {
+                return pattern.is_match(name).then(|| self.demangle(name));
+            }
+
+            let demangled = self.demangle(name);
+            pattern.is_match(&demangled).then_some(demangled)
+        };
+
+        let imports: Vec<(&Import, String)> = match filter {
+            SymbolFilter::Defined => Vec::new(),
+            _ => self
+                .imports
+                .iter()
+                .filter_map(|import| Some((import, name_of(&import.name)?)))
+                .collect(),
+        };
+        let exports: Vec<(&Export, String)> = match filter {
+            SymbolFilter::Undefined => Vec::new(),
+            _ => self
+                .exports
+                .iter()
+                .filter_map(|export| Some((export, name_of(&export.name)?)))
+                .collect(),
+        };
+
+        let tabled: Vec<_> = self
+            .symbols
+            .iter()
+            .filter(|symbol| match table {
+                Some(table) => symbol.table == table || symbol.table == SymbolTable::Both,
+                None => true,
+            })
+            .collect();
+
+        let mut symbols: Vec<(&TableSymbol, String)> = tabled
+            .iter()
+            .copied()
+            .filter(|symbol| match filter {
+                SymbolFilter::All => true,
+                SymbolFilter::Defined => symbol.section.is_some(),
+                SymbolFilter::Undefined => symbol.section.is_none(),
+            })
+            .filter_map(|symbol| Some((symbol, name_of(&symbol.name)?)))
+            .collect();
+
+        // ties are broken by address, then by where they're stored. thread-local symbols come
+        // last as their offsets aren't comparable to addresses
+        symbols.sort_by_key(|(symbol, _)| {
+            let tls = symbol.kind == object::SymbolKind::Tls;
+            (symbol.section.is_some(), tls, symbol.addr)
+        });
+        match order {
+            SymbolOrder::Address => {}
+            SymbolOrder::Size => symbols.sort_by(|(a, _), (b, _)| b.size.cmp(&a.size)),
+            SymbolOrder::Name => symbols.sort_by(|(_, a), (_, b)| a.cmp(b)),
+        }
+
+        if !imports.is_empty() {
+            writeln!(w, "Imports:")?;
+            for (import, name) in &imports {
+                match import.module.as_str() {
+                    "" => writeln!(w, "  {name}")?,
+                    module => writeln!(w, "  {module}::{name}")?,
+                }
+            }
+        }
+
+        if !exports.is_empty() {
+            if !imports.is_empty() {
+                writeln!(w)?;
+            }
+
+            writeln!(w, "Exports:")?;
+            for (export, name) in &exports {
+                match export.addr {
+                    Some(addr) => writeln!(w, "  {addr:016x} {name}")?,
+                    None => writeln!(w, "  {:16} {name}", "")?,
+                }
+            }
+        }
+
+        // raw code and wasm modules don't have a symbol table to speak of
+        if tabled.is_empty() && self.format.is_some() {
+            if !imports.is_empty() || !exports.is_empty() {
+                writeln!(w)?;
+            }
+
+            let note = match table {
+                Some(SymbolTable::Dynamic) => "none, the object has no dynamic symbols",
+                _ => "none, the object has no symbol table and might be stripped",
+            };
+
+            writeln!(w, "Symbols:\n  {note}")?;
+        }
+
+        if !symbols.is_empty() {
+            if !imports.is_empty() || !exports.is_empty() {
+                writeln!(w)?;
+            }
+
+            let width = symbols
+                .iter()
+                .filter_map(|(symbol, _)| symbol.section.as_ref())
+                .map(String::len)
+                .fold("Section".len(), usize::max);
+
+            writeln!(w, "Symbols:")?;
+            writeln!(
+                w,
+                "  {:16} {:8} {:6} {:6} {:width$} {:6} Name",
+                "Address", "Size", "Type", "Bind", "Section", "Table"
+            )?;
+            for (symbol, name) in symbols {
+                let binding = match symbol.binding {
+                    Binding::Local => "LOCAL",
+                    Binding::Global => "GLOBAL",
+                    Binding::Weak => "WEAK",
+                };
+                let table = match symbol.table {
+                    SymbolTable::Static => "symtab",
+                    SymbolTable::Dynamic => "dynsym",
+                    SymbolTable::Both => "both",
+                    SymbolTable::Synthetic => "synth",
+                };
+
+                // thread-local symbols are at an offset into the TLS template
+                match &symbol.section {
+                    Some(_) if symbol.kind == object::SymbolKind::Tls => {
+                        write!(w, "  {:>16} ", format!("tls+{:#x}", symbol.addr))?
+                    }
+                    Some(_) => write!(w, "  {:016x} ", symbol.addr)?,
+                    None => write!(w, "  {:16} ", "")?,
+                }
+
+                writeln!(
+                    w,
+                    "{:08x} {:6} {binding:6} {:width$} {table:6} {}",
+                    symbol.size,
+                    symbol_kind_name(symbol.kind),
+                    symbol.section.as_deref().unwrap_or("UND"),
+                    name,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a table of the object's sections, as they're found in its section table. Sizes are
+    /// in hexadecimal unless `human` is set. Templates of thread-local storage are flagged `T`
+    /// and sections stored compressed, like debug info often is, are flagged `C`.
+    ///
+    /// ```text
+    ///   [Nr] Name                 Type     Address          Offset   Size     Align Flags
+    ///   [ 1] .text                CODE     0000000000401000 00001000 00000013     1 AX
+    /// ```
+    pub fn write_sections<W: Write>(&self, w: &mut W, human: bool) -> io::Result<()> {
+        writeln!(
+            w,
+            "  [Nr] {:20} {:8} {:16} {:8} {:8} Align Flags",
+            "Name", "Type", "Address", "Offset", "Size"
+        )?;
+
+        for header in &self.section_headers {
+            let offset = match header.offset {
+                Some(offset) => format!("{offset:08x}"),
+                None => "-".to_string(),
+            };
+
+            let size = match human {
+                true => human_size(header.size),
+                false => format!("{:08x}", header.size),
+            };
+
+            let mut flags = String::new();
+            for (set, flag) in [
+                (header.write, 'W'),
+                (header.alloc, 'A'),
+                (header.exec, 'X'),
+                (header.tls, 'T'),
+                (header.compressed, 'C'),
+            ] {
+                if set {
+                    flags.push(flag);
+                }
+            }
+
+            let line = format!(
+                "  [{:2}] {:20} {:8} {:016x} {offset:8} {size:>8} {:5} {flags}",
+                header.index,
+                header.name,
+                kind_name(header.kind),
+                header.addr,
+                header.align,
+            );
+
+            writeln!(w, "{}", line.trim_end())?;
+        }
+
+        if self.section_headers.iter().any(|header| header.tls) {
+            writeln!(
+                w,
+                "\nT: template of thread-local storage, copied for each thread instead of being \
+                 accessed at its address"
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Write what kind of object it is and where it's loaded, followed by its program headers,
+    /// or its segments for formats without any.
+    ///
+    /// ```text
+    /// Type: executable
+    /// Entry point: 0x401005
+    /// Base address: 0x400000
+    ///
+    ///   Type           Offset   Address          FileSize MemSize  Flags Align
+    ///   LOAD           00001000 0000000000401000 00000013 00000013 R X   1000
+    /// ```
+    pub fn write_segments<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        if let Some(kind) = self.kind_name() {
+            writeln!(w, "Type: {kind}")?;
+        }
+
+        writeln!(w, "Entry point: {:#x}", self.entrypoint)?;
+        if let Some(image_base) = self.image_base {
+            writeln!(w, "Base address: {image_base:#x}")?;
+        }
+
+        if let Some(interpreter) = &self.interpreter {
+            writeln!(w, "Interpreter: {interpreter}")?;
+        }
+
+        let stack = self.segment_headers.iter().find(|header| header.kind == "GNU_STACK");
+        if let Some(stack) = stack {
+            let exec = if stack.exec { "yes" } else { "no" };
+            writeln!(w, "Executable stack: {exec}")?;
+        }
+
+        if self.segment_headers.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(w)?;
+        writeln!(
+            w,
+            "  {:14} {:8} {:16} {:8} {:8} {:5} Align",
+            "Type", "Offset", "Address", "FileSize", "MemSize", "Flags"
+        )?;
+
+        for header in &self.segment_headers {
+            let flags: String = [(header.read, 'R'), (header.write, 'W'), (header.exec, 'X')]
+                .into_iter()
+                .map(|(set, flag)| if set { flag } else { ' ' })
+                .collect();
+
+            writeln!(
+                w,
+                "  {:14} {:08x} {:016x} {:08x} {:08x} {flags:5} {:x}",
+                header.kind,
+                header.offset,
+                header.addr,
+                header.file_size,
+                header.mem_size,
+                header.align,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the relocations of each relocation section, like `readelf -r`.
+    ///
+    /// ```text
+    /// Relocation section '.rela.text' contains 2 entries:
+    ///   Offset           Type                   Symbol + Addend
+    ///   0000000000000001 R_X86_64_PLT32         foo - 0x4
+    ///   0000000000000008 R_X86_64_PC32          table + 0x4
+    /// ```
+    pub fn write_relocations<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        if self.relocation_tables.is_empty() {
+            return writeln!(w, "No relocations.");
+        }
+
+        for (idx, table) in self.relocation_tables.iter().enumerate() {
+            if idx != 0 {
+                writeln!(w)?;
+            }
+
+            let count = table.entries.len();
+            let entries = if count == 1 { "entry" } else { "entries" };
+            writeln!(w, "Relocation section '{}' contains {count} {entries}:", table.section)?;
+            writeln!(w, "  {:16} {:22} Symbol + Addend", "Offset", "Type")?;
+
+            for entry in &table.entries {
+                let addend = entry.addend.unwrap_or(0);
+                let sign = if addend < 0 { '-' } else { '+' };
+                let target = match &entry.symbol {
+                    Some(symbol) if addend == 0 => symbol.clone(),
+                    Some(symbol) => format!("{symbol} {sign} {:#x}", addend.unsigned_abs()),
+                    None if entry.addend.is_some() => format!("{addend:#x}"),
+                    None => String::new(),
+                };
+
+                let line = format!("  {:016x} {:22} {target}", entry.offset, entry.kind);
+                writeln!(w, "{}", line.trim_end())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the notes of each note section, decoding the types that are known and dumping
+    /// the bytes of the others.
+    ///
+    /// ```text
+    /// Notes in '.note.gnu.property':
+    ///   Owner    Type                     Size
+    ///   GNU      NT_GNU_PROPERTY_TYPE_0   0x20
+    ///     x86 features: IBT, SHSTK
+    ///     x86 ISA needed: x86-64-baseline
+    /// ```
+    pub fn write_notes<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        if self.note_sections.is_empty() {
+            return writeln!(w, "No notes.");
+        }
+
+        for (idx, section) in self.note_sections.iter().enumerate() {
+            if idx != 0 {
+                writeln!(w)?;
+            }
+
+            writeln!(w, "Notes in '{}':", section.name)?;
+            writeln!(w, "  {:8} {:24} Size", "Owner", "Type")?;
+
+            for note in &section.notes {
+                let kind = match note.kind_name() {
+                    Some(name) => name.to_string(),
+                    None => format!("{:#x}", note.kind),
+                };
+
+                writeln!(w, "  {:8} {kind:24} {:#x}", note.owner, note.desc.len())?;
+
+                if !note.description.is_empty() {
+                    for line in &note.description {
+                        writeln!(w, "    {line}")?;
+                    }
+                    continue;
+                }
+
+                for chunk in note.desc.chunks(16) {
+                    writeln!(w, "    Data: {}", hex(chunk))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// What kind of object it is, for objects that have a kind.
+    fn kind_name(&self) -> Option<&'static str> {
+        match self.object_kind {
+            ObjectKind::Executable => Some("executable"),
+            ObjectKind::Dynamic if self.interpreter.is_some() => {
+                Some("position-independent executable")
+            }
+            ObjectKind::Dynamic => Some("shared library"),
+            ObjectKind::Relocatable => Some("relocatable object"),
+            ObjectKind::Core => Some("core dump"),
+            _ => None,
+        }
+    }
+
+    /// Write what the object is built for and the facts that identify it, like its build-id.
+    /// Core dumps are followed by the signal that caused the dump, where each thread was
+    /// stopped and the files that were mapped.
+    ///
+    /// ```text
+    /// Format: ELF
+    /// Machine: x86_64
+    /// Endianness: little
+    /// Type: position-independent executable
+    /// Interpreter: /lib64/ld-linux-x86-64.so.2
+    /// Stripped: yes
+    /// Build ID: 0123456789abcdef0123456789abcdef01234567
+    /// ```
+    pub fn write_info<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let format = match self.format {
+            Some(BinaryFormat::Elf) => "ELF",
+            Some(BinaryFormat::MachO) => "Mach-O",
+            Some(BinaryFormat::Pe) => "PE",
+            Some(BinaryFormat::Coff) => "COFF",
+            Some(BinaryFormat::Wasm) => "WebAssembly",
+            Some(BinaryFormat::Xcoff) => "XCOFF",
+            Some(..) => "unknown",
+            None if self.arch == object::Architecture::Wasm32 => "WebAssembly",
+            None => "raw",
+        };
+
+        let endianness = match self.endianness {
+            object::Endianness::Little => "little",
+            object::Endianness::Big => "big",
+        };
+
+        writeln!(w, "Format: {format}")?;
+        writeln!(w, "Machine: {}", arch_name(self.arch, self.endianness))?;
+        writeln!(w, "Endianness: {endianness}")?;
+
+        if let Some(kind) = self.kind_name() {
+            writeln!(w, "Type: {kind}")?;
+        }
+
+        if let Some(interpreter) = &self.interpreter {
+            writeln!(w, "Interpreter: {interpreter}")?;
+        }
+
+        let identity = &self.identity;
+        if self.format.is_some() {
+            let stripped = if identity.stripped { "yes" } else { "no" };
+            writeln!(w, "Stripped: {stripped}")?;
+        }
+
+        if let Some(build_id) = &identity.build_id {
+            writeln!(w, "Build ID: {}", hex(build_id))?;
+        }
+
+        if let Some(id) = identity.uuid {
+            writeln!(w, "UUID: {}", uuid(id, false))?;
+        }
+
+        if let Some(timestamp) = identity.timestamp {
+            writeln!(w, "Timestamp: {timestamp:#x} ({} UTC)", utc_date(timestamp))?;
+        }
+
+        if let Some(pdb) = &identity.pdb {
+            writeln!(w, "PDB: {}", pdb.path)?;
+            writeln!(w, "PDB GUID: {}", uuid(pdb.guid, true))?;
+            writeln!(w, "PDB age: {}", pdb.age)?;
+        }
+
+        if let Some(go) = &identity.go {
+            writeln!(w, "Go version: {}", go.version)?;
+            if let Some(path) = &go.path {
+                writeln!(w, "Go package: {path}")?;
+            }
+        }
+
+        if let Some(core) = &self.core_dump {
+            // the thread that caused the dump comes first
+            if let Some(thread) = core.threads.first() {
+                match signal_name(thread.signal) {
+                    Some(name) => writeln!(w, "Signal: {} ({name})", thread.signal)?,
+                    None => writeln!(w, "Signal: {}", thread.signal)?,
+                }
+            }
+
+            for thread in &core.threads {
+                match (thread.pc(), thread.sp()) {
+                    (Some(pc), Some(sp)) => {
+                        writeln!(w, "Thread {}: pc {pc:#x}, sp {sp:#x}", thread.pid)?
+                    }
+                    _ => writeln!(w, "Thread {}", thread.pid)?,
+                }
+            }
+
+            for file in &core.files {
+                writeln!(
+                    w,
+                    "Mapped file: {:#x}-{:#x} at offset {:#x}: {}",
+                    file.start, file.end, file.offset, file.path
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{human_size, utc_date};
+    use crate::{archive_members, Error, ParseOptions, Processor, RawBinary};
+    use crate::{NameOptions, NamePattern, SymbolFilter, SymbolOrder, SymbolTable};
+    use std::path::Path;
+
+    /// Statically linked x86_64 executable, see `fixtures/tiny.s`.
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/tiny");
+
+    /// Code of the same executable without any headers.
+    const RAW: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/tiny.bin");
+
+    /// WebAssembly module, see `fixtures/tiny.wat`.
+    const WASM: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/tiny.wasm");
+
+    /// Static archive of `tiny.o` and `relocs-x86_64.o`, see `fixtures/tiny.s`.
+    const ARCHIVE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/libtiny.a");
+
+    /// Stripped shared library, see `fixtures/dynamic.c`.
+    const SHARED: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/libdynamic.so");
+
+    /// PE executable importing from KERNEL32.dll, see `fixtures/tiny-pe.s`.
+    const PE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/tiny.exe");
+
+    /// Stripped executable with a `.plt.sec` section, see `fixtures/dynamic.c`.
+    const IBT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/dynamic-ibt");
+
+    /// Stripped 32-bit x86 executable calling `__x86.get_pc_thunk`s, see `fixtures/thunks.s`.
+    const THUNKS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/thunks");
+
+    /// Stripped executable with a build-id and Go build info, see `fixtures/identity.s`.
+    const IDENTITY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/identity");
+
+    /// Unlinked x86_64 object, see `fixtures/relocs-x86_64.s`.
+    const RELOCS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/relocs-x86_64.o");
+
+    /// Unlinked riscv64 object, see `fixtures/relocs-riscv64.s`.
+    const RELOCS_RISCV: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/relocs-riscv64.o");
+
+    /// Stripped dynamically linked executable, see `fixtures/dynamic.c`.
+    const DYNAMIC: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/dynamic");
+
+    /// Unlinked x86_64 object with mangled and plain names, see `fixtures/names.s`.
+    const NAMES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/names.o");
+
+    /// Statically linked x86_64 executable with its debug info compressed using zstd, see
+    /// `fixtures/lines.c`.
+    const LINES_ZSTD: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/lines-zstd");
+
+    /// Executable that crashes writing to address zero, see `fixtures/crash.s`.
+    const CRASH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/crash");
+
+    /// Core dumped by [`CRASH`].
+    const CORE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/crash.core");
+
+    /// Static executable with thread-local variables, see `fixtures/tls.s`.
+    const TLS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/tls");
+
+    /// Unlinked x86_64 object with property and unknown notes, see `fixtures/notes.s`.
+    const NOTES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/notes.o");
+
+    static GOLDEN: &str = include_str!("../fixtures/tiny.txt");
+
+    fn listing(start: Option<usize>) -> String {
+        let processor = Processor::parse(Path::new(FIXTURE)).unwrap();
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &processor.listing_formatter(), start).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn golden() {
+        assert_eq!(listing(None), GOLDEN);
+    }
+
+    #[test]
+    fn start() {
+        // starting in the middle of `xor edi, edi` includes all of it
+        let (_, entry) = GOLDEN.split_once("\n<entry>:").unwrap();
+        let expected = format!("Disassembly of section .text:\n\n<entry>:{entry}");
+        assert_eq!(listing(Some(0x401006)), expected);
+    }
+
+    #[test]
+    fn raw() {
+        let options = ParseOptions {
+            raw: RawBinary::new("x86_64", 0x401000),
+            ..ParseOptions::default()
+        };
+
+        let processor = Processor::parse_with(Path::new(RAW), options).unwrap();
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &processor.listing_formatter(), None).unwrap();
+
+        // without symbols, only functions that are called get a name
+        let expected = "\
+Disassembly of section raw:
+0000000000401000: 90                  nop
+
+<sub_401001>:
+0000000000401001: 8d 04 37            lea eax, dword [rdi + rsi * 1]
+0000000000401004: c3                  ret
+0000000000401005: 31 ff               xor edi, edi
+0000000000401007: 89 fe               mov esi, edi
+0000000000401009: e8 f3 ff ff ff      call sub_401001
+000000000040100e: 89 c7               mov edi, eax
+0000000000401010: 0f 05               syscall
+0000000000401012: f4                  hlt
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn wasm() {
+        let processor = Processor::parse(Path::new(WASM)).unwrap();
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &processor.listing_formatter(), None).unwrap();
+        let listing = String::from_utf8(out).unwrap();
+
+        // functions are named by the name section, exports or their index
+        for name in ["<add>:", "<count>:", "<_start>:", "<func4>:"] {
+            assert!(listing.contains(name), "{name} missing from:\n{listing}");
+        }
+
+        for inst in ["i32.load offset=16", "call func4", "call print", "locals i32"] {
+            assert!(listing.contains(inst), "{inst} missing from:\n{listing}");
+        }
+
+        // instructions are indented by how deeply they're nested
+        let indent = |mnemonic: &str| {
+            let line = listing.lines().find(|line| line.ends_with(mnemonic)).unwrap();
+            let start = &line[..line.len() - mnemonic.len()];
+            start.len() - start.trim_end().len()
+        };
+        assert_eq!(indent("i32.ge_u"), indent("block") + 4);
+
+        let mut out = Vec::new();
+        processor.write_names(&mut out, NameOptions::default()).unwrap();
+        let expected = "\
+Imports:
+  env::print
+
+Exports:
+                   memory
+  000000000000005a add
+  000000000000007f _start
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn pe() {
+        let processor = Processor::parse(Path::new(PE)).unwrap();
+        assert_eq!(processor.entrypoint, 0x140001004);
+
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &processor.listing_formatter(), None).unwrap();
+        let listing = String::from_utf8(out).unwrap();
+
+        // addresses include the image base
+        for line in ["<add>:", "0000000140001000: 8d 04 11", "call add"] {
+            assert!(listing.contains(line), "{line} missing from:\n{listing}");
+        }
+
+        let mut out = Vec::new();
+        processor.write_libs(&mut out, false).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "KERNEL32.dll\n");
+
+        let mut out = Vec::new();
+        processor.write_names(&mut out, NameOptions::default()).unwrap();
+        let expected = "\
+Imports:
+  KERNEL32.dll::ExitProcess
+
+Exports:
+  0000000140001004 _start
+  0000000140001000 add
+
+Symbols:
+  Address          Size     Type   Bind   Section Table  Name
+  0000000140001000 00000000 OBJECT GLOBAL .text   symtab add
+  0000000140001004 00000000 OBJECT GLOBAL .text   symtab _start
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn names() {
+        let processor = Processor::parse(Path::new(FIXTURE)).unwrap();
+        let names = |order, filter| {
+            let mut out = Vec::new();
+            let options = NameOptions { order, filter, ..NameOptions::default() };
+            processor.write_names(&mut out, options).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let expected = "\
+Symbols:
+  Address          Size     Type   Bind   Section Table  Name
+  0000000000401001 00000004 FUNC   GLOBAL .text   symtab add
+  0000000000402000 00000000 NOTYPE GLOBAL .text   symtab __bss_start
+  0000000000402000 00000000 NOTYPE GLOBAL .text   symtab _edata
+  0000000000402000 00000000 NOTYPE GLOBAL .text   symtab _end
+";
+        assert_eq!(names(SymbolOrder::Address, SymbolFilter::All), expected);
+        assert_eq!(names(SymbolOrder::Size, SymbolFilter::All), expected);
+
+        let sorted = names(SymbolOrder::Name, SymbolFilter::Defined);
+        let sorted: Vec<_> = sorted.lines().skip(2).map(|line| &line[57..]).collect();
+        assert_eq!(sorted, ["__bss_start", "_edata", "_end", "add"]);
+        assert_eq!(names(SymbolOrder::Address, SymbolFilter::Undefined), "");
+
+        // references of a relocatable object are undefined
+        let processor = Processor::parse(format!("{ARCHIVE}(relocs-x86_64.o)")).unwrap();
+        let mut out = Vec::new();
+        let options = NameOptions {
+            order: SymbolOrder::Name,
+            filter: SymbolFilter::Undefined,
+            ..NameOptions::default()
+        };
+        processor.write_names(&mut out, options).unwrap();
+        let expected = "\
+Symbols:
+  Address          Size     Type   Bind   Section Table  Name
+                   00000000 NOTYPE GLOBAL UND     symtab bar
+                   00000000 NOTYPE GLOBAL UND     symtab counter
+                   00000000 NOTYPE GLOBAL UND     symtab errno
+                   00000000 NOTYPE GLOBAL UND     symtab foo
+                   00000000 NOTYPE GLOBAL UND     symtab table
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn filtered_names() {
+        let processor = Processor::parse(Path::new(NAMES)).unwrap();
+        let names = |pattern, mangled| {
+            let mut out = Vec::new();
+            let options = NameOptions {
+                order: SymbolOrder::Name,
+                pattern: Some(pattern),
+                mangled,
+                ..NameOptions::default()
+            };
+            processor.write_names(&mut out, options).unwrap();
+            let out = String::from_utf8(out).unwrap();
+            out.lines().skip(2).map(|line| line[57..].to_string()).collect::<Vec<_>>()
+        };
+
+        // patterns are matched against demangled names by default
+        let substring = |text: &str| NamePattern::Substring(text.to_string());
+        assert_eq!(names(substring("foo::"), false), ["foo::bar", "foo::baz"]);
+        assert_eq!(names(substring("bar"), false), ["bar", "foo::bar"]);
+        assert_eq!(names(substring("3foo"), false), Vec::<String>::new());
+        assert_eq!(names(substring("3foo3bar"), true), ["foo::bar"]);
+
+        let regex = NamePattern::regex("^foo::ba[rz]$").unwrap();
+        assert_eq!(names(regex, false), ["foo::bar", "foo::baz"]);
+        let regex = NamePattern::regex("^_Z.*Ei$").unwrap();
+        assert_eq!(names(regex, true), ["foo::baz"]);
+        assert!(NamePattern::regex("(").is_err());
+    }
+
+    #[test]
+    fn dynamic_symbols() {
+        let processor = Processor::parse(Path::new(SHARED)).unwrap();
+
+        // exported functions are labeled without a `.symtab`
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &processor.listing_formatter(), None).unwrap();
+        let listing = String::from_utf8(out).unwrap();
+        assert!(listing.contains("<main>:"), "<main>: missing from:\n{listing}");
+
+        let mut out = Vec::new();
+        let options = NameOptions { filter: SymbolFilter::Defined, ..NameOptions::default() };
+        processor.write_names(&mut out, options).unwrap();
+        let expected = "\
+Exports:
+  0000000000001050 main
+
+Symbols:
+  Address          Size     Type   Bind   Section Table  Name
+  0000000000001050 0000000c FUNC   GLOBAL .text   dynsym main
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let mut out = Vec::new();
+        let options = NameOptions { table: Some(SymbolTable::Static), ..NameOptions::default() };
+        processor.write_names(&mut out, options).unwrap();
+        let names = String::from_utf8(out).unwrap();
+        let note = "Symbols:\n  none, the object has no symbol table and might be stripped\n";
+        assert!(names.ends_with(note), "note missing from:\n{names}");
+    }
+
+    #[test]
+    fn synthesized_symbols() {
+        let processor = Processor::parse(Path::new(IBT)).unwrap();
+
+        // calls go through `.plt.sec` and `.plt.got`, not the lazy binding stubs of `.plt`
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &processor.listing_formatter(), None).unwrap();
+        let listing = String::from_utf8(out).unwrap();
+        for line in ["<puts@plt>:", "0000000000001050: f3 0f 1e fa", "call __cxa_finalize@plt"] {
+            assert!(listing.contains(line), "{line} missing from:\n{listing}");
+        }
+
+        let mut out = Vec::new();
+        let options = NameOptions { filter: SymbolFilter::Defined, ..NameOptions::default() };
+        processor.write_names(&mut out, options).unwrap();
+        let names = String::from_utf8(out).unwrap();
+        for line in [
+            "  0000000000001040 00000010 FUNC   LOCAL  .plt.got synth  __cxa_finalize@plt\n",
+            "  0000000000001050 00000010 FUNC   LOCAL  .plt.sec synth  puts@plt\n",
+        ] {
+            assert!(names.contains(line), "{line} missing from:\n{names}");
+        }
+
+        let processor = Processor::parse(Path::new(THUNKS)).unwrap();
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &processor.listing_formatter(), None).unwrap();
+        let listing = String::from_utf8(out).unwrap();
+        for line in ["call __x86.get_pc_thunk.bx", "call __x86.get_pc_thunk.ax"] {
+            assert!(listing.contains(line), "{line} missing from:\n{listing}");
+        }
+
+        let mut out = Vec::new();
+        processor.write_names(&mut out, NameOptions::default()).unwrap();
+        let expected = "\
+Symbols:
+  Address          Size     Type   Bind   Section Table  Name
+  0000000008049011 00000004 FUNC   LOCAL  .text   synth  __x86.get_pc_thunk.bx
+  0000000008049015 00000004 FUNC   LOCAL  .text   synth  __x86.get_pc_thunk.ax
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn archive() {
+        let members = archive_members(ARCHIVE).unwrap().unwrap();
+        assert_eq!(members, ["tiny.o", "relocs-x86_64.o"]);
+        assert!(archive_members(FIXTURE).unwrap().is_none());
+
+        // members are picked like `libtiny.a(tiny.o)`
+        let processor = Processor::parse(format!("{ARCHIVE}(tiny.o)")).unwrap();
+        let mut out = Vec::new();
+        processor.write_listing(&mut out, &processor.listing_formatter(), None).unwrap();
+        let listing = String::from_utf8(out).unwrap();
+        assert!(listing.contains("<add>:"), "<add>: missing from:\n{listing}");
+
+        match Processor::parse(ARCHIVE) {
+            Err(Error::Archive(members)) => assert_eq!(members, ["tiny.o", "relocs-x86_64.o"]),
+            _ => panic!("archive parsed without picking a member"),
+        }
+
+        match Processor::parse(format!("{ARCHIVE}(missing.o)")) {
+            Err(Error::MissingMember(member)) => assert_eq!(member, "missing.o"),
+            _ => panic!("missing member parsed"),
+        }
+    }
+
+    #[test]
+    fn sections() {
+        let processor = Processor::parse(Path::new(FIXTURE)).unwrap();
+        let write = |human| {
+            let mut out = Vec::new();
+            processor.write_sections(&mut out, human).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let text = "[ 1] .text                CODE     0000000000401000 00001000 00000013     1 AX";
+        let table = write(false);
+        assert!(table.lines().any(|line| line.trim() == text), "{text} missing from:\n{table}");
+
+        let text = "[ 1] .text                CODE     0000000000401000 00001000       19     1 AX";
+        let table = write(true);
+        assert!(table.lines().any(|line| line.trim() == text), "{text} missing from:\n{table}");
+    }
+
+    #[test]
+    fn metadata_only() {
+        let options = ParseOptions { metadata_only: true, ..ParseOptions::default() };
+        let processor = Processor::parse_with(Path::new(FIXTURE), options).unwrap();
+        assert_eq!(processor.instructions().count(), 0);
+
+        let mut out = Vec::new();
+        processor.write_sections(&mut out, false).unwrap();
+        let table = String::from_utf8(out).unwrap();
+        assert!(table.contains(".text"), ".text missing from:\n{table}");
+    }
+
+    #[test]
+    fn compressed_sections() {
+        let processor = Processor::parse(Path::new(LINES_ZSTD)).unwrap();
+        let mut out = Vec::new();
+        processor.write_sections(&mut out, false).unwrap();
+        let table = String::from_utf8(out).unwrap();
+        for text in [
+            "[ 5] .debug_info          DEBUG    0000000000000000 00002098 0000008d     8 C",
+            "[ 7] .debug_line          DEBUG    0000000000000000 000021b4 00000066     1",
+        ] {
+            assert!(table.lines().any(|line| line.trim() == text), "{text} missing from:\n{table}");
+        }
+    }
+
+    #[test]
+    fn core_dump() {
+        let core = Processor::parse(Path::new(CORE)).unwrap();
+        let mut out = Vec::new();
+        core.write_info(&mut out).unwrap();
+        let info = String::from_utf8(out).unwrap();
+        for text in [
+            "Type: core dump\n",
+            "Signal: 11 (SIGSEGV)\n",
+            "Thread 4594: pc 0x401015, sp 0x7ffe6f109538\n",
+            "Mapped file: 0x400000-0x401000 at offset 0x0: /tmp/c2/crash\n",
+            "Mapped file: 0x401000-0x402000 at offset 0x1000: /tmp/c2/crash\n",
+        ] {
+            assert!(info.contains(text), "{text} missing from:\n{info}");
+        }
+
+        let dump = core.core_dump().unwrap();
+        let thread = &dump.threads[0];
+        assert_eq!(thread.register("rax"), Some(0x1234));
+        assert_eq!(thread.register("rbx"), Some(0x2a));
+
+        // none of the process's memory is decoded as code
+        assert_eq!(core.instructions().count(), 0);
+
+        // the core only has the process's memory, its code is found in the executable
+        let pc = thread.pc().unwrap() as usize;
+        let crash = Processor::parse(Path::new(CRASH)).unwrap();
+        assert_eq!(&*crash.index.resolve(pc).unwrap().name, "fault");
+
+        // the return address of the call to `fault` is on top of the stack
+        let mut word = [0; 8];
+        dump.read_memory(thread.sp().unwrap() as usize, &mut word).unwrap();
+        assert_eq!(u64::from_le_bytes(word), 0x40100c);
+
+        // code mapped from an unmodified file isn't dumped
+        assert_eq!(dump.read_memory(pc, &mut word), Err(pc));
+    }
+
+    #[test]
+    fn thread_local_storage() {
+        let processor = Processor::parse(Path::new(TLS)).unwrap();
+        let mut out = Vec::new();
+        processor.write_sections(&mut out, false).unwrap();
+        let table = String::from_utf8(out).unwrap();
+        for text in [
+            "[ 2] .tdata               TLS      0000000000001ff0 00001ff0 00000004    16 WAT",
+            "[ 3] .tbss                TLS      0000000000002000 -        00000010    16 WAT",
+        ] {
+            assert!(table.lines().any(|line| line.trim() == text), "{text} missing from:\n{table}");
+        }
+        assert!(table.contains("\nT: template of thread-local storage"), "{table}");
+
+        let mut out = Vec::new();
+        processor.write_names(&mut out, NameOptions::default()).unwrap();
+        let expected = "\
+Symbols:
+  Address          Size     Type   Bind   Section Table  Name
+  0000000000000010 00000019 FUNC   GLOBAL .text   symtab _start
+  0000000000002000 00000000 NOTYPE GLOBAL .text   symtab __bss_start
+  0000000000002000 00000000 NOTYPE GLOBAL .text   symtab _edata
+  0000000000002000 00000000 NOTYPE GLOBAL .text   symtab _end
+           tls+0x0 00000004 TLS    GLOBAL .tdata  symtab counter
+          tls+0x10 00000010 TLS    GLOBAL .tbss   symtab buffer
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn human_sizes() {
+        assert_eq!(human_size(1023), "1023");
+        assert_eq!(human_size(1536), "1.5K");
+        assert_eq!(human_size(3 * 1024 * 1024), "3.0M");
+    }
+
+    #[test]
+    fn segments() {
+        let processor = Processor::parse(Path::new(FIXTURE)).unwrap();
+        let mut out = Vec::new();
+        processor.write_segments(&mut out).unwrap();
+        let expected = "\
+Type: executable
+Entry point: 0x401005
+Base address: 0x400000
+
+  Type           Offset   Address          FileSize MemSize  Flags Align
+  LOAD           00000000 0000000000400000 000000b0 000000b0 R     1000
+  LOAD           00001000 0000000000401000 00000013 00000013 R X   1000
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn relocations() {
+        let processor = Processor::parse(Path::new(RELOCS)).unwrap();
+        let mut out = Vec::new();
+        processor.write_relocations(&mut out).unwrap();
+        let expected = "\
+Relocation section '.rela.text' contains 5 entries:
+  Offset           Type                   Symbol + Addend
+  0000000000000001 R_X86_64_PLT32         foo - 0x4
+  0000000000000008 R_X86_64_PC32          table + 0x4
+  000000000000000e R_X86_64_PC32          counter - 0x4
+  0000000000000015 R_X86_64_REX_GOTPCRELX errno - 0x4
+  0000000000000023 R_X86_64_PLT32         bar - 0x4
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let processor = Processor::parse(Path::new(RELOCS_RISCV)).unwrap();
+        let mut out = Vec::new();
+        processor.write_relocations(&mut out).unwrap();
+        let relocs = String::from_utf8(out).unwrap();
+        for line in [
+            "0000000000000004 R_RISCV_CALL           foo",
+            "0000000000000010 R_RISCV_PCREL_LO12_I   .Lpcrel_hi0",
+            "0000000000000018 R_RISCV_JAL            helper",
+        ] {
+            assert!(relocs.contains(line), "{line} missing from:\n{relocs}");
+        }
+
+        // linked executables only keep the relocations of the dynamic linker
+        let processor = Processor::parse(Path::new(DYNAMIC)).unwrap();
+        let mut out = Vec::new();
+        processor.write_relocations(&mut out).unwrap();
+        let relocs = String::from_utf8(out).unwrap();
+        for line in [
+            "Relocation section '.rela.plt' contains 1 entry:",
+            "0000000000003dc0 R_X86_64_RELATIVE      0x1140",
+            "0000000000004000 R_X86_64_JUMP_SLOT     puts",
+        ] {
+            assert!(relocs.contains(line), "{line} missing from:\n{relocs}");
+        }
+
+        let processor = Processor::parse(Path::new(FIXTURE)).unwrap();
+        let mut out = Vec::new();
+        processor.write_relocations(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "No relocations.\n");
+    }
+
+    #[test]
+    fn notes() {
+        let processor = Processor::parse(Path::new(NOTES)).unwrap();
+        let mut out = Vec::new();
+        processor.write_notes(&mut out).unwrap();
+        let expected = "\
+Notes in '.note.gnu.property':
+  Owner    Type                     Size
+  GNU      NT_GNU_PROPERTY_TYPE_0   0x20
+    x86 features: IBT, SHSTK
+    x86 ISA needed: x86-64-baseline
+
+Notes in '.note.gnu.gold-version':
+  Owner    Type                     Size
+  GNU      NT_GNU_GOLD_VERSION      0xa
+    Version: gold 1.16
+
+Notes in '.note.bite':
+  Owner    Type                     Size
+  bite     0x1234                   0x6
+    Data: deadbeef0102
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let processor = Processor::parse(Path::new(DYNAMIC)).unwrap();
+        let mut out = Vec::new();
+        processor.write_notes(&mut out).unwrap();
+        let notes = String::from_utf8(out).unwrap();
+        assert!(notes.contains("    GNU ABI: Linux 3.2.0\n"), "{notes}");
+
+        let processor = Processor::parse(Path::new(IDENTITY)).unwrap();
+        let mut out = Vec::new();
+        processor.write_notes(&mut out).unwrap();
+        let notes = String::from_utf8(out).unwrap();
+        assert!(notes.contains("Build ID: 0123456789abcdef0123456789abcdef01234567"), "{notes}");
+
+        let processor = Processor::parse(Path::new(FIXTURE)).unwrap();
+        let mut out = Vec::new();
+        processor.write_notes(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "No notes.\n");
+    }
+
+    #[test]
+    fn info() {
+        let processor = Processor::parse(Path::new(IDENTITY)).unwrap();
+        let build_id = processor.build_id().unwrap();
+        assert_eq!(build_id.len(), 20);
+        assert_eq!(build_id[..4], [0x01, 0x23, 0x45, 0x67]);
+
+        let mut out = Vec::new();
+        processor.write_info(&mut out).unwrap();
+        let expected = "\
+Format: ELF
+Machine: x86_64
+Endianness: little
+Type: executable
+Stripped: yes
+Build ID: 0123456789abcdef0123456789abcdef01234567
+Go version: go1.22.1
+Go package: example.com/hello
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        // PE executables are identified by when they were linked
+        let processor = Processor::parse(Path::new(PE)).unwrap();
+        let mut out = Vec::new();
+        processor.write_info(&mut out).unwrap();
+        let info = String::from_utf8(out).unwrap();
+        let timestamp = "Timestamp: 0x6ad10c5f (2026-10-15 17:24:47 UTC)\n";
+        assert!(info.contains(timestamp), "{timestamp} missing from:\n{info}");
+        assert!(processor.build_id().is_none());
+
+        assert_eq!(utc_date(0), "1970-01-01 00:00:00");
+        assert_eq!(utc_date(951782400), "2000-02-29 00:00:00");
+    }
+}
@@ -0,0 +1,94 @@
+use decoder::XrefKind;
+use std::collections::BTreeMap;
+
+/// Reference from an instruction to some address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Xref {
+    /// Address of the referencing instruction.
+    pub from: u64,
+
+    /// How the instruction uses the address.
+    pub kind: XrefKind,
+}
+
+/// Cross-references keyed by the address they refer to.
+#[derive(Debug, Default)]
+pub struct XrefMap {
+    pub to: BTreeMap<u64, Vec<Xref>>,
+}
+
+impl XrefMap {
+    pub fn insert(&mut self, from: u64, to: u64, kind: XrefKind) {
+        self.to.entry(to).or_default().push(Xref { from, kind });
+    }
+
+    /// References to `addr`, sorted by the address they originate from.
+    pub fn get(&self, addr: u64) -> &[Xref] {
+        self.to.get(&addr).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Call edges as `(caller, callee)` pairs, sorted by callee.
+    pub fn calls(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.to.iter().flat_map(|(&to, xrefs)| {
+            xrefs.iter().filter(|xref| xref.kind == XrefKind::Call).map(move |xref| (xref.from, to))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XrefMap;
+    use decoder::Decoded;
+
+    /// Decodes a big endian MIPS program at 0x400000 and collects it's xrefs.
+    fn xrefs(dwords: &[u32]) -> XrefMap {
+        let decoder = mips::Decoder { is_64: false, big_endian: true };
+        let bytes: Vec<u8> = dwords.iter().flat_map(|dword| dword.to_be_bytes()).collect();
+        let mut xrefs = XrefMap::default();
+
+        for (ip, inst) in decoder::InstructionStream::new(&bytes, 0x400000, decoder) {
+            let Ok(mut inst) = inst else { break };
+            inst.update_rel_addrs(ip, None);
+            if let Some((to, kind)) = inst.xref() {
+                xrefs.insert(ip as u64, to as u64, kind);
+            }
+        }
+
+        xrefs
+    }
+
+    #[test]
+    fn call_graph() {
+        let xrefs = xrefs(&[
+            // main:
+            0x0c100006, // jal f
+            0x00000000, // nop
+            0x0c100008, // jal g
+            0x00000000, // nop
+            0x03e00008, // jr ra
+            0x00000000, // nop
+            // f:
+            0x08100008, // j g
+            0x00000000, // nop
+            // g:
+            0x1080ffff, // beqz a0, g
+            0x00000000, // nop
+            0x03e00008, // jr ra
+            0x00000000, // nop
+        ]);
+
+        assert_eq!(xrefs.calls().collect::<Vec<_>>(), [(0x400000, 0x400018), (0x400008, 0x400020)]);
+
+        let to_g: Vec<_> = xrefs.get(0x400020).iter().map(|xref| (xref.from, xref.kind)).collect();
+        assert_eq!(
+            to_g,
+            [
+                (0x400008, decoder::XrefKind::Call),
+                (0x400018, decoder::XrefKind::Jump),
+                (0x400020, decoder::XrefKind::Jump),
+            ]
+        );
+
+        assert!(xrefs.get(0x400010).is_empty());
+    }
+}
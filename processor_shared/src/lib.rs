@@ -1,4 +1,5 @@
-use object::{Pod, ReadRef};
+use object::{CompressedData, CompressionFormat, Pod, ReadRef};
+use std::sync::OnceLock;
 
 /// Address in memory.
 pub type VirtAddr = usize;
@@ -58,6 +59,13 @@ pub struct Section {
     /// Section data.
     bytes: &'static [u8],
 
+    /// How the section data is compressed, for sections like `.debug_info` that are often
+    /// stored compressed.
+    compressed: Option<CompressedData<'static>>,
+
+    /// Decompressed section data, inflated the first time it's read.
+    inflated: OnceLock<Vec<u8>>,
+
     /// Address where section starts.
     pub start: PhysAddr,
 
@@ -79,25 +87,39 @@ impl Section {
             ident,
             kind,
             bytes,
+            compressed: None,
+            inflated: OnceLock::new(),
             start,
             end
         }
     }
 
+    /// Section whose data is stored compressed, and is only decompressed once it's read.
+    pub fn with_compression(mut self, compressed: Option<CompressedData<'static>>) -> Self {
+        self.compressed = compressed.filter(|data| data.format != CompressionFormat::None);
+        self
+    }
+
     #[inline]
     pub fn bytes(&self) -> &[u8] {
-        self.bytes
+        match self.compressed {
+            Some(compressed) => self.inflated.get_or_init(|| {
+                // data that fails to decompress is treated like a section without any
+                compressed.decompress().map(|data| data.into_owned()).unwrap_or_default()
+            }),
+            None => self.bytes,
+        }
     }
 
     pub fn bytes_by_addr(&self, addr: PhysAddr, len: usize) -> &[u8] {
         let rva = addr - self.start;
-        let bytes = &self.bytes.get(rva..).unwrap_or(&[]);
+        let bytes = &self.bytes().get(rva..).unwrap_or(&[]);
         &bytes[..std::cmp::min(bytes.len(), len)]
     }
 
     pub fn read_at<T: Pod>(&self, addr: PhysAddr) -> Result<&T, ()> {
         let rva = addr - self.start;
-        let bytes = &self.bytes.get(rva..).unwrap_or(&[]);
+        let bytes = &self.bytes().get(rva..).unwrap_or(&[]);
         bytes.read_at(rva as u64)
     }
 }
@@ -194,7 +216,7 @@ pub fn encode_hex_bytes_truncated(bytes: &[u8], max_width: usize, is_padded: boo
         let len = bytes.len() * 3;
         let pad = is_padded as usize * max_width.saturating_sub(len);
         let mut buffer = Vec::with_capacity(len + pad);
-        let slice = &mut buffer[..];
+        let slice = std::slice::from_raw_parts_mut(buffer.as_mut_ptr(), buffer.capacity());
         let mut idx = 0;
 
         // truncation has to occur
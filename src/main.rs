@@ -7,18 +7,332 @@
 compile_error!("Bite can only be build for windows, macos and linux.");
 
 mod wayland;
-use commands::ARGS;
+use commands::{Dump, ARGS};
+use processor::{DumpError, DumpTarget, NameOptions, NamePattern, Processor};
+use processor::{SymbolFilter, SymbolOrder, SymbolTable};
+use std::io::{self, BufWriter, StdoutLock, Write};
+use std::path::{Path, PathBuf};
 
 fn main() {
+    if let Some(path) = &ARGS.config {
+        config::set_path(path.clone());
+    }
+
     #[cfg(target_os = "linux")]
     if nix::unistd::getuid() == 0.into() {
         wayland::set_env();
     }
 
     if ARGS.disassemble {
+        if ARGS.path.is_some() && (ARGS.text || stdout_is_redirected()) {
+            print_listing();
+            return;
+        }
+
         let mut ui = gui::UI::new().unwrap();
         ui.process_args();
         ui.run();
         return;
     }
+
+    if ARGS.libs {
+        print_libs();
+    }
+
+    if ARGS.names {
+        print_names();
+    }
+
+    if let Some(symbol) = &ARGS.cfg {
+        print_cfg(symbol);
+    }
+
+    if ARGS.sections {
+        print_sections();
+    }
+
+    if ARGS.segments {
+        print_segments();
+    }
+
+    if ARGS.strings {
+        print_strings();
+    }
+
+    if let Some(dump) = &ARGS.hexdump {
+        print_hexdump(dump);
+    }
+
+    if ARGS.imports {
+        print_imports();
+    }
+
+    if ARGS.exports {
+        print_exports();
+    }
+
+    if ARGS.relocs {
+        print_relocations();
+    }
+
+    if ARGS.info {
+        print_info();
+    }
+
+    if ARGS.notes {
+        print_notes();
+    }
+
+    if let Some(core) = &ARGS.backtrace {
+        print_backtrace(core);
+    }
+}
+
+/// Whether stdout is piped or written to a file. Anything else, like a terminal or the
+/// `/dev/null` desktop launchers tend to leave stdout at, gets the GUI.
+#[cfg(target_family = "unix")]
+fn stdout_is_redirected() -> bool {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::metadata("/dev/stdout") {
+        Ok(meta) => meta.file_type().is_fifo() || meta.file_type().is_file(),
+        Err(..) => false,
+    }
+}
+
+/// Release builds on windows don't have a console attached.
+#[cfg(target_family = "windows")]
+fn stdout_is_redirected() -> bool {
+    false
+}
+
+fn parse_processor(path: &Path) -> Processor {
+    match Processor::parse_with(path, ARGS.parse_options()) {
+        Ok(processor) => processor,
+        Err(err) => {
+            eprintln!("{err:?}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Call `write` with the object that's given, or with each member of a static archive after
+/// writing the member's name.
+fn for_each_object<W: Write>(
+    w: &mut W,
+    write: impl Fn(&Processor, &mut W) -> io::Result<()>,
+) -> io::Result<()> {
+    let path = ARGS.path.as_ref().unwrap();
+    let members = match processor::archive_members(path) {
+        Ok(Some(members)) => members,
+        _ => return write(&parse_processor(path), w),
+    };
+
+    for (idx, member) in members.iter().enumerate() {
+        if idx != 0 {
+            writeln!(w)?;
+        }
+
+        // members are parsed through a path like `libfoo.a(bar.o)`
+        let mut path = path.clone().into_os_string();
+        path.push(format!("({member})"));
+
+        writeln!(w, "{member}:")?;
+        write(&parse_processor(&PathBuf::from(path)), w)?;
+    }
+
+    Ok(())
+}
+
+/// Run `write` with buffered stdout and exit if writing `what` fails. Being piped into something
+/// like `head` isn't an error.
+fn with_stdout(what: &str, write: impl FnOnce(&mut BufWriter<StdoutLock>) -> io::Result<()>) {
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    let result = write(&mut stdout).and_then(|_| stdout.flush());
+
+    if let Err(err) = result {
+        if err.kind() != io::ErrorKind::BrokenPipe {
+            eprintln!("Failed to write {what}: {err}.");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_listing() {
+    let found = std::cell::Cell::new(false);
+    with_stdout("listing", |w| {
+        for_each_object(w, |processor, w| {
+            let mut listing = processor.listing_formatter();
+            listing.show_bytes = !ARGS.hide_bytes;
+            listing.show_source = ARGS.lines;
+            listing.show_source_text = ARGS.source;
+
+            let symbol = match &ARGS.symbol {
+                Some(symbol) => symbol,
+                None => return processor.write_listing(w, &listing, ARGS.start),
+            };
+
+            // members of an archive that don't define the function are left empty
+            match processor.function_range_by_name(symbol) {
+                Some(function) => {
+                    found.set(true);
+                    processor.write_function(w, &listing, function)
+                }
+                None => Ok(()),
+            }
+        })
+    });
+
+    if let Some(symbol) = &ARGS.symbol {
+        if !found.get() {
+            eprintln!("Unknown function '{symbol}'.");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_libs() {
+    with_stdout("libraries", |w| {
+        for_each_object(w, |processor, w| processor.write_libs(w, ARGS.libs_recursive))
+    });
+}
+
+fn print_names() {
+    let order = match ARGS.sort.as_deref() {
+        Some("size") => SymbolOrder::Size,
+        Some("name") => SymbolOrder::Name,
+        _ => SymbolOrder::Address,
+    };
+    let filter = match ARGS.defined {
+        Some(true) => SymbolFilter::Defined,
+        Some(false) => SymbolFilter::Undefined,
+        None => SymbolFilter::All,
+    };
+    let table = match ARGS.dynamic {
+        Some(true) => Some(SymbolTable::Dynamic),
+        Some(false) => Some(SymbolTable::Static),
+        None => None,
+    };
+
+    let pattern = match &ARGS.filter {
+        Some(regex) if ARGS.regex => match NamePattern::regex(regex) {
+            Ok(pattern) => Some(pattern),
+            Err(err) => {
+                eprintln!("Invalid regular expression '{regex}': {err}.");
+                std::process::exit(1);
+            }
+        },
+        Some(text) => Some(NamePattern::Substring(text.clone())),
+        None => None,
+    };
+
+    let options = NameOptions { order, filter, table, pattern, mangled: ARGS.mangled };
+    with_stdout("names", |w| {
+        for_each_object(w, |processor, w| processor.write_names(w, options.clone()))
+    });
+}
+
+fn print_sections() {
+    with_stdout("sections", |w| {
+        for_each_object(w, |processor, w| processor.write_sections(w, ARGS.human))
+    });
+}
+
+fn print_segments() {
+    with_stdout("segments", |w| {
+        for_each_object(w, |processor, w| processor.write_segments(w))
+    });
+}
+
+fn print_strings() {
+    let min_len = ARGS.min_length.unwrap_or(processor::MIN_STRING_LEN);
+    with_stdout("strings", |w| {
+        for_each_object(w, |processor, w| processor.write_strings(w, min_len))
+    });
+}
+
+fn print_hexdump(dump: &Dump) {
+    let target = match dump {
+        Dump::Section(name) => DumpTarget::Section(name),
+        Dump::Range(start, end) => DumpTarget::Range(*start..*end),
+    };
+
+    with_stdout("bytes", |w| {
+        for_each_object(w, |processor, w| {
+            processor.write_hexdump(w, target.clone()).map_err(|err| match err {
+                DumpError::IO(err) => err,
+                err => io::Error::other(err),
+            })
+        })
+    });
+}
+
+fn print_imports() {
+    with_stdout("imports", |w| {
+        for_each_object(w, |processor, w| processor.write_imports(w))
+    });
+}
+
+fn print_exports() {
+    with_stdout("exports", |w| {
+        for_each_object(w, |processor, w| processor.write_exports(w))
+    });
+}
+
+fn print_relocations() {
+    with_stdout("relocations", |w| {
+        for_each_object(w, |processor, w| processor.write_relocations(w))
+    });
+}
+
+fn print_info() {
+    with_stdout("info", |w| {
+        for_each_object(w, |processor, w| processor.write_info(w))
+    });
+}
+
+fn print_notes() {
+    with_stdout("notes", |w| {
+        for_each_object(w, |processor, w| processor.write_notes(w))
+    });
+}
+
+fn print_backtrace(path: &Path) {
+    let dump = parse_processor(path);
+    let core = match dump.core_dump() {
+        Some(core) => core,
+        None => {
+            eprintln!("'{}' isn't a core dump.", path.display());
+            std::process::exit(1);
+        }
+    };
+
+    let processor = parse_processor(ARGS.path.as_ref().unwrap());
+    with_stdout("backtrace", |w| processor.write_backtrace(w, core));
+}
+
+fn print_cfg(symbol: &str) {
+    let processor = parse_processor(ARGS.path.as_ref().unwrap());
+    let cfg = match processor.build_cfg(symbol) {
+        Some(cfg) => cfg,
+        None => {
+            eprintln!("Unknown function '{symbol}'.");
+            std::process::exit(1);
+        }
+    };
+
+    let path = match &ARGS.output {
+        Some(path) => path,
+        None => return with_stdout("control-flow graph", |w| cfg.write_dot(w)),
+    };
+
+    let result = std::fs::File::create(path).and_then(|file| {
+        let mut file = BufWriter::new(file);
+        cfg.write_dot(&mut file).and_then(|_| file.flush())
+    });
+
+    if let Err(err) = result {
+        eprintln!("Failed to write control-flow graph: {err}.");
+        std::process::exit(1);
+    }
 }
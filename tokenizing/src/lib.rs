@@ -1,4 +1,5 @@
 //! Colors used for rendering text in the GUI.
+use std::fmt;
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -95,15 +96,71 @@ impl PartialEq for Token {
     }
 }
 
+/// Base immediates are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    Decimal,
+    Hex,
+}
+
+/// How registers are named, for architectures that have more than one convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterNaming {
+    /// Names given by the calling convention, e.g. `a0` and `sp` on riscv.
+    Abi,
+    /// Names given by the register number, e.g. `x10` and `x2` on riscv.
+    Numeric,
+}
+
+/// Style instructions are formatted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatterConfig {
+    /// Base of immediates pushed through [`TokenStream::push_immediate`], every architecture uses
+    /// it's usual base if unset.
+    pub immediate_radix: Option<Radix>,
+
+    /// How registers are named.
+    pub register_naming: RegisterNaming,
+
+    /// Whether mnemonics are written in uppercase.
+    pub uppercase_mnemonics: bool,
+
+    /// Text written between operands.
+    pub operand_separator: &'static str,
+}
+
+impl Default for FormatterConfig {
+    fn default() -> Self {
+        Self {
+            immediate_radix: None,
+            register_naming: RegisterNaming::Abi,
+            uppercase_mnemonics: false,
+            operand_separator: ", ",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TokenStream {
     pub inner: Vec<Token>,
+
+    /// Style of the tokens being pushed.
+    pub config: FormatterConfig,
+
+    /// Text of tokens formatted at runtime is written here first, so the buffer is reused.
+    buffer: String,
 }
 
 impl TokenStream {
     pub fn new() -> Self {
+        Self::with_config(FormatterConfig::default())
+    }
+
+    pub fn with_config(config: FormatterConfig) -> Self {
         Self {
             inner: Vec::with_capacity(25),
+            config,
+            buffer: String::with_capacity(32),
         }
     }
 
@@ -119,13 +176,56 @@ impl TokenStream {
         self.push_token(Token::from_string(text, color));
     }
 
+    /// Push an instruction's mnemonic.
+    pub fn push_mnemonic(&mut self, text: &'static str, color: Color32) {
+        if self.config.uppercase_mnemonics {
+            self.push_owned(text.to_ascii_uppercase(), color);
+        } else {
+            self.push(text, color);
+        }
+    }
+
+    /// Push an instruction's mnemonic that's built at runtime.
+    pub fn push_owned_mnemonic(&mut self, mut text: String, color: Color32) {
+        if self.config.uppercase_mnemonics {
+            text.make_ascii_uppercase();
+        }
+        self.push_owned(text, color);
+    }
+
+    /// Push text that's formatted at runtime, e.g. `format_args!("{imm:#x}")`.
+    pub fn push_fmt(&mut self, args: fmt::Arguments, color: Color32) {
+        use fmt::Write;
+
+        self.buffer.clear();
+        let _ = self.buffer.write_fmt(args);
+        let text = MaybeStatic::Dynamic(Arc::from(self.buffer.as_str()));
+        self.push_token(Token { text, color });
+    }
+
+    /// Push the separator between two operands.
+    pub fn push_separator(&mut self, color: Color32) {
+        self.push(self.config.operand_separator, color);
+    }
+
+    /// Push an immediate in the configured radix, or in `radix` if none is configured.
+    pub fn push_immediate(&mut self, imm: i64, radix: Radix, color: Color32) {
+        match self.config.immediate_radix.unwrap_or(radix) {
+            Radix::Decimal => self.push_fmt(format_args!("{imm}"), color),
+            Radix::Hex if imm < 0 => {
+                self.push_fmt(format_args!("-{:#x}", imm.unsigned_abs()), color)
+            }
+            Radix::Hex => self.push_fmt(format_args!("{imm:#x}"), color),
+        }
+    }
+
     pub fn clear(&mut self) {
         self.inner.clear();
     }
 }
 
-impl ToString for TokenStream {
-    fn to_string(&self) -> String {
-        self.inner.iter().map(|t| &t.text as &str).collect()
+impl fmt::Display for TokenStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.iter().try_for_each(|token| f.write_str(&token.text))
     }
 }